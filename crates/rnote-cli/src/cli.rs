@@ -4,8 +4,8 @@ use anyhow::Context;
 use clap::Parser;
 use rnote_compose::SplitOrder;
 use rnote_engine::engine::export::{
-    DocExportFormat, DocPagesExportFormat, DocPagesExportPrefs, SelectionExportFormat,
-    SelectionExportPrefs,
+    DocExportFormat, DocPagesExportFormat, DocPagesExportPrefs, PageNumberFont, PageNumberPosition,
+    PageNumberingPrefs, SelectionExportFormat, SelectionExportPrefs,
 };
 use rnote_engine::engine::import::XoppImportPrefs;
 use rnote_engine::SelectionCollision;
@@ -104,6 +104,15 @@ pub(crate) enum ExportCommand {
         /// pages.
         #[arg(long, default_value_t = Default::default())]
         page_order: SplitOrder,
+        #[command(flatten)]
+        page_numbering_args: PageNumberingArgs,
+        /// Move page breaks to gaps between strokes, so that strokes are not cut through.{n}
+        /// Only applies to the Pdf export format and layouts that are not fixed-size.
+        #[arg(long)]
+        smart_page_breaks: bool,
+        /// Export the document as a single page that is cropped to the bounds of the content.
+        #[arg(long)]
+        crop_to_content: bool,
     },
     /// Export each page of the document(s) individually.{n}
     /// Both "--output-dir" and "--output-format" need to be set.
@@ -127,6 +136,8 @@ pub(crate) enum ExportCommand {
         /// The quality of the generated image(s) when Jpeg is used as export format.
         #[arg(long, default_value_t = DocPagesExportPrefs::default().jpeg_quality)]
         jpeg_quality: u8,
+        #[command(flatten)]
+        page_numbering_args: PageNumberingArgs,
     },
     /// Export a selection in a document.{n}
     /// When using "--output-file", only a single input file can be specified.{n}
@@ -151,6 +162,10 @@ pub(crate) enum ExportCommand {
         /// The margin around the to be exported content.
         #[arg(long, default_value_t = SelectionExportPrefs::default().margin, global = true)]
         margin: f64,
+        /// Don't embed the strokes into the exported Svg.{n}
+        /// When embedded, the strokes are editable again when the Svg is imported into Rnote.
+        #[arg(long, global = true)]
+        no_embed_strokes: bool,
     },
 }
 
@@ -173,6 +188,37 @@ pub(crate) enum SelectionCommand {
     },
 }
 
+#[derive(clap::Args, Debug, Clone, Copy)]
+pub(crate) struct PageNumberingArgs {
+    /// Stamp page numbers onto the exported pages.
+    #[arg(long)]
+    pub(crate) page_numbers: bool,
+    /// The position of the page numbers.
+    #[arg(long, value_enum, default_value_t = PageNumberingPrefs::default().position)]
+    pub(crate) page_number_position: PageNumberPosition,
+    /// The font of the page numbers.
+    #[arg(long, value_enum, default_value_t = PageNumberingPrefs::default().font)]
+    pub(crate) page_number_font: PageNumberFont,
+    /// The font size of the page numbers.
+    #[arg(long, default_value_t = PageNumberingPrefs::default().font_size)]
+    pub(crate) page_number_font_size: f64,
+    /// The number of the first exported page.
+    #[arg(long, default_value_t = PageNumberingPrefs::default().start)]
+    pub(crate) page_number_start: u32,
+}
+
+impl PageNumberingArgs {
+    pub(crate) fn prefs(&self) -> PageNumberingPrefs {
+        PageNumberingPrefs {
+            enabled: self.page_numbers,
+            position: self.page_number_position,
+            font: self.page_number_font,
+            font_size: self.page_number_font_size,
+            start: self.page_number_start,
+        }
+    }
+}
+
 #[derive(clap::Args, Debug, Clone)]
 #[group(required = true, multiple = false)]
 pub(crate) struct FileArgs<T: clap::ValueEnum + 'static + Send + Sync> {
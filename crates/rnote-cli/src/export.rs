@@ -5,7 +5,7 @@ use anyhow::Context;
 use p2d::bounding_volume::Aabb;
 use rnote_compose::SplitOrder;
 use rnote_engine::engine::export::{
-    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs,
+    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs, PageNumberingPrefs,
     SelectionExportFormat, SelectionExportPrefs,
 };
 use rnote_engine::engine::EngineSnapshot;
@@ -195,6 +195,9 @@ fn apply_export_prefs(
         cli::ExportCommand::Doc {
             file_args,
             page_order,
+            page_numbering_args,
+            smart_page_breaks,
+            crop_to_content,
         } => {
             engine.export_prefs.doc_export_prefs = create_doc_export_prefs_from_args(
                 output_file,
@@ -203,6 +206,9 @@ fn apply_export_prefs(
                 no_pattern,
                 optimize_printing,
                 *page_order,
+                page_numbering_args.prefs(),
+                *smart_page_breaks,
+                *crop_to_content,
             )?;
         }
         cli::ExportCommand::DocPages {
@@ -210,6 +216,7 @@ fn apply_export_prefs(
             page_order,
             bitmap_scalefactor,
             jpeg_quality,
+            page_numbering_args,
             ..
         } => {
             engine.export_prefs.doc_pages_export_prefs = create_doc_pages_export_prefs_from_args(
@@ -220,6 +227,7 @@ fn apply_export_prefs(
                 *page_order,
                 *bitmap_scalefactor,
                 *jpeg_quality,
+                page_numbering_args.prefs(),
             )?;
         }
         cli::ExportCommand::Selection {
@@ -227,6 +235,7 @@ fn apply_export_prefs(
             bitmap_scalefactor,
             jpeg_quality,
             margin,
+            no_embed_strokes,
             ..
         } => {
             engine.export_prefs.selection_export_prefs = create_selection_export_prefs_from_args(
@@ -238,6 +247,7 @@ fn apply_export_prefs(
                 *bitmap_scalefactor,
                 *jpeg_quality,
                 *margin,
+                !no_embed_strokes,
             )?;
         }
     }
@@ -267,6 +277,7 @@ fn file_ext_from_export_command(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn create_doc_export_prefs_from_args(
     output_file: Option<impl AsRef<Path>>,
     output_format: Option<DocExportFormat>,
@@ -274,6 +285,9 @@ pub(crate) fn create_doc_export_prefs_from_args(
     no_pattern: bool,
     optimize_printing: bool,
     page_order: SplitOrder,
+    page_numbering: PageNumberingPrefs,
+    smart_page_breaks: bool,
+    crop_to_content: bool,
 ) -> anyhow::Result<DocExportPrefs> {
     let format = match (output_file, output_format) {
         (Some(file), None) => match file.as_ref().extension().and_then(|ext| ext.to_str()) {
@@ -304,6 +318,9 @@ pub(crate) fn create_doc_export_prefs_from_args(
         with_pattern: !no_pattern,
         optimize_printing,
         page_order,
+        page_numbering,
+        smart_page_breaks,
+        crop_to_content,
     };
 
     Ok(prefs)
@@ -320,6 +337,7 @@ fn doc_export_format_from_ext_str(format: &str) -> anyhow::Result<DocExportForma
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn create_doc_pages_export_prefs_from_args(
     export_format: DocPagesExportFormat,
     no_background: bool,
@@ -328,6 +346,7 @@ pub(crate) fn create_doc_pages_export_prefs_from_args(
     page_order: SplitOrder,
     bitmap_scalefactor: f64,
     jpeg_quality: u8,
+    page_numbering: PageNumberingPrefs,
 ) -> anyhow::Result<DocPagesExportPrefs> {
     Ok(DocPagesExportPrefs {
         export_format,
@@ -337,6 +356,7 @@ pub(crate) fn create_doc_pages_export_prefs_from_args(
         page_order,
         bitmap_scalefactor,
         jpeg_quality,
+        page_numbering,
     })
 }
 
@@ -350,6 +370,7 @@ pub(crate) fn create_selection_export_prefs_from_args(
     bitmap_scalefactor: f64,
     jpeg_quality: u8,
     margin: f64,
+    embed_strokes: bool,
 ) -> anyhow::Result<SelectionExportPrefs> {
     let format = match (output_file, output_format) {
         (Some(file), None) => match file.as_ref().extension().and_then(|ext| ext.to_str()) {
@@ -383,6 +404,7 @@ pub(crate) fn create_selection_export_prefs_from_args(
         bitmap_scalefactor,
         jpeg_quality,
         margin,
+        embed_strokes,
     };
 
     Ok(prefs)
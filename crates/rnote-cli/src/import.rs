@@ -59,9 +59,12 @@ pub(crate) async fn import_file(
         return Err(anyhow::anyhow!("Failed to get filename from rnote_file"));
     };
     let input_bytes = cli::read_bytes_from_file(&input_file).await?;
-    let snapshot =
-        EngineSnapshot::load_from_xopp_bytes(input_bytes, engine.import_prefs.xopp_import_prefs)
-            .await?;
+    let snapshot = EngineSnapshot::load_from_xopp_bytes(
+        input_bytes,
+        Some(input_file.to_path_buf()),
+        engine.import_prefs.xopp_import_prefs,
+    )
+    .await?;
     let _ = engine.load_snapshot(snapshot);
     let rnote_bytes = engine.save_as_rnote_bytes(rnote_file_name).await??;
     cli::create_overwrite_file_w_bytes(&rnote_file, &rnote_bytes).await?;
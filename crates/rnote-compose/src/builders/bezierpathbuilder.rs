@@ -0,0 +1,206 @@
+// Imports
+use super::buildable::{Buildable, BuilderCreator, BuilderProgress};
+use crate::eventresult::EventPropagation;
+use crate::penevent::{KeyboardKey, PenEvent, PenState};
+use crate::penpath::Element;
+use crate::shapes::{BezierPath, BezierSegment};
+use crate::style::{indicators, Composer};
+use crate::{Constraints, EventResult};
+use crate::{Shape, Style};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::RenderContext;
+use std::time::Instant;
+
+/// Bezier path builder.
+///
+/// Anchors are placed by clicking, dragging while the pen is down pulls out the handles of the anchor.
+/// The path is finished by clicking on the last anchor again or by pressing enter.
+#[derive(Debug, Clone)]
+pub struct BezierPathBuilder {
+    /// The placed anchors and segments.
+    path: BezierPath,
+    /// The outgoing handle of the last placed anchor, the first control point of the next segment.
+    last_handle: na::Vector2<f64>,
+    /// The anchor that is currently placed while the pen is down.
+    current_anchor: Option<na::Vector2<f64>>,
+    /// Pen state.
+    pen_state: PenState,
+    /// Pen position.
+    pen_pos: na::Vector2<f64>,
+    /// Finish the path on the next `PenEvent::Up`.
+    finish: bool,
+}
+
+impl BuilderCreator for BezierPathBuilder {
+    fn start(element: Element, _now: Instant) -> Self {
+        Self {
+            path: BezierPath::new(element.pos),
+            last_handle: element.pos,
+            current_anchor: None,
+            pen_state: PenState::Down,
+            pen_pos: element.pos,
+            finish: false,
+        }
+    }
+}
+
+impl Buildable for BezierPathBuilder {
+    type Emit = Shape;
+
+    fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        constraints: Constraints,
+    ) -> EventResult<BuilderProgress<Self::Emit>> {
+        let progress = match event {
+            PenEvent::Down { element, .. } => {
+                if self.pen_state == PenState::Up || self.pen_state == PenState::Proximity {
+                    if self.pos_in_finish(element.pos) {
+                        self.finish = true;
+                    } else {
+                        let last_anchor = self.last_anchor();
+                        self.current_anchor =
+                            Some(constraints.constrain(element.pos - last_anchor) + last_anchor);
+                    }
+                }
+                self.pen_state = PenState::Down;
+                self.pen_pos = element.pos;
+                if self.current_anchor.is_none() && !self.finish {
+                    // dragging out the handle of the start anchor
+                    self.last_handle = element.pos;
+                }
+                BuilderProgress::InProgress
+            }
+            PenEvent::Up { element, .. } => {
+                if self.finish {
+                    self.finished()
+                } else {
+                    if let Some(anchor) = self.current_anchor.take() {
+                        let handle = self.pen_pos;
+                        self.path.segments.push(BezierSegment {
+                            cp1: self.last_handle,
+                            cp2: Self::mirrored_handle(anchor, handle),
+                            end: anchor,
+                        });
+                        self.last_handle = handle;
+                    }
+                    self.pen_state = PenState::Up;
+                    self.pen_pos = element.pos;
+                    BuilderProgress::InProgress
+                }
+            }
+            PenEvent::Proximity { element, .. } => {
+                self.pen_state = PenState::Proximity;
+                self.pen_pos = element.pos;
+                BuilderProgress::InProgress
+            }
+            PenEvent::KeyPressed { keyboard_key, .. } => match keyboard_key {
+                KeyboardKey::Escape | KeyboardKey::CarriageReturn | KeyboardKey::Linefeed => {
+                    self.finished()
+                }
+                _ => BuilderProgress::InProgress,
+            },
+            PenEvent::Text { .. } => BuilderProgress::InProgress,
+            PenEvent::Cancel => {
+                self.pen_state = PenState::Up;
+                self.finish = false;
+                BuilderProgress::Finished(vec![])
+            }
+        };
+
+        EventResult {
+            handled: true,
+            propagate: EventPropagation::Stop,
+            progress,
+        }
+    }
+
+    fn bounds(&self, style: &Style, zoom: f64) -> Option<Aabb> {
+        let mut bounds = self.state_as_bezier_path().composed_bounds(style);
+        bounds.take_point(self.last_handle.into());
+        bounds.take_point(self.pen_pos.into());
+        if let Some(anchor) = self.current_anchor {
+            bounds.take_point(Self::mirrored_handle(anchor, self.pen_pos).into());
+        }
+        Some(bounds.loosened(indicators::POS_INDICATOR_RADIUS / zoom))
+    }
+
+    fn draw_styled(&self, cx: &mut piet_cairo::CairoRenderContext, style: &Style, zoom: f64) {
+        cx.save().unwrap();
+
+        self.state_as_bezier_path().draw_composed(cx, style);
+
+        let last_anchor = self.last_anchor();
+        indicators::draw_vec_indicator(cx, PenState::Up, last_anchor, self.last_handle, zoom);
+        indicators::draw_pos_indicator(cx, PenState::Up, self.path.start, zoom);
+        indicators::draw_pos_indicator(cx, PenState::Up, self.last_handle, zoom);
+        if let Some(anchor) = self.current_anchor {
+            let mirrored = Self::mirrored_handle(anchor, self.pen_pos);
+            indicators::draw_vec_indicator(cx, PenState::Down, mirrored, self.pen_pos, zoom);
+            indicators::draw_pos_indicator(cx, PenState::Down, anchor, zoom);
+            indicators::draw_pos_indicator(cx, PenState::Down, self.pen_pos, zoom);
+        } else if !self.finish {
+            if self.pos_in_finish(self.pen_pos)
+                && (self.pen_state == PenState::Up || self.pen_state == PenState::Proximity)
+            {
+                indicators::draw_finish_indicator(cx, self.pen_state, last_anchor, zoom);
+            } else {
+                indicators::draw_pos_indicator(cx, self.pen_state, self.pen_pos, zoom);
+            }
+        }
+
+        cx.restore().unwrap();
+    }
+}
+
+impl BezierPathBuilder {
+    const FINISH_THRESHOLD_DIST: f64 = 8.0;
+
+    /// The current state as a bezier path, including the segment that is currently built.
+    pub fn state_as_bezier_path(&self) -> BezierPath {
+        let mut path = self.path.clone();
+        if self.finish {
+            return path;
+        }
+        match self.current_anchor {
+            Some(anchor) => path.segments.push(BezierSegment {
+                cp1: self.last_handle,
+                cp2: Self::mirrored_handle(anchor, self.pen_pos),
+                end: anchor,
+            }),
+            None if self.pen_state != PenState::Down => path.segments.push(BezierSegment {
+                cp1: self.last_handle,
+                cp2: self.pen_pos,
+                end: self.pen_pos,
+            }),
+            None => {}
+        }
+        path
+    }
+
+    fn finished(&self) -> BuilderProgress<Shape> {
+        if self.path.segments.is_empty() {
+            BuilderProgress::Finished(vec![])
+        } else {
+            BuilderProgress::Finished(vec![Shape::BezierPath(self.path.clone())])
+        }
+    }
+
+    fn last_anchor(&self) -> na::Vector2<f64> {
+        self.path
+            .segments
+            .last()
+            .map(|segment| segment.end)
+            .unwrap_or(self.path.start)
+    }
+
+    /// The incoming handle of an anchor, mirrored from the outgoing one so that the path is smooth.
+    fn mirrored_handle(anchor: na::Vector2<f64>, handle: na::Vector2<f64>) -> na::Vector2<f64> {
+        anchor * 2.0 - handle
+    }
+
+    fn pos_in_finish(&self, pos: na::Vector2<f64>) -> bool {
+        (pos - self.last_anchor()).magnitude() < Self::FINISH_THRESHOLD_DIST
+    }
+}
@@ -1,5 +1,6 @@
 // Modules
 mod arrowbuilder;
+mod bezierpathbuilder;
 /// Buildable trait.
 pub mod buildable;
 mod coordsystem2dbuilder;
@@ -20,6 +21,7 @@ mod rectanglebuilder;
 
 // Re-exports
 pub use arrowbuilder::ArrowBuilder;
+pub use bezierpathbuilder::BezierPathBuilder;
 pub use coordsystem2dbuilder::CoordSystem2DBuilder;
 pub use coordsystem3dbuilder::CoordSystem3DBuilder;
 pub use cubbezbuilder::CubBezBuilder;
@@ -85,6 +87,9 @@ pub enum ShapeBuilderType {
     /// A polygon builder
     #[serde(rename = "polygon")]
     Polygon,
+    /// A bezier path builder
+    #[serde(rename = "bezier_path")]
+    BezierPath,
 }
 
 impl ShapeBuilderType {
@@ -104,6 +109,7 @@ impl ShapeBuilderType {
             "shapebuilder-cubbez-symbolic" => Some(Self::CubBez),
             "shapebuilder-polyline-symbolic" => Some(Self::Polyline),
             "shapebuilder-polygon-symbolic" => Some(Self::Polygon),
+            "shapebuilder-bezierpath-symbolic" => Some(Self::BezierPath),
             _ => None,
         }
     }
@@ -126,6 +132,7 @@ impl ShapeBuilderType {
             Self::CubBez => String::from("shapebuilder-cubbez-symbolic"),
             Self::Polyline => String::from("shapebuilder-polyline-symbolic"),
             Self::Polygon => String::from("shapebuilder-polygon-symbolic"),
+            Self::BezierPath => String::from("shapebuilder-bezierpath-symbolic"),
         }
     }
 }
@@ -77,6 +77,10 @@ pub enum KeyboardKey {
     NavLeft,
     /// Arrow right.
     NavRight,
+    /// Page up.
+    PageUp,
+    /// Page down.
+    PageDown,
     /// Shift left.
     ShiftLeft,
     /// Shift right.
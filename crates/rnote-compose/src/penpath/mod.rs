@@ -10,6 +10,7 @@ pub use segment::Segment;
 use crate::ext::{KurboShapeExt, Vector2Ext};
 use crate::shapes::{CubicBezier, Line, QuadraticBezier, Shapeable};
 use crate::transform::Transformable;
+use crate::utils::rdp_keep_mask;
 use kurbo::Shape;
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use serde::{Deserialize, Serialize};
@@ -235,6 +236,44 @@ impl PenPath {
         hitboxes
     }
 
+    /// Simplifies the path with the Ramer-Douglas-Peucker algorithm.
+    ///
+    /// Only consecutive line segments are simplified, curve segments are kept as they are.
+    /// `epsilon` is the maximum distance a removed element is allowed to have to the simplified path.
+    pub fn simplify(&mut self, epsilon: f64) {
+        if epsilon <= 0.0 || self.segments.len() < 2 {
+            return;
+        }
+
+        fn flush_run(run: &mut Vec<Element>, segments: &mut Vec<Segment>, epsilon: f64) {
+            let keep = rdp_keep_mask(&run.iter().map(|el| el.pos).collect::<Vec<_>>(), epsilon);
+            segments.extend(
+                run.iter()
+                    .zip(keep)
+                    .skip(1)
+                    .filter_map(|(el, keep)| keep.then_some(Segment::LineTo { end: *el })),
+            );
+            run.clear();
+        }
+
+        let mut segments = Vec::with_capacity(self.segments.len());
+        let mut run = vec![self.start];
+
+        for seg in self.segments.drain(..) {
+            match seg {
+                Segment::LineTo { end } => run.push(end),
+                Segment::QuadBezTo { end, .. } | Segment::CubBezTo { end, .. } => {
+                    flush_run(&mut run, &mut segments, epsilon);
+                    segments.push(seg);
+                    run.push(end);
+                }
+            }
+        }
+        flush_run(&mut run, &mut segments, epsilon);
+
+        self.segments = segments;
+    }
+
     /// Convert to [kurbo::BezPath], flattened to the given precision.
     pub fn to_kurbo_flattened(&self, tolerance: f64) -> kurbo::BezPath {
         let elements = self.to_kurbo_el_iter();
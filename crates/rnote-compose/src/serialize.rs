@@ -36,6 +36,27 @@ pub mod na_vector2_f64_dp3 {
     }
 }
 
+/// (De)Serialize a [`na::Vector2<f64>`] rounded to 5 decimal places
+///
+/// For coordinates that are relative to some extents, in the range 0.0 to 1.0.
+pub mod na_vector2_f64_dp5 {
+    use serde::{Deserialize, Serialize};
+    use serde::{Deserializer, Serializer};
+
+    /// Serialize a [`na::Vector2<f64>`] rounded to 5 decimal places
+    pub fn serialize<S: Serializer>(v: &na::Vector2<f64>, s: S) -> Result<S::Ok, S::Error> {
+        const D: f64 = (10_u32.pow(5)) as f64;
+        let mut a = v * D;
+        a = na::vector![a[0].round(), a[1].round()];
+        (a / D).serialize(s)
+    }
+
+    /// Deserialize a [`na::Vector2<f64>`]
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<na::Vector2<f64>, D::Error> {
+        na::Vector2::<f64>::deserialize(d)
+    }
+}
+
 /// (De)Serialize a [`na::Affine2<f64>`] rounded to 3 decimal places
 pub mod na_affine2_f64_dp3 {
     use serde::{Deserialize, Serialize};
@@ -0,0 +1,270 @@
+// Imports
+use super::{CubicBezier, Shapeable};
+use crate::ext::{KurboShapeExt, Vector2Ext};
+use crate::transform::Transformable;
+use kurbo::Shape;
+use p2d::bounding_volume::Aabb;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default, rename = "bezier_segment")]
+/// A segment of a bezier path, the cubic curve from the end of the previous segment to its end.
+pub struct BezierSegment {
+    #[serde(rename = "cp1", with = "crate::serialize::na_vector2_f64_dp3")]
+    /// First control point coordinate, the outgoing handle of the previous anchor.
+    pub cp1: na::Vector2<f64>,
+    #[serde(rename = "cp2", with = "crate::serialize::na_vector2_f64_dp3")]
+    /// Second control point coordinate, the incoming handle of the end anchor.
+    pub cp2: na::Vector2<f64>,
+    #[serde(rename = "end", with = "crate::serialize::na_vector2_f64_dp3")]
+    /// End anchor coordinate.
+    pub end: na::Vector2<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "bezier_path")]
+/// A path of connected cubic bezier curves.
+///
+/// Its nodes are the anchors and the control points (handles) of the segments.
+/// They are indexed in path order: the start anchor, then `cp1`, `cp2` and `end` of every segment.
+pub struct BezierPath {
+    #[serde(rename = "start", with = "crate::serialize::na_vector2_f64_dp3")]
+    /// Start anchor coordinate.
+    pub start: na::Vector2<f64>,
+    #[serde(rename = "segments")]
+    /// The segments.
+    pub segments: Vec<BezierSegment>,
+}
+
+impl Transformable for BezierPath {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.start += offset;
+        for segment in &mut self.segments {
+            segment.cp1 += offset;
+            segment.cp2 += offset;
+            segment.end += offset;
+        }
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        let mut isometry = na::Isometry2::identity();
+        isometry.append_rotation_wrt_point_mut(&na::UnitComplex::new(angle), &center);
+
+        self.start = isometry.transform_point(&self.start.into()).coords;
+        for segment in &mut self.segments {
+            segment.cp1 = isometry.transform_point(&segment.cp1.into()).coords;
+            segment.cp2 = isometry.transform_point(&segment.cp2.into()).coords;
+            segment.end = isometry.transform_point(&segment.end.into()).coords;
+        }
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.start = self.start.component_mul(&scale);
+        for segment in &mut self.segments {
+            segment.cp1 = segment.cp1.component_mul(&scale);
+            segment.cp2 = segment.cp2.component_mul(&scale);
+            segment.end = segment.end.component_mul(&scale);
+        }
+    }
+}
+
+impl Shapeable for BezierPath {
+    fn bounds(&self) -> Aabb {
+        if self.segments.is_empty() {
+            return Aabb::new(self.start.into(), self.start.into());
+        }
+        self.outline_path().bounding_box().bounds_to_p2d_aabb()
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        if self.segments.is_empty() {
+            return vec![self.bounds()];
+        }
+        self.to_cubbezs()
+            .flat_map(|cubbez| cubbez.hitboxes())
+            .collect()
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        let iter = std::iter::once(kurbo::PathEl::MoveTo(self.start.to_kurbo_point())).chain(
+            self.segments.iter().map(|segment| {
+                kurbo::PathEl::CurveTo(
+                    segment.cp1.to_kurbo_point(),
+                    segment.cp2.to_kurbo_point(),
+                    segment.end.to_kurbo_point(),
+                )
+            }),
+        );
+        kurbo::BezPath::from_iter(iter)
+    }
+}
+
+impl BezierPath {
+    /// A new bezier path with only the start anchor.
+    pub fn new(start: na::Vector2<f64>) -> Self {
+        Self {
+            start,
+            segments: Vec::new(),
+        }
+    }
+
+    /// The segments as cubic bezier curves.
+    pub fn to_cubbezs(&self) -> impl Iterator<Item = CubicBezier> + '_ {
+        let starts = std::iter::once(self.start).chain(self.segments.iter().map(|s| s.end));
+        starts
+            .zip(self.segments.iter())
+            .map(|(start, segment)| CubicBezier {
+                start,
+                cp1: segment.cp1,
+                cp2: segment.cp2,
+                end: segment.end,
+            })
+    }
+
+    /// The positions of all nodes, in node index order.
+    pub fn nodes(&self) -> Vec<na::Vector2<f64>> {
+        std::iter::once(self.start)
+            .chain(
+                self.segments
+                    .iter()
+                    .flat_map(|segment| [segment.cp1, segment.cp2, segment.end]),
+            )
+            .collect()
+    }
+
+    /// Whether the node at the index is an anchor, else it is a control point.
+    pub fn is_anchor_node(index: usize) -> bool {
+        index % 3 == 0
+    }
+
+    /// The lines between the anchors and their control points, as (anchor, control point) pairs.
+    pub fn handle_lines(&self) -> Vec<(na::Vector2<f64>, na::Vector2<f64>)> {
+        self.to_cubbezs()
+            .flat_map(|cubbez| [(cubbez.start, cubbez.cp1), (cubbez.end, cubbez.cp2)])
+            .collect()
+    }
+
+    /// Move the node at the index to a new position.
+    ///
+    /// Moving an anchor moves its control points along with it.
+    /// Returns false if there is no node at the index.
+    pub fn move_node(&mut self, index: usize, pos: na::Vector2<f64>) -> bool {
+        if index > self.segments.len() * 3 {
+            return false;
+        }
+        if index == 0 {
+            let offset = pos - self.start;
+            self.start = pos;
+            if let Some(first) = self.segments.first_mut() {
+                first.cp1 += offset;
+            }
+            return true;
+        }
+        let segment_index = (index - 1) / 3;
+        match (index - 1) % 3 {
+            0 => self.segments[segment_index].cp1 = pos,
+            1 => self.segments[segment_index].cp2 = pos,
+            _ => {
+                let offset = pos - self.segments[segment_index].end;
+                self.segments[segment_index].end = pos;
+                self.segments[segment_index].cp2 += offset;
+                if let Some(next) = self.segments.get_mut(segment_index + 1) {
+                    next.cp1 += offset;
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shapes::bezierpath::{BezierPath, BezierSegment};
+    use approx::assert_relative_eq;
+
+    fn bezier_path() -> BezierPath {
+        BezierPath {
+            start: na::vector![0.0, 0.0],
+            segments: vec![
+                BezierSegment {
+                    cp1: na::vector![10.0, 0.0],
+                    cp2: na::vector![20.0, 10.0],
+                    end: na::vector![30.0, 10.0],
+                },
+                BezierSegment {
+                    cp1: na::vector![40.0, 10.0],
+                    cp2: na::vector![50.0, 0.0],
+                    end: na::vector![60.0, 0.0],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn move_start_anchor() {
+        let mut path = bezier_path();
+        assert!(path.move_node(0, na::vector![5.0, 5.0]));
+        assert_relative_eq!(path.start, na::vector![5.0, 5.0]);
+        assert_relative_eq!(path.segments[0].cp1, na::vector![15.0, 5.0]);
+        assert_relative_eq!(path.segments[0].cp2, na::vector![20.0, 10.0]);
+    }
+
+    #[test]
+    fn move_control_points() {
+        let mut path = bezier_path();
+        assert!(path.move_node(1, na::vector![1.0, 2.0]));
+        assert!(path.move_node(5, na::vector![3.0, 4.0]));
+        assert_relative_eq!(path.segments[0].cp1, na::vector![1.0, 2.0]);
+        assert_relative_eq!(path.segments[1].cp2, na::vector![3.0, 4.0]);
+        // the anchors stay in place
+        assert_relative_eq!(path.start, na::vector![0.0, 0.0]);
+        assert_relative_eq!(path.segments[0].end, na::vector![30.0, 10.0]);
+        assert_relative_eq!(path.segments[1].end, na::vector![60.0, 0.0]);
+    }
+
+    #[test]
+    fn move_inner_anchor() {
+        let mut path = bezier_path();
+        assert!(path.move_node(3, na::vector![30.0, 20.0]));
+        assert_relative_eq!(path.segments[0].end, na::vector![30.0, 20.0]);
+        // both adjacent handles move along
+        assert_relative_eq!(path.segments[0].cp2, na::vector![20.0, 20.0]);
+        assert_relative_eq!(path.segments[1].cp1, na::vector![40.0, 20.0]);
+        assert_relative_eq!(path.segments[0].cp1, na::vector![10.0, 0.0]);
+        assert_relative_eq!(path.segments[1].cp2, na::vector![50.0, 0.0]);
+    }
+
+    #[test]
+    fn move_end_anchor() {
+        let mut path = bezier_path();
+        assert!(path.move_node(6, na::vector![70.0, 0.0]));
+        assert_relative_eq!(path.segments[1].end, na::vector![70.0, 0.0]);
+        assert_relative_eq!(path.segments[1].cp2, na::vector![60.0, 0.0]);
+        assert_relative_eq!(path.segments[1].cp1, na::vector![40.0, 10.0]);
+    }
+
+    #[test]
+    fn move_node_out_of_range() {
+        let mut path = bezier_path();
+        assert!(!path.move_node(7, na::vector![1.0, 1.0]));
+        assert_eq!(path.nodes(), bezier_path().nodes());
+
+        let mut path = BezierPath::new(na::vector![0.0, 0.0]);
+        assert!(!path.move_node(1, na::vector![1.0, 1.0]));
+        assert!(path.move_node(0, na::vector![1.0, 1.0]));
+        assert_relative_eq!(path.start, na::vector![1.0, 1.0]);
+    }
+
+    #[test]
+    fn node_indices() {
+        let path = bezier_path();
+        let nodes = path.nodes();
+        assert_eq!(nodes.len(), 7);
+        assert_relative_eq!(nodes[3], path.segments[0].end);
+        assert!(BezierPath::is_anchor_node(0));
+        assert!(!BezierPath::is_anchor_node(1));
+        assert!(!BezierPath::is_anchor_node(2));
+        assert!(BezierPath::is_anchor_node(3));
+        assert!(BezierPath::is_anchor_node(6));
+    }
+}
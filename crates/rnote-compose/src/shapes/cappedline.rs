@@ -0,0 +1,406 @@
+// Imports
+use super::{Arrow, Line, Shapeable};
+use crate::ext::Vector2Ext;
+use crate::transform::Transformable;
+use crate::Color;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use piet::{RenderContext, Text, TextLayout, TextLayoutBuilder};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "line_cap")]
+/// A decoration at the start or end of a line.
+pub enum LineCap {
+    /// No decoration.
+    #[serde(rename = "none")]
+    None = 0,
+    /// An arrowhead pointing away from the line.
+    #[serde(rename = "arrow")]
+    Arrow,
+    /// A filled dot.
+    #[serde(rename = "dot")]
+    Dot,
+    /// A bar perpendicular to the line.
+    #[serde(rename = "bar")]
+    Bar,
+}
+
+impl Default for LineCap {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl TryFrom<u32> for LineCap {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value)
+            .ok_or_else(|| anyhow::anyhow!("LineCap try_from::<u32>() for value {value} failed"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "dimension_label")]
+/// The measured length label of a dimension line.
+pub struct DimensionLabel {
+    /// The factor converting a length in document coordinates into the measured length.
+    #[serde(rename = "units_per_px")]
+    pub units_per_px: f64,
+    /// The abbreviation of the unit of the measured length.
+    #[serde(rename = "unit")]
+    pub unit: String,
+}
+
+impl Default for DimensionLabel {
+    fn default() -> Self {
+        Self {
+            units_per_px: 1.0,
+            unit: String::from("px"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "capped_line")]
+/// A line with decorations at its start and end.
+///
+/// As a dimension line it additionally shows its measured length at the midpoint.
+pub struct CappedLine {
+    /// The line.
+    #[serde(rename = "line")]
+    pub line: Line,
+    /// The decoration at the start.
+    #[serde(rename = "start_cap")]
+    pub start_cap: LineCap,
+    /// The decoration at the end.
+    #[serde(rename = "end_cap")]
+    pub end_cap: LineCap,
+    /// The measured length label, if it is a dimension line.
+    #[serde(rename = "dimension")]
+    pub dimension: Option<DimensionLabel>,
+}
+
+impl Transformable for CappedLine {
+    fn translate(&mut self, offset: na::Vector2<f64>) {
+        self.line.translate(offset);
+    }
+
+    fn rotate(&mut self, angle: f64, center: na::Point2<f64>) {
+        self.line.rotate(angle, center);
+    }
+
+    fn scale(&mut self, scale: na::Vector2<f64>) {
+        self.line.scale(scale);
+    }
+}
+
+impl Shapeable for CappedLine {
+    fn bounds(&self) -> Aabb {
+        self.internal_compute_bounds(None)
+    }
+
+    fn hitboxes(&self) -> Vec<Aabb> {
+        self.line.hitboxes()
+    }
+
+    fn outline_path(&self) -> kurbo::BezPath {
+        let mut bez_path = self.line.outline_path();
+        bez_path.extend(self.caps_path(None));
+        for (center, radius) in self.cap_dots(None) {
+            bez_path.extend(kurbo::Shape::path_elements(
+                &kurbo::Circle::new(center.to_kurbo_point(), radius),
+                0.25,
+            ));
+        }
+        bez_path
+    }
+}
+
+impl CappedLine {
+    /// The default font size of the dimension label.
+    const LABEL_FONT_SIZE_DEFAULT: f64 = 12.0;
+    /// The gap between the line and the dimension label.
+    const LABEL_GAP: f64 = 4.0;
+
+    /// A new line with the given caps and without a dimension label.
+    pub fn new(line: Line, start_cap: LineCap, end_cap: LineCap) -> Self {
+        Self {
+            line,
+            start_cap,
+            end_cap,
+            dimension: None,
+        }
+    }
+
+    /// The length of the line in document coordinates.
+    pub fn length(&self) -> f64 {
+        (self.line.end - self.line.start).magnitude()
+    }
+
+    /// The text of the dimension label, if it is a dimension line.
+    pub fn dimension_text(&self) -> Option<String> {
+        let dimension = self.dimension.as_ref()?;
+        Some(format!(
+            "{:.2} {}",
+            self.length() * dimension.units_per_px,
+            dimension.unit
+        ))
+    }
+
+    /// The arrowheads and bars as open paths that are stroked.
+    ///
+    /// Optionally the stroke width is used to adjust their size.
+    pub fn caps_path(&self, stroke_width: Option<f64>) -> kurbo::BezPath {
+        let mut bez_path = kurbo::BezPath::new();
+        for (cap, from, pos) in self.caps() {
+            let points = match cap {
+                LineCap::Arrow => {
+                    let arrow = Arrow::new(from, pos);
+                    vec![
+                        arrow.compute_lline(stroke_width),
+                        pos,
+                        arrow.compute_rline(stroke_width),
+                    ]
+                }
+                LineCap::Bar => {
+                    let direction = (pos - from)
+                        .try_normalize(f64::EPSILON)
+                        .unwrap_or(na::Vector2::x());
+                    let half_extent = na::vector![-direction[1], direction[0]]
+                        * Self::bar_half_length(stroke_width);
+                    vec![pos - half_extent, pos + half_extent]
+                }
+                LineCap::None | LineCap::Dot => continue,
+            };
+            bez_path.move_to(points[0].to_kurbo_point());
+            for p in points.iter().skip(1) {
+                bez_path.line_to(p.to_kurbo_point());
+            }
+        }
+        bez_path
+    }
+
+    /// The dots as (center, radius) pairs that are filled.
+    ///
+    /// Optionally the stroke width is used to adjust their size.
+    pub fn cap_dots(&self, stroke_width: Option<f64>) -> Vec<(na::Vector2<f64>, f64)> {
+        let radius = 2.5 + stroke_width.unwrap_or(0.0) * 1.2;
+        self.caps()
+            .into_iter()
+            .filter(|(cap, ..)| *cap == LineCap::Dot)
+            .map(|(_, _, pos)| (pos, radius))
+            .collect()
+    }
+
+    /// Compute the bounds in respect to the given stroke width.
+    pub fn internal_compute_bounds(&self, stroke_width: Option<f64>) -> Aabb {
+        let mut bounds = self.line.bounds();
+        for el in self.caps_path(stroke_width).elements() {
+            if let kurbo::PathEl::MoveTo(p) | kurbo::PathEl::LineTo(p) = el {
+                bounds.take_point(na::point![p.x, p.y]);
+            }
+        }
+        for (center, radius) in self.cap_dots(stroke_width) {
+            bounds.merge(&Aabb::from_half_extents(
+                center.into(),
+                na::Vector2::repeat(radius),
+            ));
+        }
+        if let Some(label_bounds) = self.dimension_label_bounds(stroke_width.unwrap_or(0.0)) {
+            bounds.merge(&label_bounds);
+        }
+        bounds
+    }
+
+    /// Draw the dimension label above the midpoint of the line, if it is a dimension line.
+    pub fn draw_dimension_label(
+        &self,
+        cx: &mut impl RenderContext,
+        color: Color,
+        stroke_width: f64,
+    ) {
+        let Some(text) = self.dimension_text() else {
+            return;
+        };
+        let text_layout = match cx
+            .text()
+            .new_text_layout(text)
+            .text_color(color.into())
+            .font(
+                piet::FontFamily::SANS_SERIF,
+                Self::label_font_size(stroke_width),
+            )
+            .build()
+        {
+            Ok(text_layout) => text_layout,
+            Err(e) => {
+                error!("Building dimension label text layout failed, Err: {e:?}");
+                return;
+            }
+        };
+        let size = text_layout.size();
+        let center = self.label_center(stroke_width);
+        cx.draw_text(
+            &text_layout,
+            kurbo::Point::new(center[0] - size.width * 0.5, center[1] - size.height * 0.5),
+        );
+    }
+
+    /// The caps with the position they point away from and their position.
+    fn caps(&self) -> [(LineCap, na::Vector2<f64>, na::Vector2<f64>); 2] {
+        [
+            (self.start_cap, self.line.end, self.line.start),
+            (self.end_cap, self.line.start, self.line.end),
+        ]
+    }
+
+    fn bar_half_length(stroke_width: Option<f64>) -> f64 {
+        6.0 * (1.0 + 0.18 * stroke_width.unwrap_or(0.0))
+    }
+
+    fn label_font_size(stroke_width: f64) -> f64 {
+        Self::LABEL_FONT_SIZE_DEFAULT + stroke_width * 2.0
+    }
+
+    /// The center of the dimension label, offset from the midpoint to the upper side of the line.
+    fn label_center(&self, stroke_width: f64) -> na::Vector2<f64> {
+        let direction = (self.line.end - self.line.start)
+            .try_normalize(f64::EPSILON)
+            .unwrap_or(na::Vector2::x());
+        let mut normal = na::vector![direction[1], -direction[0]];
+        if normal[1] > 0.0 {
+            normal = -normal;
+        }
+        let offset =
+            Self::label_font_size(stroke_width) * 0.6 + stroke_width * 0.5 + Self::LABEL_GAP;
+        (self.line.start + self.line.end) * 0.5 + normal * offset
+    }
+
+    /// The approximate bounds of the dimension label, since measuring the text needs a render context.
+    fn dimension_label_bounds(&self, stroke_width: f64) -> Option<Aabb> {
+        let text = self.dimension_text()?;
+        let font_size = Self::label_font_size(stroke_width);
+        let half_extents = na::vector![
+            text.chars().count() as f64 * font_size * 0.3,
+            font_size * 0.6
+        ];
+        Some(
+            Aabb::from_half_extents(self.label_center(stroke_width).into(), half_extents)
+                .loosened(font_size * 0.2),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shapes::cappedline::{CappedLine, DimensionLabel, LineCap};
+    use crate::shapes::{Line, Shapeable};
+    use approx::assert_relative_eq;
+
+    fn horizontal_line() -> Line {
+        Line {
+            start: na::vector![0.0, 0.0],
+            end: na::vector![100.0, 0.0],
+        }
+    }
+
+    #[test]
+    fn line_cap_try_from() {
+        assert_eq!(LineCap::try_from(0).unwrap(), LineCap::None);
+        assert_eq!(LineCap::try_from(3).unwrap(), LineCap::Bar);
+        assert!(LineCap::try_from(4).is_err());
+    }
+
+    #[test]
+    fn no_caps_bounds() {
+        let capped_line = CappedLine::new(horizontal_line(), LineCap::None, LineCap::None);
+        assert!(capped_line.caps_path(None).elements().is_empty());
+        assert!(capped_line.cap_dots(None).is_empty());
+        assert_relative_eq!(capped_line.bounds().mins, horizontal_line().bounds().mins);
+        assert_relative_eq!(capped_line.bounds().maxs, horizontal_line().bounds().maxs);
+    }
+
+    #[test]
+    fn bar_and_dot_bounds() {
+        let capped_line = CappedLine::new(horizontal_line(), LineCap::Dot, LineCap::Bar);
+
+        let dots = capped_line.cap_dots(None);
+        assert_eq!(dots.len(), 1);
+        assert_relative_eq!(dots[0].0, na::vector![0.0, 0.0]);
+
+        let bounds = capped_line.bounds();
+        // the dot at the start
+        assert_relative_eq!(bounds.mins[0], -dots[0].1);
+        // the bar perpendicular to the line at the end
+        assert_relative_eq!(bounds.maxs[0], 100.0);
+        assert_relative_eq!(bounds.mins[1], -6.0);
+        assert_relative_eq!(bounds.maxs[1], 6.0);
+
+        // caps grow with the stroke width
+        let wide_bounds = capped_line.internal_compute_bounds(Some(10.0));
+        assert!(wide_bounds.mins[0] < bounds.mins[0]);
+        assert!(wide_bounds.maxs[1] > bounds.maxs[1]);
+    }
+
+    #[test]
+    fn arrow_bounds() {
+        let capped_line = CappedLine::new(horizontal_line(), LineCap::Arrow, LineCap::Arrow);
+        let bounds = capped_line.bounds();
+        assert_relative_eq!(bounds.mins[0], 0.0);
+        assert_relative_eq!(bounds.maxs[0], 100.0);
+        assert!(bounds.mins[1] < 0.0);
+        assert!(bounds.maxs[1] > 0.0);
+    }
+
+    #[test]
+    fn dimension_label() {
+        let mut capped_line = CappedLine::new(horizontal_line(), LineCap::Bar, LineCap::Bar);
+        assert_relative_eq!(capped_line.length(), 100.0);
+        assert!(capped_line.dimension_text().is_none());
+
+        capped_line.dimension = Some(DimensionLabel {
+            units_per_px: 0.1,
+            unit: String::from("mm"),
+        });
+        assert_eq!(capped_line.dimension_text().unwrap(), "10.00 mm");
+        // the label is above the line, so it extends the bounds upwards
+        let bounds = capped_line.bounds();
+        assert!(bounds.mins[1] < -6.0);
+        assert_relative_eq!(bounds.maxs[1], 6.0);
+    }
+
+    #[test]
+    fn dimension_label_above_in_both_directions() {
+        let line = Line {
+            start: na::vector![0.0, 0.0],
+            end: na::vector![100.0, 100.0],
+        };
+        let reversed = Line {
+            start: line.end,
+            end: line.start,
+        };
+        let label_center = |line: Line| {
+            let capped_line = CappedLine {
+                dimension: Some(DimensionLabel::default()),
+                ..CappedLine::new(line, LineCap::None, LineCap::None)
+            };
+            capped_line.label_center(0.0)
+        };
+        let center = label_center(line);
+        assert_relative_eq!(center, label_center(reversed));
+        assert!(center[1] < 50.0);
+        assert!(center[0] > 50.0);
+    }
+}
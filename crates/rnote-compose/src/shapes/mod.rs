@@ -1,6 +1,10 @@
 // Modules
 /// Arrow
 pub mod arrow;
+/// Bezier path
+pub mod bezierpath;
+/// Line with caps
+pub mod cappedline;
 /// Cubic-bezier curve
 pub mod cubbez;
 /// Ellipse
@@ -22,6 +26,8 @@ pub mod shapeable;
 
 // Re-exports
 pub use arrow::Arrow;
+pub use bezierpath::{BezierPath, BezierSegment};
+pub use cappedline::{CappedLine, DimensionLabel, LineCap};
 pub use cubbez::CubicBezier;
 pub use ellipse::Ellipse;
 pub use line::Line;
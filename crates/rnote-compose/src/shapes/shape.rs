@@ -1,6 +1,7 @@
 // Imports
 use super::{
-    Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle, Shapeable,
+    Arrow, BezierPath, CappedLine, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier,
+    Rectangle, Shapeable,
 };
 use crate::transform::Transformable;
 use p2d::bounding_volume::Aabb;
@@ -34,6 +35,12 @@ pub enum Shape {
     /// A polygon shape.
     #[serde(rename = "polygon")]
     Polygon(Polygon),
+    /// A bezier path shape.
+    #[serde(rename = "bezier_path")]
+    BezierPath(BezierPath),
+    /// A line with caps, optionally as dimension line.
+    #[serde(rename = "capped_line")]
+    CappedLine(CappedLine),
 }
 
 impl Default for Shape {
@@ -42,6 +49,46 @@ impl Default for Shape {
     }
 }
 
+impl Shape {
+    /// Join polylines and polygons whose end point lies within the tolerance of the start point.
+    ///
+    /// The end point is snapped onto the start point and polylines become (closed) polygons.
+    /// Other shapes are returned unchanged.
+    pub fn join_nearly_closed(self, tolerance: f64) -> Self {
+        match self {
+            Self::Polyline(polyline) => {
+                if polyline.path.len() >= 3
+                    && polyline
+                        .path
+                        .last()
+                        .is_some_and(|last| (last - polyline.start).magnitude() <= tolerance)
+                {
+                    let mut path = polyline.path;
+                    path.pop();
+                    Self::Polygon(Polygon {
+                        start: polyline.start,
+                        path,
+                    })
+                } else {
+                    Self::Polyline(polyline)
+                }
+            }
+            Self::Polygon(mut polygon) => {
+                if polygon.path.len() >= 3
+                    && polygon
+                        .path
+                        .last()
+                        .is_some_and(|last| (last - polygon.start).magnitude() <= tolerance)
+                {
+                    polygon.path.pop();
+                }
+                Self::Polygon(polygon)
+            }
+            shape => shape,
+        }
+    }
+}
+
 impl Transformable for Shape {
     fn translate(&mut self, offset: na::Vector2<f64>) {
         match self {
@@ -69,6 +116,12 @@ impl Transformable for Shape {
             Self::Polygon(polygon) => {
                 polygon.translate(offset);
             }
+            Self::BezierPath(bezier_path) => {
+                bezier_path.translate(offset);
+            }
+            Self::CappedLine(capped_line) => {
+                capped_line.translate(offset);
+            }
         }
     }
 
@@ -98,6 +151,12 @@ impl Transformable for Shape {
             Self::Polygon(polygon) => {
                 polygon.rotate(angle, center);
             }
+            Self::BezierPath(bezier_path) => {
+                bezier_path.rotate(angle, center);
+            }
+            Self::CappedLine(capped_line) => {
+                capped_line.rotate(angle, center);
+            }
         }
     }
 
@@ -127,6 +186,12 @@ impl Transformable for Shape {
             Self::Polygon(polygon) => {
                 polygon.scale(scale);
             }
+            Self::BezierPath(bezier_path) => {
+                bezier_path.scale(scale);
+            }
+            Self::CappedLine(capped_line) => {
+                capped_line.scale(scale);
+            }
         }
     }
 }
@@ -142,6 +207,8 @@ impl Shapeable for Shape {
             Self::CubicBezier(cubbez) => cubbez.bounds(),
             Self::Polyline(polyline) => polyline.bounds(),
             Self::Polygon(polygon) => polygon.bounds(),
+            Self::BezierPath(bezier_path) => bezier_path.bounds(),
+            Self::CappedLine(capped_line) => capped_line.bounds(),
         }
     }
 
@@ -155,6 +222,8 @@ impl Shapeable for Shape {
             Self::CubicBezier(cubbez) => cubbez.hitboxes(),
             Self::Polyline(polyline) => polyline.hitboxes(),
             Self::Polygon(polygon) => polygon.hitboxes(),
+            Self::BezierPath(bezier_path) => bezier_path.hitboxes(),
+            Self::CappedLine(capped_line) => capped_line.hitboxes(),
         }
     }
 
@@ -168,6 +237,8 @@ impl Shapeable for Shape {
             Self::CubicBezier(cubbez) => cubbez.outline_path(),
             Self::Polyline(polyline) => polyline.outline_path(),
             Self::Polygon(polygon) => polygon.outline_path(),
+            Self::BezierPath(bezier_path) => bezier_path.outline_path(),
+            Self::CappedLine(capped_line) => capped_line.outline_path(),
         }
     }
 }
@@ -0,0 +1,58 @@
+// Imports
+use crate::Color;
+use serde::{Deserialize, Serialize};
+
+/// Options for paths that are drawn with a flat calligraphy nib.
+///
+/// The stroke width varies with the direction of the path relative to the nib angle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "calligraphy_options")]
+pub struct CalligraphyOptions {
+    /// Stroke width, the length of the nib.
+    #[serde(rename = "stroke_width", with = "crate::serialize::f64_dp3")]
+    pub stroke_width: f64,
+    /// Stroke color. When set to None, the stroke is not drawn.
+    #[serde(rename = "stroke_color")]
+    pub stroke_color: Option<Color>,
+    /// The angle of the nib to the x-axis, in radians.
+    #[serde(rename = "nib_angle", with = "crate::serialize::f64_dp3")]
+    pub nib_angle: f64,
+    /// The ratio of the nib thickness to its length.
+    #[serde(rename = "width_ratio", with = "crate::serialize::f64_dp3")]
+    pub width_ratio: f64,
+}
+
+impl Default for CalligraphyOptions {
+    fn default() -> Self {
+        Self {
+            stroke_width: 8.0,
+            stroke_color: Some(Color::BLACK),
+            nib_angle: std::f64::consts::FRAC_PI_4,
+            width_ratio: 0.15,
+        }
+    }
+}
+
+impl CalligraphyOptions {
+    /// Minimum nib width ratio.
+    pub const WIDTH_RATIO_MIN: f64 = 0.01;
+    /// Maximum nib width ratio.
+    pub const WIDTH_RATIO_MAX: f64 = 1.0;
+
+    /// The corners of the nib, relative to its center.
+    pub(super) fn nib_corners(&self) -> [na::Vector2<f64>; 4] {
+        let width_ratio = self
+            .width_ratio
+            .clamp(Self::WIDTH_RATIO_MIN, Self::WIDTH_RATIO_MAX);
+        let dir = na::vector![self.nib_angle.cos(), self.nib_angle.sin()];
+        let along = dir * self.stroke_width * 0.5;
+        let across = na::vector![-dir[1], dir[0]] * self.stroke_width * width_ratio * 0.5;
+
+        [
+            along + across,
+            along - across,
+            -along - across,
+            -along + across,
+        ]
+    }
+}
@@ -0,0 +1,92 @@
+// Modules
+mod calligraphyoptions;
+
+// Re-exports
+pub use calligraphyoptions::CalligraphyOptions;
+
+// Imports
+use super::Composer;
+use crate::ext::Vector2Ext;
+use crate::shapes::{Line, Shapeable};
+use crate::PenPath;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+
+/// The tolerance used when flattening curve segments before sweeping the nib along them.
+const FLATTEN_TOLERANCE: f64 = 0.1;
+
+impl Composer<CalligraphyOptions> for Line {
+    fn composed_bounds(&self, options: &CalligraphyOptions) -> Aabb {
+        self.bounds().loosened(options.stroke_width * 0.5)
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &CalligraphyOptions) {
+        let Some(stroke_color) = options.stroke_color else {
+            return;
+        };
+        cx.save().unwrap();
+        let mut bez_path = kurbo::BezPath::new();
+        append_swept_nib(&mut bez_path, self.start, self.end, &options.nib_corners());
+        cx.fill(bez_path, &Into::<piet::Color>::into(stroke_color));
+        cx.restore().unwrap();
+    }
+}
+
+impl Composer<CalligraphyOptions> for PenPath {
+    fn composed_bounds(&self, options: &CalligraphyOptions) -> Aabb {
+        self.bounds().loosened(options.stroke_width * 0.5)
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &CalligraphyOptions) {
+        let Some(stroke_color) = options.stroke_color else {
+            return;
+        };
+        let nib_corners = options.nib_corners();
+        let mut bez_path = kurbo::BezPath::new();
+        let mut prev = self.start.pos;
+
+        // Stamp the nib at the start so that single positions are drawn as well
+        append_swept_nib(&mut bez_path, prev, prev, &nib_corners);
+
+        for el in self.to_kurbo_flattened(FLATTEN_TOLERANCE).elements() {
+            match el {
+                kurbo::PathEl::MoveTo(p) => prev = na::vector![p.x, p.y],
+                kurbo::PathEl::LineTo(p) => {
+                    let pos = na::vector![p.x, p.y];
+                    append_swept_nib(&mut bez_path, prev, pos, &nib_corners);
+                    prev = pos;
+                }
+                _ => {}
+            }
+        }
+
+        cx.save().unwrap();
+        // All swept polygons share the same orientation, so the non-zero fill rule merges them
+        // without overlapping alpha.
+        cx.fill(bez_path, &Into::<piet::Color>::into(stroke_color));
+        cx.restore().unwrap();
+    }
+}
+
+/// Append the area the nib covers when moving from start to end as a closed, counter-clockwise polygon.
+fn append_swept_nib(
+    bez_path: &mut kurbo::BezPath,
+    start: na::Vector2<f64>,
+    end: na::Vector2<f64>,
+    nib_corners: &[na::Vector2<f64>; 4],
+) {
+    let points = nib_corners
+        .iter()
+        .flat_map(|c| [na::Point2::from(start + c), na::Point2::from(end + c)])
+        .collect::<Vec<na::Point2<f64>>>();
+    let hull = p2d::transformation::convex_hull(&points);
+    let mut hull_iter = hull.iter();
+
+    let Some(first) = hull_iter.next() else {
+        return;
+    };
+    bez_path.move_to(first.coords.to_kurbo_point());
+    for p in hull_iter {
+        bez_path.line_to(p.coords.to_kurbo_point());
+    }
+    bez_path.close_path();
+}
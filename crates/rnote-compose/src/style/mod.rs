@@ -1,4 +1,6 @@
 // Modules
+/// The calligraphy module for the calligraphy style
+pub mod calligraphy;
 /// Composer
 mod composer;
 /// Draw indicators
@@ -11,13 +13,15 @@ pub mod smooth;
 pub mod textured;
 
 // Re-exports
+use self::calligraphy::CalligraphyOptions;
 use self::rough::RoughOptions;
 use self::smooth::SmoothOptions;
 use self::textured::TexturedOptions;
 
 // Imports
 use crate::shapes::{
-    Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle,
+    Arrow, BezierPath, CappedLine, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier,
+    Rectangle,
 };
 use crate::{Color, PenPath, Shape};
 use anyhow::Context;
@@ -37,6 +41,9 @@ pub enum Style {
     /// A textured style
     #[serde(rename = "textured")]
     Textured(TexturedOptions),
+    /// A calligraphy style
+    #[serde(rename = "calligraphy")]
+    Calligraphy(CalligraphyOptions),
 }
 
 impl Default for Style {
@@ -52,6 +59,7 @@ impl Style {
             Style::Smooth(options) => options.stroke_width,
             Style::Rough(options) => options.stroke_width,
             Style::Textured(options) => options.stroke_width,
+            Style::Calligraphy(options) => options.stroke_width,
         }
     }
 
@@ -61,6 +69,7 @@ impl Style {
             Style::Smooth(options) => options.stroke_width = stroke_width,
             Style::Rough(options) => options.stroke_width = stroke_width,
             Style::Textured(options) => options.stroke_width = stroke_width,
+            Style::Calligraphy(options) => options.stroke_width = stroke_width,
         }
     }
 
@@ -70,6 +79,7 @@ impl Style {
             Style::Smooth(options) => options.stroke_width,
             Style::Rough(options) => options.stroke_width + RoughOptions::ROUGH_BOUNDS_MARGIN,
             Style::Textured(options) => options.stroke_width,
+            Style::Calligraphy(options) => options.stroke_width,
         }
     }
 
@@ -79,6 +89,7 @@ impl Style {
             Style::Smooth(_) => {}
             Style::Rough(options) => options.advance_seed(),
             Style::Textured(options) => options.advance_seed(),
+            Style::Calligraphy(_) => {}
         }
     }
 
@@ -88,6 +99,7 @@ impl Style {
             Style::Smooth(options) => options.stroke_color,
             Style::Rough(options) => options.stroke_color,
             Style::Textured(options) => options.stroke_color,
+            Style::Calligraphy(options) => options.stroke_color,
         }
     }
 
@@ -96,7 +108,7 @@ impl Style {
         match self {
             Style::Smooth(options) => options.fill_color,
             Style::Rough(options) => options.fill_color,
-            Style::Textured(_) => None,
+            Style::Textured(_) | Style::Calligraphy(_) => None,
         }
     }
 
@@ -106,6 +118,7 @@ impl Style {
             Style::Smooth(options) => options.stroke_color = Some(color),
             Style::Rough(options) => options.stroke_color = Some(color),
             Style::Textured(options) => options.stroke_color = Some(color),
+            Style::Calligraphy(options) => options.stroke_color = Some(color),
         };
     }
 
@@ -114,7 +127,7 @@ impl Style {
         match self {
             Style::Smooth(options) => options.fill_color = Some(color),
             Style::Rough(options) => options.fill_color = Some(color),
-            Style::Textured(_) => {}
+            Style::Textured(_) | Style::Calligraphy(_) => {}
         };
     }
 }
@@ -125,6 +138,7 @@ impl Composer<Style> for Line {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(options) => self.composed_bounds(options),
+            Style::Calligraphy(options) => self.composed_bounds(options),
         }
     }
 
@@ -133,6 +147,7 @@ impl Composer<Style> for Line {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(options) => self.draw_composed(cx, options),
+            Style::Calligraphy(options) => self.draw_composed(cx, options),
         }
     }
 }
@@ -143,6 +158,7 @@ impl Composer<Style> for Arrow {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
         }
     }
 
@@ -151,6 +167,7 @@ impl Composer<Style> for Arrow {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
         }
     }
 }
@@ -161,6 +178,7 @@ impl Composer<Style> for Rectangle {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
         }
     }
 
@@ -169,6 +187,7 @@ impl Composer<Style> for Rectangle {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
         }
     }
 }
@@ -179,6 +198,7 @@ impl Composer<Style> for Ellipse {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
         }
     }
 
@@ -187,6 +207,7 @@ impl Composer<Style> for Ellipse {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
         }
     }
 }
@@ -197,6 +218,7 @@ impl Composer<Style> for QuadraticBezier {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
         }
     }
 
@@ -205,6 +227,7 @@ impl Composer<Style> for QuadraticBezier {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
         }
     }
 }
@@ -215,6 +238,7 @@ impl Composer<Style> for CubicBezier {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
         }
     }
 
@@ -223,6 +247,7 @@ impl Composer<Style> for CubicBezier {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
         }
     }
 }
@@ -233,6 +258,7 @@ impl Composer<Style> for Polyline {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
         }
     }
 
@@ -241,6 +267,7 @@ impl Composer<Style> for Polyline {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
         }
     }
 }
@@ -251,6 +278,7 @@ impl Composer<Style> for Polygon {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(options) => self.composed_bounds(options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
         }
     }
 
@@ -259,6 +287,47 @@ impl Composer<Style> for Polygon {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(options) => self.draw_composed(cx, options),
             Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
+        }
+    }
+}
+
+impl Composer<Style> for BezierPath {
+    fn composed_bounds(&self, options: &Style) -> p2d::bounding_volume::Aabb {
+        match options {
+            Style::Smooth(options) => self.composed_bounds(options),
+            Style::Rough(options) => self.composed_bounds(options),
+            Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
+        }
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &Style) {
+        match options {
+            Style::Smooth(options) => self.draw_composed(cx, options),
+            Style::Rough(options) => self.draw_composed(cx, options),
+            Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
+        }
+    }
+}
+
+impl Composer<Style> for CappedLine {
+    fn composed_bounds(&self, options: &Style) -> p2d::bounding_volume::Aabb {
+        match options {
+            Style::Smooth(options) => self.composed_bounds(options),
+            Style::Rough(options) => self.composed_bounds(options),
+            Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
+        }
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &Style) {
+        match options {
+            Style::Smooth(options) => self.draw_composed(cx, options),
+            Style::Rough(options) => self.draw_composed(cx, options),
+            Style::Textured(_options) => unimplemented!(),
+            Style::Calligraphy(_options) => unimplemented!(),
         }
     }
 }
@@ -269,6 +338,7 @@ impl Composer<Style> for PenPath {
             Style::Smooth(options) => self.composed_bounds(options),
             Style::Rough(_) => unimplemented!(),
             Style::Textured(options) => self.composed_bounds(options),
+            Style::Calligraphy(options) => self.composed_bounds(options),
         }
     }
 
@@ -277,6 +347,7 @@ impl Composer<Style> for PenPath {
             Style::Smooth(options) => self.draw_composed(cx, options),
             Style::Rough(_) => unimplemented!(),
             Style::Textured(options) => self.draw_composed(cx, options),
+            Style::Calligraphy(options) => self.draw_composed(cx, options),
         }
     }
 }
@@ -292,6 +363,8 @@ impl Composer<Style> for Shape {
             Shape::CubicBezier(cubic_bezier) => cubic_bezier.composed_bounds(options),
             Shape::Polyline(polyline) => polyline.composed_bounds(options),
             Shape::Polygon(polygon) => polygon.composed_bounds(options),
+            Shape::BezierPath(bezier_path) => bezier_path.composed_bounds(options),
+            Shape::CappedLine(capped_line) => capped_line.composed_bounds(options),
         }
     }
 
@@ -305,6 +378,8 @@ impl Composer<Style> for Shape {
             Shape::CubicBezier(cubic_bezier) => cubic_bezier.draw_composed(cx, options),
             Shape::Polyline(polyline) => polyline.draw_composed(cx, options),
             Shape::Polygon(polygon) => polygon.draw_composed(cx, options),
+            Shape::BezierPath(bezier_path) => bezier_path.draw_composed(cx, options),
+            Shape::CappedLine(capped_line) => capped_line.draw_composed(cx, options),
         }
     }
 }
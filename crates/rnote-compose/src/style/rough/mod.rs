@@ -9,7 +9,8 @@ pub use roughoptions::RoughOptions;
 use super::Composer;
 use crate::ext::Vector2Ext;
 use crate::shapes::{
-    Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle, Shapeable,
+    Arrow, BezierPath, CappedLine, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier,
+    Rectangle, Shapeable,
 };
 use crate::Color;
 use p2d::bounding_volume::{Aabb, BoundingVolume};
@@ -109,6 +110,55 @@ impl Composer<RoughOptions> for Arrow {
     }
 }
 
+impl Composer<RoughOptions> for CappedLine {
+    fn composed_bounds(&self, options: &RoughOptions) -> p2d::bounding_volume::Aabb {
+        self.internal_compute_bounds(Some(options.stroke_width))
+            .loosened(options.stroke_width * 0.5 + RoughOptions::ROUGH_BOUNDS_MARGIN)
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
+        cx.save().unwrap();
+
+        let generator = rough_piet::KurboGenerator::new(generate_roughr_options(options));
+        generator
+            .line(
+                self.line.start[0],
+                self.line.start[1],
+                self.line.end[0],
+                self.line.end[1],
+            )
+            .draw(cx);
+        let mut cap_points = vec![];
+        for el in self.caps_path(Some(options.stroke_width)).elements() {
+            match el {
+                kurbo::PathEl::MoveTo(p) => {
+                    if cap_points.len() > 1 {
+                        generator.linear_path(&cap_points, false).draw(cx);
+                    }
+                    cap_points = vec![Point2D::new(p.x, p.y)];
+                }
+                kurbo::PathEl::LineTo(p) => cap_points.push(Point2D::new(p.x, p.y)),
+                _ => {}
+            }
+        }
+        if cap_points.len() > 1 {
+            generator.linear_path(&cap_points, false).draw(cx);
+        }
+        if let Some(stroke_color) = options.stroke_color {
+            // the dots are filled regardless of the fill style
+            for (center, radius) in self.cap_dots(Some(options.stroke_width)) {
+                cx.fill(
+                    kurbo::Circle::new(center.to_kurbo_point(), radius),
+                    &Into::<piet::Color>::into(stroke_color),
+                );
+            }
+            self.draw_dimension_label(cx, stroke_color, options.stroke_width);
+        }
+
+        cx.restore().unwrap();
+    }
+}
+
 impl Composer<RoughOptions> for Rectangle {
     fn composed_bounds(&self, options: &RoughOptions) -> p2d::bounding_volume::Aabb {
         self.bounds()
@@ -251,6 +301,32 @@ impl Composer<RoughOptions> for Polygon {
     }
 }
 
+impl Composer<RoughOptions> for BezierPath {
+    fn composed_bounds(&self, options: &RoughOptions) -> Aabb {
+        self.bounds()
+            .loosened(options.stroke_width * 0.5 + RoughOptions::ROUGH_BOUNDS_MARGIN)
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &RoughOptions) {
+        cx.save().unwrap();
+
+        // The segments are drawn individually, so the path is not filled
+        let generator = rough_piet::KurboGenerator::new(generate_roughr_options(options));
+        for cubbez in self.to_cubbezs() {
+            generator
+                .bezier_cubic(
+                    roughr::Point2D::new(cubbez.start[0] as f32, cubbez.start[1] as f32),
+                    roughr::Point2D::new(cubbez.cp1[0] as f32, cubbez.cp1[1] as f32),
+                    roughr::Point2D::new(cubbez.cp2[0] as f32, cubbez.cp2[1] as f32),
+                    roughr::Point2D::new(cubbez.end[0] as f32, cubbez.end[1] as f32),
+                )
+                .draw(cx);
+        }
+
+        cx.restore().unwrap();
+    }
+}
+
 impl Composer<RoughOptions> for crate::Shape {
     fn composed_bounds(&self, options: &RoughOptions) -> Aabb {
         match self {
@@ -262,6 +338,8 @@ impl Composer<RoughOptions> for crate::Shape {
             crate::Shape::CubicBezier(cubbez) => cubbez.composed_bounds(options),
             crate::Shape::Polyline(polyline) => polyline.composed_bounds(options),
             crate::Shape::Polygon(polygon) => polygon.composed_bounds(options),
+            crate::Shape::BezierPath(bezier_path) => bezier_path.composed_bounds(options),
+            crate::Shape::CappedLine(capped_line) => capped_line.composed_bounds(options),
         }
     }
 
@@ -275,6 +353,8 @@ impl Composer<RoughOptions> for crate::Shape {
             crate::Shape::CubicBezier(cubbez) => cubbez.draw_composed(cx, options),
             crate::Shape::Polyline(polyline) => polyline.draw_composed(cx, options),
             crate::Shape::Polygon(polygon) => polygon.draw_composed(cx, options),
+            crate::Shape::BezierPath(bezier_path) => bezier_path.draw_composed(cx, options),
+            crate::Shape::CappedLine(capped_line) => capped_line.draw_composed(cx, options),
         }
     }
 }
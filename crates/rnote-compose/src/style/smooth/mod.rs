@@ -9,7 +9,8 @@ use super::Composer;
 use crate::ext::Vector2Ext;
 use crate::penpath::{self, Segment};
 use crate::shapes::{
-    Arrow, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier, Rectangle, Shapeable,
+    Arrow, BezierPath, CappedLine, CubicBezier, Ellipse, Line, Polygon, Polyline, QuadraticBezier,
+    Rectangle, Shapeable,
 };
 use crate::PenPath;
 use kurbo::Shape;
@@ -54,6 +55,38 @@ impl Composer<SmoothOptions> for Arrow {
     }
 }
 
+impl Composer<SmoothOptions> for CappedLine {
+    fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
+        self.internal_compute_bounds(Some(options.stroke_width))
+            .loosened(options.stroke_width)
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
+        let Some(stroke_color) = options.stroke_color else {
+            return;
+        };
+        cx.save().unwrap();
+        let color = Into::<piet::Color>::into(stroke_color);
+
+        let mut path = self.line.outline_path();
+        path.extend(self.caps_path(Some(options.stroke_width)));
+        cx.stroke_styled(
+            path,
+            &color,
+            options.stroke_width,
+            &piet::StrokeStyle::default()
+                .line_cap(piet::LineCap::Round)
+                .line_join(piet::LineJoin::Round),
+        );
+        for (center, radius) in self.cap_dots(Some(options.stroke_width)) {
+            cx.fill(kurbo::Circle::new(center.to_kurbo_point(), radius), &color);
+        }
+        self.draw_dimension_label(cx, stroke_color, options.stroke_width);
+
+        cx.restore().unwrap();
+    }
+}
+
 impl Composer<SmoothOptions> for Rectangle {
     fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
         self.bounds().loosened(options.stroke_width * 0.5)
@@ -209,6 +242,41 @@ impl Composer<SmoothOptions> for Polygon {
     }
 }
 
+impl Composer<SmoothOptions> for BezierPath {
+    fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
+        self.bounds().loosened(options.stroke_width * 0.5)
+    }
+
+    fn draw_composed(&self, cx: &mut impl piet::RenderContext, options: &SmoothOptions) {
+        // Paths with only the start anchor need special treatment to be rendered
+        if self.segments.is_empty() {
+            if let Some(stroke_color) = options.stroke_color {
+                cx.fill(
+                    kurbo::Circle::new(self.start.to_kurbo_point(), options.stroke_width),
+                    &Into::<piet::Color>::into(stroke_color),
+                );
+            }
+            return;
+        }
+        let outline_path = self.outline_path();
+
+        if let Some(fill_color) = options.fill_color {
+            cx.fill(&outline_path, &Into::<piet::Color>::into(fill_color));
+        }
+
+        if let Some(stroke_color) = options.stroke_color {
+            cx.stroke_styled(
+                &outline_path,
+                &Into::<piet::Color>::into(stroke_color),
+                options.stroke_width,
+                &piet::StrokeStyle::default()
+                    .line_cap(piet::LineCap::Round)
+                    .line_join(piet::LineJoin::Round),
+            );
+        }
+    }
+}
+
 impl Composer<SmoothOptions> for PenPath {
     fn composed_bounds(&self, options: &SmoothOptions) -> Aabb {
         self.bounds().loosened(options.stroke_width * 0.5)
@@ -344,6 +412,8 @@ impl Composer<SmoothOptions> for crate::Shape {
             crate::Shape::CubicBezier(cubbez) => cubbez.composed_bounds(options),
             crate::Shape::Polyline(polyline) => polyline.composed_bounds(options),
             crate::Shape::Polygon(polygon) => polygon.composed_bounds(options),
+            crate::Shape::BezierPath(bezier_path) => bezier_path.composed_bounds(options),
+            crate::Shape::CappedLine(capped_line) => capped_line.composed_bounds(options),
         }
     }
 
@@ -357,6 +427,8 @@ impl Composer<SmoothOptions> for crate::Shape {
             crate::Shape::CubicBezier(cubbez) => cubbez.draw_composed(cx, options),
             crate::Shape::Polyline(polyline) => polyline.draw_composed(cx, options),
             crate::Shape::Polygon(polygon) => polygon.draw_composed(cx, options),
+            crate::Shape::BezierPath(bezier_path) => bezier_path.draw_composed(cx, options),
+            crate::Shape::CappedLine(capped_line) => capped_line.draw_composed(cx, options),
         }
     }
 }
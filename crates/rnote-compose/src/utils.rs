@@ -118,3 +118,59 @@ pub fn seed_advance(seed: u64) -> u64 {
     let mut rng = rand_pcg::Pcg64::seed_from_u64(seed);
     rng.gen()
 }
+
+/// Returns which of the points of the polyline are kept when simplifying it with the Ramer-Douglas-Peucker algorithm.
+///
+/// `epsilon` is the maximum distance a removed point is allowed to have to the simplified polyline.
+///
+/// The first and last point are always kept.
+pub fn rdp_keep_mask(points: &[na::Vector2<f64>], epsilon: f64) -> Vec<bool> {
+    let mut keep = vec![false; points.len()];
+    if points.len() <= 2 {
+        keep.fill(true);
+        return keep;
+    }
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = stack.pop() {
+        if last <= first + 1 {
+            continue;
+        }
+        let a = points[first];
+        let b = points[last];
+        let ab = b - a;
+        let ab_len_sq = ab.norm_squared();
+
+        let (max_i, max_dist) = ((first + 1)..last)
+            .map(|i| {
+                let p = points[i];
+                let dist = if ab_len_sq > 0.0 {
+                    let t = ((p - a).dot(&ab) / ab_len_sq).clamp(0.0, 1.0);
+                    (p - (a + ab * t)).norm()
+                } else {
+                    (p - a).norm()
+                };
+                (i, dist)
+            })
+            .fold(
+                (first, 0.0),
+                |acc, (i, dist)| {
+                    if dist > acc.1 {
+                        (i, dist)
+                    } else {
+                        acc
+                    }
+                },
+            );
+
+        if max_dist > epsilon {
+            keep[max_i] = true;
+            stack.push((first, max_i));
+            stack.push((max_i, last));
+        }
+    }
+
+    keep
+}
@@ -1,16 +1,72 @@
 // Imports
+#[cfg(feature = "audio")]
 use anyhow::Context;
+#[cfg(feature = "audio")]
 use rand::Rng;
 use rnote_compose::penevent::KeyboardKey;
+#[cfg(feature = "audio")]
 use rodio::source::Buffered;
+#[cfg(feature = "audio")]
 use rodio::{Decoder, Source};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "audio")]
 use std::collections::HashMap;
+#[cfg(feature = "audio")]
 use std::fs::File;
 use std::path::PathBuf;
+#[cfg(feature = "audio")]
 use std::time::Duration;
+#[cfg(feature = "audio")]
 use tracing::error;
 
+/// Configures which pens play sounds, their volume and the sound theme.
+///
+/// Pen sounds are enabled as soon as any of the pens plays sounds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "pen_sounds_config")]
+pub struct PenSoundsConfig {
+    #[serde(rename = "brush_sounds")]
+    pub brush_sounds: bool,
+    #[serde(rename = "typewriter_sounds")]
+    pub typewriter_sounds: bool,
+    #[serde(rename = "volume")]
+    pub volume: f64,
+    /// Whether the sounds are panned between the left and right channel,
+    /// following the horizontal position of the pen in the viewport.
+    #[serde(rename = "spatial")]
+    pub spatial: bool,
+    /// A directory containing sound files that replace the builtin ones.
+    ///
+    /// Sounds that are missing in the theme fall back to the builtin ones.
+    #[serde(rename = "theme_dir")]
+    pub theme_dir: Option<PathBuf>,
+}
+
+impl Default for PenSoundsConfig {
+    fn default() -> Self {
+        Self {
+            brush_sounds: false,
+            typewriter_sounds: false,
+            volume: Self::VOLUME_DEFAULT,
+            spatial: true,
+            theme_dir: None,
+        }
+    }
+}
+
+impl PenSoundsConfig {
+    pub const VOLUME_MIN: f64 = 0.0;
+    pub const VOLUME_MAX: f64 = 1.0;
+    pub const VOLUME_DEFAULT: f64 = 1.0;
+
+    /// Whether any pen plays sounds.
+    pub fn enabled(&self) -> bool {
+        self.brush_sounds || self.typewriter_sounds
+    }
+}
+
 /// The audio player for pen sounds.
+#[cfg(feature = "audio")]
 pub struct AudioPlayer {
     // we need to hold the output streams, even if they are not used.
     #[allow(unused)]
@@ -25,8 +81,12 @@ pub struct AudioPlayer {
 
     sounds: HashMap<String, Buffered<Decoder<File>>>,
     brush_sound_task_handle: Option<crate::tasks::OneOffTaskHandle>,
+    config: PenSoundsConfig,
+    /// The stereo panning of spatial sounds, from -1.0 (left) to 1.0 (right).
+    pan: f64,
 }
 
+#[cfg(feature = "audio")]
 impl std::fmt::Debug for AudioPlayer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AudioPlayer")
@@ -38,10 +98,13 @@ impl std::fmt::Debug for AudioPlayer {
             .field("typewriter_outputstream_handle", &"{.. no debug impl ..}")
             .field("sounds", &"{.. no debug impl ..}")
             .field("brush_sound_task_handle", &self.brush_sound_task_handle)
+            .field("config", &self.config)
+            .field("pan", &self.pan)
             .finish()
     }
 }
 
+#[cfg(feature = "audio")]
 impl AudioPlayer {
     pub const BRUSH_SOUND_TIMEOUT: Duration = Duration::from_millis(600);
     pub const N_SOUND_FILES_MARKER: usize = 15;
@@ -49,9 +112,21 @@ impl AudioPlayer {
     pub const SOUND_FILE_BRUSH_SEEK_TIMES_MS: [f64; 5] = [0., 910., 4129., 6000., 8560.];
 
     /// Create and initialize new audioplayer.
-    /// `pkg_data_dir` is the app data directory which has a "sounds" subfolder containing the sound files
-    pub fn new_init(mut pkg_data_dir: PathBuf) -> Result<Self, anyhow::Error> {
+    /// `pkg_data_dir` is the app data directory which has a "sounds" subfolder containing the sound files.
+    /// The sound files in the theme directory of the config take precedence.
+    pub fn new_init(
+        mut pkg_data_dir: PathBuf,
+        config: PenSoundsConfig,
+    ) -> Result<Self, anyhow::Error> {
         pkg_data_dir.push("sounds/");
+        let theme_dir = config.theme_dir.clone();
+        let load_sound = |name: &str| match theme_dir
+            .as_ref()
+            .and_then(|theme_dir| load_sound_from_path(theme_dir.clone(), name, "wav").ok())
+        {
+            Some(buffered) => Ok(buffered),
+            None => load_sound_from_path(pkg_data_dir.clone(), name, "wav"),
+        };
 
         let mut sounds = HashMap::new();
 
@@ -63,7 +138,7 @@ impl AudioPlayer {
         // Init marker sounds
         for i in 0..Self::N_SOUND_FILES_MARKER {
             let name = format!("marker_{i:02}");
-            let buffered = load_sound_from_path(pkg_data_dir.clone(), &name, "wav")?;
+            let buffered = load_sound(&name)?;
 
             sounds.insert(name, buffered);
         }
@@ -71,7 +146,7 @@ impl AudioPlayer {
         // Init brush sounds
         {
             let name = String::from("brush");
-            let buffered = load_sound_from_path(pkg_data_dir.clone(), &name, "wav")?;
+            let buffered = load_sound(&name)?;
             sounds.insert(name, buffered);
         }
 
@@ -79,32 +154,32 @@ impl AudioPlayer {
         // the enumerated key sounds
         for i in 0..Self::N_SOUND_FILES_TYPEWRITER {
             let name = format!("typewriter_{i:02}");
-            let buffered = load_sound_from_path(pkg_data_dir.clone(), &name, "wav")?;
+            let buffered = load_sound(&name)?;
             sounds.insert(name, buffered);
         }
 
         // the custom sounds
         {
             let name = String::from("typewriter_insert");
-            let buffered = load_sound_from_path(pkg_data_dir.clone(), &name, "wav")?;
+            let buffered = load_sound(&name)?;
             sounds.insert(name, buffered);
         }
 
         {
             let name = String::from("typewriter_thump");
-            let buffered = load_sound_from_path(pkg_data_dir.clone(), &name, "wav")?;
+            let buffered = load_sound(&name)?;
             sounds.insert(name, buffered);
         }
 
         {
             let name = String::from("typewriter_bell");
-            let buffered = load_sound_from_path(pkg_data_dir.clone(), &name, "wav")?;
+            let buffered = load_sound(&name)?;
             sounds.insert(name, buffered);
         }
 
         {
             let name = String::from("typewriter_linefeed");
-            let buffered = load_sound_from_path(pkg_data_dir.clone(), &name, "wav")?;
+            let buffered = load_sound(&name)?;
             sounds.insert(name, buffered);
         }
 
@@ -118,16 +193,66 @@ impl AudioPlayer {
 
             sounds,
             brush_sound_task_handle: None,
+            config,
+            pan: 0.0,
         })
     }
 
+    pub fn config(&self) -> &PenSoundsConfig {
+        &self.config
+    }
+
+    /// Update the config.
+    ///
+    /// Changing the theme directory has no effect here, the audioplayer needs to be recreated for it.
+    pub fn set_config(&mut self, config: PenSoundsConfig) {
+        self.config = config;
+    }
+
+    /// Set the stereo panning of spatial sounds, from -1.0 (left) to 1.0 (right).
+    ///
+    /// Sounds that are already playing keep their panning.
+    pub fn set_pan(&mut self, pan: f64) {
+        self.pan = pan.clamp(-1.0, 1.0);
+    }
+
+    fn volume(&self) -> f32 {
+        self.config
+            .volume
+            .clamp(PenSoundsConfig::VOLUME_MIN, PenSoundsConfig::VOLUME_MAX) as f32
+    }
+
+    /// Append the source to the sink, panned with constant power when spatial sounds are enabled.
+    fn append_panned<S>(&self, sink: &rodio::Sink, source: S)
+    where
+        S: Source + Send + 'static,
+        S::Item: rodio::Sample + Send,
+    {
+        if self.config.spatial {
+            let angle = (self.pan + 1.0) * std::f64::consts::FRAC_PI_4;
+            sink.append(rodio::source::ChannelVolume::new(
+                source,
+                vec![angle.cos() as f32, angle.sin() as f32],
+            ));
+        } else {
+            sink.append(source);
+        }
+    }
+
     pub fn play_random_marker_sound(&self) {
+        if !self.config.brush_sounds {
+            return;
+        }
         let mut rng = rand::thread_rng();
         let marker_sound_index = rng.gen_range(0..Self::N_SOUND_FILES_MARKER);
 
         match rodio::Sink::try_new(&self.marker_outputstream_handle) {
             Ok(sink) => {
-                sink.append(self.sounds[&format!("marker_{marker_sound_index:02}")].clone());
+                sink.set_volume(self.volume());
+                self.append_panned(
+                    &sink,
+                    self.sounds[&format!("marker_{marker_sound_index:02}")].clone(),
+                );
                 sink.detach();
             }
             Err(e) => {
@@ -137,6 +262,9 @@ impl AudioPlayer {
     }
 
     pub fn trigger_random_brush_sound(&mut self) {
+        if !self.config.brush_sounds {
+            return;
+        }
         let mut rng = rand::thread_rng();
         let brush_sound_seek_time_index =
             rng.gen_range(0..Self::SOUND_FILE_BRUSH_SEEK_TIMES_MS.len());
@@ -167,8 +295,10 @@ impl AudioPlayer {
                     return;
                 }
             };
+            sink.set_volume(self.volume());
 
-            sink.append(
+            self.append_panned(
+                &sink,
                 self.sounds["brush"]
                     .clone()
                     .repeat_infinite()
@@ -189,6 +319,9 @@ impl AudioPlayer {
 
     /// Play a typewriter sound that fits the given key type, or a generic sound when None.
     pub fn play_typewriter_key_sound(&self, keyboard_key: Option<KeyboardKey>) {
+        if !self.config.typewriter_sounds {
+            return;
+        }
         let sink = match rodio::Sink::try_new(&self.typewriter_outputstream_handle) {
             Ok(sink) => sink,
             Err(e) => {
@@ -196,9 +329,11 @@ impl AudioPlayer {
                 return;
             }
         };
+        sink.set_volume(self.volume());
         match keyboard_key {
             Some(KeyboardKey::CarriageReturn) | Some(KeyboardKey::Linefeed) => {
-                sink.append(
+                self.append_panned(
+                    &sink,
                     self.sounds["typewriter_bell"].clone().mix(
                         self.sounds["typewriter_linefeed"]
                             .clone()
@@ -216,19 +351,21 @@ impl AudioPlayer {
                 let mut rng = rand::thread_rng();
                 let typewriter_sound_index = rng.gen_range(0..Self::N_SOUND_FILES_TYPEWRITER);
 
-                sink.append(
+                self.append_panned(
+                    &sink,
                     self.sounds[&format!("typewriter_{typewriter_sound_index:02}")].clone(),
                 );
                 sink.detach();
             }
             _ => {
-                sink.append(self.sounds["typewriter_thump"].clone());
+                self.append_panned(&sink, self.sounds["typewriter_thump"].clone());
                 sink.detach();
             }
         }
     }
 }
 
+#[cfg(feature = "audio")]
 fn load_sound_from_path(
     mut resource_path: PathBuf,
     sound_name: &str,
@@ -253,3 +390,40 @@ fn load_sound_from_path(
         ))
     }
 }
+
+/// The audio player when the crate is built without the `audio` feature.
+///
+/// It can't be initialized, so the engine never plays pen sounds.
+#[cfg(not(feature = "audio"))]
+#[derive(Debug)]
+pub struct AudioPlayer {
+    config: PenSoundsConfig,
+}
+
+#[cfg(not(feature = "audio"))]
+impl AudioPlayer {
+    pub fn new_init(
+        _pkg_data_dir: PathBuf,
+        _config: PenSoundsConfig,
+    ) -> Result<Self, anyhow::Error> {
+        Err(anyhow::anyhow!(
+            "Failed to init audioplayer, built without the `audio` feature."
+        ))
+    }
+
+    pub fn config(&self) -> &PenSoundsConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: PenSoundsConfig) {
+        self.config = config;
+    }
+
+    pub fn set_pan(&mut self, _pan: f64) {}
+
+    pub fn play_random_marker_sound(&self) {}
+
+    pub fn trigger_random_brush_sound(&mut self) {}
+
+    pub fn play_typewriter_key_sound(&self, _keyboard_key: Option<KeyboardKey>) {}
+}
@@ -81,6 +81,12 @@ impl Camera {
     pub const DRAG_ZOOM_MAGN_ZOOM_FACTOR: f64 = 0.005;
     pub const OVERSHOOT_HORIZONTAL: f64 = 96.0;
     pub const OVERSHOOT_VERTICAL: f64 = 96.0;
+    /// The margin around bounds that are zoomed to fit, in surface coordinate space.
+    pub const ZOOM_FIT_MARGIN: f64 = 48.0;
+    /// The zoom presets that can be quickly selected.
+    pub const ZOOM_PRESETS: [f64; 4] = [0.5, 1.0, 1.5, 2.0];
+    /// The margin around the document that can be panned to when panning is clamped, in surface coordinate space.
+    pub const CLAMP_PANNING_MARGIN: f64 = 32.0;
 
     pub fn with_zoom(mut self, zoom: f64) -> Self {
         self.zoom = zoom.clamp(Self::ZOOM_MIN, Self::ZOOM_MAX);
@@ -103,7 +109,11 @@ impl Camera {
 
     pub fn set_offset(&mut self, offset: na::Vector2<f64>, doc: &Document) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
-        let (lower, upper) = self.offset_lower_upper(doc);
+        let (lower, mut upper) = self.offset_lower_upper(doc);
+        if doc.clamps_panning() {
+            // The viewport must stay entirely inside the bounds
+            upper = (upper - self.size).sup(&lower);
+        }
         self.offset = na::vector![
             offset[0].clamp(lower[0], upper[0]),
             offset[1].clamp(lower[1], upper[1])
@@ -118,6 +128,17 @@ impl Camera {
     pub fn offset_lower_upper(&self, doc: &Document) -> (na::Vector2<f64>, na::Vector2<f64>) {
         let total_zoom = self.total_zoom();
 
+        if doc.clamps_panning() {
+            let doc_mins = na::vector![doc.x, doc.y] * total_zoom
+                - na::Vector2::repeat(Self::CLAMP_PANNING_MARGIN);
+            let doc_extents = na::vector![doc.width, doc.height] * total_zoom
+                + na::Vector2::repeat(2.0 * Self::CLAMP_PANNING_MARGIN);
+            // When the document is smaller than the viewport it gets centered
+            let lower = doc_mins - (self.size - doc_extents).sup(&na::Vector2::zeros()) * 0.5;
+            let upper = lower + doc_extents.sup(&self.size);
+            return (lower, upper);
+        }
+
         let (h_lower, h_upper) = match doc.layout {
             Layout::FixedSize | Layout::ContinuousVertical => (
                 doc.x * total_zoom - Self::OVERSHOOT_HORIZONTAL,
@@ -127,7 +148,16 @@ impl Camera {
                 doc.x * total_zoom - Self::OVERSHOOT_HORIZONTAL,
                 (doc.x + doc.width) * total_zoom,
             ),
+            Layout::Infinite if !doc.infinite_expand_direction.expands_horizontally() => (
+                doc.x * total_zoom - Self::OVERSHOOT_HORIZONTAL,
+                (doc.x + doc.width) * total_zoom + Self::OVERSHOOT_HORIZONTAL,
+            ),
             Layout::Infinite => (doc.x * total_zoom, (doc.x + doc.width) * total_zoom),
+            // Allows panning until the document edges reach the viewport center, to make room for new strokes
+            Layout::Whiteboard => (
+                doc.x * total_zoom - self.size[0] * 0.5,
+                (doc.x + doc.width) * total_zoom + self.size[0] * 0.5,
+            ),
         };
         let (v_lower, v_upper) = match doc.layout {
             Layout::FixedSize | Layout::ContinuousVertical => (
@@ -138,7 +168,15 @@ impl Camera {
                 doc.y * total_zoom - Self::OVERSHOOT_VERTICAL,
                 (doc.y + doc.height) * total_zoom,
             ),
+            Layout::Infinite if !doc.infinite_expand_direction.expands_vertically() => (
+                doc.y * total_zoom - Self::OVERSHOOT_VERTICAL,
+                (doc.y + doc.height) * total_zoom + Self::OVERSHOOT_VERTICAL,
+            ),
             Layout::Infinite => (doc.y * total_zoom, (doc.y + doc.height) * total_zoom),
+            Layout::Whiteboard => (
+                doc.y * total_zoom - self.size[1] * 0.5,
+                (doc.y + doc.height) * total_zoom + self.size[1] * 0.5,
+            ),
         };
 
         (na::vector![h_lower, v_lower], na::vector![h_upper, v_upper])
@@ -221,6 +259,31 @@ impl Camera {
         widget_flags
     }
 
+    /// The zoom that is needed to fit the bounds into the viewport, keeping the given margin around them.
+    ///
+    /// `bounds` must be in document coordinate space, `margin` in surface coordinate space.
+    pub fn zoom_to_fit_bounds(&self, bounds: Aabb, margin: f64) -> f64 {
+        let available_size =
+            (self.size - na::Vector2::from_element(2.0 * margin)).map(|v| v.max(1.0));
+        let extents = bounds.extents().map(|v| v.max(1.0));
+
+        (available_size[0] / extents[0])
+            .min(available_size[1] / extents[1])
+            .clamp(Self::ZOOM_MIN, Self::ZOOM_MAX)
+    }
+
+    /// The zoom that is needed to fit the page width into the viewport, including the horizontal overshoot.
+    pub fn zoom_to_fit_page_width(&self, page_width: f64) -> f64 {
+        (self.size[0] / (page_width + 2.0 * Self::OVERSHOOT_HORIZONTAL).max(1.0))
+            .clamp(Self::ZOOM_MIN, Self::ZOOM_MAX)
+    }
+
+    /// The zoom that is needed to fit the page height into the viewport, keeping the fit margin above and below.
+    pub fn zoom_to_fit_page_height(&self, page_height: f64) -> f64 {
+        ((self.size[1] - 2.0 * Self::ZOOM_FIT_MARGIN).max(1.0) / page_height.max(1.0))
+            .clamp(Self::ZOOM_MIN, Self::ZOOM_MAX)
+    }
+
     /// The total zoom of the camera, including the temporary zoom.
     pub fn total_zoom(&self) -> f64 {
         self.zoom * self.temporary_zoom
@@ -360,6 +423,38 @@ impl Camera {
         self.nudge_by(NUDGE_AMOUNT, direction, doc)
     }
 
+    /// The vertical distance of a page jump in surface coordinate space.
+    ///
+    /// Exactly the height of a page in the layouts where the pages are stacked vertically,
+    /// the height of the viewport in all others.
+    pub fn page_jump_distance(&self, doc: &Document) -> f64 {
+        match doc.layout {
+            Layout::FixedSize | Layout::ContinuousVertical if doc.format.height() > 0.0 => {
+                doc.format.height() * self.total_zoom()
+            }
+            _ => self.size[1],
+        }
+    }
+
+    /// Jump one page up or down, keeping the position of the viewport relative to the page.
+    pub fn page_jump(&mut self, forward: bool, doc: &Document) -> WidgetFlags {
+        let direction = if forward {
+            NudgeDirection::South
+        } else {
+            NudgeDirection::North
+        };
+        self.nudge_by(self.page_jump_distance(doc), direction, doc)
+    }
+
+    /// The offset that shows the top of the page bounds with the page centered horizontally, at the current zoom.
+    pub fn offset_for_page_top(&self, page: Aabb) -> na::Vector2<f64> {
+        let total_zoom = self.total_zoom();
+        na::vector![
+            page.center()[0] * total_zoom - self.size[0] * 0.5,
+            (page.mins[1] - Document::SHADOW_WIDTH) * total_zoom
+        ]
+    }
+
     pub fn nudge_w_pos(&mut self, pos: na::Vector2<f64>, doc: &Document) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
         if let Some(nudge_direction) = self.detect_nudge_needed(pos) {
@@ -1,11 +1,17 @@
 // Imports
 use crate::render;
 use anyhow::Context;
+use once_cell::sync::Lazy;
 use p2d::bounding_volume::Aabb;
 use rnote_compose::Color;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 use svg::node::element;
 use svg::Node;
+use tracing::warn;
 
 #[derive(
     Debug,
@@ -32,6 +38,9 @@ pub enum PatternStyle {
     IsometricGrid,
     #[serde(rename = "isometric_dots")]
     IsometricDots,
+    /// The user provided pattern tile of the background.
+    #[serde(rename = "custom_tile")]
+    CustomTile,
 }
 
 impl Default for PatternStyle {
@@ -331,7 +340,181 @@ fn gen_iso_dots_pattern(
     group.into()
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+/// A user provided image or Svg file that gets tiled as background pattern.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "pattern_tile")]
+pub enum PatternTile {
+    /// Only the path is stored, the file is read again when it was modified.
+    #[serde(rename = "reference")]
+    Reference {
+        #[serde(rename = "path")]
+        path: PathBuf,
+    },
+    /// The file content is embedded in the document.
+    #[serde(rename = "embedded")]
+    Embedded {
+        #[serde(rename = "data", with = "crate::utils::glib_bytes_base64")]
+        data: glib::Bytes,
+    },
+}
+
+/// A referenced pattern tile file that was read, together with its modification time at that point.
+#[derive(Debug, Clone)]
+struct ReferencedTile {
+    modified: Option<SystemTime>,
+    data: glib::Bytes,
+    data_url: Option<Arc<str>>,
+}
+
+/// The referenced pattern tile files, by their path.
+///
+/// Avoids reading and encoding the file every time the background gets regenerated.
+static REFERENCED_TILES: Lazy<Mutex<HashMap<PathBuf, ReferencedTile>>> = Lazy::new(Mutex::default);
+
+impl PatternTile {
+    /// The maximum number of cached referenced tile files.
+    const REFERENCED_TILES_MAX: usize = 8;
+
+    /// Read the file content of the tile.
+    pub fn data(&self) -> anyhow::Result<glib::Bytes> {
+        match self {
+            Self::Reference { path } => Ok(Self::referenced_tile(path)?.data),
+            Self::Embedded { data } => Ok(data.clone()),
+        }
+    }
+
+    /// The cached referenced tile at the path, reading the file if it is not cached or was modified since.
+    fn referenced_tile(path: &Path) -> anyhow::Result<ReferencedTile> {
+        let modified = std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let mut referenced_tiles = REFERENCED_TILES
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Referenced pattern tiles cache is poisoned."))?;
+        if let Some(referenced_tile) = referenced_tiles
+            .get(path)
+            .filter(|t| modified.is_some() && t.modified == modified)
+        {
+            return Ok(referenced_tile.clone());
+        }
+        let data = glib::Bytes::from_owned(std::fs::read(path).with_context(|| {
+            format!(
+                "Reading pattern tile file at path `{}` failed.",
+                path.display()
+            )
+        })?);
+        let referenced_tile = ReferencedTile {
+            modified,
+            data,
+            data_url: None,
+        };
+        if referenced_tiles.len() >= Self::REFERENCED_TILES_MAX {
+            referenced_tiles.clear();
+        }
+        referenced_tiles.insert(path.to_path_buf(), referenced_tile.clone());
+        Ok(referenced_tile)
+    }
+
+    /// Converts a referenced tile into an embedded one.
+    pub fn embed(&self) -> anyhow::Result<Self> {
+        Ok(Self::Embedded { data: self.data()? })
+    }
+
+    /// The intrinsic size of the tile.
+    pub fn intrinsic_size(&self) -> anyhow::Result<na::Vector2<f64>> {
+        let data = self.data()?;
+        if Self::is_svg(&data) {
+            let size = usvg::Tree::from_data(&data, &usvg::Options::default())?.size();
+            Ok(na::vector![size.width() as f64, size.height() as f64])
+        } else {
+            let (width, height) = image::ImageReader::new(std::io::Cursor::new(&data))
+                .with_guessed_format()?
+                .into_dimensions()?;
+            Ok(na::vector![width as f64, height as f64])
+        }
+    }
+
+    fn is_svg(data: &[u8]) -> bool {
+        image::guess_format(data).is_err()
+            && String::from_utf8_lossy(&data[..data.len().min(1024)]).contains("<svg")
+    }
+
+    /// The tile as data url that can be referenced in Svg image elements.
+    fn data_url(&self) -> anyhow::Result<Arc<str>> {
+        match self {
+            Self::Reference { path } => {
+                let referenced_tile = Self::referenced_tile(path)?;
+                if let Some(data_url) = referenced_tile.data_url {
+                    return Ok(data_url);
+                }
+                let data_url = Arc::<str>::from(Self::encode_data_url(&referenced_tile.data)?);
+                if let Ok(mut referenced_tiles) = REFERENCED_TILES.lock() {
+                    if let Some(cached) = referenced_tiles
+                        .get_mut(path.as_path())
+                        .filter(|t| t.modified == referenced_tile.modified)
+                    {
+                        cached.data_url = Some(Arc::clone(&data_url));
+                    }
+                }
+                Ok(data_url)
+            }
+            Self::Embedded { data } => Ok(Arc::from(Self::encode_data_url(data)?)),
+        }
+    }
+
+    fn encode_data_url(data: &[u8]) -> anyhow::Result<String> {
+        let mime_type = if Self::is_svg(&data) {
+            render::Svg::MIME_TYPE
+        } else {
+            image::guess_format(&data)
+                .context("Unsupported pattern tile image format.")?
+                .to_mime_type()
+        };
+        Ok(format!(
+            "data:{mime_type};base64,{}",
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, data)
+        ))
+    }
+}
+
+fn gen_custom_tile_pattern(
+    bounds: Aabb,
+    tile_size: na::Vector2<f64>,
+    tile_data_url: &str,
+) -> svg::node::element::Element {
+    let pattern_id = rnote_compose::utils::svg_random_id_prefix() + "_bg_custom_tile_pattern";
+
+    let pattern = element::Definitions::new().add(
+        element::Pattern::new()
+            .set("id", pattern_id.as_str())
+            .set("x", 0_f64)
+            .set("y", 0_f64)
+            .set("width", tile_size[0])
+            .set("height", tile_size[1])
+            .set("patternUnits", "userSpaceOnUse")
+            .set("patternContentUnits", "userSpaceOnUse")
+            .add(
+                element::Image::new()
+                    .set("x", 0_f64)
+                    .set("y", 0_f64)
+                    .set("width", tile_size[0])
+                    .set("height", tile_size[1])
+                    .set("preserveAspectRatio", "none")
+                    .set("href", tile_data_url),
+            ),
+    );
+
+    let mut rect = element::Rectangle::new().set("fill", format!("url(#{pattern_id})"));
+    rect.assign("x", format!("{}px", bounds.mins[0]));
+    rect.assign("y", format!("{}px", bounds.mins[1]));
+    rect.assign("width", format!("{}px", bounds.extents()[0]));
+    rect.assign("height", format!("{}px", bounds.extents()[1]));
+
+    let group = element::Group::new().add(pattern).add(rect);
+    group.into()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, rename = "background")]
 pub struct Background {
     #[serde(rename = "color")]
@@ -345,6 +528,9 @@ pub struct Background {
     pub pattern_size: na::Vector2<f64>,
     #[serde(rename = "pattern_color")]
     pub pattern_color: Color,
+    /// The tile that is used for the custom tile pattern style.
+    #[serde(rename = "pattern_tile")]
+    pub pattern_tile: Option<PatternTile>,
 }
 
 impl Default for Background {
@@ -354,6 +540,7 @@ impl Default for Background {
             pattern: PatternStyle::default(),
             pattern_size: Self::PATTERN_SIZE_DEFAULT,
             pattern_color: Self::PATTERN_COLOR_DEFAULT,
+            pattern_tile: None,
         }
     }
 }
@@ -485,6 +672,25 @@ impl Background {
                         Self::HEXAGON_HEIGHT,
                     ));
                 }
+                PatternStyle::CustomTile => {
+                    if let Some(pattern_tile) = &self.pattern_tile {
+                        // A missing or invalid tile should not prevent the background from being drawn
+                        match pattern_tile.data_url() {
+                            Ok(data_url) => {
+                                svg_group = svg_group.add(gen_custom_tile_pattern(
+                                    bounds,
+                                    self.pattern_size,
+                                    &data_url,
+                                ));
+                            }
+                            Err(e) => {
+                                warn!(
+                                    "Generating custom background tile pattern failed, Err: {e:?}"
+                                );
+                            }
+                        }
+                    }
+                }
             }
         }
 
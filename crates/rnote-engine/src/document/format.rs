@@ -92,6 +92,8 @@ pub enum MeasureUnit {
     Mm,
     #[serde(rename = "cm")]
     Cm,
+    #[serde(rename = "in")]
+    Inch,
 }
 
 impl Default for MeasureUnit {
@@ -124,12 +126,24 @@ impl MeasureUnit {
             MeasureUnit::Px => value,
             MeasureUnit::Mm => (value / Self::AMOUNT_MM_IN_INCH) * value_dpi,
             MeasureUnit::Cm => ((value * 10.0) / Self::AMOUNT_MM_IN_INCH) * value_dpi,
+            MeasureUnit::Inch => value * value_dpi,
         };
 
         match desired_unit {
             MeasureUnit::Px => value_in_px,
             MeasureUnit::Mm => (value_in_px / desired_dpi) * Self::AMOUNT_MM_IN_INCH,
             MeasureUnit::Cm => (value_in_px / desired_dpi) * Self::AMOUNT_MM_IN_INCH / 10.0,
+            MeasureUnit::Inch => value_in_px / desired_dpi,
+        }
+    }
+
+    /// The unit symbol, as used in readouts.
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            MeasureUnit::Px => "px",
+            MeasureUnit::Mm => "mm",
+            MeasureUnit::Cm => "cm",
+            MeasureUnit::Inch => "in",
         }
     }
 }
@@ -166,6 +180,12 @@ pub struct Format {
     pub show_borders: bool,
     #[serde(rename = "show_origin_indicator")]
     pub show_origin_indicator: bool,
+    /// The drawing scale, as the real-world length per drawn length. E.g. 50.0 for a scale of 1:50.
+    #[serde(rename = "scale", with = "rnote_compose::serialize::f64_dp3")]
+    scale: f64,
+    /// The unit that scaled dimensions on the document are shown in.
+    #[serde(rename = "unit")]
+    pub unit: MeasureUnit,
 }
 
 impl Default for Format {
@@ -178,6 +198,8 @@ impl Default for Format {
             border_color: Color::from(Self::BORDER_COLOR_DEFAULT),
             show_borders: true,
             show_origin_indicator: true,
+            scale: Self::SCALE_DEFAULT,
+            unit: MeasureUnit::Mm,
         }
     }
 }
@@ -195,6 +217,10 @@ impl Format {
     pub const DPI_MAX: f64 = 5000.0;
     pub const DPI_DEFAULT: f64 = 96.0;
 
+    pub const SCALE_MIN: f64 = 0.001;
+    pub const SCALE_MAX: f64 = 100000.0;
+    pub const SCALE_DEFAULT: f64 = 1.0;
+
     pub const BORDER_COLOR_DEFAULT: piet::Color = color::GNOME_BRIGHTS[2];
 
     pub fn width(&self) -> f64 {
@@ -223,6 +249,20 @@ impl Format {
         self.dpi = dpi.clamp(Self::DPI_MIN, Self::DPI_MAX);
     }
 
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    pub fn set_scale(&mut self, scale: f64) {
+        self.scale = scale.clamp(Self::SCALE_MIN, Self::SCALE_MAX);
+    }
+
+    /// Converts a length on the document to its real-world length in the given unit, applying the drawing scale.
+    pub fn scaled_length(&self, length_px: f64, unit: MeasureUnit) -> f64 {
+        MeasureUnit::convert_measurement(length_px, MeasureUnit::Px, self.dpi, unit, self.dpi)
+            * self.scale
+    }
+
     pub fn orientation(&self) -> Orientation {
         self.orientation
     }
@@ -239,3 +279,56 @@ impl Format {
         }
     }
 }
+
+/// A user-defined, named page format that can be stored and applied to documents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "custom_format")]
+pub struct CustomFormat {
+    #[serde(rename = "name")]
+    pub name: String,
+    /// The width in px, at the given dpi.
+    #[serde(rename = "width", with = "rnote_compose::serialize::f64_dp3")]
+    pub width: f64,
+    /// The height in px, at the given dpi.
+    #[serde(rename = "height", with = "rnote_compose::serialize::f64_dp3")]
+    pub height: f64,
+    #[serde(rename = "dpi", with = "rnote_compose::serialize::f64_dp3")]
+    pub dpi: f64,
+}
+
+impl Default for CustomFormat {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            width: Format::WIDTH_DEFAULT,
+            height: Format::HEIGHT_DEFAULT,
+            dpi: Format::DPI_DEFAULT,
+        }
+    }
+}
+
+impl CustomFormat {
+    pub fn from_format(name: String, format: &Format) -> Self {
+        Self {
+            name,
+            width: format.width(),
+            height: format.height(),
+            dpi: format.dpi(),
+        }
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        if self.width <= self.height {
+            Orientation::Portrait
+        } else {
+            Orientation::Landscape
+        }
+    }
+
+    /// Applies the dimensions to the format. Other properties like the border color are kept.
+    pub fn apply_to(&self, format: &mut Format) {
+        format.set_dpi(self.dpi);
+        format.set_width(self.width);
+        format.set_height(self.height);
+    }
+}
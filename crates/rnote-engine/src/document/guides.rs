@@ -0,0 +1,59 @@
+// Imports
+use rnote_compose::Color;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "guide_orientation")]
+pub enum GuideOrientation {
+    /// A horizontal line, positioned on the y-axis.
+    #[serde(rename = "horizontal")]
+    Horizontal,
+    /// A vertical line, positioned on the x-axis.
+    #[serde(rename = "vertical")]
+    Vertical,
+}
+
+/// A guide line spanning the entire document, which positions snap to.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "guide")]
+pub struct Guide {
+    #[serde(rename = "orientation")]
+    pub orientation: GuideOrientation,
+    /// The position of the guide on the axis perpendicular to the line, in document coordinates.
+    #[serde(rename = "pos", with = "rnote_compose::serialize::f64_dp3")]
+    pub pos: f64,
+}
+
+impl Guide {
+    pub const COLOR: Color = Color {
+        r: 0.0,
+        g: 0.6,
+        b: 0.9,
+        a: 0.8,
+    };
+    /// The distance in document coordinates in which positions are snapped to the guide.
+    pub const SNAP_DIST: f64 = 8.0;
+
+    pub fn new(orientation: GuideOrientation, pos: f64) -> Self {
+        Self { orientation, pos }
+    }
+
+    /// The distance of the given position to the guide line.
+    pub fn distance_to(&self, pos: na::Vector2<f64>) -> f64 {
+        match self.orientation {
+            GuideOrientation::Horizontal => (pos[1] - self.pos).abs(),
+            GuideOrientation::Vertical => (pos[0] - self.pos).abs(),
+        }
+    }
+
+    /// Snaps the coordinate perpendicular to the guide when the position is within the snap distance.
+    pub(crate) fn snap(&self, pos: &mut na::Vector2<f64>) {
+        if self.distance_to(*pos) >= Self::SNAP_DIST {
+            return;
+        }
+        match self.orientation {
+            GuideOrientation::Horizontal => pos[1] = self.pos,
+            GuideOrientation::Vertical => pos[0] = self.pos,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+// Imports
+use crate::fileformats::{rnoteformat, FileFormatLoader};
+use anyhow::Context;
+use futures::channel::oneshot;
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+/// Descriptive metadata of a document, stored in the .rnote file and embedded into exported Pdf's.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename = "document_metadata")]
+pub struct DocumentMetadata {
+    #[serde(rename = "title")]
+    pub title: String,
+    #[serde(rename = "author")]
+    pub author: String,
+    #[serde(rename = "subject")]
+    pub subject: String,
+    #[serde(rename = "tags")]
+    pub tags: Vec<String>,
+    /// When the document was first saved, in seconds since the unix epoch.
+    #[serde(rename = "created")]
+    pub created: Option<i64>,
+    /// When the document was last saved, in seconds since the unix epoch.
+    #[serde(rename = "modified")]
+    pub modified: Option<i64>,
+}
+
+impl DocumentMetadata {
+    /// Update the timestamps for saving the document now.
+    pub fn mark_saved(&mut self) {
+        let now = chrono::Utc::now().timestamp();
+        self.created.get_or_insert(now);
+        self.modified = Some(now);
+    }
+
+    /// Whether none of the descriptive fields are set.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_empty()
+            && self.author.is_empty()
+            && self.subject.is_empty()
+            && self.tags.is_empty()
+    }
+
+    /// The tags as a comma separated list.
+    pub fn tags_joined(&self) -> String {
+        self.tags.join(", ")
+    }
+
+    /// Set the tags from a comma separated list. Empty and duplicate tags are skipped.
+    pub fn set_tags_from_str(&mut self, tags: &str) {
+        self.tags.clear();
+        for tag in tags.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            if !self.tags.iter().any(|t| t == tag) {
+                self.tags.push(tag.to_string());
+            }
+        }
+    }
+
+    /// Loads only the metadata from the bytes of a .rnote file.
+    ///
+    /// The strokes are not deserialized, but the file still needs to be decompressed and parsed entirely,
+    /// so this should not be called for many files at once.
+    ///
+    /// Files saved by older versions have empty metadata.
+    pub async fn load_from_rnote_bytes(bytes: Vec<u8>) -> anyhow::Result<Self> {
+        let (metadata_sender, metadata_receiver) = oneshot::channel::<anyhow::Result<Self>>();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Self> {
+                let rnote_file = rnoteformat::RnoteFile::load_from_bytes(&bytes)
+                    .context("loading RnoteFile from bytes failed.")?;
+                let Some(metadata) = rnote_file
+                    .engine_snapshot
+                    .as_object()
+                    .and_then(|o| o.get("metadata"))
+                else {
+                    return Ok(Self::default());
+                };
+                ijson::from_value(metadata).context("deserializing DocumentMetadata failed.")
+            };
+
+            if metadata_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while loading metadata from rnote bytes. Receiver already dropped."
+                );
+            }
+        });
+
+        metadata_receiver.await?
+    }
+}
@@ -1,10 +1,14 @@
 // Modules
 pub mod background;
 pub mod format;
+pub mod guides;
+pub mod metadata;
 
 // Re-exports
 pub use background::Background;
 pub use format::Format;
+pub use guides::{Guide, GuideOrientation};
+pub use metadata::DocumentMetadata;
 
 // Imports
 use crate::{Camera, CloneConfig, StrokeStore, WidgetFlags};
@@ -37,6 +41,9 @@ pub enum Layout {
     SemiInfinite,
     #[serde(rename = "infinite")]
     Infinite,
+    /// A single page that grows in all directions to fit the strokes.
+    #[serde(rename = "whiteboard")]
+    Whiteboard,
 }
 
 impl Default for Layout {
@@ -63,6 +70,7 @@ impl std::str::FromStr for Layout {
             "continuous-vertical" => Ok(Self::ContinuousVertical),
             "semi-infinite" => Ok(Self::SemiInfinite),
             "infinite" => Ok(Self::Infinite),
+            "whiteboard" => Ok(Self::Whiteboard),
             s => Err(anyhow::anyhow!(
                 "Layout from_string failed, invalid name: {s}"
             )),
@@ -77,6 +85,7 @@ impl Display for Layout {
             Layout::ContinuousVertical => write!(f, "continuous-vertical"),
             Layout::SemiInfinite => write!(f, "semi-infinite"),
             Layout::Infinite => write!(f, "infinite"),
+            Layout::Whiteboard => write!(f, "whiteboard"),
         }
     }
 }
@@ -88,6 +97,59 @@ impl Layout {
     }
 }
 
+/// The directions in which the document grows in the infinite layout.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "expand_direction")]
+pub enum ExpandDirection {
+    #[serde(rename = "both")]
+    Both,
+    #[serde(rename = "vertical")]
+    Vertical,
+    #[serde(rename = "horizontal")]
+    Horizontal,
+}
+
+impl Default for ExpandDirection {
+    fn default() -> Self {
+        Self::Both
+    }
+}
+
+impl TryFrom<u32> for ExpandDirection {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!(
+                "ExpandDirection try_from::<u32>() for value {} failed",
+                value
+            )
+        })
+    }
+}
+
+impl ExpandDirection {
+    pub fn expands_horizontally(&self) -> bool {
+        matches!(self, Self::Both | Self::Horizontal)
+    }
+
+    pub fn expands_vertically(&self) -> bool {
+        matches!(self, Self::Both | Self::Vertical)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, rename = "document")]
 pub struct Document {
@@ -105,8 +167,28 @@ pub struct Document {
     pub background: Background,
     #[serde(rename = "layout", alias = "expand_mode")]
     pub layout: Layout,
+    /// The directions the document grows in when in the infinite layout.
+    #[serde(rename = "infinite_expand_direction")]
+    pub infinite_expand_direction: ExpandDirection,
+    /// Whether the pages are laid out two-up side by side like the spreads of a book in the fixed size layout.
+    #[serde(rename = "two_page_spread")]
+    pub two_page_spread: bool,
     #[serde(rename = "snap_positions")]
     pub snap_positions: bool,
+    /// Whether panning is restricted to the document bounds in the fixed size and continuous vertical layouts.
+    #[serde(rename = "clamp_panning")]
+    pub clamp_panning: bool,
+    /// Whether the strokes of the previous and next page are drawn translucently beneath the current page.
+    #[serde(rename = "onion_skinning")]
+    pub onion_skinning: bool,
+    #[serde(rename = "onion_skinning_opacity")]
+    pub onion_skinning_opacity: f64,
+    /// Whether highlighter strokes are always drawn beneath all other strokes, regardless of their chronological order.
+    #[serde(rename = "highlighters_behind")]
+    pub highlighters_behind: bool,
+    /// User placed guide lines, which positions always snap to.
+    #[serde(rename = "guides")]
+    pub guides: Vec<Guide>,
 }
 
 impl Default for Document {
@@ -119,14 +201,34 @@ impl Default for Document {
             format: Format::default(),
             background: Background::default(),
             layout: Layout::default(),
+            infinite_expand_direction: ExpandDirection::default(),
+            two_page_spread: false,
             snap_positions: false,
+            clamp_panning: false,
+            onion_skinning: false,
+            onion_skinning_opacity: Self::ONION_SKINNING_OPACITY_DEFAULT,
+            highlighters_behind: true,
+            guides: Vec::new(),
         }
     }
 }
 
+/// The parts of the document layout that are changed together with the strokes,
+/// and are therefore recorded in the store history.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DocLayout {
+    pub format_width: f64,
+    pub format_height: f64,
+    pub two_page_spread: bool,
+}
+
 impl CloneConfig for Document {
     fn clone_config(&self) -> Self {
-        self.clone()
+        // Guides belong to the content of a single document and are not part of its config
+        Self {
+            guides: Vec::new(),
+            ..self.clone()
+        }
     }
 }
 
@@ -139,6 +241,18 @@ impl Document {
         b: 0.0,
         a: 0.35,
     };
+    pub const ONION_SKINNING_OPACITY_MIN: f64 = 0.05;
+    pub const ONION_SKINNING_OPACITY_MAX: f64 = 1.0;
+    pub const ONION_SKINNING_OPACITY_DEFAULT: f64 = 0.25;
+    /// The padding around the content in the whiteboard layout.
+    pub const WHITEBOARD_PADDING: f64 = 64.0;
+    /// The color the area outside of the document is dimmed with when panning is clamped.
+    pub const OUT_OF_BOUNDS_DIM_COLOR: Color = Color {
+        r: 0.0,
+        g: 0.0,
+        b: 0.0,
+        a: 0.15,
+    };
 
     pub(crate) fn bounds(&self) -> Aabb {
         Aabb::new(
@@ -147,12 +261,69 @@ impl Document {
         )
     }
 
+    /// The current layout that is recorded in the history.
+    pub(crate) fn doc_layout(&self) -> DocLayout {
+        DocLayout {
+            format_width: self.format.width(),
+            format_height: self.format.height(),
+            two_page_spread: self.two_page_spread,
+        }
+    }
+
+    /// Apply a layout that was recorded in the history.
+    ///
+    /// Returns true if the layout has changed.
+    pub(crate) fn apply_doc_layout(&mut self, doc_layout: DocLayout) -> bool {
+        if self.doc_layout() == doc_layout {
+            return false;
+        }
+        self.format.set_width(doc_layout.format_width);
+        self.format.set_height(doc_layout.format_height);
+        self.two_page_spread = doc_layout.two_page_spread;
+        true
+    }
+
+    /// Whether panning is currently restricted to the document bounds.
+    pub fn clamps_panning(&self) -> bool {
+        self.clamp_panning && matches!(self.layout, Layout::FixedSize | Layout::ContinuousVertical)
+    }
+
+    /// The number of pages laid out next to each other.
+    pub fn pages_per_row(&self) -> u32 {
+        if self.two_page_spread && self.layout == Layout::FixedSize {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// The width of a row of pages, which is a spread of two pages in the two-page spread.
+    pub fn spread_width(&self) -> f64 {
+        self.format.width() * f64::from(self.pages_per_row())
+    }
+
+    /// Generate bounds for each spread of pages for the doc bounds.
+    ///
+    /// Equal to the pages bounds when the pages are not laid out as two-page spread.
+    pub(crate) fn spreads_bounds(&self) -> Vec<Aabb> {
+        if self.format.height() > 0.0 && self.format.width() > 0.0 {
+            self.bounds().split_extended_origin_aligned(
+                na::vector![self.spread_width(), self.format.height()],
+                SplitOrder::RowMajor,
+            )
+        } else {
+            vec![]
+        }
+    }
+
     /// Generate bounds for each page for the doc bounds, extended to fit the format.
     ///
-    /// May contain many empty pages (in infinite mode)
-    #[allow(unused)]
+    /// May contain many empty pages (in infinite mode). In the whiteboard layout the document is a single page.
     pub(crate) fn pages_bounds(&self, split_order: SplitOrder) -> Vec<Aabb> {
         let doc_bounds = self.bounds();
+        if self.layout == Layout::Whiteboard {
+            return vec![doc_bounds];
+        }
 
         if self.format.height() > 0.0 && self.format.width() > 0.0 {
             doc_bounds.split_extended_origin_aligned(
@@ -196,6 +367,9 @@ impl Document {
                 widget_flags.resize |=
                     self.resize_doc_infinite_layout(camera.viewport(), store, true);
             }
+            Layout::Whiteboard => {
+                widget_flags.resize |= self.resize_doc_whiteboard_layout(store);
+            }
         }
         widget_flags
     }
@@ -221,6 +395,9 @@ impl Document {
                 widget_flags.resize |=
                     self.resize_doc_infinite_layout(camera.viewport(), store, true);
             }
+            Layout::Whiteboard => {
+                widget_flags.resize |= self.resize_doc_whiteboard_layout(store);
+            }
         }
         widget_flags
     }
@@ -232,7 +409,7 @@ impl Document {
     ) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
         match self.layout {
-            Layout::FixedSize | Layout::ContinuousVertical => {
+            Layout::FixedSize | Layout::ContinuousVertical | Layout::Whiteboard => {
                 // not resizing in these modes, the size is not dependent on the camera
             }
             Layout::SemiInfinite => {
@@ -249,7 +426,7 @@ impl Document {
         widget_flags
     }
 
-    /// Adds a page when in fixed-size layout.
+    /// Adds a page when in fixed-size layout. In the two-page spread a whole spread is added.
     ///
     /// Returns false when not in fixed-size layout.
     pub(crate) fn add_page_fixed_size(&mut self) -> bool {
@@ -263,6 +440,7 @@ impl Document {
     }
 
     /// Removes a page when in fixed-size layout and the size is not the last page.
+    /// In the two-page spread the last spread is removed.
     ///
     /// Returns false when not in fixed-size layout.
     pub(crate) fn remove_page_fixed_size(&mut self) -> bool {
@@ -278,7 +456,7 @@ impl Document {
     fn resize_doc_fixed_size_layout(&mut self, store: &StrokeStore) -> bool {
         let format_height = self.format.height();
 
-        let new_width = self.format.width();
+        let new_width = self.spread_width();
         // max(1.0) because then 'fraction'.ceil() is at least 1
         let new_height = ((store.calc_height().max(1.0)) / format_height).ceil() * format_height;
 
@@ -363,6 +541,9 @@ impl Document {
     /// if `include_content` is set, this also expands to included the content.
     /// The computation will then get more expensive, though.
     ///
+    /// In directions the document should not expand in (see `infinite_expand_direction`), the size is
+    /// only fit to the first page and the content, but never expanded to include the viewport.
+    ///
     /// Returns true if a resize happened.
     #[must_use = "Determines if the resize flag should be set"]
     fn resize_doc_infinite_layout(
@@ -377,19 +558,57 @@ impl Document {
         let mut new_bounds = self
             .bounds()
             .merged(&viewport.extend_by(na::vector![padding_horizontal, padding_vertical]));
+        // The bounds the document is restricted to in the directions it should not expand in
+        let mut fixed_bounds = self.bounds();
 
         if include_content {
             let keys = store.stroke_keys_as_rendered();
+            let first_page_bounds = Aabb::new(na::point![0.0, 0.0], self.format.size().into());
             let content_bounds = if let Some(content_bounds) = store.bounds_for_strokes(&keys) {
+                fixed_bounds = content_bounds.merged(&first_page_bounds);
                 content_bounds.extend_by(na::vector![padding_horizontal, padding_vertical])
             } else {
                 // If doc is empty, resize to one page with the format size
-                Aabb::new(na::point![0.0, 0.0], self.format.size().into())
-                    .extend_by(na::vector![padding_horizontal, padding_vertical])
+                fixed_bounds = first_page_bounds;
+                first_page_bounds.extend_by(na::vector![padding_horizontal, padding_vertical])
             };
             new_bounds.merge(&content_bounds);
         }
 
+        if !self.infinite_expand_direction.expands_horizontally() {
+            new_bounds.mins[0] = fixed_bounds.mins[0];
+            new_bounds.maxs[0] = fixed_bounds.maxs[0];
+        }
+        if !self.infinite_expand_direction.expands_vertically() {
+            new_bounds.mins[1] = fixed_bounds.mins[1];
+            new_bounds.maxs[1] = fixed_bounds.maxs[1];
+        }
+
+        set_dimensions_checked(
+            &mut self.x,
+            &mut self.y,
+            &mut self.width,
+            &mut self.height,
+            new_bounds.mins[0],
+            new_bounds.mins[1],
+            new_bounds.extents()[0],
+            new_bounds.extents()[1],
+        )
+    }
+
+    /// Resizes the document to the bounds of the content with some padding for the whiteboard layout.
+    ///
+    /// An empty document is resized to the first page with the format size.
+    ///
+    /// Returns true if a resize happened.
+    #[must_use = "Determines if the resize flag should be set"]
+    fn resize_doc_whiteboard_layout(&mut self, store: &StrokeStore) -> bool {
+        let keys = store.stroke_keys_as_rendered();
+        let new_bounds = match store.bounds_for_strokes(&keys) {
+            Some(content_bounds) => content_bounds.loosened(Self::WHITEBOARD_PADDING),
+            None => Aabb::new(na::point![0.0, 0.0], self.format.size().into()),
+        };
+
         set_dimensions_checked(
             &mut self.x,
             &mut self.y,
@@ -402,18 +621,30 @@ impl Document {
         )
     }
 
-    /// Snap the position to the document and pattern grid when `snap_positions` is enabled.
+    /// Snap the position to the document and pattern grid when `snap_positions` is enabled,
+    /// and to the guides when it is close to them.
     ///
-    /// If not, the original coordinates are returned.
+    /// If neither applies, the original coordinates are returned.
     pub(crate) fn snap_position(&self, pos: na::Vector2<f64>) -> na::Vector2<f64> {
+        let mut pos_snapped = if self.snap_positions {
+            self.snap_position_to_grid(pos)
+        } else {
+            pos
+        };
+
+        // Guides take precedence, they were explicitly placed by the user.
+        for guide in self.guides.iter() {
+            guide.snap(&mut pos_snapped);
+        }
+
+        pos_snapped
+    }
+
+    fn snap_position_to_grid(&self, pos: na::Vector2<f64>) -> na::Vector2<f64> {
         const DOCUMENT_SNAP_DIST: f64 = 10.;
         let doc_format_size = self.format.size();
         let pattern_size = self.background.pattern_size;
 
-        if !self.snap_positions {
-            return pos;
-        }
-
         let snap_to_grid = |pos: na::Vector2<f64>, grid_size: na::Vector2<f64>| {
             let grid_pos = pos.component_div(&grid_size);
             grid_size.component_mul(&grid_pos.round())
@@ -434,6 +665,23 @@ impl Document {
 
         pos_snapped
     }
+
+    /// The index of the closest guide with the given orientation, if it is within `dist` of the position.
+    pub fn guide_near(
+        &self,
+        orientation: GuideOrientation,
+        pos: na::Vector2<f64>,
+        dist: f64,
+    ) -> Option<usize> {
+        self.guides
+            .iter()
+            .enumerate()
+            .filter(|(_, guide)| guide.orientation == orientation)
+            .map(|(i, guide)| (i, guide.distance_to(pos)))
+            .filter(|(_, guide_dist)| *guide_dist < dist)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i)
+    }
 }
 
 #[must_use = "Determines if the resize flag should be set"]
@@ -1,13 +1,18 @@
 // Imports
+use super::profiling::{self, ProfileCategory};
 use super::{Engine, EngineConfig, StrokeContent};
-use crate::fileformats::rnoteformat::RnoteFile;
-use crate::fileformats::{xoppformat, FileFormatSaver};
+use crate::document::background::PatternStyle;
+use crate::document::DocumentMetadata;
+use crate::fileformats::rnoteformat::{CompressionMethod, RevisionHistory, RnoteFile};
+use crate::fileformats::{xoppformat, FileFormatLoader, FileFormatSaver};
+use crate::render;
 use crate::CloneConfig;
 use anyhow::Context;
 use futures::channel::oneshot;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rayon::prelude::*;
 use rnote_compose::transform::Transformable;
-use rnote_compose::SplitOrder;
+use rnote_compose::{Color, SplitOrder};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::error;
@@ -67,6 +72,221 @@ impl DocExportFormat {
     }
 }
 
+/// The position of the page numbers on exported pages.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename = "page_number_position")]
+pub enum PageNumberPosition {
+    #[serde(rename = "bottom_center")]
+    BottomCenter,
+    #[serde(rename = "bottom_right")]
+    BottomRight,
+    #[serde(rename = "bottom_left")]
+    BottomLeft,
+    #[serde(rename = "top_center")]
+    TopCenter,
+    #[serde(rename = "top_right")]
+    TopRight,
+    #[serde(rename = "top_left")]
+    TopLeft,
+}
+
+impl Default for PageNumberPosition {
+    fn default() -> Self {
+        Self::BottomCenter
+    }
+}
+
+impl TryFrom<u32> for PageNumberPosition {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!(
+                "PageNumberPosition try_from::<u32>() for value {} failed",
+                value
+            )
+        })
+    }
+}
+
+/// The font of the page numbers on exported pages.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename = "page_number_font")]
+pub enum PageNumberFont {
+    #[serde(rename = "serif")]
+    Serif,
+    #[serde(rename = "sans_serif")]
+    SansSerif,
+    #[serde(rename = "monospace")]
+    Monospace,
+}
+
+impl Default for PageNumberFont {
+    fn default() -> Self {
+        Self::SansSerif
+    }
+}
+
+impl TryFrom<u32> for PageNumberFont {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!(
+                "PageNumberFont try_from::<u32>() for value {} failed",
+                value
+            )
+        })
+    }
+}
+
+impl PageNumberFont {
+    /// The generic font family name.
+    pub fn family_name(self) -> &'static str {
+        match self {
+            Self::Serif => "serif",
+            Self::SansSerif => "sans-serif",
+            Self::Monospace => "monospace",
+        }
+    }
+}
+
+/// Page numbering preferences, stamping page numbers onto the exported pages.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "page_numbering_prefs")]
+pub struct PageNumberingPrefs {
+    /// Whether page numbers should be added.
+    #[serde(rename = "enabled")]
+    pub enabled: bool,
+    /// The position of the page numbers.
+    #[serde(rename = "position")]
+    pub position: PageNumberPosition,
+    /// The font of the page numbers.
+    #[serde(rename = "font")]
+    pub font: PageNumberFont,
+    /// The font size of the page numbers.
+    #[serde(rename = "font_size")]
+    pub font_size: f64,
+    /// The number of the first exported page.
+    #[serde(rename = "start")]
+    pub start: u32,
+}
+
+impl Default for PageNumberingPrefs {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            position: PageNumberPosition::default(),
+            font: PageNumberFont::default(),
+            font_size: 12.0,
+            start: 1,
+        }
+    }
+}
+
+impl PageNumberingPrefs {
+    pub const FONT_SIZE_MIN: f64 = 4.0;
+    pub const FONT_SIZE_MAX: f64 = 96.0;
+    /// The margin between the page edges and the page number, relative to the font size.
+    const MARGIN_FONT_SIZE_FACTOR: f64 = 3.0;
+
+    /// Draw the number of the page with the given index to the cairo context.
+    ///
+    /// Expects that the context is transformed into the coordinate space of the page bounds.
+    pub fn draw_to_cairo(
+        &self,
+        cairo_cx: &cairo::Context,
+        page_bounds: Aabb,
+        page_index: usize,
+    ) -> anyhow::Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let text = (self.start as usize + page_index).to_string();
+        let font_size = self
+            .font_size
+            .clamp(Self::FONT_SIZE_MIN, Self::FONT_SIZE_MAX);
+        let margin = font_size * Self::MARGIN_FONT_SIZE_FACTOR;
+
+        cairo_cx.save()?;
+        cairo_cx.select_font_face(
+            self.font.family_name(),
+            cairo::FontSlant::Normal,
+            cairo::FontWeight::Normal,
+        );
+        cairo_cx.set_font_size(font_size);
+        let extents = cairo_cx.text_extents(&text)?;
+
+        let x = match self.position {
+            PageNumberPosition::TopLeft | PageNumberPosition::BottomLeft => {
+                page_bounds.mins[0] + margin - extents.x_bearing()
+            }
+            PageNumberPosition::TopCenter | PageNumberPosition::BottomCenter => {
+                page_bounds.center()[0] - extents.width() * 0.5 - extents.x_bearing()
+            }
+            PageNumberPosition::TopRight | PageNumberPosition::BottomRight => {
+                page_bounds.maxs[0] - margin - extents.width() - extents.x_bearing()
+            }
+        };
+        let y = match self.position {
+            PageNumberPosition::TopLeft
+            | PageNumberPosition::TopCenter
+            | PageNumberPosition::TopRight => page_bounds.mins[1] + margin - extents.y_bearing(),
+            PageNumberPosition::BottomLeft
+            | PageNumberPosition::BottomCenter
+            | PageNumberPosition::BottomRight => {
+                page_bounds.maxs[1] - margin - extents.height() - extents.y_bearing()
+            }
+        };
+
+        cairo_cx.set_source_rgba(0.0, 0.0, 0.0, 1.0);
+        cairo_cx.move_to(x, y);
+        cairo_cx.show_text(&text)?;
+        cairo_cx.restore()?;
+        Ok(())
+    }
+
+    /// Stamp the number of the page with the given index onto the page Svg.
+    pub fn stamp_svg(&self, page_svg: &mut render::Svg, page_index: usize) -> anyhow::Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let page_bounds = page_svg.bounds;
+        let overlay = render::Svg::gen_with_cairo(
+            |cairo_cx| self.draw_to_cairo(cairo_cx, page_bounds, page_index),
+            page_bounds,
+        )?;
+        page_svg.merge([overlay]);
+        Ok(())
+    }
+}
+
 /// Document export preferences.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(default, rename = "doc_export_prefs")]
@@ -86,6 +306,17 @@ pub struct DocExportPrefs {
     /// The page order when documents with layouts that expand in horizontal and vertical directions are cut into pages.
     #[serde(rename = "page_order")]
     pub page_order: SplitOrder,
+    /// The page numbering. Only applies to the Pdf export format.
+    #[serde(rename = "page_numbering")]
+    pub page_numbering: PageNumberingPrefs,
+    /// Whether page breaks are moved to gaps between strokes, so that strokes are not cut through.
+    ///
+    /// Only applies to the Pdf export format and layouts that are not fixed-size.
+    #[serde(rename = "smart_page_breaks")]
+    pub smart_page_breaks: bool,
+    /// Whether the document is exported as a single page that is cropped to the bounds of the content.
+    #[serde(rename = "crop_to_content")]
+    pub crop_to_content: bool,
 }
 
 impl Default for DocExportPrefs {
@@ -96,12 +327,17 @@ impl Default for DocExportPrefs {
             optimize_printing: false,
             export_format: DocExportFormat::default(),
             page_order: SplitOrder::default(),
+            page_numbering: PageNumberingPrefs::default(),
+            smart_page_breaks: false,
+            crop_to_content: false,
         }
     }
 }
 
 impl DocExportPrefs {
     const MARGIN: f64 = 0.0;
+    /// The margin around the content when the document is cropped to it.
+    const CROP_TO_CONTENT_MARGIN: f64 = 12.0;
 }
 
 /// Document pages export format.
@@ -183,6 +419,9 @@ pub struct DocPagesExportPrefs {
     /// Quality when exporting as Jpeg.
     #[serde(rename = "jpg_quality")]
     pub jpeg_quality: u8,
+    /// The page numbering.
+    #[serde(rename = "page_numbering")]
+    pub page_numbering: PageNumberingPrefs,
 }
 
 impl DocPagesExportPrefs {
@@ -199,6 +438,7 @@ impl Default for DocPagesExportPrefs {
             page_order: SplitOrder::default(),
             bitmap_scalefactor: 1.8,
             jpeg_quality: 85,
+            page_numbering: PageNumberingPrefs::default(),
         }
     }
 }
@@ -281,6 +521,9 @@ pub struct SelectionExportPrefs {
     /// The margins of the export extending the bounds of the selection.
     #[serde(rename = "margin")]
     pub margin: f64,
+    /// Whether the strokes are embedded into exported Svgs, so that they are editable again when imported.
+    #[serde(rename = "embed_strokes")]
+    pub embed_strokes: bool,
 }
 
 impl Default for SelectionExportPrefs {
@@ -293,6 +536,142 @@ impl Default for SelectionExportPrefs {
             bitmap_scalefactor: 1.8,
             jpeg_quality: 85,
             margin: 12.0,
+            embed_strokes: true,
+        }
+    }
+}
+
+/// Viewport export preferences.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "viewport_export_prefs")]
+pub struct ViewportExportPrefs {
+    /// Whether the background should be exported.
+    #[serde(rename = "with_background")]
+    pub with_background: bool,
+    /// Whether the background pattern should be exported.
+    #[serde(rename = "with_pattern")]
+    pub with_pattern: bool,
+    /// Whether the background and stroke colors should be optimized for printing.
+    #[serde(rename = "optimize_printing")]
+    pub optimize_printing: bool,
+    /// Export format.
+    #[serde(rename = "export_format")]
+    pub export_format: SelectionExportFormat,
+    /// The bitmap scale-factor in relation to the actual size on the document.
+    #[serde(rename = "bitmap_scalefactor")]
+    pub bitmap_scalefactor: f64,
+    /// Quality when exporting as Jpeg.
+    #[serde(rename = "jpg_quality")]
+    pub jpeg_quality: u8,
+}
+
+impl Default for ViewportExportPrefs {
+    fn default() -> Self {
+        Self {
+            with_background: true,
+            with_pattern: true,
+            optimize_printing: false,
+            export_format: SelectionExportFormat::Png,
+            bitmap_scalefactor: 1.8,
+            jpeg_quality: 85,
+        }
+    }
+}
+
+/// Preferences for the revision history that is stored inside saved .rnote files.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "revision_history_prefs")]
+pub struct RevisionHistoryPrefs {
+    /// Whether previous saves are stored as revisions.
+    ///
+    /// When disabled, the stored revisions are removed on the next save.
+    #[serde(rename = "enabled")]
+    pub enabled: bool,
+    /// The maximum number of stored revisions. The oldest are removed beyond it.
+    #[serde(rename = "max_revisions")]
+    pub max_revisions: u32,
+}
+
+impl Default for RevisionHistoryPrefs {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_revisions: 20,
+        }
+    }
+}
+
+impl RevisionHistoryPrefs {
+    pub const MAX_REVISIONS_MIN: u32 = 1;
+    pub const MAX_REVISIONS_MAX: u32 = 200;
+}
+
+/// Compression preferences for saved .rnote files.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "rnote_compression_prefs")]
+pub struct RnoteCompressionPrefs {
+    /// The compression method.
+    #[serde(rename = "method")]
+    pub method: CompressionMethod,
+    /// The compression level. Clamped to the range supported by the method when saving.
+    #[serde(rename = "level")]
+    pub level: u32,
+}
+
+impl Default for RnoteCompressionPrefs {
+    fn default() -> Self {
+        let method = CompressionMethod::default();
+        Self {
+            method,
+            level: method.default_level(),
+        }
+    }
+}
+
+/// How the pages are scaled onto the paper when printing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "print_scaling")]
+pub enum PrintScaling {
+    /// Scale the pages to fit into the printable area of the paper.
+    #[default]
+    #[serde(rename = "fit_to_paper")]
+    FitToPaper,
+    /// Print the pages in their real-world size, according to the document Dpi.
+    #[serde(rename = "actual_size")]
+    ActualSize,
+}
+
+/// Print preferences.
+///
+/// The paper size, margins and page ranges are chosen in the print dialog.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "print_prefs")]
+pub struct PrintPrefs {
+    /// Whether the background should be printed.
+    #[serde(rename = "with_background")]
+    pub with_background: bool,
+    /// Whether the background pattern should be printed.
+    #[serde(rename = "with_pattern")]
+    pub with_pattern: bool,
+    /// Whether the background and stroke colors should be optimized for printing.
+    #[serde(rename = "optimize_printing")]
+    pub optimize_printing: bool,
+    /// The page order when documents with layouts that expand in horizontal and vertical directions are cut into pages.
+    #[serde(rename = "page_order")]
+    pub page_order: SplitOrder,
+    /// How the pages are scaled onto the paper.
+    #[serde(rename = "scaling")]
+    pub scaling: PrintScaling,
+}
+
+impl Default for PrintPrefs {
+    fn default() -> Self {
+        Self {
+            with_background: true,
+            with_pattern: true,
+            optimize_printing: false,
+            page_order: SplitOrder::default(),
+            scaling: PrintScaling::default(),
         }
     }
 }
@@ -310,6 +689,18 @@ pub struct ExportPrefs {
     /// Selection export preferences.
     #[serde(rename = "selection_export_prefs")]
     pub selection_export_prefs: SelectionExportPrefs,
+    /// Viewport export preferences.
+    #[serde(rename = "viewport_export_prefs")]
+    pub viewport_export_prefs: ViewportExportPrefs,
+    /// Revision history preferences.
+    #[serde(rename = "revision_history_prefs")]
+    pub revision_history_prefs: RevisionHistoryPrefs,
+    /// Print preferences.
+    #[serde(rename = "print_prefs")]
+    pub print_prefs: PrintPrefs,
+    /// Compression preferences for saved .rnote files.
+    #[serde(rename = "rnote_compression_prefs")]
+    pub rnote_compression_prefs: RnoteCompressionPrefs,
 }
 
 impl CloneConfig for ExportPrefs {
@@ -328,13 +719,19 @@ impl Engine {
         file_name: String,
     ) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
-        let engine_snapshot = self.take_snapshot();
+        let mut engine_snapshot = self.take_snapshot();
+        engine_snapshot.metadata.mark_saved();
+        let compression_prefs = self.export_prefs.rnote_compression_prefs;
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Vec<u8>> {
+                let _span = profiling::span(ProfileCategory::Save);
                 let rnote_file = RnoteFile {
                     engine_snapshot: ijson::to_value(&engine_snapshot)?,
+                    revision_history: RevisionHistory::default(),
                 };
-                rnote_file.save_as_bytes(&file_name)
+                rnote_file
+                    .save_as_compressed_bytes(compression_prefs.method, compression_prefs.level)
+                    .with_context(|| format!("Saving '{file_name}' as rnote bytes failed."))
             };
             if oneshot_sender.send(result()).is_err() {
                 error!(
@@ -345,6 +742,62 @@ impl Engine {
         oneshot_receiver
     }
 
+    /// Save the current document as a .rnote file and record the previously saved file as a revision,
+    /// when the revision history is enabled.
+    ///
+    /// `previous_bytes` are the bytes of the previously saved .rnote file of this document. Its revision history is
+    /// carried over. `autosave` marks the save as autosave, which is merged into the next save instead of being kept
+    /// as its own revision.
+    pub fn save_as_rnote_bytes_w_revisions(
+        &self,
+        file_name: String,
+        previous_bytes: Option<Vec<u8>>,
+        autosave: bool,
+    ) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+        let mut engine_snapshot = self.take_snapshot();
+        engine_snapshot.metadata.mark_saved();
+        let revision_history_prefs = self.export_prefs.revision_history_prefs;
+        let compression_prefs = self.export_prefs.rnote_compression_prefs;
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<u8>> {
+                let _span = profiling::span(ProfileCategory::Save);
+                let engine_snapshot = ijson::to_value(&engine_snapshot)?;
+                let mut revision_history = RevisionHistory::default();
+                if revision_history_prefs.enabled {
+                    match previous_bytes.map(|bytes| RnoteFile::load_from_bytes(&bytes)) {
+                        Some(Ok(previous_file)) => {
+                            revision_history = previous_file.revision_history;
+                            revision_history.record(
+                                &previous_file.engine_snapshot,
+                                &engine_snapshot,
+                                revision_history_prefs.max_revisions as usize,
+                            );
+                        }
+                        Some(Err(e)) => {
+                            error!("Loading the previously saved file failed while recording its revision, starting a new revision history. Err: {e:?}");
+                        }
+                        None => {}
+                    }
+                    revision_history.mark_saved(autosave);
+                }
+                let rnote_file = RnoteFile {
+                    engine_snapshot,
+                    revision_history,
+                };
+                rnote_file
+                    .save_as_compressed_bytes(compression_prefs.method, compression_prefs.level)
+                    .with_context(|| format!("Saving '{file_name}' as rnote bytes failed."))
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while saving document as rnote bytes with revisions. Receiver already dropped."
+                );
+            }
+        });
+        oneshot_receiver
+    }
+
     /// Extract the current engine configuration.
     pub fn extract_engine_config(&self) -> EngineConfig {
         EngineConfig {
@@ -353,11 +806,13 @@ impl Engine {
             penholder: self.penholder.clone_config(),
             import_prefs: self.import_prefs.clone_config(),
             export_prefs: self.export_prefs.clone_config(),
-            pen_sounds: self.pen_sounds(),
+            pen_sounds: None,
+            pen_sounds_config: self.pen_sounds_config().clone(),
             optimize_epd: self.optimize_epd(),
         }
     }
 
+    /// Extract the strokes of the document in the order they are rendered, together with its bounds and background.
     pub fn extract_document_content(&self) -> StrokeContent {
         StrokeContent::default()
             .with_strokes(
@@ -368,11 +823,45 @@ impl Engine {
                 self.bounds_w_content_extended()
                     .unwrap_or(self.document.bounds()),
             ))
-            .with_background(Some(self.document.background))
+            .with_background(Some(self.document.background.clone()))
+    }
+
+    /// The bounds of the content with a margin, as the document is exported when it is cropped to it.
+    ///
+    /// The origin page when the document has no content.
+    pub fn content_bounds_cropped(&self) -> Aabb {
+        self.store
+            .bounds_for_strokes(&self.store.stroke_keys_as_rendered())
+            .map(|bounds| bounds.loosened(DocExportPrefs::CROP_TO_CONTENT_MARGIN))
+            .unwrap_or_else(|| Aabb::new(na::point![0.0, 0.0], self.document.format.size().into()))
     }
 
     pub fn extract_pages_content(&self, page_order: SplitOrder) -> Vec<StrokeContent> {
-        self.pages_bounds_w_content(page_order)
+        self.extract_content_in_pages(self.pages_bounds_w_content(page_order))
+    }
+
+    /// Extract the pages content as it is exported with the given document export preferences.
+    ///
+    /// Differs from [Self::extract_pages_content] when the content is cropped or smart page breaks apply.
+    pub fn extract_doc_export_pages_content(
+        &self,
+        doc_export_prefs: &DocExportPrefs,
+    ) -> Vec<StrokeContent> {
+        if doc_export_prefs.crop_to_content {
+            self.extract_content_in_pages(vec![self.content_bounds_cropped()])
+        } else if doc_export_prefs.smart_page_breaks
+            && doc_export_prefs.export_format == DocExportFormat::Pdf
+        {
+            self.extract_content_in_pages(
+                self.pages_bounds_w_smart_breaks(doc_export_prefs.page_order),
+            )
+        } else {
+            self.extract_pages_content(doc_export_prefs.page_order)
+        }
+    }
+
+    fn extract_content_in_pages(&self, pages_bounds: Vec<Aabb>) -> Vec<StrokeContent> {
+        pages_bounds
             .into_iter()
             .map(|bounds| {
                 StrokeContent::default()
@@ -384,7 +873,7 @@ impl Engine {
                         ),
                     )
                     .with_bounds(Some(bounds))
-                    .with_background(Some(self.document.background))
+                    .with_background(Some(self.document.background.clone()))
             })
             .collect()
     }
@@ -397,10 +886,80 @@ impl Engine {
         Some(
             StrokeContent::default()
                 .with_strokes(self.store.get_strokes_arc(&selection_keys))
-                .with_background(Some(self.document.background)),
+                .with_background(Some(self.document.background.clone())),
         )
     }
 
+    /// Extract the content that is currently visible in the viewport, bounded by it.
+    pub fn extract_viewport_content(&self) -> StrokeContent {
+        let viewport = self.camera.viewport();
+        StrokeContent::default()
+            .with_strokes(
+                self.store.get_strokes_arc(
+                    &self
+                        .store
+                        .stroke_keys_as_rendered_intersecting_bounds(viewport),
+                ),
+            )
+            .with_bounds(Some(viewport))
+            .with_background(Some(self.document.background.clone()))
+    }
+
+    /// Generate thumbnails of the pages at the given indices, scaled to the thumbnail width.
+    ///
+    /// The indices refer to the pages as returned by `Engine::pages_bounds()`, out of bounds indices are skipped.
+    /// The thumbnails are generated on a worker thread and returned together with their page index.
+    pub fn gen_page_thumbnails(
+        &self,
+        page_indices: &[usize],
+        thumbnail_width: f64,
+    ) -> oneshot::Receiver<anyhow::Result<Vec<(usize, render::Image)>>> {
+        let (oneshot_sender, oneshot_receiver) =
+            oneshot::channel::<anyhow::Result<Vec<(usize, render::Image)>>>();
+        let pages_bounds = self.pages_bounds();
+        let pages_contents = page_indices
+            .iter()
+            .filter_map(|&i| {
+                let bounds = *pages_bounds.get(i)?;
+                Some((
+                    i,
+                    StrokeContent::default()
+                        .with_strokes(
+                            self.store.get_strokes_arc(
+                                &self
+                                    .store
+                                    .stroke_keys_as_rendered_intersecting_bounds(bounds),
+                            ),
+                        )
+                        .with_bounds(Some(bounds))
+                        .with_background(Some(self.document.background.clone())),
+                ))
+            })
+            .collect::<Vec<(usize, StrokeContent)>>();
+        let image_scale = thumbnail_width / self.document.format.width();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<(usize, render::Image)>> {
+                pages_contents
+                    .into_par_iter()
+                    .map(|(i, page_content)| {
+                        let page_svg = page_content.gen_svg(true, true, false, 0.0)?.ok_or(
+                            anyhow::anyhow!(
+                                "Generating Svg for page {i} thumbnail failed, returned None."
+                            ),
+                        )?;
+                        Ok((i, page_svg.gen_image(image_scale)?))
+                    })
+                    .collect()
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!("Sending result to receiver failed while generating page thumbnails. Receiver already dropped.");
+            }
+        });
+
+        oneshot_receiver
+    }
+
     /// Export the current engine config as Json string.
     pub fn export_engine_config_as_json(&self) -> anyhow::Result<String> {
         Ok(serde_json::to_string(&self.extract_engine_config())?)
@@ -439,7 +998,10 @@ impl Engine {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
         let doc_export_prefs =
             doc_export_prefs_override.unwrap_or(self.export_prefs.doc_export_prefs);
-        let doc_content = self.extract_document_content();
+        let mut doc_content = self.extract_document_content();
+        if doc_export_prefs.crop_to_content {
+            doc_content = doc_content.with_bounds(Some(self.content_bounds_cropped()));
+        }
 
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Vec<u8>> {
@@ -480,8 +1042,12 @@ impl Engine {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
         let doc_export_prefs =
             doc_export_prefs_override.unwrap_or(self.export_prefs.doc_export_prefs);
-        let pages_content = self.extract_pages_content(doc_export_prefs.page_order);
+        let pages_content = self.extract_doc_export_pages_content(&doc_export_prefs);
         let format_size = self.document.format.size();
+        // Pages with smart page breaks can be shorter than the format, but are still printed on the full format.
+        let pages_have_format_size =
+            doc_export_prefs.smart_page_breaks && !doc_export_prefs.crop_to_content;
+        let metadata = self.metadata.clone();
 
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Vec<u8>> {
@@ -489,15 +1055,7 @@ impl Engine {
                     cairo::PdfSurface::for_stream(format_size[0], format_size[1], Vec::<u8>::new())
                         .context("Creating Pdf target surface failed.")?;
 
-                target_surface
-                    .set_metadata(cairo::PdfMetadata::Title, title.as_str())
-                    .context("Set pdf surface title metadata failed.")?;
-                target_surface
-                    .set_metadata(
-                        cairo::PdfMetadata::CreateDate,
-                        crate::utils::now_formatted_string().as_str(),
-                    )
-                    .context("Set pdf surface date metadata failed.")?;
+                set_pdf_surface_metadata(&target_surface, &metadata, &title)?;
 
                 // New scope to avoid errors when flushing
                 {
@@ -508,6 +1066,15 @@ impl Engine {
                         let Some(page_bounds) = page_content.bounds() else {
                             continue;
                         };
+                        // The page size can vary, e.g. when cropped to the content or in the whiteboard layout
+                        let page_size = if pages_have_format_size {
+                            format_size
+                        } else {
+                            page_bounds.extents()
+                        };
+                        target_surface
+                            .set_size(page_size[0], page_size[1])
+                            .context("Setting the size of the pdf page failed.")?;
                         cairo_cx.save()?;
                         cairo_cx.translate(-page_bounds.mins[0], -page_bounds.mins[1]);
                         page_content.draw_to_cairo(
@@ -518,6 +1085,11 @@ impl Engine {
                             DocExportPrefs::MARGIN,
                             Engine::STROKE_EXPORT_IMAGE_SCALE,
                         )?;
+                        doc_export_prefs.page_numbering.draw_to_cairo(
+                            &cairo_cx,
+                            Aabb::new(page_bounds.mins, page_bounds.mins + page_size),
+                            i,
+                        )?;
                         cairo_cx.show_page().map_err(|e| {
                             anyhow::anyhow!(
                                 "Showing page failed while exporting page {i} as pdf, Err: {e:?}"
@@ -554,17 +1126,38 @@ impl Engine {
         let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
         let doc_export_prefs =
             doc_export_prefs_override.unwrap_or(self.export_prefs.doc_export_prefs);
-        let pages_content = self.extract_pages_content(doc_export_prefs.page_order);
+        let pages_content = self.extract_doc_export_pages_content(&doc_export_prefs);
         let document = self.document.clone();
 
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Vec<u8>> {
-                // Only one background for all pages
+                // Only one background for all pages.
+                // Xopp backgrounds don't have configurable pattern sizes, so only the pattern style is mapped.
+                let xopp_background_style = match document.background.pattern {
+                    _ if !doc_export_prefs.with_pattern => {
+                        xoppformat::XoppBackgroundSolidStyle::Plain
+                    }
+                    PatternStyle::None => xoppformat::XoppBackgroundSolidStyle::Plain,
+                    PatternStyle::Lines => xoppformat::XoppBackgroundSolidStyle::Ruled,
+                    PatternStyle::Grid => xoppformat::XoppBackgroundSolidStyle::Graph,
+                    PatternStyle::Dots => xoppformat::XoppBackgroundSolidStyle::Dotted,
+                    PatternStyle::IsometricGrid => {
+                        xoppformat::XoppBackgroundSolidStyle::IsometricGraph
+                    }
+                    PatternStyle::IsometricDots => {
+                        xoppformat::XoppBackgroundSolidStyle::IsometricDotted
+                    }
+                    PatternStyle::CustomTile => xoppformat::XoppBackgroundSolidStyle::Plain,
+                };
                 let xopp_background = xoppformat::XoppBackground {
                     name: None,
                     bg_type: xoppformat::XoppBackgroundType::Solid {
-                        color: crate::utils::xoppcolor_from_color(document.background.color),
-                        style: xoppformat::XoppBackgroundSolidStyle::Plain,
+                        color: if doc_export_prefs.with_background {
+                            crate::utils::xoppcolor_from_color(document.background.color)
+                        } else {
+                            crate::utils::xoppcolor_from_color(Color::WHITE)
+                        },
+                        style: xopp_background_style,
                     },
                 };
 
@@ -711,7 +1304,7 @@ impl Engine {
                     .into_par_iter()
                     .enumerate()
                     .map(|(i, page_content)| {
-                        let page_svg = page_content
+                        let mut page_svg = page_content
                             .gen_svg(
                                 doc_pages_export_prefs.with_background,
                                 doc_pages_export_prefs.with_pattern,
@@ -721,6 +1314,9 @@ impl Engine {
                             .ok_or(anyhow::anyhow!(
                                 "Generating Svg for page {i} failed, returned None."
                             ))?;
+                        doc_pages_export_prefs
+                            .page_numbering
+                            .stamp_svg(&mut page_svg, i)?;
                         Ok(rnote_compose::utils::add_xml_header(
                             rnote_compose::utils::wrap_svg_root(
                                 page_svg.svg_data.as_str(),
@@ -768,7 +1364,7 @@ impl Engine {
                     .into_par_iter()
                     .enumerate()
                     .map(|(i, page_content)| {
-                        page_content
+                        let mut page_svg = page_content
                             .gen_svg(
                                 doc_pages_export_prefs.with_background,
                                 doc_pages_export_prefs.with_pattern,
@@ -777,7 +1373,11 @@ impl Engine {
                             )?
                             .ok_or(anyhow::anyhow!(
                                 "Generating Svg for page {i} failed, returned None."
-                            ))?
+                            ))?;
+                        doc_pages_export_prefs
+                            .page_numbering
+                            .stamp_svg(&mut page_svg, i)?;
+                        page_svg
                             .gen_image(doc_pages_export_prefs.bitmap_scalefactor)?
                             .into_encoded_bytes(
                                 image_format,
@@ -794,6 +1394,80 @@ impl Engine {
         oneshot_receiver
     }
 
+    /// Export the document as a self-contained Html page for viewing it in a web browser.
+    ///
+    /// Every page is embedded as inline Svg. The page reloads itself periodically,
+    /// so that it follows the changes of the document when it is served.
+    pub fn export_doc_as_html_viewer_bytes(
+        &self,
+        title: String,
+    ) -> oneshot::Receiver<Result<Vec<u8>, anyhow::Error>> {
+        /// The interval in seconds in which the page reloads itself.
+        const RELOAD_INTERVAL_SECS: u32 = 10;
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+        let doc_pages_export_prefs = self.export_prefs.doc_pages_export_prefs;
+        let pages_content = self.extract_pages_content(doc_pages_export_prefs.page_order);
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<u8>> {
+                let pages_svgs = pages_content
+                    .into_par_iter()
+                    .enumerate()
+                    .map(|(i, page_content)| {
+                        let page_svg = page_content
+                            .gen_svg(
+                                doc_pages_export_prefs.with_background,
+                                doc_pages_export_prefs.with_pattern,
+                                false,
+                                DocPagesExportPrefs::MARGIN,
+                            )?
+                            .ok_or(anyhow::anyhow!(
+                                "Generating Svg for page {i} failed, returned None."
+                            ))?;
+                        Ok(rnote_compose::utils::wrap_svg_root(
+                            page_svg.svg_data.as_str(),
+                            Some(page_svg.bounds),
+                            Some(page_svg.bounds),
+                            false,
+                        ))
+                    })
+                    .collect::<anyhow::Result<Vec<String>>>()?;
+
+                let mut html = format!(
+                    r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta http-equiv="refresh" content="{RELOAD_INTERVAL_SECS}">
+<title>{}</title>
+<style>
+body {{ margin: 0; padding: 12px; background: #deddda; }}
+.page {{ max-width: 1000px; margin: 0 auto 12px auto; background: white; box-shadow: 0 1px 4px rgba(0, 0, 0, 0.3); }}
+.page svg {{ display: block; width: 100%; height: auto; }}
+</style>
+</head>
+<body>
+"#,
+                    html_escape(&title)
+                );
+                for page_svg in pages_svgs {
+                    html.push_str("<div class=\"page\">\n");
+                    html.push_str(&page_svg);
+                    html.push_str("\n</div>\n");
+                }
+                html.push_str("</body>\n</html>\n");
+                Ok(html.into_bytes())
+            };
+
+            if oneshot_sender.send(result()).is_err() {
+                error!("Sending result to receiver failed while exporting document as Html viewer bytes. Receiver already dropped.");
+            }
+        });
+
+        oneshot_receiver
+    }
+
     /// Exports the current selection.
     pub fn export_selection(
         &self,
@@ -828,7 +1502,7 @@ impl Engine {
                 let Some(content) = content else {
                     return Ok(None);
                 };
-                let Some(svg) = content.gen_svg(
+                let Some(mut svg) = content.gen_svg(
                     selection_export_prefs.with_background,
                     selection_export_prefs.with_pattern,
                     selection_export_prefs.optimize_printing,
@@ -837,6 +1511,9 @@ impl Engine {
                 else {
                     return Ok(None);
                 };
+                if selection_export_prefs.embed_strokes {
+                    svg.svg_data.push_str(&content.gen_svg_metadata()?);
+                }
 
                 Ok(Some(
                     rnote_compose::utils::add_xml_header(
@@ -907,4 +1584,117 @@ impl Engine {
 
         oneshot_receiver
     }
+
+    /// Exports the content of the current viewport, as it is displayed on screen without any UI overlays.
+    pub fn export_viewport(
+        &self,
+        viewport_export_prefs_override: Option<ViewportExportPrefs>,
+    ) -> oneshot::Receiver<Result<Vec<u8>, anyhow::Error>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+        let viewport_export_prefs =
+            viewport_export_prefs_override.unwrap_or(self.export_prefs.viewport_export_prefs);
+        let content = self.extract_viewport_content();
+
+        rayon::spawn(move || {
+            let result = || -> Result<Vec<u8>, anyhow::Error> {
+                let svg = content
+                    .gen_svg(
+                        viewport_export_prefs.with_background,
+                        viewport_export_prefs.with_pattern,
+                        viewport_export_prefs.optimize_printing,
+                        0.0,
+                    )?
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Generating Svg for viewport content failed, no content bounds"
+                        )
+                    })?;
+                let image_format = match viewport_export_prefs.export_format {
+                    SelectionExportFormat::Svg => {
+                        return Ok(rnote_compose::utils::add_xml_header(
+                            rnote_compose::utils::wrap_svg_root(
+                                svg.svg_data.as_str(),
+                                Some(svg.bounds),
+                                Some(svg.bounds),
+                                false,
+                            )
+                            .as_str(),
+                        )
+                        .into_bytes());
+                    }
+                    SelectionExportFormat::Png => image::ImageFormat::Png,
+                    SelectionExportFormat::Jpeg => image::ImageFormat::Jpeg,
+                };
+
+                svg.gen_image(viewport_export_prefs.bitmap_scalefactor)?
+                    .into_encoded_bytes(image_format, Some(viewport_export_prefs.jpeg_quality))
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!("Sending result to receiver failed while exporting viewport. Receiver already dropped.");
+            }
+        });
+
+        oneshot_receiver
+    }
+}
+
+/// Escape the characters that have a special meaning in Html text.
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Embed the document metadata into the Pdf surface. The title falls back to the given title when it is not set.
+fn set_pdf_surface_metadata(
+    surface: &cairo::PdfSurface,
+    metadata: &DocumentMetadata,
+    fallback_title: &str,
+) -> anyhow::Result<()> {
+    // Cairo expects dates in the ISO 8601 format
+    let iso_date = |timestamp: i64| {
+        chrono::DateTime::from_timestamp(timestamp, 0).map(|date| {
+            date.with_timezone(&chrono::Local)
+                .format("%Y-%m-%dT%H:%M:%S%:z")
+                .to_string()
+        })
+    };
+    let title = if metadata.title.is_empty() {
+        fallback_title
+    } else {
+        metadata.title.as_str()
+    };
+    let now = chrono::Utc::now().timestamp();
+    let create_date = iso_date(metadata.created.unwrap_or(now)).unwrap_or_default();
+    let mod_date = iso_date(now).unwrap_or_default();
+    let keywords = metadata.tags_joined();
+    let creator = format!("Rnote {}", crate::utils::crate_version());
+
+    let entries = [
+        (cairo::PdfMetadata::Title, title),
+        (cairo::PdfMetadata::Author, metadata.author.as_str()),
+        (cairo::PdfMetadata::Subject, metadata.subject.as_str()),
+        (cairo::PdfMetadata::Keywords, keywords.as_str()),
+        (cairo::PdfMetadata::Creator, creator.as_str()),
+        (cairo::PdfMetadata::CreateDate, create_date.as_str()),
+        (cairo::PdfMetadata::ModDate, mod_date.as_str()),
+    ];
+    for (key, value) in entries {
+        if value.is_empty() {
+            continue;
+        }
+        surface
+            .set_metadata(key, value)
+            .with_context(|| format!("Set pdf surface metadata {key:?} failed."))?;
+    }
+    Ok(())
 }
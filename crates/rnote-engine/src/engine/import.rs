@@ -1,22 +1,26 @@
 // Imports
-use super::{EngineConfig, StrokeContent};
-use crate::document::Layout;
-use crate::engine_view_mut;
+use super::{EngineConfig, EngineSnapshot, ImportJobHandle, StrokeContent};
+use crate::audioplayer::PenSoundsConfig;
+use crate::document::{Format, Layout};
+use crate::pens::typewriter::SnippetFields;
 use crate::pens::Pen;
 use crate::pens::PenStyle;
+use crate::render;
 use crate::store::chrono_comp::StrokeLayer;
 use crate::store::StrokeKey;
 use crate::strokes::{resize::calculate_resize_ratio, resize::ImageSizeOption, Resize};
 use crate::strokes::{BitmapImage, Stroke, VectorImage};
+use crate::{engine_view, engine_view_mut};
 use crate::{CloneConfig, Engine, WidgetFlags};
 use futures::channel::oneshot;
 use rnote_compose::ext::Vector2Ext;
 use rnote_compose::shapes::Shapeable;
+use rnote_compose::transform::Transformable;
+use rnote_compose::Color;
+use rnote_compose::SplitOrder;
 use serde::{Deserialize, Serialize};
-use std::ops::Range;
 use std::path::PathBuf;
 use std::time::Instant;
-use tracing::error;
 
 #[derive(
     Debug, Clone, Copy, Serialize, Deserialize, num_derive::FromPrimitive, num_derive::ToPrimitive,
@@ -88,6 +92,9 @@ pub struct PdfImportPrefs {
     /// Pdf page spacing.
     #[serde(rename = "page_spacing")]
     pub page_spacing: PdfImportPageSpacing,
+    /// The gap between the pages when they are spaced continuously.
+    #[serde(rename = "page_gap")]
+    pub page_gap: f64,
     /// Pdf pages import type.
     #[serde(rename = "pages_type")]
     pub pages_type: PdfImportPagesType,
@@ -100,6 +107,9 @@ pub struct PdfImportPrefs {
     /// Whether the document layout should be adjusted to the Pdf
     #[serde(rename = "adjust_document")]
     pub adjust_document: bool,
+    /// Remapping the colors of the imported pages
+    #[serde(rename = "color_remap")]
+    pub color_remap: ColorRemapPrefs,
 }
 
 impl Default for PdfImportPrefs {
@@ -108,13 +118,79 @@ impl Default for PdfImportPrefs {
             pages_type: PdfImportPagesType::default(),
             page_width_perc: 50.0,
             page_spacing: PdfImportPageSpacing::default(),
+            page_gap: Self::PAGE_GAP_DEFAULT,
             bitmap_scalefactor: 1.8,
             page_borders: true,
             adjust_document: false,
+            color_remap: ColorRemapPrefs::default(),
         }
     }
 }
 
+impl PdfImportPrefs {
+    pub const PAGE_GAP_DEFAULT: f64 = 16.0;
+    pub const PAGE_GAP_MAX: f64 = 1000.0;
+
+    /// The vertical offset from an imported page with the given height to the next one.
+    pub fn page_offset(&self, page_height: f64, format: &Format) -> f64 {
+        if self.adjust_document {
+            page_height
+        } else {
+            match self.page_spacing {
+                PdfImportPageSpacing::Continuous => {
+                    page_height + self.page_gap.clamp(0.0, Self::PAGE_GAP_MAX)
+                }
+                PdfImportPageSpacing::OnePerDocumentPage => format.height(),
+            }
+        }
+    }
+}
+
+/// Parses a page selection like `1-3, 5, 8-` into zero-based page indices.
+///
+/// Page numbers are one-based, open ranges extend to the first or last page.
+/// An empty selection selects all pages.
+pub fn parse_pdf_page_selection(selection: &str, n_pages: u32) -> anyhow::Result<Vec<u32>> {
+    let parse_page = |s: &str, default: u32| -> anyhow::Result<u32> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Ok(default);
+        }
+        let page = s
+            .parse::<u32>()
+            .map_err(|e| anyhow::anyhow!("Invalid page number '{s}', Err: {e:?}"))?;
+        if page == 0 || page > n_pages {
+            return Err(anyhow::anyhow!(
+                "Page number {page} is out of the range 1 - {n_pages}"
+            ));
+        }
+        Ok(page)
+    };
+
+    if selection.trim().is_empty() {
+        return Ok((0..n_pages).collect());
+    }
+    let mut pages = Vec::new();
+    for part in selection.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (parse_page(start, 1)?, parse_page(end, n_pages)?),
+            None => {
+                let page = parse_page(part, 1)?;
+                (page, page)
+            }
+        };
+        if start > end {
+            return Err(anyhow::anyhow!("Invalid page range '{part}'"));
+        }
+        pages.extend((start - 1)..end);
+    }
+    Ok(pages)
+}
+
 /// Xournal++ `.xopp` file import preferences.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename = "xopp_import_prefs")]
@@ -122,11 +198,170 @@ pub struct XoppImportPrefs {
     /// Import DPI.
     #[serde(rename = "pages_type")]
     pub dpi: f64,
+    /// Remapping the colors of the imported strokes and the background
+    #[serde(default, rename = "color_remap")]
+    pub color_remap: ColorRemapPrefs,
 }
 
 impl Default for XoppImportPrefs {
     fn default() -> Self {
-        Self { dpi: 96.0 }
+        Self {
+            dpi: 96.0,
+            color_remap: ColorRemapPrefs::default(),
+        }
+    }
+}
+
+/// Remapping the colors of imported content, so that it is readable in dark documents.
+///
+/// Near-black colors are mapped to the dark target, near-white colors to the light target
+/// and the grey tones in between are interpolated. Saturated colors are kept.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "color_remap_prefs")]
+pub struct ColorRemapPrefs {
+    /// Whether the colors are remapped
+    #[serde(rename = "enabled")]
+    pub enabled: bool,
+    /// The color that near-black colors are mapped to
+    #[serde(rename = "dark_target")]
+    pub dark_target: Color,
+    /// The color that near-white colors are mapped to
+    #[serde(rename = "light_target")]
+    pub light_target: Color,
+}
+
+impl Default for ColorRemapPrefs {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dark_target: Color::WHITE,
+            light_target: Color::new(0.14, 0.14, 0.14, 1.0),
+        }
+    }
+}
+
+impl ColorRemapPrefs {
+    /// Colors with a luma below or above one minus this threshold are mapped entirely to the targets.
+    const NEAR_THRESHOLD: f64 = 0.2;
+    /// Colors with a larger chroma are considered saturated and are not remapped.
+    const GREY_CHROMA_MAX: f64 = 0.2;
+
+    /// Remap the color, keeping its alpha.
+    pub fn remap_color(&self, color: Color) -> Color {
+        let max = color.r.max(color.g).max(color.b);
+        let min = color.r.min(color.g).min(color.b);
+        if max - min > Self::GREY_CHROMA_MAX {
+            return color;
+        }
+        let t = ((color.luma() - Self::NEAR_THRESHOLD) / (1.0 - 2.0 * Self::NEAR_THRESHOLD))
+            .clamp(0.0, 1.0);
+        let lerp = |dark: f64, light: f64| dark + (light - dark) * t;
+        Color::new(
+            lerp(self.dark_target.r, self.light_target.r),
+            lerp(self.dark_target.g, self.light_target.g),
+            lerp(self.dark_target.b, self.light_target.b),
+            color.a,
+        )
+    }
+
+    /// Remap the colors of the pixels of an image surface in the `ARgb32` format.
+    pub fn remap_image_surface(&self, surface: &mut cairo::ImageSurface) -> anyhow::Result<()> {
+        if surface.format() != cairo::Format::ARgb32 {
+            return Err(anyhow::anyhow!(
+                "Remapping colors of image surface failed, unsupported format {:?}",
+                surface.format()
+            ));
+        }
+        surface.flush();
+        {
+            let mut data = surface.data()?;
+            // The pixels are stored as native endian u32 with premultiplied alpha
+            for pixel in data.chunks_exact_mut(4) {
+                let argb = u32::from_ne_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]);
+                let alpha = argb >> 24;
+                if alpha == 0 {
+                    continue;
+                }
+                let unpremultiply = |v: u32| ((v & 0xff) as f64 / alpha as f64);
+                let remapped = self.remap_color(Color::new(
+                    unpremultiply(argb >> 16),
+                    unpremultiply(argb >> 8),
+                    unpremultiply(argb),
+                    1.0,
+                ));
+                let premultiply = |v: f64| (v * alpha as f64).round() as u32;
+                let argb = (alpha << 24)
+                    | (premultiply(remapped.r) << 16)
+                    | (premultiply(remapped.g) << 8)
+                    | premultiply(remapped.b);
+                pixel.copy_from_slice(&argb.to_ne_bytes());
+            }
+        }
+        surface.mark_dirty();
+        Ok(())
+    }
+
+    /// Remap the colors of Svg data, which is expected to be written by usvg.
+    ///
+    /// usvg writes all colors as attribute values in the `#rrggbb` notation. Embedded bitmap images are not remapped.
+    pub fn remap_svg_colors(&self, svg_data: &str) -> String {
+        const MARKER: &str = "=\"#";
+        let mut remapped = String::with_capacity(svg_data.len());
+        let mut rest = svg_data;
+        while let Some(i) = rest.find(MARKER) {
+            let (before, after) = rest.split_at(i + MARKER.len());
+            remapped.push_str(before);
+            rest = after;
+            let Some(color) = rest
+                .get(..7)
+                .and_then(|s| s.strip_suffix('"'))
+                .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            else {
+                continue;
+            };
+            let channel = |shift: u32| ((color >> shift) & 0xff) as f64 / 255.0;
+            let remapped_color =
+                self.remap_color(Color::new(channel(16), channel(8), channel(0), 1.0));
+            let to_u8 = |v: f64| (v * 255.0).round() as u8;
+            remapped.push_str(&format!(
+                "{:02x}{:02x}{:02x}",
+                to_u8(remapped_color.r),
+                to_u8(remapped_color.g),
+                to_u8(remapped_color.b)
+            ));
+            rest = &rest[6..];
+        }
+        remapped.push_str(rest);
+        remapped
+    }
+}
+
+/// Bitmap image import preferences.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "bitmap_import_prefs")]
+pub struct BitmapImportPrefs {
+    /// The maximum width or height in pixels. Larger images can be downscaled when importing them.
+    #[serde(rename = "max_pixel_size")]
+    pub max_pixel_size: u32,
+}
+
+impl Default for BitmapImportPrefs {
+    fn default() -> Self {
+        Self {
+            max_pixel_size: Self::MAX_PIXEL_SIZE_DEFAULT,
+        }
+    }
+}
+
+impl BitmapImportPrefs {
+    pub const MAX_PIXEL_SIZE_MIN: u32 = 256;
+    pub const MAX_PIXEL_SIZE_MAX: u32 = 32768;
+    pub const MAX_PIXEL_SIZE_DEFAULT: u32 = 4096;
+
+    /// Whether an image with the given pixel size exceeds the maximum size.
+    pub fn exceeds_max_pixel_size(&self, pixel_width: u32, pixel_height: u32) -> bool {
+        pixel_width.max(pixel_height) > self.max_pixel_size
     }
 }
 
@@ -140,6 +375,9 @@ pub struct ImportPrefs {
     /// Xournal++ `.xopp` file import preferences
     #[serde(rename = "xopp_import_prefs")]
     pub xopp_import_prefs: XoppImportPrefs,
+    /// Bitmap image import preferences
+    #[serde(rename = "bitmap_import_prefs")]
+    pub bitmap_import_prefs: BitmapImportPrefs,
 }
 
 impl CloneConfig for ImportPrefs {
@@ -148,6 +386,18 @@ impl CloneConfig for ImportPrefs {
     }
 }
 
+impl EngineConfig {
+    /// The pen sounds config, with the global pen sounds toggle of older configs applied to the toggles of the pens.
+    fn migrated_pen_sounds_config(&self) -> PenSoundsConfig {
+        let mut pen_sounds_config = self.pen_sounds_config.clone();
+        if let Some(pen_sounds) = self.pen_sounds {
+            pen_sounds_config.brush_sounds = pen_sounds;
+            pen_sounds_config.typewriter_sounds = pen_sounds;
+        }
+        pen_sounds_config
+    }
+}
+
 impl Engine {
     /// Loads the engine config
     pub fn load_engine_config(
@@ -157,14 +407,17 @@ impl Engine {
     ) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
 
-        self.document = engine_config.document;
+        // Configs persisted before guides were excluded from them might still contain some
+        self.document = engine_config.document.clone_config();
+        self.store
+            .set_highlighters_behind(self.document.highlighters_behind);
         self.pens_config = engine_config.pens_config;
         self.penholder = engine_config.penholder;
         self.import_prefs = engine_config.import_prefs;
         self.export_prefs = engine_config.export_prefs;
 
         // Set the pen sounds to update the audioplayer
-        self.set_pen_sounds(engine_config.pen_sounds, data_dir);
+        self.set_pen_sounds_config(engine_config.migrated_pen_sounds_config(), data_dir);
 
         self.set_optimize_epd(engine_config.optimize_epd);
 
@@ -178,6 +431,8 @@ impl Engine {
     }
 
     /// Loads the config when syncing engine state between tabs.
+    ///
+    /// The pens configuration is kept, because it belongs to the document of the tab.
     pub fn load_engine_config_sync_tab(
         &mut self,
         engine_config: EngineConfig,
@@ -185,13 +440,12 @@ impl Engine {
     ) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
 
-        self.pens_config = engine_config.pens_config;
         self.penholder = engine_config.penholder;
         self.import_prefs = engine_config.import_prefs;
         self.export_prefs = engine_config.export_prefs;
 
         // Set the pen sounds to update the audioplayer
-        self.set_pen_sounds(engine_config.pen_sounds, data_dir);
+        self.set_pen_sounds_config(engine_config.migrated_pen_sounds_config(), data_dir);
 
         self.set_optimize_epd(engine_config.optimize_epd);
 
@@ -219,14 +473,16 @@ impl Engine {
     /// Generate a vectorimage from the bytes.
     ///
     /// The bytes are expected to be from a valid UTF-8 encoded Svg string.
+    /// The generation is enqueued as import job, its handle can be used to track the progress and cancel it.
     pub fn generate_vectorimage_from_bytes(
         &self,
         pos: na::Vector2<f64>,
         bytes: Vec<u8>,
         respect_borders: bool,
-    ) -> oneshot::Receiver<anyhow::Result<VectorImage>> {
-        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<VectorImage>>();
-
+    ) -> (
+        ImportJobHandle,
+        oneshot::Receiver<anyhow::Result<VectorImage>>,
+    ) {
         let resize_struct = Resize {
             width: self.document.format.width(),
             height: self.document.format.height(),
@@ -235,38 +491,40 @@ impl Engine {
             restrain_to_viewport: true,
             respect_borders,
         };
-        rayon::spawn(move || {
-            let result = || -> anyhow::Result<VectorImage> {
-                let svg_str = String::from_utf8(bytes)?;
-
-                VectorImage::from_svg_str(
-                    &svg_str,
-                    pos,
-                    ImageSizeOption::ResizeImage(resize_struct),
-                )
-            };
+        self.import_queue.enqueue(move |_job| {
+            let svg_str = String::from_utf8(bytes)?;
 
-            if oneshot_sender.send(result()).is_err() {
-                error!(
-                    "Sending result to receiver while generating VectorImage from bytes failed. Receiver already dropped."
-                );
-            }
-        });
+            VectorImage::from_svg_str(&svg_str, pos, ImageSizeOption::ResizeImage(resize_struct))
+        })
+    }
 
-        oneshot_receiver
+    /// The pixel size of the bitmap image bytes, if it exceeds the maximum size of the bitmap import prefs.
+    ///
+    /// Only the image header is read, so this is cheap enough to be called before importing.
+    pub fn bitmapimage_bytes_oversized(&self, bytes: &[u8]) -> anyhow::Result<Option<(u32, u32)>> {
+        let (pixel_width, pixel_height) = render::Image::encoded_bytes_pixel_size(bytes)?;
+        Ok(self
+            .import_prefs
+            .bitmap_import_prefs
+            .exceeds_max_pixel_size(pixel_width, pixel_height)
+            .then_some((pixel_width, pixel_height)))
     }
 
     /// Generate a bitmapimage for the bytes.
     ///
     /// The bytes are expected to be from a valid bitmap image (Png/Jpeg).
+    /// When `downscale` is set, images exceeding the maximum size of the bitmap import prefs are downscaled to it.
+    /// The generation is enqueued as import job, its handle can be used to track the progress and cancel it.
     pub fn generate_bitmapimage_from_bytes(
         &self,
         pos: na::Vector2<f64>,
         bytes: Vec<u8>,
         respect_borders: bool,
-    ) -> oneshot::Receiver<anyhow::Result<BitmapImage>> {
-        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<BitmapImage>>();
-
+        downscale: bool,
+    ) -> (
+        ImportJobHandle,
+        oneshot::Receiver<anyhow::Result<BitmapImage>>,
+    ) {
         let resize_struct = Resize {
             width: self.document.format.width(),
             height: self.document.format.height(),
@@ -275,40 +533,46 @@ impl Engine {
             restrain_to_viewport: true,
             respect_borders,
         };
-        rayon::spawn(move || {
-            let result = || -> anyhow::Result<BitmapImage> {
-                BitmapImage::from_image_bytes(
-                    &bytes,
-                    pos,
-                    ImageSizeOption::ResizeImage(resize_struct),
-                )
+        let max_pixel_size =
+            downscale.then_some(self.import_prefs.bitmap_import_prefs.max_pixel_size);
+        self.import_queue.enqueue(move |job| {
+            let image = match max_pixel_size {
+                Some(max_pixel_size) => {
+                    render::Image::try_from_encoded_bytes_downscaled(&bytes, max_pixel_size)?
+                }
+                None => render::Image::try_from_encoded_bytes(&bytes)?,
             };
-
-            if oneshot_sender.send(result()).is_err() {
-                error!(
-                    "Sending result to receiver while generating BitmapImage from bytes failed. Receiver already dropped."
-                );
-            }
-        });
-
-        oneshot_receiver
+            // Decoding is the expensive part
+            job.set_progress(0.9);
+            job.check_cancelled()?;
+            Ok(BitmapImage::from_image(
+                image,
+                pos,
+                ImageSizeOption::ResizeImage(resize_struct),
+            ))
+        })
     }
 
     /// Generate image strokes for each page for the bytes.
     ///
     /// The bytes are expected to be from a valid Pdf.
     ///
+    /// `pages` are the zero-based indices of the pages that get imported, all pages are imported when `None`.
+    ///
     /// Note: `insert_pos` does not have an effect when the `adjust_document` import pref is set true.
+    ///
+    /// The generation is enqueued as import job that reports the progress of the rendered pages.
     #[allow(clippy::type_complexity)]
     pub fn generate_pdf_pages_from_bytes(
         &self,
         bytes: Vec<u8>,
         insert_pos: na::Vector2<f64>,
-        page_range: Option<Range<u32>>,
+        pages: Option<Vec<u32>>,
         password: Option<String>,
-    ) -> oneshot::Receiver<anyhow::Result<Vec<(Stroke, Option<StrokeLayer>)>>> {
-        let (oneshot_sender, oneshot_receiver) =
-            oneshot::channel::<anyhow::Result<Vec<(Stroke, Option<StrokeLayer>)>>>();
+    ) -> (
+        ImportJobHandle,
+        oneshot::Receiver<anyhow::Result<Vec<(Stroke, Option<StrokeLayer>)>>>,
+    ) {
         let pdf_import_prefs = self.import_prefs.pdf_import_prefs;
         let format = self.document.format;
         let insert_pos = if self.import_prefs.pdf_import_prefs.adjust_document {
@@ -317,46 +581,39 @@ impl Engine {
             insert_pos
         };
 
-        rayon::spawn(move || {
-            let result = || -> anyhow::Result<Vec<(Stroke, Option<StrokeLayer>)>> {
-                match pdf_import_prefs.pages_type {
-                    PdfImportPagesType::Bitmap => {
-                        let bitmapimages = BitmapImage::from_pdf_bytes(
-                            &bytes,
-                            pdf_import_prefs,
-                            insert_pos,
-                            page_range,
-                            &format,
-                            password,
-                        )?
-                        .into_iter()
-                        .map(|s| (Stroke::BitmapImage(s), Some(StrokeLayer::Document)))
-                        .collect::<Vec<(Stroke, Option<StrokeLayer>)>>();
-                        Ok(bitmapimages)
-                    }
-                    PdfImportPagesType::Vector => {
-                        let vectorimages = VectorImage::from_pdf_bytes(
-                            &bytes,
-                            pdf_import_prefs,
-                            insert_pos,
-                            page_range,
-                            &format,
-                            password,
-                        )?
-                        .into_iter()
-                        .map(|s| (Stroke::VectorImage(s), Some(StrokeLayer::Document)))
-                        .collect::<Vec<(Stroke, Option<StrokeLayer>)>>();
-                        Ok(vectorimages)
-                    }
+        self.import_queue
+            .enqueue(move |job| match pdf_import_prefs.pages_type {
+                PdfImportPagesType::Bitmap => {
+                    let bitmapimages = BitmapImage::from_pdf_bytes(
+                        &bytes,
+                        pdf_import_prefs,
+                        insert_pos,
+                        pages,
+                        &format,
+                        password,
+                        Some(job),
+                    )?
+                    .into_iter()
+                    .map(|s| (Stroke::BitmapImage(s), Some(StrokeLayer::Document)))
+                    .collect::<Vec<(Stroke, Option<StrokeLayer>)>>();
+                    Ok(bitmapimages)
                 }
-            };
-
-            if oneshot_sender.send(result()).is_err() {
-                error!("Sending result to receiver while importing Pdf bytes failed. Receiver already dropped");
-            }
-        });
-
-        oneshot_receiver
+                PdfImportPagesType::Vector => {
+                    let vectorimages = VectorImage::from_pdf_bytes(
+                        &bytes,
+                        pdf_import_prefs,
+                        insert_pos,
+                        pages,
+                        &format,
+                        password,
+                        Some(job),
+                    )?
+                    .into_iter()
+                    .map(|s| (Stroke::VectorImage(s), Some(StrokeLayer::Document)))
+                    .collect::<Vec<(Stroke, Option<StrokeLayer>)>>();
+                    Ok(vectorimages)
+                }
+            })
     }
 
     /// Import the generated strokes into the store.
@@ -409,6 +666,64 @@ impl Engine {
         widget_flags
     }
 
+    /// Append the strokes of a snapshot (usually loaded from another `.rnote` file) to the current document.
+    ///
+    /// The strokes are placed below the last page that has content, keeping their chronological order and layers.
+    /// The document and format of the snapshot are discarded.
+    pub fn append_snapshot(&mut self, snapshot: EngineSnapshot) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        let format_height = self.document.format.height();
+        let content_bottom = self
+            .store
+            .bounds_for_strokes(&self.store.stroke_keys_as_rendered())
+            .map(|bounds| bounds.maxs[1])
+            .unwrap_or(self.document.y);
+        let n_pages = ((content_bottom - self.document.y) / format_height)
+            .ceil()
+            .max(0.0);
+        let offset = na::vector![
+            self.document.x - snapshot.document.x,
+            self.document.y + n_pages * format_height - snapshot.document.y
+        ];
+
+        let mut strokes = snapshot
+            .stroke_components
+            .iter()
+            .map(|(key, stroke)| {
+                let chrono_comp = snapshot
+                    .chrono_components
+                    .get(key)
+                    .map(|c| **c)
+                    .unwrap_or_default();
+                (chrono_comp, stroke)
+            })
+            .collect::<Vec<_>>();
+        strokes.sort_unstable_by_key(|(chrono_comp, _)| *chrono_comp);
+        if strokes.is_empty() {
+            return widget_flags;
+        }
+
+        let all_strokes = self.store.stroke_keys_as_rendered();
+        self.store.set_selected_keys(&all_strokes, false);
+
+        for (chrono_comp, stroke) in strokes {
+            let mut stroke = (**stroke).clone();
+            stroke.translate(offset);
+            self.store.insert_stroke(stroke, Some(chrono_comp.layer));
+        }
+
+        // resize after the strokes are inserted
+        widget_flags |= self.doc_resize_to_fit_content();
+        widget_flags |= self.current_pen_update_state();
+        widget_flags |= self.store.record(Instant::now());
+        widget_flags.resize = true;
+        widget_flags.store_modified = true;
+        widget_flags.refresh_ui = true;
+
+        widget_flags
+    }
+
     /// Insert text.
     pub fn insert_text(&mut self, text: String, pos: Option<na::Vector2<f64>>) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
@@ -428,6 +743,31 @@ impl Engine {
         widget_flags
     }
 
+    /// Insert a text snippet with the typewriter, with its fields expanded.
+    ///
+    /// The page fields refer to the page where the text is inserted.
+    pub fn insert_snippet(&mut self, snippet: &str, title: String) -> WidgetFlags {
+        let text_pos = match self.penholder.current_pen_ref() {
+            Pen::Typewriter(typewriter) => typewriter.text_pos(&engine_view!(self)),
+            _ => None,
+        };
+        let pos = text_pos
+            .unwrap_or_else(|| self.camera.viewport().mins.coords + Stroke::IMPORT_OFFSET_DEFAULT);
+        let pages_bounds = self.document.pages_bounds(SplitOrder::default());
+        let page = pages_bounds
+            .iter()
+            .position(|bounds| bounds.contains_local_point(&pos.into()))
+            .unwrap_or(0);
+        let fields = SnippetFields {
+            now: chrono::Local::now(),
+            title,
+            page: page + 1,
+            n_pages: pages_bounds.len(),
+        };
+
+        self.insert_text(fields.expand(snippet), None)
+    }
+
     /// Insert the stroke content.
     ///
     /// The data usually comes from the clipboard, drag-and-drop, ..
@@ -473,3 +813,62 @@ impl Engine {
         widget_flags
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::import::parse_pdf_page_selection;
+
+    #[test]
+    fn page_selection_all() {
+        assert_eq!(parse_pdf_page_selection("", 3).unwrap(), vec![0, 1, 2]);
+        assert_eq!(parse_pdf_page_selection("  ", 3).unwrap(), vec![0, 1, 2]);
+        assert_eq!(parse_pdf_page_selection("-", 3).unwrap(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn page_selection_ranges() {
+        assert_eq!(parse_pdf_page_selection("2", 5).unwrap(), vec![1]);
+        assert_eq!(parse_pdf_page_selection("1-3", 5).unwrap(), vec![0, 1, 2]);
+        assert_eq!(
+            parse_pdf_page_selection(" 1 - 2 , 4,5 ", 5).unwrap(),
+            vec![0, 1, 3, 4]
+        );
+        assert_eq!(parse_pdf_page_selection("3-3", 5).unwrap(), vec![2]);
+        assert_eq!(parse_pdf_page_selection("1,,2,", 5).unwrap(), vec![0, 1]);
+    }
+
+    #[test]
+    fn page_selection_open_ends() {
+        assert_eq!(parse_pdf_page_selection("3-", 5).unwrap(), vec![2, 3, 4]);
+        assert_eq!(parse_pdf_page_selection("-2", 5).unwrap(), vec![0, 1]);
+        assert_eq!(
+            parse_pdf_page_selection("-2, 4-", 5).unwrap(),
+            vec![0, 1, 3, 4]
+        );
+    }
+
+    #[test]
+    fn page_selection_reversed_range() {
+        assert!(parse_pdf_page_selection("3-1", 5).is_err());
+        assert!(parse_pdf_page_selection("1-2, 5-4", 5).is_err());
+    }
+
+    #[test]
+    fn page_selection_out_of_range() {
+        assert!(parse_pdf_page_selection("0", 5).is_err());
+        assert!(parse_pdf_page_selection("6", 5).is_err());
+        assert!(parse_pdf_page_selection("4-6", 5).is_err());
+        assert!(parse_pdf_page_selection("0-", 5).is_err());
+        assert!(parse_pdf_page_selection("1", 0).is_err());
+    }
+
+    #[test]
+    fn page_selection_junk() {
+        assert!(parse_pdf_page_selection("a", 5).is_err());
+        assert!(parse_pdf_page_selection("1-b", 5).is_err());
+        assert!(parse_pdf_page_selection("1.5", 5).is_err());
+        assert!(parse_pdf_page_selection("1-2-3", 5).is_err());
+        assert!(parse_pdf_page_selection("1;2", 5).is_err());
+        assert!(parse_pdf_page_selection("-1-", 5).is_err());
+    }
+}
@@ -0,0 +1,295 @@
+// Imports
+use futures::channel::oneshot;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use tracing::error;
+
+/// The error of an import job that was cancelled before it finished.
+#[derive(Error, Debug, Clone, Copy)]
+#[error("import job was cancelled")]
+pub struct ImportCancelled;
+
+#[derive(Debug, Default)]
+struct ImportJobState {
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+    /// The bits of the progress as f64.
+    progress: AtomicU64,
+}
+
+/// A handle to a queued import job, for reporting its progress and cancelling it.
+#[derive(Debug, Clone, Default)]
+pub struct ImportJobHandle {
+    state: Arc<ImportJobState>,
+}
+
+impl ImportJobHandle {
+    /// Request cancelling the job.
+    ///
+    /// A job that is still pending is not started anymore, a running job stops at the next checkpoint.
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Whether the job has finished, either with a result, an error or because it was cancelled.
+    pub fn is_finished(&self) -> bool {
+        self.state.finished.load(Ordering::Relaxed)
+    }
+
+    /// The progress of the job, ranging from 0.0 to 1.0.
+    pub fn progress(&self) -> f64 {
+        f64::from_bits(self.state.progress.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn set_progress(&self, progress: f64) {
+        self.state
+            .progress
+            .store(progress.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+    }
+
+    /// Returns [ImportCancelled] as error when the job was requested to be cancelled.
+    ///
+    /// Intended to be called by long running jobs between their steps.
+    pub(crate) fn check_cancelled(&self) -> Result<(), ImportCancelled> {
+        if self.is_cancelled() {
+            Err(ImportCancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn set_finished(&self) {
+        self.set_progress(1.0);
+        self.state.finished.store(true, Ordering::Relaxed);
+    }
+}
+
+type ImportJob = Box<dyn FnOnce() + Send + 'static>;
+
+#[derive(Default)]
+struct ImportQueueState {
+    running: usize,
+    pending: VecDeque<ImportJob>,
+}
+
+impl std::fmt::Debug for ImportQueueState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ImportQueueState")
+            .field("running", &self.running)
+            .field("pending", &self.pending.len())
+            .finish()
+    }
+}
+
+/// A queue for import jobs, running them in the thread pool while limiting how many run at the same time.
+///
+/// Importing large files is memory intensive, so only a few jobs run concurrently and the rest is kept pending.
+#[derive(Debug, Clone, Default)]
+pub struct ImportQueue {
+    state: Arc<Mutex<ImportQueueState>>,
+}
+
+impl ImportQueue {
+    /// The maximum number of jobs that run at the same time.
+    pub const CONCURRENT_JOBS_MAX: usize = 2;
+
+    /// Enqueue a job, it is started as soon as there is a free slot.
+    ///
+    /// Returns the handle of the job and a receiver for its result.
+    /// Cancelled jobs resolve with [ImportCancelled] as error.
+    pub(crate) fn enqueue<T, F>(
+        &self,
+        job: F,
+    ) -> (ImportJobHandle, oneshot::Receiver<anyhow::Result<T>>)
+    where
+        T: Send + 'static,
+        F: FnOnce(&ImportJobHandle) -> anyhow::Result<T> + Send + 'static,
+    {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<T>>();
+        let handle = ImportJobHandle::default();
+        let queue = self.clone();
+        let job_handle = handle.clone();
+        let job: ImportJob = Box::new(move || {
+            let result = match job_handle.check_cancelled() {
+                Ok(()) => job(&job_handle),
+                Err(e) => Err(e.into()),
+            };
+            job_handle.set_finished();
+            if oneshot_sender.send(result).is_err() {
+                error!(
+                    "Sending result to receiver while running import job failed. Receiver already dropped."
+                );
+            }
+            queue.job_finished();
+        });
+
+        let start = {
+            let mut state = self.state.lock().unwrap();
+            if state.running < Self::CONCURRENT_JOBS_MAX {
+                state.running += 1;
+                Some(job)
+            } else {
+                state.pending.push_back(job);
+                None
+            }
+        };
+        if let Some(job) = start {
+            rayon::spawn(job);
+        }
+        (handle, oneshot_receiver)
+    }
+
+    fn job_finished(&self) {
+        let next = {
+            let mut state = self.state.lock().unwrap();
+            let next = state.pending.pop_front();
+            if next.is_none() {
+                state.running = state.running.saturating_sub(1);
+            }
+            next
+        };
+        if let Some(job) = next {
+            rayon::spawn(job);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::importqueue::{ImportCancelled, ImportQueue};
+    use futures::channel::oneshot;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn job_result() {
+        let queue = ImportQueue::default();
+        let (handle, receiver) = queue.enqueue(|job| {
+            job.set_progress(0.5);
+            Ok(42)
+        });
+        assert_eq!(futures::executor::block_on(receiver).unwrap().unwrap(), 42);
+        assert!(handle.is_finished());
+        assert!(!handle.is_cancelled());
+        assert_eq!(handle.progress(), 1.0);
+    }
+
+    #[test]
+    fn job_error() {
+        let queue = ImportQueue::default();
+        let (handle, receiver) = queue.enqueue::<(), _>(|_| Err(anyhow::anyhow!("import failed")));
+        let err = futures::executor::block_on(receiver).unwrap().unwrap_err();
+        assert!(!err.is::<ImportCancelled>());
+        assert!(handle.is_finished());
+    }
+
+    #[test]
+    fn progress_clamped() {
+        let queue = ImportQueue::default();
+        let (gate_sender, gate_receiver) = oneshot::channel::<()>();
+        let (handle, receiver) = queue.enqueue(move |job| {
+            job.set_progress(2.0);
+            futures::executor::block_on(gate_receiver)?;
+            Ok(())
+        });
+        while handle.progress() == 0.0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(handle.progress(), 1.0);
+        assert!(!handle.is_finished());
+        gate_sender.send(()).unwrap();
+        futures::executor::block_on(receiver).unwrap().unwrap();
+    }
+
+    #[test]
+    fn cancel_pending_job() {
+        let queue = ImportQueue::default();
+        // occupy all slots
+        let (gate_senders, running_receivers): (Vec<_>, Vec<_>) = (0
+            ..ImportQueue::CONCURRENT_JOBS_MAX)
+            .map(|_| {
+                let (gate_sender, gate_receiver) = oneshot::channel::<()>();
+                let (_, receiver) = queue.enqueue(move |_| {
+                    futures::executor::block_on(gate_receiver)?;
+                    Ok(())
+                });
+                (gate_sender, receiver)
+            })
+            .unzip();
+
+        let started = Arc::new(AtomicBool::new(false));
+        let (handle, receiver) = queue.enqueue({
+            let started = Arc::clone(&started);
+            move |_| {
+                started.store(true, Ordering::Relaxed);
+                Ok(())
+            }
+        });
+        handle.cancel();
+        assert!(handle.is_cancelled());
+        for gate_sender in gate_senders {
+            gate_sender.send(()).unwrap();
+        }
+
+        let err = futures::executor::block_on(receiver).unwrap().unwrap_err();
+        assert!(err.is::<ImportCancelled>());
+        assert!(handle.is_finished());
+        assert!(!started.load(Ordering::Relaxed));
+        for receiver in running_receivers {
+            futures::executor::block_on(receiver).unwrap().unwrap();
+        }
+    }
+
+    #[test]
+    fn cancel_running_job() {
+        let queue = ImportQueue::default();
+        let (gate_sender, gate_receiver) = oneshot::channel::<()>();
+        let (handle, receiver) = queue.enqueue(move |job| {
+            futures::executor::block_on(gate_receiver)?;
+            job.check_cancelled()?;
+            Ok(())
+        });
+        handle.cancel();
+        gate_sender.send(()).unwrap();
+
+        let err = futures::executor::block_on(receiver).unwrap().unwrap_err();
+        assert!(err.is::<ImportCancelled>());
+    }
+
+    #[test]
+    fn concurrent_jobs_limited() {
+        let queue = ImportQueue::default();
+        let running = Arc::new(AtomicUsize::new(0));
+        let running_max = Arc::new(AtomicUsize::new(0));
+
+        let receivers = (0..8)
+            .map(|i| {
+                let running = Arc::clone(&running);
+                let running_max = Arc::clone(&running_max);
+                queue
+                    .enqueue(move |_| {
+                        let n = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        running_max.fetch_max(n, Ordering::SeqCst);
+                        std::thread::sleep(Duration::from_millis(5));
+                        running.fetch_sub(1, Ordering::SeqCst);
+                        Ok(i)
+                    })
+                    .1
+            })
+            .collect::<Vec<_>>();
+
+        let results = receivers
+            .into_iter()
+            .map(|receiver| futures::executor::block_on(receiver).unwrap().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(results, (0..8).collect::<Vec<_>>());
+        assert!(running_max.load(Ordering::SeqCst) <= ImportQueue::CONCURRENT_JOBS_MAX);
+    }
+}
@@ -1,40 +1,58 @@
 // Modules
 pub mod export;
 pub mod import;
+pub mod importqueue;
+pub mod profiling;
 pub mod rendering;
+pub mod replay;
 pub mod snapshot;
 pub mod strokecontent;
+pub mod tablegen;
+pub mod textsearch;
+pub mod tikz;
 pub mod visual_debug;
+pub mod visualsettings;
 
 // Re-exports
 pub use export::ExportPrefs;
-use futures::channel::mpsc::UnboundedReceiver;
 use futures::StreamExt;
 pub use import::ImportPrefs;
+pub use importqueue::{ImportCancelled, ImportJobHandle, ImportQueue};
+pub use replay::Replay;
 pub use snapshot::EngineSnapshot;
 pub use strokecontent::StrokeContent;
+pub use textsearch::{TextMatch, TextReplaceScope};
+pub use visualsettings::VisualSettings;
 
 // Imports
-use crate::document::Layout;
-use crate::pens::{Pen, PenStyle};
+use crate::audioplayer::PenSoundsConfig;
+use crate::document::background::{PatternStyle, PatternTile};
+use crate::document::{DocumentMetadata, ExpandDirection, Format, Guide, Layout};
+use crate::pens::penmode::PenModeState;
+use crate::pens::pensconfig::toolsconfig::ToolStyle;
+use crate::pens::{Pen, PenStyle, TextFont};
 use crate::pens::{PenMode, PensConfig};
 use crate::store::render_comp::{self, RenderCompState};
-use crate::store::StrokeKey;
+use crate::store::{FlipAxis, SelectSimilar, StrokeKey, ZOrderChange};
 use crate::strokes::content::GeneratedContentImages;
-use crate::strokes::textstroke::{TextAttribute, TextStyle};
-use crate::{render, AudioPlayer, CloneConfig, SelectionCollision, WidgetFlags};
+use crate::strokes::textstroke::{TextAlignment, TextAttribute, TextStyle};
+use crate::strokes::{Stroke, TextStroke};
+use crate::{render, AudioPlayer, CloneConfig, Drawable, SelectionCollision, WidgetFlags};
 use crate::{Camera, Document, PenHolder, StrokeStore};
 use futures::channel::{mpsc, oneshot};
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::eventresult::EventPropagation;
 use rnote_compose::ext::AabbExt;
 use rnote_compose::penevent::{PenEvent, ShortcutKey};
+use rnote_compose::shapes::Shapeable;
 use rnote_compose::{Color, SplitOrder};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 /// An immutable view into the engine, excluding the penholder.
 #[derive(Debug)]
@@ -46,6 +64,8 @@ pub struct EngineView<'a> {
     pub camera: &'a Camera,
     pub audioplayer: &'a Option<AudioPlayer>,
     pub animation: &'a Animation,
+    pub righthanded: bool,
+    pub visual_settings: VisualSettings,
 }
 
 /// Constructs an `EngineView` from an identifier containing an `Engine` instance.
@@ -60,6 +80,8 @@ macro_rules! engine_view {
             camera: &$engine.camera,
             audioplayer: &$engine.audioplayer,
             animation: &$engine.animation,
+            righthanded: $engine.righthanded,
+            visual_settings: $engine.visual_settings,
         }
     };
 }
@@ -74,6 +96,8 @@ pub struct EngineViewMut<'a> {
     pub camera: &'a mut Camera,
     pub audioplayer: &'a mut Option<AudioPlayer>,
     pub animation: &'a mut Animation,
+    pub righthanded: bool,
+    pub visual_settings: VisualSettings,
 }
 
 /// Constructs an `EngineViewMut` from an identifier containing an `Engine` instance.
@@ -88,6 +112,8 @@ macro_rules! engine_view_mut {
             camera: &mut $engine.camera,
             audioplayer: &mut $engine.audioplayer,
             animation: &mut $engine.animation,
+            righthanded: $engine.righthanded,
+            visual_settings: $engine.visual_settings,
         }
     };
 }
@@ -103,6 +129,8 @@ impl EngineViewMut<'_> {
             camera: self.camera,
             audioplayer: self.audioplayer,
             animation: self.animation,
+            righthanded: self.righthanded,
+            visual_settings: self.visual_settings,
         }
     }
 }
@@ -121,6 +149,8 @@ pub enum EngineTask {
         images: GeneratedContentImages,
         /// The image scale-factor the render task was using while generating the images.
         image_scale: f64,
+        /// The rendering generation of the render task. The images are discarded when it is outdated.
+        generation: u64,
     },
     /// Appends the images to the rendering of the given stroke.
     ///
@@ -132,6 +162,13 @@ pub enum EngineTask {
         /// The generated images
         images: GeneratedContentImages,
     },
+    /// Insert the region that was traced by the fill tool as filled polygon.
+    InsertFillRegion {
+        /// The outline of the region in document coordinates.
+        outline: Vec<na::Vector2<f64>>,
+        /// The color the region is filled with.
+        fill_color: Color,
+    },
     /// Requests that the typewriter cursor should be blinked/toggled
     BlinkTypewriterCursor,
     /// Change the permanent zoom to the given value
@@ -140,6 +177,15 @@ pub enum EngineTask {
     Quit,
 }
 
+/// A trashed stroke, listed in the recently deleted strokes.
+#[derive(Debug, Clone)]
+pub struct TrashedStroke {
+    /// The stroke key.
+    pub key: StrokeKey,
+    /// When the stroke was trashed, if known.
+    pub trashed_at: Option<chrono::DateTime<chrono::Local>>,
+}
+
 /// The engine configuration. Used when loading/saving the current configuration from/into persistent application settings.
 #[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(default, rename = "engine_config")]
@@ -154,18 +200,65 @@ pub struct EngineConfig {
     import_prefs: ImportPrefs,
     #[serde(rename = "export_prefs")]
     export_prefs: ExportPrefs,
-    #[serde(rename = "pen_sounds")]
-    pen_sounds: bool,
+    /// The global pen sounds toggle of older configs, replaced by the toggles in the pen sounds config.
+    #[serde(rename = "pen_sounds", skip_serializing)]
+    pen_sounds: Option<bool>,
+    #[serde(rename = "pen_sounds_config")]
+    pen_sounds_config: PenSoundsConfig,
     #[serde(rename = "optimize_epd")]
     optimize_epd: bool,
 }
 
+/// Counters of the engine task channel, to diagnose when tasks pile up faster than they are handled.
+#[derive(Debug, Default)]
+pub struct EngineTaskStats {
+    /// Tasks that are sent, but not yet received.
+    pending: AtomicUsize,
+    /// Tasks that were dropped in favor of a newer task of the same kind.
+    coalesced: AtomicUsize,
+    /// Render tasks that were skipped because a newer one was started in the meantime.
+    superseded: AtomicUsize,
+}
+
+impl EngineTaskStats {
+    /// The number of pending tasks at which a warning is logged.
+    const PENDING_WARN_THRESHOLD: usize = 1000;
+
+    /// The tasks that are sent, but not yet received.
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// The total number of tasks that were dropped in favor of a newer task of the same kind.
+    pub fn coalesced(&self) -> usize {
+        self.coalesced.load(Ordering::Relaxed)
+    }
+
+    /// The total number of render tasks that were skipped because they were superseded.
+    pub fn superseded(&self) -> usize {
+        self.superseded.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_superseded(&self) {
+        self.superseded.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
 #[derive(Debug, Clone)]
-pub struct EngineTaskSender(mpsc::UnboundedSender<EngineTask>);
+pub struct EngineTaskSender {
+    tx: mpsc::UnboundedSender<EngineTask>,
+    stats: Arc<EngineTaskStats>,
+}
 
 impl EngineTaskSender {
     pub fn send(&self, task: EngineTask) {
-        if let Err(e) = self.0.unbounded_send(task) {
+        // Counted before sending, so that the receiver never decrements below zero
+        let pending = self.stats.pending.fetch_add(1, Ordering::Relaxed) + 1;
+        if pending == EngineTaskStats::PENDING_WARN_THRESHOLD {
+            warn!("Engine tasks are piling up, {pending} tasks are pending to be handled");
+        }
+        if let Err(e) = self.tx.unbounded_send(task) {
+            self.stats.pending.fetch_sub(1, Ordering::Relaxed);
             let err = format!("{e:?}");
             error!(
                 "Failed to send engine task {:?}, Err: {err}",
@@ -173,14 +266,56 @@ impl EngineTaskSender {
             );
         }
     }
+
+    pub fn stats(&self) -> &EngineTaskStats {
+        &self.stats
+    }
 }
 
 #[derive(Debug)]
-pub struct EngineTaskReceiver(mpsc::UnboundedReceiver<EngineTask>);
+pub struct EngineTaskReceiver {
+    rx: mpsc::UnboundedReceiver<EngineTask>,
+    stats: Arc<EngineTaskStats>,
+}
 
 impl EngineTaskReceiver {
-    pub fn recv(&mut self) -> futures::stream::Next<'_, UnboundedReceiver<EngineTask>> {
-        self.0.next()
+    pub async fn recv(&mut self) -> Option<EngineTask> {
+        let task = self.rx.next().await?;
+        self.stats.pending.fetch_sub(1, Ordering::Relaxed);
+        Some(task)
+    }
+
+    /// Wait for the next task and take all others that are already queued up along with it,
+    /// with redundant tasks coalesced.
+    ///
+    /// Of the stroke image updates only the newest for every stroke is kept, of the zoom tasks only the last.
+    /// Returns `None` when the channel is closed.
+    pub async fn recv_coalesced(&mut self) -> Option<Vec<EngineTask>> {
+        let mut tasks = vec![self.rx.next().await?];
+        while let Ok(Some(task)) = self.rx.try_next() {
+            tasks.push(task);
+        }
+        self.stats.pending.fetch_sub(tasks.len(), Ordering::Relaxed);
+
+        let n_received = tasks.len();
+        let mut updated_keys = HashSet::new();
+        let mut zoomed = false;
+        // Iterate from the back, so that the newest tasks are kept
+        let mut coalesced_tasks = tasks
+            .into_iter()
+            .rev()
+            .filter(|task| match task {
+                EngineTask::UpdateStrokeWithImages { key, .. } => updated_keys.insert(*key),
+                EngineTask::Zoom(_) => !std::mem::replace(&mut zoomed, true),
+                _ => true,
+            })
+            .collect::<Vec<EngineTask>>();
+        coalesced_tasks.reverse();
+        self.stats
+            .coalesced
+            .fetch_add(n_received - coalesced_tasks.len(), Ordering::Relaxed);
+
+        Some(coalesced_tasks)
     }
 }
 
@@ -235,10 +370,12 @@ pub struct Engine {
     pub import_prefs: ImportPrefs,
     #[serde(rename = "export_prefs")]
     pub export_prefs: ExportPrefs,
-    #[serde(rename = "pen_sounds")]
-    pen_sounds: bool,
+    #[serde(rename = "pen_sounds_config")]
+    pen_sounds_config: PenSoundsConfig,
     #[serde(rename = "optimize_epd")]
     optimize_epd: bool,
+    #[serde(rename = "metadata")]
+    pub metadata: DocumentMetadata,
 
     #[serde(skip)]
     audioplayer: Option<AudioPlayer>,
@@ -246,6 +383,19 @@ pub struct Engine {
     pub animation: Animation,
     #[serde(skip)]
     visual_debug: bool,
+    #[serde(skip)]
+    righthanded: bool,
+    #[serde(skip)]
+    visual_settings: VisualSettings,
+    #[serde(skip)]
+    presentation_mode: bool,
+    /// The pen mode state and tool style from before entering the presentation mode, restored when leaving it.
+    #[serde(skip)]
+    pens_before_presentation: Option<(PenModeState, ToolStyle)>,
+    #[serde(skip)]
+    replay: Option<Replay>,
+    #[serde(skip)]
+    import_queue: ImportQueue,
     // the task sender. Must not be modified, only cloned.
     #[serde(skip)]
     tasks_tx: EngineTaskSender,
@@ -268,6 +418,7 @@ pub struct Engine {
 impl Default for Engine {
     fn default() -> Self {
         let (tasks_tx, tasks_rx) = futures::channel::mpsc::unbounded::<EngineTask>();
+        let tasks_stats = Arc::new(EngineTaskStats::default());
 
         Self {
             document: Document::default(),
@@ -277,14 +428,27 @@ impl Default for Engine {
             penholder: PenHolder::default(),
             import_prefs: ImportPrefs::default(),
             export_prefs: ExportPrefs::default(),
-            pen_sounds: false,
+            pen_sounds_config: PenSoundsConfig::default(),
             optimize_epd: false,
+            metadata: DocumentMetadata::default(),
 
             audioplayer: None,
             animation: Animation::default(),
             visual_debug: false,
-            tasks_tx: EngineTaskSender(tasks_tx),
-            tasks_rx: Some(EngineTaskReceiver(tasks_rx)),
+            righthanded: true,
+            visual_settings: VisualSettings::default(),
+            presentation_mode: false,
+            pens_before_presentation: None,
+            replay: None,
+            import_queue: ImportQueue::default(),
+            tasks_tx: EngineTaskSender {
+                tx: tasks_tx,
+                stats: Arc::clone(&tasks_stats),
+            },
+            tasks_rx: Some(EngineTaskReceiver {
+                rx: tasks_rx,
+                stats: tasks_stats,
+            }),
             background_tile_image: None,
             #[cfg(feature = "ui")]
             background_rendernodes: Vec::default(),
@@ -297,6 +461,11 @@ impl Default for Engine {
 
 impl Engine {
     pub(crate) const STROKE_BOUNDS_INTERSECTION_TOLERANCE: f64 = 1e-3;
+    /// How far a smart page break can be moved up to the nearest gap between strokes,
+    /// as factor of the format height.
+    const SMART_PAGE_BREAK_THRESHOLD_FACTOR: f64 = 0.25;
+    /// The maximum number of trashed strokes that are listed for restoring.
+    pub const TRASHED_STROKES_MAX: usize = 100;
 
     pub fn engine_tasks_tx(&self) -> EngineTaskSender {
         self.tasks_tx.clone()
@@ -306,27 +475,38 @@ impl Engine {
         self.tasks_rx.take()
     }
 
-    /// Whether pen sounds are enabled.
-    pub fn pen_sounds(&self) -> bool {
-        self.pen_sounds
+    /// The pen sounds configuration.
+    pub fn pen_sounds_config(&self) -> &PenSoundsConfig {
+        &self.pen_sounds_config
     }
 
-    /// Enables/disables the pen sounds.
+    /// Set the pen sounds configuration.
     ///
-    /// If pen sound should be enabled, the pkg data dir must be provided.
-    pub fn set_pen_sounds(&mut self, pen_sounds: bool, pkg_data_dir: Option<PathBuf>) {
-        self.pen_sounds = pen_sounds;
-
-        if pen_sounds {
-            if let Some(pkg_data_dir) = pkg_data_dir {
-                // Only create and init a new audioplayer if it does not already exists
-                if self.audioplayer.is_none() {
-                    self.audioplayer = match AudioPlayer::new_init(pkg_data_dir) {
-                        Ok(audioplayer) => Some(audioplayer),
-                        Err(e) => {
-                            error!("Creating a new audioplayer failed while enabling pen sounds, Err: {e:?}");
-                            None
-                        }
+    /// The audioplayer only exists while any pen plays sounds. It is (re)created when pen sounds get enabled or the
+    /// sound theme changes, so the pkg data dir must be provided for it.
+    pub fn set_pen_sounds_config(
+        &mut self,
+        pen_sounds_config: PenSoundsConfig,
+        pkg_data_dir: Option<PathBuf>,
+    ) {
+        if pen_sounds_config.theme_dir != self.pen_sounds_config.theme_dir {
+            // Recreate the audioplayer with the sounds of the new theme
+            self.audioplayer.take();
+        }
+        self.pen_sounds_config = pen_sounds_config;
+
+        if self.pen_sounds_config.enabled() {
+            if let Some(audioplayer) = self.audioplayer.as_mut() {
+                audioplayer.set_config(self.pen_sounds_config.clone());
+            } else if let Some(pkg_data_dir) = pkg_data_dir {
+                self.audioplayer = match AudioPlayer::new_init(
+                    pkg_data_dir,
+                    self.pen_sounds_config.clone(),
+                ) {
+                    Ok(audioplayer) => Some(audioplayer),
+                    Err(e) => {
+                        error!("Creating a new audioplayer failed while enabling pen sounds, Err: {e:?}");
+                        None
                     }
                 }
             }
@@ -347,6 +527,9 @@ impl Engine {
         self.visual_debug
     }
 
+    /// Set visual debugging.
+    ///
+    /// The timings of the global [profiling::PROFILER] are shown as well while it is enabled.
     pub fn set_visual_debug(&mut self, visual_debug: bool) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
         self.visual_debug = visual_debug;
@@ -354,6 +537,73 @@ impl Engine {
         widget_flags
     }
 
+    pub fn righthanded(&self) -> bool {
+        self.righthanded
+    }
+
+    /// Sets the handedness, which determines on which side overlays like the selection rotate node are placed.
+    pub fn set_righthanded(&mut self, righthanded: bool) -> WidgetFlags {
+        self.righthanded = righthanded;
+        let mut widget_flags = self.current_pen_update_state();
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    pub fn visual_settings(&self) -> VisualSettings {
+        self.visual_settings
+    }
+
+    /// Sets the visual settings, which determine how overlays like selection outlines and pen indicators are drawn.
+    pub fn set_visual_settings(&mut self, visual_settings: VisualSettings) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.visual_settings = visual_settings;
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    pub fn presentation_mode(&self) -> bool {
+        self.presentation_mode
+    }
+
+    /// Enables or disables the presentation mode.
+    ///
+    /// While enabled, the selection is cleared, the laser pointer is installed and any other pen is refused,
+    /// so that the document can't be modified. The previous pens are restored when disabled again.
+    pub fn set_presentation_mode(&mut self, presentation_mode: bool) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if self.presentation_mode == presentation_mode {
+            return widget_flags;
+        }
+        if presentation_mode {
+            self.store
+                .set_selected_keys(&self.store.selection_keys_as_rendered(), false);
+            self.pens_before_presentation = Some((
+                self.penholder.pen_mode_state(),
+                self.pens_config.tools_config.style,
+            ));
+            self.pens_config.tools_config.style = ToolStyle::Laser;
+            widget_flags |= self.change_pen_style(PenStyle::Tools)
+                | self.change_pen_style_override(None)
+                | self.change_pen_mode(PenMode::Pen)
+                | self.reinstall_pen_current_style()
+                | self.update_rendering_current_viewport();
+        } else if let Some((pen_mode_state, tool_style)) = self.pens_before_presentation.take() {
+            self.pens_config.tools_config.style = tool_style;
+            self.penholder.set_pen_mode_state(pen_mode_state);
+            widget_flags |= self.reinstall_pen_current_style();
+        }
+        self.presentation_mode = presentation_mode;
+        widget_flags.refresh_ui = true;
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// Whether the laser pointer is the current pen, the only pen allowed in presentation mode.
+    fn laser_pointer_active(&self) -> bool {
+        self.penholder.current_pen_style_w_override() == PenStyle::Tools
+            && self.pens_config.tools_config.style == ToolStyle::Laser
+    }
+
     /// Takes a snapshot of the current state.
     pub fn take_snapshot(&self) -> EngineSnapshot {
         let mut store_history_entry = self.store.create_history_entry();
@@ -370,19 +620,41 @@ impl Engine {
         }
 
         EngineSnapshot {
-            document: self.document.clone_config(),
+            document: Document {
+                guides: self.document.guides.clone(),
+                ..self.document.clone_config()
+            },
             camera: self.camera.clone_config(),
             stroke_components: Arc::clone(&store_history_entry.stroke_components),
             chrono_components: Arc::clone(&store_history_entry.chrono_components),
+            lock_components: Arc::clone(&store_history_entry.lock_components),
+            link_components: Arc::clone(&store_history_entry.link_components),
             chrono_counter: store_history_entry.chrono_counter,
+            pens_config: Some(self.pens_config.clone_config()),
+            pen_style: Some(self.penholder.current_pen_style()),
+            metadata: self.metadata.clone(),
         }
     }
 
     /// Imports an engine snapshot. A save file should always be loaded with this method.
     pub fn load_snapshot(&mut self, snapshot: EngineSnapshot) -> WidgetFlags {
-        self.document = snapshot.document.clone_config();
+        self.document = Document {
+            guides: snapshot.document.guides.clone(),
+            ..snapshot.document.clone_config()
+        };
         self.camera = snapshot.camera.clone_config();
-        let mut widget_flags = self.store.import_from_snapshot(&snapshot)
+        self.metadata = snapshot.metadata.clone();
+        let mut widget_flags = WidgetFlags::default();
+        if let Some(pens_config) = &snapshot.pens_config {
+            self.pens_config = pens_config.clone_config();
+            widget_flags |= self
+                .penholder
+                .reinstall_pen_current_style(&mut engine_view_mut!(self));
+        }
+        if let Some(pen_style) = snapshot.pen_style {
+            widget_flags |= self.change_pen_style(pen_style);
+        }
+        widget_flags |= self.store.import_from_snapshot(&snapshot)
             | self.doc_resize_autoexpand()
             | self.current_pen_update_state()
             | self.background_rendering_regenerate()
@@ -405,6 +677,7 @@ impl Engine {
     /// Undo the latest changes.
     pub fn undo(&mut self, now: Instant) -> WidgetFlags {
         self.store.undo(now)
+            | self.apply_live_doc_layout()
             | self.doc_resize_autoexpand()
             | self.current_pen_update_state()
             | self.update_rendering_current_viewport()
@@ -413,11 +686,27 @@ impl Engine {
     /// Redo the latest changes.
     pub fn redo(&mut self, now: Instant) -> WidgetFlags {
         self.store.redo(now)
+            | self.apply_live_doc_layout()
             | self.doc_resize_autoexpand()
             | self.current_pen_update_state()
             | self.update_rendering_current_viewport()
     }
 
+    /// Restore the document layout recorded in the current history entry after undo/redo.
+    fn apply_live_doc_layout(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let Some(doc_layout) = self.store.live_doc_layout() else {
+            return widget_flags;
+        };
+        if self.document.apply_doc_layout(doc_layout) {
+            widget_flags |= self.doc_resize_to_fit_content()
+                | self.camera.set_offset(self.camera.offset(), &self.document);
+            widget_flags.resize = true;
+            widget_flags.refresh_ui = true;
+        }
+        widget_flags
+    }
+
     pub fn can_undo(&self) -> bool {
         self.store.can_undo()
     }
@@ -428,6 +717,7 @@ impl Engine {
 
     // Clears the entire engine.
     pub fn clear(&mut self) -> WidgetFlags {
+        self.metadata = DocumentMetadata::default();
         self.store.clear() | self.current_pen_update_state() | self.return_to_origin(None)
     }
 
@@ -461,8 +751,13 @@ impl Engine {
                 key,
                 images,
                 image_scale,
+                generation,
             } => {
-                if let Some(state) = self.store.render_comp_state(key) {
+                if self.store.render_comp_generation(key) != Some(generation) {
+                    // A newer render task was started or the rendering was invalidated in the meantime,
+                    // so the images are already outdated
+                    self.tasks_tx.stats().record_superseded();
+                } else if let Some(state) = self.store.render_comp_state(key) {
                     match state {
                         RenderCompState::Complete | RenderCompState::ForViewport(_) => {
                             // The rendering was already regenerated in the meantime,
@@ -492,17 +787,31 @@ impl Engine {
                 self.store.append_rendering_images(key, images);
                 widget_flags.redraw = true;
             }
+            EngineTask::InsertFillRegion {
+                outline,
+                fill_color,
+            } => {
+                // The document can't be modified in the meantime
+                if self.presentation_mode || self.replay.is_some() {
+                    return (widget_flags, quit);
+                }
+                widget_flags |= crate::pens::tools::insert_fill_region(
+                    &mut engine_view_mut!(self),
+                    outline,
+                    fill_color,
+                );
+            }
             EngineTask::BlinkTypewriterCursor => {
                 if let Pen::Typewriter(typewriter) = self.penholder.current_pen_mut() {
-                    typewriter.toggle_cursor_visibility();
+                    typewriter.toggle_cursor_visibility(self.visual_settings.reduced_motion);
                     widget_flags.redraw = true;
                 }
             }
             EngineTask::Zoom(zoom) => {
                 widget_flags |= self.camera.zoom_temporarily_to(1.0) | self.camera.zoom_to(zoom);
 
-                let all_strokes = self.store.stroke_keys_unordered();
-                self.store.set_rendering_dirty_for_strokes(&all_strokes);
+                self.store
+                    .set_rendering_dirty_after_zoom(self.camera.image_scale());
                 widget_flags |= self.doc_resize_autoexpand()
                     | self.background_rendering_regenerate()
                     | self.update_rendering_current_viewport();
@@ -523,6 +832,29 @@ impl Engine {
         pen_mode: Option<PenMode>,
         now: Instant,
     ) -> (EventPropagation, WidgetFlags) {
+        if self.replay.is_some() {
+            return (EventPropagation::Proceed, WidgetFlags::default());
+        }
+        if self.presentation_mode {
+            if !self.laser_pointer_active() {
+                return (EventPropagation::Proceed, WidgetFlags::default());
+            }
+            // The eraser end of a stylus must not switch away from the laser pointer
+            return self.penholder.handle_pen_event(
+                event,
+                Some(PenMode::Pen),
+                now,
+                &mut engine_view_mut!(self),
+            );
+        }
+        if let (Some(audioplayer), PenEvent::Down { element, .. } | PenEvent::Up { element, .. }) =
+            (self.audioplayer.as_mut(), &event)
+        {
+            // Spatial pen sounds follow the horizontal position of the pen in the viewport
+            let viewport = self.camera.viewport();
+            audioplayer
+                .set_pan((element.pos[0] - viewport.center()[0]) / (viewport.extents()[0] * 0.5));
+        }
         self.penholder
             .handle_pen_event(event, pen_mode, now, &mut engine_view_mut!(self))
     }
@@ -533,27 +865,58 @@ impl Engine {
         shortcut_key: ShortcutKey,
         now: Instant,
     ) -> (EventPropagation, WidgetFlags) {
+        if self.presentation_mode || self.replay.is_some() {
+            return (EventPropagation::Proceed, WidgetFlags::default());
+        }
         self.penholder
             .handle_pressed_shortcut_key(shortcut_key, now, &mut engine_view_mut!(self))
     }
 
+    /// Handle a released shortcut key.
+    pub fn handle_released_shortcut_key(
+        &mut self,
+        shortcut_key: ShortcutKey,
+        now: Instant,
+    ) -> (EventPropagation, WidgetFlags) {
+        self.penholder
+            .handle_released_shortcut_key(shortcut_key, now)
+    }
+
     /// Change the pen style.
     pub fn change_pen_style(&mut self, new_style: PenStyle) -> WidgetFlags {
+        if self.presentation_mode && new_style != PenStyle::Tools {
+            return WidgetFlags::default();
+        }
         self.penholder
             .change_style(new_style, &mut engine_view_mut!(self))
     }
 
+    /// Change back to the previously used pen style.
+    pub fn change_to_previous_pen_style(&mut self) -> WidgetFlags {
+        if self.presentation_mode {
+            return WidgetFlags::default();
+        }
+        self.penholder
+            .change_to_previous_style(&mut engine_view_mut!(self))
+    }
+
     /// Change the pen style (temporary) override.
     pub fn change_pen_style_override(
         &mut self,
         new_style_override: Option<PenStyle>,
     ) -> WidgetFlags {
+        if self.presentation_mode && new_style_override.is_some() {
+            return WidgetFlags::default();
+        }
         self.penholder
             .change_style_override(new_style_override, &mut engine_view_mut!(self))
     }
 
     /// Change the pen mode. Relevant for stylus input.
     pub fn change_pen_mode(&mut self, pen_mode: PenMode) -> WidgetFlags {
+        if self.presentation_mode && pen_mode != PenMode::Pen {
+            return WidgetFlags::default();
+        }
         self.penholder
             .change_pen_mode(pen_mode, &mut engine_view_mut!(self))
     }
@@ -564,6 +927,94 @@ impl Engine {
             .reinstall_pen_current_style(&mut engine_view_mut!(self))
     }
 
+    /// Increase or decrease the stroke width of the current pen by a step.
+    ///
+    /// Returns the new stroke width, or None if the current pen has no stroke width.
+    pub fn step_current_pen_stroke_width(&mut self, increase: bool) -> (Option<f64>, WidgetFlags) {
+        /// The factor the stroke width is multiplied or divided by in one step.
+        const STROKE_WIDTH_STEP_FACTOR: f64 = 1.2;
+        let mut widget_flags = WidgetFlags::default();
+        if self.presentation_mode {
+            return (None, widget_flags);
+        }
+        let pen_style = self.penholder.current_pen_style_w_override();
+        let Some(stroke_width) = self.pens_config.stroke_width(pen_style) else {
+            return (None, widget_flags);
+        };
+        let new_stroke_width = if increase {
+            stroke_width * STROKE_WIDTH_STEP_FACTOR
+        } else {
+            stroke_width / STROKE_WIDTH_STEP_FACTOR
+        };
+        let new_stroke_width = self
+            .pens_config
+            .set_stroke_width(pen_style, new_stroke_width);
+        widget_flags.refresh_ui = true;
+        widget_flags.redraw = true;
+        (new_stroke_width, widget_flags)
+    }
+
+    /// Set the stroke width of the current pen to its width preset at the given index.
+    ///
+    /// Returns the new stroke width, or None if the current pen has no stroke width.
+    pub fn apply_current_pen_width_preset(&mut self, index: usize) -> (Option<f64>, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+        if self.presentation_mode {
+            return (None, widget_flags);
+        }
+        let pen_style = self.penholder.current_pen_style_w_override();
+        let Some(preset_width) = self
+            .pens_config
+            .width_presets(pen_style)
+            .and_then(|presets| presets.get(index).copied())
+        else {
+            return (None, widget_flags);
+        };
+        let new_stroke_width = self.pens_config.set_stroke_width(pen_style, preset_width);
+        widget_flags.refresh_ui = true;
+        widget_flags.redraw = true;
+        (new_stroke_width, widget_flags)
+    }
+
+    /// Store the configuration of the current pen as a new preset.
+    ///
+    /// Returns false if the current pen can't be stored as preset or the maximum number of presets is reached.
+    pub fn add_pen_preset(&mut self, name: String) -> bool {
+        if self.pens_config.presets.len() >= PensConfig::PRESETS_MAX {
+            return false;
+        }
+        let pen_style = self.penholder.current_pen_style_w_override();
+        let Some(preset) = self.pens_config.preset_for_pen_style(pen_style, name) else {
+            return false;
+        };
+        self.pens_config.presets.push(preset);
+        true
+    }
+
+    /// Remove the preset at the given index.
+    pub fn remove_pen_preset(&mut self, index: usize) {
+        if index < self.pens_config.presets.len() {
+            self.pens_config.presets.remove(index);
+        }
+    }
+
+    /// Apply the preset at the given index and switch to its pen.
+    pub fn apply_pen_preset(&mut self, index: usize) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if self.presentation_mode {
+            return widget_flags;
+        }
+        let Some(preset) = self.pens_config.presets.get(index).cloned() else {
+            return widget_flags;
+        };
+        self.pens_config.apply_preset(&preset);
+        widget_flags |= self.change_pen_style(preset.pen_style())
+            | self.change_pen_style_override(None)
+            | self.reinstall_pen_current_style();
+        widget_flags.refresh_ui = true;
+        widget_flags
+    }
+
     /// Set the engine active or inactive.
     pub fn set_active(&mut self, active: bool) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
@@ -579,8 +1030,13 @@ impl Engine {
     }
 
     /// Generate bounds for each page on the document which contains content.
+    ///
+    /// In the whiteboard layout this is the single page that fits the content.
     pub fn pages_bounds_w_content(&self, split_order: SplitOrder) -> Vec<Aabb> {
         let doc_bounds = self.document.bounds();
+        if self.document.layout == Layout::Whiteboard {
+            return vec![doc_bounds];
+        }
         let keys = self.store.stroke_keys_as_rendered();
 
         let strokes_bounds = self.store.strokes_bounds(&keys);
@@ -610,6 +1066,110 @@ impl Engine {
         }
     }
 
+    /// Generate bounds for each page on the document which contains content, like [Self::pages_bounds_w_content],
+    /// but with the page breaks moved up to the nearest gap between strokes, so that strokes are not cut through.
+    ///
+    /// Pages are never taller than the format, when there is no gap within the threshold the break is kept.
+    /// For the fixed-size and whiteboard layouts the regular pages are returned.
+    pub fn pages_bounds_w_smart_breaks(&self, split_order: SplitOrder) -> Vec<Aabb> {
+        let regular_pages_bounds = self.pages_bounds_w_content(split_order);
+        if matches!(self.document.layout, Layout::FixedSize | Layout::Whiteboard) {
+            return regular_pages_bounds;
+        }
+        let format_size = self.document.format.size();
+        let threshold = format_size[1] * Self::SMART_PAGE_BREAK_THRESHOLD_FACTOR;
+        // Query the key tree, so that only the strokes near the page breaks are visited
+        let strokes_bounds_intersecting = |bounds: Aabb| {
+            self.store
+                .strokes_bounds(&self.store.stroke_keys_unordered_intersecting_bounds(bounds))
+                .into_iter()
+                .filter(move |stroke_bounds| {
+                    stroke_bounds
+                        .intersects_w_tolerance(&bounds, Self::STROKE_BOUNDS_INTERSECTION_TOLERANCE)
+                })
+        };
+
+        // The columns of pages with content, as x position and vertical extent.
+        let mut columns: Vec<(f64, f64, f64)> = Vec::new();
+        for page_bounds in regular_pages_bounds {
+            match columns
+                .iter_mut()
+                .find(|(x, ..)| approx::relative_eq!(*x, page_bounds.mins[0]))
+            {
+                Some((_, top, bottom)) => {
+                    *top = top.min(page_bounds.mins[1]);
+                    *bottom = bottom.max(page_bounds.maxs[1]);
+                }
+                None => columns.push((
+                    page_bounds.mins[0],
+                    page_bounds.mins[1],
+                    page_bounds.maxs[1],
+                )),
+            }
+        }
+        columns.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut pages_bounds = Vec::new();
+        for (x, top, bottom) in columns {
+            // The vertical extents of the strokes in the column between the given y positions
+            let strokes_extents = |from_y: f64, to_y: f64| {
+                self.store
+                    .strokes_bounds(&self.store.stroke_keys_unordered_intersecting_bounds(
+                        Aabb::new(na::point![x, from_y], na::point![x + format_size[0], to_y]),
+                    ))
+                    .into_iter()
+                    .filter(move |bounds| {
+                        bounds.maxs[0].min(x + format_size[0]) - bounds.mins[0].max(x)
+                            > Self::STROKE_BOUNDS_INTERSECTION_TOLERANCE
+                    })
+                    .map(|bounds| (bounds.mins[1], bounds.maxs[1]))
+            };
+            let cuts_through_stroke =
+                |y: f64| strokes_extents(y, y).any(|(min, max)| min < y && y < max);
+
+            let mut page_top = top;
+            while page_top < bottom {
+                let nominal_break = page_top + format_size[1];
+                let page_break = if nominal_break >= bottom || !cuts_through_stroke(nominal_break) {
+                    nominal_break
+                } else {
+                    // The nearest gap is above the top edge of one of the strokes
+                    strokes_extents(nominal_break - threshold, nominal_break)
+                        .map(|(min, _)| min)
+                        .filter(|y| {
+                            *y > page_top && *y >= nominal_break - threshold && *y < nominal_break
+                        })
+                        .filter(|y| !cuts_through_stroke(*y))
+                        .max_by(|a, b| a.total_cmp(b))
+                        .unwrap_or(nominal_break)
+                };
+                let page_bounds = Aabb::new(
+                    na::point![x, page_top],
+                    na::point![x + format_size[0], page_break],
+                );
+                // Filter the pages out that don't intersect with any stroke
+                if strokes_bounds_intersecting(page_bounds).next().is_some() {
+                    pages_bounds.push(page_bounds);
+                }
+                page_top = page_break;
+            }
+        }
+
+        if split_order == SplitOrder::RowMajor {
+            // Page breaks differ between columns, so the pages are ordered by their top edge
+            pages_bounds.sort_by(|a, b| {
+                a.mins[1]
+                    .total_cmp(&b.mins[1])
+                    .then(a.mins[0].total_cmp(&b.mins[0]))
+            });
+        }
+        if pages_bounds.is_empty() {
+            vec![Aabb::new(na::point![0.0, 0.0], format_size.into())]
+        } else {
+            pages_bounds
+        }
+    }
+
     /// Generates bounds which contain all pages on the doc with content, extended to fit the current format.
     pub fn bounds_w_content_extended(&self) -> Option<Aabb> {
         let pages_bounds = self.pages_bounds_w_content(SplitOrder::default());
@@ -632,6 +1192,120 @@ impl Engine {
         self.camera.zoom_w_timeout(zoom, self.tasks_tx.clone())
     }
 
+    /// Zoom and center the viewport to fit the given bounds.
+    ///
+    /// `bounds` must be in document coordinate space.
+    pub fn zoom_to_bounds(&mut self, bounds: Aabb) -> WidgetFlags {
+        let new_zoom = self
+            .camera
+            .zoom_to_fit_bounds(bounds, Camera::ZOOM_FIT_MARGIN);
+        self.zoom_w_timeout(new_zoom) | self.camera.set_viewport_center(bounds.center().coords)
+    }
+
+    /// Zoom to the given value, keeping the current viewport center.
+    pub fn zoom_keep_center(&mut self, zoom: f64) -> WidgetFlags {
+        let viewport_center = self.camera.viewport_center();
+        self.zoom_w_timeout(zoom) | self.camera.set_viewport_center(viewport_center)
+    }
+
+    /// Zoom to fit the page width into the viewport, keeping the current viewport center.
+    ///
+    /// Fits the width of both pages in the two-page spread.
+    pub fn zoom_fit_page_width(&mut self) -> WidgetFlags {
+        let new_zoom = self
+            .camera
+            .zoom_to_fit_page_width(self.document.spread_width());
+        self.zoom_keep_center(new_zoom)
+    }
+
+    /// Zoom to fit the page height into the viewport and center the current page.
+    pub fn zoom_fit_page_height(&mut self) -> WidgetFlags {
+        let pages = self.document.pages_bounds(SplitOrder::default());
+        let new_zoom = self
+            .camera
+            .zoom_to_fit_page_height(self.document.format.height());
+        let new_center = match pages.get(self.current_page_index(&pages)) {
+            Some(page) => page.center().coords,
+            None => self.camera.viewport_center(),
+        };
+        self.zoom_w_timeout(new_zoom) | self.camera.set_viewport_center(new_center)
+    }
+
+    /// Zoom and center the viewport to fit the whole current page.
+    pub fn zoom_fit_page(&mut self) -> WidgetFlags {
+        let pages = self.document.pages_bounds(SplitOrder::default());
+        let Some(page) = pages.get(self.current_page_index(&pages)).copied() else {
+            return WidgetFlags::default();
+        };
+        self.zoom_to_bounds(page)
+    }
+
+    /// The bounds of the document pages, in the order they are numbered.
+    pub fn pages_bounds(&self) -> Vec<Aabb> {
+        self.document.pages_bounds(SplitOrder::default())
+    }
+
+    /// The index of the page that is currently viewed, the one containing the center of the viewport.
+    pub fn current_page(&self) -> usize {
+        self.current_page_index(&self.pages_bounds())
+    }
+
+    /// Move the viewport to the top of the page at the index, keeping the current zoom.
+    ///
+    /// The page is centered horizontally.
+    pub fn go_to_page(&mut self, index: usize) -> WidgetFlags {
+        let Some(page) = self.pages_bounds().get(index).copied() else {
+            return WidgetFlags::default();
+        };
+        self.camera_set_offset_expand(self.camera.offset_for_page_top(page))
+    }
+
+    /// The index of the page containing the center of the viewport, falling back to the first page.
+    fn current_page_index(&self, pages: &[Aabb]) -> usize {
+        let center = self.camera.viewport_center();
+        pages
+            .iter()
+            .position(|page| page.contains_local_point(&center.into()))
+            .unwrap_or(0)
+    }
+
+    /// Flip to the next or previous page and fit it into the viewport.
+    ///
+    /// The current page is the one containing the center of the viewport.
+    /// In the two-page spread, whole spreads are flipped.
+    pub fn flip_page(&mut self, forward: bool) -> WidgetFlags {
+        let pages = self.document.spreads_bounds();
+        if pages.is_empty() {
+            return WidgetFlags::default();
+        }
+        let current = self.current_page_index(&pages);
+        let target = if forward {
+            (current + 1).min(pages.len() - 1)
+        } else {
+            current.saturating_sub(1)
+        };
+        self.zoom_to_bounds(pages[target])
+    }
+
+    /// Zoom and center the viewport to fit the current selection.
+    pub fn zoom_to_selection(&mut self) -> WidgetFlags {
+        let Some(bounds) = self.store.selection_bounds() else {
+            return WidgetFlags::default();
+        };
+        self.zoom_to_bounds(bounds)
+    }
+
+    /// Zoom and center the viewport to fit all strokes.
+    pub fn zoom_to_fit_strokes(&mut self) -> WidgetFlags {
+        let Some(bounds) = self
+            .store
+            .bounds_for_strokes(&self.store.stroke_keys_as_rendered())
+        else {
+            return WidgetFlags::default();
+        };
+        self.zoom_to_bounds(bounds)
+    }
+
     pub fn set_scale_factor(&mut self, scale_factor: f64) -> WidgetFlags {
         self.store
             .set_rendering_dirty_for_strokes(&self.store.stroke_keys_as_rendered());
@@ -652,9 +1326,9 @@ impl Engine {
     pub fn return_to_origin(&mut self, parent_width: Option<f64>) -> WidgetFlags {
         let zoom = self.camera.zoom();
         let new_offset = if let Some(parent_width) = parent_width {
-            if self.document.format.width() * zoom <= parent_width {
+            if self.document.spread_width() * zoom <= parent_width {
                 na::vector![
-                    (self.document.format.width() * 0.5 * zoom) - parent_width * 0.5,
+                    (self.document.spread_width() * 0.5 * zoom) - parent_width * 0.5,
                     -Document::SHADOW_WIDTH * zoom
                 ]
             } else {
@@ -776,13 +1450,173 @@ impl Engine {
 
     pub fn set_doc_layout(&mut self, layout: Layout) -> WidgetFlags {
         if self.document.layout != layout {
+            let mut widget_flags = WidgetFlags::default();
+            if layout != Layout::FixedSize {
+                // The two-page spread is only available in the fixed size layout
+                widget_flags |= self.set_doc_two_page_spread(false);
+            }
             self.document.layout = layout;
-            self.doc_resize_to_fit_content()
+            widget_flags | self.doc_resize_to_fit_content()
         } else {
             self.doc_resize_autoexpand()
         }
     }
 
+    /// Lay out the pages two-up side by side like the spreads of a book, or below each other again.
+    ///
+    /// Only available in the fixed size layout. The strokes are moved together with the page they are on.
+    pub fn set_doc_two_page_spread(&mut self, two_page_spread: bool) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if self.document.two_page_spread == two_page_spread
+            || self.document.layout != Layout::FixedSize
+        {
+            return widget_flags;
+        }
+        let page_size = self.document.format.size();
+        if page_size[0] <= 0.0 || page_size[1] <= 0.0 {
+            return widget_flags;
+        }
+        let prev_doc_layout = self.document.doc_layout();
+        let prev_pages_per_row = self.document.pages_per_row() as usize;
+        self.document.two_page_spread = two_page_spread;
+        let pages_per_row = self.document.pages_per_row() as usize;
+
+        let page_origin = |index: usize, pages_per_row: usize| {
+            na::vector![
+                (index % pages_per_row) as f64 * page_size[0],
+                (index / pages_per_row) as f64 * page_size[1]
+            ]
+        };
+        for key in self.store.stroke_keys_unordered() {
+            let Some(center) = self
+                .store
+                .get_stroke_ref(key)
+                .map(|stroke| stroke.bounds().center().coords)
+            else {
+                continue;
+            };
+            let column =
+                ((center[0] / page_size[0]).floor().max(0.0) as usize).min(prev_pages_per_row - 1);
+            let row = (center[1] / page_size[1]).floor().max(0.0) as usize;
+            let index = row * prev_pages_per_row + column;
+            let offset = page_origin(index, pages_per_row) - page_origin(index, prev_pages_per_row);
+            if offset != na::Vector2::zeros() {
+                self.store.translate_strokes(&[key], offset);
+                self.store.translate_strokes_images(&[key], offset);
+            }
+        }
+        self.store
+            .set_rendering_dirty_for_strokes(&self.store.stroke_keys_as_rendered());
+
+        let doc_layout = self.document.doc_layout();
+        widget_flags |= self.doc_resize_to_fit_content()
+            | self.camera.set_offset(self.camera.offset(), &self.document)
+            | self
+                .store
+                .record_w_doc_layout(Instant::now(), prev_doc_layout, doc_layout);
+        widget_flags.resize = true;
+        widget_flags.refresh_ui = true;
+        widget_flags
+    }
+
+    /// Set the directions the document grows in when in the infinite layout.
+    pub fn set_doc_infinite_expand_direction(
+        &mut self,
+        expand_direction: ExpandDirection,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if self.document.infinite_expand_direction == expand_direction {
+            return widget_flags;
+        }
+        self.document.infinite_expand_direction = expand_direction;
+        widget_flags |= self.doc_resize_to_fit_content();
+        widget_flags |= self.camera.set_offset(self.camera.offset(), &self.document);
+        // The expand direction is saved in the document
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Set the tile of the custom background pattern and switch to it.
+    ///
+    /// The pattern size is set to the intrinsic size of the tile.
+    pub fn set_background_pattern_tile(
+        &mut self,
+        pattern_tile: PatternTile,
+    ) -> anyhow::Result<WidgetFlags> {
+        let pattern_size = pattern_tile.intrinsic_size()?;
+        if pattern_size[0] < 1.0 || pattern_size[1] < 1.0 {
+            return Err(anyhow::anyhow!(
+                "Pattern tile has invalid size {pattern_size:?}."
+            ));
+        }
+        self.document.background.pattern_tile = Some(pattern_tile);
+        self.document.background.pattern = PatternStyle::CustomTile;
+        self.document.background.pattern_size = pattern_size;
+        Ok(self.background_rendering_regenerate())
+    }
+
+    /// Set whether panning is restricted to the document bounds in fixed layouts.
+    pub fn set_doc_clamp_panning(&mut self, clamp_panning: bool) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.document.clamp_panning = clamp_panning;
+        widget_flags |= self.camera.set_offset(self.camera.offset(), &self.document);
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// Set whether highlighter strokes are always drawn beneath all other strokes.
+    pub fn set_highlighters_behind(&mut self, highlighters_behind: bool) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.document.highlighters_behind = highlighters_behind;
+        self.store.set_highlighters_behind(highlighters_behind);
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// Add a guide line to the document.
+    ///
+    /// Returns the index of the new guide.
+    pub fn add_guide(&mut self, guide: Guide) -> (usize, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+        self.document.guides.push(guide);
+        widget_flags.redraw = true;
+        widget_flags.store_modified = true;
+        (self.document.guides.len() - 1, widget_flags)
+    }
+
+    /// Move the guide line at the given index to a new position.
+    pub fn move_guide(&mut self, index: usize, pos: f64) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if let Some(guide) = self.document.guides.get_mut(index) {
+            guide.pos = pos;
+            widget_flags.redraw = true;
+            widget_flags.store_modified = true;
+        }
+        widget_flags
+    }
+
+    /// Remove the guide line at the given index.
+    pub fn remove_guide(&mut self, index: usize) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if index < self.document.guides.len() {
+            self.document.guides.remove(index);
+            widget_flags.redraw = true;
+            widget_flags.store_modified = true;
+        }
+        widget_flags
+    }
+
+    /// Remove all guide lines.
+    pub fn clear_guides(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if !self.document.guides.is_empty() {
+            self.document.guides.clear();
+            widget_flags.redraw = true;
+            widget_flags.store_modified = true;
+        }
+        widget_flags
+    }
+
     pub fn select_all_strokes(&mut self) -> WidgetFlags {
         let widget_flags = self.change_pen_style(PenStyle::Selector);
         self.store
@@ -794,6 +1628,67 @@ impl Engine {
             | self.update_rendering_current_viewport()
     }
 
+    /// Select all strokes intersecting the page at the given position.
+    pub fn select_all_strokes_on_page(&mut self, pos: na::Vector2<f64>) -> WidgetFlags {
+        let Some(page_bounds) = self
+            .document
+            .pages_bounds(SplitOrder::default())
+            .into_iter()
+            .find(|bounds| bounds.contains_local_point(&pos.into()))
+        else {
+            return WidgetFlags::default();
+        };
+        let widget_flags = self.change_pen_style(PenStyle::Selector);
+        self.store.set_selected_keys(
+            &self
+                .store
+                .stroke_keys_as_rendered_intersecting_bounds(page_bounds),
+            true,
+        );
+        widget_flags
+            | self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport()
+    }
+
+    /// Expand the selection to all strokes that are similar to the selected strokes.
+    pub fn select_similar(&mut self, similar: SelectSimilar) -> WidgetFlags {
+        let selection_keys = self.store.selection_keys_as_rendered();
+        if selection_keys.is_empty() {
+            return WidgetFlags::default();
+        }
+        let similar_keys = match similar {
+            SelectSimilar::Color => self
+                .store
+                .stroke_keys_as_rendered_w_same_color(&selection_keys),
+            SelectSimilar::PenKind => self
+                .store
+                .stroke_keys_as_rendered_w_same_pen_kind(&selection_keys),
+            SelectSimilar::Page => {
+                let selection_strokes_bounds = self.store.strokes_bounds(&selection_keys);
+                self.document
+                    .pages_bounds(SplitOrder::default())
+                    .into_iter()
+                    .filter(|page_bounds| {
+                        selection_strokes_bounds
+                            .iter()
+                            .any(|bounds| page_bounds.intersects(bounds))
+                    })
+                    .flat_map(|page_bounds| {
+                        self.store
+                            .stroke_keys_as_rendered_intersecting_bounds(page_bounds)
+                    })
+                    .collect()
+            }
+        };
+        self.store.set_selected_keys(&similar_keys, true);
+        self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport()
+    }
+
     pub fn deselect_all_strokes(&mut self) -> WidgetFlags {
         let widget_flags = self.change_pen_style(PenStyle::Selector);
         self.store
@@ -840,10 +1735,237 @@ impl Engine {
             | self.update_rendering_current_viewport()
     }
 
+    /// The trashed strokes that can still be restored, the most recently trashed first.
+    ///
+    /// Capped at [Engine::TRASHED_STROKES_MAX] strokes.
+    pub fn trashed_strokes(&self) -> Vec<TrashedStroke> {
+        self.store
+            .trashed_keys_sorted_recent()
+            .into_iter()
+            .take(Self::TRASHED_STROKES_MAX)
+            .map(|key| TrashedStroke {
+                key,
+                trashed_at: self.store.trashed_at(key),
+            })
+            .collect()
+    }
+
+    /// Generate thumbnails for the given trashed strokes in a background thread.
+    ///
+    /// The thumbnails are generated to fit into a square of `thumbnail_size`.
+    pub fn gen_trashed_strokes_thumbnails(
+        &self,
+        keys: &[StrokeKey],
+        thumbnail_size: f64,
+    ) -> oneshot::Receiver<Vec<(StrokeKey, render::Image)>> {
+        let (oneshot_sender, oneshot_receiver) =
+            oneshot::channel::<Vec<(StrokeKey, render::Image)>>();
+        let strokes = keys
+            .iter()
+            .filter_map(|&key| Some((key, self.store.get_stroke_arc(key)?)))
+            .collect::<Vec<(StrokeKey, Arc<Stroke>)>>();
+
+        rayon::spawn(move || {
+            let thumbnails = strokes
+                .into_iter()
+                .filter_map(|(key, stroke)| {
+                    let bounds = stroke.bounds();
+                    let max_extent = bounds.extents().max();
+                    if max_extent <= 0.0 {
+                        return None;
+                    }
+                    match render::Image::gen_with_piet(
+                        |piet_cx| stroke.draw(piet_cx, 1.0),
+                        bounds,
+                        thumbnail_size / max_extent,
+                    ) {
+                        Ok(image) => Some((key, image)),
+                        Err(e) => {
+                            error!("Generating thumbnail for trashed stroke failed, Err: {e:?}");
+                            None
+                        }
+                    }
+                })
+                .collect();
+            if oneshot_sender.send(thumbnails).is_err() {
+                error!("Sending trashed strokes thumbnails to receiver failed. Receiver already dropped.");
+            }
+        });
+
+        oneshot_receiver
+    }
+
+    /// Restore the given trashed strokes.
+    pub fn restore_trashed_strokes(&mut self, keys: &[StrokeKey]) -> WidgetFlags {
+        let keys = keys
+            .iter()
+            .copied()
+            .filter(|&key| self.store.trashed(key).unwrap_or(false))
+            .collect::<Vec<StrokeKey>>();
+        if keys.is_empty() {
+            return WidgetFlags::default();
+        }
+        self.store.set_trashed_keys(&keys, false);
+        let mut widget_flags = self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Attach a caption with the given text below the selected image.
+    ///
+    /// Only possible when the selection consists of a single image. The caption stays attached when the image is
+    /// moved or resized and gets selected instead of the image.
+    pub fn add_caption_to_selected_image(&mut self, text: String) -> WidgetFlags {
+        let selection_keys = self.store.selection_keys_as_rendered();
+        let [image_key] = selection_keys.as_slice() else {
+            return WidgetFlags::default();
+        };
+        let image_key = *image_key;
+        let Some(image_bounds) = self
+            .store
+            .get_stroke_ref(image_key)
+            .filter(|stroke| matches!(stroke, Stroke::BitmapImage(_) | Stroke::VectorImage(_)))
+            .map(|stroke| stroke.bounds())
+        else {
+            return WidgetFlags::default();
+        };
+
+        let mut text_style = self.pens_config.typewriter_config.text_style.clone();
+        text_style.alignment = TextAlignment::Center;
+        let caption = TextStroke::new(
+            text,
+            na::vector![
+                image_bounds.mins[0],
+                image_bounds.maxs[1] + StrokeStore::CAPTION_GAP
+            ],
+            text_style,
+        );
+        let caption_key = self.store.insert_stroke(Stroke::TextStroke(caption), None);
+        self.store.set_caption_of(caption_key, Some(image_key));
+        self.store.update_geometry_for_stroke(image_key);
+        self.store.set_selected(image_key, false);
+        self.store.set_selected(caption_key, true);
+
+        let mut widget_flags = self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_content_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Let the selected text flow along the selected brush or shape stroke.
+    ///
+    /// Only possible when exactly one text and one path stroke are selected. Otherwise the selected text strokes that
+    /// are flowing along paths are detached from them.
+    pub fn toggle_selected_text_along_path(&mut self) -> WidgetFlags {
+        let selection_keys = self.store.selection_keys_as_rendered();
+        let (text_keys, path_keys): (Vec<StrokeKey>, Vec<StrokeKey>) = selection_keys
+            .iter()
+            .copied()
+            .filter(|&key| {
+                matches!(
+                    self.store.get_stroke_ref(key),
+                    Some(Stroke::TextStroke(_) | Stroke::BrushStroke(_) | Stroke::ShapeStroke(_))
+                )
+            })
+            .partition(|&key| {
+                matches!(self.store.get_stroke_ref(key), Some(Stroke::TextStroke(_)))
+            });
+
+        let modified_keys = match (text_keys.as_slice(), path_keys.as_slice()) {
+            ([text_key], [path_key]) => {
+                let Some(bez_path) = self
+                    .store
+                    .get_stroke_ref(*path_key)
+                    .map(|stroke| stroke.outline_path())
+                else {
+                    return WidgetFlags::default();
+                };
+                let Some(Stroke::TextStroke(textstroke)) = self.store.get_stroke_mut(*text_key)
+                else {
+                    return WidgetFlags::default();
+                };
+                if !textstroke.attach_to_path(&bez_path) {
+                    return WidgetFlags::default();
+                }
+                vec![*text_key]
+            }
+            _ => {
+                let mut detached_keys = vec![];
+                for key in text_keys {
+                    if let Some(Stroke::TextStroke(textstroke)) = self.store.get_stroke_mut(key) {
+                        if textstroke.path.is_some() {
+                            textstroke.detach_from_path();
+                            detached_keys.push(key);
+                        }
+                    }
+                }
+                detached_keys
+            }
+        };
+        if modified_keys.is_empty() {
+            return WidgetFlags::default();
+        }
+        for &key in modified_keys.iter() {
+            self.store.update_geometry_for_stroke(key);
+            self.store.regenerate_rendering_for_stroke(
+                key,
+                self.camera.viewport(),
+                self.camera.image_scale(),
+            );
+        }
+
+        let mut widget_flags = self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_content_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Lock the selected strokes against selecting, erasing and transforming.
+    ///
+    /// The locked strokes get deselected.
+    pub fn lock_selection(&mut self) -> WidgetFlags {
+        let selection_keys = self.store.selection_keys_as_rendered();
+        if selection_keys.is_empty() {
+            return WidgetFlags::default();
+        }
+        self.store.set_locked_keys(&selection_keys, true);
+        let mut widget_flags = self.current_pen_update_state()
+            | self.record(Instant::now())
+            | self.update_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
+    /// Unlock all locked strokes.
+    pub fn unlock_all_strokes(&mut self) -> WidgetFlags {
+        let locked_keys = self.store.locked_keys_unordered();
+        if locked_keys.is_empty() {
+            return WidgetFlags::default();
+        }
+        self.store.set_locked_keys(&locked_keys, false);
+        let mut widget_flags = self.record(Instant::now());
+        widget_flags.store_modified = true;
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
     pub fn nothing_selected(&self) -> bool {
         self.store.selection_keys_unordered().is_empty()
     }
 
+    /// The bounds of the current selection.
+    ///
+    /// None if nothing is selected.
+    pub fn selection_bounds(&self) -> Option<Aabb> {
+        self.store.selection_bounds()
+    }
+
     pub fn change_selection_stroke_colors(&mut self, stroke_color: Color) -> WidgetFlags {
         self.store
             .change_stroke_colors(&self.store.selection_keys_as_rendered(), stroke_color)
@@ -858,6 +1980,26 @@ impl Engine {
             | self.update_content_rendering_current_viewport()
     }
 
+    /// The distinct colors of the selected strokes.
+    pub fn selection_colors(&self) -> Vec<Color> {
+        self.store
+            .colors_for_strokes(&self.store.selection_keys_as_rendered())
+    }
+
+    /// Replace all occurrences of the color `from` with `to` in the selected strokes.
+    pub fn remap_selection_colors(&mut self, from: Color, to: Color) -> WidgetFlags {
+        let widget_flags =
+            self.store
+                .remap_colors(&self.store.selection_keys_as_rendered(), from, to);
+        if !widget_flags.store_modified {
+            return widget_flags;
+        }
+
+        widget_flags
+            | self.record(Instant::now())
+            | self.update_content_rendering_current_viewport()
+    }
+
     pub fn invert_selection_colors(&mut self) -> WidgetFlags {
         self.store
             .invert_color_brightness(&self.store.selection_keys_as_rendered())
@@ -865,6 +2007,190 @@ impl Engine {
             | self.update_content_rendering_current_viewport()
     }
 
+    /// Mirror the selection along the axis through the center of its bounds.
+    pub fn flip_selection(&mut self, axis: FlipAxis) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let selection_keys = self.store.selection_keys_as_rendered();
+        let Some(selection_bounds) = self.store.bounds_for_strokes(&selection_keys) else {
+            return widget_flags;
+        };
+        let pivot = selection_bounds.center().coords;
+
+        self.store.flip_strokes(&selection_keys, axis, pivot);
+        self.store.flip_strokes_images(&selection_keys, axis, pivot);
+        widget_flags.store_modified = true;
+
+        widget_flags
+            | self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_content_rendering_current_viewport()
+    }
+
+    /// Change the order in which the selection is drawn relative to the other strokes of their layer.
+    pub fn change_selection_z_order(&mut self, change: ZOrderChange) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let selection_keys = self.store.selection_keys_as_rendered();
+        if selection_keys.is_empty() {
+            return widget_flags;
+        }
+
+        self.store.change_z_order(&selection_keys, change);
+        widget_flags.store_modified = true;
+
+        widget_flags
+            | self.current_pen_update_state()
+            | self.record(Instant::now())
+            | self.update_content_rendering_current_viewport()
+    }
+
+    /// Resize the selection to the given size, keeping the top left corner of its bounds in place.
+    ///
+    /// Stroke widths are only scaled when enabled in the selector config.
+    pub fn resize_selection(&mut self, size: na::Vector2<f64>) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let selection_keys = self.store.selection_keys_as_rendered();
+        let Some(selection_bounds) = self.store.bounds_for_strokes(&selection_keys) else {
+            return widget_flags;
+        };
+        let extents = selection_bounds.extents();
+        if extents[0] <= 0.0 || extents[1] <= 0.0 || size[0] <= 0.0 || size[1] <= 0.0 {
+            return widget_flags;
+        }
+        let scale = size.component_div(&extents);
+        let pivot = selection_bounds.mins.coords;
+
+        self.store
+            .scale_strokes_with_pivot(&selection_keys, scale, pivot);
+        if !self.pens_config.selector_config.resize_scale_stroke_width {
+            // Scaling the strokes also scales their widths, so this undoes it.
+            self.store
+                .scale_stroke_widths(&selection_keys, 1.0 / (scale[0] * scale[1]).sqrt());
+        }
+        self.store
+            .scale_strokes_images_with_pivot(&selection_keys, scale, pivot);
+        widget_flags.store_modified = true;
+
+        widget_flags
+            | self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_content_rendering_current_viewport()
+    }
+
+    /// Scale the content of the entire document uniformly by the factor, with the document origin as pivot.
+    ///
+    /// When `scale_format` is set, the page format is scaled as well so that the content stays on the same pages.
+    /// Errors when the scaled format would exceed the supported format size.
+    /// The scaled strokes and the format are recorded as a single history entry.
+    pub fn scale_doc_content(
+        &mut self,
+        factor: f64,
+        scale_stroke_widths: bool,
+        scale_format: bool,
+    ) -> anyhow::Result<WidgetFlags> {
+        let mut widget_flags = WidgetFlags::default();
+        if !factor.is_finite() || factor <= 0.0 || factor == 1.0 {
+            return Ok(widget_flags);
+        }
+        let prev_doc_layout = self.document.doc_layout();
+        let format_size = self.document.format.size() * factor;
+        if scale_format
+            && !((Format::WIDTH_MIN..=Format::WIDTH_MAX).contains(&format_size[0])
+                && (Format::HEIGHT_MIN..=Format::HEIGHT_MAX).contains(&format_size[1]))
+        {
+            return Err(anyhow::anyhow!(
+                "Scaled format size {format_size:?} is outside of the supported format size."
+            ));
+        }
+        let keys = self.store.keys_unordered();
+        let scale = na::Vector2::from_element(factor);
+        let pivot = na::vector![self.document.x, self.document.y];
+
+        self.store.scale_strokes_with_pivot(&keys, scale, pivot);
+        if !scale_stroke_widths {
+            self.store.scale_stroke_widths(&keys, 1.0 / factor);
+        }
+        self.store
+            .scale_strokes_images_with_pivot(&keys, scale, pivot);
+        if scale_format {
+            self.document.format.set_width(format_size[0]);
+            self.document.format.set_height(format_size[1]);
+            widget_flags.refresh_ui = true;
+        }
+        widget_flags.store_modified = true;
+
+        widget_flags |= self.current_pen_update_state() | self.doc_resize_to_fit_content();
+        if scale_format {
+            let doc_layout = self.document.doc_layout();
+            widget_flags |=
+                self.store
+                    .record_w_doc_layout(Instant::now(), prev_doc_layout, doc_layout);
+        } else {
+            widget_flags |= self.record(Instant::now());
+        }
+        Ok(widget_flags | self.update_content_rendering_current_viewport())
+    }
+
+    /// Enter the crop mode for the selected image, or leave it when already cropping.
+    ///
+    /// Only possible when the selection consists of a single image.
+    pub fn toggle_image_crop(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if let Pen::Selector(selector) = self.penholder.current_pen_mut() {
+            if selector.is_cropping_image() {
+                widget_flags |= selector.finish_image_crop(&mut engine_view_mut!(self));
+            } else {
+                widget_flags |= selector.start_image_crop(&mut engine_view_mut!(self));
+            }
+        }
+        widget_flags
+    }
+
+    /// Enter the node editing mode for the selected bezier path, or leave it when already editing.
+    ///
+    /// Only possible when the selection consists of a single shape stroke with a bezier path.
+    pub fn toggle_edit_nodes(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if let Pen::Selector(selector) = self.penholder.current_pen_mut() {
+            if selector.is_editing_nodes() {
+                widget_flags |= selector.finish_edit_nodes(&mut engine_view_mut!(self));
+            } else {
+                widget_flags |= selector.start_edit_nodes(&mut engine_view_mut!(self));
+            }
+        }
+        widget_flags
+    }
+
+    /// Reset the crop of the selected images, making them fully visible again.
+    pub fn reset_selection_image_crop(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let mut modified = false;
+        for key in self.store.selection_keys_as_rendered() {
+            modified |= self.store.set_image_crop(key, None);
+        }
+        if !modified {
+            return widget_flags;
+        }
+        widget_flags.store_modified = true;
+
+        widget_flags
+            | self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_content_rendering_current_viewport()
+    }
+
+    /// Simplify the selected brush strokes with the epsilon of the current brush config.
+    pub fn simplify_selection(&mut self) -> WidgetFlags {
+        self.store.simplify_brushstrokes(
+            &self.store.selection_keys_as_rendered(),
+            self.pens_config.brush_config.simplify_epsilon,
+        ) | self.current_pen_update_state()
+            | self.record(Instant::now())
+            | self.update_content_rendering_current_viewport()
+    }
+
     pub fn text_selection_change_style<F>(&mut self, modify_func: F) -> WidgetFlags
     where
         F: FnOnce(&mut TextStyle),
@@ -877,6 +2203,19 @@ impl Engine {
         widget_flags
     }
 
+    /// Switch to the typewriter and place the text cursor at the position.
+    pub fn start_text_at(&mut self, pos: na::Vector2<f64>) -> WidgetFlags {
+        // Changing the pen style only deselects when the pen actually changes
+        let selection_keys = self.store.selection_keys_as_rendered();
+        self.store.set_selected_keys(&selection_keys, false);
+
+        let mut widget_flags = self.change_pen_style(PenStyle::Typewriter);
+        if let Pen::Typewriter(typewriter) = self.penholder.current_pen_mut() {
+            widget_flags |= typewriter.start_text_at(pos, &mut engine_view_mut!(self));
+        }
+        widget_flags
+    }
+
     pub fn text_selection_remove_attributes(&mut self) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
         if let Pen::Typewriter(typewriter) = self.penholder.current_pen_mut() {
@@ -909,6 +2248,23 @@ impl Engine {
         widget_flags
     }
 
+    /// The font of the current text selection, or of the text that is being modified when nothing is selected.
+    pub fn text_font(&self) -> Option<TextFont> {
+        match self.penholder.current_pen_ref() {
+            Pen::Typewriter(typewriter) => typewriter.current_font(&engine_view!(self)),
+            _ => None,
+        }
+    }
+
+    /// Change the font of the current text selection, or of the text that is being modified when nothing is selected.
+    pub fn text_change_font(&mut self, font: TextFont) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if let Pen::Typewriter(typewriter) = self.penholder.current_pen_mut() {
+            widget_flags |= typewriter.change_font(font, &mut engine_view_mut!(self));
+        }
+        widget_flags
+    }
+
     pub fn text_change_color(&mut self, color: Color) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
         if let Pen::Typewriter(typewriter) = self.penholder.current_pen_mut() {
@@ -931,7 +2287,283 @@ impl Engine {
     ///
     /// Can request another frame using `EngineViewMut#animation.claim_frame()`.
     pub fn handle_animation_frame(&mut self, optimize_epd: bool) {
+        self.handle_replay_animation_frame();
         self.penholder
             .handle_animation_frame(&mut engine_view_mut!(self), optimize_epd);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::document::{ExpandDirection, Layout};
+    use crate::engine::EngineSnapshot;
+    use crate::strokes::resize::ImageSizeOption;
+    use crate::strokes::{ShapeStroke, Stroke, VectorImage};
+    use crate::Engine;
+    use approx::assert_relative_eq;
+    use p2d::bounding_volume::Aabb;
+    use rnote_compose::shapes::{Rectangle, Shape, Shapeable};
+    use rnote_compose::{SplitOrder, Style};
+    use std::time::Instant;
+
+    fn rectangle_stroke() -> Stroke {
+        Stroke::ShapeStroke(ShapeStroke::new(
+            Shape::Rectangle(Rectangle::from_corners(
+                na::vector![10.0, 20.0],
+                na::vector![110.0, 220.0],
+            )),
+            Style::default(),
+        ))
+    }
+
+    /// Only uses the public api, the way the engine is used as standalone library.
+    #[test]
+    fn save_and_load_rnote_bytes() {
+        let mut engine = Engine::default();
+        let _ = engine.import_generated_content(vec![(rectangle_stroke(), None)], false);
+
+        let bytes =
+            futures::executor::block_on(engine.save_as_rnote_bytes(String::from("test.rnote")))
+                .unwrap()
+                .unwrap();
+        let snapshot =
+            futures::executor::block_on(EngineSnapshot::load_from_rnote_bytes(bytes)).unwrap();
+        let mut loaded = Engine::default();
+        let _ = loaded.load_snapshot(snapshot);
+
+        let content = loaded.extract_document_content();
+        assert_eq!(content.strokes.len(), 1);
+        assert_relative_eq!(
+            content.strokes[0].bounds().mins,
+            rectangle_stroke().bounds().mins
+        );
+    }
+
+    #[test]
+    fn trash_image_w_caption() {
+        let mut engine = Engine::default();
+        let image = VectorImage::from_svg_str(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="50"><rect width="100" height="50"/></svg>"#,
+            na::vector![10.0, 20.0],
+            ImageSizeOption::RespectOriginalSize,
+        )
+        .unwrap();
+        let image_key = engine.store.insert_stroke(Stroke::VectorImage(image), None);
+        engine.store.set_selected(image_key, true);
+        let _ = engine.record(Instant::now());
+        let _ = engine.add_caption_to_selected_image(String::from("caption"));
+        let [caption_key] = engine.store.selection_keys_as_rendered()[..] else {
+            panic!("caption is not selected");
+        };
+        let trashed = |engine: &Engine| {
+            (
+                engine.store.trashed(image_key).unwrap(),
+                engine.store.trashed(caption_key).unwrap(),
+            )
+        };
+
+        engine.store.set_selected(caption_key, false);
+        engine.store.set_selected(image_key, true);
+        let _ = engine.trash_selection();
+        assert_eq!(trashed(&engine), (true, true));
+
+        let _ = engine.undo(Instant::now());
+        assert_eq!(trashed(&engine), (false, false));
+        let _ = engine.redo(Instant::now());
+        assert_eq!(trashed(&engine), (true, true));
+
+        let _ = engine.restore_trashed_strokes(&[image_key]);
+        assert_eq!(trashed(&engine), (false, false));
+        let _ = engine.undo(Instant::now());
+        assert_eq!(trashed(&engine), (true, true));
+    }
+
+    #[test]
+    fn set_doc_infinite_expand_direction_modifies_store() {
+        let mut engine = Engine::default();
+        let widget_flags = engine.set_doc_infinite_expand_direction(ExpandDirection::Vertical);
+        assert!(widget_flags.store_modified);
+        // Setting the same direction again is a no-op
+        let widget_flags = engine.set_doc_infinite_expand_direction(ExpandDirection::Vertical);
+        assert!(!widget_flags.store_modified);
+    }
+
+    #[test]
+    fn scale_doc_content_undo() {
+        let mut engine = Engine::default();
+        let key = engine.store.insert_stroke(rectangle_stroke(), None);
+        let _ = engine.record(Instant::now());
+        let format_size = engine.document.format.size();
+        let bounds = engine.store.get_stroke_ref(key).unwrap().bounds();
+
+        engine.scale_doc_content(2.0, true, true).unwrap();
+        assert_relative_eq!(engine.document.format.size(), format_size * 2.0);
+        assert_relative_eq!(
+            engine.store.get_stroke_ref(key).unwrap().bounds().maxs,
+            bounds.maxs * 2.0
+        );
+
+        let _ = engine.undo(Instant::now());
+        assert_relative_eq!(engine.document.format.size(), format_size);
+        assert_relative_eq!(
+            engine.store.get_stroke_ref(key).unwrap().bounds().mins,
+            bounds.mins
+        );
+        assert_relative_eq!(
+            engine.store.get_stroke_ref(key).unwrap().bounds().maxs,
+            bounds.maxs
+        );
+
+        let _ = engine.redo(Instant::now());
+        assert_relative_eq!(engine.document.format.size(), format_size * 2.0);
+    }
+
+    #[test]
+    fn scale_doc_content_refuses_clamped_format() {
+        let mut engine = Engine::default();
+        let format_size = engine.document.format.size();
+
+        assert!(engine.scale_doc_content(100.0, true, true).is_err());
+        assert_relative_eq!(engine.document.format.size(), format_size);
+        assert!(!engine.can_undo());
+    }
+
+    /// An engine with a continuous vertical layout and a 1000 x 1000 format.
+    fn smart_breaks_engine() -> Engine {
+        let mut engine = Engine::default();
+        engine.document.layout = Layout::ContinuousVertical;
+        engine.document.format.set_width(1000.0);
+        engine.document.format.set_height(1000.0);
+        engine
+    }
+
+    fn insert_rectangle(
+        engine: &mut Engine,
+        mins: na::Vector2<f64>,
+        maxs: na::Vector2<f64>,
+    ) -> Aabb {
+        let stroke = Stroke::ShapeStroke(ShapeStroke::new(
+            Shape::Rectangle(Rectangle::from_corners(mins, maxs)),
+            Style::default(),
+        ));
+        let bounds = stroke.bounds();
+        engine.store.insert_stroke(stroke, None);
+        let _ = engine.doc_resize_to_fit_content();
+        bounds
+    }
+
+    fn assert_pages_vertical_extents(pages_bounds: &[Aabb], extents: &[(f64, f64)]) {
+        assert_eq!(pages_bounds.len(), extents.len());
+        for (page_bounds, (top, bottom)) in pages_bounds.iter().zip(extents) {
+            assert_relative_eq!(page_bounds.mins[1], *top);
+            assert_relative_eq!(page_bounds.maxs[1], *bottom);
+        }
+    }
+
+    #[test]
+    fn smart_page_break_above_stroke() {
+        let mut engine = smart_breaks_engine();
+        insert_rectangle(
+            &mut engine,
+            na::vector![10.0, 100.0],
+            na::vector![110.0, 200.0],
+        );
+        let crossing = insert_rectangle(
+            &mut engine,
+            na::vector![10.0, 900.0],
+            na::vector![110.0, 1100.0],
+        );
+
+        let pages_bounds = engine.pages_bounds_w_smart_breaks(SplitOrder::RowMajor);
+        assert_pages_vertical_extents(
+            &pages_bounds,
+            &[
+                (0.0, crossing.mins[1]),
+                (crossing.mins[1], crossing.mins[1] + 1000.0),
+            ],
+        );
+        assert_relative_eq!(pages_bounds[0].mins[0], 0.0);
+        assert_relative_eq!(pages_bounds[0].maxs[0], 1000.0);
+    }
+
+    #[test]
+    fn smart_page_break_in_gap_not_cut_by_other_stroke() {
+        let mut engine = smart_breaks_engine();
+        insert_rectangle(
+            &mut engine,
+            na::vector![10.0, 100.0],
+            na::vector![110.0, 200.0],
+        );
+        let overlapping = insert_rectangle(
+            &mut engine,
+            na::vector![10.0, 850.0],
+            na::vector![110.0, 950.0],
+        );
+        insert_rectangle(
+            &mut engine,
+            na::vector![50.0, 900.0],
+            na::vector![150.0, 1100.0],
+        );
+
+        // The top of the crossing stroke is cut by the overlapping one, so the break moves further up
+        assert_pages_vertical_extents(
+            &engine.pages_bounds_w_smart_breaks(SplitOrder::RowMajor),
+            &[
+                (0.0, overlapping.mins[1]),
+                (overlapping.mins[1], overlapping.mins[1] + 1000.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn smart_page_break_kept_without_gap() {
+        let mut engine = smart_breaks_engine();
+        // The top edge of the stroke is further away from the break than the threshold
+        insert_rectangle(
+            &mut engine,
+            na::vector![10.0, 500.0],
+            na::vector![110.0, 1500.0],
+        );
+
+        assert_pages_vertical_extents(
+            &engine.pages_bounds_w_smart_breaks(SplitOrder::RowMajor),
+            &[(0.0, 1000.0), (1000.0, 2000.0)],
+        );
+    }
+
+    #[test]
+    fn smart_page_breaks_skip_empty_pages() {
+        let mut engine = smart_breaks_engine();
+        insert_rectangle(
+            &mut engine,
+            na::vector![10.0, 100.0],
+            na::vector![110.0, 200.0],
+        );
+        insert_rectangle(
+            &mut engine,
+            na::vector![10.0, 2100.0],
+            na::vector![110.0, 2200.0],
+        );
+
+        assert_pages_vertical_extents(
+            &engine.pages_bounds_w_smart_breaks(SplitOrder::RowMajor),
+            &[(0.0, 1000.0), (2000.0, 3000.0)],
+        );
+    }
+
+    #[test]
+    fn smart_page_breaks_fixed_size_layout() {
+        let mut engine = smart_breaks_engine();
+        insert_rectangle(
+            &mut engine,
+            na::vector![10.0, 900.0],
+            na::vector![110.0, 1100.0],
+        );
+        engine.document.layout = Layout::FixedSize;
+
+        assert_pages_vertical_extents(
+            &engine.pages_bounds_w_smart_breaks(SplitOrder::RowMajor),
+            &[(0.0, 1000.0), (1000.0, 2000.0)],
+        );
+    }
+}
@@ -0,0 +1,230 @@
+// Imports
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// The global profiler that records the timings of the instrumented engine operations.
+///
+/// It is disabled by default, then recording the timings is a no-op.
+/// The operations are recorded from all engine instances and from the worker threads.
+pub static PROFILER: Lazy<Profiler> = Lazy::new(Profiler::default);
+
+/// The instrumented engine operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "profile_category")]
+pub enum ProfileCategory {
+    /// Generating the rendering images of a stroke.
+    #[serde(rename = "rendering_regeneration")]
+    RenderingRegeneration = 0,
+    /// Inserting a stroke into the store.
+    #[serde(rename = "stroke_insertion")]
+    StrokeInsertion,
+    /// Saving the document as .rnote file.
+    #[serde(rename = "save")]
+    Save,
+    /// Loading a .rnote file.
+    #[serde(rename = "load")]
+    Load,
+}
+
+impl ProfileCategory {
+    pub const ALL: [Self; 4] = [
+        Self::RenderingRegeneration,
+        Self::StrokeInsertion,
+        Self::Save,
+        Self::Load,
+    ];
+
+    /// A short name for displaying the category.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::RenderingRegeneration => "rendering",
+            Self::StrokeInsertion => "stroke insertion",
+            Self::Save => "save",
+            Self::Load => "load",
+        }
+    }
+}
+
+/// The recorded timings of one category.
+#[derive(Debug, Clone, Default)]
+struct CategoryTimings {
+    count: u64,
+    total: Duration,
+    max: Duration,
+    /// The most recent timings, the newest last.
+    recent: VecDeque<Duration>,
+}
+
+impl CategoryTimings {
+    const RECENT_MAX: usize = 200;
+
+    fn record(&mut self, duration: Duration) {
+        self.count += 1;
+        self.total += duration;
+        self.max = self.max.max(duration);
+        if self.recent.len() >= Self::RECENT_MAX {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(duration);
+    }
+
+    fn summary(&self, category: ProfileCategory) -> ProfileSummary {
+        let mut recent = self.recent.iter().copied().collect::<Vec<Duration>>();
+        recent.sort_unstable();
+        let recent_percentile = |p: f64| {
+            recent
+                .get(((recent.len() as f64 - 1.0) * p).round() as usize)
+                .map_or(0.0, |d| d.as_secs_f64() * 1000.0)
+        };
+        ProfileSummary {
+            category,
+            count: self.count,
+            total_ms: self.total.as_secs_f64() * 1000.0,
+            mean_ms: if self.count > 0 {
+                self.total.as_secs_f64() * 1000.0 / self.count as f64
+            } else {
+                0.0
+            },
+            max_ms: self.max.as_secs_f64() * 1000.0,
+            recent_median_ms: recent_percentile(0.5),
+            recent_p95_ms: recent_percentile(0.95),
+        }
+    }
+}
+
+/// A summary of the recorded timings of a category. The timings are in milliseconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "profile_summary")]
+pub struct ProfileSummary {
+    #[serde(rename = "category")]
+    pub category: ProfileCategory,
+    /// How often the operation was recorded.
+    #[serde(rename = "count")]
+    pub count: u64,
+    #[serde(rename = "total_ms")]
+    pub total_ms: f64,
+    #[serde(rename = "mean_ms")]
+    pub mean_ms: f64,
+    #[serde(rename = "max_ms")]
+    pub max_ms: f64,
+    /// The median of the most recent timings.
+    #[serde(rename = "recent_median_ms")]
+    pub recent_median_ms: f64,
+    /// The 95th percentile of the most recent timings.
+    #[serde(rename = "recent_p95_ms")]
+    pub recent_p95_ms: f64,
+}
+
+/// A profile of the recorded timings, that can be attached to performance regression reports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "profile")]
+pub struct Profile {
+    /// The engine version that recorded the profile.
+    #[serde(rename = "version")]
+    pub version: String,
+    /// The duration of the recording in seconds.
+    #[serde(rename = "recording_secs")]
+    pub recording_secs: f64,
+    #[serde(rename = "summaries")]
+    pub summaries: Vec<ProfileSummary>,
+}
+
+impl Profile {
+    /// Export the profile as Json string.
+    pub fn export_as_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[derive(Debug, Default)]
+struct ProfilerState {
+    recording_start: Option<Instant>,
+    timings: [CategoryTimings; ProfileCategory::ALL.len()],
+}
+
+#[derive(Debug, Default)]
+pub struct Profiler {
+    enabled: AtomicBool,
+    state: Mutex<ProfilerState>,
+}
+
+impl Profiler {
+    pub fn enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Enable or disable recording. Enabling it starts a new recording and clears the previous timings.
+    pub fn set_enabled(&self, enabled: bool) {
+        if self.enabled.swap(enabled, Ordering::Relaxed) == enabled {
+            return;
+        }
+        if enabled {
+            self.reset();
+        }
+    }
+
+    /// Clear the recorded timings.
+    pub fn reset(&self) {
+        *self.lock_state() = ProfilerState {
+            recording_start: Some(Instant::now()),
+            ..Default::default()
+        };
+    }
+
+    /// Record the timing of an operation. Does nothing when the profiler is disabled.
+    pub fn record(&self, category: ProfileCategory, duration: Duration) {
+        if !self.enabled() {
+            return;
+        }
+        self.lock_state().timings[category as usize].record(duration);
+    }
+
+    /// The profile of the timings recorded until now.
+    pub fn profile(&self) -> Profile {
+        let state = self.lock_state();
+        Profile {
+            version: crate::utils::crate_version().to_string(),
+            recording_secs: state
+                .recording_start
+                .map_or(0.0, |start| start.elapsed().as_secs_f64()),
+            summaries: ProfileCategory::ALL
+                .into_iter()
+                .map(|category| state.timings[category as usize].summary(category))
+                .collect(),
+        }
+    }
+
+    /// Recording a timing can't leave the state half-updated, so a poisoned lock is recovered.
+    fn lock_state(&self) -> MutexGuard<'_, ProfilerState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Measures the time until it is dropped and records it in the global profiler.
+#[derive(Debug)]
+#[must_use = "the span records its timing when it is dropped"]
+pub struct ProfileSpan {
+    category: ProfileCategory,
+    /// None when the profiler was disabled when the span was started.
+    start: Option<Instant>,
+}
+
+impl Drop for ProfileSpan {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            PROFILER.record(self.category, start.elapsed());
+        }
+    }
+}
+
+/// Start measuring an operation, the timing is recorded when the returned span is dropped.
+pub fn span(category: ProfileCategory) -> ProfileSpan {
+    ProfileSpan {
+        category,
+        start: PROFILER.enabled().then(Instant::now),
+    }
+}
@@ -176,8 +176,24 @@ impl Engine {
         self.draw_background_to_gtk_snapshot(snapshot)?;
         self.draw_format_borders_to_gtk_snapshot(snapshot)?;
         self.draw_origin_indicator_to_gtk_snapshot(snapshot)?;
-        self.store
-            .draw_strokes_to_gtk_snapshot(snapshot, doc_bounds, viewport);
+        snapshot.restore();
+        self.draw_onion_skin_to_gtk_snapshot(snapshot)?;
+        snapshot.save();
+        snapshot.transform(Some(&camera_transform));
+        if let Some(revealed_keys) = self.replay_revealed_keys() {
+            self.store.draw_keys_intersecting_bounds_to_gtk_snapshot(
+                snapshot,
+                doc_bounds,
+                viewport,
+                revealed_keys,
+            );
+        } else {
+            self.store
+                .draw_strokes_to_gtk_snapshot(snapshot, doc_bounds, viewport);
+        }
+        self.draw_locked_strokes_indicator_to_gtk_snapshot(snapshot);
+        self.draw_guides_to_gtk_snapshot(snapshot);
+        self.draw_out_of_bounds_dimming_to_gtk_snapshot(snapshot);
         snapshot.restore();
         /*
                let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(surface_bounds));
@@ -268,7 +284,6 @@ impl Engine {
         use crate::ext::{GdkRGBAExt, GrapheneRectExt};
         use gtk4::{gdk, graphene, gsk, prelude::*};
         use p2d::bounding_volume::BoundingVolume;
-        use rnote_compose::ext::AabbExt;
         use rnote_compose::SplitOrder;
 
         if self.document.format.show_borders {
@@ -279,9 +294,7 @@ impl Engine {
 
             snapshot.push_clip(&graphene::Rect::from_p2d_aabb(doc_bounds.loosened(2.0)));
 
-            for page_bounds in doc_bounds
-                .split_extended_origin_aligned(self.document.format.size(), SplitOrder::default())
-            {
+            for page_bounds in self.document.pages_bounds(SplitOrder::default()) {
                 if !page_bounds.intersects(&viewport) {
                     continue;
                 }
@@ -317,6 +330,239 @@ impl Engine {
         Ok(())
     }
 
+    /// Draw the strokes of the previous and next page translucently on the page the viewport center is located in.
+    ///
+    /// Expects the snapshot to be in surface coordinates.
+    /// The neighbouring pages usually are not in the viewport, so they don't have a cached rendering
+    /// and need to be drawn immediately.
+    #[cfg(feature = "ui")]
+    fn draw_onion_skin_to_gtk_snapshot(&self, snapshot: &gtk4::Snapshot) -> anyhow::Result<()> {
+        use crate::ext::GrapheneRectExt;
+        use gtk4::{graphene, prelude::*};
+        use rnote_compose::ext::{AabbExt, Affine2Ext, Vector2Ext};
+        use rnote_compose::SplitOrder;
+
+        if !self.document.onion_skinning {
+            return Ok(());
+        }
+
+        let doc_bounds = self.document.bounds();
+        let viewport = self.camera.viewport();
+        let pages_bounds = self.document.pages_bounds(SplitOrder::default());
+        let viewport_center = self.camera.viewport_center();
+        let Some(current_idx) = pages_bounds
+            .iter()
+            .position(|bounds| bounds.contains_local_point(&viewport_center.into()))
+        else {
+            return Ok(());
+        };
+        let current_page = pages_bounds[current_idx];
+        let Some(visible_bounds) = current_page.intersection(&viewport) else {
+            return Ok(());
+        };
+        let neighbour_pages = [current_idx.checked_sub(1), Some(current_idx + 1)]
+            .into_iter()
+            .flatten()
+            .filter_map(|idx| pages_bounds.get(idx).copied())
+            .collect::<Vec<Aabb>>();
+
+        let mut bounds_on_surface = visible_bounds
+            .scale(self.camera.total_zoom())
+            .translate(-self.camera.offset())
+            .ceil();
+        bounds_on_surface.ensure_positive();
+        bounds_on_surface.assert_valid()?;
+
+        snapshot.push_opacity(self.document.onion_skinning_opacity);
+        {
+            let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(bounds_on_surface));
+            let mut piet_cx = piet_cairo::CairoRenderContext::new(&cairo_cx);
+            piet_cx.transform(self.camera.transform().to_kurbo());
+            piet_cx.clip(visible_bounds.to_kurbo_rect());
+
+            for neighbour_page in neighbour_pages {
+                let offset = current_page.mins.coords - neighbour_page.mins.coords;
+
+                piet_cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+                piet_cx.transform(kurbo::Affine::translate(offset.to_kurbo_vec()));
+                self.store.draw_strokes_immediate(
+                    &mut piet_cx,
+                    doc_bounds,
+                    neighbour_page,
+                    self.camera.image_scale(),
+                );
+                piet_cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+            }
+        }
+        snapshot.pop();
+
+        Ok(())
+    }
+
+    /// Outline the locked strokes in the viewport with a badge in the upper left corner,
+    /// while a pen that would otherwise act on them is active.
+    ///
+    /// Expects the snapshot to be in document coordinates.
+    #[cfg(feature = "ui")]
+    fn draw_locked_strokes_indicator_to_gtk_snapshot(&self, snapshot: &gtk4::Snapshot) {
+        use crate::ext::{GdkRGBAExt, GrapheneRectExt};
+        use crate::pens::PenStyle;
+        use gtk4::{gdk, graphene, gsk, prelude::*};
+        use p2d::bounding_volume::BoundingVolume;
+        use rnote_compose::Color;
+
+        const INDICATOR_COLOR: Color = Color {
+            r: 0.5,
+            g: 0.5,
+            b: 0.5,
+            a: 0.6,
+        };
+        const BORDER_WIDTH: f64 = 1.5;
+        const BADGE_SIZE: f64 = 8.0;
+
+        if !matches!(
+            self.penholder.current_pen_style_w_override(),
+            PenStyle::Selector | PenStyle::Eraser
+        ) {
+            return;
+        }
+        let viewport = self.camera.viewport();
+        let total_zoom = self.camera.total_zoom();
+        let border_width = BORDER_WIDTH / total_zoom;
+        let badge_size = BADGE_SIZE / total_zoom;
+        let color = gdk::RGBA::from_compose_color(INDICATOR_COLOR);
+
+        for bounds in self
+            .store
+            .locked_keys_unordered()
+            .into_iter()
+            .filter_map(|key| self.store.bounds_for_strokes(&[key]))
+            .filter(|bounds| bounds.intersects(&viewport))
+        {
+            let rounded_rect = gsk::RoundedRect::new(
+                graphene::Rect::from_p2d_aabb(bounds),
+                graphene::Size::zero(),
+                graphene::Size::zero(),
+                graphene::Size::zero(),
+                graphene::Size::zero(),
+            );
+            snapshot.append_border(
+                &rounded_rect,
+                &[border_width as f32; 4],
+                &[
+                    gdk::RGBA::from_compose_color(INDICATOR_COLOR),
+                    gdk::RGBA::from_compose_color(INDICATOR_COLOR),
+                    gdk::RGBA::from_compose_color(INDICATOR_COLOR),
+                    gdk::RGBA::from_compose_color(INDICATOR_COLOR),
+                ],
+            );
+            snapshot.append_color(
+                &color,
+                &graphene::Rect::new(
+                    bounds.mins[0] as f32,
+                    bounds.mins[1] as f32,
+                    badge_size as f32,
+                    badge_size as f32,
+                ),
+            );
+        }
+    }
+
+    /// Draw the guide lines across the viewport.
+    ///
+    /// Expects the snapshot to be in document coordinates.
+    #[cfg(feature = "ui")]
+    fn draw_guides_to_gtk_snapshot(&self, snapshot: &gtk4::Snapshot) {
+        use crate::document::{Guide, GuideOrientation};
+        use crate::ext::GdkRGBAExt;
+        use gtk4::{gdk, graphene, prelude::*};
+
+        let viewport = self.camera.viewport();
+        let line_width = 1.0 / self.camera.total_zoom();
+        let color = gdk::RGBA::from_compose_color(Guide::COLOR);
+
+        for guide in self.document.guides.iter() {
+            let rect = match guide.orientation {
+                GuideOrientation::Horizontal => {
+                    if guide.pos < viewport.mins[1] || guide.pos > viewport.maxs[1] {
+                        continue;
+                    }
+                    graphene::Rect::new(
+                        viewport.mins[0] as f32,
+                        (guide.pos - line_width * 0.5) as f32,
+                        viewport.extents()[0] as f32,
+                        line_width as f32,
+                    )
+                }
+                GuideOrientation::Vertical => {
+                    if guide.pos < viewport.mins[0] || guide.pos > viewport.maxs[0] {
+                        continue;
+                    }
+                    graphene::Rect::new(
+                        (guide.pos - line_width * 0.5) as f32,
+                        viewport.mins[1] as f32,
+                        line_width as f32,
+                        viewport.extents()[1] as f32,
+                    )
+                }
+            };
+            snapshot.append_color(&color, &rect);
+        }
+    }
+
+    /// Dim the area outside of the document when panning is clamped to it.
+    ///
+    /// Expects the snapshot to be in document coordinates.
+    #[cfg(feature = "ui")]
+    fn draw_out_of_bounds_dimming_to_gtk_snapshot(&self, snapshot: &gtk4::Snapshot) {
+        use crate::ext::GdkRGBAExt;
+        use crate::Document;
+        use gtk4::{gdk, graphene, prelude::*};
+
+        if !self.document.clamps_panning() {
+            return;
+        }
+        let viewport = self.camera.viewport();
+        let doc_bounds = self.document.bounds();
+        let color = gdk::RGBA::from_compose_color(Document::OUT_OF_BOUNDS_DIM_COLOR);
+        let rect = |x0: f64, y0: f64, x1: f64, y1: f64| {
+            graphene::Rect::new(x0 as f32, y0 as f32, (x1 - x0) as f32, (y1 - y0) as f32)
+        };
+
+        for r in [
+            // above and below span the entire viewport width
+            rect(
+                viewport.mins[0],
+                viewport.mins[1],
+                viewport.maxs[0],
+                doc_bounds.mins[1],
+            ),
+            rect(
+                viewport.mins[0],
+                doc_bounds.maxs[1],
+                viewport.maxs[0],
+                viewport.maxs[1],
+            ),
+            // left and right only span the document height
+            rect(
+                viewport.mins[0],
+                doc_bounds.mins[1],
+                doc_bounds.mins[0],
+                doc_bounds.maxs[1],
+            ),
+            rect(
+                doc_bounds.maxs[0],
+                doc_bounds.mins[1],
+                viewport.maxs[0],
+                doc_bounds.maxs[1],
+            ),
+        ] {
+            if r.width() > 0.0 && r.height() > 0.0 {
+                snapshot.append_color(&color, &r);
+            }
+        }
+    }
+
     /// Draw the document origin indicator cross.
     #[cfg(feature = "ui")]
     fn draw_origin_indicator_to_gtk_snapshot(
@@ -0,0 +1,195 @@
+// Imports
+use super::{Engine, StrokeContent};
+use crate::render;
+use crate::store::StrokeKey;
+use crate::WidgetFlags;
+use anyhow::Context;
+use futures::channel::oneshot;
+use p2d::bounding_volume::BoundingVolume;
+use std::time::Instant;
+use tracing::error;
+
+/// Replays the strokes of the document in the order they were drawn.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    /// The keys in drawing order.
+    keys: Vec<StrokeKey>,
+    /// The number of revealed strokes. Fractional while the next stroke is about to appear.
+    progress: f64,
+    /// The speed in strokes per second.
+    speed: f64,
+    last_frame: Option<Instant>,
+}
+
+impl Replay {
+    pub const SPEED_MIN: f64 = 0.5;
+    pub const SPEED_MAX: f64 = 100.0;
+    pub const SPEED_DEFAULT: f64 = 5.0;
+    /// The margin around the content when exporting the replay.
+    const EXPORT_MARGIN: f64 = 12.0;
+    /// The maximum number of frames of an exported replay. Strokes are grouped into frames beyond it.
+    const EXPORT_FRAMES_MAX: usize = 300;
+    /// The maximum width and height of the exported replay in pixels. Larger documents are scaled down.
+    const EXPORT_SIZE_MAX: f64 = 1920.0;
+    /// The time the last frame of an exported replay is shown before it loops.
+    const EXPORT_LAST_FRAME_HOLD_MS: u32 = 2000;
+
+    fn new(keys: Vec<StrokeKey>, speed: f64) -> Self {
+        Self {
+            keys,
+            progress: 0.0,
+            speed: speed.clamp(Self::SPEED_MIN, Self::SPEED_MAX),
+            last_frame: None,
+        }
+    }
+
+    fn finished(&self) -> bool {
+        self.progress >= self.keys.len() as f64
+    }
+
+    fn revealed_keys(&self) -> &[StrokeKey] {
+        let n_revealed = (self.progress.floor() as usize).min(self.keys.len());
+        &self.keys[..n_revealed]
+    }
+
+    fn advance(&mut self, now: Instant) {
+        if let Some(last_frame) = self.last_frame {
+            let elapsed = now.saturating_duration_since(last_frame).as_secs_f64();
+            self.progress = (self.progress + elapsed * self.speed).min(self.keys.len() as f64);
+        }
+        self.last_frame = Some(now);
+    }
+}
+
+impl Engine {
+    pub fn replay_active(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Start replaying the strokes of the document in the order they were drawn.
+    ///
+    /// The speed is in strokes per second. Pen input is ignored until the replay is stopped.
+    pub fn start_replay(&mut self, speed: f64) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.store
+            .set_selected_keys(&self.store.selection_keys_as_rendered(), false);
+        widget_flags |= self.current_pen_update_state();
+        self.replay = Some(Replay::new(self.store.stroke_keys_drawing_order(), speed));
+        self.animation.claim_frame();
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// Stop the replay and show all strokes again.
+    pub fn stop_replay(&mut self) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        if self.replay.take().is_some() {
+            widget_flags.redraw = true;
+        }
+        widget_flags
+    }
+
+    pub fn set_replay_speed(&mut self, speed: f64) {
+        if let Some(replay) = self.replay.as_mut() {
+            replay.speed = speed.clamp(Replay::SPEED_MIN, Replay::SPEED_MAX);
+        }
+    }
+
+    /// The keys of the strokes that are currently revealed by the replay, None if no replay is active.
+    pub(crate) fn replay_revealed_keys(&self) -> Option<&[StrokeKey]> {
+        self.replay.as_ref().map(|replay| replay.revealed_keys())
+    }
+
+    /// Advance the replay on an animation frame and request the next one, until all strokes are revealed.
+    pub(crate) fn handle_replay_animation_frame(&mut self) {
+        let Some(replay) = self.replay.as_mut() else {
+            return;
+        };
+        replay.advance(Instant::now());
+        if !replay.finished() {
+            self.animation.claim_frame();
+        }
+    }
+
+    /// Export the replay of the document strokes in the order they were drawn as animated Gif.
+    ///
+    /// The speed is in strokes per second. Large documents are scaled down to a maximum resolution
+    /// and the strokes are grouped into a maximum number of frames.
+    ///
+    /// There is no WebM export, because encoding it would need a video encoder like libvpx as additional
+    /// native dependency on all platforms.
+    pub fn export_replay_as_gif(&self, speed: f64) -> oneshot::Receiver<anyhow::Result<Vec<u8>>> {
+        let (oneshot_sender, oneshot_receiver) = oneshot::channel::<anyhow::Result<Vec<u8>>>();
+        let strokes = self
+            .store
+            .get_strokes_arc(&self.store.stroke_keys_drawing_order());
+        let background = self.document.background.clone();
+        let speed = speed.clamp(Replay::SPEED_MIN, Replay::SPEED_MAX);
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Vec<u8>> {
+                let Some(bounds) = StrokeContent::default()
+                    .with_strokes(strokes.clone())
+                    .bounds()
+                else {
+                    return Err(anyhow::anyhow!(
+                        "Exporting replay failed, the document has no strokes."
+                    ));
+                };
+                let strokes_per_frame = strokes.len().div_ceil(Replay::EXPORT_FRAMES_MAX);
+                let frame_delay_ms = ((strokes_per_frame as f64 / speed) * 1000.0).round() as u32;
+                let n_frames = strokes.len().div_ceil(strokes_per_frame);
+                let export_bounds = bounds.loosened(Replay::EXPORT_MARGIN);
+                let image_scale =
+                    (Replay::EXPORT_SIZE_MAX / export_bounds.extents().max()).min(1.0);
+
+                let mut bytes = Vec::new();
+                {
+                    let mut encoder = image::codecs::gif::GifEncoder::new(&mut bytes);
+                    encoder.set_repeat(image::codecs::gif::Repeat::Infinite)?;
+
+                    for i in 1..=n_frames {
+                        let n_revealed = (i * strokes_per_frame).min(strokes.len());
+                        let content = StrokeContent::default()
+                            .with_strokes(strokes[..n_revealed].to_vec())
+                            .with_bounds(Some(bounds))
+                            .with_background(Some(background.clone()));
+                        let frame_image = render::Image::gen_with_cairo(
+                            |cairo_cx| {
+                                content.draw_to_cairo(
+                                    cairo_cx,
+                                    true,
+                                    true,
+                                    false,
+                                    Replay::EXPORT_MARGIN,
+                                    1.0,
+                                )
+                            },
+                            export_bounds,
+                            image_scale,
+                        )?;
+                        let delay_ms = if i == n_frames {
+                            frame_delay_ms + Replay::EXPORT_LAST_FRAME_HOLD_MS
+                        } else {
+                            frame_delay_ms
+                        };
+                        encoder
+                            .encode_frame(image::Frame::from_parts(
+                                frame_image.into_imgbuf()?,
+                                0,
+                                0,
+                                image::Delay::from_numer_denom_ms(delay_ms, 1),
+                            ))
+                            .context("Encoding replay frame failed.")?;
+                    }
+                }
+                Ok(bytes)
+            };
+            if oneshot_sender.send(result()).is_err() {
+                error!("Sending result to receiver failed while exporting replay as Gif bytes. Receiver already dropped.");
+            }
+        });
+
+        oneshot_receiver
+    }
+}
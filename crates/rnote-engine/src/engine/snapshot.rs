@@ -1,14 +1,19 @@
 // Imports
-use crate::document::background;
-use crate::engine::import::XoppImportPrefs;
+use crate::document::{background, DocumentMetadata};
+use crate::engine::import::{PdfImportPrefs, XoppImportPrefs};
+use crate::engine::profiling::{self, ProfileCategory};
+use crate::fileformats::rnoteformat::RevisionHistory;
 use crate::fileformats::{rnoteformat, xoppformat, FileFormatLoader};
-use crate::store::{ChronoComponent, StrokeKey};
-use crate::strokes::Stroke;
+use crate::pens::{PenStyle, PensConfig};
+use crate::store::chrono_comp::StrokeLayer;
+use crate::store::{ChronoComponent, LinkComponent, LockComponent, StrokeKey};
+use crate::strokes::{Stroke, VectorImage};
 use crate::{Camera, Document, Engine};
 use anyhow::Context;
 use futures::channel::oneshot;
 use serde::{Deserialize, Serialize};
 use slotmap::{HopSlotMap, SecondaryMap};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tracing::error;
 
@@ -24,8 +29,33 @@ pub struct EngineSnapshot {
     pub stroke_components: Arc<HopSlotMap<StrokeKey, Arc<Stroke>>>,
     #[serde(rename = "chrono_components")]
     pub chrono_components: Arc<SecondaryMap<StrokeKey, Arc<ChronoComponent>>>,
+    /// The lock components.
+    ///
+    /// Is empty for files saved by older versions, where all strokes are unlocked.
+    #[serde(rename = "lock_components")]
+    pub lock_components: Arc<SecondaryMap<StrokeKey, Arc<LockComponent>>>,
+    /// The link components.
+    ///
+    /// Is empty for files saved by older versions, where no strokes are linked.
+    #[serde(rename = "link_components")]
+    pub link_components: Arc<SecondaryMap<StrokeKey, Arc<LinkComponent>>>,
     #[serde(rename = "chrono_counter")]
     pub chrono_counter: u32,
+    /// The pens configuration the document was last edited with.
+    ///
+    /// Is `None` for files saved by older versions and for imported documents.
+    #[serde(rename = "pens_config")]
+    pub pens_config: Option<PensConfig>,
+    /// The pen style that was active when the document was saved.
+    ///
+    /// Is `None` for files saved by older versions and for imported documents.
+    #[serde(rename = "pen_style")]
+    pub pen_style: Option<PenStyle>,
+    /// The document metadata.
+    ///
+    /// Is empty for files saved by older versions.
+    #[serde(rename = "metadata")]
+    pub metadata: DocumentMetadata,
 }
 
 impl Default for EngineSnapshot {
@@ -35,12 +65,25 @@ impl Default for EngineSnapshot {
             camera: Camera::default(),
             stroke_components: Arc::new(HopSlotMap::with_key()),
             chrono_components: Arc::new(SecondaryMap::new()),
+            lock_components: Arc::new(SecondaryMap::new()),
+            link_components: Arc::new(SecondaryMap::new()),
             chrono_counter: 0,
+            pens_config: None,
+            pen_style: None,
+            metadata: DocumentMetadata::default(),
         }
     }
 }
 
 impl EngineSnapshot {
+    /// Discard the stored view state, so that the document is opened at the origin with the default zoom
+    /// and the current pen style is kept.
+    pub fn discard_view_state(&mut self) {
+        let _ = self.camera.zoom_to(Camera::ZOOM_DEFAULT);
+        let _ = self.camera.set_offset(na::Vector2::zeros(), &self.document);
+        self.pen_style = None;
+    }
+
     /// Loads a snapshot from the bytes of a .rnote file.
     ///
     /// To import this snapshot into the current engine, use [`Engine::load_snapshot()`].
@@ -49,6 +92,7 @@ impl EngineSnapshot {
 
         rayon::spawn(move || {
             let result = || -> anyhow::Result<Self> {
+                let _span = profiling::span(ProfileCategory::Load);
                 let rnote_file = rnoteformat::RnoteFile::load_from_bytes(&bytes)
                     .context("loading RnoteFile from bytes failed.")?;
                 Ok(ijson::from_value(&rnote_file.engine_snapshot)?)
@@ -63,11 +107,69 @@ impl EngineSnapshot {
 
         snapshot_receiver.await?
     }
+
+    /// Loads the revision history from the bytes of a .rnote file.
+    pub async fn load_revision_history_from_rnote_bytes(
+        bytes: Vec<u8>,
+    ) -> anyhow::Result<RevisionHistory> {
+        let (history_sender, history_receiver) =
+            oneshot::channel::<anyhow::Result<RevisionHistory>>();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<RevisionHistory> {
+                let rnote_file = rnoteformat::RnoteFile::load_from_bytes(&bytes)
+                    .context("loading RnoteFile from bytes failed.")?;
+                Ok(rnote_file.revision_history)
+            };
+
+            if history_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while loading the revision history from rnote bytes. Receiver already dropped."
+                );
+            }
+        });
+
+        history_receiver.await?
+    }
+
+    /// Loads a snapshot of a previous revision from the bytes of a .rnote file.
+    ///
+    /// The index is the position in the revision history, starting with the newest revision.
+    pub async fn load_revision_from_rnote_bytes(
+        bytes: Vec<u8>,
+        index: usize,
+    ) -> anyhow::Result<Self> {
+        let (snapshot_sender, snapshot_receiver) = oneshot::channel::<anyhow::Result<Self>>();
+
+        rayon::spawn(move || {
+            let result = || -> anyhow::Result<Self> {
+                let rnote_file = rnoteformat::RnoteFile::load_from_bytes(&bytes)
+                    .context("loading RnoteFile from bytes failed.")?;
+                let revision_snapshot = rnote_file
+                    .revision_history
+                    .restore(&rnote_file.engine_snapshot, index)?;
+                Ok(ijson::from_value(&revision_snapshot)?)
+            };
+
+            if snapshot_sender.send(result()).is_err() {
+                error!(
+                    "Sending result to receiver failed while loading a revision from rnote bytes. Receiver already dropped."
+                );
+            }
+        });
+
+        snapshot_receiver.await?
+    }
+
     /// Loads from the bytes of a Xournal++ .xopp file.
     ///
+    /// The file path of the .xopp file is needed to resolve background pdfs that are referenced relative to it
+    /// or attached to it.
+    ///
     /// To import this snapshot into the current engine, use [`Engine::load_snapshot()`].
     pub async fn load_from_xopp_bytes(
         bytes: Vec<u8>,
+        file_path: Option<PathBuf>,
         xopp_import_prefs: XoppImportPrefs,
     ) -> anyhow::Result<Self> {
         let (snapshot_sender, snapshot_receiver) = oneshot::channel::<anyhow::Result<Self>>();
@@ -138,9 +240,70 @@ impl EngineSnapshot {
 
                 // Offsetting as rnote has one global coordinate space
                 let mut offset = na::Vector2::<f64>::zeros();
+                // Subsequent pages only reference the background pdf by its page number
+                let mut background_pdf_bytes: Option<Vec<u8>> = None;
 
                 for page in xopp_file.xopp_root.pages.into_iter() {
-                    for layers in page.layers.into_iter() {
+                    if let xoppformat::XoppBackgroundType::Pdf {
+                        domain,
+                        filename,
+                        pageno,
+                    } = &page.background.bg_type
+                    {
+                        if let Some(filename) = filename {
+                            match read_xopp_background_pdf(
+                                file_path.as_deref(),
+                                domain.as_ref(),
+                                filename,
+                            ) {
+                                Ok(bytes) => background_pdf_bytes = Some(bytes),
+                                Err(e) => {
+                                    error!("Reading background pdf `{filename}` failed while loading Xopp bytes, Err: {e:?}");
+                                    background_pdf_bytes = None;
+                                }
+                            }
+                        }
+
+                        if let Some(bytes) = &background_pdf_bytes {
+                            let mut page_format = engine.document.format;
+                            page_format.set_width(crate::utils::convert_value_dpi(
+                                page.width,
+                                xoppformat::XoppFile::DPI,
+                                xopp_import_prefs.dpi,
+                            ));
+                            let pdf_import_prefs = PdfImportPrefs {
+                                page_borders: false,
+                                adjust_document: true,
+                                color_remap: xopp_import_prefs.color_remap,
+                                ..Default::default()
+                            };
+                            let page_i = pageno.saturating_sub(1);
+
+                            match VectorImage::from_pdf_bytes(
+                                bytes,
+                                pdf_import_prefs,
+                                offset,
+                                Some(vec![page_i]),
+                                &page_format,
+                                None,
+                                None,
+                            ) {
+                                Ok(images) => {
+                                    for image in images {
+                                        engine.store.insert_stroke(
+                                            Stroke::VectorImage(image),
+                                            Some(StrokeLayer::Document),
+                                        );
+                                    }
+                                }
+                                Err(e) => {
+                                    error!("Importing background pdf page failed while loading Xopp bytes, Err: {e:?}");
+                                }
+                            }
+                        }
+                    }
+
+                    for (layer_i, layers) in page.layers.into_iter().enumerate() {
                         // import strokes
                         for new_xoppstroke in layers.strokes.into_iter() {
                             match Stroke::from_xoppstroke(
@@ -149,6 +312,13 @@ impl EngineSnapshot {
                                 xopp_import_prefs.dpi,
                             ) {
                                 Ok((new_stroke, layer)) => {
+                                    // Preserve the layer structure of the xopp page
+                                    let layer = match layer {
+                                        StrokeLayer::UserLayer(_) => {
+                                            StrokeLayer::UserLayer(layer_i as u32)
+                                        }
+                                        layer => layer,
+                                    };
                                     engine.store.insert_stroke(new_stroke, Some(layer));
                                 }
                                 Err(e) => {
@@ -176,6 +346,24 @@ impl EngineSnapshot {
                                 }
                             }
                         }
+
+                        // import texts
+                        for new_xopptext in layers.texts.into_iter() {
+                            match Stroke::from_xopptext(new_xopptext, offset, xopp_import_prefs.dpi)
+                            {
+                                Ok(new_text) => {
+                                    engine.store.insert_stroke(
+                                        new_text,
+                                        Some(StrokeLayer::UserLayer(layer_i as u32)),
+                                    );
+                                }
+                                Err(e) => {
+                                    error!(
+                                        "Creating Stroke from XoppText failed while loading Xopp bytes, Err: {e:?}",
+                                    );
+                                }
+                            }
+                        }
                     }
 
                     // Only add to y offset, results in vertical pages
@@ -186,7 +374,22 @@ impl EngineSnapshot {
                     );
                 }
 
-                Ok(engine.take_snapshot())
+                if xopp_import_prefs.color_remap.enabled {
+                    let color_remap = xopp_import_prefs.color_remap;
+                    for key in engine.store.stroke_keys_unordered() {
+                        if let Some(stroke) = engine.store.get_stroke_mut(key) {
+                            stroke.map_colors(|color| color_remap.remap_color(color));
+                        }
+                    }
+                    let background = &mut engine.document.background;
+                    background.color = color_remap.remap_color(background.color);
+                    background.pattern_color = color_remap.remap_color(background.pattern_color);
+                }
+
+                let mut snapshot = engine.take_snapshot();
+                // Keep the current pens configuration when importing
+                snapshot.pens_config = None;
+                Ok(snapshot)
             };
 
             if snapshot_sender.send(result()).is_err() {
@@ -197,3 +400,32 @@ impl EngineSnapshot {
         snapshot_receiver.await?
     }
 }
+
+/// Reads the bytes of a background pdf referenced in a Xournal++ file.
+fn read_xopp_background_pdf(
+    xopp_file_path: Option<&Path>,
+    domain: Option<&xoppformat::XoppBackgroundPixmapDomain>,
+    filename: &str,
+) -> anyhow::Result<Vec<u8>> {
+    let pdf_path = match domain {
+        Some(xoppformat::XoppBackgroundPixmapDomain::Attach) => {
+            let xopp_file_path = xopp_file_path
+                .ok_or_else(|| anyhow::anyhow!("Attached pdf needs the path of the Xopp file"))?;
+            PathBuf::from(format!("{}.{filename}", xopp_file_path.display()))
+        }
+        _ => {
+            let pdf_path = PathBuf::from(filename);
+            match (pdf_path.exists(), xopp_file_path) {
+                // The pdf might have been moved together with the Xopp file
+                (false, Some(xopp_file_path)) => xopp_file_path
+                    .parent()
+                    .zip(pdf_path.file_name())
+                    .map(|(dir, name)| dir.join(name))
+                    .unwrap_or(pdf_path),
+                _ => pdf_path,
+            }
+        }
+    };
+    std::fs::read(&pdf_path)
+        .with_context(|| format!("Reading pdf file `{}` failed", pdf_path.display()))
+}
@@ -6,6 +6,7 @@ use crate::Drawable;
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::shapes::Shapeable;
 use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
 use std::sync::Arc;
 use tracing::warn;
 
@@ -26,6 +27,11 @@ pub struct StrokeContent {
 impl StrokeContent {
     pub const MIME_TYPE: &'static str = "application/rnote-stroke-content";
     pub const CLIPBOARD_EXPORT_MARGIN: f64 = 6.0;
+    /// The Xml namespace of the element the content is embedded with into exported Svgs.
+    pub const SVG_METADATA_NAMESPACE: &'static str = "https://rnote.flxzt.net/ns/stroke-content";
+    const SVG_METADATA_ELEMENT: &'static str = "stroke-content";
+    /// The maximum size of the decompressed embedded content, guarding against decompression bombs in imported Svgs.
+    const SVG_METADATA_DECOMPRESSED_MAX_LEN: u64 = 256 * 1024 * 1024;
 
     pub fn with_bounds(mut self, bounds: Option<Aabb>) -> Self {
         self.bounds = bounds;
@@ -61,6 +67,73 @@ impl StrokeContent {
         self.bounds().map(|b| b.extents())
     }
 
+    /// Serialize the content into a Svg `metadata` element, so that the strokes can be restored
+    /// when the Svg is imported again.
+    ///
+    /// The content is stored as gzip compressed json, encoded in base64.
+    pub fn gen_svg_metadata(&self) -> anyhow::Result<String> {
+        let json = serde_json::to_vec(self)?;
+        let mut encoder =
+            flate2::write::GzEncoder::new(Vec::<u8>::new(), flate2::Compression::new(5));
+        encoder.write_all(&json)?;
+        let encoded = base64::Engine::encode(
+            &base64::engine::general_purpose::STANDARD,
+            encoder.finish()?,
+        );
+        Ok(format!(
+            "<metadata><rnote:{element} xmlns:rnote=\"{namespace}\">{encoded}</rnote:{element}></metadata>",
+            element = Self::SVG_METADATA_ELEMENT,
+            namespace = Self::SVG_METADATA_NAMESPACE
+        ))
+    }
+
+    /// Restore the content that was embedded into the Svg with [StrokeContent::gen_svg_metadata].
+    ///
+    /// Returns Ok(None) if the Svg does not contain embedded content.
+    pub fn from_svg_metadata(svg_str: &str) -> anyhow::Result<Option<Self>> {
+        // Avoid parsing arbitrary Svgs that can't contain the content
+        if !svg_str.contains(Self::SVG_METADATA_NAMESPACE) {
+            return Ok(None);
+        }
+        let parsed_doc = roxmltree::Document::parse_with_options(
+            svg_str,
+            roxmltree::ParsingOptions {
+                allow_dtd: true,
+                ..Default::default()
+            },
+        )?;
+        let Some(encoded) = parsed_doc
+            .descendants()
+            .find(|node| {
+                node.has_tag_name((Self::SVG_METADATA_NAMESPACE, Self::SVG_METADATA_ELEMENT))
+            })
+            .and_then(|node| node.text())
+        else {
+            return Ok(None);
+        };
+        let compressed =
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, encoded.trim())?;
+        let json = Self::decompress_svg_metadata(
+            compressed.as_slice(),
+            Self::SVG_METADATA_DECOMPRESSED_MAX_LEN,
+        )?;
+        Ok(Some(serde_json::from_slice(&json)?))
+    }
+
+    /// Decompress the embedded content, erroring if it exceeds the given length.
+    fn decompress_svg_metadata(compressed: &[u8], max_len: u64) -> anyhow::Result<Vec<u8>> {
+        let mut decompressed = Vec::new();
+        flate2::read::GzDecoder::new(compressed)
+            .take(max_len + 1)
+            .read_to_end(&mut decompressed)?;
+        if decompressed.len() as u64 > max_len {
+            return Err(anyhow::anyhow!(
+                "Decompressed embedded stroke content exceeds the maximum size of {max_len} bytes."
+            ));
+        }
+        Ok(decompressed)
+    }
+
     /// Generate a Svg from the content.
     ///
     /// Moves the bounds to mins: [0.0, 0.0], maxs: extents.
@@ -144,8 +217,8 @@ impl StrokeContent {
             .strokes
             .iter()
             .filter_map(|stroke| match stroke.as_ref() {
-                Stroke::BitmapImage(image) => Some(image.rectangle.bounds()),
-                Stroke::VectorImage(image) => Some(image.rectangle.bounds()),
+                Stroke::BitmapImage(image) => Some(image.bounds()),
+                Stroke::VectorImage(image) => Some(image.bounds()),
                 _ => None,
             })
             .collect::<Vec<Aabb>>();
@@ -175,3 +248,67 @@ impl StrokeContent {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::StrokeContent;
+    use crate::strokes::{ShapeStroke, Stroke};
+    use approx::assert_relative_eq;
+    use rnote_compose::shapes::{Rectangle, Shape};
+    use rnote_compose::Style;
+    use std::io::Write;
+    use std::sync::Arc;
+
+    #[test]
+    fn svg_metadata_roundtrip() {
+        let content = StrokeContent::default().with_strokes(vec![Arc::new(Stroke::ShapeStroke(
+            ShapeStroke::new(
+                Shape::Rectangle(Rectangle::from_corners(
+                    na::vector![10.0, 20.0],
+                    na::vector![110.0, 220.0],
+                )),
+                Style::default(),
+            ),
+        ))]);
+        // Embedded the same way as when exporting the selection as Svg
+        let mut svg = content.gen_svg(false, false, false, 0.0).unwrap().unwrap();
+        svg.svg_data.push_str(&content.gen_svg_metadata().unwrap());
+        let svg_str = rnote_compose::utils::add_xml_header(&rnote_compose::utils::wrap_svg_root(
+            svg.svg_data.as_str(),
+            Some(svg.bounds),
+            Some(svg.bounds),
+            false,
+        ));
+
+        let restored = StrokeContent::from_svg_metadata(&svg_str).unwrap().unwrap();
+        assert_eq!(restored.strokes.len(), 1);
+        let bounds = content.bounds().unwrap();
+        let restored_bounds = restored.bounds().unwrap();
+        assert_relative_eq!(restored_bounds.mins, bounds.mins);
+        assert_relative_eq!(restored_bounds.maxs, bounds.maxs);
+    }
+
+    #[test]
+    fn svg_metadata_absent() {
+        let svg_str = rnote_compose::utils::wrap_svg_root("", None, None, false);
+        assert!(StrokeContent::from_svg_metadata(&svg_str)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn svg_metadata_decompressed_len_limited() {
+        let mut encoder =
+            flate2::write::GzEncoder::new(Vec::<u8>::new(), flate2::Compression::new(5));
+        encoder.write_all(&[0; 1024]).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(
+            StrokeContent::decompress_svg_metadata(&compressed, 1024)
+                .unwrap()
+                .len(),
+            1024
+        );
+        assert!(StrokeContent::decompress_svg_metadata(&compressed, 1023).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+// Imports
+use super::{Engine, StrokeContent};
+use crate::strokes::resize::ImageSizeOption;
+use crate::strokes::{ShapeStroke, Stroke};
+use crate::WidgetFlags;
+use rnote_compose::shapes::{Line, Shape};
+use rnote_compose::style::smooth::SmoothOptions;
+use rnote_compose::style::PressureCurve;
+use rnote_compose::{Color, Style};
+use std::sync::Arc;
+
+/// Generates the lines of a table or grid with evenly sized cells.
+#[derive(Debug, Clone, Copy)]
+pub struct TableGenerator {
+    pub rows: u32,
+    pub columns: u32,
+    /// The width of a cell, in document coordinates.
+    pub cell_width: f64,
+    /// The height of a cell, in document coordinates.
+    pub cell_height: f64,
+    pub line_width: f64,
+    pub line_color: Color,
+}
+
+impl Default for TableGenerator {
+    fn default() -> Self {
+        Self {
+            rows: 3,
+            columns: 3,
+            cell_width: 120.0,
+            cell_height: 40.0,
+            line_width: 2.0,
+            line_color: Color::BLACK,
+        }
+    }
+}
+
+impl TableGenerator {
+    pub const CELLS_MAX: u32 = 100;
+    pub const CELL_SIZE_MIN: f64 = 4.0;
+    pub const CELL_SIZE_MAX: f64 = 2000.0;
+    pub const LINE_WIDTH_MIN: f64 = 0.1;
+    pub const LINE_WIDTH_MAX: f64 = 50.0;
+
+    /// Clamps the parameters to their allowed ranges.
+    pub fn clamped(mut self) -> Self {
+        self.rows = self.rows.clamp(1, Self::CELLS_MAX);
+        self.columns = self.columns.clamp(1, Self::CELLS_MAX);
+        self.cell_width = self
+            .cell_width
+            .clamp(Self::CELL_SIZE_MIN, Self::CELL_SIZE_MAX);
+        self.cell_height = self
+            .cell_height
+            .clamp(Self::CELL_SIZE_MIN, Self::CELL_SIZE_MAX);
+        self.line_width = self
+            .line_width
+            .clamp(Self::LINE_WIDTH_MIN, Self::LINE_WIDTH_MAX);
+        self
+    }
+
+    /// The size of the entire table.
+    pub fn size(&self) -> na::Vector2<f64> {
+        na::vector![
+            self.cell_width * f64::from(self.columns),
+            self.cell_height * f64::from(self.rows)
+        ]
+    }
+
+    /// Generate the horizontal and vertical lines of the table as shape strokes, with the top left corner at the origin.
+    pub fn gen_strokes(&self) -> Vec<Stroke> {
+        let Self {
+            rows,
+            columns,
+            cell_width,
+            cell_height,
+            ..
+        } = *self;
+        let width = cell_width * f64::from(columns);
+        let height = cell_height * f64::from(rows);
+
+        let mut options = SmoothOptions::default();
+        options.stroke_width = self.line_width;
+        options.stroke_color = Some(self.line_color);
+        options.pressure_curve = PressureCurve::Const;
+        let style = Style::Smooth(options);
+
+        let horizontal_lines = (0..=rows).map(|row| {
+            let y = cell_height * f64::from(row);
+            Line {
+                start: na::vector![0.0, y],
+                end: na::vector![width, y],
+            }
+        });
+        let vertical_lines = (0..=columns).map(|column| {
+            let x = cell_width * f64::from(column);
+            Line {
+                start: na::vector![x, 0.0],
+                end: na::vector![x, height],
+            }
+        });
+
+        horizontal_lines
+            .chain(vertical_lines)
+            .map(|line| Stroke::ShapeStroke(ShapeStroke::new(Shape::Line(line), style.clone())))
+            .collect()
+    }
+}
+
+impl Engine {
+    /// Insert the lines of the generated table centered in the viewport.
+    ///
+    /// The lines are selected afterwards, so that they can be moved together.
+    pub fn insert_table(&mut self, table: TableGenerator) -> WidgetFlags {
+        let table = table.clamped();
+        let strokes = table.gen_strokes().into_iter().map(Arc::new).collect();
+        let pos = self.camera.viewport_center() - table.size() * 0.5;
+
+        self.insert_stroke_content(
+            StrokeContent::default().with_strokes(strokes),
+            pos,
+            ImageSizeOption::RespectOriginalSize,
+        )
+    }
+}
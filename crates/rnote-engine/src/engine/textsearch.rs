@@ -0,0 +1,303 @@
+// Imports
+use super::Engine;
+use crate::store::StrokeKey;
+use crate::strokes::Stroke;
+use crate::WidgetFlags;
+use p2d::bounding_volume::Aabb;
+use rnote_compose::shapes::Shapeable;
+use std::ops::Range;
+use std::time::Instant;
+
+/// A match of a text search in a text stroke.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextMatch {
+    /// The key of the text stroke.
+    pub key: StrokeKey,
+    /// The byte range of the match in the text.
+    pub range: Range<usize>,
+}
+
+/// The scope of a text replacement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextReplaceScope {
+    /// Only the given match.
+    Match(TextMatch),
+    /// All matches in the selected text strokes.
+    Selection,
+    /// All matches in the document.
+    Document,
+}
+
+impl Engine {
+    /// Search the text strokes for the query.
+    ///
+    /// The matches are ordered from top to bottom, then left to right by the position of their stroke,
+    /// and in text order within a stroke.
+    pub fn search_text(&self, query: &str, match_case: bool) -> Vec<TextMatch> {
+        self.search_text_in_strokes(&self.store.stroke_keys_as_rendered(), query, match_case)
+    }
+
+    /// Replace the occurrences of the query in the given scope.
+    ///
+    /// Locked text strokes are left unchanged. Returns the number of replaced matches.
+    pub fn replace_text_matches(
+        &mut self,
+        query: &str,
+        replacement: &str,
+        match_case: bool,
+        scope: TextReplaceScope,
+    ) -> (usize, WidgetFlags) {
+        let matches = match scope {
+            TextReplaceScope::Match(text_match) => self
+                .search_text_in_strokes(&[text_match.key], query, match_case)
+                .into_iter()
+                .filter(|m| *m == text_match)
+                .collect(),
+            TextReplaceScope::Selection => self.search_text_in_strokes(
+                &self.store.selection_keys_as_rendered(),
+                query,
+                match_case,
+            ),
+            TextReplaceScope::Document => self.search_text(query, match_case),
+        };
+
+        let mut modified_keys: Vec<StrokeKey> = vec![];
+        let mut n_replaced = 0;
+        // Replace back to front, so that the ranges of the remaining matches in a stroke stay valid
+        for text_match in matches.into_iter().rev() {
+            if self.store.locked(text_match.key).unwrap_or(false) {
+                continue;
+            }
+            let Some(Stroke::TextStroke(textstroke)) = self.store.get_stroke_mut(text_match.key)
+            else {
+                continue;
+            };
+            textstroke.replace_text_range(text_match.range, replacement);
+            n_replaced += 1;
+            if !modified_keys.contains(&text_match.key) {
+                modified_keys.push(text_match.key);
+            }
+        }
+        if modified_keys.is_empty() {
+            return (0, WidgetFlags::default());
+        }
+
+        self.store.update_geometry_for_strokes(&modified_keys);
+        let mut widget_flags = self.current_pen_update_state()
+            | self.doc_resize_autoexpand()
+            | self.record(Instant::now())
+            | self.update_content_rendering_current_viewport();
+        widget_flags.store_modified = true;
+        (n_replaced, widget_flags)
+    }
+
+    /// Center the viewport on the stroke of the text match.
+    pub fn show_text_match(&mut self, text_match: &TextMatch) -> WidgetFlags {
+        let Some(bounds) = self
+            .store
+            .get_stroke_ref(text_match.key)
+            .map(|stroke| stroke.bounds())
+        else {
+            return WidgetFlags::default();
+        };
+        self.camera.set_viewport_center(bounds.center().coords)
+            | self.doc_expand_autoexpand()
+            | self.update_rendering_current_viewport()
+    }
+
+    fn search_text_in_strokes(
+        &self,
+        keys: &[StrokeKey],
+        query: &str,
+        match_case: bool,
+    ) -> Vec<TextMatch> {
+        let mut textstrokes = keys
+            .iter()
+            .filter_map(|&key| match self.store.get_stroke_ref(key) {
+                Some(Stroke::TextStroke(textstroke)) => Some((key, textstroke)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        textstrokes.sort_by(|(_, a), (_, b)| {
+            let (a, b): (Aabb, Aabb) = (a.bounds(), b.bounds());
+            a.mins[1]
+                .total_cmp(&b.mins[1])
+                .then(a.mins[0].total_cmp(&b.mins[0]))
+        });
+        textstrokes
+            .into_iter()
+            .flat_map(|(key, textstroke)| {
+                textstroke
+                    .find_text_matches(query, match_case)
+                    .into_iter()
+                    .map(move |range| TextMatch { key, range })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::textsearch::{TextMatch, TextReplaceScope};
+    use crate::store::StrokeKey;
+    use crate::strokes::textstroke::{RangedTextAttribute, TextAttribute, TextStyle};
+    use crate::strokes::{Stroke, TextStroke};
+    use crate::Engine;
+
+    fn insert_text(engine: &mut Engine, text: &str, pos: na::Vector2<f64>) -> StrokeKey {
+        engine.store.insert_stroke(
+            Stroke::TextStroke(TextStroke::new(
+                String::from(text),
+                pos,
+                TextStyle::default(),
+            )),
+            None,
+        )
+    }
+
+    fn text(engine: &Engine, key: StrokeKey) -> String {
+        match engine.store.get_stroke_ref(key) {
+            Some(Stroke::TextStroke(textstroke)) => textstroke.text.clone(),
+            _ => panic!("not a text stroke"),
+        }
+    }
+
+    #[test]
+    fn find_text_matches() {
+        let textstroke = TextStroke::new(
+            String::from("Foo foo fOO fooo"),
+            na::Vector2::zeros(),
+            TextStyle::default(),
+        );
+        assert_eq!(
+            textstroke.find_text_matches("foo", true),
+            vec![4..7, 12..15]
+        );
+        assert_eq!(
+            textstroke.find_text_matches("foo", false),
+            vec![0..3, 4..7, 8..11, 12..15]
+        );
+        assert!(textstroke.find_text_matches("", false).is_empty());
+        assert!(textstroke.find_text_matches("bar", false).is_empty());
+
+        // non-overlapping
+        let textstroke = TextStroke::new(
+            String::from("aaaa"),
+            na::Vector2::zeros(),
+            TextStyle::default(),
+        );
+        assert_eq!(textstroke.find_text_matches("aa", true), vec![0..2, 2..4]);
+
+        // byte ranges of multi-byte characters
+        let textstroke = TextStroke::new(
+            String::from("Grüße, GRÜSSE"),
+            na::Vector2::zeros(),
+            TextStyle::default(),
+        );
+        assert_eq!(
+            textstroke.find_text_matches("grü", false),
+            vec![0..4, 9..13]
+        );
+    }
+
+    #[test]
+    fn replace_text_range_translates_attrs() {
+        let mut textstroke = TextStroke::new(
+            String::from("abc"),
+            na::Vector2::zeros(),
+            TextStyle::default(),
+        );
+        textstroke.text_style.ranged_text_attributes = vec![RangedTextAttribute {
+            range: 2..3,
+            attribute: TextAttribute::FontWeight(700),
+        }];
+
+        textstroke.replace_text_range(1..2, "XYZ");
+        assert_eq!(textstroke.text, "aXYZc");
+        assert_eq!(textstroke.text_style.ranged_text_attributes[0].range, 4..5);
+
+        textstroke.replace_text_range(1..4, "");
+        assert_eq!(textstroke.text, "ac");
+        assert_eq!(textstroke.text_style.ranged_text_attributes[0].range, 1..2);
+    }
+
+    #[test]
+    fn search_order() {
+        let mut engine = Engine::default();
+        let below = insert_text(&mut engine, "foo", na::vector![0.0, 500.0]);
+        let right = insert_text(&mut engine, "foo foo", na::vector![300.0, 0.0]);
+        let left = insert_text(&mut engine, "foo", na::vector![0.0, 0.0]);
+
+        let keys = engine
+            .search_text("foo", true)
+            .into_iter()
+            .map(|m| m.key)
+            .collect::<Vec<StrokeKey>>();
+        assert_eq!(keys, vec![left, right, right, below]);
+    }
+
+    #[test]
+    fn replace_in_scopes() {
+        let mut engine = Engine::default();
+        let first = insert_text(&mut engine, "foo foo", na::vector![0.0, 0.0]);
+        let second = insert_text(&mut engine, "Foo", na::vector![0.0, 500.0]);
+
+        let (n_replaced, _) = engine.replace_text_matches(
+            "foo",
+            "bar",
+            true,
+            TextReplaceScope::Match(TextMatch {
+                key: first,
+                range: 4..7,
+            }),
+        );
+        assert_eq!(n_replaced, 1);
+        assert_eq!(text(&engine, first), "foo bar");
+
+        engine.store.set_selected(second, true);
+        let (n_replaced, _) =
+            engine.replace_text_matches("foo", "baz", false, TextReplaceScope::Selection);
+        assert_eq!(n_replaced, 1);
+        assert_eq!(text(&engine, first), "foo bar");
+        assert_eq!(text(&engine, second), "baz");
+
+        let (n_replaced, _) =
+            engine.replace_text_matches("ba", "qu", true, TextReplaceScope::Document);
+        assert_eq!(n_replaced, 2);
+        assert_eq!(text(&engine, first), "foo qur");
+        assert_eq!(text(&engine, second), "quz");
+
+        // a stale match is not replaced
+        let (n_replaced, _) = engine.replace_text_matches(
+            "foo",
+            "bar",
+            true,
+            TextReplaceScope::Match(TextMatch {
+                key: first,
+                range: 1..4,
+            }),
+        );
+        assert_eq!(n_replaced, 0);
+        assert_eq!(text(&engine, first), "foo qur");
+    }
+
+    #[test]
+    fn replace_skips_locked() {
+        let mut engine = Engine::default();
+        let locked = insert_text(&mut engine, "foo", na::vector![0.0, 0.0]);
+        let unlocked = insert_text(&mut engine, "foo", na::vector![0.0, 500.0]);
+        engine.store.set_locked(locked, true);
+
+        let (n_replaced, widget_flags) =
+            engine.replace_text_matches("foo", "bar", true, TextReplaceScope::Document);
+        assert_eq!(n_replaced, 1);
+        assert!(widget_flags.store_modified);
+        assert_eq!(text(&engine, locked), "foo");
+        assert_eq!(text(&engine, unlocked), "bar");
+
+        let (n_replaced, widget_flags) =
+            engine.replace_text_matches("foo", "bar", true, TextReplaceScope::Document);
+        assert_eq!(n_replaced, 0);
+        assert!(!widget_flags.store_modified);
+    }
+}
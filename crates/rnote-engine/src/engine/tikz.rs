@@ -0,0 +1,260 @@
+// Imports
+use super::Engine;
+use crate::strokes::Stroke;
+use rnote_compose::shapes::{
+    Arrow, BezierPath, CappedLine, CubicBezier, Ellipse, Line, LineCap, Polygon, Polyline,
+    QuadraticBezier, Rectangle, Shape,
+};
+use rnote_compose::{Color, Style};
+
+/// Converts shapes from document coordinates into TikZ code.
+///
+/// TikZ coordinates are in centimeters with the y-axis pointing up,
+/// so the shapes are positioned relative to the top left corner of the converted bounds.
+#[derive(Debug, Clone, Copy)]
+struct TikzConverter {
+    origin: na::Vector2<f64>,
+}
+
+impl TikzConverter {
+    /// Document coordinates are at 96 DPI.
+    const CM_PER_PX: f64 = 2.54 / 96.0;
+    const PT_PER_PX: f64 = 72.0 / 96.0;
+
+    fn coord(&self, pos: na::Vector2<f64>) -> String {
+        let pos = pos - self.origin;
+        format!(
+            "({:.3},{:.3})",
+            pos[0] * Self::CM_PER_PX,
+            -pos[1] * Self::CM_PER_PX
+        )
+    }
+
+    fn color(color: Color) -> String {
+        let to_u8 = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        format!(
+            "{{rgb,255:red,{};green,{};blue,{}}}",
+            to_u8(color.r),
+            to_u8(color.g),
+            to_u8(color.b)
+        )
+    }
+
+    fn options(style: &Style, arrow_tips: Option<String>, closed: bool) -> String {
+        let mut options = vec![];
+        if let Some(arrow_tips) = arrow_tips {
+            options.push(arrow_tips);
+        }
+        if let Some(stroke_color) = style.stroke_color().filter(|c| c.a > 0.0) {
+            options.push(format!("draw={}", Self::color(stroke_color)));
+            if stroke_color.a < 1.0 {
+                options.push(format!("draw opacity={:.2}", stroke_color.a));
+            }
+            options.push(format!(
+                "line width={:.2}pt",
+                style.stroke_width() * Self::PT_PER_PX
+            ));
+        } else {
+            options.push(String::from("draw=none"));
+        }
+        if closed {
+            if let Some(fill_color) = style.fill_color().filter(|c| c.a > 0.0) {
+                options.push(format!("fill={}", Self::color(fill_color)));
+                if fill_color.a < 1.0 {
+                    options.push(format!("fill opacity={:.2}", fill_color.a));
+                }
+            }
+        }
+        options.join(", ")
+    }
+
+    fn path(&self, shape: &Shape) -> String {
+        match shape {
+            Shape::Line(Line { start, end }) => {
+                format!("{} -- {}", self.coord(*start), self.coord(*end))
+            }
+            Shape::Arrow(arrow) => self.arrow_path(arrow),
+            Shape::Rectangle(rectangle) => self.rectangle_path(rectangle),
+            Shape::Ellipse(ellipse) => self.ellipse_path(ellipse),
+            Shape::QuadraticBezier(QuadraticBezier { start, cp, end }) => {
+                // TikZ only supports cubic curves, so the control point is elevated
+                let cp1 = start + (cp - start) * 2.0 / 3.0;
+                let cp2 = end + (cp - end) * 2.0 / 3.0;
+                self.cubbez_path(*start, cp1, cp2, *end)
+            }
+            Shape::CubicBezier(CubicBezier {
+                start,
+                cp1,
+                cp2,
+                end,
+            }) => self.cubbez_path(*start, *cp1, *cp2, *end),
+            Shape::Polyline(Polyline { start, path }) => self.polyline_path(*start, path, false),
+            Shape::Polygon(Polygon { start, path }) => self.polyline_path(*start, path, true),
+            Shape::BezierPath(bezier_path) => self.bezier_path_path(bezier_path),
+            Shape::CappedLine(capped_line) => self.capped_line_path(capped_line),
+        }
+    }
+
+    fn arrow_path(&self, arrow: &Arrow) -> String {
+        format!("{} -- {}", self.coord(arrow.start), self.coord(arrow.tip))
+    }
+
+    fn rectangle_path(&self, rectangle: &Rectangle) -> String {
+        let half_extents = rectangle.cuboid.half_extents;
+        let corners = [
+            na::vector![-half_extents[0], -half_extents[1]],
+            na::vector![half_extents[0], -half_extents[1]],
+            na::vector![half_extents[0], half_extents[1]],
+            na::vector![-half_extents[0], half_extents[1]],
+        ];
+        let mut path = corners
+            .into_iter()
+            .map(|corner| self.coord(rectangle.transform.transform_point(corner.into()).coords))
+            .collect::<Vec<String>>()
+            .join(" -- ");
+        path.push_str(" -- cycle");
+        path
+    }
+
+    fn ellipse_path(&self, ellipse: &Ellipse) -> String {
+        let center = ellipse
+            .transform
+            .transform_point(na::Point2::origin())
+            .coords;
+        let axis_x = ellipse
+            .transform
+            .transform_vec(na::vector![ellipse.radii[0], 0.0]);
+        let axis_y = ellipse
+            .transform
+            .transform_vec(na::vector![0.0, ellipse.radii[1]]);
+        // The y-axis is flipped in TikZ, which reverses the rotation direction
+        let angle = -axis_x[1].atan2(axis_x[0]).to_degrees();
+        format!(
+            "{} ellipse [x radius={:.3}, y radius={:.3}, rotate={:.2}]",
+            self.coord(center),
+            axis_x.norm() * Self::CM_PER_PX,
+            axis_y.norm() * Self::CM_PER_PX,
+            angle
+        )
+    }
+
+    fn cubbez_path(
+        &self,
+        start: na::Vector2<f64>,
+        cp1: na::Vector2<f64>,
+        cp2: na::Vector2<f64>,
+        end: na::Vector2<f64>,
+    ) -> String {
+        format!(
+            "{} .. controls {} and {} .. {}",
+            self.coord(start),
+            self.coord(cp1),
+            self.coord(cp2),
+            self.coord(end)
+        )
+    }
+
+    fn capped_line_path(&self, capped_line: &CappedLine) -> String {
+        let mut path = format!(
+            "{} -- {}",
+            self.coord(capped_line.line.start),
+            self.coord(capped_line.line.end)
+        );
+        if let Some(text) = capped_line.dimension_text() {
+            path.push_str(&format!(" node[midway, above, sloped] {{{text}}}"));
+        }
+        path
+    }
+
+    /// The TikZ arrow tips option of the shape, if it has arrowheads or bars.
+    ///
+    /// Dots are not converted, since they need an additional TikZ library.
+    fn arrow_tips(shape: &Shape) -> Option<String> {
+        let tip = |cap: LineCap, arrow: &'static str| match cap {
+            LineCap::Arrow => arrow,
+            LineCap::Bar => "|",
+            LineCap::None | LineCap::Dot => "",
+        };
+        match shape {
+            Shape::Arrow(_) => Some(String::from("->")),
+            Shape::CappedLine(capped_line) => {
+                let start = tip(capped_line.start_cap, "<");
+                let end = tip(capped_line.end_cap, ">");
+                (!start.is_empty() || !end.is_empty()).then(|| format!("{start}-{end}"))
+            }
+            _ => None,
+        }
+    }
+
+    fn bezier_path_path(&self, bezier_path: &BezierPath) -> String {
+        let mut path = self.coord(bezier_path.start);
+        for segment in bezier_path.segments.iter() {
+            path.push_str(&format!(
+                " .. controls {} and {} .. {}",
+                self.coord(segment.cp1),
+                self.coord(segment.cp2),
+                self.coord(segment.end)
+            ));
+        }
+        path
+    }
+
+    fn polyline_path(
+        &self,
+        start: na::Vector2<f64>,
+        path: &[na::Vector2<f64>],
+        closed: bool,
+    ) -> String {
+        let mut path = std::iter::once(start)
+            .chain(path.iter().copied())
+            .map(|pos| self.coord(pos))
+            .collect::<Vec<String>>()
+            .join(" -- ");
+        if closed {
+            path.push_str(" -- cycle");
+        }
+        path
+    }
+}
+
+impl Engine {
+    /// Convert the shapes in the selection into a TikZ picture.
+    ///
+    /// This is experimental, only shape strokes are converted and their styles are approximated.
+    /// Returns None when nothing is selected.
+    pub fn export_selection_as_tikz(&self) -> Option<String> {
+        let selection_keys = self.store.selection_keys_as_rendered();
+        let selection_bounds = self.store.bounds_for_strokes(&selection_keys)?;
+        let converter = TikzConverter {
+            origin: selection_bounds.mins.coords,
+        };
+
+        let mut tikz = String::from("\\begin{tikzpicture}\n");
+        let mut n_skipped = 0;
+        for key in selection_keys {
+            match self.store.get_stroke_ref(key) {
+                Some(Stroke::ShapeStroke(shapestroke)) => {
+                    let closed = matches!(
+                        shapestroke.shape,
+                        Shape::Rectangle(_) | Shape::Ellipse(_) | Shape::Polygon(_)
+                    );
+                    let arrow_tips = TikzConverter::arrow_tips(&shapestroke.shape);
+                    tikz.push_str(&format!(
+                        "  \\draw[{}] {};\n",
+                        TikzConverter::options(&shapestroke.style, arrow_tips, closed),
+                        converter.path(&shapestroke.shape)
+                    ));
+                }
+                Some(_) => n_skipped += 1,
+                None => {}
+            }
+        }
+        if n_skipped > 0 {
+            tikz.push_str(&format!(
+                "  % {n_skipped} strokes that are not shapes were skipped\n"
+            ));
+        }
+        tikz.push_str("\\end{tikzpicture}\n");
+        Some(tikz)
+    }
+}
@@ -132,6 +132,7 @@ pub(crate) fn draw_statistics_to_gtk_snapshot(
     engine: &crate::Engine,
     surface_bounds: p2d::bounding_volume::Aabb,
 ) -> anyhow::Result<()> {
+    use crate::engine::profiling::PROFILER;
     use crate::ext::GrapheneRectExt;
     use gtk4::{graphene, prelude::*};
     use p2d::bounding_volume::Aabb;
@@ -140,6 +141,11 @@ pub(crate) fn draw_statistics_to_gtk_snapshot(
 
     // A statistics overlay
     {
+        let profile = PROFILER.enabled().then(|| PROFILER.profile());
+        let text_height = 150.0
+            + profile
+                .as_ref()
+                .map_or(0.0, |profile| 20.0 + 14.0 * profile.summaries.len() as f64);
         let text_bounds = Aabb::new(
             na::point![
                 surface_bounds.maxs[0] - 320.0,
@@ -147,7 +153,7 @@ pub(crate) fn draw_statistics_to_gtk_snapshot(
             ],
             na::point![
                 surface_bounds.maxs[0] - 20.0,
-                surface_bounds.mins[1] + 120.0
+                surface_bounds.mins[1] + 20.0 + text_height
             ],
         );
         let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(text_bounds));
@@ -157,7 +163,7 @@ pub(crate) fn draw_statistics_to_gtk_snapshot(
         let strokes_total = engine.store.keys_unordered();
         let strokes_in_viewport = engine
             .store
-            .keys_unordered_intersecting_bounds(engine.camera.viewport());
+            .keys_intersecting_bounds(engine.camera.viewport());
         let selected_strokes = engine.store.selection_keys_unordered();
         let trashed_strokes = engine.store.trashed_keys_unordered();
         let strokes_hold_image = strokes_total
@@ -165,14 +171,32 @@ pub(crate) fn draw_statistics_to_gtk_snapshot(
             .filter(|&&key| engine.store.holds_images(key))
             .count();
 
-        let statistics_text_string = format!(
-            "strokes in store:   {}\nstrokes in current viewport:   {}\nstrokes selected: {}\nstroke trashed: {}\nstrokes holding images: {}",
+        let task_stats = engine.tasks_tx.stats();
+
+        let mut statistics_text_string = format!(
+            "strokes in store:   {}\nstrokes in current viewport:   {}\nstrokes selected: {}\nstroke trashed: {}\nstrokes holding images: {}\n\nengine tasks pending: {}\nengine tasks coalesced: {}\nrender tasks superseded: {}",
             strokes_total.len(),
             strokes_in_viewport.len(),
             selected_strokes.len(),
             trashed_strokes.len(),
             strokes_hold_image,
+            task_stats.pending(),
+            task_stats.coalesced(),
+            task_stats.superseded(),
         );
+        if let Some(profile) = profile {
+            statistics_text_string.push_str("\n\ntimings (mean / p95 / max ms):");
+            for summary in profile.summaries {
+                statistics_text_string.push_str(&format!(
+                    "\n{}: {} x  {:.1} / {:.1} / {:.1}",
+                    summary.category.name(),
+                    summary.count,
+                    summary.mean_ms,
+                    summary.recent_p95_ms,
+                    summary.max_ms,
+                ));
+            }
+        }
         let text_layout = piet_cx
             .text()
             .new_text_layout(statistics_text_string)
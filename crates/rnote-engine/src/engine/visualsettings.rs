@@ -0,0 +1,59 @@
+// Imports
+use piet::Color;
+use rnote_compose::Color as ComposeColor;
+
+/// Settings for how the overlays on the canvas are drawn, like selection outlines and pen indicators.
+///
+/// Usually follow the accessibility settings of the system.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VisualSettings {
+    /// Draw the overlays with thicker outlines and increased contrast.
+    pub high_contrast: bool,
+    /// Disable animated effects, like the fading of the laser pointer.
+    pub reduced_motion: bool,
+    /// The accent color overlays like the selection outline are drawn with, instead of their default color.
+    pub accent_color: Option<ComposeColor>,
+}
+
+impl VisualSettings {
+    /// The factor outline widths are multiplied with in high contrast mode.
+    const HIGH_CONTRAST_WIDTH_FACTOR: f64 = 2.0;
+    /// The factor the alpha of translucent fills is multiplied with in high contrast mode.
+    const HIGH_CONTRAST_FILL_ALPHA_FACTOR: f64 = 3.0;
+
+    /// The width of an overlay outline.
+    pub fn outline_width(&self, width: f64) -> f64 {
+        if self.high_contrast {
+            width * Self::HIGH_CONTRAST_WIDTH_FACTOR
+        } else {
+            width
+        }
+    }
+
+    /// The color of an overlay outline, fully opaque in high contrast mode.
+    pub fn outline_color(&self, color: Color) -> Color {
+        if self.high_contrast {
+            color.with_alpha(1.0)
+        } else {
+            color
+        }
+    }
+
+    /// The accent color with the alpha of the given color, or the color itself when no accent color is set.
+    pub fn accented(&self, color: Color) -> Color {
+        match self.accent_color {
+            Some(accent_color) => Color::from(accent_color).with_alpha(color.as_rgba().3),
+            None => color,
+        }
+    }
+
+    /// The color of an overlay fill, more opaque in high contrast mode.
+    pub fn fill_color(&self, color: Color) -> Color {
+        if self.high_contrast {
+            let (.., a) = color.as_rgba();
+            color.with_alpha((a * Self::HIGH_CONTRAST_FILL_ALPHA_FACTOR).min(1.0))
+        } else {
+            color
+        }
+    }
+}
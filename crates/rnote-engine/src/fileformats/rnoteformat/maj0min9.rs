@@ -1,5 +1,6 @@
 // Imports
 use super::maj0min6::RnoteFileMaj0Min6;
+use super::revisions::RevisionHistory;
 use crate::Camera;
 use serde::{Deserialize, Serialize};
 
@@ -8,6 +9,11 @@ pub struct RnoteFileMaj0Min9 {
     /// A snapshot of the engine.
     #[serde(rename = "engine_snapshot")]
     pub engine_snapshot: ijson::IValue,
+    /// The revision history of previous saves.
+    ///
+    /// Is empty for files saved by older versions or when storing revisions is disabled.
+    #[serde(default, rename = "revision_history")]
+    pub revision_history: RevisionHistory,
 }
 
 impl TryFrom<RnoteFileMaj0Min6> for RnoteFileMaj0Min9 {
@@ -23,6 +29,7 @@ impl TryFrom<RnoteFileMaj0Min6> for RnoteFileMaj0Min9 {
 
         Ok(Self {
             engine_snapshot: value.engine_snapshot,
+            revision_history: RevisionHistory::default(),
         })
     }
 }
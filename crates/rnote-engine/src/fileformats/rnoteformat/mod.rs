@@ -10,6 +10,10 @@ pub(crate) mod maj0min5patch8;
 pub(crate) mod maj0min5patch9;
 pub(crate) mod maj0min6;
 pub(crate) mod maj0min9;
+pub(crate) mod revisions;
+
+// Re-exports
+pub use revisions::RevisionHistory;
 
 // Imports
 use self::maj0min5patch8::RnoteFileMaj0Min5Patch8;
@@ -20,10 +24,102 @@ use super::{FileFormatLoader, FileFormatSaver};
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use std::io::{Read, Write};
+use std::ops::RangeInclusive;
+
+/// The compression method of saved `.rnote` files.
+///
+/// When loading, the method is detected from the magic bytes at the start of the file.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    num_derive::FromPrimitive,
+    num_derive::ToPrimitive,
+)]
+#[serde(rename = "compression_method")]
+pub enum CompressionMethod {
+    /// Gzip, readable by all versions.
+    #[default]
+    #[serde(rename = "gzip")]
+    Gzip,
+    /// Zstandard, faster and with better ratios, especially for documents with many images.
+    #[serde(rename = "zstd")]
+    Zstd,
+}
+
+impl TryFrom<u32> for CompressionMethod {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        num_traits::FromPrimitive::from_u32(value).ok_or_else(|| {
+            anyhow::anyhow!(
+                "CompressionMethod try_from::<u32>() for value {} failed",
+                value
+            )
+        })
+    }
+}
+
+impl CompressionMethod {
+    const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+    const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+    /// The range of the supported compression levels.
+    pub fn level_range(&self) -> RangeInclusive<u32> {
+        match self {
+            Self::Gzip => 0..=9,
+            Self::Zstd => 1..=22,
+        }
+    }
+
+    /// The level with a good trade-off between the size and the time it takes to compress.
+    pub fn default_level(&self) -> u32 {
+        match self {
+            Self::Gzip => 5,
+            Self::Zstd => 3,
+        }
+    }
+
+    /// Detect the method from the magic bytes of the compressed data.
+    fn detect(compressed: &[u8]) -> Option<Self> {
+        if compressed.starts_with(&Self::GZIP_MAGIC) {
+            Some(Self::Gzip)
+        } else if compressed.starts_with(&Self::ZSTD_MAGIC) {
+            Some(Self::Zstd)
+        } else {
+            None
+        }
+    }
+
+    /// Compress the bytes with the level, which is clamped to the supported range.
+    fn compress(&self, level: u32, to_compress: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let level_range = self.level_range();
+        let level = level.clamp(*level_range.start(), *level_range.end());
+        match self {
+            Self::Gzip => compress_to_gzip(to_compress, level),
+            Self::Zstd => Ok(zstd::stream::encode_all(to_compress, level as i32)?),
+        }
+    }
+}
+
+/// Decompress bytes, detecting the compression method.
+fn decompress(compressed: &[u8]) -> anyhow::Result<Vec<u8>> {
+    match CompressionMethod::detect(compressed) {
+        Some(CompressionMethod::Gzip) => decompress_from_gzip(compressed),
+        Some(CompressionMethod::Zstd) => Ok(zstd::stream::decode_all(compressed)?),
+        None => Err(anyhow::anyhow!("Unknown compression method")),
+    }
+}
 
 /// Compress bytes with gzip.
-fn compress_to_gzip(to_compress: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
-    let mut encoder = flate2::write::GzEncoder::new(Vec::<u8>::new(), flate2::Compression::new(5));
+fn compress_to_gzip(to_compress: &[u8], level: u32) -> Result<Vec<u8>, anyhow::Error> {
+    let mut encoder =
+        flate2::write::GzEncoder::new(Vec::<u8>::new(), flate2::Compression::new(level));
     encoder.write_all(to_compress)?;
     Ok(encoder.finish()?)
 }
@@ -76,12 +172,32 @@ pub type RnoteFile = RnoteFileMaj0Min9;
 
 impl RnoteFile {
     pub const SEMVER: &'static str = crate::utils::crate_version();
+
+    /// Save as bytes, compressed with the method and level.
+    pub fn save_as_compressed_bytes(
+        &self,
+        method: CompressionMethod,
+        level: u32,
+    ) -> anyhow::Result<Vec<u8>> {
+        let wrapper = RnotefileWrapper {
+            version: semver::Version::parse(Self::SEMVER).unwrap(),
+            data: ijson::to_value(self).context("converting RnoteFile to JSON value failed.")?,
+        };
+        let compressed = method
+            .compress(
+                level,
+                &serde_json::to_vec(&wrapper).context("Serializing RnoteFileWrapper failed.")?,
+            )
+            .context("compressing bytes failed.")?;
+
+        Ok(compressed)
+    }
 }
 
 impl FileFormatLoader for RnoteFile {
     fn load_from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
         let wrapper = serde_json::from_slice::<RnotefileWrapper>(
-            &decompress_from_gzip(bytes).context("decompressing bytes failed.")?,
+            &decompress(bytes).context("decompressing bytes failed.")?,
         )
         .context("deserializing RnotefileWrapper from bytes failed.")?;
 
@@ -130,15 +246,74 @@ impl FileFormatLoader for RnoteFile {
 
 impl FileFormatSaver for RnoteFile {
     fn save_as_bytes(&self, _file_name: &str) -> anyhow::Result<Vec<u8>> {
+        let method = CompressionMethod::default();
+        self.save_as_compressed_bytes(method, method.default_level())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rnote_file() -> RnoteFile {
+        RnoteFile {
+            engine_snapshot: ijson::to_value(serde_json::json!({ "key": "value" })).unwrap(),
+            revision_history: RevisionHistory::default(),
+        }
+    }
+
+    #[test]
+    fn compression_roundtrip() {
+        let data = serde_json::to_vec(&serde_json::json!({ "data": [1, 2, 3] })).unwrap();
+
+        for method in [CompressionMethod::Gzip, CompressionMethod::Zstd] {
+            let level_range = method.level_range();
+            for level in [
+                *level_range.start(),
+                method.default_level(),
+                *level_range.end(),
+                // clamped
+                u32::MAX,
+            ] {
+                let compressed = method.compress(level, &data).unwrap();
+                assert_eq!(CompressionMethod::detect(&compressed), Some(method));
+                assert_eq!(decompress(&compressed).unwrap(), data);
+            }
+        }
+    }
+
+    #[test]
+    fn save_and_load_compressed() {
+        for method in [CompressionMethod::Gzip, CompressionMethod::Zstd] {
+            let bytes = rnote_file()
+                .save_as_compressed_bytes(method, method.default_level())
+                .unwrap();
+            let loaded = RnoteFile::load_from_bytes(&bytes).unwrap();
+            assert_eq!(loaded.engine_snapshot, rnote_file().engine_snapshot);
+        }
+    }
+
+    #[test]
+    fn load_previously_saved_gzip() {
+        // Files were always saved with gzip at level 5 before the compression method became configurable.
         let wrapper = RnotefileWrapper {
-            version: semver::Version::parse(Self::SEMVER).unwrap(),
-            data: ijson::to_value(self).context("converting RnoteFile to JSON value failed.")?,
+            version: semver::Version::parse("0.9.0").unwrap(),
+            data: ijson::to_value(rnote_file()).unwrap(),
         };
-        let compressed = compress_to_gzip(
-            &serde_json::to_vec(&wrapper).context("Serializing RnoteFileWrapper failed.")?,
-        )
-        .context("compressing bytes failed.")?;
+        let mut encoder =
+            flate2::write::GzEncoder::new(Vec::<u8>::new(), flate2::Compression::new(5));
+        encoder
+            .write_all(&serde_json::to_vec(&wrapper).unwrap())
+            .unwrap();
+        let bytes = encoder.finish().unwrap();
 
-        Ok(compressed)
+        let loaded = RnoteFile::load_from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.engine_snapshot, rnote_file().engine_snapshot);
+    }
+
+    #[test]
+    fn unknown_compression_fails() {
+        assert!(decompress(b"{\"version\": \"0.9.0\"}").is_err());
+        assert!(RnoteFile::load_from_bytes(&[]).is_err());
     }
 }
@@ -0,0 +1,333 @@
+// Imports
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+/// A structural delta between two JSON values.
+///
+/// Objects and arrays are compared recursively, so that only the changed parts are stored.
+/// The stroke components keep their slot indices between saves, which keeps the deltas of arrays small.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "json_delta")]
+enum JsonDelta {
+    #[serde(rename = "replace")]
+    Replace(ijson::IValue),
+    #[serde(rename = "object")]
+    Object {
+        #[serde(rename = "set")]
+        set: Vec<(String, JsonDelta)>,
+        #[serde(rename = "removed")]
+        removed: Vec<String>,
+    },
+    #[serde(rename = "array")]
+    Array {
+        #[serde(rename = "len")]
+        len: usize,
+        /// The changed elements in ascending order of their index.
+        #[serde(rename = "changed")]
+        changed: Vec<(usize, JsonDelta)>,
+    },
+}
+
+impl JsonDelta {
+    /// The delta that turns `from` into `to`. None when both are equal.
+    fn between(from: &ijson::IValue, to: &ijson::IValue) -> Option<Self> {
+        if from == to {
+            return None;
+        }
+        if let (Some(from_object), Some(to_object)) = (from.as_object(), to.as_object()) {
+            let set = to_object
+                .iter()
+                .filter_map(|(key, to_value)| {
+                    let delta = match from_object.get(key.as_str()) {
+                        Some(from_value) => Self::between(from_value, to_value)?,
+                        None => Self::Replace(to_value.clone()),
+                    };
+                    Some((key.as_str().to_string(), delta))
+                })
+                .collect();
+            let removed = from_object
+                .keys()
+                .filter(|key| !to_object.contains_key(key.as_str()))
+                .map(|key| key.as_str().to_string())
+                .collect();
+            return Some(Self::Object { set, removed });
+        }
+        if let (Some(from_array), Some(to_array)) = (from.as_array(), to.as_array()) {
+            let changed = to_array
+                .iter()
+                .enumerate()
+                .filter_map(|(i, to_value)| {
+                    let delta = match from_array.as_slice().get(i) {
+                        Some(from_value) => Self::between(from_value, to_value)?,
+                        None => Self::Replace(to_value.clone()),
+                    };
+                    Some((i, delta))
+                })
+                .collect();
+            return Some(Self::Array {
+                len: to_array.len(),
+                changed,
+            });
+        }
+        Some(Self::Replace(to.clone()))
+    }
+
+    fn apply(self, value: &mut ijson::IValue) -> anyhow::Result<()> {
+        match self {
+            Self::Replace(new_value) => *value = new_value,
+            Self::Object { set, removed } => {
+                let object = value.as_object_mut().ok_or_else(|| {
+                    anyhow::anyhow!("Applying object delta failed, value is not an object.")
+                })?;
+                for key in removed {
+                    object.remove(key.as_str());
+                }
+                for (key, delta) in set {
+                    if let Some(value) = object.get_mut(key.as_str()) {
+                        delta.apply(value)?;
+                    } else if let Self::Replace(new_value) = delta {
+                        object.insert(key, new_value);
+                    } else {
+                        return Err(anyhow::anyhow!(
+                            "Applying object delta failed, key `{key}` is missing."
+                        ));
+                    }
+                }
+            }
+            Self::Array { len, changed } => {
+                let array = value.as_array_mut().ok_or_else(|| {
+                    anyhow::anyhow!("Applying array delta failed, value is not an array.")
+                })?;
+                array.truncate(len);
+                for (i, delta) in changed {
+                    if let Some(value) = array.as_mut_slice().get_mut(i) {
+                        delta.apply(value)?;
+                    } else if let (true, Self::Replace(new_value)) = (i == array.len(), delta) {
+                        array.push(new_value);
+                    } else {
+                        return Err(anyhow::anyhow!(
+                            "Applying array delta failed, index {i} is out of bounds."
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A previous save of the document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "revision")]
+struct Revision {
+    /// When the revision was saved, in seconds since the unix epoch.
+    #[serde(rename = "saved_at")]
+    saved_at: Option<i64>,
+    /// The delta that turns the engine snapshot of the next newer revision into the one of this revision.
+    #[serde(rename = "delta")]
+    delta: JsonDelta,
+}
+
+/// The revision history of a .rnote file.
+///
+/// Only the newest engine snapshot is stored in full. Every revision stores the delta to the next newer one,
+/// so restoring a revision applies the deltas from the newest revision down to it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename = "revision_history")]
+pub struct RevisionHistory {
+    /// When the current content was saved, in seconds since the unix epoch.
+    #[serde(rename = "saved_at")]
+    saved_at: Option<i64>,
+    /// Whether the current content was saved by an autosave.
+    #[serde(rename = "autosaved")]
+    autosaved: bool,
+    /// The previous revisions, newest first.
+    #[serde(rename = "revisions")]
+    revisions: Vec<Revision>,
+}
+
+impl RevisionHistory {
+    /// When the previous revisions were saved in seconds since the unix epoch, newest first.
+    pub fn revisions_saved_at(&self) -> Vec<Option<i64>> {
+        self.revisions.iter().map(|r| r.saved_at).collect()
+    }
+
+    /// Record the previously saved engine snapshot as the newest revision, when it differs from the current one.
+    ///
+    /// A previous snapshot that was saved by an autosave is not kept as its own revision, instead it is merged
+    /// into the current one. That way frequent autosaves don't push out the revisions of explicit saves.
+    ///
+    /// Must be called before the current content is marked as saved.
+    ///
+    /// The oldest revisions are dropped beyond the maximum number of revisions.
+    pub(crate) fn record(
+        &mut self,
+        previous_snapshot: &ijson::IValue,
+        current_snapshot: &ijson::IValue,
+        max_revisions: usize,
+    ) {
+        if self.autosaved && !self.revisions.is_empty() {
+            let mut newest_snapshot = previous_snapshot.clone();
+            match self.revisions[0].delta.clone().apply(&mut newest_snapshot) {
+                Ok(()) => {
+                    match JsonDelta::between(current_snapshot, &newest_snapshot) {
+                        Some(delta) => self.revisions[0].delta = delta,
+                        None => {
+                            self.revisions.remove(0);
+                        }
+                    }
+                    self.revisions.truncate(max_revisions);
+                    return;
+                }
+                Err(e) => {
+                    error!("Merging the autosaved revision failed, recording it separately. Err: {e:?}");
+                }
+            }
+        }
+        if let Some(delta) = JsonDelta::between(current_snapshot, previous_snapshot) {
+            self.revisions.insert(
+                0,
+                Revision {
+                    saved_at: self.saved_at,
+                    delta,
+                },
+            );
+        }
+        self.revisions.truncate(max_revisions);
+    }
+
+    /// Mark the current content as saved now.
+    pub(crate) fn mark_saved(&mut self, autosaved: bool) {
+        self.saved_at = Some(chrono::Utc::now().timestamp());
+        self.autosaved = autosaved;
+    }
+
+    /// Restore the engine snapshot of the revision at the index, starting from the current engine snapshot.
+    pub(crate) fn restore(
+        &self,
+        current_snapshot: &ijson::IValue,
+        index: usize,
+    ) -> anyhow::Result<ijson::IValue> {
+        if index >= self.revisions.len() {
+            return Err(anyhow::anyhow!(
+                "Restoring revision failed, index {index} is out of bounds."
+            ));
+        }
+        let mut snapshot = current_snapshot.clone();
+        for revision in self.revisions.iter().take(index + 1) {
+            revision.delta.clone().apply(&mut snapshot)?;
+        }
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonDelta, RevisionHistory};
+    use crate::fileformats::rnoteformat::RnoteFile;
+    use crate::fileformats::FileFormatLoader;
+    use crate::Engine;
+
+    fn snapshot(value: serde_json::Value) -> ijson::IValue {
+        ijson::to_value(value).unwrap()
+    }
+
+    #[test]
+    fn delta_apply_roundtrip() {
+        let from = snapshot(serde_json::json!({
+            "document": { "x": 0.0, "width": 100.0, "removed": true },
+            "strokes": [1, { "a": 2 }, 3, 4],
+            "changes_type": [1, 2],
+        }));
+        let to = snapshot(serde_json::json!({
+            "document": { "x": 0.0, "width": 200.0, "added": "yes" },
+            "strokes": [1, { "a": 5 }],
+            "changes_type": "now a string",
+            "new": [null],
+        }));
+
+        let mut value = from.clone();
+        JsonDelta::between(&from, &to)
+            .unwrap()
+            .apply(&mut value)
+            .unwrap();
+        assert_eq!(value, to);
+
+        let mut value = to.clone();
+        JsonDelta::between(&to, &from)
+            .unwrap()
+            .apply(&mut value)
+            .unwrap();
+        assert_eq!(value, from);
+
+        assert!(JsonDelta::between(&from, &from).is_none());
+    }
+
+    #[test]
+    fn record_and_restore() {
+        let snapshots = (0..3)
+            .map(|i| snapshot(serde_json::json!({ "strokes": vec![i; i + 1] })))
+            .collect::<Vec<ijson::IValue>>();
+        let mut history = RevisionHistory::default();
+        history.mark_saved(false);
+        for pair in snapshots.windows(2) {
+            history.record(&pair[0], &pair[1], 20);
+            history.mark_saved(false);
+        }
+
+        assert_eq!(history.revisions_saved_at().len(), 2);
+        assert_eq!(history.restore(&snapshots[2], 0).unwrap(), snapshots[1]);
+        assert_eq!(history.restore(&snapshots[2], 1).unwrap(), snapshots[0]);
+        assert!(history.restore(&snapshots[2], 2).is_err());
+    }
+
+    #[test]
+    fn autosaves_are_merged() {
+        let saved = snapshot(serde_json::json!({ "strokes": [1] }));
+        let autosaved = snapshot(serde_json::json!({ "strokes": [1, 2] }));
+        let current = snapshot(serde_json::json!({ "strokes": [1, 2, 3] }));
+        let mut history = RevisionHistory::default();
+        history.mark_saved(false);
+
+        history.record(&saved, &autosaved, 20);
+        history.mark_saved(true);
+        history.record(&autosaved, &current, 20);
+        history.mark_saved(false);
+
+        // The autosave is not kept as its own revision
+        assert_eq!(history.revisions_saved_at().len(), 1);
+        assert_eq!(history.restore(&current, 0).unwrap(), saved);
+    }
+
+    #[test]
+    fn only_own_history_is_carried_over() {
+        let mut engine = Engine::default();
+        engine.export_prefs.revision_history_prefs.enabled = true;
+        let save = |engine: &Engine, previous_bytes: Option<Vec<u8>>| {
+            futures::executor::block_on(engine.save_as_rnote_bytes_w_revisions(
+                String::from("test.rnote"),
+                previous_bytes,
+                false,
+            ))
+            .unwrap()
+            .unwrap()
+        };
+        let n_revisions = |bytes: &[u8]| {
+            RnoteFile::load_from_bytes(bytes)
+                .unwrap()
+                .revision_history
+                .revisions_saved_at()
+                .len()
+        };
+
+        let first = save(&engine, None);
+        engine.document.snap_positions = !engine.document.snap_positions;
+        let second = save(&engine, Some(first));
+        assert_eq!(n_revisions(&second), 1);
+
+        // Saving to another file starts a new history
+        engine.document.snap_positions = !engine.document.snap_positions;
+        let other = save(&engine, None);
+        assert_eq!(n_revisions(&other), 0);
+    }
+}
@@ -217,8 +217,15 @@ pub enum XoppBackgroundType {
         /// The filename that is to the image for the pixmap.
         filename: String,
     },
-    /// A background with a pdf. Currently **UNIMPLEMENTED**.
-    Pdf,
+    /// A background with a page of a pdf.
+    Pdf {
+        /// The domain for the pdf. Only present on the first page that references the pdf.
+        domain: Option<XoppBackgroundPixmapDomain>,
+        /// The filename of the pdf. Only present on the first page that references the pdf.
+        filename: Option<String>,
+        /// The page number in the pdf, starting at 1.
+        pageno: u32,
+    },
 }
 
 impl XmlWritable for XoppBackgroundType {
@@ -234,8 +241,19 @@ impl XmlWritable for XoppBackgroundType {
                 w.write_attribute("domain", &domain.to_xml_attr_value());
                 w.write_attribute("filename", filename);
             }
-            Self::Pdf => {
+            Self::Pdf {
+                domain,
+                filename,
+                pageno,
+            } => {
                 w.write_attribute("type", "pdf");
+                if let Some(domain) = domain {
+                    w.write_attribute("domain", &domain.to_xml_attr_value());
+                }
+                if let Some(filename) = filename {
+                    w.write_attribute("filename", filename);
+                }
+                w.write_attribute("pageno", &format!("{pageno}ll"));
             }
         }
     }
@@ -406,7 +424,30 @@ impl XmlLoadable for XoppBackground {
                 self.bg_type = XoppBackgroundType::Pixmap { domain, filename };
             }
             "pdf" => {
-                self.bg_type = XoppBackgroundType::Pdf;
+                let domain = match node.attribute("domain") {
+                    Some("absolute") => Some(XoppBackgroundPixmapDomain::Absolute),
+                    Some("attach") => Some(XoppBackgroundPixmapDomain::Attach),
+                    Some("clone") => Some(XoppBackgroundPixmapDomain::Clone),
+                    Some(_) => {
+                        return Err(anyhow::anyhow!("Err while parsing `domain` attribute of XoppBackground with node id {:?}, is not a valid value", node.id()));
+                    }
+                    None => None,
+                };
+                let filename = node.attribute("filename").map(|f| f.to_string());
+                // The page number is followed by a "ll" suffix
+                let pageno = node
+                    .attribute("pageno")
+                    .map(|p| {
+                        p.trim_end_matches(|c: char| !c.is_ascii_digit())
+                            .parse::<u32>()
+                    })
+                    .transpose()?
+                    .unwrap_or(1);
+                self.bg_type = XoppBackgroundType::Pdf {
+                    domain,
+                    filename,
+                    pageno,
+                };
             }
             _ => {
                 return Err(anyhow::anyhow!("Failed to parse `type` attribute of XoppBackground with node id {:?}, is not a valid value", node.id()));
@@ -7,6 +7,47 @@
 //! The rnote-engine crate is the core of Rnote. It holds the strokes store, the pens, has methods for importing / exporting, rendering, etc.. .
 //!
 //! The main entry point is the [Engine] struct.
+//!
+//! # Usage as a library
+//!
+//! Without the Rnote app the engine can be used to read, modify and write `.rnote` files,
+//! and to import and export other formats. It does not need a running gtk main loop.
+//!
+//! ```no_run
+//! use rnote_engine::engine::EngineSnapshot;
+//! use rnote_engine::Engine;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let bytes = std::fs::read("notes.rnote")?;
+//! let snapshot = EngineSnapshot::load_from_rnote_bytes(bytes).await?;
+//! let mut engine = Engine::default();
+//! let _ = engine.load_snapshot(snapshot);
+//!
+//! let content = engine.extract_document_content();
+//! println!("The document contains {} strokes", content.strokes.len());
+//!
+//! let bytes = engine
+//!     .save_as_rnote_bytes(String::from("notes.rnote"))
+//!     .await??;
+//! std::fs::write("notes.rnote", bytes)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Public API
+//!
+//! The supported entry points for library users are [Engine], [engine::EngineSnapshot] for loading `.rnote` files,
+//! [engine::StrokeContent] for the extracted strokes, and the import and export preferences in [engine::import] and
+//! [engine::export]. The other public modules are shared with the Rnote app and can change between releases.
+//!
+//! The rendering backends (cairo, poppler and librsvg) are always needed, because importing and exporting depends on
+//! them. Only the gtk4 and audio integrations are optional.
+//!
+//! # Features
+//!
+//! - `ui`: integration with gtk4, used by the Rnote app.
+//! - `audio`: pen sounds. Without it the engine is silent and no audio backend is needed.
+//! - `cli`: argument parsing support for the export and import preferences, used by rnote-cli.
 
 // Modules
 pub mod audioplayer;
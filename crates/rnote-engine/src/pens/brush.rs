@@ -16,8 +16,10 @@ use rnote_compose::builders::{
 use rnote_compose::eventresult::{EventPropagation, EventResult};
 use rnote_compose::penevent::{PenEvent, PenProgress};
 use rnote_compose::penpath::{Element, Segment};
-use rnote_compose::Constraints;
-use std::time::Instant;
+use rnote_compose::style::Composer;
+use rnote_compose::{Constraints, PenPath};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 enum BrushState {
@@ -28,15 +30,79 @@ enum BrushState {
     },
 }
 
+/// Extrapolates the upcoming input from the velocity of the most recent input elements.
+#[derive(Debug, Default)]
+struct InputPredictor {
+    recent: VecDeque<(Element, Instant)>,
+}
+
+impl InputPredictor {
+    const N_RECENT: usize = 4;
+    /// The maximum length of the predicted segment, in document coordinates.
+    const MAX_DISTANCE: f64 = 40.0;
+
+    fn push(&mut self, element: Element, now: Instant) {
+        self.recent.push_back((element, now));
+        while self.recent.len() > Self::N_RECENT {
+            self.recent.pop_front();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.recent.clear();
+    }
+
+    /// The mean speed over the recent input elements, in document coordinates per second.
+    fn speed(&self) -> Option<f64> {
+        let (first, first_time) = self.recent.front()?;
+        let (last, last_time) = self.recent.back()?;
+        let dt = last_time.duration_since(*first_time).as_secs_f64();
+        if dt <= 0.0 {
+            return None;
+        }
+        Some((last.pos - first.pos).magnitude() / dt)
+    }
+
+    /// The provisional path from the last received element to the predicted position.
+    fn predict(&self, window: Duration) -> Option<PenPath> {
+        let (first, first_time) = self.recent.front()?;
+        let (last, last_time) = self.recent.back()?;
+        let dt = last_time.duration_since(*first_time).as_secs_f64();
+        if dt <= 0.0 {
+            return None;
+        }
+        let mut offset = (last.pos - first.pos) * (window.as_secs_f64() / dt);
+        let distance = offset.magnitude();
+        if distance < 0.1 {
+            return None;
+        }
+        if distance > Self::MAX_DISTANCE {
+            offset *= Self::MAX_DISTANCE / distance;
+        }
+
+        Some(PenPath::new_w_segments(
+            *last,
+            [Segment::LineTo {
+                end: Element::new(last.pos + offset, last.pressure),
+            }],
+        ))
+    }
+}
+
 #[derive(Debug)]
 pub struct Brush {
     state: BrushState,
+    predictor: InputPredictor,
+    /// Whether new strokes are drawn in the modifier color instead of the configured one.
+    modifier_color_active: bool,
 }
 
 impl Default for Brush {
     fn default() -> Self {
         Self {
             state: BrushState::Idle,
+            predictor: InputPredictor::default(),
+            modifier_color_active: false,
         }
     }
 }
@@ -82,13 +148,18 @@ impl PenBehaviour for Brush {
 
                     engine_view.pens_config.brush_config.new_style_seeds();
 
-                    let brushstroke = Stroke::BrushStroke(BrushStroke::new(
+                    let mut brushstroke = BrushStroke::new(
                         element,
                         engine_view
                             .pens_config
                             .brush_config
-                            .style_for_current_options(),
-                    ));
+                            .style_w_modifier_color(self.modifier_color_active),
+                    );
+                    brushstroke.outline = engine_view
+                        .pens_config
+                        .brush_config
+                        .outline_for_current_options();
+                    let brushstroke = Stroke::BrushStroke(brushstroke);
                     let current_stroke_key = engine_view.store.insert_stroke(
                         brushstroke,
                         Some(
@@ -105,6 +176,8 @@ impl PenBehaviour for Brush {
                         engine_view.camera.image_scale(),
                     );
 
+                    self.predictor.reset();
+                    self.predictor.push(element, now);
                     self.state = BrushState::Drawing {
                         path_builder: new_builder(
                             engine_view.pens_config.brush_config.builder_type,
@@ -139,6 +212,12 @@ impl PenBehaviour for Brush {
                 PenEvent::Cancel,
             ) => {
                 // Finish up the last stroke
+                if engine_view.pens_config.brush_config.simplify {
+                    widget_flags |= engine_view.store.simplify_brushstrokes(
+                        &[*current_stroke_key],
+                        engine_view.pens_config.brush_config.simplify_epsilon,
+                    );
+                }
                 engine_view
                     .store
                     .update_geometry_for_stroke(*current_stroke_key);
@@ -168,8 +247,17 @@ impl PenBehaviour for Brush {
                     path_builder,
                     current_stroke_key,
                 },
-                pen_event,
+                mut pen_event,
             ) => {
+                if let PenEvent::Down { element, .. } = &mut pen_event {
+                    self.predictor.push(*element, now);
+                    if let Some(speed) = self.predictor.speed() {
+                        engine_view
+                            .pens_config
+                            .brush_config
+                            .apply_speed_sensitivity(element, speed);
+                    }
+                }
                 let builder_result =
                     path_builder.handle_event(pen_event, now, Constraints::default());
                 let handled = builder_result.handled;
@@ -230,6 +318,12 @@ impl PenBehaviour for Brush {
                         }
 
                         // Finish up the last stroke
+                        if engine_view.pens_config.brush_config.simplify {
+                            widget_flags |= engine_view.store.simplify_brushstrokes(
+                                &[*current_stroke_key],
+                                engine_view.pens_config.brush_config.simplify_epsilon,
+                            );
+                        }
                         engine_view
                             .store
                             .update_geometry_for_stroke(*current_stroke_key);
@@ -266,15 +360,25 @@ impl PenBehaviour for Brush {
 
 impl DrawableOnDoc for Brush {
     fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
-        let style = engine_view
-            .pens_config
-            .brush_config
-            .style_for_current_options();
+        let brush_config = &engine_view.pens_config.brush_config;
+        let style = brush_config.style_w_modifier_color(self.modifier_color_active);
+        // The outline style is wider, so its bounds include the outline
+        let style = match brush_config.outline_for_current_options() {
+            Some(outline) => outline.outline_style(&style),
+            None => style,
+        };
 
         match &self.state {
             BrushState::Idle => None,
             BrushState::Drawing { path_builder, .. } => {
-                path_builder.bounds(&style, engine_view.camera.zoom())
+                let bounds = path_builder.bounds(&style, engine_view.camera.zoom());
+                match self.prediction(engine_view) {
+                    Some(predicted) => {
+                        let predicted_bounds = predicted.composed_bounds(&style);
+                        Some(bounds.map_or(predicted_bounds, |b| b.merged(&predicted_bounds)))
+                    }
+                    None => bounds,
+                }
             }
         }
     }
@@ -293,12 +397,26 @@ impl DrawableOnDoc for Brush {
                     BrushStyle::Marker => {
                         // Don't draw the marker, as the pen would render on top of other strokes, while the stroke itself would render underneath them.
                     }
-                    BrushStyle::Solid | BrushStyle::Textured => {
-                        let style = engine_view
-                            .pens_config
-                            .brush_config
-                            .style_for_current_options();
+                    BrushStyle::Solid | BrushStyle::Textured | BrushStyle::Calligraphy => {
+                        let brush_config = &engine_view.pens_config.brush_config;
+                        let style = brush_config.style_w_modifier_color(self.modifier_color_active);
+                        let prediction = self.prediction(engine_view);
+                        if let Some(outline) = brush_config.outline_for_current_options() {
+                            let outline_style = outline.outline_style(&style);
+                            path_builder.draw_styled(
+                                cx,
+                                &outline_style,
+                                engine_view.camera.total_zoom(),
+                            );
+                            if let Some(predicted) = &prediction {
+                                predicted.draw_composed(cx, &outline_style);
+                            }
+                        }
                         path_builder.draw_styled(cx, &style, engine_view.camera.total_zoom());
+                        // Replaced by the actual input once it arrives
+                        if let Some(predicted) = prediction {
+                            predicted.draw_composed(cx, &style);
+                        }
                     }
                 }
             }
@@ -311,6 +429,23 @@ impl DrawableOnDoc for Brush {
 
 impl Brush {
     const INPUT_OVERSHOOT: f64 = 30.0;
+
+    /// Set whether new strokes are drawn in the modifier color.
+    ///
+    /// A stroke that is currently drawn keeps its color.
+    pub(crate) fn set_modifier_color_active(&mut self, active: bool) {
+        self.modifier_color_active = active;
+    }
+
+    fn prediction(&self, engine_view: &EngineView) -> Option<PenPath> {
+        let brush_config = &engine_view.pens_config.brush_config;
+        if !brush_config.predict_input {
+            return None;
+        }
+        self.predictor.predict(Duration::from_secs_f64(
+            brush_config.prediction_window / 1000.0,
+        ))
+    }
 }
 
 fn play_marker_sound(engine_view: &mut EngineViewMut) {
@@ -182,7 +182,9 @@ impl DrawableOnDoc for Eraser {
         const OUTLINE_COLOR: piet::Color = color::GNOME_REDS[2].with_a8(240);
         const FILL_COLOR: piet::Color = color::GNOME_REDS[0].with_a8(160);
         const PROXIMITY_FILL_COLOR: piet::Color = color::GNOME_REDS[0].with_a8(51);
-        let outline_width = 2.0 / engine_view.camera.total_zoom();
+        let visual_settings = engine_view.visual_settings;
+        let outline_color = visual_settings.outline_color(OUTLINE_COLOR);
+        let outline_width = visual_settings.outline_width(2.0) / engine_view.camera.total_zoom();
 
         match &self.state {
             EraserState::Up => {}
@@ -195,8 +197,8 @@ impl DrawableOnDoc for Eraser {
                 let fill_rect = bounds.to_kurbo_rect();
                 let outline_rect = bounds.tightened(outline_width * 0.5).to_kurbo_rect();
 
-                cx.fill(fill_rect, &PROXIMITY_FILL_COLOR);
-                cx.stroke(outline_rect, &OUTLINE_COLOR, outline_width);
+                cx.fill(fill_rect, &visual_settings.fill_color(PROXIMITY_FILL_COLOR));
+                cx.stroke(outline_rect, &outline_color, outline_width);
             }
             EraserState::Down(current_element) => {
                 let bounds = engine_view
@@ -207,8 +209,8 @@ impl DrawableOnDoc for Eraser {
                 let fill_rect = bounds.to_kurbo_rect();
                 let outline_rect = bounds.tightened(outline_width * 0.5).to_kurbo_rect();
 
-                cx.fill(fill_rect, &FILL_COLOR);
-                cx.stroke(outline_rect, &OUTLINE_COLOR, outline_width);
+                cx.fill(fill_rect, &visual_settings.fill_color(FILL_COLOR));
+                cx.stroke(outline_rect, &outline_color, outline_width);
             }
         }
 
@@ -225,13 +227,13 @@ fn erase(element: Element, engine_view: &mut EngineViewMut) -> WidgetFlags {
         EraserStyle::TrashCollidingStrokes => {
             widget_flags |= engine_view.store.trash_colliding_strokes(
                 engine_view.pens_config.eraser_config.eraser_bounds(element),
-                engine_view.camera.viewport(),
+                &engine_view.pens_config.eraser_config.filter,
             );
         }
         EraserStyle::SplitCollidingStrokes => {
             let (modified_strokes, wf) = engine_view.store.split_colliding_strokes(
                 engine_view.pens_config.eraser_config.eraser_bounds(element),
-                engine_view.camera.viewport(),
+                &engine_view.pens_config.eraser_config.filter,
             );
             widget_flags |= wf;
 
@@ -22,7 +22,7 @@ pub use selector::Selector;
 pub use shaper::Shaper;
 pub use shortcuts::Shortcuts;
 pub use tools::Tools;
-pub use typewriter::Typewriter;
+pub use typewriter::{TextFont, Typewriter};
 
 // Imports
 use crate::engine::{EngineView, EngineViewMut};
@@ -15,6 +15,7 @@ use p2d::bounding_volume::Aabb;
 use piet::RenderContext;
 use rnote_compose::eventresult::EventPropagation;
 use rnote_compose::penevent::{KeyboardKey, ModifierKey, PenEvent, PenProgress, ShortcutKey};
+use rnote_compose::SplitOrder;
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
 
@@ -43,6 +44,9 @@ pub struct PenHolder {
     progress: PenProgress,
     #[serde(skip)]
     toggle_pen_style: Option<PenStyle>,
+    /// The style that was used before the current one.
+    #[serde(skip)]
+    previous_pen_style: Option<PenStyle>,
     #[serde(skip)]
     prev_shortcut_key: Option<ShortcutKey>,
 }
@@ -57,6 +61,7 @@ impl Default for PenHolder {
             current_pen: Pen::default(),
             progress: PenProgress::Idle,
             toggle_pen_style: None,
+            previous_pen_style: None,
             prev_shortcut_key: None,
         }
     }
@@ -162,6 +167,23 @@ impl PenHolder {
         widget_flags
     }
 
+    /// The style that was used before the current one, if the style was already changed.
+    pub fn previous_pen_style(&self) -> Option<PenStyle> {
+        self.previous_pen_style
+    }
+
+    /// Change back to the previous style.
+    ///
+    /// Repeatedly calling this swaps between the two most recently used styles.
+    pub fn change_to_previous_style(&mut self, engine_view: &mut EngineViewMut) -> WidgetFlags {
+        let Some(previous_pen_style) = self.previous_pen_style else {
+            return WidgetFlags::default();
+        };
+        let mut widget_flags = self.change_style(previous_pen_style, engine_view);
+        widget_flags |= self.change_style_override(None, engine_view);
+        widget_flags
+    }
+
     /// Change the style override.
     pub fn change_style_override(
         &mut self,
@@ -195,6 +217,9 @@ impl PenHolder {
 
         if self.pen_mode_state.pen_mode() != new_pen_mode {
             self.pen_mode_state.set_pen_mode(new_pen_mode);
+            if let Some(style) = engine_view.pens_config.pen_mode_target_style(new_pen_mode) {
+                self.pen_mode_state.set_style(style);
+            }
             widget_flags |= self.reinstall_pen_current_style(engine_view);
             widget_flags.refresh_ui = true;
         }
@@ -217,6 +242,7 @@ impl PenHolder {
         let mut new_pen = new_pen(self.current_pen_style_w_override());
         widget_flags |= new_pen.init(&engine_view.as_im()) | new_pen.update_state(engine_view);
         self.current_pen = new_pen;
+        self.apply_modifier_color();
         widget_flags |= self.handle_changed_pen_style();
         self.progress = PenProgress::Idle;
 
@@ -311,6 +337,13 @@ impl PenHolder {
                     }
                     ShortcutMode::Disabled => {}
                 },
+                ShortcutAction::ChangeToPreviousPenStyle => {
+                    widget_flags |= self.change_to_previous_style(engine_view);
+                }
+                ShortcutAction::ModifierColor => {
+                    self.pen_mode_state.set_modifier_color_active(true);
+                    self.apply_modifier_color();
+                }
             }
 
             propagate = EventPropagation::Stop;
@@ -322,6 +355,28 @@ impl PenHolder {
         (propagate, widget_flags)
     }
 
+    /// Handle a released shortcut key.
+    ///
+    /// Only the modifier color is bound to the key being held, all other actions are completed when it is pressed.
+    pub fn handle_released_shortcut_key(
+        &mut self,
+        shortcut_key: ShortcutKey,
+        _now: Instant,
+    ) -> (EventPropagation, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+
+        if self.get_shortcut_action(shortcut_key) != Some(ShortcutAction::ModifierColor)
+            || !self.pen_mode_state.modifier_color_active()
+        {
+            return (EventPropagation::Proceed, widget_flags);
+        }
+        self.pen_mode_state.set_modifier_color_active(false);
+        self.apply_modifier_color();
+        widget_flags.redraw = true;
+
+        (EventPropagation::Stop, widget_flags)
+    }
+
     /// Fetch clipboard content from the current pen.
     #[allow(clippy::type_complexity)]
     pub fn fetch_clipboard_content(
@@ -354,6 +409,7 @@ impl PenHolder {
             let all_strokes = engine_view.store.selection_keys_as_rendered();
             engine_view.store.set_selected_keys(&all_strokes, false);
 
+            self.previous_pen_style = Some(self.pen_mode_state.style());
             self.pen_mode_state.set_style(new_style);
             widget_flags |= self.reinstall_pen_current_style(engine_view);
             widget_flags.refresh_ui = true;
@@ -464,6 +520,39 @@ impl PenHolder {
 
                     EventPropagation::Stop
                 }
+                KeyboardKey::PageUp | KeyboardKey::PageDown => {
+                    widget_flags |= engine_view
+                        .camera
+                        .page_jump(keyboard_key == KeyboardKey::PageDown, engine_view.document);
+                    engine_view.store.regenerate_rendering_in_viewport_threaded(
+                        engine_view.tasks_tx.clone(),
+                        false,
+                        engine_view.camera.viewport(),
+                        engine_view.camera.image_scale(),
+                    );
+
+                    EventPropagation::Stop
+                }
+                KeyboardKey::Home | KeyboardKey::End => {
+                    let pages = engine_view.document.pages_bounds(SplitOrder::default());
+                    let page = if keyboard_key == KeyboardKey::Home {
+                        pages.first()
+                    } else {
+                        pages.last()
+                    };
+                    if let Some(page) = page {
+                        let offset = engine_view.camera.offset_for_page_top(*page);
+                        widget_flags |= engine_view.camera.set_offset(offset, engine_view.document);
+                        engine_view.store.regenerate_rendering_in_viewport_threaded(
+                            engine_view.tasks_tx.clone(),
+                            false,
+                            engine_view.camera.viewport(),
+                            engine_view.camera.image_scale(),
+                        );
+                    }
+
+                    EventPropagation::Stop
+                }
                 _ => EventPropagation::Proceed,
             },
         };
@@ -495,6 +584,13 @@ impl PenHolder {
         widget_flags
     }
 
+    /// Pass the modifier color state on to the brush.
+    fn apply_modifier_color(&mut self) {
+        if let Pen::Brush(brush) = &mut self.current_pen {
+            brush.set_modifier_color_active(self.pen_mode_state.modifier_color_active());
+        }
+    }
+
     fn handle_changed_pen_style(&mut self) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
         let current_style = self.pen_mode_state.current_style_w_override();
@@ -32,6 +32,9 @@ pub struct PenModeState {
     penmode_pen_style_override: Option<PenStyle>,
     #[serde(skip)]
     penmode_eraser_style_override: Option<PenStyle>,
+    /// Whether the brush temporarily draws in the modifier color, while the shortcut is held.
+    #[serde(skip)]
+    modifier_color_active: bool,
 }
 
 impl Default for PenModeState {
@@ -43,6 +46,7 @@ impl Default for PenModeState {
 
             penmode_pen_style_override: None,
             penmode_eraser_style_override: None,
+            modifier_color_active: false,
         }
     }
 }
@@ -121,6 +125,14 @@ impl PenModeState {
         }
     }
 
+    pub fn modifier_color_active(&self) -> bool {
+        self.modifier_color_active
+    }
+
+    pub fn set_modifier_color_active(&mut self, active: bool) {
+        self.modifier_color_active = active;
+    }
+
     pub fn pen_mode(&self) -> PenMode {
         self.pen_mode
     }
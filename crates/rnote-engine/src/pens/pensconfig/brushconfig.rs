@@ -1,11 +1,14 @@
 // Imports
 use crate::store::chrono_comp::StrokeLayer;
+use crate::strokes::StrokeOutline;
 use rand::{Rng, SeedableRng};
 use rnote_compose::builders::PenPathBuilderType;
+use rnote_compose::penpath::Element;
+use rnote_compose::style::calligraphy::CalligraphyOptions;
 use rnote_compose::style::smooth::SmoothOptions;
 use rnote_compose::style::textured::TexturedOptions;
 use rnote_compose::style::PressureCurve;
-use rnote_compose::Style;
+use rnote_compose::{Color, Style};
 use serde::{Deserialize, Serialize};
 
 #[derive(
@@ -27,6 +30,8 @@ pub enum BrushStyle {
     Solid,
     #[serde(rename = "textured")]
     Textured,
+    #[serde(rename = "calligraphy")]
+    Calligraphy,
 }
 
 impl Default for BrushStyle {
@@ -97,7 +102,7 @@ impl std::ops::DerefMut for SolidOptions {
     }
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default, rename = "brush_config")]
 pub struct BrushConfig {
     #[serde(rename = "builder_type")]
@@ -110,17 +115,96 @@ pub struct BrushConfig {
     pub solid_options: SolidOptions,
     #[serde(rename = "textured_options")]
     pub textured_options: TexturedOptions,
+    #[serde(rename = "calligraphy_options")]
+    pub calligraphy_options: CalligraphyOptions,
+    /// Whether finished strokes get simplified.
+    #[serde(rename = "simplify")]
+    pub simplify: bool,
+    /// The maximum deviation of the simplified stroke path, in document coordinates.
+    #[serde(rename = "simplify_epsilon")]
+    pub simplify_epsilon: f64,
+    /// Whether the next input points are predicted and drawn provisionally while drawing.
+    #[serde(rename = "predict_input")]
+    pub predict_input: bool,
+    /// How far ahead the input is predicted, in milliseconds.
+    #[serde(rename = "prediction_window")]
+    pub prediction_window: f64,
+    /// How strongly fast drawing thins out the solid and textured styles, emulating ink pooling when drawing slowly.
+    ///
+    /// Applied by lowering the pressure of the input, so it has no effect with a constant pressure curve.
+    #[serde(rename = "speed_sensitivity")]
+    pub speed_sensitivity: f64,
+    /// Whether new strokes are drawn with an outline.
+    #[serde(rename = "outline_enabled")]
+    pub outline_enabled: bool,
+    #[serde(rename = "outline")]
+    pub outline: StrokeOutline,
+    /// The secondary color the brush temporarily switches to while the modifier color shortcut is held.
+    #[serde(rename = "modifier_color")]
+    pub modifier_color: Color,
+    /// The stroke widths of the width presets.
+    #[serde(rename = "width_presets")]
+    pub width_presets: [f64; 3],
+}
+
+impl Default for BrushConfig {
+    fn default() -> Self {
+        Self {
+            builder_type: PenPathBuilderType::default(),
+            style: BrushStyle::default(),
+            marker_options: MarkerOptions::default(),
+            solid_options: SolidOptions::default(),
+            textured_options: TexturedOptions::default(),
+            calligraphy_options: CalligraphyOptions::default(),
+            simplify: false,
+            simplify_epsilon: Self::SIMPLIFY_EPSILON_DEFAULT,
+            predict_input: false,
+            prediction_window: Self::PREDICTION_WINDOW_DEFAULT,
+            speed_sensitivity: Self::SPEED_SENSITIVITY_DEFAULT,
+            outline_enabled: false,
+            outline: StrokeOutline::default(),
+            modifier_color: Color::RED,
+            width_presets: Self::WIDTH_PRESETS_DEFAULT,
+        }
+    }
 }
 
 impl BrushConfig {
     pub const STROKE_WIDTH_MIN: f64 = 0.1;
     pub const STROKE_WIDTH_MAX: f64 = 500.0;
+    pub const WIDTH_PRESETS_DEFAULT: [f64; 3] = [2.0, 6.0, 12.0];
+    pub const SIMPLIFY_EPSILON_MIN: f64 = 0.05;
+    pub const SIMPLIFY_EPSILON_MAX: f64 = 10.0;
+    pub const SIMPLIFY_EPSILON_DEFAULT: f64 = 0.5;
+    pub const PREDICTION_WINDOW_MIN: f64 = 5.0;
+    pub const PREDICTION_WINDOW_MAX: f64 = 100.0;
+    pub const PREDICTION_WINDOW_DEFAULT: f64 = 20.0;
+    pub const SPEED_SENSITIVITY_MIN: f64 = 0.0;
+    pub const SPEED_SENSITIVITY_MAX: f64 = 1.0;
+    pub const SPEED_SENSITIVITY_DEFAULT: f64 = 0.0;
+    /// The drawing speed at which the speed sensitivity fully applies, in document coordinates per second.
+    const SPEED_SENSITIVITY_SATURATION_SPEED: f64 = 2000.0;
 
     pub(crate) fn layer_for_current_options(&self) -> StrokeLayer {
         match &self.style {
             BrushStyle::Marker => StrokeLayer::Highlighter,
-            BrushStyle::Solid | BrushStyle::Textured => StrokeLayer::UserLayer(0),
+            BrushStyle::Solid | BrushStyle::Textured | BrushStyle::Calligraphy => {
+                StrokeLayer::UserLayer(0)
+            }
+        }
+    }
+
+    /// Lower the pressure of the element depending on the drawing speed, in document coordinates per second.
+    pub(crate) fn apply_speed_sensitivity(&self, element: &mut Element, speed: f64) {
+        if !matches!(self.style, BrushStyle::Solid | BrushStyle::Textured)
+            || self.speed_sensitivity <= 0.0
+        {
+            return;
         }
+        let speed_factor = (speed / Self::SPEED_SENSITIVITY_SATURATION_SPEED).clamp(0.0, 1.0);
+        element.pressure = (element.pressure
+            * (1.0 - self.speed_sensitivity.clamp(0.0, 1.0) * speed_factor))
+            .clamp(0.0, 1.0);
     }
 
     /// A new seed for new shapes
@@ -129,6 +213,19 @@ impl BrushConfig {
         self.textured_options.seed = seed;
     }
 
+    pub(crate) fn outline_for_current_options(&self) -> Option<StrokeOutline> {
+        self.outline_enabled.then_some(self.outline)
+    }
+
+    /// The style for the current options, drawn in the modifier color when it is active.
+    pub(crate) fn style_w_modifier_color(&self, modifier_color_active: bool) -> Style {
+        let mut style = self.style_for_current_options();
+        if modifier_color_active {
+            style.set_stroke_color(self.modifier_color);
+        }
+        style
+    }
+
     pub(crate) fn style_for_current_options(&self) -> Style {
         match &self.style {
             BrushStyle::Marker => {
@@ -146,6 +243,11 @@ impl BrushConfig {
 
                 Style::Textured(options)
             }
+            BrushStyle::Calligraphy => {
+                let options = self.calligraphy_options.clone();
+
+                Style::Calligraphy(options)
+            }
         }
     }
 }
@@ -1,6 +1,9 @@
 // Imports
+use crate::store::chrono_comp::StrokeLayer;
+use crate::strokes::Stroke;
 use p2d::bounding_volume::Aabb;
 use rnote_compose::penpath::Element;
+use rnote_compose::Color;
 use serde::{Deserialize, Serialize};
 
 #[derive(
@@ -30,6 +33,55 @@ impl TryFrom<u32> for EraserStyle {
     }
 }
 
+/// Restricts the strokes that are affected by the eraser. Strokes not matching the filter are left untouched.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "eraser_filter")]
+pub struct EraserFilter {
+    /// Erase strokes drawn with the brush, except highlighter strokes.
+    #[serde(rename = "ink")]
+    pub ink: bool,
+    /// Erase highlighter strokes.
+    #[serde(rename = "highlighter")]
+    pub highlighter: bool,
+    /// Erase shapes.
+    #[serde(rename = "shapes")]
+    pub shapes: bool,
+    /// When set, only strokes having this color are erased.
+    #[serde(rename = "color")]
+    pub color: Option<Color>,
+}
+
+impl Default for EraserFilter {
+    fn default() -> Self {
+        Self {
+            ink: true,
+            highlighter: true,
+            shapes: true,
+            color: None,
+        }
+    }
+}
+
+impl EraserFilter {
+    /// Whether the filter restricts the erased strokes in any way.
+    pub fn is_restricting(&self) -> bool {
+        *self != Self::default()
+    }
+
+    pub(crate) fn matches(&self, stroke: &Stroke, layer: StrokeLayer) -> bool {
+        let kind_matches = match stroke {
+            Stroke::BrushStroke(_) if layer == StrokeLayer::Highlighter => self.highlighter,
+            Stroke::BrushStroke(_) => self.ink,
+            Stroke::ShapeStroke(_) => self.shapes,
+            Stroke::TextStroke(_) | Stroke::VectorImage(_) | Stroke::BitmapImage(_) => false,
+        };
+        kind_matches
+            && self.color.map_or(true, |color| {
+                stroke.colors().into_iter().any(|c| c.approx_eq_f32(color))
+            })
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default, rename = "eraser_config")]
 pub struct EraserConfig {
@@ -37,6 +89,11 @@ pub struct EraserConfig {
     pub width: f64,
     #[serde(rename = "style")]
     pub style: EraserStyle,
+    /// The widths of the width presets.
+    #[serde(rename = "width_presets")]
+    pub width_presets: [f64; 3],
+    #[serde(rename = "filter")]
+    pub filter: EraserFilter,
 }
 
 impl Default for EraserConfig {
@@ -44,6 +101,8 @@ impl Default for EraserConfig {
         Self {
             width: Self::WIDTH_DEFAULT,
             style: EraserStyle::default(),
+            width_presets: Self::WIDTH_PRESETS_DEFAULT,
+            filter: EraserFilter::default(),
         }
     }
 }
@@ -52,6 +111,7 @@ impl EraserConfig {
     pub const WIDTH_MIN: f64 = 1.0;
     pub const WIDTH_MAX: f64 = 500.0;
     pub const WIDTH_DEFAULT: f64 = 12.0;
+    pub const WIDTH_PRESETS_DEFAULT: [f64; 3] = [4.0, 9.0, 24.0];
 
     pub(crate) fn eraser_bounds(&self, element: Element) -> Aabb {
         Aabb::from_half_extents(element.pos.into(), na::Vector2::repeat(self.width * 0.5))
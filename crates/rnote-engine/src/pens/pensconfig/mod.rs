@@ -1,6 +1,7 @@
 // Modules
 pub mod brushconfig;
 pub mod eraserconfig;
+pub mod penpreset;
 pub mod selectorconfig;
 pub mod shaperconfig;
 pub mod toolsconfig;
@@ -9,15 +10,19 @@ pub mod typewriterconfig;
 // Re-exports
 pub use brushconfig::BrushConfig;
 pub use eraserconfig::EraserConfig;
+pub use penpreset::{PenPreset, PenPresetConfig};
 pub use selectorconfig::SelectorConfig;
 pub use shaperconfig::ShaperConfig;
 pub use toolsconfig::ToolsConfig;
 pub use typewriterconfig::TypewriterConfig;
 
 // Imports
+use super::{PenMode, PenStyle};
 use crate::CloneConfig;
+use brushconfig::BrushStyle;
 use rnote_compose::Color;
 use serde::{Deserialize, Serialize};
+use shaperconfig::ShaperStyle;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(default, rename = "pens_config")]
@@ -34,6 +39,17 @@ pub struct PensConfig {
     pub selector_config: SelectorConfig,
     #[serde(default, rename = "tools_config")]
     pub tools_config: ToolsConfig,
+    /// The pen style the eraser end of a stylus switches to.
+    ///
+    /// When `None`, the style that was last used with the eraser end is kept.
+    #[serde(default, rename = "eraser_end_pen_style")]
+    pub eraser_end_pen_style: Option<PenStyle>,
+    /// The most recently used stroke and fill colors, the most recent first.
+    #[serde(default, rename = "recent_colors")]
+    pub recent_colors: Vec<Color>,
+    /// The pinned pen presets.
+    #[serde(default, rename = "presets")]
+    pub presets: Vec<PenPreset>,
 }
 
 impl CloneConfig for PensConfig {
@@ -43,13 +59,28 @@ impl CloneConfig for PensConfig {
 }
 
 impl PensConfig {
+    /// The maximum number of remembered recent colors.
+    pub const RECENT_COLORS_MAX: usize = 9;
+    /// The maximum number of pen presets.
+    pub const PRESETS_MAX: usize = 9;
+
+    /// The pen style that gets installed when switching to the given pen mode, if it is fixed.
+    pub fn pen_mode_target_style(&self, pen_mode: PenMode) -> Option<PenStyle> {
+        match pen_mode {
+            PenMode::Pen => None,
+            PenMode::Eraser => self.eraser_end_pen_style,
+        }
+    }
+
     pub fn set_all_stroke_colors(&mut self, stroke_color: Color) {
         self.brush_config.marker_options.stroke_color = Some(stroke_color);
         self.brush_config.solid_options.stroke_color = Some(stroke_color);
         self.brush_config.textured_options.stroke_color = Some(stroke_color);
+        self.brush_config.calligraphy_options.stroke_color = Some(stroke_color);
         self.shaper_config.smooth_options.stroke_color = Some(stroke_color);
         self.shaper_config.rough_options.stroke_color = Some(stroke_color);
         self.typewriter_config.text_style.color = stroke_color;
+        self.push_recent_color(stroke_color);
     }
 
     pub fn set_all_fill_colors(&mut self, fill_color: Color) {
@@ -57,5 +88,110 @@ impl PensConfig {
         self.brush_config.solid_options.fill_color = Some(fill_color);
         self.shaper_config.smooth_options.fill_color = Some(fill_color);
         self.shaper_config.rough_options.fill_color = Some(fill_color);
+        self.tools_config.fill_tool_config.color = fill_color;
+        self.push_recent_color(fill_color);
+    }
+
+    /// The current stroke width of the given pen style, if it has one.
+    ///
+    /// For the eraser this is its width.
+    pub fn stroke_width(&self, pen_style: PenStyle) -> Option<f64> {
+        match pen_style {
+            PenStyle::Brush => Some(self.brush_config.style_for_current_options().stroke_width()),
+            PenStyle::Shaper => Some(
+                self.shaper_config
+                    .gen_style_for_current_options()
+                    .stroke_width(),
+            ),
+            PenStyle::Eraser => Some(self.eraser_config.width),
+            PenStyle::Typewriter | PenStyle::Selector | PenStyle::Tools => None,
+        }
+    }
+
+    /// The stroke widths of the width presets of the given pen style, if it has a stroke width.
+    pub fn width_presets(&self, pen_style: PenStyle) -> Option<[f64; 3]> {
+        match pen_style {
+            PenStyle::Brush => Some(self.brush_config.width_presets),
+            PenStyle::Shaper => Some(self.shaper_config.width_presets),
+            PenStyle::Eraser => Some(self.eraser_config.width_presets),
+            PenStyle::Typewriter | PenStyle::Selector | PenStyle::Tools => None,
+        }
+    }
+
+    /// Set the current stroke width of the given pen style, clamped to its allowed range.
+    ///
+    /// Returns the new stroke width, or None if the pen style has no stroke width.
+    pub fn set_stroke_width(&mut self, pen_style: PenStyle, stroke_width: f64) -> Option<f64> {
+        match pen_style {
+            PenStyle::Brush => {
+                let stroke_width = stroke_width
+                    .clamp(BrushConfig::STROKE_WIDTH_MIN, BrushConfig::STROKE_WIDTH_MAX);
+                let brush_config = &mut self.brush_config;
+                match brush_config.style {
+                    BrushStyle::Marker => brush_config.marker_options.stroke_width = stroke_width,
+                    BrushStyle::Solid => brush_config.solid_options.stroke_width = stroke_width,
+                    BrushStyle::Textured => {
+                        brush_config.textured_options.stroke_width = stroke_width
+                    }
+                    BrushStyle::Calligraphy => {
+                        brush_config.calligraphy_options.stroke_width = stroke_width
+                    }
+                }
+                Some(stroke_width)
+            }
+            PenStyle::Shaper => {
+                let stroke_width = stroke_width.clamp(
+                    ShaperConfig::STROKE_WIDTH_MIN,
+                    ShaperConfig::STROKE_WIDTH_MAX,
+                );
+                match self.shaper_config.style {
+                    ShaperStyle::Smooth => {
+                        self.shaper_config.smooth_options.stroke_width = stroke_width
+                    }
+                    ShaperStyle::Rough => {
+                        self.shaper_config.rough_options.stroke_width = stroke_width
+                    }
+                }
+                Some(stroke_width)
+            }
+            PenStyle::Eraser => {
+                let stroke_width =
+                    stroke_width.clamp(EraserConfig::WIDTH_MIN, EraserConfig::WIDTH_MAX);
+                self.eraser_config.width = stroke_width;
+                Some(stroke_width)
+            }
+            PenStyle::Typewriter | PenStyle::Selector | PenStyle::Tools => None,
+        }
+    }
+
+    /// Create a preset from the current configuration of the given pen style.
+    ///
+    /// None if the pen style can't be stored as a preset.
+    pub fn preset_for_pen_style(&self, pen_style: PenStyle, name: String) -> Option<PenPreset> {
+        let config = match pen_style {
+            PenStyle::Brush => PenPresetConfig::Brush(self.brush_config.clone()),
+            PenStyle::Shaper => PenPresetConfig::Shaper(self.shaper_config.clone()),
+            PenStyle::Typewriter => PenPresetConfig::Typewriter(self.typewriter_config.clone()),
+            PenStyle::Eraser => PenPresetConfig::Eraser(self.eraser_config.clone()),
+            PenStyle::Selector | PenStyle::Tools => return None,
+        };
+        Some(PenPreset { name, config })
+    }
+
+    /// Apply the configuration stored in the preset.
+    pub fn apply_preset(&mut self, preset: &PenPreset) {
+        match &preset.config {
+            PenPresetConfig::Brush(config) => self.brush_config = config.clone(),
+            PenPresetConfig::Shaper(config) => self.shaper_config = config.clone(),
+            PenPresetConfig::Typewriter(config) => self.typewriter_config = config.clone(),
+            PenPresetConfig::Eraser(config) => self.eraser_config = config.clone(),
+        }
+    }
+
+    /// Move the color to the front of the recent colors, dropping the oldest when the maximum is exceeded.
+    pub fn push_recent_color(&mut self, color: Color) {
+        self.recent_colors.retain(|c| *c != color);
+        self.recent_colors.insert(0, color);
+        self.recent_colors.truncate(Self::RECENT_COLORS_MAX);
     }
 }
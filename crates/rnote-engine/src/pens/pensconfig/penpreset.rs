@@ -0,0 +1,67 @@
+// Imports
+use super::{BrushConfig, EraserConfig, ShaperConfig, TypewriterConfig};
+use crate::pens::PenStyle;
+use rnote_compose::Color;
+use serde::{Deserialize, Serialize};
+
+/// The stored configuration of a pen preset.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename = "pen_preset_config")]
+pub enum PenPresetConfig {
+    #[serde(rename = "brush")]
+    Brush(BrushConfig),
+    #[serde(rename = "shaper")]
+    Shaper(ShaperConfig),
+    #[serde(rename = "typewriter")]
+    Typewriter(TypewriterConfig),
+    #[serde(rename = "eraser")]
+    Eraser(EraserConfig),
+}
+
+/// A named, complete pen configuration that can be switched to quickly.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename = "pen_preset")]
+pub struct PenPreset {
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "config")]
+    pub config: PenPresetConfig,
+}
+
+impl PenPreset {
+    /// The pen style the preset switches to.
+    pub fn pen_style(&self) -> PenStyle {
+        match &self.config {
+            PenPresetConfig::Brush(_) => PenStyle::Brush,
+            PenPresetConfig::Shaper(_) => PenStyle::Shaper,
+            PenPresetConfig::Typewriter(_) => PenStyle::Typewriter,
+            PenPresetConfig::Eraser(_) => PenStyle::Eraser,
+        }
+    }
+
+    /// The stroke color of the preset, if the pen has one.
+    pub fn stroke_color(&self) -> Option<Color> {
+        match &self.config {
+            PenPresetConfig::Brush(config) => config.style_for_current_options().stroke_color(),
+            PenPresetConfig::Shaper(config) => {
+                config.gen_style_for_current_options().stroke_color()
+            }
+            PenPresetConfig::Typewriter(config) => Some(config.text_style.color),
+            PenPresetConfig::Eraser(_) => None,
+        }
+    }
+
+    /// The stroke width of the preset, if the pen has one.
+    pub fn stroke_width(&self) -> Option<f64> {
+        match &self.config {
+            PenPresetConfig::Brush(config) => {
+                Some(config.style_for_current_options().stroke_width())
+            }
+            PenPresetConfig::Shaper(config) => {
+                Some(config.gen_style_for_current_options().stroke_width())
+            }
+            PenPresetConfig::Typewriter(config) => Some(config.text_style.font_size),
+            PenPresetConfig::Eraser(config) => Some(config.width),
+        }
+    }
+}
@@ -49,6 +49,8 @@ pub struct SelectorConfig {
     pub style: SelectorStyle,
     #[serde(rename = "resize_lock_aspectratio")]
     pub resize_lock_aspectratio: bool,
+    #[serde(rename = "resize_scale_stroke_width")]
+    pub resize_scale_stroke_width: bool,
 }
 
 impl Default for SelectorConfig {
@@ -56,6 +58,7 @@ impl Default for SelectorConfig {
         Self {
             style: SelectorStyle::default(),
             resize_lock_aspectratio: false,
+            resize_scale_stroke_width: true,
         }
     }
 }
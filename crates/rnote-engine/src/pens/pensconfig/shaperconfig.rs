@@ -1,11 +1,14 @@
 // Imports
+use crate::document::Format;
+use crate::strokes::StrokeOutline;
 use rand::{Rng, SeedableRng};
 use rnote_compose::builders::ShapeBuilderType;
 use rnote_compose::constraints::ConstraintRatio;
+use rnote_compose::shapes::{CappedLine, DimensionLabel, LineCap};
 use rnote_compose::style::rough::RoughOptions;
 use rnote_compose::style::smooth::SmoothOptions;
 use rnote_compose::Constraints;
-use rnote_compose::Style;
+use rnote_compose::{Shape, Style};
 use serde::{Deserialize, Serialize};
 
 #[derive(
@@ -48,6 +51,29 @@ pub struct ShaperConfig {
     pub rough_options: RoughOptions,
     #[serde(rename = "constraints")]
     pub constraints: Constraints,
+    /// Whether polylines and polygons which end close to their start get joined into closed polygons.
+    #[serde(rename = "join_nearly_closed")]
+    pub join_nearly_closed: bool,
+    /// The maximum distance between the end and the start point for joining, in document coordinates.
+    #[serde(rename = "join_tolerance")]
+    pub join_tolerance: f64,
+    /// Whether new shapes are drawn with an outline.
+    #[serde(rename = "outline_enabled")]
+    pub outline_enabled: bool,
+    #[serde(rename = "outline")]
+    pub outline: StrokeOutline,
+    /// The decoration at the start of new lines.
+    #[serde(rename = "line_start_cap")]
+    pub line_start_cap: LineCap,
+    /// The decoration at the end of new lines.
+    #[serde(rename = "line_end_cap")]
+    pub line_end_cap: LineCap,
+    /// Whether new lines are dimension lines, showing their measured length.
+    #[serde(rename = "dimension_line")]
+    pub dimension_line: bool,
+    /// The stroke widths of the width presets.
+    #[serde(rename = "width_presets")]
+    pub width_presets: [f64; 3],
 }
 
 impl Default for ShaperConfig {
@@ -63,6 +89,14 @@ impl Default for ShaperConfig {
             smooth_options: SmoothOptions::default(),
             rough_options: RoughOptions::default(),
             constraints,
+            join_nearly_closed: false,
+            join_tolerance: Self::JOIN_TOLERANCE_DEFAULT,
+            outline_enabled: false,
+            outline: StrokeOutline::default(),
+            line_start_cap: LineCap::None,
+            line_end_cap: LineCap::None,
+            dimension_line: false,
+            width_presets: Self::WIDTH_PRESETS_DEFAULT,
         }
     }
 }
@@ -70,6 +104,10 @@ impl Default for ShaperConfig {
 impl ShaperConfig {
     pub const STROKE_WIDTH_MIN: f64 = 0.1;
     pub const STROKE_WIDTH_MAX: f64 = 500.0;
+    pub const WIDTH_PRESETS_DEFAULT: [f64; 3] = [2.0, 6.0, 12.0];
+    pub const JOIN_TOLERANCE_MIN: f64 = 1.0;
+    pub const JOIN_TOLERANCE_MAX: f64 = 100.0;
+    pub const JOIN_TOLERANCE_DEFAULT: f64 = 12.0;
 
     /// A new seed for new shapes
     pub(crate) fn new_style_seeds(&mut self) {
@@ -77,6 +115,34 @@ impl ShaperConfig {
         self.rough_options.seed = seed;
     }
 
+    /// Post-process the shapes emitted by the shape builders according to the current options.
+    ///
+    /// The document format determines the unit and scale of the measured length of dimension lines.
+    pub(crate) fn post_process_shape(&self, shape: Shape, format: &Format) -> Shape {
+        match shape {
+            Shape::Line(line)
+                if self.line_start_cap != LineCap::None
+                    || self.line_end_cap != LineCap::None
+                    || self.dimension_line =>
+            {
+                let mut capped_line = CappedLine::new(line, self.line_start_cap, self.line_end_cap);
+                if self.dimension_line {
+                    capped_line.dimension = Some(DimensionLabel {
+                        units_per_px: format.scaled_length(1.0, format.unit),
+                        unit: format.unit.abbreviation().to_string(),
+                    });
+                }
+                Shape::CappedLine(capped_line)
+            }
+            shape if self.join_nearly_closed => shape.join_nearly_closed(self.join_tolerance),
+            shape => shape,
+        }
+    }
+
+    pub(crate) fn outline_for_current_options(&self) -> Option<StrokeOutline> {
+        self.outline_enabled.then_some(self.outline)
+    }
+
     pub(crate) fn gen_style_for_current_options(&self) -> Style {
         match &self.style {
             ShaperStyle::Smooth => {
@@ -1,4 +1,6 @@
 // Imports
+use crate::document::format::MeasureUnit;
+use rnote_compose::Color;
 use serde::{Deserialize, Serialize};
 
 #[derive(
@@ -24,6 +26,17 @@ pub enum ToolStyle {
     Zoom,
     #[serde(rename = "laser")]
     Laser,
+    #[serde(rename = "measure")]
+    Measure,
+    /// Select and copy the text of imported Pdf pages.
+    #[serde(rename = "pdftext")]
+    PdfText,
+    /// Fill closed regions that are bounded by strokes.
+    #[serde(rename = "fill")]
+    Fill,
+    /// Push strokes near the pen apart along the drag direction.
+    #[serde(rename = "pushapart")]
+    PushApart,
 }
 
 impl Default for ToolStyle {
@@ -51,10 +64,78 @@ pub struct VerticalSpaceToolConfig {
     pub limit_movement_vertical_borders: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "measure_tool_config")]
+pub struct MeasureToolConfig {
+    /// The unit the measured distances are shown in
+    #[serde(rename = "unit")]
+    pub unit: MeasureUnit,
+    /// Whether the measured line and its readout are inserted as strokes
+    #[serde(rename = "leave_annotation")]
+    pub leave_annotation: bool,
+}
+
+impl Default for MeasureToolConfig {
+    fn default() -> Self {
+        Self {
+            unit: MeasureUnit::Mm,
+            leave_annotation: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "fill_tool_config")]
+pub struct FillToolConfig {
+    /// The color closed regions are filled with
+    #[serde(rename = "color")]
+    pub color: Color,
+}
+
+impl Default for FillToolConfig {
+    fn default() -> Self {
+        Self {
+            color: Color::new(0.6, 0.76, 0.93, 1.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename = "pushapart_tool_config")]
+pub struct PushApartToolConfig {
+    /// The radius around the pen in which strokes get pushed
+    #[serde(rename = "radius")]
+    pub radius: f64,
+    /// How far strokes are pushed relative to the movement of the pen, ranging from 0.0 to 1.0
+    #[serde(rename = "strength")]
+    pub strength: f64,
+}
+
+impl Default for PushApartToolConfig {
+    fn default() -> Self {
+        Self {
+            radius: Self::RADIUS_DEFAULT,
+            strength: Self::STRENGTH_DEFAULT,
+        }
+    }
+}
+
+impl PushApartToolConfig {
+    pub const RADIUS_MIN: f64 = 5.0;
+    pub const RADIUS_MAX: f64 = 500.0;
+    pub const RADIUS_DEFAULT: f64 = 60.0;
+    pub const STRENGTH_MIN: f64 = 0.0;
+    pub const STRENGTH_MAX: f64 = 1.0;
+    pub const STRENGTH_DEFAULT: f64 = 0.7;
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(default, rename = "tools_config")]
 pub struct ToolsConfig {
     #[serde(rename = "style")]
     pub style: ToolStyle,
     pub verticalspace_tool_config: VerticalSpaceToolConfig,
+    pub measure_tool_config: MeasureToolConfig,
+    pub fill_tool_config: FillToolConfig,
+    pub pushapart_tool_config: PushApartToolConfig,
 }
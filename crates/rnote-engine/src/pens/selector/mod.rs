@@ -5,20 +5,22 @@ mod penevents;
 use super::pensconfig::selectorconfig::SelectorStyle;
 use super::PenBehaviour;
 use super::PenStyle;
-use crate::engine::{EngineView, EngineViewMut, StrokeContent};
+use crate::document::Format;
+use crate::engine::{EngineView, EngineViewMut, StrokeContent, VisualSettings};
 use crate::render::Svg;
 use crate::snap::SnapCorner;
 use crate::store::StrokeKey;
-use crate::strokes::Content;
+use crate::strokes::{Content, ImageCrop};
 use crate::{Camera, DrawableOnDoc, Engine, WidgetFlags};
 use futures::channel::oneshot;
 use kurbo::Shape;
 use p2d::bounding_volume::{Aabb, BoundingSphere, BoundingVolume};
 use p2d::query::PointQuery;
-use piet::RenderContext;
+use piet::{RenderContext, Text, TextLayout, TextLayoutBuilder};
 use rnote_compose::ext::{AabbExt, Vector2Ext};
 use rnote_compose::penevent::{PenEvent, PenProgress, PenState};
 use rnote_compose::penpath::Element;
+use rnote_compose::shapes::{BezierPath, Rectangle, Shapeable};
 use rnote_compose::style::indicators;
 use rnote_compose::EventResult;
 use rnote_compose::{color, Color};
@@ -46,6 +48,8 @@ pub(super) enum ModifyState {
         start_rotation_angle: f64,
         current_rotation_angle: f64,
     },
+    /// Dragging the marker of the rotation pivot.
+    DragPivot,
     Resize {
         from_corner: ResizeCorner,
         start_bounds: Aabb,
@@ -60,6 +64,21 @@ impl Default for ModifyState {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum CropState {
+    Idle,
+    Drag { from_corner: ResizeCorner },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum EditNodesState {
+    Idle,
+    /// Dragging the node of the bezier path at the index.
+    Drag {
+        node: usize,
+    },
+}
+
 #[derive(Clone, Debug)]
 pub(super) enum SelectorState {
     Idle,
@@ -70,6 +89,16 @@ pub(super) enum SelectorState {
         modify_state: ModifyState,
         selection: Vec<StrokeKey>,
         selection_bounds: Aabb,
+        /// The custom pivot the selection is rotated around, the center of the selection bounds when `None`.
+        rotation_pivot: Option<na::Point2<f64>>,
+    },
+    CropImage {
+        key: StrokeKey,
+        crop_state: CropState,
+    },
+    EditNodes {
+        key: StrokeKey,
+        edit_state: EditNodesState,
     },
 }
 
@@ -111,6 +140,15 @@ impl PenBehaviour for Selector {
         let mut widget_flags = WidgetFlags::default();
 
         let selection = engine_view.store.selection_keys_as_rendered();
+        // Keep the custom pivot as long as the selection stays the same
+        let rotation_pivot = match &self.state {
+            SelectorState::ModifySelection {
+                selection: prev_selection,
+                rotation_pivot,
+                ..
+            } if *prev_selection == selection => *rotation_pivot,
+            _ => None,
+        };
 
         self.state =
             if let Some(selection_bounds) = engine_view.store.bounds_for_strokes(&selection) {
@@ -118,6 +156,7 @@ impl PenBehaviour for Selector {
                     modify_state: ModifyState::default(),
                     selection,
                     selection_bounds,
+                    rotation_pivot,
                 }
             } else {
                 SelectorState::Idle
@@ -275,6 +314,9 @@ impl PenBehaviour for Selector {
 impl DrawableOnDoc for Selector {
     fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
         let total_zoom = engine_view.camera.total_zoom();
+        let outline_width = engine_view
+            .visual_settings
+            .outline_width(Self::OUTLINE_STROKE_WIDTH);
 
         match &self.state {
             SelectorState::Idle => None,
@@ -284,13 +326,13 @@ impl DrawableOnDoc for Selector {
                 if let Some(first) = path_iter.next() {
                     let mut new_bounds = Aabb::from_half_extents(
                         first.pos.into(),
-                        na::Vector2::repeat(Self::OUTLINE_STROKE_WIDTH / total_zoom),
+                        na::Vector2::repeat(outline_width / total_zoom),
                     );
 
                     path_iter.for_each(|element| {
                         let pos_bounds = Aabb::from_half_extents(
                             element.pos.into(),
-                            na::Vector2::repeat(Self::OUTLINE_STROKE_WIDTH / total_zoom),
+                            na::Vector2::repeat(outline_width / total_zoom),
                         );
                         new_bounds.merge(&pos_bounds);
                     });
@@ -301,8 +343,41 @@ impl DrawableOnDoc for Selector {
                 }
             }
             SelectorState::ModifySelection {
-                selection_bounds, ..
-            } => Some(selection_bounds.extend_by(Self::RESIZE_NODE_SIZE / total_zoom)),
+                selection_bounds,
+                modify_state,
+                rotation_pivot,
+                ..
+            } => {
+                let mut bounds = selection_bounds.extend_by(Self::RESIZE_NODE_SIZE / total_zoom);
+                if let Some(rotation_pivot) = rotation_pivot {
+                    // The rotation indicator is the larger one
+                    bounds.merge(&Aabb::from_half_extents(
+                        *rotation_pivot,
+                        na::Vector2::repeat(Self::ROTATION_INDICATOR_HALF_EXTENTS / total_zoom),
+                    ));
+                }
+                if matches!(modify_state, ModifyState::Resize { .. }) {
+                    Some(bounds.extend_by(Self::DIMENSIONS_LABEL_MAX_SIZE / total_zoom))
+                } else {
+                    Some(bounds)
+                }
+            }
+            SelectorState::CropImage { key, .. } => {
+                let (rectangle, _) = engine_view.store.get_stroke_ref(*key)?.image_crop()?;
+                Some(
+                    rectangle
+                        .bounds()
+                        .extend_by(Self::RESIZE_NODE_SIZE / total_zoom),
+                )
+            }
+            SelectorState::EditNodes { key, .. } => {
+                let stroke = engine_view.store.get_stroke_ref(*key)?;
+                let mut bounds = stroke.bounds();
+                for node in stroke.bezier_path()?.nodes() {
+                    bounds.take_point(node.into());
+                }
+                Some(bounds.loosened(Self::EDIT_NODE_SIZE / total_zoom))
+            }
         }
     }
 
@@ -313,6 +388,11 @@ impl DrawableOnDoc for Selector {
     ) -> anyhow::Result<()> {
         cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
         let total_zoom = engine_view.camera.total_zoom();
+        let visual_settings = engine_view.visual_settings;
+        let outline_color =
+            visual_settings.outline_color(visual_settings.accented(Self::SELECTION_OUTLINE_COLOR));
+        let outline_width = visual_settings.outline_width(Self::OUTLINE_STROKE_WIDTH) / total_zoom;
+        let fill_color = visual_settings.fill_color(Self::SELECTION_FILL_COLOR);
 
         match &self.state {
             SelectorState::Idle => {}
@@ -339,11 +419,11 @@ impl DrawableOnDoc for Selector {
                                     .collect::<Vec<f64>>(),
                             );
 
-                            cx.fill(bez_path.clone(), &Self::SELECTION_FILL_COLOR);
+                            cx.fill(bez_path.clone(), &fill_color);
                             cx.stroke_styled(
                                 bez_path,
-                                &Self::SELECTION_OUTLINE_COLOR,
-                                Self::OUTLINE_STROKE_WIDTH / total_zoom,
+                                &outline_color,
+                                outline_width,
                                 &stroke_style,
                             );
                         }
@@ -363,11 +443,11 @@ impl DrawableOnDoc for Selector {
                                     .collect::<Vec<f64>>(),
                             );
 
-                            cx.fill(select_rect, &Self::SELECTION_FILL_COLOR);
+                            cx.fill(select_rect, &fill_color);
                             cx.stroke_styled(
                                 select_rect,
-                                &Self::SELECTION_OUTLINE_COLOR,
-                                Self::OUTLINE_STROKE_WIDTH / total_zoom,
+                                &outline_color,
+                                outline_width,
                                 &stroke_style,
                             );
                         }
@@ -379,8 +459,8 @@ impl DrawableOnDoc for Selector {
                                     last.pos.to_kurbo_point(),
                                     Self::SELECTING_SINGLE_CIRCLE_RADIUS / total_zoom,
                                 ),
-                                &Self::SELECTION_OUTLINE_COLOR,
-                                Self::OUTLINE_STROKE_WIDTH / total_zoom,
+                                &outline_color,
+                                outline_width,
                             );
                         }
                     }
@@ -405,8 +485,8 @@ impl DrawableOnDoc for Selector {
 
                             cx.stroke_styled(
                                 bez_path,
-                                &Self::SELECTION_OUTLINE_COLOR,
-                                Self::OUTLINE_STROKE_WIDTH / total_zoom,
+                                &outline_color,
+                                outline_width,
                                 &stroke_style,
                             );
                         }
@@ -417,7 +497,7 @@ impl DrawableOnDoc for Selector {
                 modify_state,
                 selection,
                 selection_bounds,
-                ..
+                rotation_pivot,
             } => {
                 // Draw the highlight for the selected strokes
                 for stroke in engine_view.store.get_strokes_ref(selection) {
@@ -432,8 +512,13 @@ impl DrawableOnDoc for Selector {
                     modify_state,
                     self.pos,
                     engine_view.camera,
+                    engine_view.righthanded,
+                    visual_settings,
                 )?;
 
+                let pivot = rotation_pivot.unwrap_or_else(|| selection_bounds.center());
+                Self::draw_pivot_node(cx, pivot, modify_state, self.pos, engine_view.camera);
+
                 match modify_state {
                     ModifyState::Rotate {
                         rotation_center,
@@ -448,9 +533,49 @@ impl DrawableOnDoc for Selector {
                             engine_view.camera,
                         )?;
                     }
+                    ModifyState::Resize { .. } => {
+                        Self::draw_dimensions_label(
+                            cx,
+                            *selection_bounds,
+                            &engine_view.document.format,
+                            engine_view.camera,
+                        )?;
+                    }
                     _ => {}
                 }
             }
+            SelectorState::CropImage { key, crop_state } => {
+                if let Some((rectangle, crop)) = engine_view
+                    .store
+                    .get_stroke_ref(*key)
+                    .and_then(|stroke| stroke.image_crop())
+                {
+                    Self::draw_crop_overlay(
+                        cx,
+                        rectangle,
+                        crop.unwrap_or_default(),
+                        crop_state,
+                        self.pos,
+                        engine_view.camera,
+                        visual_settings,
+                    )?;
+                }
+            }
+            SelectorState::EditNodes { key, edit_state } => {
+                if let Some(bezier_path) = engine_view
+                    .store
+                    .get_stroke_ref(*key)
+                    .and_then(|stroke| stroke.bezier_path())
+                {
+                    Self::draw_edit_nodes_overlay(
+                        cx,
+                        bezier_path,
+                        edit_state,
+                        self.pos,
+                        engine_view.camera,
+                    )?;
+                }
+            }
         }
 
         cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
@@ -471,12 +596,24 @@ impl Selector {
     const SELECTING_SINGLE_CIRCLE_RADIUS: f64 = 4.0;
     /// Resize node size, in surface coordinates.
     const RESIZE_NODE_SIZE: na::Vector2<f64> = na::vector![18.0, 18.0];
+    /// The size of the nodes when editing the nodes of a bezier path, in surface coordinates.
+    const EDIT_NODE_SIZE: f64 = 12.0;
     /// Rotate node diameter, in surface coordinates.
     const ROTATE_NODE_DIAMETER: f64 = 18.0;
+    /// Rotation pivot node diameter, in surface coordinates.
+    const PIVOT_NODE_DIAMETER: f64 = 14.0;
+    /// Half extents of the cross indicating the rotation center, in surface coordinates.
+    const ROTATION_INDICATOR_HALF_EXTENTS: f64 = 10.0;
     /// The outline color when drawing a selection
     const SELECTION_OUTLINE_COLOR: piet::Color = color::GNOME_BRIGHTS[4].with_a8(240);
     /// The fill color when drawing a selection
     const SELECTION_FILL_COLOR: piet::Color = color::GNOME_BRIGHTS[2].with_a8(13);
+    /// Size of the area reserved for the dimensions label while resizing, in surface coordinates.
+    const DIMENSIONS_LABEL_MAX_SIZE: na::Vector2<f64> = na::vector![240.0, 40.0];
+    const DIMENSIONS_LABEL_FONT_SIZE: f64 = 12.0;
+    const DIMENSIONS_LABEL_PADDING: f64 = 4.0;
+    const DIMENSIONS_LABEL_TEXT_COLOR: piet::Color = color::GNOME_BRIGHTS[1];
+    const DIMENSIONS_LABEL_BG_COLOR: piet::Color = color::GNOME_DARKS[3].with_a8(220);
 
     fn add_to_select_path(style: SelectorStyle, path: &mut Vec<Element>, element: Element) {
         match style {
@@ -528,26 +665,72 @@ impl Selector {
         }
     }
 
-    fn rotate_node_sphere(selection_bounds: Aabb, camera: &Camera) -> BoundingSphere {
+    /// The rotate node is placed on the right side of the selection, mirrored for left-handed users.
+    fn rotate_node_sphere(
+        selection_bounds: Aabb,
+        camera: &Camera,
+        righthanded: bool,
+    ) -> BoundingSphere {
         let total_zoom = camera.total_zoom();
+        let x = if righthanded {
+            selection_bounds.maxs[0] + Self::RESIZE_NODE_SIZE[0] / (2.0 * total_zoom)
+        } else {
+            selection_bounds.mins[0] - Self::RESIZE_NODE_SIZE[0] / (2.0 * total_zoom)
+        };
         let pos = na::point![
-            selection_bounds.maxs[0] + Self::RESIZE_NODE_SIZE[0] / (2.0 * total_zoom),
+            x,
             (selection_bounds.maxs[1] + selection_bounds.mins[1]) * 0.5
         ];
         BoundingSphere::new(pos, Self::ROTATE_NODE_DIAMETER * 0.5 / total_zoom)
     }
 
+    fn pivot_node_sphere(pivot: na::Point2<f64>, camera: &Camera) -> BoundingSphere {
+        BoundingSphere::new(pivot, Self::PIVOT_NODE_DIAMETER * 0.5 / camera.total_zoom())
+    }
+
+    /// Draws the marker of the rotation pivot, which can be dragged to rotate around a custom point.
+    ///
+    /// Inside the selection bounds the marker is only dragged while holding Alt.
+    fn draw_pivot_node(
+        piet_cx: &mut impl RenderContext,
+        pivot: na::Point2<f64>,
+        modify_state: &ModifyState,
+        pos: Option<na::Vector2<f64>>,
+        camera: &Camera,
+    ) {
+        let pivot_node_sphere = Self::pivot_node_sphere(pivot, camera);
+        let pivot_node_state = match modify_state {
+            ModifyState::DragPivot | ModifyState::Rotate { .. } => PenState::Down,
+            _ if pos.is_some_and(|pos| pivot_node_sphere.contains_local_point(&pos.into())) => {
+                PenState::Proximity
+            }
+            _ => PenState::Up,
+        };
+        indicators::draw_circular_node(
+            piet_cx,
+            pivot_node_state,
+            pivot_node_sphere,
+            camera.total_zoom(),
+        );
+    }
+
     fn draw_selection_overlay(
         piet_cx: &mut impl RenderContext,
         selection_bounds: Aabb,
         modify_state: &ModifyState,
         pos: Option<na::Vector2<f64>>,
         camera: &Camera,
+        righthanded: bool,
+        visual_settings: VisualSettings,
     ) -> anyhow::Result<()> {
         piet_cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
         let total_zoom = camera.total_zoom();
+        let outline_color =
+            visual_settings.outline_color(visual_settings.accented(Self::SELECTION_OUTLINE_COLOR));
+        let outline_width = visual_settings.outline_width(Self::OUTLINE_STROKE_WIDTH) / total_zoom;
+        let fill_color = visual_settings.fill_color(Self::SELECTION_FILL_COLOR);
 
-        let rotate_node_sphere = Self::rotate_node_sphere(selection_bounds, camera);
+        let rotate_node_sphere = Self::rotate_node_sphere(selection_bounds, camera, righthanded);
         let rotate_node_state = if matches!(modify_state, ModifyState::Rotate { .. }) {
             PenState::Down
         } else if let Some(pos) = pos {
@@ -687,22 +870,18 @@ impl Selector {
         // so that the inner shapes become the exterior for correct clipping
         clip_path.extend(
             kurbo::Rect::new(
-                selection_bounds.maxs[0] + Self::OUTLINE_STROKE_WIDTH / total_zoom,
-                selection_bounds.mins[1] - Self::OUTLINE_STROKE_WIDTH / total_zoom,
-                selection_bounds.mins[0] - Self::OUTLINE_STROKE_WIDTH / total_zoom,
-                selection_bounds.maxs[1] + Self::OUTLINE_STROKE_WIDTH / total_zoom,
+                selection_bounds.maxs[0] + outline_width,
+                selection_bounds.mins[1] - outline_width,
+                selection_bounds.mins[0] - outline_width,
+                selection_bounds.maxs[1] + outline_width,
             )
             .path_elements(0.1),
         );
 
         piet_cx.clip(clip_path);
 
-        piet_cx.fill(selection_rect, &Self::SELECTION_FILL_COLOR);
-        piet_cx.stroke(
-            selection_rect,
-            &Self::SELECTION_OUTLINE_COLOR,
-            Self::OUTLINE_STROKE_WIDTH / total_zoom,
-        );
+        piet_cx.fill(selection_rect, &fill_color);
+        piet_cx.stroke(selection_rect, &outline_color, outline_width);
 
         piet_cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
 
@@ -739,6 +918,51 @@ impl Selector {
         Ok(())
     }
 
+    /// Draws the real-world dimensions of the selection below it, in the document unit and drawing scale.
+    fn draw_dimensions_label(
+        piet_cx: &mut impl RenderContext,
+        selection_bounds: Aabb,
+        format: &Format,
+        camera: &Camera,
+    ) -> anyhow::Result<()> {
+        let total_zoom = camera.total_zoom();
+        let extents = selection_bounds.extents();
+        let width = format.scaled_length(extents[0], format.unit);
+        let height = format.scaled_length(extents[1], format.unit);
+        let text_layout = piet_cx
+            .text()
+            .new_text_layout(format!(
+                "{width:.2} × {height:.2} {}",
+                format.unit.abbreviation()
+            ))
+            .text_color(Self::DIMENSIONS_LABEL_TEXT_COLOR)
+            .font(
+                piet::FontFamily::SANS_SERIF,
+                Self::DIMENSIONS_LABEL_FONT_SIZE / total_zoom,
+            )
+            .build()
+            .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        let padding = Self::DIMENSIONS_LABEL_PADDING / total_zoom;
+        let label_size = text_layout.size();
+        let label_pos = na::vector![
+            selection_bounds.center()[0] - label_size.width * 0.5,
+            selection_bounds.maxs[1] + Self::RESIZE_NODE_SIZE[1] / total_zoom + padding
+        ];
+        let label_rect = kurbo::Rect::new(
+            label_pos[0] - padding,
+            label_pos[1] - padding,
+            label_pos[0] + label_size.width + padding,
+            label_pos[1] + label_size.height + padding,
+        );
+
+        piet_cx.fill(
+            kurbo::RoundedRect::from_rect(label_rect, padding),
+            &Self::DIMENSIONS_LABEL_BG_COLOR,
+        );
+        piet_cx.draw_text(&text_layout, label_pos.to_kurbo_point());
+        Ok(())
+    }
+
     fn draw_rotation_indicator(
         piet_cx: &mut impl RenderContext,
         rotation_center: na::Point2<f64>,
@@ -754,7 +978,7 @@ impl Selector {
             a: 1.0,
         };
         let total_zoom = camera.total_zoom();
-        let center_cross_half_extents: f64 = 10.0 / total_zoom;
+        let center_cross_half_extents: f64 = Self::ROTATION_INDICATOR_HALF_EXTENTS / total_zoom;
         let center_cross_path_width: f64 = 1.5 / total_zoom;
 
         let mut center_cross = kurbo::BezPath::new();
@@ -789,6 +1013,244 @@ impl Selector {
         Ok(())
     }
 
+    /// The corners of the visible region of a cropped image, in document coordinates.
+    fn crop_corners(
+        rectangle: &Rectangle,
+        crop: ImageCrop,
+    ) -> [(ResizeCorner, na::Point2<f64>); 4] {
+        let local_aabb = crop.local_aabb(rectangle);
+        [
+            (ResizeCorner::TopLeft, local_aabb.mins),
+            (
+                ResizeCorner::TopRight,
+                na::point![local_aabb.maxs[0], local_aabb.mins[1]],
+            ),
+            (
+                ResizeCorner::BottomLeft,
+                na::point![local_aabb.mins[0], local_aabb.maxs[1]],
+            ),
+            (ResizeCorner::BottomRight, local_aabb.maxs),
+        ]
+        .map(|(corner, pos)| (corner, rectangle.transform.transform_point(pos)))
+    }
+
+    fn crop_node_bounds(corner_pos: na::Point2<f64>, camera: &Camera) -> Aabb {
+        Aabb::from_half_extents(
+            corner_pos,
+            Self::RESIZE_NODE_SIZE * 0.5 / camera.total_zoom(),
+        )
+    }
+
+    /// Draws the outline of the uncropped image, the visible region and the crop nodes at its corners.
+    fn draw_crop_overlay(
+        piet_cx: &mut impl RenderContext,
+        rectangle: Rectangle,
+        crop: ImageCrop,
+        crop_state: &CropState,
+        pos: Option<na::Vector2<f64>>,
+        camera: &Camera,
+        visual_settings: VisualSettings,
+    ) -> anyhow::Result<()> {
+        piet_cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        let total_zoom = camera.total_zoom();
+        let outline_color =
+            visual_settings.outline_color(visual_settings.accented(Self::SELECTION_OUTLINE_COLOR));
+        let outline_width = visual_settings.outline_width(Self::OUTLINE_STROKE_WIDTH) / total_zoom;
+        let fill_color = visual_settings.fill_color(Self::SELECTION_FILL_COLOR);
+
+        let mut stroke_style = piet::StrokeStyle::new();
+        stroke_style.set_dash_pattern(
+            Self::SELECTING_DASH_PATTERN
+                .into_iter()
+                .map(|x| x / total_zoom)
+                .collect::<Vec<f64>>(),
+        );
+        piet_cx.stroke_styled(
+            rectangle.outline_path(),
+            &outline_color,
+            outline_width,
+            &stroke_style,
+        );
+
+        let cropped_path = crop.cropped_rectangle(&rectangle).outline_path();
+        piet_cx.fill(cropped_path.clone(), &fill_color);
+        piet_cx.stroke(cropped_path, &outline_color, outline_width);
+
+        for (corner, corner_pos) in Self::crop_corners(&rectangle, crop) {
+            let node_bounds = Self::crop_node_bounds(corner_pos, camera);
+            let node_state = if *crop_state
+                == (CropState::Drag {
+                    from_corner: corner,
+                }) {
+                PenState::Down
+            } else if pos.is_some_and(|pos| node_bounds.contains_local_point(&pos.into())) {
+                PenState::Proximity
+            } else {
+                PenState::Up
+            };
+            indicators::draw_rectangular_node(piet_cx, node_state, node_bounds, total_zoom);
+        }
+
+        piet_cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+
+    fn edit_node_bounds(node_pos: na::Vector2<f64>, camera: &Camera) -> Aabb {
+        Aabb::from_half_extents(
+            node_pos.into(),
+            na::Vector2::repeat(Self::EDIT_NODE_SIZE * 0.5 / camera.total_zoom()),
+        )
+    }
+
+    /// The index of the bezier path node at the position.
+    ///
+    /// When nodes overlap the nearest one is picked, control points are preferred over anchors
+    /// so that they can be pulled out of an anchor they coincide with.
+    fn edit_node_at_pos(
+        bezier_path: &BezierPath,
+        pos: na::Vector2<f64>,
+        camera: &Camera,
+    ) -> Option<usize> {
+        bezier_path
+            .nodes()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, node_pos)| {
+                Self::edit_node_bounds(*node_pos, camera).contains_local_point(&pos.into())
+            })
+            .min_by(|(a_index, a_pos), (b_index, b_pos)| {
+                (a_pos - pos)
+                    .magnitude()
+                    .total_cmp(&(b_pos - pos).magnitude())
+                    .then(
+                        BezierPath::is_anchor_node(*a_index)
+                            .cmp(&BezierPath::is_anchor_node(*b_index)),
+                    )
+            })
+            .map(|(index, _)| index)
+    }
+
+    /// Draws the handle lines and the nodes of the bezier path.
+    ///
+    /// Anchors are drawn as rectangular and control points as circular nodes.
+    fn draw_edit_nodes_overlay(
+        piet_cx: &mut impl RenderContext,
+        bezier_path: &BezierPath,
+        edit_state: &EditNodesState,
+        pos: Option<na::Vector2<f64>>,
+        camera: &Camera,
+    ) -> anyhow::Result<()> {
+        piet_cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        let total_zoom = camera.total_zoom();
+
+        for (anchor, handle) in bezier_path.handle_lines() {
+            indicators::draw_vec_indicator(piet_cx, PenState::Up, anchor, handle, total_zoom);
+        }
+
+        let hovered = pos.and_then(|pos| Self::edit_node_at_pos(bezier_path, pos, camera));
+        for (index, node_pos) in bezier_path.nodes().into_iter().enumerate() {
+            let node_state = if *edit_state == (EditNodesState::Drag { node: index }) {
+                PenState::Down
+            } else if hovered == Some(index) {
+                PenState::Proximity
+            } else {
+                PenState::Up
+            };
+            if BezierPath::is_anchor_node(index) {
+                indicators::draw_rectangular_node(
+                    piet_cx,
+                    node_state,
+                    Self::edit_node_bounds(node_pos, camera),
+                    total_zoom,
+                );
+            } else {
+                indicators::draw_circular_node(
+                    piet_cx,
+                    node_state,
+                    BoundingSphere::new(node_pos.into(), Self::EDIT_NODE_SIZE * 0.5 / total_zoom),
+                    total_zoom,
+                );
+            }
+        }
+
+        piet_cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+
+    /// Enters the node editing mode when the selection consists of a single bezier path shape.
+    pub(crate) fn start_edit_nodes(&mut self, engine_view: &mut EngineViewMut) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let SelectorState::ModifySelection { selection, .. } = &self.state else {
+            return widget_flags;
+        };
+        let &[key] = selection.as_slice() else {
+            return widget_flags;
+        };
+        if engine_view
+            .store
+            .get_stroke_ref(key)
+            .and_then(|stroke| stroke.bezier_path())
+            .is_none()
+        {
+            return widget_flags;
+        }
+        self.state = SelectorState::EditNodes {
+            key,
+            edit_state: EditNodesState::Idle,
+        };
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// Leaves the node editing mode, keeping the edited stroke selected.
+    pub(crate) fn finish_edit_nodes(&mut self, engine_view: &mut EngineViewMut) -> WidgetFlags {
+        if !self.is_editing_nodes() {
+            return WidgetFlags::default();
+        }
+        self.update_state(engine_view)
+    }
+
+    pub(crate) fn is_editing_nodes(&self) -> bool {
+        matches!(self.state, SelectorState::EditNodes { .. })
+    }
+
+    /// Enters the crop mode when the selection consists of a single image.
+    pub(crate) fn start_image_crop(&mut self, engine_view: &mut EngineViewMut) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let SelectorState::ModifySelection { selection, .. } = &self.state else {
+            return widget_flags;
+        };
+        let &[key] = selection.as_slice() else {
+            return widget_flags;
+        };
+        if engine_view
+            .store
+            .get_stroke_ref(key)
+            .and_then(|stroke| stroke.image_crop())
+            .is_none()
+        {
+            return widget_flags;
+        }
+        self.state = SelectorState::CropImage {
+            key,
+            crop_state: CropState::Idle,
+        };
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
+    /// Leaves the crop mode, keeping the cropped image selected.
+    pub(crate) fn finish_image_crop(&mut self, engine_view: &mut EngineViewMut) -> WidgetFlags {
+        if !self.is_cropping_image() {
+            return WidgetFlags::default();
+        }
+        self.update_state(engine_view)
+    }
+
+    pub(crate) fn is_cropping_image(&self) -> bool {
+        matches!(self.state, SelectorState::CropImage { .. })
+    }
+
     fn select_all(&mut self, engine_view: &mut EngineViewMut, widget_flags: &mut WidgetFlags) {
         // Select all keys
         let all_strokes = engine_view.store.stroke_keys_as_rendered();
@@ -1,10 +1,11 @@
 // Imports
-use super::{ModifyState, ResizeCorner, Selector, SelectorState};
+use super::{CropState, EditNodesState, ModifyState, ResizeCorner, Selector, SelectorState};
 use crate::engine::EngineViewMut;
 use crate::pens::pensconfig::selectorconfig::SelectorStyle;
 use crate::pens::PenBehaviour;
 use crate::snap::SnapCorner;
 use crate::store::StrokeKey;
+use crate::strokes::ImageCrop;
 use crate::WidgetFlags;
 use p2d::bounding_volume::Aabb;
 use p2d::query::PointQuery;
@@ -12,6 +13,7 @@ use rnote_compose::eventresult::{EventPropagation, EventResult};
 use rnote_compose::ext::{AabbExt, Vector2Ext};
 use rnote_compose::penevent::{KeyboardKey, ModifierKey, PenProgress};
 use rnote_compose::penpath::Element;
+use rnote_compose::shapes::Shapeable;
 use std::collections::HashSet;
 use std::time::Instant;
 
@@ -75,6 +77,7 @@ impl Selector {
                 modify_state,
                 selection,
                 selection_bounds,
+                rotation_pivot,
             } => {
                 let mut progress = PenProgress::InProgress;
 
@@ -84,10 +87,7 @@ impl Selector {
                         // while pressing Shift, we add it to the selection
                         let key_to_add = engine_view
                             .store
-                            .stroke_hitboxes_contain_coord(
-                                engine_view.camera.viewport(),
-                                element.pos,
-                            )
+                            .stroke_hitboxes_contain_coord(element.pos)
                             .pop();
 
                         if (modifier_keys.contains(&ModifierKey::KeyboardShift))
@@ -103,21 +103,26 @@ impl Selector {
                             {
                                 *selection_bounds = new_bounds;
                             }
-                        } else if Self::rotate_node_sphere(*selection_bounds, engine_view.camera)
-                            .contains_local_point(&element.pos.into())
+                        } else if Self::rotate_node_sphere(
+                            *selection_bounds,
+                            engine_view.camera,
+                            engine_view.righthanded,
+                        )
+                        .contains_local_point(&element.pos.into())
                         {
                             // clicking on the rotate node
+                            let rotation_center =
+                                rotation_pivot.unwrap_or_else(|| selection_bounds.center());
                             let rotation_angle = {
-                                let vec = element.pos - selection_bounds.center().coords;
+                                let vec = element.pos - rotation_center.coords;
                                 na::Vector2::x().angle_ahead(&vec)
                             };
 
                             *modify_state = ModifyState::Rotate {
-                                rotation_center: selection_bounds.center(),
+                                rotation_center,
                                 start_rotation_angle: rotation_angle,
                                 current_rotation_angle: rotation_angle,
                             };
-                            // clicking on one of the resize nodes at the corners
                         } else if Self::resize_node_bounds(
                             ResizeCorner::TopLeft,
                             *selection_bounds,
@@ -125,6 +130,7 @@ impl Selector {
                         )
                         .contains_local_point(&element.pos.into())
                         {
+                            // clicking on one of the resize nodes at the corners
                             *modify_state = ModifyState::Resize {
                                 from_corner: ResizeCorner::TopLeft,
                                 start_bounds: *selection_bounds,
@@ -170,6 +176,18 @@ impl Selector {
                                 start_pos: element.pos,
                                 last_rendered_bounds: *selection_bounds,
                             }
+                        } else if Self::pivot_node_sphere(
+                            rotation_pivot.unwrap_or_else(|| selection_bounds.center()),
+                            engine_view.camera,
+                        )
+                        .contains_local_point(&element.pos.into())
+                            && (modifier_keys.contains(&ModifierKey::KeyboardAlt)
+                                || !selection_bounds.contains_local_point(&element.pos.into()))
+                        {
+                            // clicking on the rotation pivot, starting to drag it.
+                            // Inside the selection bounds only together with Alt, so that the selection can
+                            // still be moved by grabbing it in the center.
+                            *modify_state = ModifyState::DragPivot;
                         } else if engine_view.pens_config.selector_config.style
                             == SelectorStyle::Single
                             && key_to_add
@@ -234,6 +252,9 @@ impl Selector {
                                 .store
                                 .translate_strokes_images(selection, offset);
                             *selection_bounds = selection_bounds.translate(offset);
+                            if let Some(rotation_pivot) = rotation_pivot {
+                                *rotation_pivot += offset;
+                            }
                             *current_pos += offset;
                         }
 
@@ -282,6 +303,10 @@ impl Selector {
                             *current_rotation_angle = new_rotation_angle;
                         }
                     }
+                    ModifyState::DragPivot => {
+                        *rotation_pivot =
+                            Some(engine_view.document.snap_position(element.pos).into());
+                    }
                     ModifyState::Resize {
                         from_corner,
                         start_bounds,
@@ -350,6 +375,16 @@ impl Selector {
                         engine_view
                             .store
                             .scale_strokes_with_pivot(selection, scale, pivot);
+                        if !engine_view
+                            .pens_config
+                            .selector_config
+                            .resize_scale_stroke_width
+                        {
+                            // Scaling the strokes also scales their widths, so this undoes it.
+                            engine_view
+                                .store
+                                .scale_stroke_widths(selection, 1.0 / (scale[0] * scale[1]).sqrt());
+                        }
                         engine_view
                             .store
                             .scale_strokes_images_with_pivot(selection, scale, pivot);
@@ -357,6 +392,12 @@ impl Selector {
                             .translate(-pivot)
                             .scale_non_uniform(scale)
                             .translate(pivot);
+                        // The custom rotation pivot keeps its position relative to the selection
+                        if let Some(rotation_pivot) = rotation_pivot {
+                            *rotation_pivot =
+                                ((rotation_pivot.coords - pivot).component_mul(&scale) + pivot)
+                                    .into();
+                        }
 
                         // possibly nudge camera
                         widget_flags |= engine_view
@@ -403,6 +444,120 @@ impl Selector {
                     progress,
                 }
             }
+            SelectorState::CropImage { key, crop_state } => {
+                let key = *key;
+                let image_crop = engine_view
+                    .store
+                    .get_stroke_ref(key)
+                    .and_then(|stroke| stroke.image_crop());
+
+                match (*crop_state, image_crop) {
+                    (_, None) => {
+                        // the image got removed in the meantime
+                        widget_flags |= self.update_state(engine_view);
+                    }
+                    (CropState::Idle, Some((rectangle, crop))) => {
+                        let crop = crop.unwrap_or_default();
+                        let local_pos =
+                            rectangle.transform.affine.inverse() * na::Point2::from(element.pos);
+
+                        if let Some((from_corner, _)) = Self::crop_corners(&rectangle, crop)
+                            .into_iter()
+                            .find(|(_, corner_pos)| {
+                                Self::crop_node_bounds(*corner_pos, engine_view.camera)
+                                    .contains_local_point(&element.pos.into())
+                            })
+                        {
+                            // clicking on one of the crop nodes at the corners
+                            *crop_state = CropState::Drag { from_corner };
+                        } else if !crop.local_aabb(&rectangle).contains_local_point(&local_pos) {
+                            // when clicking outside the visible region, leave the crop mode
+                            widget_flags |= self.update_state(engine_view);
+                        }
+                    }
+                    (CropState::Drag { from_corner }, Some((rectangle, crop))) => {
+                        let current = crop.unwrap_or_default().local_aabb(&rectangle);
+                        let local_pos =
+                            rectangle.transform.affine.inverse() * na::Point2::from(element.pos);
+                        // The corner across from the dragged one stays in place
+                        let (mins, maxs) = match from_corner {
+                            ResizeCorner::TopLeft => (local_pos, current.maxs),
+                            ResizeCorner::TopRight => (
+                                na::point![current.mins[0], local_pos[1]],
+                                na::point![local_pos[0], current.maxs[1]],
+                            ),
+                            ResizeCorner::BottomLeft => (
+                                na::point![local_pos[0], current.mins[1]],
+                                na::point![current.maxs[0], local_pos[1]],
+                            ),
+                            ResizeCorner::BottomRight => (current.mins, local_pos),
+                        };
+                        let new_crop =
+                            ImageCrop::from_local_aabb(&rectangle, Aabb::new(mins, maxs));
+
+                        if engine_view.store.set_image_crop(key, Some(new_crop)) {
+                            engine_view.store.regenerate_rendering_for_stroke_threaded(
+                                engine_view.tasks_tx.clone(),
+                                key,
+                                engine_view.camera.viewport(),
+                                engine_view.camera.image_scale(),
+                            );
+                            widget_flags.store_modified = true;
+                        }
+                        widget_flags.redraw = true;
+                    }
+                }
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            SelectorState::EditNodes { key, edit_state } => {
+                let key = *key;
+                let stroke = engine_view.store.get_stroke_ref(key);
+                let stroke_bounds = stroke.map(|stroke| stroke.bounds());
+                let bezier_path = stroke.and_then(|stroke| stroke.bezier_path());
+
+                match (*edit_state, bezier_path, stroke_bounds) {
+                    (_, None, _) | (_, _, None) => {
+                        // the stroke got removed in the meantime
+                        widget_flags |= self.update_state(engine_view);
+                    }
+                    (EditNodesState::Idle, Some(bezier_path), Some(stroke_bounds)) => {
+                        if let Some(node) =
+                            Self::edit_node_at_pos(bezier_path, element.pos, engine_view.camera)
+                        {
+                            *edit_state = EditNodesState::Drag { node };
+                        } else if !stroke_bounds.contains_local_point(&element.pos.into()) {
+                            // when clicking outside the stroke, leave the node editing mode
+                            widget_flags |= self.update_state(engine_view);
+                        }
+                    }
+                    (EditNodesState::Drag { node }, Some(_), Some(_)) => {
+                        if engine_view
+                            .store
+                            .move_bezier_path_node(key, node, element.pos)
+                        {
+                            engine_view.store.regenerate_rendering_for_stroke_threaded(
+                                engine_view.tasks_tx.clone(),
+                                key,
+                                engine_view.camera.viewport(),
+                                engine_view.camera.image_scale(),
+                            );
+                            widget_flags.store_modified = true;
+                        }
+                        widget_flags.redraw = true;
+                    }
+                }
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
         };
 
         (event_result, widget_flags)
@@ -432,10 +587,7 @@ impl Selector {
                         if path.len() >= 3 {
                             engine_view
                                 .store
-                                .strokes_hitboxes_contained_in_path_polygon(
-                                    path,
-                                    engine_view.camera.viewport(),
-                                )
+                                .strokes_hitboxes_contained_in_path_polygon(path)
                         } else {
                             vec![]
                         }
@@ -443,10 +595,7 @@ impl Selector {
                     SelectorStyle::Rectangle => {
                         if let (Some(first), Some(last)) = (path.first(), path.last()) {
                             let aabb = Aabb::new_positive(first.pos.into(), last.pos.into());
-                            engine_view.store.strokes_hitboxes_contained_in_aabb(
-                                aabb,
-                                engine_view.camera.viewport(),
-                            )
+                            engine_view.store.strokes_hitboxes_contained_in_aabb(aabb)
                         } else {
                             vec![]
                         }
@@ -455,10 +604,7 @@ impl Selector {
                         if let Some(key) = path.last().and_then(|last| {
                             engine_view
                                 .store
-                                .stroke_hitboxes_contain_coord(
-                                    engine_view.camera.viewport(),
-                                    last.pos,
-                                )
+                                .stroke_hitboxes_contain_coord(last.pos)
                                 .pop()
                         }) {
                             vec![key]
@@ -468,10 +614,7 @@ impl Selector {
                     }
                     SelectorStyle::IntersectingPath => {
                         if path.len() >= 3 {
-                            engine_view.store.strokes_hitboxes_intersect_path(
-                                path,
-                                engine_view.camera.viewport(),
-                            )
+                            engine_view.store.strokes_hitboxes_intersect_path(path)
                         } else {
                             vec![]
                         }
@@ -499,6 +642,7 @@ impl Selector {
                 modify_state,
                 selection,
                 selection_bounds,
+                rotation_pivot,
             } => {
                 match modify_state {
                     ModifyState::Translate { .. }
@@ -524,11 +668,52 @@ impl Selector {
                         widget_flags |= engine_view.store.record(Instant::now());
                         widget_flags.store_modified = true;
                     }
+                    ModifyState::DragPivot => {
+                        // Dropping the pivot onto the center of the selection resets it
+                        if rotation_pivot.is_some_and(|rotation_pivot| {
+                            Self::pivot_node_sphere(selection_bounds.center(), engine_view.camera)
+                                .contains_local_point(&rotation_pivot)
+                        }) {
+                            *rotation_pivot = None;
+                        }
+                    }
                     _ => {}
                 }
 
                 *modify_state = ModifyState::Idle;
 
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Proceed,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            SelectorState::CropImage { crop_state, .. } => {
+                if matches!(crop_state, CropState::Drag { .. }) {
+                    widget_flags |= engine_view
+                        .document
+                        .resize_autoexpand(engine_view.store, engine_view.camera);
+                    widget_flags |= engine_view.store.record(Instant::now());
+                    widget_flags.store_modified = true;
+                }
+                *crop_state = CropState::Idle;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Proceed,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            SelectorState::EditNodes { edit_state, .. } => {
+                if matches!(edit_state, EditNodesState::Drag { .. }) {
+                    widget_flags |= engine_view
+                        .document
+                        .resize_autoexpand(engine_view.store, engine_view.camera);
+                    widget_flags |= engine_view.store.record(Instant::now());
+                    widget_flags.store_modified = true;
+                }
+                *edit_state = EditNodesState::Idle;
+
                 EventResult {
                     handled: true,
                     propagate: EventPropagation::Proceed,
@@ -570,6 +755,11 @@ impl Selector {
                     progress: PenProgress::InProgress,
                 }
             }
+            SelectorState::CropImage { .. } | SelectorState::EditNodes { .. } => EventResult {
+                handled: true,
+                propagate: EventPropagation::Stop,
+                progress: PenProgress::InProgress,
+            },
         };
 
         (event_result, widget_flags)
@@ -710,6 +900,36 @@ impl Selector {
                     },
                 }
             }
+            SelectorState::CropImage { .. } => match keyboard_key {
+                KeyboardKey::Escape | KeyboardKey::CarriageReturn | KeyboardKey::Linefeed => {
+                    widget_flags |= self.finish_image_crop(engine_view);
+                    EventResult {
+                        handled: true,
+                        propagate: EventPropagation::Stop,
+                        progress: PenProgress::InProgress,
+                    }
+                }
+                _ => EventResult {
+                    handled: false,
+                    propagate: EventPropagation::Proceed,
+                    progress: PenProgress::InProgress,
+                },
+            },
+            SelectorState::EditNodes { .. } => match keyboard_key {
+                KeyboardKey::Escape | KeyboardKey::CarriageReturn | KeyboardKey::Linefeed => {
+                    widget_flags |= self.finish_edit_nodes(engine_view);
+                    EventResult {
+                        handled: true,
+                        propagate: EventPropagation::Stop,
+                        progress: PenProgress::InProgress,
+                    }
+                }
+                _ => EventResult {
+                    handled: false,
+                    propagate: EventPropagation::Proceed,
+                    progress: PenProgress::InProgress,
+                },
+            },
         };
 
         (event_result, widget_flags)
@@ -735,7 +955,9 @@ impl Selector {
                 propagate: EventPropagation::Proceed,
                 progress: PenProgress::InProgress,
             },
-            SelectorState::ModifySelection { .. } => EventResult {
+            SelectorState::ModifySelection { .. }
+            | SelectorState::CropImage { .. }
+            | SelectorState::EditNodes { .. } => EventResult {
                 handled: false,
                 propagate: EventPropagation::Proceed,
                 progress: PenProgress::InProgress,
@@ -776,6 +998,22 @@ impl Selector {
                     progress: PenProgress::Finished,
                 }
             }
+            SelectorState::CropImage { .. } => {
+                widget_flags |= self.finish_image_crop(engine_view);
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            SelectorState::EditNodes { .. } => {
+                widget_flags |= self.finish_edit_nodes(engine_view);
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
         };
 
         (event_result, widget_flags)
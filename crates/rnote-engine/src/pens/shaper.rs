@@ -8,20 +8,29 @@ use crate::{DrawableOnDoc, WidgetFlags};
 use p2d::bounding_volume::Aabb;
 use piet::RenderContext;
 use rnote_compose::builders::buildable::{Buildable, BuilderCreator, BuilderProgress};
-use rnote_compose::builders::{ArrowBuilder, GridBuilder, PolygonBuilder, PolylineBuilder};
+use rnote_compose::builders::{
+    ArrowBuilder, BezierPathBuilder, GridBuilder, PolygonBuilder, PolylineBuilder,
+};
 use rnote_compose::builders::{
     CoordSystem2DBuilder, CoordSystem3DBuilder, CubBezBuilder, EllipseBuilder, FociEllipseBuilder,
     LineBuilder, QuadBezBuilder, QuadrantCoordSystem2DBuilder, RectangleBuilder, ShapeBuilderType,
 };
+use rnote_compose::color;
 use rnote_compose::eventresult::{EventPropagation, EventResult};
+use rnote_compose::ext::Vector2Ext;
 use rnote_compose::penevent::{KeyboardKey, ModifierKey, PenEvent, PenProgress};
 use rnote_compose::penpath::Element;
 use rnote_compose::Shape;
+use rnote_compose::Style;
 use std::time::Instant;
 
 #[derive(Debug)]
 enum ShaperState {
     Idle,
+    /// The stylus hovers above the canvas, the preview is drawn at the element position.
+    Proximity {
+        element: Element,
+    },
     BuildShape {
         builder: Box<dyn Buildable<Emit = Shape>>,
     },
@@ -40,12 +49,20 @@ impl Default for Shaper {
     }
 }
 
+impl Shaper {
+    /// The length of the proximity preview crosshair arms, in surface coordinates.
+    const PROXIMITY_CROSSHAIR_LEN: f64 = 12.0;
+    /// The width of the proximity preview crosshair lines, in surface coordinates.
+    const PROXIMITY_CROSSHAIR_WIDTH: f64 = 1.5;
+}
+
 impl PenBehaviour for Shaper {
     fn init(&mut self, _engine_view: &EngineView) -> WidgetFlags {
         WidgetFlags::default()
     }
 
     fn deinit(&mut self) -> WidgetFlags {
+        self.state = ShaperState::Idle;
         WidgetFlags::default()
     }
 
@@ -66,7 +83,7 @@ impl PenBehaviour for Shaper {
         let mut widget_flags = WidgetFlags::default();
 
         let event_result = match (&mut self.state, event) {
-            (ShaperState::Idle, PenEvent::Down { element, .. }) => {
+            (ShaperState::Idle | ShaperState::Proximity { .. }, PenEvent::Down { element, .. }) => {
                 engine_view.pens_config.shaper_config.new_style_seeds();
 
                 self.state = ShaperState::BuildShape {
@@ -83,7 +100,28 @@ impl PenBehaviour for Shaper {
                     progress: PenProgress::InProgress,
                 }
             }
-            (ShaperState::Idle, _) => EventResult {
+            (
+                ShaperState::Idle | ShaperState::Proximity { .. },
+                PenEvent::Proximity { element, .. },
+            ) => {
+                self.state = ShaperState::Proximity { element };
+
+                EventResult {
+                    handled: false,
+                    propagate: EventPropagation::Proceed,
+                    progress: PenProgress::Idle,
+                }
+            }
+            (ShaperState::Proximity { .. }, PenEvent::Up { .. } | PenEvent::Cancel) => {
+                self.state = ShaperState::Idle;
+
+                EventResult {
+                    handled: false,
+                    propagate: EventPropagation::Proceed,
+                    progress: PenProgress::Idle,
+                }
+            }
+            (ShaperState::Idle | ShaperState::Proximity { .. }, _) => EventResult {
                 handled: false,
                 propagate: EventPropagation::Proceed,
                 progress: PenProgress::Idle,
@@ -126,13 +164,22 @@ impl PenBehaviour for Shaper {
                             .pens_config
                             .shaper_config
                             .gen_style_for_current_options();
+                        let outline = engine_view
+                            .pens_config
+                            .shaper_config
+                            .outline_for_current_options();
                         let shapes_emitted = !shapes.is_empty();
 
                         for shape in shapes {
-                            let key = engine_view.store.insert_stroke(
-                                Stroke::ShapeStroke(ShapeStroke::new(shape, style.clone())),
-                                None,
-                            );
+                            let shape = engine_view
+                                .pens_config
+                                .shaper_config
+                                .post_process_shape(shape, &engine_view.document.format);
+                            let mut shapestroke = ShapeStroke::new(shape, style.clone());
+                            shapestroke.outline = outline;
+                            let key = engine_view
+                                .store
+                                .insert_stroke(Stroke::ShapeStroke(shapestroke), None);
                             style.advance_seed();
                             engine_view.store.regenerate_rendering_for_stroke(
                                 key,
@@ -152,13 +199,22 @@ impl PenBehaviour for Shaper {
                             .pens_config
                             .shaper_config
                             .gen_style_for_current_options();
+                        let outline = engine_view
+                            .pens_config
+                            .shaper_config
+                            .outline_for_current_options();
 
                         let shapes_emitted = !shapes.is_empty();
                         for shape in shapes {
-                            let key = engine_view.store.insert_stroke(
-                                Stroke::ShapeStroke(ShapeStroke::new(shape, style.clone())),
-                                None,
-                            );
+                            let shape = engine_view
+                                .pens_config
+                                .shaper_config
+                                .post_process_shape(shape, &engine_view.document.format);
+                            let mut shapestroke = ShapeStroke::new(shape, style.clone());
+                            shapestroke.outline = outline;
+                            let key = engine_view
+                                .store
+                                .insert_stroke(Stroke::ShapeStroke(shapestroke), None);
                             style.advance_seed();
                             engine_view.store.regenerate_rendering_for_stroke(
                                 key,
@@ -213,7 +269,25 @@ impl DrawableOnDoc for Shaper {
 
         match &self.state {
             ShaperState::Idle => None,
+            ShaperState::Proximity { element } => {
+                let half_extent = (Self::PROXIMITY_CROSSHAIR_LEN + Self::PROXIMITY_CROSSHAIR_WIDTH)
+                    / engine_view.camera.total_zoom()
+                    + style.stroke_width() * 0.5;
+                Some(Aabb::from_half_extents(
+                    element.pos.into(),
+                    na::Vector2::repeat(half_extent),
+                ))
+            }
             ShaperState::BuildShape { builder } => {
+                // The outline style is wider, so its bounds include the outline
+                let style = match engine_view
+                    .pens_config
+                    .shaper_config
+                    .outline_for_current_options()
+                {
+                    Some(outline) => outline.outline_style(&style),
+                    None => style,
+                };
                 builder.bounds(&style, engine_view.camera.total_zoom())
             }
         }
@@ -232,7 +306,21 @@ impl DrawableOnDoc for Shaper {
 
         match &self.state {
             ShaperState::Idle => {}
+            ShaperState::Proximity { element } => {
+                draw_proximity_preview(cx, element.pos, &style, engine_view.camera.total_zoom())
+            }
             ShaperState::BuildShape { builder } => {
+                if let Some(outline) = engine_view
+                    .pens_config
+                    .shaper_config
+                    .outline_for_current_options()
+                {
+                    builder.draw_styled(
+                        cx,
+                        &outline.outline_style(&style),
+                        engine_view.camera.total_zoom(),
+                    );
+                }
                 builder.draw_styled(cx, &style, engine_view.camera.total_zoom())
             }
         }
@@ -242,6 +330,46 @@ impl DrawableOnDoc for Shaper {
     }
 }
 
+/// Draws a lightweight preview where the shape will start when the stylus is put down.
+///
+/// Consists of a crosshair and a dot with the size and color of the current stroke style.
+fn draw_proximity_preview(
+    cx: &mut piet_cairo::CairoRenderContext,
+    pos: na::Vector2<f64>,
+    style: &Style,
+    total_zoom: f64,
+) {
+    const CROSSHAIR_COLOR: piet::Color = color::GNOME_DARKS[2].with_a8(200);
+    let arm_len = Shaper::PROXIMITY_CROSSHAIR_LEN / total_zoom;
+    let line_width = Shaper::PROXIMITY_CROSSHAIR_WIDTH / total_zoom;
+    let center = pos.to_kurbo_point();
+
+    cx.stroke(
+        kurbo::Line::new(
+            (pos - na::vector![arm_len, 0.0]).to_kurbo_point(),
+            (pos + na::vector![arm_len, 0.0]).to_kurbo_point(),
+        ),
+        &CROSSHAIR_COLOR,
+        line_width,
+    );
+    cx.stroke(
+        kurbo::Line::new(
+            (pos - na::vector![0.0, arm_len]).to_kurbo_point(),
+            (pos + na::vector![0.0, arm_len]).to_kurbo_point(),
+        ),
+        &CROSSHAIR_COLOR,
+        line_width,
+    );
+
+    if let Some(stroke_color) = style.stroke_color() {
+        let dot_color: piet::Color = stroke_color.into();
+        cx.fill(
+            kurbo::Circle::new(center, (style.stroke_width() * 0.5).max(line_width)),
+            &dot_color.with_alpha(0.6),
+        );
+    }
+}
+
 fn new_builder(
     builder_type: ShapeBuilderType,
     element: Element,
@@ -263,5 +391,6 @@ fn new_builder(
         ShapeBuilderType::CubBez => Box::new(CubBezBuilder::start(element, now)),
         ShapeBuilderType::Polyline => Box::new(PolylineBuilder::start(element, now)),
         ShapeBuilderType::Polygon => Box::new(PolygonBuilder::start(element, now)),
+        ShapeBuilderType::BezierPath => Box::new(BezierPathBuilder::start(element, now)),
     }
 }
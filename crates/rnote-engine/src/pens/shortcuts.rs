@@ -49,6 +49,12 @@ pub enum ShortcutAction {
         #[serde(rename = "mode")]
         mode: ShortcutMode,
     },
+    /// Swap between the current and the previously used pen style.
+    #[serde(rename = "change_to_previous_pen_style")]
+    ChangeToPreviousPenStyle,
+    /// Draw with the modifier color of the brush while the shortcut key is held.
+    #[serde(rename = "modifier_color")]
+    ModifierColor,
 }
 
 /// The registered shortcut actions for the given shortcut keys.
@@ -0,0 +1,401 @@
+// Imports
+use super::ToolsState;
+use crate::engine::{EngineTask, EngineView, EngineViewMut};
+use crate::store::{StrokeKey, StrokeStore};
+use crate::strokes::{ShapeStroke, Stroke};
+use crate::{render, Drawable, DrawableOnDoc, WidgetFlags};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use rnote_compose::eventresult::EventPropagation;
+use rnote_compose::penevent::PenProgress;
+use rnote_compose::shapes::{Polygon, Shape, Shapeable};
+use rnote_compose::style::smooth::SmoothOptions;
+use rnote_compose::{Color, EventResult, PenEvent, Style};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, error};
+
+/// Fills the closed region around the pointer that is bounded by strokes.
+///
+/// The region is found by rasterizing the strokes in the viewport and flood-filling from the pointer position
+/// in a background thread. Its outline is then traced and inserted as a filled polygon beneath the bounding strokes
+/// once the [EngineTask::InsertFillRegion] task is handled.
+#[derive(Clone, Debug, Default)]
+pub(super) struct FillTool {
+    state: ToolsState,
+}
+
+impl FillTool {
+    /// Pixels with a larger alpha value are boundaries of the filled region.
+    const BOUNDARY_ALPHA_THRESHOLD: u8 = 48;
+    /// The maximum number of pixels of the rasterized viewport.
+    const RASTER_PIXELS_MAX: f64 = 4_000_000.0;
+    /// The region is grown by this many pixels, so that it reaches beneath the antialiased edges of the strokes.
+    const REGION_GROW_PIXELS: usize = 1;
+    /// The tolerance of the simplification of the traced outline, in pixels.
+    const OUTLINE_SIMPLIFY_TOLERANCE: f64 = 0.75;
+
+    pub(super) fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        engine_view: &mut EngineViewMut,
+    ) -> (EventResult<PenProgress>, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+
+        let result = match (&mut self.state, event) {
+            (ToolsState::Idle, PenEvent::Down { element, .. }) => {
+                Self::spawn_fill_region_at(engine_view, element.pos);
+                self.state = ToolsState::Active;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            (ToolsState::Idle, _) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::Idle,
+            },
+            (ToolsState::Active, PenEvent::Down { .. }) => EventResult {
+                handled: true,
+                propagate: EventPropagation::Stop,
+                progress: PenProgress::InProgress,
+            },
+            (ToolsState::Active, PenEvent::Up { .. } | PenEvent::Cancel) => {
+                self.state = ToolsState::Idle;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+            (
+                ToolsState::Active,
+                PenEvent::Proximity { .. } | PenEvent::KeyPressed { .. } | PenEvent::Text { .. },
+            ) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+        };
+
+        (result, widget_flags)
+    }
+
+    /// Trace the closed region around the position in a background thread,
+    /// and send the task to insert it as filled polygon when it was found.
+    ///
+    /// Nothing is inserted when the position is on a stroke or the region is not closed within the viewport.
+    fn spawn_fill_region_at(engine_view: &mut EngineViewMut, pos: na::Vector2<f64>) {
+        let viewport = engine_view.camera.viewport();
+        if !viewport.contains_local_point(&pos.into()) {
+            return;
+        }
+        let image_scale = engine_view.camera.total_zoom().min(
+            (Self::RASTER_PIXELS_MAX / (viewport.extents()[0] * viewport.extents()[1])).sqrt(),
+        );
+        let boundary_keys = boundary_stroke_keys(engine_view.store, viewport);
+        let strokes = engine_view.store.get_strokes_arc(&boundary_keys);
+        let fill_color = engine_view.pens_config.tools_config.fill_tool_config.color;
+        let tasks_tx = engine_view.tasks_tx.clone();
+
+        rayon::spawn(
+            move || match Self::trace_region(&strokes, viewport, image_scale, pos) {
+                Ok(Some(outline)) => {
+                    tasks_tx.send(EngineTask::InsertFillRegion {
+                        outline,
+                        fill_color,
+                    });
+                }
+                Ok(None) => {
+                    debug!("Fill tool found no closed region at position {pos:?}");
+                }
+                Err(e) => {
+                    error!("Tracing the region for the fill tool failed, Err: {e:?}");
+                }
+            },
+        );
+    }
+
+    /// Trace the outline of the closed region around the position in document coordinates.
+    ///
+    /// Returns None when the position is on a stroke or the region reaches the border of the viewport.
+    fn trace_region(
+        strokes: &[Arc<Stroke>],
+        viewport: Aabb,
+        image_scale: f64,
+        pos: na::Vector2<f64>,
+    ) -> anyhow::Result<Option<Vec<na::Vector2<f64>>>> {
+        let image = render::Image::gen_with_cairo(
+            |cairo_cx| {
+                for stroke in strokes {
+                    stroke.draw_to_cairo(cairo_cx, image_scale)?;
+                }
+                Ok(())
+            },
+            viewport,
+            image_scale,
+        )?;
+        let raster_bounds = image.rect.bounds();
+        let width = image.pixel_width as usize;
+        let height = image.pixel_height as usize;
+        if width == 0 || height == 0 {
+            return Ok(None);
+        }
+        let boundary = image
+            .data
+            .chunks_exact(4)
+            .map(|pixel| pixel[3] > Self::BOUNDARY_ALPHA_THRESHOLD)
+            .collect::<Vec<bool>>();
+
+        let seed = (pos - raster_bounds.mins.coords) * image_scale;
+        let (seed_x, seed_y) = (seed[0].floor() as usize, seed[1].floor() as usize);
+        if seed_x >= width || seed_y >= height || boundary[seed_y * width + seed_x] {
+            return Ok(None);
+        }
+        let Some(mut region) = flood_fill(&boundary, width, height, seed_x, seed_y) else {
+            return Ok(None);
+        };
+        for _ in 0..Self::REGION_GROW_PIXELS {
+            region = grow_region(&region, width, height);
+        }
+
+        let Some(outline) = trace_outer_outline(&region, width, height) else {
+            return Ok(None);
+        };
+        let outline = simplify_polyline(&outline, Self::OUTLINE_SIMPLIFY_TOLERANCE);
+        if outline.len() < 3 {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            outline
+                .into_iter()
+                .map(|p| raster_bounds.mins.coords + p / image_scale)
+                .collect(),
+        ))
+    }
+}
+
+/// Insert the traced outline of a region as filled polygon beneath the strokes that bound it.
+pub(crate) fn insert_fill_region(
+    engine_view: &mut EngineViewMut,
+    outline: Vec<na::Vector2<f64>>,
+    fill_color: Color,
+) -> WidgetFlags {
+    let mut widget_flags = WidgetFlags::default();
+    let Some((&start, path)) = outline.split_first() else {
+        return widget_flags;
+    };
+    let style = Style::Smooth(SmoothOptions {
+        stroke_width: 0.0,
+        stroke_color: None,
+        fill_color: Some(fill_color),
+        ..Default::default()
+    });
+    let fill_stroke = Stroke::ShapeStroke(ShapeStroke::new(
+        Shape::Polygon(Polygon {
+            start,
+            path: path.to_vec(),
+        }),
+        style,
+    ));
+    let fill_bounds = fill_stroke.bounds();
+    let outline_keys = boundary_stroke_keys(engine_view.store, fill_bounds);
+    let key = engine_view.store.insert_stroke(fill_stroke, None);
+    engine_view.store.update_chrono_below(key, &outline_keys);
+    engine_view.store.regenerate_rendering_for_stroke(
+        key,
+        engine_view.camera.viewport(),
+        engine_view.camera.image_scale(),
+    );
+
+    widget_flags |= engine_view
+        .document
+        .resize_autoexpand(engine_view.store, engine_view.camera)
+        | engine_view.store.record(Instant::now());
+    widget_flags.redraw = true;
+    widget_flags.store_modified = true;
+    widget_flags
+}
+
+/// The strokes that can bound a region. Images are ignored, so that regions on imported pages can be filled.
+fn boundary_stroke_keys(store: &StrokeStore, bounds: Aabb) -> Vec<StrokeKey> {
+    store
+        .stroke_keys_as_rendered_intersecting_bounds(bounds)
+        .into_iter()
+        .filter(|&key| {
+            matches!(
+                store.get_stroke_ref(key),
+                Some(Stroke::BrushStroke(_) | Stroke::ShapeStroke(_) | Stroke::TextStroke(_))
+            )
+        })
+        .collect()
+}
+
+impl DrawableOnDoc for FillTool {
+    fn bounds_on_doc(&self, _engine_view: &EngineView) -> Option<Aabb> {
+        None
+    }
+
+    fn draw_on_doc(
+        &self,
+        _cx: &mut piet_cairo::CairoRenderContext,
+        _engine_view: &EngineView,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Flood-fill the 4-connected region of non-boundary pixels around the seed.
+///
+/// Returns None when the region reaches the border of the raster, meaning it is not closed.
+fn flood_fill(
+    boundary: &[bool],
+    width: usize,
+    height: usize,
+    seed_x: usize,
+    seed_y: usize,
+) -> Option<Vec<bool>> {
+    let mut region = vec![false; width * height];
+    let mut queue = VecDeque::from([(seed_x, seed_y)]);
+    region[seed_y * width + seed_x] = true;
+
+    while let Some((x, y)) = queue.pop_front() {
+        if x == 0 || y == 0 || x == width - 1 || y == height - 1 {
+            return None;
+        }
+        for (nx, ny) in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+            let i = ny * width + nx;
+            if !region[i] && !boundary[i] {
+                region[i] = true;
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    Some(region)
+}
+
+/// Grow the region by one pixel in all four directions.
+fn grow_region(region: &[bool], width: usize, height: usize) -> Vec<bool> {
+    let mut grown = region.to_vec();
+    for y in 0..height {
+        for x in 0..width {
+            if !region[y * width + x] {
+                continue;
+            }
+            if x > 0 {
+                grown[y * width + x - 1] = true;
+            }
+            if x + 1 < width {
+                grown[y * width + x + 1] = true;
+            }
+            if y > 0 {
+                grown[(y - 1) * width + x] = true;
+            }
+            if y + 1 < height {
+                grown[(y + 1) * width + x] = true;
+            }
+        }
+    }
+    grown
+}
+
+/// Trace the outer outline of the region along the pixel edges, in pixel coordinates.
+///
+/// The edges between region and non-region pixels are chained into closed loops and the loop enclosing the largest area
+/// is returned, which leaves out the outlines of holes.
+fn trace_outer_outline(
+    region: &[bool],
+    width: usize,
+    height: usize,
+) -> Option<Vec<na::Vector2<f64>>> {
+    let in_region = |x: i64, y: i64| {
+        x >= 0
+            && y >= 0
+            && (x as usize) < width
+            && (y as usize) < height
+            && region[y as usize * width + x as usize]
+    };
+    // Directed edges between pixel corners, running clockwise around the region
+    let mut edges: HashMap<(i64, i64), Vec<(i64, i64)>> = HashMap::new();
+    for y in 0..height as i64 {
+        for x in 0..width as i64 {
+            if !in_region(x, y) {
+                continue;
+            }
+            if !in_region(x, y - 1) {
+                edges.entry((x, y)).or_default().push((x + 1, y));
+            }
+            if !in_region(x + 1, y) {
+                edges.entry((x + 1, y)).or_default().push((x + 1, y + 1));
+            }
+            if !in_region(x, y + 1) {
+                edges.entry((x + 1, y + 1)).or_default().push((x, y + 1));
+            }
+            if !in_region(x - 1, y) {
+                edges.entry((x, y + 1)).or_default().push((x, y));
+            }
+        }
+    }
+
+    let mut outer: Option<(f64, Vec<(i64, i64)>)> = None;
+    while let Some(&start) = edges.keys().next() {
+        let mut corners = vec![start];
+        let mut current = start;
+        loop {
+            let Some(next) = edges.get_mut(&current).and_then(|targets| targets.pop()) else {
+                break;
+            };
+            if next == start {
+                break;
+            }
+            corners.push(next);
+            current = next;
+        }
+        edges.retain(|_, targets| !targets.is_empty());
+
+        // Shoelace formula
+        let area = corners
+            .iter()
+            .zip(corners.iter().cycle().skip(1))
+            .map(|(a, b)| (a.0 * b.1 - b.0 * a.1) as f64)
+            .sum::<f64>()
+            .abs()
+            * 0.5;
+        if outer
+            .as_ref()
+            .map_or(true, |(outer_area, _)| area > *outer_area)
+        {
+            outer = Some((area, corners));
+        }
+    }
+
+    outer.map(|(_, corners)| {
+        corners
+            .into_iter()
+            .map(|(x, y)| na::vector![x as f64, y as f64])
+            .collect()
+    })
+}
+
+/// Simplify the closed polyline with the Ramer-Douglas-Peucker algorithm.
+fn simplify_polyline(points: &[na::Vector2<f64>], tolerance: f64) -> Vec<na::Vector2<f64>> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    // Close the loop, so that the segment back to the start is simplified as well
+    let mut closed = points.to_vec();
+    closed.push(points[0]);
+    let keep = rnote_compose::utils::rdp_keep_mask(&closed, tolerance);
+    closed.pop();
+    closed
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(p, keep)| keep.then_some(p))
+        .collect()
+}
@@ -180,7 +180,9 @@ impl LaserTool {
         if faded {
             self.reset(true);
         } else {
-            if !optimize_epd {
+            // The laser is only faded out gradually when that doesn't cause too many redraws
+            // or is unwanted because motion should be reduced.
+            if !optimize_epd && !engine_view.visual_settings.reduced_motion {
                 let transparency = self
                     .fade_start_time
                     .unwrap() // Never fails because `has_fully_faded` has not returned `None`.
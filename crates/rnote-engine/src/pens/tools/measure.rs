@@ -0,0 +1,285 @@
+// Imports
+use super::ToolsState;
+use crate::engine::{EngineView, EngineViewMut};
+use crate::strokes::{ShapeStroke, Stroke, TextStroke};
+use crate::{DrawableOnDoc, WidgetFlags};
+use p2d::bounding_volume::Aabb;
+use piet::{RenderContext, Text, TextLayout, TextLayoutBuilder};
+use rnote_compose::eventresult::EventPropagation;
+use rnote_compose::ext::{AabbExt, Vector2Ext};
+use rnote_compose::penevent::{KeyboardKey, PenProgress};
+use rnote_compose::shapes::{Line, Shape};
+use rnote_compose::{color, EventResult, PenEvent};
+use std::time::Instant;
+
+#[derive(Clone, Debug)]
+pub(super) struct MeasureTool {
+    state: ToolsState,
+    start: na::Vector2<f64>,
+    current: na::Vector2<f64>,
+}
+
+impl Default for MeasureTool {
+    fn default() -> Self {
+        Self {
+            state: ToolsState::default(),
+            start: na::Vector2::zeros(),
+            current: na::Vector2::zeros(),
+        }
+    }
+}
+
+impl MeasureTool {
+    const LINE_COLOR: piet::Color = color::GNOME_BLUES[3];
+    const LINE_WIDTH: f64 = 1.5;
+    const ENDPOINT_RADIUS: f64 = 3.0;
+    const LABEL_FONT_SIZE: f64 = 12.0;
+    const LABEL_PADDING: f64 = 4.0;
+    /// Size of the area reserved for the readout label in surface coordinates.
+    const LABEL_MAX_SIZE: na::Vector2<f64> = na::vector![240.0, 40.0];
+    const LABEL_TEXT_COLOR: piet::Color = color::GNOME_BRIGHTS[1];
+    const LABEL_BG_COLOR: piet::Color = color::GNOME_DARKS[3].with_a8(220);
+    /// The offset of the annotation text from the end point of the measured line.
+    const ANNOTATION_TEXT_OFFSET: na::Vector2<f64> = na::vector![8.0, 8.0];
+
+    pub(super) fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        engine_view: &mut EngineViewMut,
+    ) -> (EventResult<PenProgress>, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+
+        let result = match (&mut self.state, event) {
+            (ToolsState::Idle, PenEvent::Down { element, .. }) => {
+                self.start = engine_view.document.snap_position(element.pos);
+                self.current = self.start;
+                self.state = ToolsState::Active;
+                widget_flags.redraw = true;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            (ToolsState::Idle, _) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::Idle,
+            },
+            (ToolsState::Active, PenEvent::Down { element, .. }) => {
+                self.current = engine_view.document.snap_position(element.pos);
+                widget_flags |= engine_view
+                    .camera
+                    .nudge_w_pos(element.pos, engine_view.document);
+                widget_flags.redraw = true;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            (ToolsState::Active, PenEvent::Up { element, .. }) => {
+                self.current = engine_view.document.snap_position(element.pos);
+
+                if engine_view
+                    .pens_config
+                    .tools_config
+                    .measure_tool_config
+                    .leave_annotation
+                    && (self.current - self.start).magnitude() > 0.0
+                {
+                    widget_flags |= self.insert_annotation(engine_view);
+                }
+                self.reset();
+                widget_flags.redraw = true;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+            (ToolsState::Active, PenEvent::Proximity { .. }) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+            (ToolsState::Active, PenEvent::KeyPressed { keyboard_key, .. }) => {
+                if keyboard_key == KeyboardKey::Escape {
+                    self.reset();
+                    widget_flags.redraw = true;
+
+                    EventResult {
+                        handled: true,
+                        propagate: EventPropagation::Stop,
+                        progress: PenProgress::Finished,
+                    }
+                } else {
+                    EventResult {
+                        handled: false,
+                        propagate: EventPropagation::Proceed,
+                        progress: PenProgress::InProgress,
+                    }
+                }
+            }
+            (ToolsState::Active, PenEvent::Text { .. }) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+            (ToolsState::Active, PenEvent::Cancel) => {
+                self.reset();
+                widget_flags.redraw = true;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+        };
+
+        (result, widget_flags)
+    }
+
+    fn reset(&mut self) {
+        self.start = na::Vector2::zeros();
+        self.current = na::Vector2::zeros();
+        self.state = ToolsState::Idle;
+    }
+
+    /// The readout of the current measurement, in the configured unit and the document drawing scale.
+    fn readout(&self, engine_view: &EngineView) -> String {
+        let unit = engine_view
+            .pens_config
+            .tools_config
+            .measure_tool_config
+            .unit;
+        let delta = self.current - self.start;
+        let distance = engine_view
+            .document
+            .format
+            .scaled_length(delta.magnitude(), unit);
+        // Angle to the positive x-axis, counterclockwise as usual, while the document y-axis points down.
+        let angle = (-delta[1]).atan2(delta[0]).to_degrees();
+
+        format!("{distance:.2} {}  {angle:.1}°", unit.abbreviation())
+    }
+
+    /// Insert the measured line and its readout as strokes.
+    fn insert_annotation(&self, engine_view: &mut EngineViewMut) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let readout = self.readout(&engine_view.as_im());
+        let line_style = engine_view
+            .pens_config
+            .shaper_config
+            .gen_style_for_current_options();
+        let mut text_style = engine_view.pens_config.typewriter_config.text_style.clone();
+        text_style.ranged_text_attributes.clear();
+        text_style.set_max_width(None);
+
+        let line_key = engine_view.store.insert_stroke(
+            Stroke::ShapeStroke(ShapeStroke::new(
+                Shape::Line(Line {
+                    start: self.start,
+                    end: self.current,
+                }),
+                line_style,
+            )),
+            None,
+        );
+        let text_key = engine_view.store.insert_stroke(
+            Stroke::TextStroke(TextStroke::new(
+                readout,
+                self.current + Self::ANNOTATION_TEXT_OFFSET,
+                text_style,
+            )),
+            None,
+        );
+        for key in [line_key, text_key] {
+            engine_view.store.regenerate_rendering_for_stroke(
+                key,
+                engine_view.camera.viewport(),
+                engine_view.camera.image_scale(),
+            );
+        }
+
+        widget_flags |= engine_view
+            .document
+            .resize_autoexpand(engine_view.store, engine_view.camera)
+            | engine_view.store.record(Instant::now());
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+}
+
+impl DrawableOnDoc for MeasureTool {
+    fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
+        if matches!(self.state, ToolsState::Idle) {
+            return None;
+        }
+        let total_zoom = engine_view.camera.total_zoom();
+
+        Some(
+            Aabb::new_positive(self.start.into(), self.current.into())
+                .extend_by(Self::LABEL_MAX_SIZE / total_zoom),
+        )
+    }
+
+    fn draw_on_doc(
+        &self,
+        cx: &mut piet_cairo::CairoRenderContext,
+        engine_view: &EngineView,
+    ) -> anyhow::Result<()> {
+        if matches!(self.state, ToolsState::Idle) {
+            return Ok(());
+        }
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        let total_zoom = engine_view.camera.total_zoom();
+
+        cx.stroke_styled(
+            kurbo::Line::new(self.start.to_kurbo_point(), self.current.to_kurbo_point()),
+            &Self::LINE_COLOR,
+            Self::LINE_WIDTH / total_zoom,
+            &piet::StrokeStyle::new().line_cap(piet::LineCap::Round),
+        );
+        for pos in [self.start, self.current] {
+            cx.fill(
+                kurbo::Circle::new(pos.to_kurbo_point(), Self::ENDPOINT_RADIUS / total_zoom),
+                &Self::LINE_COLOR,
+            );
+        }
+
+        let text_layout = cx
+            .text()
+            .new_text_layout(self.readout(engine_view))
+            .text_color(Self::LABEL_TEXT_COLOR)
+            .font(
+                piet::FontFamily::SANS_SERIF,
+                Self::LABEL_FONT_SIZE / total_zoom,
+            )
+            .build()
+            .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        let padding = Self::LABEL_PADDING / total_zoom;
+        let label_pos = self.current + na::Vector2::repeat(padding * 3.0);
+        let label_size = text_layout.size();
+        let label_rect = kurbo::Rect::new(
+            label_pos[0] - padding,
+            label_pos[1] - padding,
+            label_pos[0] + label_size.width + padding,
+            label_pos[1] + label_size.height + padding,
+        );
+
+        cx.fill(
+            kurbo::RoundedRect::from_rect(label_rect, padding),
+            &Self::LABEL_BG_COLOR,
+        );
+        cx.draw_text(&text_layout, label_pos.to_kurbo_point());
+
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+}
@@ -1,12 +1,21 @@
 // Modules
+mod fill;
 mod laser;
+mod measure;
 mod offsetcamera;
+mod pdftext;
+mod pushapart;
 mod verticalspace;
 mod zoom;
 
 // Re-Exports
+pub(crate) use fill::insert_fill_region;
+use fill::FillTool;
 use laser::LaserTool;
+use measure::MeasureTool;
 use offsetcamera::OffsetCameraTool;
+use pdftext::PdfTextTool;
+use pushapart::PushApartTool;
 use verticalspace::VerticalSpaceTool;
 use zoom::ZoomTool;
 
@@ -16,11 +25,13 @@ use super::PenBehaviour;
 use super::PenStyle;
 use crate::engine::{EngineView, EngineViewMut};
 use crate::{DrawableOnDoc, WidgetFlags};
+use futures::channel::oneshot;
 use p2d::bounding_volume::Aabb;
 use piet::RenderContext;
 use rnote_compose::eventresult::EventResult;
 use rnote_compose::penevent::{PenEvent, PenProgress};
 use std::time::Instant;
+use tracing::error;
 
 #[derive(Debug, Clone, Copy)]
 enum ToolsState {
@@ -40,6 +51,10 @@ pub struct Tools {
     offsetcamera_tool: OffsetCameraTool,
     zoom_tool: ZoomTool,
     laser_tool: LaserTool,
+    measure_tool: MeasureTool,
+    pdftext_tool: PdfTextTool,
+    fill_tool: FillTool,
+    pushapart_tool: PushApartTool,
 }
 
 impl PenBehaviour for Tools {
@@ -48,6 +63,7 @@ impl PenBehaviour for Tools {
     }
 
     fn deinit(&mut self) -> WidgetFlags {
+        self.pdftext_tool.reset();
         WidgetFlags::default()
     }
 
@@ -73,9 +89,39 @@ impl PenBehaviour for Tools {
             ToolStyle::OffsetCamera => self.offsetcamera_tool.handle_event(event, now, engine_view),
             ToolStyle::Zoom => self.zoom_tool.handle_event(event, now, engine_view),
             ToolStyle::Laser => self.laser_tool.handle_event(event, now, engine_view),
+            ToolStyle::Measure => self.measure_tool.handle_event(event, now, engine_view),
+            ToolStyle::PdfText => self.pdftext_tool.handle_event(event, now, engine_view),
+            ToolStyle::Fill => self.fill_tool.handle_event(event, now, engine_view),
+            ToolStyle::PushApart => self.pushapart_tool.handle_event(event, now, engine_view),
         }
     }
 
+    fn fetch_clipboard_content(
+        &self,
+        engine_view: &EngineView,
+    ) -> oneshot::Receiver<anyhow::Result<(Vec<(Vec<u8>, String)>, WidgetFlags)>> {
+        let (sender, receiver) =
+            oneshot::channel::<anyhow::Result<(Vec<(Vec<u8>, String)>, WidgetFlags)>>();
+        let mut clipboard_content = Vec::with_capacity(1);
+
+        if engine_view.pens_config.tools_config.style == ToolStyle::PdfText {
+            if let Some(selected_text) = self.pdftext_tool.selected_text(engine_view) {
+                clipboard_content.push((
+                    selected_text.into_bytes(),
+                    String::from("text/plain;charset=utf-8"),
+                ));
+            }
+        }
+
+        if sender
+            .send(Ok((clipboard_content, WidgetFlags::default())))
+            .is_err()
+        {
+            error!("Sending fetched tools clipboard content failed, receiver already dropped.");
+        }
+        receiver
+    }
+
     fn handle_animation_frame(&mut self, engine_view: &mut EngineViewMut, optimize_epd: bool) {
         match engine_view.pens_config.tools_config.style {
             ToolStyle::Laser => self
@@ -93,6 +139,10 @@ impl DrawableOnDoc for Tools {
             ToolStyle::OffsetCamera => self.offsetcamera_tool.bounds_on_doc(engine_view),
             ToolStyle::Zoom => self.zoom_tool.bounds_on_doc(engine_view),
             ToolStyle::Laser => self.laser_tool.bounds_on_doc(engine_view),
+            ToolStyle::Measure => self.measure_tool.bounds_on_doc(engine_view),
+            ToolStyle::PdfText => self.pdftext_tool.bounds_on_doc(engine_view),
+            ToolStyle::Fill => self.fill_tool.bounds_on_doc(engine_view),
+            ToolStyle::PushApart => self.pushapart_tool.bounds_on_doc(engine_view),
         }
     }
 
@@ -116,6 +166,18 @@ impl DrawableOnDoc for Tools {
             ToolStyle::Laser => {
                 self.laser_tool.draw_on_doc(cx, engine_view)?;
             }
+            ToolStyle::Measure => {
+                self.measure_tool.draw_on_doc(cx, engine_view)?;
+            }
+            ToolStyle::PdfText => {
+                self.pdftext_tool.draw_on_doc(cx, engine_view)?;
+            }
+            ToolStyle::Fill => {
+                self.fill_tool.draw_on_doc(cx, engine_view)?;
+            }
+            ToolStyle::PushApart => {
+                self.pushapart_tool.draw_on_doc(cx, engine_view)?;
+            }
         }
 
         cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
@@ -0,0 +1,278 @@
+// Imports
+use super::ToolsState;
+use crate::engine::{EngineView, EngineViewMut};
+use crate::store::StrokeKey;
+use crate::strokes::{PdfTextLayer, Stroke};
+use crate::{DrawableOnDoc, WidgetFlags};
+use p2d::bounding_volume::Aabb;
+use piet::RenderContext;
+use rnote_compose::color;
+use rnote_compose::eventresult::EventPropagation;
+use rnote_compose::ext::{AabbExt, Affine2Ext};
+use rnote_compose::penevent::{KeyboardKey, PenProgress};
+use rnote_compose::shapes::{Rectangle, Shapeable};
+use rnote_compose::{EventResult, PenEvent};
+use std::time::Instant;
+
+/// A selected range of characters in the text layer of an imported Pdf page.
+#[derive(Clone, Copy, Debug)]
+struct PdfTextSelection {
+    key: StrokeKey,
+    /// The index of the character where the selection was started.
+    anchor: usize,
+    /// The index of the character where the selection currently ends.
+    cursor: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct PdfTextTool {
+    state: ToolsState,
+    selection: Option<PdfTextSelection>,
+}
+
+impl PdfTextTool {
+    const SELECTION_COLOR: piet::Color = color::GNOME_BLUES[2].with_a8(100);
+    /// The half extents of the area around the pointer where Pdf pages are searched, in surface coordinates.
+    const HIT_HALF_EXTENTS: f64 = 1.0;
+
+    pub(super) fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        engine_view: &mut EngineViewMut,
+    ) -> (EventResult<PenProgress>, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+
+        let result = match (&mut self.state, event) {
+            (ToolsState::Idle, PenEvent::Down { element, .. }) => {
+                self.selection =
+                    Self::pdf_text_at(&engine_view.as_im(), element.pos).map(|(key, index)| {
+                        PdfTextSelection {
+                            key,
+                            anchor: index,
+                            cursor: index,
+                        }
+                    });
+                widget_flags.redraw = true;
+
+                if self.selection.is_some() {
+                    self.state = ToolsState::Active;
+                    EventResult {
+                        handled: true,
+                        propagate: EventPropagation::Stop,
+                        progress: PenProgress::InProgress,
+                    }
+                } else {
+                    EventResult {
+                        handled: false,
+                        propagate: EventPropagation::Proceed,
+                        progress: PenProgress::Idle,
+                    }
+                }
+            }
+            (ToolsState::Idle, PenEvent::KeyPressed { keyboard_key, .. })
+                if keyboard_key == KeyboardKey::Escape && self.selection.is_some() =>
+            {
+                self.selection = None;
+                widget_flags.redraw = true;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Idle,
+                }
+            }
+            (ToolsState::Idle, _) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::Idle,
+            },
+            (ToolsState::Active, PenEvent::Down { element, .. }) => {
+                self.extend_selection(&engine_view.as_im(), element.pos);
+                widget_flags |= engine_view
+                    .camera
+                    .nudge_w_pos(element.pos, engine_view.document);
+                widget_flags.redraw = true;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            (ToolsState::Active, PenEvent::Up { element, .. }) => {
+                self.extend_selection(&engine_view.as_im(), element.pos);
+                // The selection is kept, so that it can be copied afterwards
+                self.state = ToolsState::Idle;
+                widget_flags.redraw = true;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+            (ToolsState::Active, PenEvent::Proximity { .. }) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+            (ToolsState::Active, PenEvent::KeyPressed { keyboard_key, .. }) => {
+                if keyboard_key == KeyboardKey::Escape {
+                    self.reset();
+                    widget_flags.redraw = true;
+
+                    EventResult {
+                        handled: true,
+                        propagate: EventPropagation::Stop,
+                        progress: PenProgress::Finished,
+                    }
+                } else {
+                    EventResult {
+                        handled: false,
+                        propagate: EventPropagation::Proceed,
+                        progress: PenProgress::InProgress,
+                    }
+                }
+            }
+            (ToolsState::Active, PenEvent::Text { .. }) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+            (ToolsState::Active, PenEvent::Cancel) => {
+                self.reset();
+                widget_flags.redraw = true;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+        };
+
+        (result, widget_flags)
+    }
+
+    pub(super) fn reset(&mut self) {
+        self.selection = None;
+        self.state = ToolsState::Idle;
+    }
+
+    /// The text of the current selection.
+    pub(super) fn selected_text(&self, engine_view: &EngineView) -> Option<String> {
+        let selection = self.selection?;
+        let (_, pdf_text) = Self::pdf_text_of(engine_view.store.get_stroke_ref(selection.key)?)?;
+        Some(pdf_text.text_between(selection.anchor, selection.cursor))
+    }
+
+    /// The image rectangle and the Pdf text layer of the stroke, if it is an imported Pdf page.
+    fn pdf_text_of(stroke: &Stroke) -> Option<(&Rectangle, &PdfTextLayer)> {
+        match stroke {
+            Stroke::VectorImage(vectorimage) => vectorimage
+                .pdf_text
+                .as_ref()
+                .map(|pdf_text| (&vectorimage.rectangle, pdf_text)),
+            Stroke::BitmapImage(bitmapimage) => bitmapimage
+                .pdf_text
+                .as_ref()
+                .map(|pdf_text| (&bitmapimage.rectangle, pdf_text)),
+            _ => None,
+        }
+    }
+
+    /// The topmost imported Pdf page at the position and the index of the character closest to it.
+    fn pdf_text_at(engine_view: &EngineView, pos: na::Vector2<f64>) -> Option<(StrokeKey, usize)> {
+        let hit_bounds = Aabb::from_half_extents(
+            pos.into(),
+            na::Vector2::repeat(Self::HIT_HALF_EXTENTS / engine_view.camera.total_zoom()),
+        );
+        engine_view
+            .store
+            .stroke_keys_as_rendered_intersecting_bounds(hit_bounds)
+            .into_iter()
+            .rev()
+            .find_map(|key| {
+                let stroke = engine_view.store.get_stroke_ref(key)?;
+                let crop = match stroke {
+                    Stroke::VectorImage(vectorimage) => vectorimage.crop,
+                    Stroke::BitmapImage(bitmapimage) => bitmapimage.crop,
+                    _ => None,
+                };
+                let (rectangle, pdf_text) = Self::pdf_text_of(stroke)?;
+                let relative_pos = PdfTextLayer::relative_pos(rectangle, pos);
+                // Text that is cropped away can't be selected
+                if let Some(crop) = crop {
+                    if relative_pos
+                        .iter()
+                        .zip(crop.mins.iter().zip(crop.maxs.iter()))
+                        .any(|(v, (min, max))| v < min || v > max)
+                    {
+                        return None;
+                    }
+                }
+                pdf_text
+                    .char_index_at(relative_pos)
+                    .map(|index| (key, index))
+            })
+    }
+
+    fn extend_selection(&mut self, engine_view: &EngineView, pos: na::Vector2<f64>) {
+        let Some(selection) = self.selection.as_mut() else {
+            return;
+        };
+        let Some((rectangle, pdf_text)) = engine_view
+            .store
+            .get_stroke_ref(selection.key)
+            .and_then(Self::pdf_text_of)
+        else {
+            return;
+        };
+        // Dragging beyond the page selects up to its border
+        let relative_pos = PdfTextLayer::relative_pos(rectangle, pos).map(|v| v.clamp(0.0, 1.0));
+        if let Some(index) = pdf_text.char_index_at(relative_pos) {
+            selection.cursor = index;
+        }
+    }
+}
+
+impl DrawableOnDoc for PdfTextTool {
+    fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
+        let selection = self.selection?;
+        Some(engine_view.store.get_stroke_ref(selection.key)?.bounds())
+    }
+
+    fn draw_on_doc(
+        &self,
+        cx: &mut piet_cairo::CairoRenderContext,
+        engine_view: &EngineView,
+    ) -> anyhow::Result<()> {
+        let Some(selection) = self.selection else {
+            return Ok(());
+        };
+        let Some((rectangle, pdf_text)) = engine_view
+            .store
+            .get_stroke_ref(selection.key)
+            .and_then(Self::pdf_text_of)
+        else {
+            return Ok(());
+        };
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        cx.transform(rectangle.transform.affine.to_kurbo());
+
+        let (start, end) = (
+            selection.anchor.min(selection.cursor),
+            selection.anchor.max(selection.cursor),
+        );
+        for index in start..=end {
+            let Some(char_aabb) = pdf_text.char_local_aabb(rectangle, index) else {
+                continue;
+            };
+            cx.fill(char_aabb.to_kurbo_rect(), &Self::SELECTION_COLOR);
+        }
+
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+// Imports
+use super::ToolsState;
+use crate::engine::{EngineView, EngineViewMut};
+use crate::pens::pensconfig::toolsconfig::PushApartToolConfig;
+use crate::store::{StrokeKey, StrokeStore};
+use crate::{DrawableOnDoc, WidgetFlags};
+use p2d::bounding_volume::{Aabb, BoundingVolume};
+use p2d::query::PointQuery;
+use piet::RenderContext;
+use rnote_compose::eventresult::EventPropagation;
+use rnote_compose::ext::Vector2Ext;
+use rnote_compose::penevent::PenProgress;
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::{color, EventResult, PenEvent};
+use std::collections::HashSet;
+use std::time::Instant;
+
+/// Pushes the strokes ahead of the pen along the drag direction, making room between overlapping strokes.
+///
+/// Strokes are only translated as a whole, so their shape is preserved.
+/// How far they are pushed is weighted by their proximity to the pen.
+#[derive(Clone, Debug, Default)]
+pub(super) struct PushApartTool {
+    state: ToolsState,
+    pos: na::Vector2<f64>,
+    /// The strokes that got pushed since the pen went down.
+    pushed: HashSet<StrokeKey>,
+}
+
+impl PushApartTool {
+    /// The minimum distance the pen needs to be dragged before the strokes are pushed. In surface coordinates.
+    const DRAG_THRESHOLD: f64 = 1.0;
+    const OUTLINE_COLOR: piet::Color = color::GNOME_BLUES[3];
+    const OUTLINE_WIDTH: f64 = 1.5;
+    const FILL_COLOR: piet::Color = color::GNOME_BRIGHTS[2].with_a8(23);
+
+    pub(super) fn handle_event(
+        &mut self,
+        event: PenEvent,
+        _now: Instant,
+        engine_view: &mut EngineViewMut,
+    ) -> (EventResult<PenProgress>, WidgetFlags) {
+        let mut widget_flags = WidgetFlags::default();
+
+        let result = match (&mut self.state, event) {
+            (ToolsState::Idle, PenEvent::Down { element, .. }) => {
+                self.pos = element.pos;
+                self.state = ToolsState::Active;
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            (ToolsState::Idle, _) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::Idle,
+            },
+            (ToolsState::Active, PenEvent::Down { element, .. }) => {
+                let drag = element.pos - self.pos;
+
+                if drag.magnitude() > Self::DRAG_THRESHOLD / engine_view.camera.total_zoom() {
+                    let offsets = Self::push_offsets(
+                        engine_view.store,
+                        element.pos,
+                        drag,
+                        &engine_view.pens_config.tools_config.pushapart_tool_config,
+                    );
+                    for (key, offset) in offsets {
+                        engine_view.store.translate_strokes(&[key], offset);
+                        engine_view.store.translate_strokes_images(&[key], offset);
+                        self.pushed.insert(key);
+                    }
+                    self.pos = element.pos;
+
+                    widget_flags.store_modified = true;
+                }
+
+                // possibly nudge camera
+                widget_flags |= engine_view
+                    .camera
+                    .nudge_w_pos(element.pos, engine_view.document);
+                widget_flags |= engine_view
+                    .document
+                    .expand_autoexpand(engine_view.camera, engine_view.store);
+                engine_view.store.regenerate_rendering_in_viewport_threaded(
+                    engine_view.tasks_tx.clone(),
+                    false,
+                    engine_view.camera.viewport(),
+                    engine_view.camera.image_scale(),
+                );
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::InProgress,
+                }
+            }
+            (ToolsState::Active, PenEvent::Up { .. } | PenEvent::Cancel) => {
+                widget_flags |= self.finish(engine_view);
+
+                EventResult {
+                    handled: true,
+                    propagate: EventPropagation::Stop,
+                    progress: PenProgress::Finished,
+                }
+            }
+            (
+                ToolsState::Active,
+                PenEvent::Proximity { .. } | PenEvent::KeyPressed { .. } | PenEvent::Text { .. },
+            ) => EventResult {
+                handled: false,
+                propagate: EventPropagation::Proceed,
+                progress: PenProgress::InProgress,
+            },
+        };
+
+        (result, widget_flags)
+    }
+
+    /// The offsets for the strokes in the radius around the position that lie ahead in the drag direction.
+    fn push_offsets(
+        store: &StrokeStore,
+        pos: na::Vector2<f64>,
+        drag: na::Vector2<f64>,
+        config: &PushApartToolConfig,
+    ) -> Vec<(StrokeKey, na::Vector2<f64>)> {
+        let radius = config.radius.max(f64::EPSILON);
+        let area = Aabb::from_half_extents(pos.into(), na::Vector2::repeat(radius));
+
+        store
+            .stroke_keys_as_rendered_intersecting_bounds(area)
+            .into_iter()
+            .filter(|&key| !store.locked(key).unwrap_or(false))
+            .filter_map(|key| {
+                let bounds = store.get_stroke_ref(key)?.bounds();
+                // Strokes behind the pen stay in place, so that they separate from the pushed ones
+                if (bounds.center().coords - pos).dot(&drag) <= 0.0 {
+                    return None;
+                }
+                let dist = bounds.distance_to_local_point(&pos.into(), true);
+                let weight = config.strength.clamp(0.0, 1.0) * (1.0 - dist / radius);
+                (weight > 0.0).then_some((key, drag * weight))
+            })
+            .collect()
+    }
+
+    fn finish(&mut self, engine_view: &mut EngineViewMut) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        if !self.pushed.is_empty() {
+            let pushed = self.pushed.drain().collect::<Vec<StrokeKey>>();
+            engine_view.store.update_geometry_for_strokes(&pushed);
+
+            widget_flags |= engine_view.store.record(Instant::now());
+            widget_flags.store_modified = true;
+        }
+        widget_flags |= engine_view
+            .document
+            .resize_autoexpand(engine_view.store, engine_view.camera);
+        engine_view.store.regenerate_rendering_in_viewport_threaded(
+            engine_view.tasks_tx.clone(),
+            false,
+            engine_view.camera.viewport(),
+            engine_view.camera.image_scale(),
+        );
+
+        self.state = ToolsState::Idle;
+        widget_flags
+    }
+}
+
+impl DrawableOnDoc for PushApartTool {
+    fn bounds_on_doc(&self, engine_view: &EngineView) -> Option<Aabb> {
+        if matches!(self.state, ToolsState::Idle) {
+            return None;
+        }
+
+        let radius = engine_view
+            .pens_config
+            .tools_config
+            .pushapart_tool_config
+            .radius;
+        Some(
+            Aabb::from_half_extents(self.pos.into(), na::Vector2::repeat(radius)).loosened(
+                engine_view
+                    .visual_settings
+                    .outline_width(Self::OUTLINE_WIDTH)
+                    / engine_view.camera.total_zoom(),
+            ),
+        )
+    }
+
+    fn draw_on_doc(
+        &self,
+        cx: &mut piet_cairo::CairoRenderContext,
+        engine_view: &EngineView,
+    ) -> anyhow::Result<()> {
+        if matches!(self.state, ToolsState::Idle) {
+            return Ok(());
+        }
+        cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+
+        let radius = engine_view
+            .pens_config
+            .tools_config
+            .pushapart_tool_config
+            .radius;
+        let visual_settings = engine_view.visual_settings;
+        let circle = kurbo::Circle::new(self.pos.to_kurbo_point(), radius);
+        cx.fill(circle, &visual_settings.fill_color(Self::FILL_COLOR));
+        cx.stroke(
+            circle,
+            &visual_settings.outline_color(Self::OUTLINE_COLOR),
+            visual_settings.outline_width(Self::OUTLINE_WIDTH) / engine_view.camera.total_zoom(),
+        );
+
+        cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PushApartTool;
+    use crate::pens::pensconfig::toolsconfig::PushApartToolConfig;
+    use crate::store::StrokeStore;
+    use crate::strokes::{ShapeStroke, Stroke};
+    use rnote_compose::shapes::{Rectangle, Shape};
+    use rnote_compose::Style;
+
+    fn rectangle_stroke(mins: na::Vector2<f64>, maxs: na::Vector2<f64>) -> Stroke {
+        Stroke::ShapeStroke(ShapeStroke::new(
+            Shape::Rectangle(Rectangle::from_corners(mins, maxs)),
+            Style::default(),
+        ))
+    }
+
+    #[test]
+    fn locked_strokes_are_not_pushed() {
+        let mut store = StrokeStore::default();
+        let unlocked = store.insert_stroke(
+            rectangle_stroke(na::vector![10.0, -10.0], na::vector![30.0, 10.0]),
+            None,
+        );
+        let locked = store.insert_stroke(
+            rectangle_stroke(na::vector![10.0, 20.0], na::vector![30.0, 40.0]),
+            None,
+        );
+        store.set_locked(locked, true);
+        let config = PushApartToolConfig {
+            radius: 100.0,
+            strength: 1.0,
+        };
+
+        let offsets = PushApartTool::push_offsets(
+            &store,
+            na::vector![0.0, 0.0],
+            na::vector![5.0, 0.0],
+            &config,
+        );
+
+        assert!(offsets.iter().any(|(key, _)| *key == unlocked));
+        assert!(offsets.iter().all(|(key, _)| *key != locked));
+    }
+}
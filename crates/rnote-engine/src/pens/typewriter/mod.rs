@@ -1,5 +1,9 @@
 // Modules
 mod penevents;
+mod snippet;
+
+// Re-exports
+pub use snippet::SnippetFields;
 
 // Imports
 use super::pensconfig::TypewriterConfig;
@@ -56,6 +60,17 @@ pub(super) enum TypewriterState {
     },
 }
 
+/// The font of typewriter text.
+///
+/// When queried, values are None when they differ within the text selection.
+/// When changing the font, values that are None are left unchanged.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextFont {
+    pub font_family: Option<String>,
+    pub font_size: Option<f64>,
+    pub font_weight: Option<u16>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Typewriter {
     state: TypewriterState,
@@ -122,12 +137,15 @@ impl DrawableOnDoc for Typewriter {
         cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
         let total_zoom = engine_view.camera.total_zoom();
 
+        let visual_settings = engine_view.visual_settings;
+
         let draw_text_outline = |cx: &mut piet_cairo::CairoRenderContext, bounds: Aabb| {
-            let stroke_width = Self::TEXT_OUTLINE_STROKE_WIDTH / total_zoom;
+            let stroke_width =
+                visual_settings.outline_width(Self::TEXT_OUTLINE_STROKE_WIDTH) / total_zoom;
 
             cx.stroke(
                 bounds.tightened(stroke_width * 0.5).to_kurbo_rect(),
-                &Self::TEXT_OUTLINE_COLOR,
+                &visual_settings.outline_color(visual_settings.accented(Self::TEXT_OUTLINE_COLOR)),
                 stroke_width,
             );
         };
@@ -177,25 +195,17 @@ impl DrawableOnDoc for Typewriter {
                         selection_cursor, ..
                     } = modify_state
                     {
-                        textstroke.text_style.draw_text_selection(
+                        textstroke.draw_text_selection(
                             cx,
-                            textstroke.text.clone(),
                             cursor,
                             selection_cursor,
-                            &textstroke.transform,
                             engine_view.camera,
                         );
                     }
 
                     // Draw the cursor
                     if self.cursor_visible {
-                        textstroke.text_style.draw_cursor(
-                            cx,
-                            textstroke.text.clone(),
-                            cursor,
-                            &textstroke.transform,
-                            engine_view.camera,
-                        )?;
+                        textstroke.draw_cursor(cx, cursor, engine_view.camera)?;
                     }
 
                     // Draw the text width adjust node
@@ -562,8 +572,9 @@ impl Typewriter {
     /// The outline color when drawing a text box outline
     const TEXT_OUTLINE_COLOR: piet::Color = color::GNOME_BRIGHTS[4].with_a8(240);
 
-    pub(crate) fn toggle_cursor_visibility(&mut self) {
-        self.cursor_visible = !self.cursor_visible;
+    /// Toggle the cursor visibility to let it blink. It stays visible when motion is reduced.
+    pub(crate) fn toggle_cursor_visibility(&mut self, reduced_motion: bool) {
+        self.cursor_visible = reduced_motion || !self.cursor_visible;
     }
 
     /// The range of the current selection, if available.
@@ -587,6 +598,23 @@ impl Typewriter {
         }
     }
 
+    /// The position of the text that is currently being written, if available.
+    pub(crate) fn text_pos(&self, engine_view: &EngineView) -> Option<na::Vector2<f64>> {
+        match &self.state {
+            TypewriterState::Idle => None,
+            TypewriterState::Start(pos) => Some(*pos),
+            TypewriterState::Modifying { stroke_key, .. } => {
+                if let Some(Stroke::TextStroke(textstroke)) =
+                    engine_view.store.get_stroke_ref(*stroke_key)
+                {
+                    Some(textstroke.transform.translation_part())
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
     /// The bounds of the text rect enclosing the textstroke.
     fn text_rect_bounds(text_width: f64, textstroke: &TextStroke) -> Aabb {
         let origin = textstroke.transform.translation_part();
@@ -630,6 +658,19 @@ impl Typewriter {
         )
     }
 
+    /// Places the text cursor at the position, so that typing starts a new text stroke there.
+    pub(crate) fn start_text_at(
+        &mut self,
+        pos: na::Vector2<f64>,
+        engine_view: &mut EngineViewMut,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        self.state = TypewriterState::Start(engine_view.document.snap_position(pos));
+        self.reset_blink();
+        widget_flags.redraw = true;
+        widget_flags
+    }
+
     /// Insert text either at the current cursor position or, if the state is idle, in a new textstroke.
     ///
     /// Inserts at the given position, if supplied. Else at a default offset.
@@ -916,6 +957,104 @@ impl Typewriter {
         widget_flags
     }
 
+    /// The font of the current selection, or of the text stroke that is being modified when nothing is selected.
+    pub(crate) fn current_font(&self, engine_view: &EngineView) -> Option<TextFont> {
+        let TypewriterState::Modifying { stroke_key, .. } = &self.state else {
+            return None;
+        };
+        let Some(Stroke::TextStroke(textstroke)) = engine_view.store.get_stroke_ref(*stroke_key)
+        else {
+            return None;
+        };
+        let text_style = &textstroke.text_style;
+        let Some((selection_range, _)) = self.selection_range() else {
+            return Some(TextFont {
+                font_family: Some(text_style.font_family.clone()),
+                font_size: Some(text_style.font_size),
+                font_weight: Some(text_style.font_weight),
+            });
+        };
+
+        Some(TextFont {
+            font_family: single_value(textstroke.attr_values_for_range(
+                selection_range.clone(),
+                text_style.font_family.clone(),
+                |attr| match attr {
+                    TextAttribute::FontFamily(font_family) => Some(font_family.clone()),
+                    _ => None,
+                },
+            )),
+            font_size: single_value(textstroke.attr_values_for_range(
+                selection_range.clone(),
+                text_style.font_size,
+                |attr| match attr {
+                    TextAttribute::FontSize(font_size) => Some(*font_size),
+                    _ => None,
+                },
+            )),
+            font_weight: single_value(textstroke.attr_values_for_range(
+                selection_range,
+                text_style.font_weight,
+                |attr| match attr {
+                    TextAttribute::FontWeight(font_weight) => Some(*font_weight),
+                    _ => None,
+                },
+            )),
+        })
+    }
+
+    /// Change the font of the current selection as ranged attributes,
+    /// or of the entire text stroke that is being modified when nothing is selected.
+    pub(crate) fn change_font(
+        &mut self,
+        font: TextFont,
+        engine_view: &mut EngineViewMut,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+        let TypewriterState::Modifying { stroke_key, .. } = &self.state else {
+            return widget_flags;
+        };
+        let stroke_key = *stroke_key;
+        let selection_range = self.selection_range();
+        let Some(Stroke::TextStroke(textstroke)) = engine_view.store.get_stroke_mut(stroke_key)
+        else {
+            return widget_flags;
+        };
+
+        if let Some((selection_range, _)) = selection_range {
+            let attributes = [
+                font.font_family.map(TextAttribute::FontFamily),
+                font.font_size.map(TextAttribute::FontSize),
+                font.font_weight.map(TextAttribute::FontWeight),
+            ];
+            for attribute in attributes.into_iter().flatten() {
+                textstroke.replace_attr_for_range(selection_range.clone(), attribute);
+            }
+        } else {
+            let text_style = &mut textstroke.text_style;
+            if let Some(font_family) = font.font_family {
+                text_style.font_family = font_family;
+            }
+            if let Some(font_size) = font.font_size {
+                text_style.font_size = font_size;
+            }
+            if let Some(font_weight) = font.font_weight {
+                text_style.font_weight = font_weight;
+            }
+        }
+        engine_view.store.update_geometry_for_stroke(stroke_key);
+        engine_view.store.regenerate_rendering_for_stroke(
+            stroke_key,
+            engine_view.camera.viewport(),
+            engine_view.camera.image_scale(),
+        );
+
+        widget_flags |= engine_view.store.record(Instant::now());
+        widget_flags.redraw = true;
+        widget_flags.store_modified = true;
+        widget_flags
+    }
+
     /// Resets the blink
     fn reset_blink(&mut self) {
         if let Some(handle) = &mut self.blink_task_handle {
@@ -927,6 +1066,17 @@ impl Typewriter {
     }
 }
 
+/// The value, if all values are the same.
+fn single_value<T>(values: Vec<T>) -> Option<T> {
+    let mut values = values.into_iter();
+    let value = values.next();
+    if values.next().is_some() {
+        None
+    } else {
+        value
+    }
+}
+
 fn play_sound(keyboard_key: Option<KeyboardKey>, audioplayer: &mut Option<AudioPlayer>) {
     if let Some(audioplayer) = audioplayer {
         audioplayer.play_typewriter_key_sound(keyboard_key);
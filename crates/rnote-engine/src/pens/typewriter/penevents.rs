@@ -33,7 +33,7 @@ impl Typewriter {
 
                 if let Some(&stroke_key) = engine_view
                     .store
-                    .stroke_hitboxes_contain_coord(engine_view.camera.viewport(), element.pos)
+                    .stroke_hitboxes_contain_coord(element.pos)
                     .last()
                 {
                     // When clicked on a textstroke, we start modifying it
@@ -0,0 +1,58 @@
+// Imports
+use chrono::{DateTime, Local};
+
+/// The values of the fields that can be used in text snippets.
+#[derive(Debug, Clone)]
+pub struct SnippetFields {
+    /// The current local date and time.
+    pub now: DateTime<Local>,
+    /// The title of the document.
+    pub title: String,
+    /// The number of the page the snippet is inserted on, starting at 1.
+    pub page: usize,
+    /// The total number of pages.
+    pub n_pages: usize,
+}
+
+impl SnippetFields {
+    /// Expand the fields `{date}`, `{time}`, `{weekday}`, `{title}`, `{page}` and `{pages}` in the snippet.
+    ///
+    /// Unknown fields are left as they are.
+    pub fn expand(&self, snippet: &str) -> String {
+        let mut expanded = String::with_capacity(snippet.len());
+        let mut rest = snippet;
+
+        while let Some(start) = rest.find('{') {
+            expanded.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let Some(end) = rest.find('}') else {
+                break;
+            };
+            if rest[1..end].contains('{') {
+                expanded.push('{');
+                rest = &rest[1..];
+                continue;
+            }
+            match self.field_value(&rest[..=end]) {
+                Some(value) => expanded.push_str(&value),
+                None => expanded.push_str(&rest[..=end]),
+            }
+            rest = &rest[end + 1..];
+        }
+        expanded.push_str(rest);
+
+        expanded
+    }
+
+    fn field_value(&self, field: &str) -> Option<String> {
+        match field {
+            "{date}" => Some(self.now.format("%Y-%m-%d").to_string()),
+            "{time}" => Some(self.now.format("%H:%M").to_string()),
+            "{weekday}" => Some(self.now.format("%A").to_string()),
+            "{title}" => Some(self.title.clone()),
+            "{page}" => Some(self.page.to_string()),
+            "{pages}" => Some(self.n_pages.to_string()),
+            _ => None,
+        }
+    }
+}
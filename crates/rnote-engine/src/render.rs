@@ -209,6 +209,30 @@ impl Image {
         Ok(Image::from(reader.decode()?))
     }
 
+    /// Decode the bytes and downscale the image if its width or height exceeds the given maximum pixel size,
+    /// preserving the aspect ratio.
+    pub fn try_from_encoded_bytes_downscaled(
+        bytes: &[u8],
+        max_pixel_size: u32,
+    ) -> Result<Self, anyhow::Error> {
+        let reader = ImageReader::new(io::Cursor::new(bytes)).with_guessed_format()?;
+        let mut dynamic_image = reader.decode()?;
+        if dynamic_image.width().max(dynamic_image.height()) > max_pixel_size {
+            dynamic_image = dynamic_image.resize(
+                max_pixel_size,
+                max_pixel_size,
+                image::imageops::FilterType::Lanczos3,
+            );
+        }
+        Ok(Image::from(dynamic_image))
+    }
+
+    /// The pixel width and height of the encoded image bytes, without decoding the image data.
+    pub fn encoded_bytes_pixel_size(bytes: &[u8]) -> Result<(u32, u32), anyhow::Error> {
+        let reader = ImageReader::new(io::Cursor::new(bytes)).with_guessed_format()?;
+        Ok(reader.into_dimensions()?)
+    }
+
     pub fn try_from_cairo_surface(
         mut surface: cairo::ImageSurface,
         bounds: Aabb,
@@ -1,9 +1,11 @@
 // Imports
 use super::{StrokeKey, StrokeStore};
-use p2d::bounding_volume::Aabb;
+use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rayon::slice::ParallelSliceMut;
+use rnote_compose::shapes::Shapeable;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq)]
@@ -19,6 +21,18 @@ pub enum StrokeLayer {
     Document,
 }
 
+impl StrokeLayer {
+    /// The layer that is used to order the stroke when rendering.
+    ///
+    /// When highlighters are not kept behind, they are ordered chronologically together with the first user layer.
+    fn rendering_class(self, highlighters_behind: bool) -> Self {
+        match self {
+            Self::Highlighter if !highlighters_behind => Self::UserLayer(0),
+            layer => layer,
+        }
+    }
+}
+
 impl Default for StrokeLayer {
     fn default() -> Self {
         Self::UserLayer(0)
@@ -83,10 +97,35 @@ impl ChronoComponent {
     pub(crate) fn new(t: u32, layer: StrokeLayer) -> Self {
         Self { t, layer }
     }
+
+    /// Compare in the order the strokes get rendered, first by their layer, then chronologically.
+    pub(crate) fn cmp_rendering_order(&self, other: &Self, highlighters_behind: bool) -> Ordering {
+        self.layer
+            .rendering_class(highlighters_behind)
+            .cmp(&other.layer.rendering_class(highlighters_behind))
+            .then(self.t.cmp(&other.t))
+    }
+}
+
+/// A change of the order in which strokes are drawn on top of the other strokes of their layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZOrderChange {
+    /// Move above the next overlapping stroke.
+    BringForward,
+    /// Move below the previous overlapping stroke.
+    SendBackward,
+    /// Move above all other strokes.
+    BringToFront,
+    /// Move below all other strokes.
+    SendToBack,
 }
 
 /// Systems that are related to their chronological ordering.
 impl StrokeStore {
+    pub(crate) fn set_highlighters_behind(&mut self, highlighters_behind: bool) {
+        self.highlighters_behind = highlighters_behind;
+    }
+
     pub(crate) fn update_chrono_to_last(&mut self, key: StrokeKey) {
         if let Some(chrono_comp) = Arc::make_mut(&mut self.chrono_components).get_mut(key) {
             self.chrono_counter += 1;
@@ -94,76 +133,203 @@ impl StrokeStore {
         }
     }
 
-    /// Returns the keys in chronological order, as in first: gets drawn first, last: gets drawn last.
-    pub(crate) fn keys_sorted_chrono(&self) -> Vec<StrokeKey> {
-        let chrono_components = &self.chrono_components;
+    /// Move the stroke chronologically right before the earliest of the given strokes,
+    /// so that it gets drawn beneath them when they are on the same layer.
+    pub(crate) fn update_chrono_below(&mut self, key: StrokeKey, keys_above: &[StrokeKey]) {
+        let Some(min_t) = keys_above
+            .iter()
+            .filter(|&&k| k != key)
+            .filter_map(|&k| self.chrono_components.get(k).map(|c| c.t))
+            .min()
+        else {
+            return;
+        };
+        // The other strokes from right below the earliest stroke upwards, in chronological order
+        let mut others = self
+            .chrono_components
+            .iter()
+            .filter(|&(k, chrono_comp)| k != key && chrono_comp.t >= min_t.saturating_sub(1))
+            .map(|(k, chrono_comp)| (chrono_comp.t, k))
+            .collect::<Vec<(u32, StrokeKey)>>();
+        others.sort_unstable();
 
-        let mut keys = self.stroke_components.keys().collect::<Vec<StrokeKey>>();
+        let chrono_components = Arc::make_mut(&mut self.chrono_components);
+        let new_t = if min_t > 0 && !others.first().is_some_and(|&(t, _)| t < min_t) {
+            // The value right below is free, so no other stroke needs to be moved
+            min_t - 1
+        } else {
+            // Make room by moving up only the strokes that directly follow each other from the earliest one on
+            let mut next_free_t = min_t;
+            for (t, k) in others.into_iter().filter(|&(t, _)| t >= min_t) {
+                if t > next_free_t {
+                    break;
+                }
+                next_free_t = t + 1;
+                if let Some(chrono_comp) = chrono_components.get_mut(k) {
+                    Arc::make_mut(chrono_comp).t = next_free_t;
+                }
+            }
+            self.chrono_counter = self.chrono_counter.max(next_free_t);
+            min_t
+        };
+        if let Some(chrono_comp) = chrono_components.get_mut(key) {
+            Arc::make_mut(chrono_comp).t = new_t;
+        }
+    }
 
-        keys.par_sort_unstable_by(|&first, &second| {
-            if let (Some(first_chrono), Some(second_chrono)) =
-                (chrono_components.get(first), chrono_components.get(second))
-            {
-                let layer_order = first_chrono.layer.cmp(&second_chrono.layer);
+    /// Change the order in which the strokes are drawn relative to the other strokes of their layer.
+    ///
+    /// Strokes can't be moved out of their layer, so the chronological values of every layer are only
+    /// redistributed among its strokes in the new order.
+    pub(crate) fn change_z_order(&mut self, keys: &[StrokeKey], change: ZOrderChange) {
+        let highlighters_behind = self.highlighters_behind;
+        let selected = keys.iter().copied().collect::<HashSet<StrokeKey>>();
+        // The keys of every rendering class, in the order they are drawn
+        let mut classes: Vec<(StrokeLayer, Vec<StrokeKey>)> = Vec::new();
+        for key in self.keys_sorted_chrono() {
+            if self.trashed(key).unwrap_or(true) {
+                continue;
+            }
+            let Some(chrono_comp) = self.chrono_components.get(key) else {
+                continue;
+            };
+            let class = chrono_comp.layer.rendering_class(highlighters_behind);
+            match classes.last_mut() {
+                Some((last_class, class_keys)) if *last_class == class => class_keys.push(key),
+                _ => classes.push((class, vec![key])),
+            }
+        }
 
-                if layer_order != std::cmp::Ordering::Equal {
-                    layer_order
-                } else {
-                    first_chrono.t.cmp(&second_chrono.t)
+        for (_, class_keys) in classes {
+            if !class_keys.iter().any(|key| selected.contains(key)) {
+                continue;
+            }
+            let ts = class_keys
+                .iter()
+                .filter_map(|&key| self.chrono_components.get(key).map(|c| c.t))
+                .collect::<Vec<u32>>();
+            let reordered = self.reorder_keys(class_keys, &selected, change);
+            let chrono_components = Arc::make_mut(&mut self.chrono_components);
+            for (key, t) in reordered.into_iter().zip(ts) {
+                if let Some(chrono_comp) = chrono_components.get_mut(key) {
+                    Arc::make_mut(chrono_comp).t = t;
                 }
-            } else {
-                std::cmp::Ordering::Equal
             }
-        });
+        }
+    }
 
+    /// Reorder the keys that are drawn in the given order.
+    fn reorder_keys(
+        &self,
+        mut keys: Vec<StrokeKey>,
+        selected: &HashSet<StrokeKey>,
+        change: ZOrderChange,
+    ) -> Vec<StrokeKey> {
+        let bounds = |key: StrokeKey| self.stroke_components.get(key).map(|s| s.bounds());
+        match change {
+            ZOrderChange::BringToFront => {
+                let (selected_keys, mut keys): (Vec<StrokeKey>, Vec<StrokeKey>) =
+                    keys.into_iter().partition(|key| selected.contains(key));
+                keys.extend(selected_keys);
+                keys
+            }
+            ZOrderChange::SendToBack => {
+                let (mut selected_keys, keys): (Vec<StrokeKey>, Vec<StrokeKey>) =
+                    keys.into_iter().partition(|key| selected.contains(key));
+                selected_keys.extend(keys);
+                selected_keys
+            }
+            ZOrderChange::BringForward => {
+                // Starting with the topmost, so that moved keys are not visited again
+                for i in (0..keys.len()).rev() {
+                    if !selected.contains(&keys[i]) {
+                        continue;
+                    }
+                    let Some(key_bounds) = bounds(keys[i]) else {
+                        continue;
+                    };
+                    if let Some(j) = (i + 1..keys.len()).find(|&j| {
+                        !selected.contains(&keys[j])
+                            && bounds(keys[j]).is_some_and(|b| b.intersects(&key_bounds))
+                    }) {
+                        let key = keys.remove(i);
+                        keys.insert(j, key);
+                    }
+                }
+                keys
+            }
+            ZOrderChange::SendBackward => {
+                // Starting with the bottommost, so that moved keys are not visited again
+                for i in 0..keys.len() {
+                    if !selected.contains(&keys[i]) {
+                        continue;
+                    }
+                    let Some(key_bounds) = bounds(keys[i]) else {
+                        continue;
+                    };
+                    if let Some(j) = (0..i).rev().find(|&j| {
+                        !selected.contains(&keys[j])
+                            && bounds(keys[j]).is_some_and(|b| b.intersects(&key_bounds))
+                    }) {
+                        let key = keys.remove(i);
+                        keys.insert(j, key);
+                    }
+                }
+                keys
+            }
+        }
+    }
+
+    /// Returns the keys in chronological order, as in first: gets drawn first, last: gets drawn last.
+    pub(crate) fn keys_sorted_chrono(&self) -> Vec<StrokeKey> {
+        let mut keys = self.stroke_components.keys().collect::<Vec<StrokeKey>>();
+        self.sort_keys_chrono(&mut keys);
         keys
     }
 
     pub(crate) fn keys_sorted_chrono_intersecting_bounds(&self, bounds: Aabb) -> Vec<StrokeKey> {
-        let chrono_components = &self.chrono_components;
-
         let mut keys = self.key_tree.keys_intersecting_bounds(bounds);
+        self.sort_keys_chrono(&mut keys);
+        keys
+    }
 
-        keys.par_sort_unstable_by(|&first, &second| {
-            if let (Some(first_chrono), Some(second_chrono)) =
-                (chrono_components.get(first), chrono_components.get(second))
-            {
-                let layer_order = first_chrono.layer.cmp(&second_chrono.layer);
-
-                if layer_order != std::cmp::Ordering::Equal {
-                    layer_order
-                } else {
-                    first_chrono.t.cmp(&second_chrono.t)
-                }
-            } else {
-                std::cmp::Ordering::Equal
-            }
-        });
+    pub(crate) fn keys_sorted_chrono_in_bounds(&self, bounds: Aabb) -> Vec<StrokeKey> {
+        let mut keys = self.key_tree.keys_in_bounds(bounds);
+        self.sort_keys_chrono(&mut keys);
+        keys
+    }
 
+    pub(crate) fn keys_sorted_chrono_at_point(&self, point: na::Point2<f64>) -> Vec<StrokeKey> {
+        let mut keys = self.key_tree.keys_at_point(point);
+        self.sort_keys_chrono(&mut keys);
         keys
     }
 
-    pub(crate) fn keys_sorted_chrono_in_bounds(&self, bounds: Aabb) -> Vec<StrokeKey> {
+    /// Stroke keys that are not trashed, in the order they were drawn regardless of their layer.
+    pub(crate) fn stroke_keys_drawing_order(&self) -> Vec<StrokeKey> {
         let chrono_components = &self.chrono_components;
+        let mut keys = self
+            .stroke_components
+            .keys()
+            .filter(|&key| !(self.trashed(key).unwrap_or(false)))
+            .collect::<Vec<StrokeKey>>();
+        keys.par_sort_unstable_by_key(|&key| chrono_components.get(key).map(|c| c.t));
+        keys
+    }
 
-        let mut keys = self.key_tree.keys_in_bounds(bounds);
+    /// Sort the keys in the order that they get drawn.
+    fn sort_keys_chrono(&self, keys: &mut [StrokeKey]) {
+        let chrono_components = &self.chrono_components;
+        let highlighters_behind = self.highlighters_behind;
 
         keys.par_sort_unstable_by(|&first, &second| {
             if let (Some(first_chrono), Some(second_chrono)) =
                 (chrono_components.get(first), chrono_components.get(second))
             {
-                let layer_order = first_chrono.layer.cmp(&second_chrono.layer);
-
-                if layer_order != std::cmp::Ordering::Equal {
-                    layer_order
-                } else {
-                    first_chrono.t.cmp(&second_chrono.t)
-                }
+                first_chrono.cmp_rendering_order(second_chrono, highlighters_behind)
             } else {
                 std::cmp::Ordering::Equal
             }
         });
-
-        keys
     }
 }
@@ -2,6 +2,7 @@
 use super::StrokeKey;
 use p2d::bounding_volume::Aabb;
 use rstar::primitives::GeomWithData;
+use rstar::RTreeObject;
 
 /// The rtree object that holds the bounds and [StrokeKey].
 type KeyTreeObject = GeomWithData<rstar::primitives::Rectangle<[f64; 2]>, StrokeKey>;
@@ -10,19 +11,29 @@ type KeyTreeObject = GeomWithData<rstar::primitives::Rectangle<[f64; 2]>, Stroke
 /// A Rtree with [StrokeKey]'s as associated data.
 ///
 /// Used for faster spatial queries.
-pub(super) struct KeyTree(rstar::RTree<KeyTreeObject, rstar::DefaultParams>);
+pub(super) struct KeyTree {
+    tree: rstar::RTree<KeyTreeObject, rstar::DefaultParams>,
+    /// The currently inserted objects, so that they can be removed without searching the entire tree.
+    objects: slotmap::SecondaryMap<StrokeKey, KeyTreeObject>,
+}
 
 impl KeyTree {
     /// Insert a new tree object with the given [StrokeKey] and bounds.
+    ///
+    /// Replaces the existing object for the key.
     pub(crate) fn insert_with_key(&mut self, key: StrokeKey, bounds: Aabb) {
-        self.0.insert(new_keytree_object(key, bounds));
+        let object = new_keytree_object(key, bounds);
+        if let Some(old_object) = self.objects.insert(key, object.clone()) {
+            self.tree.remove(&old_object);
+        }
+        self.tree.insert(object);
     }
 
     /// Removes the [KeyTreeObject] for the given key.
     pub(crate) fn remove_with_key(&mut self, key: StrokeKey) -> Option<KeyTreeObject> {
-        let object_to_remove = self.0.iter().find(|&object| object.data == key)?.to_owned();
+        let object_to_remove = self.objects.remove(key)?;
 
-        self.0.remove(&object_to_remove)
+        self.tree.remove(&object_to_remove)
     }
 
     /// Update the Tree with new bounds for the given key.
@@ -35,7 +46,7 @@ impl KeyTree {
 
     /// Return the keys that intersect with the given bounds.
     pub(crate) fn keys_intersecting_bounds(&self, bounds: Aabb) -> Vec<StrokeKey> {
-        self.0
+        self.tree
             .locate_in_envelope_intersecting(&rstar::AABB::from_corners(
                 [bounds.mins[0], bounds.mins[1]],
                 [bounds.maxs[0], bounds.maxs[1]],
@@ -46,7 +57,7 @@ impl KeyTree {
 
     /// Return the keys that are completely contained in the given bounds.
     pub(crate) fn keys_in_bounds(&self, bounds: Aabb) -> Vec<StrokeKey> {
-        self.0
+        self.tree
             .locate_in_envelope(&rstar::AABB::from_corners(
                 [bounds.mins[0], bounds.mins[1]],
                 [bounds.maxs[0], bounds.maxs[1]],
@@ -55,14 +66,38 @@ impl KeyTree {
             .collect()
     }
 
+    /// Return the keys whose bounds contain the given point.
+    pub(crate) fn keys_at_point(&self, point: na::Point2<f64>) -> Vec<StrokeKey> {
+        self.tree
+            .locate_all_at_point(&[point[0], point[1]])
+            .map(|object| object.data)
+            .collect()
+    }
+
+    /// Return the keys whose bounds lie entirely below the given `y`.
+    pub(crate) fn keys_below_y(&self, y: f64) -> Vec<StrokeKey> {
+        self.tree
+            .locate_in_envelope(&rstar::AABB::from_corners(
+                [f64::NEG_INFINITY, y],
+                [f64::INFINITY, f64::INFINITY],
+            ))
+            .filter(|object| object.envelope().lower()[1] > y)
+            .map(|object| object.data)
+            .collect()
+    }
+
     /// Rebuild the entire rtree from the given Vec of (key, bounds).
     pub(crate) fn rebuild_from_vec(&mut self, strokes: Vec<(StrokeKey, Aabb)>) {
-        let objects = strokes
+        let objects: Vec<KeyTreeObject> = strokes
             .into_iter()
             .map(|(key, bounds)| new_keytree_object(key, bounds))
             .collect();
 
-        self.0 = rstar::RTree::bulk_load(objects);
+        self.objects = objects
+            .iter()
+            .map(|object| (object.data, object.clone()))
+            .collect();
+        self.tree = rstar::RTree::bulk_load(objects);
     }
 
     ///  Clear the entire tree.
@@ -80,3 +115,88 @@ fn new_keytree_object(key: StrokeKey, bounds: Aabb) -> KeyTreeObject {
         key,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::store::keytree::KeyTree;
+    use crate::store::StrokeKey;
+    use p2d::bounding_volume::Aabb;
+
+    fn new_keys(n: usize) -> Vec<StrokeKey> {
+        let mut slotmap = slotmap::SlotMap::<StrokeKey, ()>::with_key();
+        (0..n).map(|_| slotmap.insert(())).collect()
+    }
+
+    fn sorted(mut keys: Vec<StrokeKey>) -> Vec<StrokeKey> {
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn keys_at_point() {
+        let keys = new_keys(3);
+        let mut key_tree = KeyTree::default();
+        key_tree.insert_with_key(
+            keys[0],
+            Aabb::new(na::point![0.0, 0.0], na::point![10.0, 10.0]),
+        );
+        key_tree.insert_with_key(
+            keys[1],
+            Aabb::new(na::point![5.0, 5.0], na::point![20.0, 20.0]),
+        );
+        key_tree.insert_with_key(
+            keys[2],
+            Aabb::new(na::point![100.0, 100.0], na::point![110.0, 110.0]),
+        );
+
+        assert_eq!(key_tree.keys_at_point(na::point![2.0, 2.0]), vec![keys[0]]);
+        assert_eq!(
+            sorted(key_tree.keys_at_point(na::point![7.0, 7.0])),
+            sorted(vec![keys[0], keys[1]])
+        );
+        // the edges are included
+        assert_eq!(
+            key_tree.keys_at_point(na::point![110.0, 105.0]),
+            vec![keys[2]]
+        );
+        assert!(key_tree.keys_at_point(na::point![50.0, 50.0]).is_empty());
+    }
+
+    #[test]
+    fn keys_at_point_after_update_and_remove() {
+        let keys = new_keys(2);
+        let mut key_tree = KeyTree::default();
+        key_tree.insert_with_key(
+            keys[0],
+            Aabb::new(na::point![0.0, 0.0], na::point![10.0, 10.0]),
+        );
+        key_tree.insert_with_key(
+            keys[1],
+            Aabb::new(na::point![0.0, 0.0], na::point![10.0, 10.0]),
+        );
+
+        key_tree.update_with_key(
+            keys[0],
+            Aabb::new(na::point![50.0, 50.0], na::point![60.0, 60.0]),
+        );
+        assert_eq!(key_tree.keys_at_point(na::point![5.0, 5.0]), vec![keys[1]]);
+        assert_eq!(
+            key_tree.keys_at_point(na::point![55.0, 55.0]),
+            vec![keys[0]]
+        );
+
+        // inserting again replaces the existing bounds
+        key_tree.insert_with_key(
+            keys[0],
+            Aabb::new(na::point![0.0, 0.0], na::point![10.0, 10.0]),
+        );
+        assert!(key_tree.keys_at_point(na::point![55.0, 55.0]).is_empty());
+
+        assert!(key_tree.remove_with_key(keys[1]).is_some());
+        assert!(key_tree.remove_with_key(keys[1]).is_none());
+        assert_eq!(key_tree.keys_at_point(na::point![5.0, 5.0]), vec![keys[0]]);
+
+        key_tree.clear();
+        assert!(key_tree.keys_at_point(na::point![5.0, 5.0]).is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+// Imports
+use super::{StrokeKey, StrokeStore};
+use crate::strokes::Stroke;
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::Transform;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default, rename = "link_component")]
+pub struct LinkComponent {
+    /// The image stroke the stroke is attached to as caption.
+    #[serde(rename = "caption_of")]
+    pub caption_of: Option<StrokeKey>,
+}
+
+/// Systems that are related to linking strokes to each other.
+impl StrokeStore {
+    /// The gap between an image and its captions.
+    pub(crate) const CAPTION_GAP: f64 = 8.0;
+
+    /// Rebuild the slotmap with link components for the keys returned from the stroke components.
+    ///
+    /// Existing link components are kept, missing ones are inserted unlinked.
+    /// Links to strokes that don't exist anymore are removed.
+    pub(crate) fn rebuild_link_components_slotmap(&mut self) {
+        let mut link_components = slotmap::SecondaryMap::new();
+        self.stroke_components.keys().for_each(|key| {
+            let link_comp = self
+                .link_components
+                .get(key)
+                .filter(|l| {
+                    l.caption_of.map_or(true, |image_key| {
+                        self.stroke_components.contains_key(image_key)
+                    })
+                })
+                .cloned()
+                .unwrap_or_else(|| Arc::new(LinkComponent::default()));
+            link_components.insert(key, link_comp);
+        });
+        self.link_components = Arc::new(link_components);
+    }
+
+    /// The image stroke the stroke is attached to as caption.
+    pub(crate) fn caption_of(&self, key: StrokeKey) -> Option<StrokeKey> {
+        self.link_components.get(key).and_then(|l| l.caption_of)
+    }
+
+    /// Attach the stroke as caption to the image stroke, or detach it when `None`.
+    pub(crate) fn set_caption_of(&mut self, key: StrokeKey, image_key: Option<StrokeKey>) {
+        if let Some(link_comp) = Arc::make_mut(&mut self.link_components)
+            .get_mut(key)
+            .map(Arc::make_mut)
+        {
+            link_comp.caption_of = image_key;
+        }
+    }
+
+    /// The keys of the captions that are attached to the image stroke and not trashed, unordered.
+    pub(crate) fn caption_keys_unordered(&self, image_key: StrokeKey) -> Vec<StrokeKey> {
+        self.link_components
+            .iter()
+            .filter(|(key, link_comp)| {
+                link_comp.caption_of == Some(image_key) && !(self.trashed(*key).unwrap_or(false))
+            })
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// The given keys, extended with the keys of all captions that are attached to image strokes among them,
+    /// trashed or not.
+    pub(crate) fn keys_w_attached_captions(&self, keys: &[StrokeKey]) -> Vec<StrokeKey> {
+        let mut keys_w_captions = keys.to_vec();
+        keys_w_captions.extend(
+            self.link_components
+                .iter()
+                .filter(|(key, link_comp)| {
+                    link_comp
+                        .caption_of
+                        .is_some_and(|image_key| keys.contains(&image_key))
+                        && !keys.contains(key)
+                })
+                .map(|(key, _)| key),
+        );
+        keys_w_captions
+    }
+
+    /// Move the captions that are attached to the image stroke below it and fit them to its width.
+    ///
+    /// The captions then need to update their rendering.
+    pub(crate) fn update_captions_position(&mut self, image_key: StrokeKey) {
+        let Some(image_bounds) = self
+            .stroke_components
+            .get(image_key)
+            .filter(|stroke| {
+                matches!(
+                    stroke.as_ref(),
+                    Stroke::BitmapImage(_) | Stroke::VectorImage(_)
+                )
+            })
+            .map(|stroke| stroke.bounds())
+        else {
+            return;
+        };
+
+        for caption_key in self.caption_keys_unordered(image_key) {
+            let Some(Stroke::TextStroke(textstroke)) = Arc::make_mut(&mut self.stroke_components)
+                .get_mut(caption_key)
+                .map(Arc::make_mut)
+            else {
+                continue;
+            };
+            textstroke
+                .text_style
+                .set_max_width(Some(image_bounds.extents()[0]));
+            textstroke.transform = Transform::new_w_isometry(na::Isometry2::new(
+                na::vector![
+                    image_bounds.mins[0],
+                    image_bounds.maxs[1] + Self::CAPTION_GAP
+                ],
+                0.0,
+            ));
+            self.update_stroke_geometry(caption_key);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+// Imports
+use super::{StrokeKey, StrokeStore};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, rename = "lock_component")]
+pub struct LockComponent {
+    /// Whether the stroke is locked against selecting, erasing and transforming.
+    #[serde(rename = "locked")]
+    pub locked: bool,
+}
+
+impl Default for LockComponent {
+    fn default() -> Self {
+        Self { locked: false }
+    }
+}
+
+/// Systems that are related to locking.
+impl StrokeStore {
+    /// Rebuild the slotmap with lock components for the keys returned from the stroke components.
+    ///
+    /// Existing lock components are kept, missing ones are inserted unlocked.
+    pub(crate) fn rebuild_lock_components_slotmap(&mut self) {
+        let mut lock_components = slotmap::SecondaryMap::new();
+        self.stroke_components.keys().for_each(|key| {
+            let lock_comp = self
+                .lock_components
+                .get(key)
+                .cloned()
+                .unwrap_or_else(|| Arc::new(LockComponent::default()));
+            lock_components.insert(key, lock_comp);
+        });
+        self.lock_components = Arc::new(lock_components);
+    }
+
+    pub(crate) fn locked(&self, key: StrokeKey) -> Option<bool> {
+        self.lock_components.get(key).map(|l| l.locked)
+    }
+
+    /// Set if the stroke is locked. Locking deselects the stroke.
+    pub(crate) fn set_locked(&mut self, key: StrokeKey, locked: bool) {
+        if locked {
+            self.set_selected(key, false);
+        }
+        if let Some(lock_comp) = Arc::make_mut(&mut self.lock_components)
+            .get_mut(key)
+            .map(Arc::make_mut)
+        {
+            lock_comp.locked = locked;
+        }
+    }
+
+    pub(crate) fn set_locked_keys(&mut self, keys: &[StrokeKey], locked: bool) {
+        keys.iter().for_each(|&key| {
+            self.set_locked(key, locked);
+        })
+    }
+
+    /// All locked keys that are not trashed, unordered.
+    pub(crate) fn locked_keys_unordered(&self) -> Vec<StrokeKey> {
+        self.stroke_components
+            .keys()
+            .filter(|&key| {
+                !(self.trashed(key).unwrap_or(false)) && self.locked(key).unwrap_or(false)
+            })
+            .collect()
+    }
+}
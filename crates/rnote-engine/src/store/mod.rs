@@ -1,6 +1,8 @@
 // Modules
 pub mod chrono_comp;
 pub mod keytree;
+pub mod link_comp;
+pub mod lock_comp;
 pub mod render_comp;
 pub mod selection_comp;
 pub mod stroke_comp;
@@ -8,13 +10,19 @@ pub mod trash_comp;
 
 // Re-exports
 pub use chrono_comp::ChronoComponent;
+pub use chrono_comp::ZOrderChange;
 use keytree::KeyTree;
+pub use link_comp::LinkComponent;
+pub use lock_comp::LockComponent;
 pub use render_comp::RenderComponent;
-pub use selection_comp::SelectionComponent;
+pub use selection_comp::{SelectSimilar, SelectionComponent};
+pub use stroke_comp::FlipAxis;
 pub use trash_comp::TrashComponent;
 
 // Imports
 use self::chrono_comp::StrokeLayer;
+use crate::document::DocLayout;
+use crate::engine::profiling::{self, ProfileCategory};
 use crate::engine::EngineSnapshot;
 use crate::strokes::Stroke;
 use crate::WidgetFlags;
@@ -39,8 +47,15 @@ pub struct HistoryEntry {
     pub trash_components: Arc<SecondaryMap<StrokeKey, Arc<TrashComponent>>>,
     #[serde(rename = "chrono_components")]
     pub chrono_components: Arc<SecondaryMap<StrokeKey, Arc<ChronoComponent>>>,
+    #[serde(rename = "lock_components")]
+    pub lock_components: Arc<SecondaryMap<StrokeKey, Arc<LockComponent>>>,
+    #[serde(rename = "link_components")]
+    pub link_components: Arc<SecondaryMap<StrokeKey, Arc<LinkComponent>>>,
     #[serde(rename = "chrono_counter")]
     pub chrono_counter: u32,
+    /// The document layout, when it was changed together with the strokes when moving to or from this entry.
+    #[serde(skip)]
+    pub doc_layout: Option<DocLayout>,
 }
 
 impl Default for HistoryEntry {
@@ -49,8 +64,11 @@ impl Default for HistoryEntry {
             stroke_components: Arc::new(HopSlotMap::with_key()),
             trash_components: Arc::new(SecondaryMap::new()),
             chrono_components: Arc::new(SecondaryMap::new()),
+            lock_components: Arc::new(SecondaryMap::new()),
+            link_components: Arc::new(SecondaryMap::new()),
 
             chrono_counter: 0,
+            doc_layout: None,
         }
     }
 }
@@ -62,6 +80,8 @@ impl Default for HistoryEntry {
 ///     * 'trash_components': Holds state whether the strokes are trashed
 ///     * 'selection_components': Holds state whether the strokes are selected
 ///     * 'chrono_components': Holds state about the chronological ordering
+///     * 'lock_components': Holds state whether the strokes are locked against selecting, erasing and transforming
+///     * 'link_components': Holds state about links between strokes, like captions that are attached to images
 ///     * 'render_components': Holds state about the rendering.
 ///
 /// The systems are implemented as methods on StrokesStore, loosely categorized to the different components (but often modify others as well).
@@ -79,11 +99,20 @@ pub struct StrokeStore {
     selection_components: Arc<SecondaryMap<StrokeKey, Arc<SelectionComponent>>>,
     #[serde(rename = "chrono_components")]
     chrono_components: Arc<SecondaryMap<StrokeKey, Arc<ChronoComponent>>>,
+    #[serde(rename = "lock_components")]
+    lock_components: Arc<SecondaryMap<StrokeKey, Arc<LockComponent>>>,
+    #[serde(rename = "link_components")]
+    link_components: Arc<SecondaryMap<StrokeKey, Arc<LinkComponent>>>,
     /// Incrementing counter for chrono_components.
     ///
     /// Value must be kept equal to the [ChronoComponent] of the newest inserted or modified stroke.
     #[serde(rename = "chrono_counter")]
     chrono_counter: u32,
+    /// Whether highlighter strokes are ordered beneath all other strokes.
+    ///
+    /// Mirrors the document setting, so it needs to be kept in sync with it.
+    #[serde(skip)]
+    highlighters_behind: bool,
     #[serde(skip)]
     render_components: SecondaryMap<StrokeKey, RenderComponent>,
     #[serde(skip)]
@@ -105,6 +134,8 @@ impl Default for StrokeStore {
             trash_components: Arc::new(SecondaryMap::new()),
             selection_components: Arc::new(SecondaryMap::new()),
             chrono_components: Arc::new(SecondaryMap::new()),
+            lock_components: Arc::new(SecondaryMap::new()),
+            link_components: Arc::new(SecondaryMap::new()),
             render_components: SecondaryMap::new(),
 
             // Start off with state in the history
@@ -114,6 +145,7 @@ impl Default for StrokeStore {
             key_tree: KeyTree::default(),
 
             chrono_counter: 0,
+            highlighters_behind: true,
         }
     }
 }
@@ -131,11 +163,16 @@ impl StrokeStore {
         widget_flags |= self.clear();
         self.stroke_components = Arc::clone(&snapshot.stroke_components);
         self.chrono_components = Arc::clone(&snapshot.chrono_components);
+        self.lock_components = Arc::clone(&snapshot.lock_components);
+        self.link_components = Arc::clone(&snapshot.link_components);
         self.chrono_counter = snapshot.chrono_counter;
+        self.highlighters_behind = snapshot.document.highlighters_behind;
 
         self.update_geometry_for_strokes(&self.keys_unordered());
         self.rebuild_selection_components_slotmap();
         self.rebuild_trash_components_slotmap();
+        self.rebuild_lock_components_slotmap();
+        self.rebuild_link_components_slotmap();
         self.rebuild_render_components_slotmap();
         self.rebuild_rtree();
         widget_flags |= self.clear_history(self.create_history_entry());
@@ -158,6 +195,8 @@ impl StrokeStore {
         Arc::ptr_eq(&self.stroke_components, &history_entry.stroke_components)
             && Arc::ptr_eq(&self.trash_components, &history_entry.trash_components)
             && Arc::ptr_eq(&self.chrono_components, &history_entry.chrono_components)
+            && Arc::ptr_eq(&self.lock_components, &history_entry.lock_components)
+            && Arc::ptr_eq(&self.link_components, &history_entry.link_components)
             && self.chrono_counter == history_entry.chrono_counter
     }
 
@@ -167,7 +206,10 @@ impl StrokeStore {
             stroke_components: Arc::clone(&self.stroke_components),
             trash_components: Arc::clone(&self.trash_components),
             chrono_components: Arc::clone(&self.chrono_components),
+            lock_components: Arc::clone(&self.lock_components),
+            link_components: Arc::clone(&self.link_components),
             chrono_counter: self.chrono_counter,
+            doc_layout: None,
         }
     }
 
@@ -176,6 +218,8 @@ impl StrokeStore {
         self.stroke_components = Arc::clone(&history_entry.stroke_components);
         self.trash_components = Arc::clone(&history_entry.trash_components);
         self.chrono_components = Arc::clone(&history_entry.chrono_components);
+        self.lock_components = Arc::clone(&history_entry.lock_components);
+        self.link_components = Arc::clone(&history_entry.link_components);
         self.chrono_counter = history_entry.chrono_counter;
 
         // Since we don't store the rtree in the history, we need to rebuild it.
@@ -221,6 +265,48 @@ impl StrokeStore {
         widget_flags
     }
 
+    /// Record the current state together with a change of the document layout.
+    ///
+    /// Always creates a new history entry, even if the strokes did not change,
+    /// so that undo and redo restore the layout in sync with the strokes.
+    pub(crate) fn record_w_doc_layout(
+        &mut self,
+        _now: Instant,
+        prev_doc_layout: DocLayout,
+        doc_layout: DocLayout,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        // as soon as the current state is recorded, remove the future
+        self.history.truncate(self.live_index + 1);
+        if let Some(live) = self.history.get_mut(self.live_index) {
+            live.doc_layout = Some(prev_doc_layout);
+        }
+
+        let mut current = self.create_history_entry();
+        current.doc_layout = Some(doc_layout);
+        self.history.push_back(current);
+        self.live_index += 1;
+
+        // truncate history if necessary
+        while self.history.len() > Self::HISTORY_MAX_LEN {
+            self.history.pop_front();
+            self.live_index -= 1;
+        }
+
+        widget_flags.hide_undo = Some(!self.can_undo());
+        widget_flags.hide_redo = Some(!self.can_redo());
+
+        widget_flags
+    }
+
+    /// The document layout recorded in the current history entry, if it was recorded.
+    pub(crate) fn live_doc_layout(&self) -> Option<DocLayout> {
+        self.history
+            .get(self.live_index)
+            .and_then(|entry| entry.doc_layout)
+    }
+
     /// Update the state of the latest history entry with the current document state.
     pub(crate) fn update_latest_history_entry(&mut self, _now: Instant) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
@@ -234,7 +320,8 @@ impl StrokeStore {
             // as soon as the current state is recorded, remove the future
             self.history.truncate(self.live_index + 1);
 
-            let current = self.create_history_entry();
+            let mut current = self.create_history_entry();
+            current.doc_layout = self.history[self.live_index].doc_layout;
             self.history[self.live_index] = current;
         } else {
             debug!("State has not changed, no need to update history with current state.");
@@ -319,6 +406,7 @@ impl StrokeStore {
         stroke: Stroke,
         layer: Option<StrokeLayer>,
     ) -> StrokeKey {
+        let _span = profiling::span(ProfileCategory::StrokeInsertion);
         let bounds = stroke.bounds();
         let layer = layer.unwrap_or_else(|| stroke.extract_default_layer());
 
@@ -333,6 +421,8 @@ impl StrokeStore {
             key,
             Arc::new(ChronoComponent::new(self.chrono_counter, layer)),
         );
+        Arc::make_mut(&mut self.lock_components).insert(key, Arc::new(LockComponent::default()));
+        Arc::make_mut(&mut self.link_components).insert(key, Arc::new(LinkComponent::default()));
         self.render_components
             .insert(key, RenderComponent::default());
 
@@ -345,6 +435,8 @@ impl StrokeStore {
         Arc::make_mut(&mut self.trash_components).remove(key);
         Arc::make_mut(&mut self.selection_components).remove(key);
         Arc::make_mut(&mut self.chrono_components).remove(key);
+        Arc::make_mut(&mut self.lock_components).remove(key);
+        Arc::make_mut(&mut self.link_components).remove(key);
         self.render_components.remove(key);
 
         self.key_tree.remove_with_key(key);
@@ -359,6 +451,8 @@ impl StrokeStore {
         Arc::make_mut(&mut self.trash_components).clear();
         Arc::make_mut(&mut self.selection_components).clear();
         Arc::make_mut(&mut self.chrono_components).clear();
+        Arc::make_mut(&mut self.lock_components).clear();
+        Arc::make_mut(&mut self.link_components).clear();
 
         self.chrono_counter = 0;
         let widget_flags = self.clear_history(HistoryEntry::default());
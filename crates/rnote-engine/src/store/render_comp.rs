@@ -1,5 +1,6 @@
 // Imports
 use super::{Stroke, StrokeKey, StrokeStore};
+use crate::engine::profiling::{self, ProfileCategory};
 use crate::engine::{EngineTask, EngineTaskSender};
 use crate::strokes::content::GeneratedContentImages;
 use crate::strokes::Content;
@@ -7,10 +8,17 @@ use crate::{render, Drawable};
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::ext::AabbExt;
 use rnote_compose::shapes::Shapeable;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use tracing::error;
 
 /// The tolerance where check between scale-factors are considered "equal".
 pub(crate) const RENDER_IMAGE_SCALE_TOLERANCE: f64 = 0.01;
+/// The factor the image scale of a text stroke's rendering may differ from the current one
+/// before it gets rerendered on zoom changes.
+///
+/// Rerendering text is expensive, so within this range the existing images are scaled instead.
+pub(crate) const TEXT_RERENDER_IMAGE_SCALE_THRESHOLD: f64 = 1.5;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RenderCompState {
@@ -32,6 +40,11 @@ pub struct RenderComponent {
     pub(super) images: Vec<render::Image>,
     #[cfg(feature = "ui")]
     pub(super) rendernodes: Vec<gtk4::gsk::RenderNode>,
+    /// Advanced every time the rendering is invalidated or a new render task is started.
+    ///
+    /// Shared with the render tasks, which skip their work or get their result discarded
+    /// when the generation has moved on in the meantime.
+    pub(super) generation: Arc<AtomicU64>,
 }
 
 impl Default for RenderComponent {
@@ -41,10 +54,69 @@ impl Default for RenderComponent {
             images: vec![],
             #[cfg(feature = "ui")]
             rendernodes: vec![],
+            generation: Arc::new(AtomicU64::new(0)),
         }
     }
 }
 
+impl RenderComponent {
+    /// Flag the rendering dirty, superseding all render tasks that are still in flight.
+    pub(super) fn set_dirty(&mut self) {
+        self.state = RenderCompState::Dirty;
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Start a new generation for a render task, superseding all others that are still in flight.
+    ///
+    /// Returns the generation of the new task.
+    fn start_render_task(&mut self) -> u64 {
+        self.state = RenderCompState::BusyRenderingInTask;
+        self.generation.fetch_add(1, Ordering::Relaxed) + 1
+    }
+}
+
+/// Generate the images of the stroke in a worker thread and send them back with an engine task.
+///
+/// Skipped when the render task is already superseded by the time it gets to run.
+fn spawn_render_task(
+    tasks_tx: EngineTaskSender,
+    key: StrokeKey,
+    stroke: Arc<Stroke>,
+    render_comp: &mut RenderComponent,
+    viewport: Aabb,
+    image_scale: f64,
+) {
+    let task_generation = render_comp.start_render_task();
+    let generation = Arc::clone(&render_comp.generation);
+
+    rayon::spawn(move || {
+        if generation.load(Ordering::Relaxed) != task_generation {
+            tasks_tx.stats().record_superseded();
+            return;
+        }
+
+        let span = profiling::span(ProfileCategory::RenderingRegeneration);
+        let images = stroke.gen_images(viewport, image_scale);
+        drop(span);
+
+        match images {
+            Ok(images) => {
+                tasks_tx.send(EngineTask::UpdateStrokeWithImages {
+                    key,
+                    images,
+                    image_scale,
+                    generation: task_generation,
+                });
+            }
+            Err(e) => {
+                error!(
+                    "Generating images of stroke failed while regenerating stroke rendering in viewport `{viewport:?}`, stroke key {key:?} , Err: {e:?}"
+                );
+            }
+        }
+    });
+}
+
 impl StrokeStore {
     /// Rebuild the slotmap with empty render components with the keys returned from the stroke components.
     pub(crate) fn rebuild_render_components_slotmap(&mut self) {
@@ -78,9 +150,16 @@ impl StrokeStore {
             .map(|render_comp| render_comp.state)
     }
 
+    /// The current rendering generation of the stroke.
+    pub(crate) fn render_comp_generation(&self, key: StrokeKey) -> Option<u64> {
+        self.render_components
+            .get(key)
+            .map(|render_comp| render_comp.generation.load(Ordering::Relaxed))
+    }
+
     pub(crate) fn set_rendering_dirty(&mut self, key: StrokeKey) {
         if let Some(render_comp) = self.render_components.get_mut(key) {
-            render_comp.state = RenderCompState::Dirty;
+            render_comp.set_dirty();
         }
     }
 
@@ -88,6 +167,34 @@ impl StrokeStore {
         keys.iter().for_each(|&key| self.set_rendering_dirty(key));
     }
 
+    /// Flag the rendering dirty for all strokes after the zoom has changed.
+    ///
+    /// Completely rendered text strokes are kept as long as the image scale of their images
+    /// is within [TEXT_RERENDER_IMAGE_SCALE_THRESHOLD] of the new image scale.
+    pub(crate) fn set_rendering_dirty_after_zoom(&mut self, image_scale: f64) {
+        for (key, render_comp) in self.render_components.iter_mut() {
+            let keep = render_comp.state == RenderCompState::Complete
+                && !render_comp.images.is_empty()
+                && self
+                    .stroke_components
+                    .get(key)
+                    .is_some_and(|stroke| matches!(stroke.as_ref(), Stroke::TextStroke(_)))
+                && render_comp.images.iter().all(|image| {
+                    let width = image.rect.bounds().extents()[0];
+                    if width <= 0.0 {
+                        return false;
+                    }
+                    let ratio = (f64::from(image.pixel_width) / width) / image_scale;
+                    (1.0 / TEXT_RERENDER_IMAGE_SCALE_THRESHOLD
+                        ..=TEXT_RERENDER_IMAGE_SCALE_THRESHOLD)
+                        .contains(&ratio)
+                });
+            if !keep {
+                render_comp.set_dirty();
+            }
+        }
+    }
+
     #[allow(unused)]
     pub(crate) fn holds_images(&self, key: StrokeKey) -> bool {
         self.render_components
@@ -113,7 +220,11 @@ impl StrokeStore {
             let viewport_extended =
                 viewport.extend_by(viewport.extents() * render::VIEWPORT_EXTENTS_MARGIN_FACTOR);
 
-            match stroke.gen_images(viewport_extended, image_scale) {
+            let span = profiling::span(ProfileCategory::RenderingRegeneration);
+            let images = stroke.gen_images(viewport_extended, image_scale);
+            drop(span);
+
+            match images {
                 Ok(GeneratedContentImages::Partial { images, viewport }) => {
                     #[cfg(feature = "ui")]
                     match render::Image::images_to_rendernodes(&images) {
@@ -123,7 +234,7 @@ impl StrokeStore {
                             render_comp.state = RenderCompState::ForViewport(viewport);
                         }
                         Err(e) => {
-                            render_comp.state = RenderCompState::Dirty;
+                            render_comp.set_dirty();
                             error!(
                                 "Creating rendernodes from partial images failed while regenerating stroke rendering, Err: {e:?}"
                             );
@@ -144,7 +255,7 @@ impl StrokeStore {
                             render_comp.state = RenderCompState::Complete;
                         }
                         Err(e) => {
-                            render_comp.state = RenderCompState::Dirty;
+                            render_comp.set_dirty();
                             error!(
                                 "Creating rendernodes from full images failed while regenerating stroke rendering, Err: {e:?}"
                             );
@@ -157,7 +268,7 @@ impl StrokeStore {
                     }
                 }
                 Err(e) => {
-                    render_comp.state = RenderCompState::Dirty;
+                    render_comp.set_dirty();
                     error!("Generating images for stroke with key {key:?} failed, Err: {e:?}");
                 }
             }
@@ -190,29 +301,16 @@ impl StrokeStore {
                 return;
             }
 
-            let stroke = stroke.clone();
             let viewport_extended =
                 viewport.extend_by(viewport.extents() * render::VIEWPORT_EXTENTS_MARGIN_FACTOR);
 
-            // indicates that a task is now started rendering the stroke
-            render_comp.state = RenderCompState::BusyRenderingInTask;
-
-            // Spawn a new thread for image rendering
-            rayon::spawn(
-                move || match stroke.gen_images(viewport_extended, image_scale) {
-                    Ok(images) => {
-                        tasks_tx.send(EngineTask::UpdateStrokeWithImages {
-                            key,
-                            images,
-                            image_scale,
-                        });
-                    }
-                    Err(e) => {
-                        error!(
-                            "Generating images of stroke failed while regenerating stroke rendering, stroke key {key:?} , Err: {e:?}"
-                        );
-                    }
-                },
+            spawn_render_task(
+                tasks_tx,
+                key,
+                stroke.clone(),
+                render_comp,
+                viewport_extended,
+                image_scale,
             );
         }
     }
@@ -261,7 +359,7 @@ impl StrokeStore {
                         render_comp.rendernodes = vec![];
                     }
                     render_comp.images = vec![];
-                    render_comp.state = RenderCompState::Dirty;
+                    render_comp.set_dirty();
                     continue;
                 }
 
@@ -291,26 +389,13 @@ impl StrokeStore {
                     }
                 }
 
-                // indicates that a task has now started to render the stroke
-                render_comp.state = RenderCompState::BusyRenderingInTask;
-                let stroke = stroke.clone();
-
-                // Spawn a new thread for image rendering
-                rayon::spawn(
-                    move || match stroke.gen_images(viewport_extended, image_scale) {
-                        Ok(images) => {
-                            tasks_tx.send(EngineTask::UpdateStrokeWithImages {
-                                key,
-                                images,
-                                image_scale,
-                            });
-                        }
-                        Err(e) => {
-                            error!(
-                                "Generating stroke images failed stroke while regenerating rendering in viewport `{viewport:?}`, stroke key: {key:?}, Err: {e:?}"
-                            );
-                        }
-                    },
+                spawn_render_task(
+                    tasks_tx,
+                    key,
+                    stroke.clone(),
+                    render_comp,
+                    viewport_extended,
+                    image_scale,
                 );
             }
         }
@@ -324,7 +409,7 @@ impl StrokeStore {
                 render_comp.rendernodes = vec![];
             }
             render_comp.images = vec![];
-            render_comp.state = RenderCompState::Dirty;
+            render_comp.set_dirty();
         }
     }
 
@@ -354,7 +439,7 @@ impl StrokeStore {
                                     render_comp.images.push(image);
                                 }
                                 Err(e) => {
-                                    render_comp.state = RenderCompState::Dirty;
+                                    render_comp.set_dirty();
                                     error!("Failed to generated rendernodes while appending last segments rendering, Err: {e:?}");
                                 }
                             }
@@ -366,7 +451,7 @@ impl StrokeStore {
 
                         Ok(None) => {}
                         Err(e) => {
-                            render_comp.state = RenderCompState::Dirty;
+                            render_comp.set_dirty();
                             error!(
                                 "Failed to generate image while appending last segments rendering, Err: {e:?}"
                             );
@@ -409,7 +494,7 @@ impl StrokeStore {
                         }
                         Err(e) => {
                             error!("Generating rendernodes failed while replacing rendering with partial images, Err {e:?}");
-                            render_comp.state = RenderCompState::Dirty;
+                            render_comp.set_dirty();
                         }
                     }
                     #[cfg(not(feature = "ui"))]
@@ -428,7 +513,7 @@ impl StrokeStore {
                         }
                         Err(e) => {
                             error!("Generating rendernodes failed while replacing rendering with full images, Err {e:?}");
-                            render_comp.state = RenderCompState::Dirty;
+                            render_comp.set_dirty();
                         }
                     }
                     #[cfg(not(feature = "ui"))]
@@ -464,7 +549,7 @@ impl StrokeStore {
                         }
                         Err(e) => {
                             error!("Generating rendernodes failed while appending rendering full images, Err {e:?}");
-                            render_comp.state = RenderCompState::Dirty;
+                            render_comp.set_dirty();
                         }
                     }
                     #[cfg(not(feature = "ui"))]
@@ -483,6 +568,44 @@ impl StrokeStore {
         snapshot: &gtk4::Snapshot,
         doc_bounds: Aabb,
         viewport: Aabb,
+    ) {
+        self.draw_keys_to_gtk_snapshot(
+            snapshot,
+            doc_bounds,
+            &self.stroke_keys_as_rendered_intersecting_bounds(viewport),
+        );
+    }
+
+    /// Draw the strokes of the given keys that intersect the viewport on the gtk snapshot, in the order of the keys.
+    ///
+    /// Used when only a part of the strokes should be visible, for example while replaying the drawing order.
+    #[cfg(feature = "ui")]
+    pub(crate) fn draw_keys_intersecting_bounds_to_gtk_snapshot(
+        &self,
+        snapshot: &gtk4::Snapshot,
+        doc_bounds: Aabb,
+        viewport: Aabb,
+        keys: &[StrokeKey],
+    ) {
+        let intersecting = self
+            .key_tree
+            .keys_intersecting_bounds(viewport)
+            .into_iter()
+            .collect::<std::collections::HashSet<StrokeKey>>();
+        let keys = keys
+            .iter()
+            .copied()
+            .filter(|key| intersecting.contains(key))
+            .collect::<Vec<StrokeKey>>();
+        self.draw_keys_to_gtk_snapshot(snapshot, doc_bounds, &keys);
+    }
+
+    #[cfg(feature = "ui")]
+    fn draw_keys_to_gtk_snapshot(
+        &self,
+        snapshot: &gtk4::Snapshot,
+        doc_bounds: Aabb,
+        keys: &[StrokeKey],
     ) {
         use crate::ext::{GdkRGBAExt, GrapheneRectExt};
         use gtk4::{gdk, graphene, prelude::*};
@@ -490,7 +613,7 @@ impl StrokeStore {
 
         snapshot.push_clip(&graphene::Rect::from_p2d_aabb(doc_bounds));
 
-        for key in self.stroke_keys_as_rendered_intersecting_bounds(viewport) {
+        for &key in keys {
             if let (Some(stroke), Some(render_comp)) = (
                 self.stroke_components.get(key),
                 self.render_components.get(key),
@@ -20,6 +20,32 @@ impl Default for SelectionComponent {
     }
 }
 
+/// What the strokes that expand the selection have in common with the selected strokes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectSimilar {
+    /// Strokes sharing a stroke, fill or text color.
+    Color,
+    /// Strokes created with the same kind of pen.
+    PenKind,
+    /// Strokes on the same pages.
+    Page,
+}
+
+impl std::str::FromStr for SelectSimilar {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "color" => Ok(Self::Color),
+            "pen-kind" => Ok(Self::PenKind),
+            "page" => Ok(Self::Page),
+            s => Err(anyhow::anyhow!(
+                "Creating SelectSimilar from &str failed, invalid name {s}"
+            )),
+        }
+    }
+}
+
 /// Systems that are related to selecting.
 impl StrokeStore {
     /// Rebuild the slotmap with empty selection components with the keys returned from the stroke components.
@@ -44,7 +70,12 @@ impl StrokeStore {
     }
 
     /// Set if the stroke is currently selected.
+    ///
+    /// Locked strokes can't be selected.
     pub(crate) fn set_selected(&mut self, key: StrokeKey, selected: bool) {
+        if selected && self.locked(key).unwrap_or(false) {
+            return;
+        }
         if let Some(selection_comp) = Arc::make_mut(&mut self.selection_components)
             .get_mut(key)
             .map(Arc::make_mut)
@@ -61,6 +92,48 @@ impl StrokeStore {
         })
     }
 
+    /// Stroke keys of the strokes sharing a color with any of the given strokes, in the order that they should be rendered.
+    pub(crate) fn stroke_keys_as_rendered_w_same_color(
+        &self,
+        keys: &[StrokeKey],
+    ) -> Vec<StrokeKey> {
+        let colors = self.colors_for_strokes(keys);
+        if colors.is_empty() {
+            return vec![];
+        }
+        self.stroke_keys_as_rendered()
+            .into_iter()
+            .filter(|&key| {
+                self.stroke_components.get(key).is_some_and(|stroke| {
+                    stroke
+                        .colors()
+                        .into_iter()
+                        .any(|color| colors.iter().any(|c| c.approx_eq_f32(color)))
+                })
+            })
+            .collect()
+    }
+
+    /// Stroke keys of the strokes created with the same kind of pen as any of the given strokes,
+    /// in the order that they should be rendered.
+    pub(crate) fn stroke_keys_as_rendered_w_same_pen_kind(
+        &self,
+        keys: &[StrokeKey],
+    ) -> Vec<StrokeKey> {
+        let strokes = keys
+            .iter()
+            .filter_map(|&key| self.stroke_components.get(key))
+            .collect::<Vec<_>>();
+        self.stroke_keys_as_rendered()
+            .into_iter()
+            .filter(|&key| {
+                self.stroke_components
+                    .get(key)
+                    .is_some_and(|stroke| strokes.iter().any(|s| s.same_pen_kind(stroke)))
+            })
+            .collect()
+    }
+
     pub(crate) fn selection_keys_unordered(&self) -> Vec<StrokeKey> {
         self.stroke_components
             .keys()
@@ -87,7 +160,6 @@ impl StrokeStore {
     /// Generate the bounds that include all selected strokes.
     ///
     /// None if no strokes are selected
-    #[allow(unused)]
     pub(crate) fn selection_bounds(&self) -> Option<Aabb> {
         self.bounds_for_strokes(&self.selection_keys_unordered())
     }
@@ -102,8 +174,10 @@ impl StrokeStore {
         let new_selected = old_selected
             .iter()
             .filter_map(|&old_key| {
+                // keep the layer, so that duplicated highlighters stay beneath other strokes
+                let layer = self.chrono_components.get(old_key).map(|c| c.layer);
                 let new_key =
-                    self.insert_stroke((**self.stroke_components.get(old_key)?).clone(), None);
+                    self.insert_stroke((**self.stroke_components.get(old_key)?).clone(), layer);
                 self.set_selected(new_key, true);
 
                 // duplicate and insert the render images of the old stroke to avoid flickering
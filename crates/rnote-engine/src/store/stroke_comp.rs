@@ -1,8 +1,7 @@
 // Imports
-use super::render_comp::RenderCompState;
 use super::StrokeKey;
 use crate::engine::StrokeContent;
-use crate::strokes::{Content, Stroke};
+use crate::strokes::{Content, ImageCrop, Stroke};
 use crate::{StrokeStore, WidgetFlags};
 use geo::intersects::Intersects;
 use geo::prelude::Contains;
@@ -15,6 +14,24 @@ use std::sync::Arc;
 #[cfg(feature = "ui")]
 use tracing::error;
 
+/// The axis strokes are mirrored along.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlipAxis {
+    /// Mirror left to right.
+    Horizontal,
+    /// Mirror top to bottom.
+    Vertical,
+}
+
+impl FlipAxis {
+    fn scale(&self) -> na::Vector2<f64> {
+        match self {
+            FlipAxis::Horizontal => na::vector![-1.0, 1.0],
+            FlipAxis::Vertical => na::vector![1.0, -1.0],
+        }
+    }
+}
+
 /// Systems that are related to the stroke components.
 impl StrokeStore {
     /// Gets a immutable reference to a stroke.
@@ -54,11 +71,20 @@ impl StrokeStore {
         self.stroke_components.keys().collect()
     }
 
-    #[allow(unused)]
-    pub(crate) fn keys_unordered_intersecting_bounds(&self, bounds: Aabb) -> Vec<StrokeKey> {
+    /// All keys whose bounds intersect the given bounds, unordered.
+    pub(crate) fn keys_intersecting_bounds(&self, bounds: Aabb) -> Vec<StrokeKey> {
         self.key_tree.keys_intersecting_bounds(bounds)
     }
 
+    /// Stroke keys whose bounds intersect the given bounds, unordered.
+    pub(crate) fn stroke_keys_unordered_intersecting_bounds(&self, bounds: Aabb) -> Vec<StrokeKey> {
+        self.key_tree
+            .keys_intersecting_bounds(bounds)
+            .into_iter()
+            .filter(|&key| !(self.trashed(key).unwrap_or(false)))
+            .collect()
+    }
+
     /// All stroke keys that are not trashed, unordered.
     pub(crate) fn stroke_keys_unordered(&self) -> Vec<StrokeKey> {
         self.stroke_components
@@ -105,7 +131,15 @@ impl StrokeStore {
     /// Updates the stroke geometry.
     ///
     /// The stroke then needs to update its rendering.
+    ///
+    /// Captions that are attached to the stroke are moved along.
     pub(crate) fn update_geometry_for_stroke(&mut self, key: StrokeKey) {
+        self.update_stroke_geometry(key);
+        self.update_captions_position(key);
+    }
+
+    /// Updates the geometry of the single stroke, without its attached captions.
+    pub(crate) fn update_stroke_geometry(&mut self, key: StrokeKey) {
         if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
             .get_mut(key)
             .map(Arc::make_mut)
@@ -298,6 +332,92 @@ impl StrokeStore {
         widget_flags
     }
 
+    /// Replace the stroke, text and fill colors matching `from` with `to` for the given keys.
+    ///
+    /// Strokes then need to update their rendering.
+    pub(crate) fn remap_colors(
+        &mut self,
+        keys: &[StrokeKey],
+        from: Color,
+        to: Color,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        keys.iter().for_each(|&key| {
+            if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
+                .get_mut(key)
+                .map(Arc::make_mut)
+            {
+                if stroke.remap_color(from, to) {
+                    self.set_rendering_dirty(key);
+                    widget_flags.redraw = true;
+                    widget_flags.store_modified = true;
+                }
+            }
+        });
+
+        widget_flags
+    }
+
+    /// Set the crop of an image stroke and update its bounds.
+    ///
+    /// Returns true if the stroke was modified. The stroke then needs to update its rendering.
+    pub(crate) fn set_image_crop(&mut self, key: StrokeKey, crop: Option<ImageCrop>) -> bool {
+        let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
+            .get_mut(key)
+            .map(Arc::make_mut)
+        else {
+            return false;
+        };
+        if !stroke.set_image_crop(crop) {
+            return false;
+        }
+        self.key_tree.update_with_key(key, stroke.bounds());
+        self.set_rendering_dirty(key);
+        true
+    }
+
+    /// Move a node of the bezier path of a shape stroke and update its geometry.
+    ///
+    /// Returns true if the stroke was modified. The stroke then needs to update its rendering.
+    pub(crate) fn move_bezier_path_node(
+        &mut self,
+        key: StrokeKey,
+        index: usize,
+        pos: na::Vector2<f64>,
+    ) -> bool {
+        let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
+            .get_mut(key)
+            .map(Arc::make_mut)
+        else {
+            return false;
+        };
+        if !stroke.move_bezier_path_node(index, pos) {
+            return false;
+        }
+        stroke.update_geometry();
+        self.key_tree.update_with_key(key, stroke.bounds());
+        self.set_rendering_dirty(key);
+        true
+    }
+
+    /// The distinct stroke, text and fill colors of the given keys, in order of appearance.
+    pub(crate) fn colors_for_strokes(&self, keys: &[StrokeKey]) -> Vec<Color> {
+        let mut colors: Vec<Color> = vec![];
+
+        for color in keys
+            .iter()
+            .filter_map(|&key| self.stroke_components.get(key))
+            .flat_map(|stroke| stroke.colors())
+        {
+            if !colors.iter().any(|c| c.approx_eq_f32(color)) {
+                colors.push(color);
+            }
+        }
+
+        colors
+    }
+
     /// Invert the stroke, text and fill color of the given keys.
     ///
     /// Strokes then need to update their rendering.
@@ -327,6 +447,37 @@ impl StrokeStore {
         widget_flags
     }
 
+    /// Simplify the paths of the brush strokes of the given keys.
+    ///
+    /// The strokes then need to update their rendering.
+    pub(crate) fn simplify_brushstrokes(
+        &mut self,
+        keys: &[StrokeKey],
+        epsilon: f64,
+    ) -> WidgetFlags {
+        let mut widget_flags = WidgetFlags::default();
+
+        if keys.is_empty() {
+            return widget_flags;
+        }
+
+        keys.iter().for_each(|&key| {
+            if let Some(Stroke::BrushStroke(brushstroke)) =
+                Arc::make_mut(&mut self.stroke_components)
+                    .get_mut(key)
+                    .map(Arc::make_mut)
+            {
+                brushstroke.path.simplify(epsilon);
+                self.update_geometry_for_stroke(key);
+            }
+        });
+
+        widget_flags.redraw = true;
+        widget_flags.store_modified = true;
+
+        widget_flags
+    }
+
     /// Change the fill color of the given keys.
     ///
     /// The strokes then need to update their rendering.
@@ -375,7 +526,7 @@ impl StrokeStore {
     ) {
         keys.iter().for_each(|&key| {
             if let Some(render_comp) = self.render_components.get_mut(key) {
-                render_comp.state = RenderCompState::Dirty;
+                render_comp.set_dirty();
 
                 for image in render_comp.images.iter_mut() {
                     image.rotate(angle, center);
@@ -412,13 +563,33 @@ impl StrokeStore {
         });
     }
 
+    /// Scale the stroke widths of brush- and shapestrokes with the factor, leaving the geometry untouched.
+    ///
+    /// The strokes then need to update their rendering.
+    pub(crate) fn scale_stroke_widths(&mut self, keys: &[StrokeKey], factor: f64) {
+        keys.iter().for_each(|&key| {
+            if let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
+                .get_mut(key)
+                .map(Arc::make_mut)
+            {
+                let style = match stroke {
+                    Stroke::BrushStroke(brushstroke) => &mut brushstroke.style,
+                    Stroke::ShapeStroke(shapestroke) => &mut shapestroke.style,
+                    _ => return,
+                };
+                style.set_stroke_width(style.stroke_width() * factor);
+                self.key_tree.update_with_key(key, stroke.bounds());
+            }
+        });
+    }
+
     /// Scale the stroke rendering images.
     ///
     /// The strokes then need to update their rendering.
     pub(crate) fn scale_strokes_images(&mut self, keys: &[StrokeKey], scale: na::Vector2<f64>) {
         keys.iter().for_each(|&key| {
             if let Some(render_comp) = self.render_components.get_mut(key) {
-                render_comp.state = RenderCompState::Dirty;
+                render_comp.set_dirty();
 
                 for image in render_comp.images.iter_mut() {
                     image.scale(scale);
@@ -465,16 +636,38 @@ impl StrokeStore {
         self.translate_strokes_images(strokes, pivot);
     }
 
+    /// Mirror the strokes along the axis through the pivot.
+    ///
+    /// The strokes then need to update their rendering.
+    pub(crate) fn flip_strokes(
+        &mut self,
+        keys: &[StrokeKey],
+        axis: FlipAxis,
+        pivot: na::Vector2<f64>,
+    ) {
+        self.scale_strokes_with_pivot(keys, axis.scale(), pivot);
+    }
+
+    /// Mirror the stroke rendering images along the axis through the pivot.
+    ///
+    /// The strokes then need to update their rendering.
+    pub(crate) fn flip_strokes_images(
+        &mut self,
+        keys: &[StrokeKey],
+        axis: FlipAxis,
+        pivot: na::Vector2<f64>,
+    ) {
+        self.scale_strokes_images_with_pivot(keys, axis.scale(), pivot);
+    }
+
     /// Return the keys for stroke whose hitboxes are contained in the given polygon path.
     pub(crate) fn strokes_hitboxes_contained_in_path_polygon(
         &mut self,
         path: &[Element],
-        viewport: Aabb,
     ) -> Vec<StrokeKey> {
-        let mut bounds = viewport;
-        for p in path {
-            bounds.take_point(p.pos.into());
-        }
+        let Some(bounds) = path_bounds(path) else {
+            return vec![];
+        };
 
         let path_polygon = {
             let selector_path_points = path
@@ -491,8 +684,8 @@ impl StrokeStore {
         self.keys_sorted_chrono_intersecting_bounds(bounds)
             .into_iter()
             .filter_map(|key| {
-                // skip if stroke is trashed
-                if self.trashed(key)? {
+                // skip if stroke is trashed or locked
+                if self.trashed(key)? || self.locked(key)? {
                     return None;
                 }
 
@@ -521,15 +714,10 @@ impl StrokeStore {
     }
 
     /// Return the keys for strokes whose hitboxes intersect in the given path.
-    pub(crate) fn strokes_hitboxes_intersect_path(
-        &mut self,
-        path: &[Element],
-        viewport: Aabb,
-    ) -> Vec<StrokeKey> {
-        let mut bounds = viewport;
-        for p in path {
-            bounds.take_point(p.pos.into());
-        }
+    pub(crate) fn strokes_hitboxes_intersect_path(&mut self, path: &[Element]) -> Vec<StrokeKey> {
+        let Some(bounds) = path_bounds(path) else {
+            return vec![];
+        };
 
         let path_linestring = {
             let selector_path_points = path
@@ -546,8 +734,8 @@ impl StrokeStore {
         self.keys_sorted_chrono_intersecting_bounds(bounds)
             .into_iter()
             .filter_map(|key| {
-                // skip if stroke is trashed
-                if self.trashed(key)? {
+                // skip if stroke is trashed or locked
+                if self.trashed(key)? || self.locked(key)? {
                     return None;
                 }
 
@@ -571,16 +759,12 @@ impl StrokeStore {
     }
 
     /// Return the keys for strokes whose hitboxes are contained in the given Aabb.
-    pub(crate) fn strokes_hitboxes_contained_in_aabb(
-        &mut self,
-        aabb: Aabb,
-        viewport: Aabb,
-    ) -> Vec<StrokeKey> {
-        self.keys_sorted_chrono_intersecting_bounds(viewport.merged(&aabb))
+    pub(crate) fn strokes_hitboxes_contained_in_aabb(&mut self, aabb: Aabb) -> Vec<StrokeKey> {
+        self.keys_sorted_chrono_intersecting_bounds(aabb)
             .into_iter()
             .filter_map(|key| {
-                // skip if stroke is trashed
-                if self.trashed(key)? {
+                // skip if stroke is trashed or locked
+                if self.trashed(key)? || self.locked(key)? {
                     return None;
                 }
 
@@ -604,18 +788,17 @@ impl StrokeStore {
             .collect()
     }
 
-    /// Return the keys for strokes where the given coord is inside at least one of their hitboxes.
-    pub(crate) fn stroke_hitboxes_contain_coord(
-        &self,
-        viewport: Aabb,
-        coord: na::Vector2<f64>,
-    ) -> Vec<StrokeKey> {
-        let mut bounds = viewport;
-        bounds.take_point(coord.into());
-
-        self.stroke_keys_as_rendered_intersecting_bounds(bounds)
+    /// Return the keys for strokes where the given coord is inside at least one of their hitboxes,
+    /// in the order that they should be rendered.
+    ///
+    /// Locked strokes are skipped.
+    pub(crate) fn stroke_hitboxes_contain_coord(&self, coord: na::Vector2<f64>) -> Vec<StrokeKey> {
+        self.keys_sorted_chrono_at_point(coord.into())
             .into_iter()
             .filter(|&key| {
+                if self.trashed(key).unwrap_or(false) || self.locked(key).unwrap_or(false) {
+                    return false;
+                }
                 if let Some(stroke) = self.stroke_components.get(key) {
                     stroke
                         .hitboxes()
@@ -630,16 +813,7 @@ impl StrokeStore {
 
     /// Return all keys below the given `y`.
     pub(crate) fn keys_below_y(&self, y: f64) -> Vec<StrokeKey> {
-        self.stroke_components
-            .iter()
-            .filter_map(|(key, stroke)| {
-                if stroke.bounds().mins[1] > y {
-                    Some(key)
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<StrokeKey>>()
+        self.key_tree.keys_below_y(y)
     }
 
     pub(crate) fn keys_between(
@@ -650,7 +824,7 @@ impl StrokeStore {
         limit_movement_vertical_border: bool,
         limit_movement_horizontal_border: bool,
     ) -> Vec<StrokeKey> {
-        self.key_tree.keys_intersecting_bounds(Aabb::new(
+        let bounds = Aabb::new(
             na::point![
                 if limit_movement_vertical_border {
                     x_lims.0
@@ -671,7 +845,13 @@ impl StrokeStore {
                     f64::INFINITY
                 }
             ],
-        ))
+        );
+        // locked strokes stay in place
+        self.key_tree
+            .keys_intersecting_bounds(bounds)
+            .into_iter()
+            .filter(|&key| !(self.locked(key).unwrap_or(false)))
+            .collect()
     }
 
     pub(crate) fn filter_keys_intersecting_bounds<'a, I: IntoIterator<Item = &'a StrokeKey>>(
@@ -696,14 +876,15 @@ impl StrokeStore {
         StrokeContent::default().with_strokes(strokes)
     }
 
-    /// Cut the strokes for the given keys and return them as stroke content.
+    /// Cut the strokes for the given keys together with their attached captions and return them as stroke content.
     pub(crate) fn cut_stroke_content(&mut self, keys: &[StrokeKey]) -> StrokeContent {
-        let strokes = keys
-            .iter()
+        let strokes = self
+            .keys_w_attached_captions(keys)
+            .into_iter()
             .filter_map(|k| {
-                self.set_selected(*k, false);
-                self.set_trashed(*k, true);
-                self.stroke_components.get(*k).cloned()
+                self.set_selected(k, false);
+                self.set_trashed(k, true);
+                self.stroke_components.get(k).cloned()
             })
             .collect();
 
@@ -750,3 +931,13 @@ impl StrokeStore {
             .collect()
     }
 }
+
+/// The bounds enclosing all elements of the path.
+fn path_bounds(path: &[Element]) -> Option<Aabb> {
+    let first = path.first()?;
+    let mut bounds = Aabb::new(first.pos.into(), first.pos.into());
+    for element in path.iter().skip(1) {
+        bounds.take_point(element.pos.into());
+    }
+    Some(bounds)
+}
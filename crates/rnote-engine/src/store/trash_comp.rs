@@ -1,8 +1,10 @@
 // Imports
 use super::chrono_comp::StrokeLayer;
 use super::{StrokeKey, StrokeStore};
+use crate::pens::pensconfig::eraserconfig::EraserFilter;
 use crate::strokes::{BrushStroke, Stroke};
 use crate::WidgetFlags;
+use chrono::{DateTime, Local};
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::shapes::Shapeable;
 use rnote_compose::PenPath;
@@ -14,11 +16,17 @@ use std::sync::Arc;
 pub struct TrashComponent {
     #[serde(rename = "trashed")]
     pub trashed: bool,
+    /// When the stroke was trashed. Only kept for the current session.
+    #[serde(skip)]
+    pub trashed_at: Option<DateTime<Local>>,
 }
 
 impl Default for TrashComponent {
     fn default() -> Self {
-        Self { trashed: false }
+        Self {
+            trashed: false,
+            trashed_at: None,
+        }
     }
 }
 
@@ -49,16 +57,20 @@ impl StrokeStore {
             .map(Arc::make_mut)
         {
             trash_comp.trashed = trash;
+            trash_comp.trashed_at = trash.then(Local::now);
             self.update_chrono_to_last(key);
         }
     }
 
+    /// Trash or restore the strokes, together with the captions that are attached to them.
     pub(crate) fn set_trashed_keys(&mut self, keys: &[StrokeKey], trash: bool) {
-        keys.iter().for_each(|&key| {
-            self.set_selected(key, false);
-            self.set_trashed(key, trash);
-            self.update_chrono_to_last(key);
-        });
+        self.keys_w_attached_captions(keys)
+            .into_iter()
+            .for_each(|key| {
+                self.set_selected(key, false);
+                self.set_trashed(key, trash);
+                self.update_chrono_to_last(key);
+            });
     }
 
     pub(crate) fn trashed_keys_unordered(&self) -> Vec<StrokeKey> {
@@ -68,6 +80,22 @@ impl StrokeStore {
             .collect()
     }
 
+    /// The time the stroke was trashed, if it is trashed.
+    pub(crate) fn trashed_at(&self, key: StrokeKey) -> Option<DateTime<Local>> {
+        self.trash_components
+            .get(key)
+            .and_then(|t| if t.trashed { t.trashed_at } else { None })
+    }
+
+    /// All trashed keys, the most recently trashed first.
+    ///
+    /// Keys without a trash time (e.g. trashed before the store was loaded) come last.
+    pub(crate) fn trashed_keys_sorted_recent(&self) -> Vec<StrokeKey> {
+        let mut keys = self.trashed_keys_unordered();
+        keys.sort_by_key(|&key| std::cmp::Reverse(self.trashed_at(key)));
+        keys
+    }
+
     /// Removes all trashed strokes permanently from the store.
     #[allow(unused)]
     pub(crate) fn remove_trashed_strokes(&mut self) -> Vec<Stroke> {
@@ -77,16 +105,30 @@ impl StrokeStore {
             .collect()
     }
 
-    /// Trash strokes that collide with the given bounds.
+    /// Whether the stroke is affected by the eraser with the given filter.
+    fn eraser_filter_matches(&self, key: StrokeKey, filter: &EraserFilter) -> bool {
+        match (
+            self.stroke_components.get(key),
+            self.chrono_components.get(key),
+        ) {
+            (Some(stroke), Some(chrono_comp)) => filter.matches(stroke, chrono_comp.layer),
+            _ => false,
+        }
+    }
+
+    /// Trash strokes that collide with the given bounds and match the eraser filter.
     pub(crate) fn trash_colliding_strokes(
         &mut self,
         eraser_bounds: Aabb,
-        viewport: Aabb,
+        filter: &EraserFilter,
     ) -> WidgetFlags {
         let mut widget_flags = WidgetFlags::default();
 
-        self.stroke_keys_as_rendered_intersecting_bounds(viewport)
+        self.stroke_keys_unordered_intersecting_bounds(eraser_bounds)
             .into_iter()
+            .filter(|&key| {
+                !(self.locked(key).unwrap_or(false)) && self.eraser_filter_matches(key, filter)
+            })
             .for_each(|key| {
                 let mut trash_current_stroke = false;
 
@@ -120,7 +162,7 @@ impl StrokeStore {
         widget_flags
     }
 
-    /// Remove colliding stroke segments with the given bounds.
+    /// Remove colliding stroke segments with the given bounds, of strokes matching the eraser filter.
     /// The stroke is then split. Strokes that don't have segments are trashed completely.
     ///
     /// Returns the keys of all created or modified strokes.
@@ -129,14 +171,17 @@ impl StrokeStore {
     pub(crate) fn split_colliding_strokes(
         &mut self,
         eraser_bounds: Aabb,
-        viewport: Aabb,
+        filter: &EraserFilter,
     ) -> (Vec<StrokeKey>, WidgetFlags) {
         let mut widget_flags = WidgetFlags::default();
         let mut modified_keys = vec![];
 
         let new_strokes = self
-            .stroke_keys_as_rendered_intersecting_bounds(viewport)
+            .stroke_keys_unordered_intersecting_bounds(eraser_bounds)
             .into_iter()
+            .filter(|&key| {
+                !(self.locked(key).unwrap_or(false)) && self.eraser_filter_matches(key, filter)
+            })
             .flat_map(|key| {
                 let Some(stroke) = Arc::make_mut(&mut self.stroke_components)
                     .get_mut(key)
@@ -186,11 +231,13 @@ impl StrokeStore {
                                     let mut next_split_iter = next_split.into_iter();
                                     let next_start = next_split_iter.next().unwrap().end();
 
+                                    let mut split_brushstroke = BrushStroke::from_penpath(
+                                        PenPath::new_w_segments(next_start, next_split_iter),
+                                        brushstroke.style.clone(),
+                                    );
+                                    split_brushstroke.outline = brushstroke.outline;
                                     new_strokes.push((
-                                        Stroke::BrushStroke(BrushStroke::from_penpath(
-                                            PenPath::new_w_segments(next_start, next_split_iter),
-                                            brushstroke.style.clone(),
-                                        )),
+                                        Stroke::BrushStroke(split_brushstroke),
                                         chrono_comp.layer,
                                     ));
                                 }
@@ -1,8 +1,9 @@
 // Imports
 use super::resize::{calculate_resize_ratio, ImageSizeOption};
-use super::{Content, Stroke};
+use super::{Content, ImageCrop, PdfTextLayer};
 use crate::document::Format;
-use crate::engine::import::{PdfImportPageSpacing, PdfImportPrefs};
+use crate::engine::import::PdfImportPrefs;
+use crate::engine::ImportJobHandle;
 use crate::render;
 use crate::Drawable;
 use anyhow::Context;
@@ -16,7 +17,6 @@ use rnote_compose::shapes::Shapeable;
 use rnote_compose::transform::Transform;
 use rnote_compose::transform::Transformable;
 use serde::{Deserialize, Serialize};
-use std::ops::Range;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, rename = "bitmapimage")]
@@ -27,8 +27,15 @@ pub struct BitmapImage {
     /// Use rectangle.bounds() instead.
     #[serde(rename = "image")]
     pub image: render::Image,
+    /// The uncropped image rectangle.
     #[serde(rename = "rectangle")]
     pub rectangle: Rectangle,
+    /// The visible region, when the image is cropped.
+    #[serde(rename = "crop")]
+    pub crop: Option<ImageCrop>,
+    /// The text layer, when the image is an imported Pdf page.
+    #[serde(rename = "pdf_text")]
+    pub pdf_text: Option<PdfTextLayer>,
 }
 
 impl Default for BitmapImage {
@@ -36,6 +43,8 @@ impl Default for BitmapImage {
         Self {
             image: render::Image::default(),
             rectangle: Rectangle::default(),
+            crop: None,
+            pdf_text: None,
         }
     }
 }
@@ -50,6 +59,9 @@ impl Drawable for BitmapImage {
 
         cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
         cx.transform(self.rectangle.transform.affine.to_kurbo());
+        if let Some(crop) = self.crop {
+            cx.clip(crop.local_aabb(&self.rectangle).to_kurbo_rect());
+        }
 
         let piet_image = cx
             .make_image(
@@ -71,7 +83,7 @@ impl Drawable for BitmapImage {
 
 impl Shapeable for BitmapImage {
     fn bounds(&self) -> Aabb {
-        self.rectangle.bounds()
+        self.visible_rectangle().bounds()
     }
 
     fn hitboxes(&self) -> Vec<Aabb> {
@@ -98,13 +110,31 @@ impl Transformable for BitmapImage {
 }
 
 impl BitmapImage {
+    /// The rectangle of the visible region of the image.
+    pub fn visible_rectangle(&self) -> Rectangle {
+        match self.crop {
+            Some(crop) => crop.cropped_rectangle(&self.rectangle),
+            None => self.rectangle,
+        }
+    }
+
     pub fn from_image_bytes(
         bytes: &[u8],
         pos: na::Vector2<f64>,
         size_option: ImageSizeOption,
     ) -> Result<Self, anyhow::Error> {
-        let image = render::Image::try_from_encoded_bytes(bytes)?;
+        Ok(Self::from_image(
+            render::Image::try_from_encoded_bytes(bytes)?,
+            pos,
+            size_option,
+        ))
+    }
 
+    pub fn from_image(
+        image: render::Image,
+        pos: na::Vector2<f64>,
+        size_option: ImageSizeOption,
+    ) -> Self {
         let initial_size = na::vector![f64::from(image.pixel_width), f64::from(image.pixel_height)];
 
         let (size, resize_ratio) = match size_option {
@@ -123,27 +153,34 @@ impl BitmapImage {
             cuboid: p2d::shape::Cuboid::new(size * 0.5),
             transform,
         };
-        Ok(Self { image, rectangle })
+        Self {
+            image,
+            rectangle,
+            crop: None,
+            pdf_text: None,
+        }
     }
 
     pub fn from_pdf_bytes(
         to_be_read: &[u8],
         pdf_import_prefs: PdfImportPrefs,
         insert_pos: na::Vector2<f64>,
-        page_range: Option<Range<u32>>,
+        pages: Option<Vec<u32>>,
         format: &Format,
         password: Option<String>,
+        job: Option<&ImportJobHandle>,
     ) -> Result<Vec<Self>, anyhow::Error> {
         let doc =
             poppler::Document::from_bytes(&glib::Bytes::from(to_be_read), password.as_deref())?;
-        let page_range = page_range.unwrap_or(0..doc.n_pages() as u32);
+        let pages = pages.unwrap_or_else(|| (0..doc.n_pages() as u32).collect());
+        let n_pages = pages.len();
         let page_width = if pdf_import_prefs.adjust_document {
             format.width()
         } else {
             format.width() * (pdf_import_prefs.page_width_perc / 100.0)
         };
-        // calculate the page zoom based on the width of the first page.
-        let page_zoom = if let Some(first_page) = doc.page(0) {
+        // calculate the page zoom based on the width of the first imported page.
+        let page_zoom = if let Some(first_page) = pages.first().and_then(|&i| doc.page(i as i32)) {
             page_width / first_page.size().0
         } else {
             return Ok(vec![]);
@@ -151,17 +188,24 @@ impl BitmapImage {
         let x = insert_pos[0];
         let mut y = insert_pos[1];
 
-        let pngs = page_range
-            .map(|page_i| {
+        let pngs = pages
+            .into_iter()
+            .enumerate()
+            .map(|(i, page_i)| {
+                if let Some(job) = job {
+                    job.check_cancelled()?;
+                    job.set_progress(i as f64 / n_pages as f64);
+                }
                 let page = doc
                     .page(page_i as i32)
                     .ok_or_else(|| anyhow::anyhow!("no page at index '{page_i}"))?;
                 let (intrinsic_width, intrinsic_height) = page.size();
+                let pdf_text = PdfTextLayer::from_poppler_page(&page);
                 let width = intrinsic_width * page_zoom;
                 let height = intrinsic_height * page_zoom;
                 let surface_width = (width * pdf_import_prefs.bitmap_scalefactor).round() as i32;
                 let surface_height = (height * pdf_import_prefs.bitmap_scalefactor).round() as i32;
-                let surface = cairo::ImageSurface::create(
+                let mut surface = cairo::ImageSurface::create(
                     cairo::Format::ARgb32,
                     surface_width,
                     surface_height,
@@ -205,29 +249,36 @@ impl BitmapImage {
                     }
                 }
 
+                if pdf_import_prefs.color_remap.enabled {
+                    pdf_import_prefs
+                        .color_remap
+                        .remap_image_surface(&mut surface)?;
+                }
+
                 let mut png_data: Vec<u8> = Vec::new();
                 surface.write_to_png(&mut png_data)?;
                 let image_pos = na::vector![x, y];
                 let image_size = na::vector![width, height];
 
-                if pdf_import_prefs.adjust_document {
-                    y += height
-                } else {
-                    y += match pdf_import_prefs.page_spacing {
-                        PdfImportPageSpacing::Continuous => {
-                            height + Stroke::IMPORT_OFFSET_DEFAULT[1] * 0.5
-                        }
-                        PdfImportPageSpacing::OnePerDocumentPage => format.height(),
-                    };
-                }
+                y += pdf_import_prefs.page_offset(height, format);
 
-                Ok((png_data, image_pos, image_size))
+                Ok((png_data, image_pos, image_size, pdf_text))
             })
-            .collect::<anyhow::Result<Vec<(Vec<u8>, na::Vector2<f64>, na::Vector2<f64>)>>>()?;
+            .collect::<anyhow::Result<
+                Vec<(
+                    Vec<u8>,
+                    na::Vector2<f64>,
+                    na::Vector2<f64>,
+                    Option<PdfTextLayer>,
+                )>,
+            >>()?;
 
         pngs.into_par_iter()
-            .map(|(png_data, pos, size)| {
-                Self::from_image_bytes(&png_data, pos, ImageSizeOption::ImposeSize(size))
+            .map(|(png_data, pos, size, pdf_text)| {
+                let mut bitmapimage =
+                    Self::from_image_bytes(&png_data, pos, ImageSizeOption::ImposeSize(size))?;
+                bitmapimage.pdf_text = pdf_text;
+                Ok(bitmapimage)
             })
             .collect()
     }
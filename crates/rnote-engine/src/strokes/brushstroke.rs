@@ -1,6 +1,6 @@
 // Imports
 use super::content::GeneratedContentImages;
-use super::Content;
+use super::{Content, StrokeOutline};
 use crate::Drawable;
 use crate::{
     render::{self},
@@ -23,6 +23,9 @@ pub struct BrushStroke {
     pub path: PenPath,
     #[serde(default, rename = "style")]
     pub style: Style,
+    /// The outline drawn beneath the stroke.
+    #[serde(default, rename = "outline")]
+    pub outline: Option<StrokeOutline>,
     // since the path can have many hitboxes, we store them here and update them when the stroke geometry changes
     #[serde(skip)]
     hitboxes: Vec<Aabb>,
@@ -58,13 +61,15 @@ impl Content for BrushStroke {
             > IMAGES_STROKE_WIDTH_BOUNDS_THRESHOLD * bounds_extents[0]
             || self.style.stroke_width() > IMAGES_STROKE_WIDTH_BOUNDS_THRESHOLD * bounds_extents[1];
 
-        // if these conditions evaluate true the stroke is rendered as a single image
-        let images = if image_size_condition || stroke_width_condition {
+        // if these conditions evaluate true the stroke is rendered as a single image.
+        // Outlined strokes are always rendered as a single image, else the outline of a segment covers the previous one.
+        let images = if image_size_condition || stroke_width_condition || self.outline.is_some() {
             // generate a single image when bounds are smaller than threshold
             match &self.style {
                 Style::Smooth(options) => {
                     let image = render::Image::gen_with_piet(
                         |piet_cx| {
+                            self.draw_outline(&self.path, &self.style, piet_cx);
                             self.path.draw_composed(piet_cx, options);
                             Ok(())
                         },
@@ -87,6 +92,26 @@ impl Content for BrushStroke {
                 Style::Textured(options) => {
                     let image = render::Image::gen_with_piet(
                         |piet_cx| {
+                            self.draw_outline(&self.path, &self.style, piet_cx);
+                            self.path.draw_composed(piet_cx, options);
+                            Ok(())
+                        },
+                        bounds,
+                        image_scale,
+                    );
+
+                    match image {
+                        Ok(image) => vec![image],
+                        Err(e) => {
+                            error!("Generating images for brushstroke failed , Err: {e:?}");
+                            vec![]
+                        }
+                    }
+                }
+                Style::Calligraphy(options) => {
+                    let image = render::Image::gen_with_piet(
+                        |piet_cx| {
+                            self.draw_outline(&self.path, &self.style, piet_cx);
                             self.path.draw_composed(piet_cx, options);
                             Ok(())
                         },
@@ -162,6 +187,32 @@ impl Content for BrushStroke {
                         prev = seg.end();
                     }
 
+                    images
+                }
+                Style::Calligraphy(options) => {
+                    let mut images = Vec::with_capacity(self.path.segments.len());
+
+                    let mut prev = self.path.start;
+                    for seg in self.path.segments.iter() {
+                        let seg_path = PenPath::new_w_segments(prev, [*seg]);
+                        let image = render::Image::gen_with_piet(
+                            |piet_cx| {
+                                seg_path.draw_composed(piet_cx, options);
+                                Ok(())
+                            },
+                            seg_path.composed_bounds(options),
+                            image_scale,
+                        );
+                        match image {
+                            Ok(image) => images.push(image),
+                            Err(e) => {
+                                error!("generating images for brushstroke failed , Err: {e:?}");
+                            }
+                        }
+
+                        prev = seg.end();
+                    }
+
                     images
                 }
             }
@@ -209,6 +260,7 @@ impl Drawable for BrushStroke {
     fn draw(&self, cx: &mut impl piet::RenderContext, _image_scale: f64) -> anyhow::Result<()> {
         cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
 
+        self.draw_outline(&self.path, &self.style, cx);
         match &self.style {
             Style::Smooth(options) => self.path.draw_composed(cx, options),
             Style::Rough(_) => {
@@ -216,6 +268,7 @@ impl Drawable for BrushStroke {
                 unimplemented!()
             }
             Style::Textured(options) => self.path.draw_composed(cx, options),
+            Style::Calligraphy(options) => self.path.draw_composed(cx, options),
         };
 
         cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
@@ -225,11 +278,13 @@ impl Drawable for BrushStroke {
 
 impl Shapeable for BrushStroke {
     fn bounds(&self) -> Aabb {
-        match &self.style {
+        let bounds = match &self.style {
             Style::Smooth(options) => self.path.composed_bounds(options),
             Style::Rough(_options) => unimplemented!(),
             Style::Textured(options) => self.path.composed_bounds(options),
-        }
+            Style::Calligraphy(options) => self.path.composed_bounds(options),
+        };
+        bounds.loosened(self.outline_bounds_margin())
     }
 
     fn hitboxes(&self) -> Vec<Aabb> {
@@ -251,9 +306,13 @@ impl Transformable for BrushStroke {
     fn scale(&mut self, scale: na::Vector2<f64>) {
         self.path.scale(scale);
         // Using the geometric mean behaves the best when scaling non-uniformly.
-        let scale_scalar = (scale[0] * scale[1]).sqrt();
+        // Negative factors mirror the geometry, but must not change the width.
+        let scale_scalar = (scale[0] * scale[1]).abs().sqrt();
         self.style
             .set_stroke_width(self.style.stroke_width() * scale_scalar);
+        if let Some(outline) = &mut self.outline {
+            outline.width *= scale_scalar;
+        }
     }
 }
 
@@ -269,6 +328,7 @@ impl BrushStroke {
         let mut new_brushstroke = Self {
             path,
             style,
+            outline: None,
             hitboxes: vec![],
         };
         new_brushstroke.update_geometry();
@@ -290,6 +350,23 @@ impl BrushStroke {
         self.update_geometry();
     }
 
+    fn outline_bounds_margin(&self) -> f64 {
+        self.outline.map_or(0.0, |outline| outline.bounds_margin())
+    }
+
+    /// Draw the outline of the path with the given style, if the stroke has one.
+    fn draw_outline(&self, path: &PenPath, style: &Style, cx: &mut impl piet::RenderContext) {
+        let Some(outline) = &self.outline else {
+            return;
+        };
+        match outline.outline_style(style) {
+            Style::Smooth(options) => path.draw_composed(cx, &options),
+            Style::Rough(_) => {}
+            Style::Textured(options) => path.draw_composed(cx, &options),
+            Style::Calligraphy(options) => path.draw_composed(cx, &options),
+        }
+    }
+
     // internal method generating the current hitboxes.
     fn gen_hitboxes_int(&self) -> Vec<Aabb> {
         let stroke_width = self.style.stroke_width();
@@ -326,10 +403,13 @@ impl BrushStroke {
 
                 let image = render::Image::gen_with_piet(
                     |piet_cx| {
+                        self.draw_outline(&range_path, &self.style, piet_cx);
                         range_path.draw_composed(piet_cx, options);
                         Ok(())
                     },
-                    range_path.composed_bounds(options),
+                    range_path
+                        .composed_bounds(options)
+                        .loosened(self.outline_bounds_margin()),
                     image_scale,
                 )?;
 
@@ -358,12 +438,47 @@ impl BrushStroke {
                         .copied(),
                 );
 
+                let style = Style::Textured(options.clone());
                 let image = render::Image::gen_with_piet(
                     |piet_cx| {
+                        self.draw_outline(&range_path, &style, piet_cx);
                         range_path.draw_composed(piet_cx, &options);
                         Ok(())
                     },
-                    range_path.composed_bounds(&options),
+                    range_path
+                        .composed_bounds(&options)
+                        .loosened(self.outline_bounds_margin()),
+                    image_scale,
+                )?;
+
+                Some(image)
+            }
+            Style::Calligraphy(options) => {
+                let path_len = self.path.segments.len();
+
+                let start_el = self
+                    .path
+                    .segments
+                    .get(path_len.saturating_sub(n_last_segments).saturating_sub(1))
+                    .map(|s| s.end())
+                    .unwrap_or(self.path.start);
+
+                let range_path = PenPath::new_w_segments(
+                    start_el,
+                    self.path.segments[path_len.saturating_sub(n_last_segments)..]
+                        .iter()
+                        .copied(),
+                );
+
+                let image = render::Image::gen_with_piet(
+                    |piet_cx| {
+                        self.draw_outline(&range_path, &self.style, piet_cx);
+                        range_path.draw_composed(piet_cx, options);
+                        Ok(())
+                    },
+                    range_path
+                        .composed_bounds(options)
+                        .loosened(self.outline_bounds_margin()),
                     image_scale,
                 )?;
 
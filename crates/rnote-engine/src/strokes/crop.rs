@@ -0,0 +1,81 @@
+// Imports
+use p2d::bounding_volume::Aabb;
+use rnote_compose::shapes::Rectangle;
+use rnote_compose::transform::Transform;
+use serde::{Deserialize, Serialize};
+
+/// The visible region of a cropped image stroke.
+///
+/// The corners are relative to the extents of the uncropped image rectangle, in the range 0.0 to 1.0.
+/// This keeps the crop valid when the stroke gets transformed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "image_crop")]
+pub struct ImageCrop {
+    /// The top left corner of the visible region.
+    #[serde(rename = "mins")]
+    pub mins: na::Vector2<f64>,
+    /// The bottom right corner of the visible region.
+    #[serde(rename = "maxs")]
+    pub maxs: na::Vector2<f64>,
+}
+
+impl Default for ImageCrop {
+    fn default() -> Self {
+        Self {
+            mins: na::Vector2::zeros(),
+            maxs: na::Vector2::repeat(1.0),
+        }
+    }
+}
+
+impl ImageCrop {
+    /// The minimum extents of the visible region, relative to the uncropped image.
+    pub const MIN_EXTENTS: f64 = 0.02;
+
+    /// Create the crop from a region in the local coordinates of the image rectangle.
+    ///
+    /// The region is clamped to the image and to the minimum extents.
+    pub fn from_local_aabb(rectangle: &Rectangle, local_aabb: Aabb) -> Self {
+        let image_aabb = rectangle.cuboid.local_aabb();
+        let image_extents = image_aabb.extents();
+        if image_extents[0] <= 0.0 || image_extents[1] <= 0.0 {
+            return Self::default();
+        }
+        let to_relative = |point: na::Point2<f64>| {
+            (point.coords - image_aabb.mins.coords)
+                .component_div(&image_extents)
+                .map(|v| v.clamp(0.0, 1.0))
+        };
+        let mins = to_relative(local_aabb.mins)
+            .zip_map(&na::Vector2::repeat(1.0 - Self::MIN_EXTENTS), f64::min);
+        let maxs = to_relative(local_aabb.maxs)
+            .zip_map(&(mins + na::Vector2::repeat(Self::MIN_EXTENTS)), f64::max);
+        Self { mins, maxs }
+    }
+
+    /// Whether the whole image is visible.
+    pub fn is_uncropped(&self) -> bool {
+        self.mins == na::Vector2::zeros() && self.maxs == na::Vector2::repeat(1.0)
+    }
+
+    /// The visible region in the local coordinates of the image rectangle.
+    pub fn local_aabb(&self, rectangle: &Rectangle) -> Aabb {
+        let image_aabb = rectangle.cuboid.local_aabb();
+        let image_extents = image_aabb.extents();
+        Aabb::new(
+            (image_aabb.mins.coords + self.mins.component_mul(&image_extents)).into(),
+            (image_aabb.mins.coords + self.maxs.component_mul(&image_extents)).into(),
+        )
+    }
+
+    /// The rectangle of the visible region.
+    pub fn cropped_rectangle(&self, rectangle: &Rectangle) -> Rectangle {
+        let local_aabb = self.local_aabb(rectangle);
+        Rectangle {
+            cuboid: p2d::shape::Cuboid::new(local_aabb.half_extents()),
+            transform: Transform::new(
+                rectangle.transform.affine * na::Translation2::from(local_aabb.center().coords),
+            ),
+        }
+    }
+}
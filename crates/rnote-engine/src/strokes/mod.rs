@@ -2,6 +2,9 @@
 pub mod bitmapimage;
 pub mod brushstroke;
 pub mod content;
+pub mod crop;
+pub mod outline;
+pub mod pdftext;
 pub mod resize;
 pub mod shapestroke;
 pub mod stroke;
@@ -12,6 +15,9 @@ pub mod vectorimage;
 pub use bitmapimage::BitmapImage;
 pub use brushstroke::BrushStroke;
 pub use content::Content;
+pub use crop::ImageCrop;
+pub use outline::StrokeOutline;
+pub use pdftext::PdfTextLayer;
 pub use resize::Resize;
 pub use shapestroke::ShapeStroke;
 pub use stroke::Stroke;
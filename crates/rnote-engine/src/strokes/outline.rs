@@ -0,0 +1,48 @@
+// Imports
+use rnote_compose::{Color, Style};
+use serde::{Deserialize, Serialize};
+
+/// An outline in a contrasting color that is drawn around brush and shape strokes.
+///
+/// It is drawn beneath the stroke with the stroke style widened on both sides by the outline width,
+/// so that it follows the shape of the stroke.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "stroke_outline")]
+pub struct StrokeOutline {
+    /// The outline color.
+    #[serde(rename = "color")]
+    pub color: Color,
+    /// The width of the outline on either side of the stroke.
+    #[serde(rename = "width", with = "rnote_compose::serialize::f64_dp3")]
+    pub width: f64,
+}
+
+impl Default for StrokeOutline {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            width: 2.0,
+        }
+    }
+}
+
+impl StrokeOutline {
+    pub const WIDTH_MIN: f64 = 0.1;
+    pub const WIDTH_MAX: f64 = 50.0;
+
+    /// The style that draws the outline beneath a stroke with the given style.
+    pub fn outline_style(&self, style: &Style) -> Style {
+        let mut outline_style = style.clone();
+        outline_style.set_stroke_width(style.stroke_width() + 2.0 * self.width);
+        outline_style.set_stroke_color(self.color);
+        if style.fill_color().is_some() {
+            outline_style.set_fill_color(self.color);
+        }
+        outline_style
+    }
+
+    /// The margin that the bounds of an outlined stroke are extended by.
+    pub fn bounds_margin(&self) -> f64 {
+        self.width
+    }
+}
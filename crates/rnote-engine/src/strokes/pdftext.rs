@@ -0,0 +1,142 @@
+// Imports
+use p2d::bounding_volume::Aabb;
+use rnote_compose::shapes::Rectangle;
+use serde::{Deserialize, Serialize};
+
+/// A character of the text of an imported Pdf page.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename = "pdf_text_char")]
+pub struct PdfTextChar {
+    #[serde(rename = "c")]
+    pub c: char,
+    /// The top left corner of the character bounds.
+    #[serde(rename = "mins", with = "rnote_compose::serialize::na_vector2_f64_dp5")]
+    pub mins: na::Vector2<f64>,
+    /// The bottom right corner of the character bounds.
+    #[serde(rename = "maxs", with = "rnote_compose::serialize::na_vector2_f64_dp5")]
+    pub maxs: na::Vector2<f64>,
+}
+
+/// The text layer of an imported Pdf page, extracted when the page is imported.
+///
+/// The character bounds are relative to the extents of the uncropped image rectangle, in the range 0.0 to 1.0,
+/// in the same way as the image crop.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "pdf_text_layer")]
+pub struct PdfTextLayer {
+    /// The characters in reading order.
+    #[serde(rename = "chars")]
+    pub chars: Vec<PdfTextChar>,
+}
+
+impl PdfTextLayer {
+    /// Extract the text layer of the poppler page.
+    ///
+    /// Returns None when the page has no text.
+    pub fn from_poppler_page(page: &poppler::Page) -> Option<Self> {
+        let (page_width, page_height) = page.size();
+        if page_width <= 0.0 || page_height <= 0.0 {
+            return None;
+        }
+        let text = page.text()?;
+        // Poppler returns one rectangle for every character of the page text
+        let layout = page.text_layout()?;
+        let chars = text
+            .chars()
+            .zip(layout.iter())
+            .map(|(c, rect)| PdfTextChar {
+                c,
+                mins: na::vector![rect.x1() / page_width, rect.y1() / page_height],
+                maxs: na::vector![rect.x2() / page_width, rect.y2() / page_height],
+            })
+            .collect::<Vec<PdfTextChar>>();
+        if chars.iter().all(|c| c.c.is_whitespace()) {
+            return None;
+        }
+        Some(Self { chars })
+    }
+
+    /// Convert a position in document coordinates to a position relative to the image rectangle.
+    pub fn relative_pos(rectangle: &Rectangle, pos: na::Vector2<f64>) -> na::Vector2<f64> {
+        let image_aabb = rectangle.cuboid.local_aabb();
+        let image_extents = image_aabb.extents();
+        let local_pos = rectangle
+            .transform
+            .affine
+            .inverse_transform_point(&pos.into())
+            .coords;
+        (local_pos - image_aabb.mins.coords).component_div(&image_extents)
+    }
+
+    /// The bounds of the character in the local coordinates of the image rectangle.
+    pub fn char_local_aabb(&self, rectangle: &Rectangle, index: usize) -> Option<Aabb> {
+        let c = self.chars.get(index)?;
+        let image_aabb = rectangle.cuboid.local_aabb();
+        let image_extents = image_aabb.extents();
+        Some(Aabb::new(
+            (image_aabb.mins.coords + c.mins.component_mul(&image_extents)).into(),
+            (image_aabb.mins.coords + c.maxs.component_mul(&image_extents)).into(),
+        ))
+    }
+
+    /// The index of the character at, or closest to the relative position.
+    ///
+    /// Returns None when the position is outside of the page.
+    pub fn char_index_at(&self, relative_pos: na::Vector2<f64>) -> Option<usize> {
+        if relative_pos.iter().any(|v| !(0.0..=1.0).contains(v)) {
+            return None;
+        }
+        self.chars
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !c.c.is_control())
+            .map(|(i, c)| {
+                let closest = relative_pos
+                    .zip_zip_map(&c.mins, &c.maxs, |v, min, max| v.clamp(min, max.max(min)));
+                (i, (closest - relative_pos).norm_squared())
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i)
+    }
+
+    /// The text of the characters in the inclusive range between the two indices.
+    pub fn text_between(&self, start: usize, end: usize) -> String {
+        let (start, end) = (start.min(end), start.max(end));
+        self.chars
+            .iter()
+            .skip(start)
+            .take(end + 1 - start)
+            .map(|c| c.c)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::strokes::pdftext::{PdfTextChar, PdfTextLayer};
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn serialized_positions_rounded() {
+        let text_layer = PdfTextLayer {
+            chars: vec![PdfTextChar {
+                c: 'a',
+                mins: na::vector![1.0 / 3.0, 0.123456789],
+                maxs: na::vector![2.0 / 3.0, 0.5],
+            }],
+        };
+
+        let json = serde_json::to_string(&text_layer).unwrap();
+        assert!(json.contains("0.33333"));
+        assert!(!json.contains("0.333333"));
+        assert!(json.contains("0.12346"));
+
+        let loaded = serde_json::from_str::<PdfTextLayer>(&json).unwrap();
+        assert_eq!(loaded.chars.len(), 1);
+        assert_eq!(loaded.chars[0].c, 'a');
+        assert_relative_eq!(loaded.chars[0].mins, na::vector![0.33333, 0.12346]);
+        assert_relative_eq!(loaded.chars[0].maxs, na::vector![0.66667, 0.5]);
+        // Rounding again does not change the positions
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+    }
+}
@@ -1,5 +1,5 @@
 // Imports
-use super::Content;
+use super::{Content, StrokeOutline};
 use crate::{strokes::content, Drawable};
 use p2d::bounding_volume::{Aabb, BoundingVolume};
 use rnote_compose::ext::AabbExt;
@@ -17,6 +17,9 @@ pub struct ShapeStroke {
     pub shape: Shape,
     #[serde(rename = "style")]
     pub style: Style,
+    /// The outline drawn beneath the stroke.
+    #[serde(rename = "outline")]
+    pub outline: Option<StrokeOutline>,
     #[serde(skip)]
     // since the shape can have many hitboxes, we store them and update them when the stroke geometry changes
     hitboxes: Vec<Aabb>,
@@ -59,6 +62,10 @@ impl Drawable for ShapeStroke {
     fn draw(&self, cx: &mut impl piet::RenderContext, _image_scale: f64) -> anyhow::Result<()> {
         cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
 
+        if let Some(outline) = &self.outline {
+            self.shape
+                .draw_composed(cx, &outline.outline_style(&self.style));
+        }
         self.shape.draw_composed(cx, &self.style);
 
         cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
@@ -68,11 +75,12 @@ impl Drawable for ShapeStroke {
 
 impl Shapeable for ShapeStroke {
     fn bounds(&self) -> Aabb {
-        match &self.style {
+        let bounds = match &self.style {
             Style::Smooth(options) => self.shape.composed_bounds(options),
             Style::Rough(options) => self.shape.composed_bounds(options),
-            Style::Textured(_) => self.shape.bounds(),
-        }
+            Style::Textured(_) | Style::Calligraphy(_) => self.shape.bounds(),
+        };
+        bounds.loosened(self.outline.map_or(0.0, |outline| outline.bounds_margin()))
     }
 
     fn hitboxes(&self) -> Vec<Aabb> {
@@ -94,9 +102,13 @@ impl Transformable for ShapeStroke {
     fn scale(&mut self, scale: na::Vector2<f64>) {
         self.shape.scale(scale);
         // Using the geometric mean behaves the best when scaling non-uniformly.
-        let scale_scalar = (scale[0] * scale[1]).sqrt();
+        // Negative factors mirror the geometry, but must not change the width.
+        let scale_scalar = (scale[0] * scale[1]).abs().sqrt();
         self.style
             .set_stroke_width(self.style.stroke_width() * scale_scalar);
+        if let Some(outline) = &mut self.outline {
+            outline.width *= scale_scalar;
+        }
     }
 }
 
@@ -105,6 +117,7 @@ impl ShapeStroke {
         let mut shapestroke = Self {
             shape,
             style,
+            outline: None,
             hitboxes: vec![],
         };
         shapestroke.update_geometry();
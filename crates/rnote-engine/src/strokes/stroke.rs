@@ -3,8 +3,9 @@ use super::bitmapimage::BitmapImage;
 use super::brushstroke::BrushStroke;
 use super::content::GeneratedContentImages;
 use super::shapestroke::ShapeStroke;
+use super::textstroke::{FontStyle, TextStyle};
 use super::vectorimage::VectorImage;
-use super::{Content, TextStroke};
+use super::{Content, ImageCrop, TextStroke};
 use crate::fileformats::xoppformat::{self, XoppColor};
 use crate::store::chrono_comp::StrokeLayer;
 use crate::{render, Engine};
@@ -12,7 +13,7 @@ use crate::{utils, Drawable};
 use p2d::bounding_volume::Aabb;
 use rnote_compose::ext::AabbExt;
 use rnote_compose::penpath::Element;
-use rnote_compose::shapes::{Rectangle, Shapeable};
+use rnote_compose::shapes::{BezierPath, Rectangle, Shape, Shapeable};
 use rnote_compose::style::smooth::SmoothOptions;
 use rnote_compose::transform::Transform;
 use rnote_compose::transform::Transformable;
@@ -301,6 +302,150 @@ impl Stroke {
         }
     }
 
+    /// Whether both strokes were created with the same kind of pen, for brush and shape strokes also in the same style.
+    pub fn same_pen_kind(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Stroke::BrushStroke(brush_stroke), Stroke::BrushStroke(other)) => {
+                std::mem::discriminant(&brush_stroke.style) == std::mem::discriminant(&other.style)
+            }
+            (Stroke::ShapeStroke(shape_stroke), Stroke::ShapeStroke(other)) => {
+                std::mem::discriminant(&shape_stroke.style) == std::mem::discriminant(&other.style)
+            }
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+
+    /// The stroke, fill and text colors of the stroke.
+    pub fn colors(&self) -> Vec<Color> {
+        match self {
+            Stroke::BrushStroke(brush_stroke) => brush_stroke
+                .style
+                .stroke_color()
+                .into_iter()
+                .chain(brush_stroke.style.fill_color())
+                .collect(),
+            Stroke::ShapeStroke(shape_stroke) => shape_stroke
+                .style
+                .stroke_color()
+                .into_iter()
+                .chain(shape_stroke.style.fill_color())
+                .collect(),
+            Stroke::TextStroke(text_stroke) => vec![text_stroke.text_style.color],
+            Stroke::VectorImage(_) | Stroke::BitmapImage(_) => vec![],
+        }
+    }
+
+    /// Replace all colors of the stroke that match `from` with `to`.
+    ///
+    /// Returns true if the stroke was modified and needs to update its rendering.
+    pub fn remap_color(&mut self, from: Color, to: Color) -> bool {
+        self.map_colors(|color| if color.approx_eq_f32(from) { to } else { color })
+    }
+
+    /// Replace the stroke, fill and text colors of the stroke with the mapped ones.
+    ///
+    /// Every color is mapped once, so mapping is independent of the order of the colors.
+    ///
+    /// Returns true if the stroke was modified and needs to update its rendering.
+    pub fn map_colors(&mut self, map: impl Fn(Color) -> Color) -> bool {
+        let mapped = |color: Option<Color>| {
+            color
+                .map(|c| (c, map(c)))
+                .filter(|(c, mapped)| !mapped.approx_eq_f32(*c))
+                .map(|(_, mapped)| mapped)
+        };
+
+        match self {
+            Stroke::BrushStroke(brush_stroke) => {
+                let mut modified = false;
+                if let Some(color) = mapped(brush_stroke.style.stroke_color()) {
+                    brush_stroke.style.set_stroke_color(color);
+                    modified = true;
+                }
+                if let Some(color) = mapped(brush_stroke.style.fill_color()) {
+                    brush_stroke.style.set_fill_color(color);
+                    modified = true;
+                }
+                modified
+            }
+            Stroke::ShapeStroke(shape_stroke) => {
+                let mut modified = false;
+                if let Some(color) = mapped(shape_stroke.style.stroke_color()) {
+                    shape_stroke.style.set_stroke_color(color);
+                    modified = true;
+                }
+                if let Some(color) = mapped(shape_stroke.style.fill_color()) {
+                    shape_stroke.style.set_fill_color(color);
+                    modified = true;
+                }
+                modified
+            }
+            Stroke::TextStroke(text_stroke) => {
+                if let Some(color) = mapped(Some(text_stroke.text_style.color)) {
+                    text_stroke.text_style.color = color;
+                    true
+                } else {
+                    false
+                }
+            }
+            Stroke::VectorImage(_) => false,
+            Stroke::BitmapImage(_) => false,
+        }
+    }
+
+    /// The uncropped image rectangle and the current crop, if the stroke is an image.
+    pub fn image_crop(&self) -> Option<(Rectangle, Option<ImageCrop>)> {
+        match self {
+            Stroke::VectorImage(vectorimage) => Some((vectorimage.rectangle, vectorimage.crop)),
+            Stroke::BitmapImage(bitmapimage) => Some((bitmapimage.rectangle, bitmapimage.crop)),
+            Stroke::BrushStroke(_) | Stroke::ShapeStroke(_) | Stroke::TextStroke(_) => None,
+        }
+    }
+
+    /// Set the crop of an image stroke. A crop covering the whole image resets it.
+    ///
+    /// Returns true if the stroke was modified and needs to update its rendering.
+    pub fn set_image_crop(&mut self, crop: Option<ImageCrop>) -> bool {
+        let crop = crop.filter(|crop| !crop.is_uncropped());
+        match self {
+            Stroke::VectorImage(vectorimage) => {
+                let modified = vectorimage.crop != crop;
+                vectorimage.crop = crop;
+                modified
+            }
+            Stroke::BitmapImage(bitmapimage) => {
+                let modified = bitmapimage.crop != crop;
+                bitmapimage.crop = crop;
+                modified
+            }
+            Stroke::BrushStroke(_) | Stroke::ShapeStroke(_) | Stroke::TextStroke(_) => false,
+        }
+    }
+
+    /// The bezier path, if the stroke is a shape stroke with a bezier path shape.
+    pub fn bezier_path(&self) -> Option<&BezierPath> {
+        match self {
+            Stroke::ShapeStroke(shapestroke) => match &shapestroke.shape {
+                Shape::BezierPath(bezier_path) => Some(bezier_path),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Move a node of the bezier path of a shape stroke to a new position.
+    ///
+    /// Returns true if the stroke was modified and needs to update its geometry and rendering.
+    pub fn move_bezier_path_node(&mut self, index: usize, pos: na::Vector2<f64>) -> bool {
+        match self {
+            Stroke::ShapeStroke(shapestroke) => match &mut shapestroke.shape {
+                Shape::BezierPath(bezier_path) => bezier_path.move_node(index, pos),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
     pub fn from_xoppstroke(
         stroke: xoppformat::XoppStroke,
         offset: na::Vector2<f64>,
@@ -382,6 +527,56 @@ impl Stroke {
         Ok((Stroke::BrushStroke(brushstroke), layer))
     }
 
+    pub fn from_xopptext(
+        xopp_text: xoppformat::XoppText,
+        offset: na::Vector2<f64>,
+        target_dpi: f64,
+    ) -> Result<Self, anyhow::Error> {
+        if xopp_text.text.is_empty() {
+            return Err(anyhow::anyhow!("XoppText has empty text."));
+        }
+        let upper_left_pos = na::vector![
+            crate::utils::convert_value_dpi(xopp_text.x, xoppformat::XoppFile::DPI, target_dpi),
+            crate::utils::convert_value_dpi(xopp_text.y, xoppformat::XoppFile::DPI, target_dpi)
+        ] + offset;
+
+        // The Xopp font is a font description without size, e.g. "Sans Bold Italic".
+        // The trailing style and weight keywords are stripped from the family name.
+        let mut font_words = xopp_text.font.split_whitespace().collect::<Vec<&str>>();
+        let mut font_weight = TextStyle::FONT_WEIGHT_DEFAULT;
+        let mut font_style = FontStyle::Regular;
+        while let Some(&last) = font_words.last() {
+            match last.to_lowercase().as_str() {
+                "bold" => font_weight = 700,
+                "semi-bold" | "semibold" => font_weight = 600,
+                "light" => font_weight = 300,
+                "italic" | "oblique" => font_style = FontStyle::Italic,
+                "regular" | "normal" | "book" => {}
+                _ => break,
+            }
+            font_words.pop();
+        }
+        let font_family = if font_words.is_empty() {
+            String::from(TextStyle::FONT_FAMILY_DEFAULT)
+        } else {
+            font_words.join(" ")
+        };
+
+        let mut text_style = TextStyle::default();
+        text_style.font_family = font_family;
+        text_style.font_size =
+            crate::utils::convert_value_dpi(xopp_text.size, xoppformat::XoppFile::DPI, target_dpi);
+        text_style.font_weight = font_weight;
+        text_style.font_style = font_style;
+        text_style.color = crate::utils::color_from_xopp(xopp_text.color);
+
+        Ok(Stroke::TextStroke(TextStroke::new(
+            xopp_text.text,
+            upper_left_pos,
+            text_style,
+        )))
+    }
+
     pub fn from_xoppimage(
         xopp_image: xoppformat::XoppImage,
         offset: na::Vector2<f64>,
@@ -424,7 +619,12 @@ impl Stroke {
         };
         let image = render::Image::try_from_encoded_bytes(&bytes)?;
 
-        Ok(Stroke::BitmapImage(BitmapImage { image, rectangle }))
+        Ok(Stroke::BitmapImage(BitmapImage {
+            image,
+            rectangle,
+            crop: None,
+            pdf_text: None,
+        }))
     }
 
     pub fn into_xopp(self, current_dpi: f64) -> Option<xoppformat::XoppStrokeType> {
@@ -444,6 +644,10 @@ impl Stroke {
                         options.stroke_width,
                         crate::utils::xoppcolor_from_color(options.stroke_color?),
                     ),
+                    Style::Calligraphy(options) => (
+                        options.stroke_width,
+                        crate::utils::xoppcolor_from_color(options.stroke_color?),
+                    ),
                 };
 
                 let tool = xoppformat::XoppTool::Pen;
@@ -463,6 +667,8 @@ impl Stroke {
                             options.pressure_curve.apply(stroke_width, element.pressure)
                         }
                         Style::Rough(_) | Style::Textured(_) => stroke_width * element.pressure,
+                        // The calligraphy width depends on the direction, not the pressure
+                        Style::Calligraphy(_) => stroke_width,
                     })
                     .collect();
                 width_vec.append(&mut pressures);
@@ -538,8 +744,48 @@ impl Stroke {
                 ))
             }
             Stroke::TextStroke(textstroke) => {
-                // Xournal++ text strokes do not support affine transformations, so we have to convert on best effort here.
-                // The best solution for now seems to be to export them as a bitmap image.
+                // Xournal++ texts can only be positioned and have a single style,
+                // so only plain text strokes are exported as texts.
+                let linear = textstroke
+                    .transform
+                    .affine
+                    .matrix()
+                    .fixed_view::<2, 2>(0, 0);
+                if linear.relative_eq(&na::Matrix2::identity(), 1e-6, 1e-6)
+                    && textstroke.text_style.ranged_text_attributes.is_empty()
+                    && textstroke.text_style.max_width().is_none()
+                {
+                    let pos = utils::convert_coord_dpi(
+                        textstroke.transform.translation_part(),
+                        current_dpi,
+                        xoppformat::XoppFile::DPI,
+                    );
+                    let mut font = textstroke.text_style.font_family.clone();
+                    match textstroke.text_style.font_weight {
+                        0..=349 => font.push_str(" Light"),
+                        550..=649 => font.push_str(" Semi-Bold"),
+                        650.. => font.push_str(" Bold"),
+                        _ => {}
+                    }
+                    if matches!(textstroke.text_style.font_style, FontStyle::Italic) {
+                        font.push_str(" Italic");
+                    }
+
+                    return Some(xoppformat::XoppStrokeType::XoppText(xoppformat::XoppText {
+                        font,
+                        size: utils::convert_value_dpi(
+                            textstroke.text_style.font_size,
+                            current_dpi,
+                            xoppformat::XoppFile::DPI,
+                        ),
+                        x: pos[0],
+                        y: pos[1],
+                        color: crate::utils::xoppcolor_from_color(textstroke.text_style.color),
+                        text: textstroke.text,
+                    }));
+                }
+
+                // Transformed or styled text strokes are exported as bitmap images on best effort.
                 let png_data = match textstroke.export_to_bitmap_image_bytes(
                     image::ImageFormat::Png,
                     Engine::STROKE_EXPORT_IMAGE_SCALE,
@@ -14,7 +14,7 @@ use std::ops::Range;
 use tracing::error;
 use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename = "font_style")]
 pub enum FontStyle {
     #[serde(rename = "regular")]
@@ -47,7 +47,7 @@ impl From<FontStyle> for piet::FontStyle {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename = "text_style")]
 pub enum TextAlignment {
     #[serde(rename = "start")]
@@ -71,6 +71,24 @@ impl From<piet::TextAlignment> for TextAlignment {
     }
 }
 
+/// The vertical alignment of the text inside its text box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "text_vertical_alignment")]
+pub enum TextVerticalAlignment {
+    #[serde(rename = "top")]
+    Top,
+    #[serde(rename = "center")]
+    Center,
+    #[serde(rename = "bottom")]
+    Bottom,
+}
+
+impl Default for TextVerticalAlignment {
+    fn default() -> Self {
+        Self::Top
+    }
+}
+
 impl From<TextAlignment> for piet::TextAlignment {
     fn from(value: TextAlignment) -> Self {
         match value {
@@ -82,7 +100,7 @@ impl From<TextAlignment> for piet::TextAlignment {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename = "text_attribute")]
 pub enum TextAttribute {
     /// The font family.
@@ -169,7 +187,7 @@ impl TextAttribute {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename = "ranged_text_attribute")]
 pub struct RangedTextAttribute {
     #[serde(rename = "range")]
@@ -178,7 +196,7 @@ pub struct RangedTextAttribute {
     pub attribute: TextAttribute,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default, rename = "text_style")]
 pub struct TextStyle {
     #[serde(rename = "font_family")]
@@ -195,6 +213,24 @@ pub struct TextStyle {
     max_width: Option<f64>,
     #[serde(rename = "alignment")]
     pub alignment: TextAlignment,
+    /// The vertical alignment of the text when the text box is higher than the text.
+    #[serde(rename = "vertical_alignment")]
+    pub vertical_alignment: TextVerticalAlignment,
+    /// The explicit height of the text box. Grows with the text if it is taller.
+    #[serde(rename = "box_height")]
+    box_height: Option<f64>,
+    /// The padding between the text and the text box edges.
+    #[serde(rename = "padding")]
+    padding: f64,
+    /// The fill color of the text box background.
+    #[serde(rename = "background_color")]
+    pub background_color: Option<Color>,
+    /// The distance of the baseline to the path, when the text flows along a path.
+    #[serde(rename = "path_offset")]
+    path_offset: f64,
+    /// The additional spacing between the graphemes, when the text flows along a path.
+    #[serde(rename = "path_spacing")]
+    path_spacing: f64,
 
     #[serde(rename = "ranged_text_attributes")]
     pub ranged_text_attributes: Vec<RangedTextAttribute>,
@@ -210,6 +246,12 @@ impl Default for TextStyle {
             color: Self::FONT_COLOR_DEFAULT,
             max_width: None,
             alignment: TextAlignment::Start,
+            vertical_alignment: TextVerticalAlignment::default(),
+            box_height: None,
+            padding: 0.0,
+            background_color: None,
+            path_offset: 0.0,
+            path_spacing: 0.0,
             ranged_text_attributes: vec![],
         }
     }
@@ -222,6 +264,10 @@ impl TextStyle {
     pub const FONT_SIZE_MAX: f64 = 512.0;
     pub const FONT_WEIGHT_DEFAULT: u16 = 500;
     pub const FONT_COLOR_DEFAULT: Color = Color::BLACK;
+    pub const PADDING_MAX: f64 = 256.0;
+    pub const BOX_HEIGHT_MAX: f64 = 4096.0;
+    pub const PATH_OFFSET_MAX: f64 = 512.0;
+    pub const PATH_SPACING_MAX: f64 = 256.0;
 
     pub fn max_width(&self) -> Option<f64> {
         self.max_width
@@ -231,6 +277,72 @@ impl TextStyle {
         self.max_width = max_width.map(|w| w.max(0.));
     }
 
+    pub fn box_height(&self) -> Option<f64> {
+        self.box_height
+    }
+
+    pub fn set_box_height(&mut self, box_height: Option<f64>) {
+        self.box_height = box_height.map(|h| h.clamp(0., Self::BOX_HEIGHT_MAX));
+    }
+
+    pub fn padding(&self) -> f64 {
+        self.padding
+    }
+
+    pub fn set_padding(&mut self, padding: f64) {
+        self.padding = padding.clamp(0., Self::PADDING_MAX);
+    }
+
+    pub fn path_offset(&self) -> f64 {
+        self.path_offset
+    }
+
+    pub fn set_path_offset(&mut self, path_offset: f64) {
+        self.path_offset = path_offset.clamp(-Self::PATH_OFFSET_MAX, Self::PATH_OFFSET_MAX);
+    }
+
+    pub fn path_spacing(&self) -> f64 {
+        self.path_spacing
+    }
+
+    pub fn set_path_spacing(&mut self, path_spacing: f64) {
+        self.path_spacing = path_spacing.clamp(0., Self::PATH_SPACING_MAX);
+    }
+
+    /// The size of the text box enclosing a text layout with the given size.
+    pub fn box_size(&self, layout_size: na::Vector2<f64>) -> na::Vector2<f64> {
+        let padded_size = layout_size + na::Vector2::repeat(2.0 * self.padding);
+        na::vector![
+            padded_size[0],
+            padded_size[1].max(self.box_height.unwrap_or(0.0))
+        ]
+    }
+
+    /// The offset of a text layout with the given size inside its text box.
+    pub fn text_offset(&self, layout_size: na::Vector2<f64>) -> na::Vector2<f64> {
+        let free_height = self.box_size(layout_size)[1] - layout_size[1] - 2.0 * self.padding;
+        let y_offset = match self.vertical_alignment {
+            TextVerticalAlignment::Top => 0.0,
+            TextVerticalAlignment::Center => free_height * 0.5,
+            TextVerticalAlignment::Bottom => free_height,
+        };
+        na::vector![self.padding, self.padding + y_offset]
+    }
+
+    /// The offset of the text layout inside its text box, built for the given text.
+    fn text_offset_for_text<T>(&self, piet_text: &mut T, text: String) -> na::Vector2<f64>
+    where
+        T: piet::Text,
+    {
+        match self.build_text_layout(piet_text, text) {
+            Ok(text_layout) => {
+                let size = text_layout.size();
+                self.text_offset(na::vector![size.width, size.height])
+            }
+            Err(_) => na::vector![self.padding, self.padding],
+        }
+    }
+
     pub fn build_text_layout<T>(
         &self,
         piet_text: &mut T,
@@ -283,6 +395,42 @@ impl TextStyle {
             .map_err(|e| anyhow::anyhow!("Building piet text layout failed, Err: {e:?}"))
     }
 
+    /// Builds the layout for text flowing along a path.
+    ///
+    /// It consists of a single line, so line breaks and the max width are ignored.
+    pub fn build_single_line_text_layout<T>(
+        &self,
+        piet_text: &mut T,
+        text: String,
+    ) -> anyhow::Result<T::TextLayout>
+    where
+        T: piet::Text,
+    {
+        let mut text_style = self.clone();
+        text_style.max_width = None;
+        // Replacing with a single byte char keeps the ranged attributes valid
+        text_style.build_text_layout(piet_text, text.replace(['\n', '\r'], " "))
+    }
+
+    /// The text style for the text in the given byte range, with the ranged attributes restricted and translated to it.
+    fn restricted_to_range(&self, range: &Range<usize>) -> Self {
+        let mut text_style = self.clone();
+        text_style.ranged_text_attributes = self
+            .ranged_text_attributes
+            .iter()
+            .filter_map(|attr| {
+                let start = attr.range.start.max(range.start);
+                let end = attr.range.end.min(range.end);
+                (start < end).then(|| RangedTextAttribute {
+                    range: start - range.start..end - range.start,
+                    attribute: attr.attribute.clone(),
+                })
+            })
+            .collect();
+        text_style
+    }
+
+    /// The untransformed size of the text box.
     pub fn untransformed_size<T>(&self, piet_text: &mut T, text: String) -> Option<na::Vector2<f64>>
     where
         T: piet::Text,
@@ -290,7 +438,7 @@ impl TextStyle {
         let text_layout = self.build_text_layout(piet_text, text).ok()?;
 
         let size = text_layout.size();
-        Some(na::vector![size.width, size.height])
+        Some(self.box_size(na::vector![size.width, size.height]))
     }
 
     /// The cursors line metric relative to the textstroke bounds.
@@ -365,9 +513,7 @@ impl TextStyle {
         transform: &Transform,
         camera: &Camera,
     ) -> anyhow::Result<()> {
-        const CURSOR_COLOR: piet::Color = color::GNOME_DARKS[2];
-        const CURSOR_OUTLINE_COLOR: piet::Color = color::GNOME_BRIGHTS[0];
-        let text_cursor_width = 2.0 / camera.total_zoom();
+        let text_offset = self.text_offset_for_text(cx.text(), text.clone());
 
         if let Ok(cursor_line_metric) =
             self.cursor_line_metric(cx.text(), text.clone(), cursor.cur_cursor())
@@ -378,6 +524,7 @@ impl TextStyle {
                 .x;
 
             let text_cursor = transform.to_kurbo()
+                * kurbo::Affine::translate(text_offset.to_kurbo_vec())
                 * kurbo::Line::new(
                     kurbo::Point::new(x_pos, cursor_line_metric.y_offset),
                     kurbo::Point::new(
@@ -386,18 +533,7 @@ impl TextStyle {
                     ),
                 );
 
-            cx.stroke_styled(
-                text_cursor,
-                &CURSOR_OUTLINE_COLOR,
-                text_cursor_width,
-                &piet::StrokeStyle::default().line_cap(piet::LineCap::Butt),
-            );
-            cx.stroke_styled(
-                text_cursor,
-                &CURSOR_COLOR,
-                text_cursor_width * 0.8,
-                &piet::StrokeStyle::default().line_cap(piet::LineCap::Butt),
-            );
+            draw_text_cursor_line(cx, text_cursor, camera);
         }
 
         Ok(())
@@ -412,23 +548,223 @@ impl TextStyle {
         transform: &Transform,
         camera: &Camera,
     ) {
-        const OUTLINE_COLOR: piet::Color = color::GNOME_BLUES[2];
-        const FILL_COLOR: piet::Color = color::GNOME_BLUES[1].with_a8(25);
-        let outline_width = 1.5 / camera.total_zoom();
+        let text_offset = self.text_offset_for_text(cx.text(), text.clone());
 
         if let Ok(selection_rects) =
             self.get_selection_rects_for_cursors(text, cursor, selection_cursor)
         {
             for selection_rect in selection_rects {
-                let outline = transform.to_kurbo() * selection_rect.to_path(0.5);
+                let outline = transform.to_kurbo()
+                    * kurbo::Affine::translate(text_offset.to_kurbo_vec())
+                    * selection_rect.to_path(0.5);
 
-                cx.fill(&outline, &FILL_COLOR);
-                cx.stroke(&outline, &OUTLINE_COLOR, outline_width);
+                draw_text_selection_outline(cx, &outline, camera);
             }
         }
     }
 }
 
+fn draw_text_cursor_line(
+    cx: &mut impl piet::RenderContext,
+    text_cursor: kurbo::Line,
+    camera: &Camera,
+) {
+    const CURSOR_COLOR: piet::Color = color::GNOME_DARKS[2];
+    const CURSOR_OUTLINE_COLOR: piet::Color = color::GNOME_BRIGHTS[0];
+    let text_cursor_width = 2.0 / camera.total_zoom();
+
+    cx.stroke_styled(
+        text_cursor,
+        &CURSOR_OUTLINE_COLOR,
+        text_cursor_width,
+        &piet::StrokeStyle::default().line_cap(piet::LineCap::Butt),
+    );
+    cx.stroke_styled(
+        text_cursor,
+        &CURSOR_COLOR,
+        text_cursor_width * 0.8,
+        &piet::StrokeStyle::default().line_cap(piet::LineCap::Butt),
+    );
+}
+
+fn draw_text_selection_outline(
+    cx: &mut impl piet::RenderContext,
+    outline: &kurbo::BezPath,
+    camera: &Camera,
+) {
+    const OUTLINE_COLOR: piet::Color = color::GNOME_BLUES[2];
+    const FILL_COLOR: piet::Color = color::GNOME_BLUES[1].with_a8(25);
+    let outline_width = 1.5 / camera.total_zoom();
+
+    cx.fill(outline, &FILL_COLOR);
+    cx.stroke(outline, &OUTLINE_COLOR, outline_width);
+}
+
+/// A path that the text of a text stroke flows along.
+///
+/// The points are in the untransformed coordinate space of the text stroke.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename = "text_path")]
+pub struct TextPath {
+    #[serde(rename = "points")]
+    points: Vec<na::Vector2<f64>>,
+}
+
+impl TextPath {
+    const FLATTEN_TOLERANCE: f64 = 0.25;
+
+    /// Flattens the first subpath of the given path.
+    ///
+    /// Returns None if it is too short to be followed.
+    pub fn from_bezpath(bez_path: &kurbo::BezPath) -> Option<Self> {
+        let mut points: Vec<na::Vector2<f64>> = vec![];
+        let mut subpath_ended = false;
+
+        kurbo::flatten(bez_path.iter(), Self::FLATTEN_TOLERANCE, |el| {
+            if subpath_ended {
+                return;
+            }
+            match el {
+                kurbo::PathEl::MoveTo(p) => {
+                    if points.is_empty() {
+                        points.push(na::Vector2::from_kurbo_point(p));
+                    } else {
+                        subpath_ended = true;
+                    }
+                }
+                kurbo::PathEl::LineTo(p) => points.push(na::Vector2::from_kurbo_point(p)),
+                kurbo::PathEl::ClosePath => {
+                    if let Some(first) = points.first().copied() {
+                        points.push(first);
+                    }
+                    subpath_ended = true;
+                }
+                _ => {}
+            }
+        });
+        points.dedup();
+
+        (points.len() >= 2).then_some(Self { points })
+    }
+
+    pub fn start(&self) -> Option<na::Vector2<f64>> {
+        self.points.first().copied()
+    }
+
+    pub fn translate(&mut self, offset: na::Vector2<f64>) {
+        for p in self.points.iter_mut() {
+            *p += offset;
+        }
+    }
+
+    /// The position and the tangent angle at the given distance along the path.
+    ///
+    /// Before the start and past the end the path is extended along its first and last segment.
+    fn sample(&self, distance: f64) -> (na::Vector2<f64>, f64) {
+        let mut remaining = distance;
+        let mut last_segment = None;
+
+        for (start, end) in self.points.iter().tuple_windows() {
+            let direction = end - start;
+            let length = direction.magnitude();
+            if length <= 0.0 {
+                continue;
+            }
+            let unit_direction = direction / length;
+            if remaining <= length {
+                return (
+                    start + unit_direction * remaining,
+                    unit_direction[1].atan2(unit_direction[0]),
+                );
+            }
+            remaining -= length;
+            last_segment = Some((*end, unit_direction));
+        }
+
+        match last_segment {
+            Some((end, unit_direction)) => (
+                end + unit_direction * remaining,
+                unit_direction[1].atan2(unit_direction[0]),
+            ),
+            None => (self.start().unwrap_or_default(), 0.0),
+        }
+    }
+
+    /// The transform that places the point `anchor` of a single line text layout onto the path, at the given
+    /// distance along it and moved away from it by `offset`.
+    ///
+    /// The layout gets rotated to follow the tangent of the path.
+    fn placement(&self, distance: f64, anchor: kurbo::Point, offset: f64) -> kurbo::Affine {
+        let (pos, angle) = self.sample(distance);
+        let normal = na::vector![angle.sin(), -angle.cos()];
+
+        kurbo::Affine::translate((pos + normal * offset).to_kurbo_vec())
+            * kurbo::Affine::rotate(angle)
+            * kurbo::Affine::translate(-anchor.to_vec2())
+    }
+}
+
+/// The placement of a single grapheme of text that flows along a path.
+#[derive(Debug, Clone)]
+struct GlyphPlacement {
+    /// The byte range of the grapheme in the text.
+    range: Range<usize>,
+    /// The rect of the grapheme in the single line text layout.
+    rect: kurbo::Rect,
+    /// Transforms the single line text layout so that the grapheme is placed on the path.
+    affine: kurbo::Affine,
+    whitespace: bool,
+}
+
+impl GlyphPlacement {
+    fn bounds(&self) -> Aabb {
+        Aabb::from_kurbo_rect((self.affine * self.rect.to_path(0.1)).bounding_box())
+    }
+}
+
+/// Places every grapheme of the text along the path.
+///
+/// The text layout must be built with `TextStyle::build_single_line_text_layout()`.
+fn glyph_placements(
+    text: &str,
+    text_layout: &impl TextLayout,
+    text_style: &TextStyle,
+    path: &TextPath,
+) -> Vec<GlyphPlacement> {
+    let Some(line_metric) = text_layout.line_metric(0) else {
+        return vec![];
+    };
+    let baseline = line_metric.y_offset + line_metric.baseline;
+
+    text.grapheme_indices(true)
+        .enumerate()
+        .map(|(i, (start, grapheme))| {
+            let range = start..start + grapheme.len();
+            let x_start = text_layout.hit_test_text_position(range.start).point.x;
+            let x_end = text_layout.hit_test_text_position(range.end).point.x;
+            let rect = kurbo::Rect::new(
+                x_start.min(x_end),
+                line_metric.y_offset,
+                x_start.max(x_end),
+                line_metric.y_offset + line_metric.height,
+            );
+            let center_x = rect.center().x;
+            let distance = center_x + i as f64 * text_style.path_spacing;
+
+            GlyphPlacement {
+                range,
+                rect,
+                affine: path.placement(
+                    distance,
+                    kurbo::Point::new(center_x, baseline),
+                    text_style.path_offset,
+                ),
+                whitespace: grapheme.trim().is_empty(),
+            }
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default, rename = "textstroke")]
 pub struct TextStroke {
@@ -441,6 +777,11 @@ pub struct TextStroke {
     pub transform: Transform,
     #[serde(rename = "text_style")]
     pub text_style: TextStyle,
+    /// The path the text flows along instead of being laid out in a text box.
+    #[serde(rename = "path")]
+    pub path: Option<TextPath>,
+    #[serde(skip)]
+    layout_cache: Option<TextLayoutCache>,
 }
 
 impl Default for TextStroke {
@@ -449,7 +790,112 @@ impl Default for TextStroke {
             text: String::default(),
             transform: Transform::default(),
             text_style: TextStyle::default(),
+            path: None,
+            layout_cache: None,
+        }
+    }
+}
+
+/// The metrics of the text layout, cached on the stroke so that bounds and hitboxes queries
+/// don't need to build a new layout every time.
+///
+/// Only valid as long as the text, the text style and the path match the ones it was generated for.
+#[derive(Debug, Clone)]
+struct TextLayoutCache {
+    text: String,
+    text_style: TextStyle,
+    path: Option<TextPath>,
+    /// The untransformed bounds of the text box.
+    bounds: Aabb,
+    /// The untransformed hitboxes of the text box.
+    hitboxes: Vec<Aabb>,
+}
+
+impl TextLayoutCache {
+    fn generate(
+        text: &str,
+        text_style: &TextStyle,
+        path: Option<&TextPath>,
+    ) -> anyhow::Result<Self> {
+        if let Some(path) = path {
+            return Self::generate_along_path(text, text_style, path);
+        }
+        let text_layout =
+            text_style.build_text_layout(&mut piet_cairo::CairoText::new(), text.to_string())?;
+        let text_size = text_layout.size();
+        let layout_size = na::vector![text_size.width, text_size.height];
+        let size = text_style.box_size(layout_size);
+        let text_offset = text_style.text_offset(layout_size);
+
+        // A filled background makes the entire text box hit-testable
+        let mut hitboxes: Vec<Aabb> = if text_style.background_color.is_some() {
+            vec![]
+        } else {
+            text_layout
+                .rects_for_range(0..text.len())
+                .into_iter()
+                .map(|rect| Aabb::from_kurbo_rect(rect).translate(text_offset))
+                .collect()
+        };
+        if hitboxes.is_empty() {
+            hitboxes.push(Aabb::new_positive(
+                na::point![0.0, 0.0],
+                size.maxs(&na::vector![1.0, 1.0]).into(),
+            ))
+        }
+
+        Ok(Self {
+            text: text.to_string(),
+            text_style: text_style.clone(),
+            path: None,
+            bounds: Aabb::new(
+                na::point![0.0, 0.0],
+                size.maxs(&na::vector![1.0, 1.0]).into(),
+            ),
+            hitboxes,
+        })
+    }
+
+    fn generate_along_path(
+        text: &str,
+        text_style: &TextStyle,
+        path: &TextPath,
+    ) -> anyhow::Result<Self> {
+        let text_layout = text_style
+            .build_single_line_text_layout(&mut piet_cairo::CairoText::new(), text.to_string())?;
+        let placements = glyph_placements(text, &text_layout, text_style, path);
+
+        let mut hitboxes = placements
+            .iter()
+            .filter(|placement| !placement.whitespace)
+            .map(|placement| placement.bounds())
+            .collect::<Vec<Aabb>>();
+        let bounds = placements
+            .iter()
+            .map(|placement| placement.bounds())
+            .reduce(|acc, bounds| acc.merged(&bounds))
+            .unwrap_or_else(|| {
+                let start = path.start().unwrap_or_default();
+                Aabb::new(
+                    start.into(),
+                    (start + na::Vector2::repeat(text_style.font_size)).into(),
+                )
+            });
+        if hitboxes.is_empty() {
+            hitboxes.push(bounds);
         }
+
+        Ok(Self {
+            text: text.to_string(),
+            text_style: text_style.clone(),
+            path: Some(path.clone()),
+            bounds,
+            hitboxes,
+        })
+    }
+
+    fn valid_for(&self, text: &str, text_style: &TextStyle, path: Option<&TextPath>) -> bool {
+        self.text == text && self.text_style == *text_style && self.path.as_ref() == path
     }
 }
 
@@ -469,48 +915,42 @@ impl Transformable for TextStroke {
 
 impl Shapeable for TextStroke {
     fn bounds(&self) -> Aabb {
-        let untransformed_size = self
-            .text_style
-            .untransformed_size(&mut piet_cairo::CairoText::new(), self.text.clone())
-            .unwrap_or_else(|| na::Vector2::repeat(self.text_style.font_size))
-            .maxs(&na::vector![1.0, 1.0]);
+        let untransformed_bounds = match self.valid_layout_cache() {
+            Some(cache) => Some(cache.bounds),
+            None => TextLayoutCache::generate(&self.text, &self.text_style, self.path.as_ref())
+                .ok()
+                .map(|cache| cache.bounds),
+        }
+        .unwrap_or_else(|| {
+            Aabb::new(
+                na::point![0.0, 0.0],
+                na::Vector2::repeat(self.text_style.font_size.max(1.0)).into(),
+            )
+        });
 
-        self.transform
-            .transform_aabb(Aabb::new(na::point![0.0, 0.0], untransformed_size.into()))
+        self.transform.transform_aabb(untransformed_bounds)
     }
 
     fn hitboxes(&self) -> Vec<Aabb> {
-        let text_layout = match self
-            .text_style
-            .build_text_layout(&mut piet_cairo::CairoText::new(), self.text.clone())
-        {
-            Ok(text_layout) => text_layout,
-            Err(e) => {
-                error!("Building text layout failed while calculating the hitboxes, Err: {e:?}");
-                return vec![self.bounds()];
+        let untransformed_hitboxes = match self.valid_layout_cache() {
+            Some(cache) => cache.hitboxes.clone(),
+            None => {
+                match TextLayoutCache::generate(&self.text, &self.text_style, self.path.as_ref()) {
+                    Ok(cache) => cache.hitboxes,
+                    Err(e) => {
+                        error!(
+                        "Building text layout failed while calculating the hitboxes, Err: {e:?}"
+                    );
+                        return vec![self.bounds()];
+                    }
+                }
             }
         };
 
-        let mut hitboxes: Vec<Aabb> = text_layout
-            .rects_for_range(0..self.text.len())
+        untransformed_hitboxes
             .into_iter()
-            .map(|rect| self.transform.transform_aabb(Aabb::from_kurbo_rect(rect)))
-            .collect();
-
-        let text_size = text_layout.size();
-
-        if hitboxes.is_empty() {
-            hitboxes.push(
-                self.transform.transform_aabb(Aabb::new_positive(
-                    na::point![0.0, 0.0],
-                    na::vector![text_size.width, text_size.height]
-                        .maxs(&na::vector![1.0, 1.0])
-                        .into(),
-                )),
-            )
-        }
-
-        hitboxes
+            .map(|hitbox| self.transform.transform_aabb(hitbox))
+            .collect()
     }
 
     fn outline_path(&self) -> kurbo::BezPath {
@@ -519,19 +959,48 @@ impl Shapeable for TextStroke {
 }
 
 impl Content for TextStroke {
-    fn update_geometry(&mut self) {}
+    fn update_geometry(&mut self) {
+        if self.valid_layout_cache().is_some() {
+            return;
+        }
+        self.layout_cache = match TextLayoutCache::generate(
+            &self.text,
+            &self.text_style,
+            self.path.as_ref(),
+        ) {
+            Ok(cache) => Some(cache),
+            Err(e) => {
+                error!("Generating the text layout cache failed while updating the geometry, Err: {e:?}");
+                None
+            }
+        };
+    }
 }
 
 impl Drawable for TextStroke {
     fn draw(&self, cx: &mut impl RenderContext, _image_scale: f64) -> anyhow::Result<()> {
+        if let Some(path) = &self.path {
+            return self.draw_along_path(cx, path);
+        }
         cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
 
         if let Ok(text_layout) = self
             .text_style
             .build_text_layout(cx.text(), self.text.clone())
         {
+            let layout_size = na::vector![text_layout.size().width, text_layout.size().height];
             cx.transform(self.transform.affine.to_kurbo());
-            cx.draw_text(&text_layout, kurbo::Point::new(0.0, 0.0))
+            if let Some(background_color) = self.text_style.background_color {
+                let box_size = self.text_style.box_size(layout_size);
+                cx.fill(
+                    kurbo::Rect::new(0.0, 0.0, box_size[0], box_size[1]),
+                    &piet::Color::from(background_color),
+                );
+            }
+            cx.draw_text(
+                &text_layout,
+                self.text_style.text_offset(layout_size).to_kurbo_point(),
+            )
         }
 
         cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
@@ -545,6 +1014,164 @@ impl TextStroke {
             text,
             transform: Transform::new_w_isometry(na::Isometry2::new(upper_left_pos, 0.0)),
             text_style,
+            path: None,
+            layout_cache: None,
+        }
+    }
+
+    fn valid_layout_cache(&self) -> Option<&TextLayoutCache> {
+        self.layout_cache
+            .as_ref()
+            .filter(|cache| cache.valid_for(&self.text, &self.text_style, self.path.as_ref()))
+    }
+
+    /// Let the text flow along the given path, in global coordinate space.
+    ///
+    /// Replaces the current transform, the text then starts at the beginning of the path.
+    /// Returns false if the path is too short to be followed.
+    pub fn attach_to_path(&mut self, bez_path: &kurbo::BezPath) -> bool {
+        let Some(mut path) = TextPath::from_bezpath(bez_path) else {
+            return false;
+        };
+        let start = path.start().unwrap_or_default();
+        path.translate(-start);
+        self.transform = Transform::new_w_isometry(na::Isometry2::new(start, 0.0));
+        self.path = Some(path);
+        true
+    }
+
+    /// Lay the text out in a text box again.
+    ///
+    /// The attached path starts at the origin of the stroke, so the text box is positioned at the beginning of it.
+    pub fn detach_from_path(&mut self) {
+        self.path = None;
+    }
+
+    fn draw_along_path(&self, cx: &mut impl RenderContext, path: &TextPath) -> anyhow::Result<()> {
+        let text_layout = self
+            .text_style
+            .build_single_line_text_layout(cx.text(), self.text.clone())?;
+        let Some(line_metric) = text_layout.line_metric(0) else {
+            return Ok(());
+        };
+        let baseline = line_metric.y_offset + line_metric.baseline;
+
+        for placement in glyph_placements(&self.text, &text_layout, &self.text_style, path)
+            .into_iter()
+            .filter(|placement| !placement.whitespace)
+        {
+            // Drawing the entire layout clipped to every grapheme would draw it once per grapheme,
+            // so only the glyph run of the grapheme is drawn, with its ranged attributes.
+            // It is aligned to the baseline of the entire layout, which the placement is relative to.
+            let glyph_run_layout = self
+                .text_style
+                .restricted_to_range(&placement.range)
+                .build_single_line_text_layout(
+                    cx.text(),
+                    self.text[placement.range.clone()].to_string(),
+                )?;
+            let glyph_run_baseline = glyph_run_layout
+                .line_metric(0)
+                .map_or(baseline, |line_metric| {
+                    line_metric.y_offset + line_metric.baseline
+                });
+
+            cx.save().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+            cx.transform(self.transform.affine.to_kurbo() * placement.affine);
+            cx.draw_text(
+                &glyph_run_layout,
+                kurbo::Point::new(placement.rect.x0, baseline - glyph_run_baseline),
+            );
+            cx.restore().map_err(|e| anyhow::anyhow!("{e:?}"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Draw the cursor, following the path if the text flows along one.
+    pub fn draw_cursor(
+        &self,
+        cx: &mut impl piet::RenderContext,
+        cursor: &GraphemeCursor,
+        camera: &Camera,
+    ) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return self.text_style.draw_cursor(
+                cx,
+                self.text.clone(),
+                cursor,
+                &self.transform,
+                camera,
+            );
+        };
+        let text_layout = self
+            .text_style
+            .build_single_line_text_layout(cx.text(), self.text.clone())?;
+        let Some(line_metric) = text_layout.line_metric(0) else {
+            return Ok(());
+        };
+        let index = cursor.cur_cursor().min(self.text.len());
+        let x_pos = text_layout.hit_test_text_position(index).point.x;
+        // The cursor sits in the middle of the spacing between the graphemes
+        let graphemes_before = self.text[..index].graphemes(true).count();
+        let distance = x_pos + (graphemes_before as f64 - 0.5) * self.text_style.path_spacing;
+        let affine = self.transform.to_kurbo()
+            * path.placement(
+                distance,
+                kurbo::Point::new(x_pos, line_metric.y_offset + line_metric.baseline),
+                self.text_style.path_offset,
+            );
+
+        draw_text_cursor_line(
+            cx,
+            affine
+                * kurbo::Line::new(
+                    kurbo::Point::new(x_pos, line_metric.y_offset),
+                    kurbo::Point::new(x_pos, line_metric.y_offset + line_metric.height),
+                ),
+            camera,
+        );
+        Ok(())
+    }
+
+    /// Draw the text selection, following the path if the text flows along one.
+    pub fn draw_text_selection(
+        &self,
+        cx: &mut impl piet::RenderContext,
+        cursor: &GraphemeCursor,
+        selection_cursor: &GraphemeCursor,
+        camera: &Camera,
+    ) {
+        let Some(path) = &self.path else {
+            self.text_style.draw_text_selection(
+                cx,
+                self.text.clone(),
+                cursor,
+                selection_cursor,
+                &self.transform,
+                camera,
+            );
+            return;
+        };
+        let Ok(text_layout) = self
+            .text_style
+            .build_single_line_text_layout(cx.text(), self.text.clone())
+        else {
+            return;
+        };
+        let selection_range =
+            crate::utils::positive_range(cursor.cur_cursor(), selection_cursor.cur_cursor());
+
+        for placement in glyph_placements(&self.text, &text_layout, &self.text_style, path)
+            .into_iter()
+            .filter(|placement| {
+                placement.range.start >= selection_range.start
+                    && placement.range.end <= selection_range.end
+            })
+        {
+            let outline =
+                self.transform.to_kurbo() * placement.affine * placement.rect.to_path(0.5);
+            draw_text_selection_outline(cx, &outline, camera);
         }
     }
 
@@ -559,16 +1186,25 @@ impl TextStroke {
         &self,
         coord: na::Vector2<f64>,
     ) -> anyhow::Result<GraphemeCursor> {
+        if let Some(path) = &self.path {
+            return self.get_cursor_for_global_coord_along_path(coord, path);
+        }
         let text_layout = self
             .text_style
             .build_text_layout(&mut piet_cairo::CairoText::new(), self.text.clone())
             .map_err(|e| anyhow::anyhow!("Building text layout failed, Err: {e:?}"))?;
+        let text_offset = self.text_style.text_offset(na::vector![
+            text_layout.size().width,
+            text_layout.size().height
+        ]);
         let hit_test_point = text_layout.hit_test_point(
-            self.transform
+            (self
+                .transform
                 .affine
                 .inverse()
                 .transform_point(&coord.into())
                 .coords
+                - text_offset)
                 .to_kurbo_point(),
         );
 
@@ -579,6 +1215,42 @@ impl TextStroke {
         ))
     }
 
+    fn get_cursor_for_global_coord_along_path(
+        &self,
+        coord: na::Vector2<f64>,
+        path: &TextPath,
+    ) -> anyhow::Result<GraphemeCursor> {
+        let text_layout = self
+            .text_style
+            .build_single_line_text_layout(&mut piet_cairo::CairoText::new(), self.text.clone())?;
+        let untransformed_coord = self
+            .transform
+            .affine
+            .inverse()
+            .transform_point(&coord.into())
+            .coords
+            .to_kurbo_point();
+
+        // Pick the closest grapheme, and the side of it that is hit
+        let index = glyph_placements(&self.text, &text_layout, &self.text_style, path)
+            .into_iter()
+            .map(|placement| {
+                let layout_coord = placement.affine.inverse() * untransformed_coord;
+                let center = placement.rect.center();
+                let index = if layout_coord.x < center.x {
+                    placement.range.start
+                } else {
+                    placement.range.end
+                };
+                (index, layout_coord.distance(center))
+            })
+            .min_by(|(_, first), (_, second)| first.total_cmp(second))
+            .map(|(index, _)| index)
+            .unwrap_or(0);
+
+        Ok(GraphemeCursor::new(index, self.text.len(), true))
+    }
+
     pub fn insert_text_after_cursor(&mut self, text: &str, cursor: &mut GraphemeCursor) {
         self.text.insert_str(cursor.cur_cursor(), text);
 
@@ -696,6 +1368,50 @@ impl TextStroke {
         );
     }
 
+    /// Find all non-overlapping occurrences of the query in the text, as byte ranges.
+    pub fn find_text_matches(&self, query: &str, match_case: bool) -> Vec<Range<usize>> {
+        if query.is_empty() {
+            return vec![];
+        }
+        let chars_match = |a: char, b: char| {
+            if match_case {
+                a == b
+            } else {
+                a.to_lowercase().eq(b.to_lowercase())
+            }
+        };
+        let mut matches = vec![];
+        let mut search_start = 0;
+        for (start, _) in self.text.char_indices() {
+            if start < search_start {
+                continue;
+            }
+            let mut text_chars = self.text[start..].char_indices();
+            let mut end = start;
+            let matched = query.chars().all(|q| match text_chars.next() {
+                Some((i, c)) if chars_match(c, q) => {
+                    end = start + i + c.len_utf8();
+                    true
+                }
+                _ => false,
+            });
+            if matched {
+                matches.push(start..end);
+                search_start = end;
+            }
+        }
+        matches
+    }
+
+    /// Replace the text in the given byte range, translating the ranged text attributes accordingly.
+    pub fn replace_text_range(&mut self, range: Range<usize>, replace_text: &str) {
+        self.text.replace_range(range.clone(), replace_text);
+        self.translate_attrs_after_cursor(
+            range.start,
+            -(range.end as i32 - range.start as i32) + replace_text.len() as i32,
+        );
+    }
+
     /// Translate the ranged text attributes after the given cursor.
     ///
     /// Overlapping ranges are extended / shrunk
@@ -778,6 +1494,43 @@ impl TextStroke {
         };
     }
 
+    /// The distinct values of an attribute kind in the given range.
+    ///
+    /// `value_of` extracts the value from attributes of the wanted kind.
+    /// Where no ranged attribute applies, the value of the text style is used.
+    pub fn attr_values_for_range<T: Clone + PartialEq>(
+        &self,
+        range: Range<usize>,
+        style_value: T,
+        value_of: impl Fn(&TextAttribute) -> Option<T>,
+    ) -> Vec<T> {
+        let attrs = self
+            .text_style
+            .ranged_text_attributes
+            .iter()
+            .filter_map(|attr| value_of(&attr.attribute).map(|value| (attr.range.clone(), value)))
+            .collect::<Vec<(Range<usize>, T)>>();
+        // The value can only change where an attribute starts or ends
+        let positions = std::iter::once(range.start)
+            .chain(attrs.iter().flat_map(|(r, _)| [r.start, r.end]))
+            .filter(|pos| range.contains(pos));
+
+        let mut values = vec![];
+        for pos in positions.chain(range.is_empty().then_some(range.start)) {
+            // Attributes that were added later take precedence
+            let value = attrs
+                .iter()
+                .rev()
+                .find(|(r, _)| r.contains(&pos))
+                .map(|(_, value)| value.clone())
+                .unwrap_or_else(|| style_value.clone());
+            if !values.contains(&value) {
+                values.push(value);
+            }
+        }
+        values
+    }
+
     pub fn toggle_attrs_for_range(&mut self, range: Range<usize>, text_attribute: TextAttribute) {
         let (matching_attributes, mut non_matching_attrs) = self
             .text_style
@@ -1024,3 +1777,58 @@ fn remove_intersecting_attrs_in_range(
         .filter(|attr| !attr.range.is_empty())
         .collect::<Vec<RangedTextAttribute>>()
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::strokes::textstroke::{RangedTextAttribute, TextAttribute, TextStyle};
+
+    #[test]
+    fn text_style_restricted_to_range() {
+        let text_style = TextStyle {
+            ranged_text_attributes: vec![
+                RangedTextAttribute {
+                    range: 0..2,
+                    attribute: TextAttribute::FontWeight(700),
+                },
+                RangedTextAttribute {
+                    range: 3..8,
+                    attribute: TextAttribute::FontSize(20.0),
+                },
+                RangedTextAttribute {
+                    range: 8..10,
+                    attribute: TextAttribute::FontWeight(300),
+                },
+            ],
+            ..TextStyle::default()
+        };
+
+        assert_eq!(
+            text_style
+                .restricted_to_range(&(4..6))
+                .ranged_text_attributes,
+            vec![RangedTextAttribute {
+                range: 0..2,
+                attribute: TextAttribute::FontSize(20.0),
+            }]
+        );
+        assert_eq!(
+            text_style
+                .restricted_to_range(&(1..4))
+                .ranged_text_attributes,
+            vec![
+                RangedTextAttribute {
+                    range: 0..1,
+                    attribute: TextAttribute::FontWeight(700),
+                },
+                RangedTextAttribute {
+                    range: 2..3,
+                    attribute: TextAttribute::FontSize(20.0),
+                },
+            ]
+        );
+        assert!(text_style
+            .restricted_to_range(&(2..3))
+            .ranged_text_attributes
+            .is_empty());
+    }
+}
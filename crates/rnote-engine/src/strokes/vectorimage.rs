@@ -1,9 +1,10 @@
 // Imports
 use super::content::GeneratedContentImages;
 use super::resize::{calculate_resize_ratio, ImageSizeOption};
-use super::{Content, Stroke};
+use super::{Content, ImageCrop, PdfTextLayer};
 use crate::document::Format;
-use crate::engine::import::{PdfImportPageSpacing, PdfImportPrefs};
+use crate::engine::import::PdfImportPrefs;
+use crate::engine::ImportJobHandle;
 use crate::{render, Drawable};
 use kurbo::Shape;
 use p2d::bounding_volume::Aabb;
@@ -15,7 +16,6 @@ use rnote_compose::shapes::Shapeable;
 use rnote_compose::transform::Transform;
 use rnote_compose::transform::Transformable;
 use serde::{Deserialize, Serialize};
-use std::ops::Range;
 use std::sync::Arc;
 use tracing::error;
 
@@ -29,8 +29,15 @@ pub struct VectorImage {
         with = "rnote_compose::serialize::na_vector2_f64_dp3"
     )]
     pub intrinsic_size: na::Vector2<f64>,
+    /// The uncropped image rectangle.
     #[serde(rename = "rectangle")]
     pub rectangle: Rectangle,
+    /// The visible region, when the image is cropped.
+    #[serde(rename = "crop")]
+    pub crop: Option<ImageCrop>,
+    /// The text layer, when the image is an imported Pdf page.
+    #[serde(rename = "pdf_text")]
+    pub pdf_text: Option<PdfTextLayer>,
 }
 
 impl Default for VectorImage {
@@ -39,6 +46,8 @@ impl Default for VectorImage {
             svg_data: String::default(),
             intrinsic_size: na::Vector2::zeros(),
             rectangle: Rectangle::default(),
+            crop: None,
+            pdf_text: None,
         }
     }
 }
@@ -59,15 +68,39 @@ impl Content for VectorImage {
             )
             .set("preserveAspectRatio", "none")
             .add(svg::node::Blob::new(self.svg_data.clone()));
-        let group = svg::node::element::Group::new()
-            .set(
-                "transform",
-                self.rectangle.transform.to_svg_transform_attr_str(),
-            )
-            .add(svg_root);
+        let group = svg::node::element::Group::new().set(
+            "transform",
+            self.rectangle.transform.to_svg_transform_attr_str(),
+        );
+        let group = match self.crop {
+            Some(crop) => {
+                // Nested svg elements clip their content to their viewport
+                let crop_aabb = crop.local_aabb(&self.rectangle);
+                let crop_extents = crop_aabb.extents();
+                group.add(
+                    svg::node::element::SVG::new()
+                        .set("x", crop_aabb.mins[0])
+                        .set("y", crop_aabb.mins[1])
+                        .set("width", crop_extents[0])
+                        .set("height", crop_extents[1])
+                        .set(
+                            "viewBox",
+                            format!(
+                                "{:.3} {:.3} {:.3} {:.3}",
+                                crop_aabb.mins[0],
+                                crop_aabb.mins[1],
+                                crop_extents[0],
+                                crop_extents[1]
+                            ),
+                        )
+                        .add(svg_root),
+                )
+            }
+            None => group.add(svg_root),
+        };
         let svg_data = rnote_compose::utils::svg_node_to_string(&group)?;
         let svg = render::Svg {
-            bounds: self.rectangle.bounds(),
+            bounds: self.bounds(),
             svg_data,
         };
         Ok(svg)
@@ -110,7 +143,7 @@ impl Drawable for VectorImage {
 
 impl Shapeable for VectorImage {
     fn bounds(&self) -> Aabb {
-        self.rectangle.bounds()
+        self.visible_rectangle().bounds()
     }
 
     fn hitboxes(&self) -> Vec<Aabb> {
@@ -137,6 +170,14 @@ impl Transformable for VectorImage {
 }
 
 impl VectorImage {
+    /// The rectangle of the visible region of the image.
+    pub fn visible_rectangle(&self) -> Rectangle {
+        match self.crop {
+            Some(crop) => crop.cropped_rectangle(&self.rectangle),
+            None => self.rectangle,
+        }
+    }
+
     pub fn from_svg_str(
         svg_data: &str,
         pos: na::Vector2<f64>,
@@ -201,6 +242,8 @@ impl VectorImage {
             svg_data,
             intrinsic_size,
             rectangle,
+            crop: None,
+            pdf_text: None,
         })
     }
 
@@ -208,20 +251,22 @@ impl VectorImage {
         bytes: &[u8],
         pdf_import_prefs: PdfImportPrefs,
         insert_pos: na::Vector2<f64>,
-        page_range: Option<Range<u32>>,
+        pages: Option<Vec<u32>>,
         format: &Format,
         password: Option<String>,
+        job: Option<&ImportJobHandle>,
     ) -> Result<Vec<Self>, anyhow::Error> {
         let doc = poppler::Document::from_bytes(&glib::Bytes::from(bytes), password.as_deref())?;
-        let page_range = page_range.unwrap_or(0..doc.n_pages() as u32);
+        let pages = pages.unwrap_or_else(|| (0..doc.n_pages() as u32).collect());
+        let n_pages = pages.len();
 
         let page_width = if pdf_import_prefs.adjust_document {
             format.width()
         } else {
             format.width() * (pdf_import_prefs.page_width_perc / 100.0)
         };
-        // calculate the page zoom based on the width of the first page.
-        let page_zoom = if let Some(first_page) = doc.page(0) {
+        // calculate the page zoom based on the width of the first imported page.
+        let page_zoom = if let Some(first_page) = pages.first().and_then(|&i| doc.page(i as i32)) {
             page_width / first_page.size().0
         } else {
             return Ok(vec![]);
@@ -229,10 +274,23 @@ impl VectorImage {
         let x = insert_pos[0];
         let mut y = insert_pos[1];
 
-        let svgs = page_range
+        let svgs = pages
+            .into_iter()
+            .enumerate()
+            // Stop rendering further pages when the job got cancelled
+            .map_while(|(i, page_i)| {
+                if let Some(job) = job {
+                    if job.is_cancelled() {
+                        return None;
+                    }
+                    job.set_progress(i as f64 / n_pages as f64);
+                }
+                Some(page_i)
+            })
             .filter_map(|page_i| {
                 let page = doc.page(page_i as i32)?;
                 let (intrinsic_width, intrinsic_height) = page.size();
+                let pdf_text = PdfTextLayer::from_poppler_page(&page);
                 let width = intrinsic_width * page_zoom;
                 let height = intrinsic_height * page_zoom;
 
@@ -305,34 +363,35 @@ impl VectorImage {
 
                 let bounds = Aabb::new(na::point![x, y], na::point![x + width, y + height]);
 
-                if pdf_import_prefs.adjust_document {
-                    y += height
-                } else {
-                    y += match pdf_import_prefs.page_spacing {
-                        PdfImportPageSpacing::Continuous => {
-                            height + Stroke::IMPORT_OFFSET_DEFAULT[1] * 0.5
-                        }
-                        PdfImportPageSpacing::OnePerDocumentPage => format.height(),
-                    };
-                }
+                y += pdf_import_prefs.page_offset(height, format);
 
                 match res() {
-                    Ok(svg_data) => Some(render::Svg { svg_data, bounds }),
+                    Ok(svg_data) => Some((render::Svg { svg_data, bounds }, pdf_text)),
                     Err(e) => {
                         error!("Importing page {page_i} from pdf failed, Err: {e:?}");
                         None
                     }
                 }
             })
-            .collect::<Vec<render::Svg>>();
+            .collect::<Vec<(render::Svg, Option<PdfTextLayer>)>>();
+        if let Some(job) = job {
+            job.check_cancelled()?;
+        }
 
         svgs.into_par_iter()
-            .map(|svg| {
-                Self::from_svg_str(
+            .map(|(svg, pdf_text)| {
+                let mut vectorimage = Self::from_svg_str(
                     svg.svg_data.as_str(),
                     svg.bounds.mins.coords,
                     ImageSizeOption::ImposeSize(svg.bounds.extents()),
-                )
+                )?;
+                if pdf_import_prefs.color_remap.enabled {
+                    vectorimage.svg_data = pdf_import_prefs
+                        .color_remap
+                        .remap_svg_colors(&vectorimage.svg_data);
+                }
+                vectorimage.pdf_text = pdf_text;
+                Ok(vectorimage)
             })
             .collect()
     }
@@ -10,11 +10,14 @@ use crate::{
     strokewidthpicker::StrokeWidthPreviewStyle, workspacebrowser::workspacesbar::RnWorkspaceRow,
     workspacebrowser::RnFileRow, workspacebrowser::RnWorkspacesBar, RnAppMenu, RnAppWindow,
     RnCanvas, RnCanvasMenu, RnCanvasWrapper, RnColorPicker, RnIconPicker, RnMainHeader, RnOverlays,
-    RnPenPicker, RnPensSideBar, RnSettingsPanel, RnSidebar, RnStrokeContentPreview,
-    RnStrokeWidthPicker, RnUnitEntry, RnWorkspaceBrowser,
+    RnPageThumbnails, RnPenPicker, RnPensSideBar, RnRuler, RnSettingsPanel, RnSidebar,
+    RnStrokeContentPreview, RnStrokeWidthPicker, RnTextSearchBar, RnToolPalette, RnUnitEntry,
+    RnWorkspaceBrowser,
 };
 use adw::subclass::prelude::AdwApplicationImpl;
-use gtk4::{gio, glib, glib::clone, prelude::*, subclass::prelude::*};
+use gtk4::{gdk, gio, glib, glib::clone, prelude::*, subclass::prelude::*, CssProvider};
+use rnote_compose::{color, Color};
+use tracing::error;
 
 mod imp {
     use super::*;
@@ -22,6 +25,7 @@ mod imp {
     #[derive(Debug)]
     pub(crate) struct RnApp {
         pub(crate) app_settings: Option<gio::Settings>,
+        pub(crate) accent_css: CssProvider,
     }
 
     impl Default for RnApp {
@@ -34,7 +38,10 @@ mod imp {
                 ))
             });
 
-            Self { app_settings }
+            Self {
+                app_settings,
+                accent_css: CssProvider::new(),
+            }
         }
     }
 
@@ -55,6 +62,7 @@ mod imp {
             self.setup_buildables();
             obj.setup_actions();
             obj.setup_action_accels();
+            self.setup_accent_color();
         }
 
         fn activate(&self) {
@@ -127,6 +135,66 @@ mod imp {
             RnStrokeContentPreview::static_type();
             RnSidebar::static_type();
             RnPenPicker::static_type();
+            RnToolPalette::static_type();
+            RnTextSearchBar::static_type();
+            RnRuler::static_type();
+            RnPageThumbnails::static_type();
+        }
+
+        /// Adds the css provider for the custom accent color and keeps it in sync with the settings.
+        fn setup_accent_color(&self) {
+            let Some(display) = gdk::Display::default() else {
+                error!("Setting up the accent color failed, no default display.");
+                return;
+            };
+            // Above the application css, so that the accent color overrides its colors
+            gtk4::style_context_add_provider_for_display(
+                &display,
+                &self.accent_css,
+                gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION + 1,
+            );
+
+            let Some(app_settings) = self.app_settings.as_ref() else {
+                return;
+            };
+            self.update_accent_color(app_settings);
+            let obj = self.obj();
+            app_settings.connect_changed(
+                None,
+                clone!(
+                    #[weak(rename_to=app)]
+                    obj,
+                    move |app_settings, key| {
+                        if key == "custom-accent-color" || key == "accent-color" {
+                            app.imp().update_accent_color(app_settings);
+                        }
+                    }
+                ),
+            );
+        }
+
+        fn update_accent_color(&self, app_settings: &gio::Settings) {
+            if !app_settings.boolean("custom-accent-color") {
+                self.accent_css.load_from_string("");
+                return;
+            }
+            let (r, g, b, _) = app_settings.get::<(f64, f64, f64, f64)>("accent-color");
+            let accent_color = Color::new(r, g, b, 1.0);
+            let accent_fg_color = if accent_color.luma() < color::FG_LUMINANCE_THRESHOLD {
+                "@light_1"
+            } else {
+                "@dark_5"
+            };
+            let accent_color_css = format!(
+                "rgb({}, {}, {})",
+                (r * 255.0).round() as i32,
+                (g * 255.0).round() as i32,
+                (b * 255.0).round() as i32,
+            );
+
+            self.accent_css.load_from_string(&format!(
+                "@define-color accent_bg_color {accent_color_css};@define-color accent_color {accent_color_css};@define-color accent_fg_color {accent_fg_color};"
+            ));
         }
 
         /// Initializes and shows a new app window
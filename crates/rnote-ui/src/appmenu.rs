@@ -1,7 +1,11 @@
 // Imports
 use crate::appwindow::RnAppWindow;
+use crate::settingspanel;
 use adw::{prelude::*, subclass::prelude::*};
-use gtk4::{gio, glib, CompositeTemplate, MenuButton, PopoverMenu, ToggleButton, Widget};
+use gettextrs::gettext;
+use gtk4::{
+    gio, glib, glib::clone, CompositeTemplate, MenuButton, PopoverMenu, ToggleButton, Widget,
+};
 
 mod imp {
     use super::*;
@@ -16,6 +20,8 @@ mod imp {
         #[template_child]
         pub(crate) menu_model: TemplateChild<gio::MenuModel>,
         #[template_child]
+        pub(crate) custom_formats_section: TemplateChild<gio::Menu>,
+        #[template_child]
         pub(crate) lefthanded_toggle: TemplateChild<ToggleButton>,
         #[template_child]
         pub(crate) righthanded_toggle: TemplateChild<ToggleButton>,
@@ -95,5 +101,38 @@ impl RnAppMenu {
             .sync_create()
             .bidirectional()
             .build();
+
+        if let Some(app_settings) = appwindow.app().app_settings() {
+            self.refresh_custom_formats_section(&app_settings);
+            app_settings.connect_changed(
+                Some("custom-formats"),
+                clone!(
+                    #[weak(rename_to=appmenu)]
+                    self,
+                    move |app_settings, _| {
+                        appmenu.refresh_custom_formats_section(app_settings);
+                    }
+                ),
+            );
+        }
+    }
+
+    fn refresh_custom_formats_section(&self, app_settings: &gio::Settings) {
+        let section = self.imp().custom_formats_section.get();
+        section.remove_all();
+        let custom_formats = settingspanel::custom_formats(app_settings);
+        if custom_formats.is_empty() {
+            // An item without an action is shown insensitive
+            section.append(Some(&gettext("No Custom Formats")), None);
+            return;
+        }
+        for custom_format in custom_formats {
+            let item = gio::MenuItem::new(Some(&custom_format.name), None);
+            item.set_action_and_target_value(
+                Some("win.new-tab-with-format"),
+                Some(&custom_format.name.to_variant()),
+            );
+            section.append_item(&item);
+        }
     }
 }
@@ -1,20 +1,18 @@
 // Imports
-use crate::{config, dialogs, RnAppWindow, RnCanvas};
+use super::share::ShareFormat;
+use crate::{config, dialogs, settingspanel, RnAppWindow, RnCanvas};
 use gettextrs::gettext;
 use gtk4::gio::InputStream;
 use gtk4::graphene;
-use gtk4::{
-    gdk, gio, glib, glib::clone, prelude::*, PrintOperation, PrintOperationAction, Unit,
-    UriLauncher, Window,
-};
-use p2d::bounding_volume::BoundingVolume;
+use gtk4::{gdk, gio, glib, glib::clone, prelude::*, UriLauncher, Window};
 use rnote_compose::penevent::ShortcutKey;
-use rnote_compose::SplitOrder;
-use rnote_engine::engine::StrokeContent;
+use rnote_engine::engine::profiling::PROFILER;
+use rnote_engine::engine::{ImportCancelled, StrokeContent};
 use rnote_engine::ext::GraphenePointExt;
-use rnote_engine::pens::PenStyle;
+use rnote_engine::pens::{PenStyle, PensConfig};
+use rnote_engine::store::{FlipAxis, SelectSimilar, ZOrderChange};
 use rnote_engine::strokes::resize::{ImageSizeOption, Resize};
-use rnote_engine::{Camera, Engine};
+use rnote_engine::Camera;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Instant;
@@ -56,6 +54,9 @@ impl RnAppWindow {
         self.add_action(&action_devel_menu);
         let action_new_tab = gio::SimpleAction::new("new-tab", None);
         self.add_action(&action_new_tab);
+        let action_new_tab_with_format =
+            gio::SimpleAction::new("new-tab-with-format", Some(&String::static_variant_type()));
+        self.add_action(&action_new_tab_with_format);
         let action_visual_debug =
             gio::SimpleAction::new_stateful("visual-debug", None, &false.to_variant());
         self.add_action(&action_visual_debug);
@@ -65,16 +66,28 @@ impl RnAppWindow {
         let action_debug_export_engine_config =
             gio::SimpleAction::new("debug-export-engine-config", None);
         self.add_action(&action_debug_export_engine_config);
+        // The profiler is global for the process, so its initial state is taken from it
+        let action_debug_profiling = gio::SimpleAction::new_stateful(
+            "debug-profiling",
+            None,
+            &PROFILER.enabled().to_variant(),
+        );
+        self.add_action(&action_debug_profiling);
+        let action_debug_export_profile = gio::SimpleAction::new("debug-export-profile", None);
+        self.add_action(&action_debug_export_profile);
         let action_righthanded = gio::PropertyAction::new("righthanded", self, "righthanded");
         self.add_action(&action_righthanded);
         let action_touch_drawing = gio::PropertyAction::new("touch-drawing", self, "touch-drawing");
         self.add_action(&action_touch_drawing);
         let action_focus_mode = gio::PropertyAction::new("focus-mode", self, "focus-mode");
         self.add_action(&action_focus_mode);
+        let action_distraction_free_mode =
+            gio::PropertyAction::new("distraction-free-mode", self, "distraction-free-mode");
+        self.add_action(&action_distraction_free_mode);
+        let action_presentation_mode =
+            gio::PropertyAction::new("presentation-mode", self, "presentation-mode");
+        self.add_action(&action_presentation_mode);
 
-        let action_pen_sounds =
-            gio::SimpleAction::new_stateful("pen-sounds", None, &false.to_variant());
-        self.add_action(&action_pen_sounds);
         let action_snap_positions =
             gio::SimpleAction::new_stateful("snap-positions", None, &false.to_variant());
         self.add_action(&action_snap_positions);
@@ -84,26 +97,92 @@ impl RnAppWindow {
         let action_show_origin_indicator =
             gio::SimpleAction::new_stateful("show-origin-indicator", None, &true.to_variant());
         self.add_action(&action_show_origin_indicator);
+        let action_onion_skinning =
+            gio::SimpleAction::new_stateful("onion-skinning", None, &false.to_variant());
+        self.add_action(&action_onion_skinning);
+        let action_highlighters_behind =
+            gio::SimpleAction::new_stateful("highlighters-behind", None, &true.to_variant());
+        self.add_action(&action_highlighters_behind);
+        let action_replay_drawing =
+            gio::SimpleAction::new_stateful("replay-drawing", None, &false.to_variant());
+        self.add_action(&action_replay_drawing);
         let action_block_pinch_zoom =
             gio::PropertyAction::new("block-pinch-zoom", self, "block-pinch-zoom");
         self.add_action(&action_block_pinch_zoom);
         let action_respect_borders =
             gio::PropertyAction::new("respect-borders", self, "respect-borders");
         self.add_action(&action_respect_borders);
+        let action_show_tool_palette = gio::PropertyAction::new(
+            "show-tool-palette",
+            &self.overlays().toolpalette(),
+            "visible",
+        );
+        self.add_action(&action_show_tool_palette);
+        let action_pens_sidebar_compact = gio::PropertyAction::new(
+            "pens-sidebar-compact",
+            &self.overlays().penssidebar(),
+            "compact",
+        );
+        self.add_action(&action_pens_sidebar_compact);
+        let action_show_rulers = gio::PropertyAction::new(
+            "show-rulers",
+            &self.sidebar().settings_panel().general_show_rulers_row(),
+            "active",
+        );
+        self.add_action(&action_show_rulers);
+        let action_show_page_thumbnails = gio::PropertyAction::new(
+            "show-page-thumbnails",
+            &self
+                .sidebar()
+                .settings_panel()
+                .general_show_page_thumbnails_row(),
+            "active",
+        );
+        self.add_action(&action_show_page_thumbnails);
         let action_pen_style = gio::SimpleAction::new_stateful(
             "pen-style",
             Some(&String::static_variant_type()),
             &String::from("brush").to_variant(),
         );
         self.add_action(&action_pen_style);
+        let action_pen_style_previous = gio::SimpleAction::new("pen-style-previous", None);
+        self.add_action(&action_pen_style_previous);
+        let action_select_workspace =
+            gio::SimpleAction::new("select-workspace", Some(&i32::static_variant_type()));
+        self.add_action(&action_select_workspace);
+        let action_pen_width_preset =
+            gio::SimpleAction::new("pen-width-preset", Some(&i32::static_variant_type()));
+        self.add_action(&action_pen_width_preset);
+        let action_pen_preset_add = gio::SimpleAction::new("pen-preset-add", None);
+        self.add_action(&action_pen_preset_add);
+        let action_pen_preset_apply =
+            gio::SimpleAction::new("pen-preset-apply", Some(&i32::static_variant_type()));
+        self.add_action(&action_pen_preset_apply);
+        let action_pen_preset_remove =
+            gio::SimpleAction::new("pen-preset-remove", Some(&i32::static_variant_type()));
+        self.add_action(&action_pen_preset_remove);
+        let action_insert_snippet =
+            gio::SimpleAction::new("insert-snippet", Some(&String::static_variant_type()));
+        self.add_action(&action_insert_snippet);
         let action_undo_stroke = gio::SimpleAction::new("undo", None);
         self.add_action(&action_undo_stroke);
         let action_redo_stroke = gio::SimpleAction::new("redo", None);
         self.add_action(&action_redo_stroke);
         let action_zoom_reset = gio::SimpleAction::new("zoom-reset", None);
         self.add_action(&action_zoom_reset);
+        let action_zoom_to_value =
+            gio::SimpleAction::new("zoom-to-value", Some(&f64::static_variant_type()));
+        self.add_action(&action_zoom_to_value);
         let action_zoom_fit_width = gio::SimpleAction::new("zoom-fit-width", None);
         self.add_action(&action_zoom_fit_width);
+        let action_zoom_fit_height = gio::SimpleAction::new("zoom-fit-height", None);
+        self.add_action(&action_zoom_fit_height);
+        let action_zoom_fit_page = gio::SimpleAction::new("zoom-fit-page", None);
+        self.add_action(&action_zoom_fit_page);
+        let action_zoom_to_selection = gio::SimpleAction::new("zoom-to-selection", None);
+        self.add_action(&action_zoom_to_selection);
+        let action_zoom_to_fit_strokes = gio::SimpleAction::new("zoom-to-fit-strokes", None);
+        self.add_action(&action_zoom_to_fit_strokes);
         let action_zoomin = gio::SimpleAction::new("zoom-in", None);
         self.add_action(&action_zoomin);
         let action_zoomout = gio::SimpleAction::new("zoom-out", None);
@@ -116,18 +195,76 @@ impl RnAppWindow {
         self.add_action(&action_resize_to_fit_content);
         let action_return_origin_page = gio::SimpleAction::new("return-origin-page", None);
         self.add_action(&action_return_origin_page);
+        let action_go_to_page = gio::SimpleAction::new("go-to-page", None);
+        self.add_action(&action_go_to_page);
+        let action_doc_properties = gio::SimpleAction::new("doc-properties", None);
+        self.add_action(&action_doc_properties);
+        let action_clear_guides = gio::SimpleAction::new("clear-guides", None);
+        self.add_action(&action_clear_guides);
         let action_selection_trash = gio::SimpleAction::new("selection-trash", None);
         self.add_action(&action_selection_trash);
         let action_selection_duplicate = gio::SimpleAction::new("selection-duplicate", None);
         self.add_action(&action_selection_duplicate);
+        let action_selection_lock = gio::SimpleAction::new("selection-lock", None);
+        self.add_action(&action_selection_lock);
+        let action_unlock_all_strokes = gio::SimpleAction::new("unlock-all-strokes", None);
+        self.add_action(&action_unlock_all_strokes);
+        let action_recently_deleted = gio::SimpleAction::new("recently-deleted", None);
+        self.add_action(&action_recently_deleted);
+        let action_revision_history = gio::SimpleAction::new("revision-history", None);
+        self.add_action(&action_revision_history);
+        let action_search_text = gio::SimpleAction::new("search-text", None);
+        self.add_action(&action_search_text);
+        let action_selection_flip_horizontal =
+            gio::SimpleAction::new("selection-flip-horizontal", None);
+        self.add_action(&action_selection_flip_horizontal);
+        let action_selection_flip_vertical =
+            gio::SimpleAction::new("selection-flip-vertical", None);
+        self.add_action(&action_selection_flip_vertical);
+        let action_selection_bring_to_front =
+            gio::SimpleAction::new("selection-bring-to-front", None);
+        self.add_action(&action_selection_bring_to_front);
+        let action_selection_bring_forward =
+            gio::SimpleAction::new("selection-bring-forward", None);
+        self.add_action(&action_selection_bring_forward);
+        let action_selection_send_backward =
+            gio::SimpleAction::new("selection-send-backward", None);
+        self.add_action(&action_selection_send_backward);
+        let action_selection_send_to_back = gio::SimpleAction::new("selection-send-to-back", None);
+        self.add_action(&action_selection_send_to_back);
+        let action_selection_crop_image = gio::SimpleAction::new("selection-crop-image", None);
+        self.add_action(&action_selection_crop_image);
+        let action_selection_reset_image_crop =
+            gio::SimpleAction::new("selection-reset-image-crop", None);
+        self.add_action(&action_selection_reset_image_crop);
+        let action_selection_add_caption = gio::SimpleAction::new("selection-add-caption", None);
+        self.add_action(&action_selection_add_caption);
+        let action_selection_text_along_path =
+            gio::SimpleAction::new("selection-text-along-path", None);
+        self.add_action(&action_selection_text_along_path);
+        let action_selection_edit_nodes = gio::SimpleAction::new("selection-edit-nodes", None);
+        self.add_action(&action_selection_edit_nodes);
         let action_selection_invert_color = gio::SimpleAction::new("selection-invert-color", None);
         self.add_action(&action_selection_invert_color);
+        let action_selection_remap_colors = gio::SimpleAction::new("selection-remap-colors", None);
+        self.add_action(&action_selection_remap_colors);
+        let action_selection_simplify = gio::SimpleAction::new("selection-simplify", None);
+        self.add_action(&action_selection_simplify);
         let action_selection_select_all = gio::SimpleAction::new("selection-select-all", None);
         self.add_action(&action_selection_select_all);
         let action_selection_deselect_all = gio::SimpleAction::new("selection-deselect-all", None);
         self.add_action(&action_selection_deselect_all);
+        let action_selection_select_similar = gio::SimpleAction::new(
+            "selection-select-similar",
+            Some(&String::static_variant_type()),
+        );
+        self.add_action(&action_selection_select_similar);
         let action_clear_doc = gio::SimpleAction::new("clear-doc", None);
         self.add_action(&action_clear_doc);
+        let action_scale_doc_content = gio::SimpleAction::new("scale-doc-content", None);
+        self.add_action(&action_scale_doc_content);
+        let action_insert_table = gio::SimpleAction::new("insert-table", None);
+        self.add_action(&action_insert_table);
         let action_new_doc = gio::SimpleAction::new("new-doc", None);
         self.add_action(&action_new_doc);
         let action_save_doc = gio::SimpleAction::new("save-doc", None);
@@ -142,12 +279,32 @@ impl RnAppWindow {
         self.add_action(&action_print_doc);
         let action_import_file = gio::SimpleAction::new("import-file", None);
         self.add_action(&action_import_file);
+        let action_insert_camera_capture = gio::SimpleAction::new("insert-camera-capture", None);
+        // The camera is only accessible through the desktop portal on Linux
+        action_insert_camera_capture.set_enabled(cfg!(target_os = "linux"));
+        self.add_action(&action_insert_camera_capture);
+        let action_append_rnote_file = gio::SimpleAction::new("append-rnote-file", None);
+        self.add_action(&action_append_rnote_file);
         let action_export_doc = gio::SimpleAction::new("export-doc", None);
         self.add_action(&action_export_doc);
         let action_export_doc_pages = gio::SimpleAction::new("export-doc-pages", None);
         self.add_action(&action_export_doc_pages);
+        let action_share_doc_email =
+            gio::SimpleAction::new("share-doc-email", Some(&String::static_variant_type()));
+        self.add_action(&action_share_doc_email);
+        let action_share_doc_open_with = gio::SimpleAction::new("share-doc-open-with", None);
+        self.add_action(&action_share_doc_open_with);
+        let action_serve_doc =
+            gio::SimpleAction::new_stateful("serve-doc", None, &false.to_variant());
+        self.add_action(&action_serve_doc);
         let action_export_selection = gio::SimpleAction::new("export-selection", None);
         self.add_action(&action_export_selection);
+        let action_export_viewport = gio::SimpleAction::new("export-viewport", None);
+        self.add_action(&action_export_viewport);
+        let action_export_selection_tikz = gio::SimpleAction::new("export-selection-tikz", None);
+        self.add_action(&action_export_selection_tikz);
+        let action_export_replay = gio::SimpleAction::new("export-replay", None);
+        self.add_action(&action_export_replay);
         let action_clipboard_copy = gio::SimpleAction::new("clipboard-copy", None);
         self.add_action(&action_clipboard_copy);
         let action_clipboard_cut = gio::SimpleAction::new("clipboard-cut", None);
@@ -157,6 +314,15 @@ impl RnAppWindow {
         let action_clipboard_paste_contextmenu =
             gio::SimpleAction::new("clipboard-paste-contextmenu", None);
         self.add_action(&action_clipboard_paste_contextmenu);
+        let action_insert_text_contextmenu =
+            gio::SimpleAction::new("insert-text-contextmenu", None);
+        self.add_action(&action_insert_text_contextmenu);
+        let action_import_file_contextmenu =
+            gio::SimpleAction::new("import-file-contextmenu", None);
+        self.add_action(&action_import_file_contextmenu);
+        let action_select_page_contextmenu =
+            gio::SimpleAction::new("select-page-contextmenu", None);
+        self.add_action(&action_select_page_contextmenu);
         let action_active_tab_move_left = gio::SimpleAction::new("active-tab-move-left", None);
         self.add_action(&action_active_tab_move_left);
         let action_active_tab_move_right = gio::SimpleAction::new("active-tab-move-right", None);
@@ -267,16 +433,19 @@ impl RnAppWindow {
             action_devel_menu,
             #[weak]
             action_visual_debug,
+            #[weak]
+            action_debug_profiling,
             move |action, _| {
                 let state = action.state().unwrap().get::<bool>().unwrap();
 
                 // Enable the devel menu action to reveal it in the app menu
                 action_devel_menu.set_enabled(!state);
 
-                // Always disable visual-debugging when disabling the developer mode
+                // Always disable visual-debugging and profiling when disabling the developer mode
                 if state {
-                    debug!("Disabling developer mode, disabling visual debugging.");
+                    debug!("Disabling developer mode, disabling visual debugging and profiling.");
                     action_visual_debug.change_state(&false.to_variant());
+                    action_debug_profiling.change_state(&false.to_variant());
                 }
                 action.change_state(&(!state).to_variant());
             }
@@ -302,6 +471,13 @@ impl RnAppWindow {
             }
         ));
 
+        // Profiling
+        action_debug_profiling.connect_change_state(move |action, state_request| {
+            let profiling = state_request.unwrap().get::<bool>().unwrap();
+            PROFILER.set_enabled(profiling);
+            action.set_state(&profiling.to_variant());
+        });
+
         // Create page
         action_new_tab.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -312,6 +488,34 @@ impl RnAppWindow {
             }
         ));
 
+        // New tab with a user-defined format
+        action_new_tab_with_format.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(name) = target.and_then(|t| t.get::<String>()) else {
+                    error!("Activated new-tab-with-format action with invalid target");
+                    return;
+                };
+                let Some(app_settings) = appwindow.app().app_settings() else {
+                    return;
+                };
+                let Some(custom_format) = settingspanel::custom_formats(&app_settings)
+                    .into_iter()
+                    .find(|f| f.name == name)
+                else {
+                    error!("Activated new-tab-with-format action with unknown format `{name}`");
+                    return;
+                };
+                let wrapper = appwindow.new_canvas_wrapper();
+                let canvas = wrapper.canvas();
+                custom_format.apply_to(&mut canvas.engine_mut().document.format);
+                let widget_flags = canvas.engine_mut().doc_resize_to_fit_content();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+                appwindow.append_wrapper_new_tab(&wrapper);
+            }
+        ));
+
         // Export engine state
         action_debug_export_engine_state.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -349,19 +553,21 @@ impl RnAppWindow {
             }
         ));
 
-        // Pen sounds
-        action_pen_sounds.connect_change_state(clone!(
+        // Export the profile
+        action_debug_export_profile.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
-            move |action, state_request| {
-                let pen_sounds = state_request.unwrap().get::<bool>().unwrap();
-                let Some(canvas) = appwindow.active_tab_canvas() else {
-                    return;
-                };
-                canvas
-                    .engine_mut()
-                    .set_pen_sounds(pen_sounds, crate::env::pkg_data_dir().ok());
-                action.set_state(&pen_sounds.to_variant());
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(canvas) = appwindow.active_tab_canvas() else {
+                            return;
+                        };
+                        dialogs::export::filechooser_export_profile(&appwindow, &canvas).await;
+                    }
+                ));
             }
         ));
 
@@ -409,6 +615,59 @@ impl RnAppWindow {
             }
         ));
 
+        // Onion skinning
+        action_onion_skinning.connect_change_state(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |action, state_request| {
+                let onion_skinning = state_request.unwrap().get::<bool>().unwrap();
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                canvas.engine_mut().document.onion_skinning = onion_skinning;
+                canvas.queue_draw();
+                action.set_state(&onion_skinning.to_variant());
+            }
+        ));
+
+        // Highlighters behind
+        action_highlighters_behind.connect_change_state(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |action, state_request| {
+                let highlighters_behind = state_request.unwrap().get::<bool>().unwrap();
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas
+                    .engine_mut()
+                    .set_highlighters_behind(highlighters_behind);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+                action.set_state(&highlighters_behind.to_variant());
+            }
+        ));
+
+        // Replay drawing
+        action_replay_drawing.connect_change_state(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |action, state_request| {
+                let replay_drawing = state_request.unwrap().get::<bool>().unwrap();
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                if canvas.engine_ref().replay_active() != replay_drawing {
+                    let widget_flags = if replay_drawing {
+                        canvas.engine_mut().start_replay(appwindow.replay_speed())
+                    } else {
+                        canvas.engine_mut().stop_replay()
+                    };
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+                action.set_state(&replay_drawing.to_variant());
+            }
+        ));
+
         // Pen style
         action_pen_style.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -438,6 +697,104 @@ impl RnAppWindow {
             }
         ));
 
+        // Change back to the previous pen style
+        action_pen_style_previous.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().change_to_previous_pen_style();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Switch to a width preset of the current pen
+        action_pen_width_preset.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(index) = target.and_then(|t| t.get::<i32>()) else {
+                    error!("Activated pen-width-preset action with invalid target");
+                    return;
+                };
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                if index < 0 {
+                    return;
+                }
+                canvas.apply_width_preset(index as usize);
+            }
+        ));
+
+        // Pen presets
+        action_pen_preset_add.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    #[weak]
+                    canvas,
+                    async move {
+                        dialogs::dialog_add_pen_preset(&appwindow, &canvas).await;
+                    }
+                ));
+            }
+        ));
+
+        action_pen_preset_apply.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(index) = target.and_then(|t| t.get::<i32>()) else {
+                    error!("Activated pen-preset-apply action with invalid target");
+                    return;
+                };
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                if index < 0 {
+                    return;
+                }
+                let widget_flags = canvas.engine_mut().apply_pen_preset(index as usize);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        action_pen_preset_remove.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(index) = target.and_then(|t| t.get::<i32>()) else {
+                    error!("Activated pen-preset-remove action with invalid target");
+                    return;
+                };
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                if index < 0 {
+                    return;
+                }
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    #[weak]
+                    canvas,
+                    async move {
+                        dialogs::dialog_remove_pen_preset(&appwindow, &canvas, index as usize)
+                            .await;
+                    }
+                ));
+            }
+        ));
+
         // Tab actions
         action_active_tab_move_left.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -568,47 +925,52 @@ impl RnAppWindow {
             }
         ));
 
-        // invert color brightness of selection
-        action_selection_invert_color.connect_activate(clone!(
+        // Lock Selection
+        action_selection_lock.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let Some(canvas) = appwindow.active_tab_canvas() else {
                     return;
                 };
-                let widget_flags = canvas.engine_mut().invert_selection_colors();
+                let widget_flags = canvas.engine_mut().lock_selection();
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // select all strokes
-        action_selection_select_all.connect_activate(clone!(
+        // Unlock all strokes
+        action_unlock_all_strokes.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let Some(canvas) = appwindow.active_tab_canvas() else {
                     return;
                 };
-                let widget_flags = canvas.engine_mut().select_all_strokes();
+                let widget_flags = canvas.engine_mut().unlock_all_strokes();
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // deselect all strokes
-        action_selection_deselect_all.connect_activate(clone!(
+        // Browse and restore recently deleted strokes
+        action_recently_deleted.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
-                let Some(canvas) = appwindow.active_tab_canvas() else {
-                    return;
-                };
-                let widget_flags = canvas.engine_mut().deselect_all_strokes();
-                appwindow.handle_widget_flags(widget_flags, &canvas);
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(canvas) = appwindow.active_tab_canvas() else {
+                            return;
+                        };
+                        dialogs::dialog_recently_deleted(&appwindow, &canvas).await;
+                    }
+                ));
             }
         ));
 
-        // Clear doc
-        action_clear_doc.connect_activate(clone!(
+        // Browse and restore revisions that are stored in the document file
+        action_revision_history.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
@@ -619,175 +981,145 @@ impl RnAppWindow {
                         let Some(canvas) = appwindow.active_tab_canvas() else {
                             return;
                         };
-                        dialogs::dialog_clear_doc(&appwindow, &canvas).await;
+                        dialogs::dialog_revision_history(&appwindow, &canvas).await;
                     }
                 ));
             }
         ));
 
-        // Undo stroke
-        action_undo_stroke.connect_activate(clone!(
+        // Search and replace text
+        action_search_text.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
-                let Some(canvas) = appwindow.active_tab_canvas() else {
-                    return;
-                };
-                let widget_flags = canvas.engine_mut().undo(Instant::now());
-                appwindow.handle_widget_flags(widget_flags, &canvas);
+                appwindow.overlays().textsearchbar().toggle(&appwindow);
             }
         ));
 
-        // Redo stroke
-        action_redo_stroke.connect_activate(clone!(
+        // flip selection
+        action_selection_flip_horizontal.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let Some(canvas) = appwindow.active_tab_canvas() else {
                     return;
                 };
-                let widget_flags = canvas.engine_mut().redo(Instant::now());
+                let widget_flags = canvas.engine_mut().flip_selection(FlipAxis::Horizontal);
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
-
-        // Zoom reset
-        action_zoom_reset.connect_activate(clone!(
+        action_selection_flip_vertical.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let Some(canvas) = appwindow.active_tab_canvas() else {
                     return;
                 };
-                let viewport_center = canvas.engine_ref().camera.viewport_center();
-                let new_zoom = Camera::ZOOM_DEFAULT;
-                let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
-                widget_flags |= canvas
-                    .engine_mut()
-                    .camera
-                    .set_viewport_center(viewport_center);
-                appwindow.handle_widget_flags(widget_flags, &canvas)
+                let widget_flags = canvas.engine_mut().flip_selection(FlipAxis::Vertical);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Zoom fit to width
-        action_zoom_fit_width.connect_activate(clone!(
-            #[weak(rename_to=appwindow)]
-            self,
-            move |_, _| {
-                let Some(wrapper) = appwindow.active_tab_wrapper() else {
-                    return;
-                };
-                let canvas = wrapper.canvas();
-                let viewport_center = canvas.engine_ref().camera.viewport_center();
-                let new_zoom = f64::from(wrapper.scroller().width())
-                    / (wrapper.canvas().engine_ref().document.format.width()
-                        + 2.0 * Camera::OVERSHOOT_HORIZONTAL);
-                let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
-                widget_flags |= canvas
-                    .engine_mut()
-                    .camera
-                    .set_viewport_center(viewport_center);
-                appwindow.handle_widget_flags(widget_flags, &canvas)
-            }
-        ));
+        // change the order in which the selection is drawn
+        for (action, change) in [
+            (&action_selection_bring_to_front, ZOrderChange::BringToFront),
+            (&action_selection_bring_forward, ZOrderChange::BringForward),
+            (&action_selection_send_backward, ZOrderChange::SendBackward),
+            (&action_selection_send_to_back, ZOrderChange::SendToBack),
+        ] {
+            action.connect_activate(clone!(
+                #[weak(rename_to=appwindow)]
+                self,
+                move |_, _| {
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+                    let widget_flags = canvas.engine_mut().change_selection_z_order(change);
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            ));
+        }
 
-        // Zoom in
-        action_zoomin.connect_activate(clone!(
+        // crop the selected image
+        action_selection_crop_image.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let Some(canvas) = appwindow.active_tab_canvas() else {
                     return;
                 };
-                let viewport_center = canvas.engine_ref().camera.viewport_center();
-                let new_zoom =
-                    canvas.engine_ref().camera.total_zoom() * (1.0 + RnCanvas::ZOOM_SCROLL_STEP);
-                let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
-                widget_flags |= canvas
-                    .engine_mut()
-                    .camera
-                    .set_viewport_center(viewport_center);
-                appwindow.handle_widget_flags(widget_flags, &canvas)
+                let widget_flags = canvas.engine_mut().toggle_image_crop();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
-
-        // Zoom out
-        action_zoomout.connect_activate(clone!(
+        action_selection_reset_image_crop.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let Some(canvas) = appwindow.active_tab_canvas() else {
                     return;
                 };
-                let viewport_center = canvas.engine_ref().camera.viewport_center();
-                let new_zoom = canvas.engine_ref().camera.total_zoom()
-                    * (1.0 / (1.0 + RnCanvas::ZOOM_SCROLL_STEP));
-                let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
-                widget_flags |= canvas
-                    .engine_mut()
-                    .camera
-                    .set_viewport_center(viewport_center);
-                appwindow.handle_widget_flags(widget_flags, &canvas)
+                let widget_flags = canvas.engine_mut().reset_selection_image_crop();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Add page to doc in fixed size mode
-        action_add_page_to_doc.connect_activate(clone!(
+        // attach a caption to the selected image
+        action_selection_add_caption.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
-            move |_action_add_page_to_doc, _| {
+            move |_, _| {
                 let Some(canvas) = appwindow.active_tab_canvas() else {
                     return;
                 };
-                let widget_flags = canvas.engine_mut().doc_add_page_fixed_size();
+                let widget_flags = canvas
+                    .engine_mut()
+                    .add_caption_to_selected_image(gettext("Caption"));
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Remove page from doc in fixed size mode
-        action_remove_page_from_doc.connect_activate(clone!(
+        // let the selected text flow along the selected path
+        action_selection_text_along_path.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let Some(canvas) = appwindow.active_tab_canvas() else {
                     return;
                 };
-                let widget_flags = canvas.engine_mut().doc_remove_page_fixed_size();
+                let widget_flags = canvas.engine_mut().toggle_selected_text_along_path();
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Resize to fit content
-        action_resize_to_fit_content.connect_activate(clone!(
+        // edit the nodes of the selected bezier path
+        action_selection_edit_nodes.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let Some(canvas) = appwindow.active_tab_canvas() else {
                     return;
                 };
-                let widget_flags = canvas.engine_mut().doc_resize_to_fit_content();
+                let widget_flags = canvas.engine_mut().toggle_edit_nodes();
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // Return to the origin page
-        action_return_origin_page.connect_activate(clone!(
+        // invert color brightness of selection
+        action_selection_invert_color.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
                 let Some(canvas) = appwindow.active_tab_canvas() else {
                     return;
                 };
-                let widget_flags = canvas
-                    .engine_mut()
-                    .return_to_origin(canvas.parent().map(|p| p.width() as f64));
+                let widget_flags = canvas.engine_mut().invert_selection_colors();
                 appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
 
-        // New doc
-        action_new_doc.connect_activate(clone!(
+        // replace colors of selection
+        action_selection_remap_colors.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
@@ -798,22 +1130,401 @@ impl RnAppWindow {
                         let Some(canvas) = appwindow.active_tab_canvas() else {
                             return;
                         };
-                        dialogs::dialog_new_doc(&appwindow, &canvas).await;
+                        dialogs::dialog_remap_selection_colors(&appwindow, &canvas).await;
                     }
                 ));
             }
         ));
 
-        // Open doc
-        action_open_doc.connect_activate(clone!(
+        // Simplify the selected brush strokes
+        action_selection_simplify.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
-                glib::spawn_future_local(clone!(
-                    #[weak]
-                    appwindow,
-                    async move {
-                        dialogs::import::filedialog_open_doc(&appwindow).await;
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().simplify_selection();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // select all strokes
+        action_selection_select_all.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().select_all_strokes();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // expand the selection to similar strokes
+        action_selection_select_similar.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(similar) = target.and_then(|t| t.str()) else {
+                    error!("Activated selection-select-similar action without target");
+                    return;
+                };
+                let similar = match SelectSimilar::from_str(similar) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        error!("Activated selection-select-similar action with invalid target, Err: {e:}");
+                        return;
+                    }
+                };
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().select_similar(similar);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // deselect all strokes
+        action_selection_deselect_all.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().deselect_all_strokes();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Clear doc
+        action_clear_doc.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(canvas) = appwindow.active_tab_canvas() else {
+                            return;
+                        };
+                        dialogs::dialog_clear_doc(&appwindow, &canvas).await;
+                    }
+                ));
+            }
+        ));
+
+        // Scale document content
+        action_scale_doc_content.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(canvas) = appwindow.active_tab_canvas() else {
+                            return;
+                        };
+                        dialogs::dialog_scale_doc_content(&appwindow, &canvas).await;
+                    }
+                ));
+            }
+        ));
+
+        // Go to page
+        action_go_to_page.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                dialogs::dialog_go_to_page(&appwindow, &canvas);
+            }
+        ));
+
+        // Document properties
+        action_doc_properties.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                dialogs::dialog_doc_properties(&appwindow, &canvas);
+            }
+        ));
+
+        // Insert table
+        action_insert_table.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(canvas) = appwindow.active_tab_canvas() else {
+                            return;
+                        };
+                        dialogs::dialog_insert_table(&appwindow, &canvas).await;
+                    }
+                ));
+            }
+        ));
+
+        // Undo stroke
+        action_undo_stroke.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().undo(Instant::now());
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Redo stroke
+        action_redo_stroke.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().redo(Instant::now());
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Zoom reset
+        action_zoom_reset.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().zoom_keep_center(Camera::ZOOM_DEFAULT);
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
+        // Zoom to value
+        action_zoom_to_value.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(zoom) = target.and_then(|t| t.get::<f64>()) else {
+                    error!("Activated zoom-to-value action with invalid target");
+                    return;
+                };
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().zoom_keep_center(zoom);
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
+        // Zoom fit to width
+        action_zoom_fit_width.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().zoom_fit_page_width();
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
+        // Zoom fit to height
+        action_zoom_fit_height.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().zoom_fit_page_height();
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
+        // Zoom fit to the whole page
+        action_zoom_fit_page.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().zoom_fit_page();
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
+        // Zoom to selection
+        action_zoom_to_selection.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().zoom_to_selection();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Zoom to fit all strokes
+        action_zoom_to_fit_strokes.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().zoom_to_fit_strokes();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Zoom in
+        action_zoomin.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvaswrapper) = appwindow.active_tab_wrapper() else {
+                    return;
+                };
+                let canvas = canvaswrapper.canvas();
+                let new_zoom =
+                    canvas.engine_ref().camera.total_zoom() * (1.0 + RnCanvas::ZOOM_SCROLL_STEP);
+                let widget_flags = canvaswrapper.zoom_w_timeout_around_anchor(new_zoom);
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
+        // Zoom out
+        action_zoomout.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvaswrapper) = appwindow.active_tab_wrapper() else {
+                    return;
+                };
+                let canvas = canvaswrapper.canvas();
+                let new_zoom = canvas.engine_ref().camera.total_zoom()
+                    * (1.0 / (1.0 + RnCanvas::ZOOM_SCROLL_STEP));
+                let widget_flags = canvaswrapper.zoom_w_timeout_around_anchor(new_zoom);
+                appwindow.handle_widget_flags(widget_flags, &canvas)
+            }
+        ));
+
+        // Add page to doc in fixed size mode
+        action_add_page_to_doc.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_action_add_page_to_doc, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().doc_add_page_fixed_size();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Remove page from doc in fixed size mode
+        action_remove_page_from_doc.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().doc_remove_page_fixed_size();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Resize to fit content
+        action_resize_to_fit_content.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().doc_resize_to_fit_content();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Return to the origin page
+        action_return_origin_page.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas
+                    .engine_mut()
+                    .return_to_origin(canvas.parent().map(|p| p.width() as f64));
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // Clear guides
+        action_clear_guides.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().clear_guides();
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        // New doc
+        action_new_doc.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(canvas) = appwindow.active_tab_canvas() else {
+                            return;
+                        };
+                        dialogs::dialog_new_doc(&appwindow, &canvas).await;
+                    }
+                ));
+            }
+        ));
+
+        // Open doc
+        action_open_doc.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::import::filedialog_open_doc(&appwindow).await;
                     }
                 ));
             }
@@ -832,7 +1543,7 @@ impl RnAppWindow {
                             return;
                         };
 
-                        if let Some(output_file) = canvas.output_file() {
+                        if let Some(output_file) = canvas.writable_output_file() {
                             appwindow.overlays().progressbar_start_pulsing();
 
                             if let Err(e) = canvas.save_document_to_file(&output_file).await {
@@ -878,69 +1589,116 @@ impl RnAppWindow {
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
-                // TODO: Expose these variables as options in the print dialog
-                let draw_background = true;
-                let draw_pattern = true;
-                let optimize_printing = false;
-                let page_order = SplitOrder::default();
-                let margin = 0.0;
                 let Some(canvas) = appwindow.active_tab_canvas() else {
                     return;
                 };
-                let pages_content = canvas.engine_ref().extract_pages_content(page_order);
-                let n_pages = pages_content.len();
-
-                appwindow.overlays().progressbar_start_pulsing();
+                appwindow.print_doc(&canvas);
+            }
+        ));
 
-                let print_op = PrintOperation::builder().unit(Unit::None).build();
+        // Insert text snippet
+        action_insert_snippet.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(snippet) = target.and_then(|t| t.get::<String>()) else {
+                    error!("Activated insert-snippet action with invalid target");
+                    return;
+                };
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let title = canvas.doc_title_display();
+                let widget_flags = canvas.engine_mut().insert_snippet(&snippet, title);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
 
-                print_op.connect_begin_print(clone!(move |print_op, _print_cx| {
-                    print_op.set_n_pages(n_pages as i32);
-                }));
+        // Select workspace by index
+        action_select_workspace.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(index) = target.and_then(|t| t.get::<i32>()) else {
+                    error!("Activated select-workspace action with invalid target");
+                    return;
+                };
+                let workspacesbar = appwindow.sidebar().workspacebrowser().workspacesbar();
+                // Don't clamp to the last workspace, only existing ones can be selected
+                if index >= 0 && (index as u32) < workspacesbar.n_workspaces() {
+                    workspacesbar.select_workspace_by_index(index as u32);
+                }
+            }
+        ));
 
-                print_op.connect_draw_page(clone!(move |_print_op, print_cx, page_no| {
-                    let page_content = &pages_content[page_no as usize];
-                    let page_bounds = page_content.bounds.unwrap().loosened(margin);
-                    let print_scale = (print_cx.width() / page_bounds.extents()[0])
-                        .min(print_cx.height() / page_bounds.extents()[1]);
-                    let cairo_cx = print_cx.cairo_context();
+        // Import
+        action_import_file.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::import::filedialog_import_file(&appwindow, None).await;
+                    }
+                ));
+            }
+        ));
 
-                    cairo_cx.scale(print_scale, print_scale);
-                    cairo_cx.translate(-page_bounds.mins[0], -page_bounds.mins[1]);
-                    if let Err(e) = page_content.draw_to_cairo(
-                        &cairo_cx,
-                        draw_background,
-                        draw_pattern,
-                        optimize_printing,
-                        margin,
-                        Engine::STROKE_EXPORT_IMAGE_SCALE,
-                    ) {
-                        error!("Drawing page no: {page_no} while printing failed, Err: {e:?}");
+        // Insert a photo from the camera
+        action_insert_camera_capture.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(canvas) = appwindow.active_tab_canvas() else {
+                            return;
+                        };
+                        appwindow.overlays().progressbar_start_pulsing();
+                        match appwindow.insert_camera_capture(&canvas).await {
+                            Ok(_) => appwindow.overlays().progressbar_finish(),
+                            Err(e) if e.is::<ImportCancelled>() => {
+                                debug!("Inserting photo from the camera was cancelled");
+                                appwindow.overlays().progressbar_abort();
+                            }
+                            Err(e) => {
+                                error!("Inserting photo from the camera failed, Err: {e:?}");
+                                appwindow.overlays().dispatch_toast_error(&gettext(
+                                    "Inserting photo from the camera failed",
+                                ));
+                                appwindow.overlays().progressbar_abort();
+                            }
+                        }
                     }
-                }));
+                ));
+            }
+        ));
 
-                print_op.connect_status_changed(clone!(move |print_op| {
-                    debug!(
-                        "Print operation status has changed to: {:?}",
-                        print_op.status()
-                    );
-                }));
-
-                // Run the print op
-                if let Err(e) = print_op.run(PrintOperationAction::PrintDialog, Some(&appwindow)) {
-                    error!("Running print operation failed , Err: {e:?}");
-                    appwindow
-                        .overlays()
-                        .dispatch_toast_error(&gettext("Printing document failed"));
-                    appwindow.overlays().progressbar_abort();
-                } else {
-                    appwindow.overlays().progressbar_finish();
-                }
+        // Import at the context menu position
+        action_import_file_contextmenu.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(wrapper) = appwindow.active_tab_wrapper() else {
+                    return;
+                };
+                let target_pos = wrapper.last_contextmenu_doc_pos();
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::import::filedialog_import_file(&appwindow, target_pos).await;
+                    }
+                ));
             }
         ));
 
-        // Import
-        action_import_file.connect_activate(clone!(
+        // Append rnote file
+        action_append_rnote_file.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
             self,
             move |_, _| {
@@ -948,7 +1706,7 @@ impl RnAppWindow {
                     #[weak]
                     appwindow,
                     async move {
-                        dialogs::import::filedialog_import_file(&appwindow).await;
+                        dialogs::import::filedialog_append_rnote_file(&appwindow).await;
                     }
                 ));
             }
@@ -990,6 +1748,99 @@ impl RnAppWindow {
             }
         ));
 
+        // Share document by email
+        action_share_doc_email.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, target| {
+                let Some(format) = target
+                    .and_then(|t| t.get::<String>())
+                    .and_then(|t| ShareFormat::try_from(t.as_str()).ok())
+                else {
+                    error!("Activated share-doc-email action with invalid target");
+                    return;
+                };
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(canvas) = appwindow.active_tab_canvas() else {
+                            return;
+                        };
+                        appwindow.overlays().progressbar_start_pulsing();
+                        if let Err(e) = appwindow.share_doc_email(&canvas, format).await {
+                            error!("Sharing document by email failed, Err: {e:?}");
+                            appwindow
+                                .overlays()
+                                .dispatch_toast_error(&gettext("Sharing document failed"));
+                            appwindow.overlays().progressbar_abort();
+                        } else {
+                            appwindow.overlays().progressbar_finish();
+                        }
+                    }
+                ));
+            }
+        ));
+
+        // Share document with another application
+        action_share_doc_open_with.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(canvas) = appwindow.active_tab_canvas() else {
+                            return;
+                        };
+                        appwindow.overlays().progressbar_start_pulsing();
+                        if let Err(e) = appwindow.share_doc_open_with(&canvas).await {
+                            error!("Sharing document with another application failed, Err: {e:?}");
+                            appwindow
+                                .overlays()
+                                .dispatch_toast_error(&gettext("Sharing document failed"));
+                            appwindow.overlays().progressbar_abort();
+                        } else {
+                            appwindow.overlays().progressbar_finish();
+                        }
+                    }
+                ));
+            }
+        ));
+
+        // Serve document in the local network
+        action_serve_doc.connect_change_state(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |action, state_request| {
+                let serve_doc = state_request.unwrap().get::<bool>().unwrap();
+                if !serve_doc {
+                    appwindow.stop_serving_doc();
+                    appwindow.overlays().dispatch_toast_text(
+                        &gettext("Stopped serving document"),
+                        crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                    );
+                    return;
+                }
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let url = match appwindow.start_serving_doc(&canvas) {
+                    Ok(url) => url,
+                    Err(e) => {
+                        error!("Starting to serve document failed, Err: {e:?}");
+                        appwindow
+                            .overlays()
+                            .dispatch_toast_error(&gettext("Serving document failed"));
+                        return;
+                    }
+                };
+                action.set_state(&true.to_variant());
+                dialogs::dialog_serve_doc(&appwindow, &url);
+            }
+        ));
+
         // Export selection
         action_export_selection.connect_activate(clone!(
             #[weak(rename_to=appwindow)]
@@ -1016,6 +1867,64 @@ impl RnAppWindow {
             }
         ));
 
+        // Export viewport
+        action_export_viewport.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(canvas) = appwindow.active_tab_canvas() else {
+                            return;
+                        };
+                        dialogs::export::dialog_export_viewport_w_prefs(&appwindow, &canvas).await;
+                    }
+                ));
+            }
+        ));
+
+        // Copy the selection as TikZ code
+        action_export_selection_tikz.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let Some(tikz) = canvas.engine_ref().export_selection_as_tikz() else {
+                    appwindow.overlays().dispatch_toast_error(&gettext(
+                        "Copying as TikZ failed, nothing is selected",
+                    ));
+                    return;
+                };
+                appwindow.clipboard().set_text(&tikz);
+                appwindow.overlays().dispatch_toast_text(
+                    &gettext("Copied the selection as TikZ code"),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+            }
+        ));
+
+        // Export replay
+        action_export_replay.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        let Some(canvas) = appwindow.active_tab_canvas() else {
+                            return;
+                        };
+                        dialogs::export::filedialog_export_replay(&appwindow, &canvas).await;
+                    }
+                ));
+            }
+        ));
+
         // Clipboard copy
         action_clipboard_copy.connect_activate(clone!(#[weak(rename_to=appwindow)] self, move |_, _| {
             glib::spawn_future_local(clone!(#[weak] appwindow, async move {
@@ -1121,16 +2030,40 @@ impl RnAppWindow {
                 let Some(wrapper) = appwindow.active_tab_wrapper() else {
                     return;
                 };
-                let canvas = wrapper.canvas();
+                appwindow.clipboard_paste(wrapper.last_contextmenu_doc_pos());
+            }
+        ));
 
-                let last_contextmenu_pos = wrapper.last_contextmenu_pos().map(|vec2| {
-                    let p = graphene::Point::new(vec2.x as f32, vec2.y as f32);
-                    (canvas.engine_ref().camera.transform().inverse()
-                        * na::point![p.x() as f64, p.y() as f64])
-                    .coords
-                });
+        action_insert_text_contextmenu.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(wrapper) = appwindow.active_tab_wrapper() else {
+                    return;
+                };
+                let Some(pos) = wrapper.last_contextmenu_doc_pos() else {
+                    return;
+                };
+                let canvas = wrapper.canvas();
+                let widget_flags = canvas.engine_mut().start_text_at(pos);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+                canvas.grab_focus();
+            }
+        ));
 
-                appwindow.clipboard_paste(last_contextmenu_pos);
+        action_select_page_contextmenu.connect_activate(clone!(
+            #[weak(rename_to=appwindow)]
+            self,
+            move |_, _| {
+                let Some(wrapper) = appwindow.active_tab_wrapper() else {
+                    return;
+                };
+                let Some(pos) = wrapper.last_contextmenu_doc_pos() else {
+                    return;
+                };
+                let canvas = wrapper.canvas();
+                let widget_flags = canvas.engine_mut().select_all_strokes_on_page(pos);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
             }
         ));
     }
@@ -1140,6 +2073,9 @@ impl RnAppWindow {
 
         app.set_accels_for_action("win.active-tab-close", &["<Ctrl>w"]);
         app.set_accels_for_action("win.fullscreen", &["F11"]);
+        app.set_accels_for_action("win.distraction-free-mode", &["<Shift>F11"]);
+        app.set_accels_for_action("win.presentation-mode", &["F5"]);
+        app.set_accels_for_action("win.righthanded", &["<Ctrl><Shift>h"]);
         app.set_accels_for_action("win.keyboard-shortcuts", &["<Ctrl>question"]);
         app.set_accels_for_action("win.toggle-overview", &["<Ctrl><Shift>o"]);
         app.set_accels_for_action("win.open-canvasmenu", &["F9"]);
@@ -1150,6 +2086,7 @@ impl RnAppWindow {
         app.set_accels_for_action("win.new-tab", &["<Ctrl>t"]);
         app.set_accels_for_action("win.snap-positions", &["<Ctrl><Shift>p"]);
         app.set_accels_for_action("win.clear-doc", &["<Ctrl>l"]);
+        app.set_accels_for_action("win.search-text", &["<Ctrl>f"]);
         app.set_accels_for_action("win.print-doc", &["<Ctrl>p"]);
         app.set_accels_for_action("win.add-page-to-doc", &["<Ctrl><Shift>a"]);
         app.set_accels_for_action("win.remove-page-from-doc", &["<Ctrl><Shift>r"]);
@@ -1159,6 +2096,22 @@ impl RnAppWindow {
         );
         app.set_accels_for_action("win.zoom-reset", &["<Ctrl>0", "<Ctrl>KP_0"]);
         app.set_accels_for_action("win.zoom-out", &["<Ctrl>minus", "<Ctrl>KP_Subtract"]);
+        app.set_accels_for_action("win.zoom-fit-width", &["<Ctrl>7", "<Ctrl>KP_7"]);
+        app.set_accels_for_action("win.zoom-fit-height", &["<Ctrl>8", "<Ctrl>KP_8"]);
+        app.set_accels_for_action("win.zoom-fit-page", &["<Ctrl>9", "<Ctrl>KP_9"]);
+        for (i, zoom) in Camera::ZOOM_PRESETS.iter().enumerate() {
+            app.set_accels_for_action(
+                &format!("win.zoom-to-value({zoom:?})"),
+                &[&format!("<Ctrl><Shift>{}", i + 1)],
+            );
+        }
+        app.set_accels_for_action("win.zoom-to-selection", &["<Ctrl><Shift>e"]);
+        app.set_accels_for_action("win.go-to-page", &["<Ctrl>g"]);
+        app.set_accels_for_action("win.selection-bring-to-front", &["<Alt>Home"]);
+        app.set_accels_for_action("win.selection-bring-forward", &["<Alt>Page_Up"]);
+        app.set_accels_for_action("win.selection-send-backward", &["<Alt>Page_Down"]);
+        app.set_accels_for_action("win.selection-send-to-back", &["<Alt>End"]);
+        app.set_accels_for_action("win.zoom-to-fit-strokes", &["<Ctrl><Shift>f"]);
         app.set_accels_for_action("win.import-file", &["<Ctrl>i"]);
         app.set_accels_for_action("win.undo", &["<Ctrl>z"]);
         app.set_accels_for_action("win.redo", &["<Ctrl><Shift>z"]);
@@ -1171,6 +2124,28 @@ impl RnAppWindow {
         app.set_accels_for_action("win.pen-style::eraser", &["<Ctrl>4", "<Ctrl>KP_4"]);
         app.set_accels_for_action("win.pen-style::selector", &["<Ctrl>5", "<Ctrl>KP_5"]);
         app.set_accels_for_action("win.pen-style::tools", &["<Ctrl>6", "<Ctrl>KP_6"]);
+        app.set_accels_for_action("win.pen-style-previous", &["<Ctrl>grave"]);
+        // Ctrl+<number> is already taken by the pen styles, Alt+<number> by the tabs
+        for i in 0..9 {
+            app.set_accels_for_action(
+                &format!("win.select-workspace({i})"),
+                &[&format!("<Ctrl><Alt>{}", i + 1)],
+            );
+        }
+        // Ctrl+Shift+<number> is taken by the zoom presets
+        for i in 0..3 {
+            app.set_accels_for_action(
+                &format!("win.pen-width-preset({i})"),
+                &[&format!("<Ctrl><Shift><Alt>{}", i + 1)],
+            );
+        }
+        // Alt+<number> is taken by the tabs, so the pen presets use Shift+Alt+<number>
+        for i in 0..PensConfig::PRESETS_MAX {
+            app.set_accels_for_action(
+                &format!("win.pen-preset-apply({i})"),
+                &[&format!("<Shift><Alt>{}", i + 1)],
+            );
+        }
 
         // shortcuts for devel build
         if config::PROFILE.to_lowercase().as_str() == "devel" {
@@ -1308,11 +2283,17 @@ impl RnAppWindow {
                             if !acc.is_empty() {
                                 match crate::utils::str_from_u8_nul_utf8(&acc) {
                                 Ok(text) => {
-                                    if let Err(e) = canvas.load_in_vectorimage_bytes(text.as_bytes().to_vec(), target_pos, appwindow.respect_borders()).await {
-                                        error!(
-                                            "Loading VectorImage bytes failed while pasting as Svg failed, Err: {e:?}"
-                                        );
-                                    };
+                                    match canvas.load_in_vectorimage_bytes(&appwindow, text.as_bytes().to_vec(), target_pos, appwindow.respect_borders(), true).await {
+                                        Ok(()) => {}
+                                        Err(e) if e.is::<ImportCancelled>() => {
+                                            debug!("Pasting clipboard as Svg was cancelled");
+                                        }
+                                        Err(e) => {
+                                            error!(
+                                                "Loading VectorImage bytes failed while pasting as Svg failed, Err: {e:?}"
+                                            );
+                                        }
+                                    }
                                 }
                                 Err(e) => error!("Failed to get string from clipboard data while pasting as Svg, Err: {e:?}"),
                             }
@@ -1331,6 +2312,8 @@ impl RnAppWindow {
             || content_formats.contain_mime_type("image/jpg")
             || content_formats.contain_mime_type("image/tiff")
             || content_formats.contain_mime_type("image/bmp")
+            // Screenshot tools and other local apps might only offer a texture
+            || content_formats.contain_gtype(gdk::Texture::static_type())
         {
             const MIMES: [&str; 5] = [
                 "image/png",
@@ -1339,43 +2322,60 @@ impl RnAppWindow {
                 "image/tiff",
                 "image/bmp",
             ];
-            if let Some(mime_type) = MIMES
+            let mime_type = MIMES
                 .into_iter()
                 .find(|&mime| content_formats.contain_mime_type(mime))
-            {
-                glib::spawn_future_local(clone!(
-                    #[weak]
-                    canvas,
-                    #[weak(rename_to=appwindow)]
-                    self,
-                    async move {
-                        debug!("Recognized clipboard content: bitmap image");
-
-                        match appwindow.clipboard().read_texture_future().await {
-                            Ok(Some(texture)) => {
-                                if let Err(e) = canvas
-                                    .load_in_bitmapimage_bytes(
-                                        texture.save_to_png_bytes().to_vec(),
-                                        target_pos,
-                                        appwindow.respect_borders(),
-                                    )
-                                    .await
-                                {
+                .unwrap_or("texture");
+            glib::spawn_future_local(clone!(
+                #[weak]
+                canvas,
+                #[weak(rename_to=appwindow)]
+                self,
+                async move {
+                    debug!("Recognized clipboard content: bitmap image");
+
+                    match appwindow.clipboard().read_texture_future().await {
+                        Ok(Some(texture)) => {
+                            let bytes = texture.save_to_png_bytes();
+                            let Some(downscale) =
+                                dialogs::import::bitmapimage_size_check_and_dialog(
+                                    &appwindow, &canvas, &bytes,
+                                )
+                                .await
+                            else {
+                                return;
+                            };
+                            match canvas
+                                .load_in_bitmapimage_bytes(
+                                    &appwindow,
+                                    bytes.to_vec(),
+                                    target_pos,
+                                    appwindow.respect_borders(),
+                                    true,
+                                    downscale,
+                                )
+                                .await
+                            {
+                                Ok(()) => {}
+                                Err(e) if e.is::<ImportCancelled>() => {
+                                    debug!("Pasting clipboard as {mime_type} was cancelled");
+                                }
+                                Err(e) => {
                                     error!(
                                         "Loading bitmap image bytes failed while pasting clipboard as {mime_type}, Err: {e:?}"
                                     );
-                                };
-                            }
-                            Ok(None) => {}
-                            Err(e) => {
-                                error!(
-                                    "Reading clipboard text failed while pasting clipboard as {mime_type}, Err: {e:?}"
-                                );
+                                }
                             }
-                        };
-                    }
-                ));
-            }
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            error!(
+                                "Reading clipboard text failed while pasting clipboard as {mime_type}, Err: {e:?}"
+                            );
+                        }
+                    };
+                }
+            ));
         } else if content_formats.contain_mime_type("text/plain")
             || content_formats.contain_mime_type("text/plain;charset=utf-8")
         {
@@ -1425,6 +2425,8 @@ async fn collect_clipboard_data(input_stream: InputStream) -> Vec<u8> {
                 if n == 0 {
                     break;
                 }
+                // the buffer is only filled up to `n`, the remaining bytes must not be collected
+                bytes.truncate(n);
                 acc.append(&mut bytes);
             }
             Err(e) => {
@@ -52,6 +52,42 @@ impl RnAppWindow {
             .get_no_changes()
             .build();
 
+        // tool palette
+        app_settings
+            .bind(
+                "show-tool-palette",
+                &self.overlays().toolpalette(),
+                "visible",
+            )
+            .get_no_changes()
+            .build();
+        app_settings
+            .bind(
+                "tool-palette-offset-x",
+                &self.overlays().toolpalette(),
+                "offset-x",
+            )
+            .get_no_changes()
+            .build();
+        app_settings
+            .bind(
+                "tool-palette-offset-y",
+                &self.overlays().toolpalette(),
+                "offset-y",
+            )
+            .get_no_changes()
+            .build();
+
+        // pens sidebar
+        app_settings
+            .bind(
+                "pens-sidebar-compact",
+                &self.overlays().penssidebar(),
+                "compact",
+            )
+            .get_no_changes()
+            .build();
+
         // block pinch zoom
         app_settings
             .bind("block-pinch-zoom", self, "block-pinch-zoom")
@@ -64,12 +100,96 @@ impl RnAppWindow {
             .get_no_changes()
             .build();
 
+        // palm rejection timeout
+        app_settings
+            .bind(
+                "palm-rejection-timeout-ms",
+                self,
+                "palm-rejection-timeout-ms",
+            )
+            .get_no_changes()
+            .build();
+
+        // mouse input
+        app_settings
+            .bind(
+                "shift-scroll-horizontal",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .input_shift_scroll_horizontal_row(),
+                "active",
+            )
+            .get_no_changes()
+            .build();
+        app_settings
+            .bind(
+                "wheel-zoom",
+                &self.sidebar().settings_panel().input_wheel_zoom_row(),
+                "active",
+            )
+            .get_no_changes()
+            .build();
+        app_settings
+            .bind(
+                "zoom-around-pointer",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .input_zoom_around_pointer_row(),
+                "active",
+            )
+            .get_no_changes()
+            .build();
+        app_settings
+            .bind(
+                "secondary-drag-pan",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .input_secondary_drag_pan_row(),
+                "active",
+            )
+            .get_no_changes()
+            .build();
+
+        // replay speed
+        app_settings
+            .bind("replay-speed", self, "replay-speed")
+            .get_no_changes()
+            .build();
+
+        // blocked input devices
+        app_settings
+            .bind("blocked-input-devices", self, "blocked-input-devices")
+            .get_no_changes()
+            .build();
+
+        // distraction free mode
+        app_settings
+            .bind("distraction-free-mode", self, "distraction-free-mode")
+            .get_no_changes()
+            .build();
+
         // respect borders
         app_settings
             .bind("respect-borders", self, "respect-borders")
             .get_no_changes()
             .build();
 
+        // restore view state
+        app_settings
+            .bind(
+                "restore-view-state",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .general_restore_view_state_row(),
+                "active",
+            )
+            .get_no_changes()
+            .build();
+
         // show scrollbars
         app_settings
             .bind(
@@ -83,6 +203,29 @@ impl RnAppWindow {
             .get_no_changes()
             .build();
 
+        // show rulers
+        app_settings
+            .bind(
+                "show-rulers",
+                &self.sidebar().settings_panel().general_show_rulers_row(),
+                "active",
+            )
+            .get_no_changes()
+            .build();
+
+        // show page thumbnails
+        app_settings
+            .bind(
+                "show-page-thumbnails",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .general_show_page_thumbnails_row(),
+                "active",
+            )
+            .get_no_changes()
+            .build();
+
         // inertial scrolling
         app_settings
             .bind(
@@ -153,6 +296,32 @@ impl RnAppWindow {
             )
         };
 
+        // accent color
+        app_settings
+            .bind(
+                "custom-accent-color",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .general_custom_accent_color_row(),
+                "active",
+            )
+            .get_no_changes()
+            .build();
+        app_settings
+            .bind(
+                "accent-color",
+                &self
+                    .sidebar()
+                    .settings_panel()
+                    .general_accent_color_button(),
+                "rgba",
+            )
+            .mapping(gdk_color_mapping)
+            .set_mapping(gdk_color_set_mapping)
+            .get_no_changes()
+            .build();
+
         app_settings
             .bind(
                 "active-stroke-color",
@@ -264,129 +433,6 @@ impl RnAppWindow {
             .get_no_changes()
             .build();
 
-        // brush stroke widths
-        app_settings
-            .bind(
-                "brush-width-1",
-                &self
-                    .overlays()
-                    .penssidebar()
-                    .brush_page()
-                    .stroke_width_picker()
-                    .setter_1(),
-                "stroke-width",
-            )
-            .get_no_changes()
-            .build();
-        app_settings
-            .bind(
-                "brush-width-2",
-                &self
-                    .overlays()
-                    .penssidebar()
-                    .brush_page()
-                    .stroke_width_picker()
-                    .setter_2(),
-                "stroke-width",
-            )
-            .get_no_changes()
-            .build();
-        app_settings
-            .bind(
-                "brush-width-3",
-                &self
-                    .overlays()
-                    .penssidebar()
-                    .brush_page()
-                    .stroke_width_picker()
-                    .setter_3(),
-                "stroke-width",
-            )
-            .get_no_changes()
-            .build();
-
-        // shaper stroke widths
-        app_settings
-            .bind(
-                "shaper-width-1",
-                &self
-                    .overlays()
-                    .penssidebar()
-                    .shaper_page()
-                    .stroke_width_picker()
-                    .setter_1(),
-                "stroke-width",
-            )
-            .get_no_changes()
-            .build();
-        app_settings
-            .bind(
-                "shaper-width-2",
-                &self
-                    .overlays()
-                    .penssidebar()
-                    .shaper_page()
-                    .stroke_width_picker()
-                    .setter_2(),
-                "stroke-width",
-            )
-            .get_no_changes()
-            .build();
-        app_settings
-            .bind(
-                "shaper-width-3",
-                &self
-                    .overlays()
-                    .penssidebar()
-                    .shaper_page()
-                    .stroke_width_picker()
-                    .setter_3(),
-                "stroke-width",
-            )
-            .get_no_changes()
-            .build();
-
-        // eraser widths
-        app_settings
-            .bind(
-                "eraser-width-1",
-                &self
-                    .overlays()
-                    .penssidebar()
-                    .eraser_page()
-                    .stroke_width_picker()
-                    .setter_1(),
-                "stroke-width",
-            )
-            .get_no_changes()
-            .build();
-        app_settings
-            .bind(
-                "eraser-width-2",
-                &self
-                    .overlays()
-                    .penssidebar()
-                    .eraser_page()
-                    .stroke_width_picker()
-                    .setter_2(),
-                "stroke-width",
-            )
-            .get_no_changes()
-            .build();
-        app_settings
-            .bind(
-                "eraser-width-3",
-                &self
-                    .overlays()
-                    .penssidebar()
-                    .eraser_page()
-                    .stroke_width_picker()
-                    .setter_3(),
-                "stroke-width",
-            )
-            .get_no_changes()
-            .build();
-
         Ok(())
     }
 
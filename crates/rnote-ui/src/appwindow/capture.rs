@@ -0,0 +1,262 @@
+// Imports
+use super::RnAppWindow;
+use crate::{dialogs, RnCanvas};
+#[cfg(target_os = "linux")]
+use gtk4::gio;
+use gtk4::{gdk, glib, prelude::*};
+
+impl RnAppWindow {
+    /// Capture a photo with the camera through the desktop portal and insert it as bitmap image,
+    /// centered in the viewport.
+    ///
+    /// Only available on Linux. Scanners are not supported, there is no desktop portal for them.
+    ///
+    /// Returns `false` when the access to the camera was denied by the user.
+    pub(crate) async fn insert_camera_capture(&self, canvas: &RnCanvas) -> anyhow::Result<bool> {
+        let Some(frame) = capture_camera_frame().await? else {
+            return Ok(false);
+        };
+        let texture = gdk::MemoryTexture::new(
+            frame.width,
+            frame.height,
+            gdk::MemoryFormat::R8g8b8a8,
+            &glib::Bytes::from_owned(frame.data),
+            frame.stride,
+        );
+        let bytes = texture.save_to_png_bytes();
+        let Some(downscale) =
+            dialogs::import::bitmapimage_size_check_and_dialog(self, canvas, &bytes).await
+        else {
+            return Ok(true);
+        };
+        canvas
+            .load_in_bitmapimage_bytes(
+                self,
+                bytes.to_vec(),
+                None,
+                self.respect_borders(),
+                true,
+                downscale,
+            )
+            .await?;
+        Ok(true)
+    }
+}
+
+/// A captured camera frame with Rgba8 pixels.
+#[derive(Debug)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+struct CameraFrame {
+    width: i32,
+    height: i32,
+    stride: usize,
+    data: Vec<u8>,
+}
+
+/// Request access to the camera and capture a single frame.
+///
+/// Returns `None` when the access was denied.
+#[cfg(target_os = "linux")]
+async fn capture_camera_frame() -> anyhow::Result<Option<CameraFrame>> {
+    let connection = gio::bus_get_future(gio::BusType::Session).await?;
+    if !is_camera_present(&connection).await? {
+        return Err(anyhow::anyhow!("No camera is available."));
+    }
+    if !access_camera(&connection).await? {
+        return Ok(None);
+    }
+    let pipewire_fd = open_pipewire_remote(&connection).await?;
+    let frame = gio::spawn_blocking(move || pipewire::capture_frame(pipewire_fd))
+        .await
+        .map_err(|_| anyhow::anyhow!("Capture thread panicked."))??;
+    Ok(Some(frame))
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn capture_camera_frame() -> anyhow::Result<Option<CameraFrame>> {
+    Err(anyhow::anyhow!(
+        "Capturing from the camera through the desktop portal is not supported on this platform."
+    ))
+}
+
+#[cfg(target_os = "linux")]
+async fn is_camera_present(connection: &gio::DBusConnection) -> anyhow::Result<bool> {
+    let reply = connection
+        .call_future(
+            Some("org.freedesktop.portal.Desktop"),
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.DBus.Properties",
+            "Get",
+            Some(&("org.freedesktop.portal.Camera", "IsCameraPresent").to_variant()),
+            Some(glib::VariantTy::new("(v)")?),
+            gio::DBusCallFlags::NONE,
+            -1,
+        )
+        .await?;
+    reply
+        .child_value(0)
+        .as_variant()
+        .and_then(|v| v.get::<bool>())
+        .ok_or_else(|| anyhow::anyhow!("Portal property `IsCameraPresent` has unexpected type"))
+}
+
+/// Ask the user for permission to access the camera.
+#[cfg(target_os = "linux")]
+async fn access_camera(connection: &gio::DBusConnection) -> anyhow::Result<bool> {
+    use futures::channel::oneshot;
+    use std::cell::RefCell;
+
+    let sender = connection
+        .unique_name()
+        .ok_or_else(|| anyhow::anyhow!("Session bus connection has no unique name"))?;
+    let token = format!("rnote_{}", glib::random_int());
+    let request_path = format!(
+        "/org/freedesktop/portal/desktop/request/{}/{token}",
+        sender.trim_start_matches(':').replace('.', "_")
+    );
+
+    let (response_sender, response_receiver) = oneshot::channel::<glib::Variant>();
+    let response_sender = RefCell::new(Some(response_sender));
+    let subscription_id = connection.signal_subscribe(
+        Some("org.freedesktop.portal.Desktop"),
+        Some("org.freedesktop.portal.Request"),
+        Some("Response"),
+        Some(&request_path),
+        None,
+        gio::DBusSignalFlags::NONE,
+        move |_, _, _, _, _, parameters| {
+            if let Some(response_sender) = response_sender.take() {
+                let _ = response_sender.send(parameters.clone());
+            }
+        },
+    );
+
+    let options = glib::VariantDict::new(None);
+    options.insert_value("handle_token", &token.to_variant());
+    let parameters = glib::Variant::tuple_from_iter([options.end()]);
+    let response = match connection
+        .call_future(
+            Some("org.freedesktop.portal.Desktop"),
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Camera",
+            "AccessCamera",
+            Some(&parameters),
+            Some(glib::VariantTy::new("(o)")?),
+            gio::DBusCallFlags::NONE,
+            -1,
+        )
+        .await
+    {
+        Ok(_) => response_receiver.await.map_err(anyhow::Error::from),
+        Err(e) => Err(e.into()),
+    };
+    connection.signal_unsubscribe(subscription_id);
+
+    let code = response?
+        .child_value(0)
+        .get::<u32>()
+        .ok_or_else(|| anyhow::anyhow!("Portal response has unexpected type"))?;
+    match code {
+        0 => Ok(true),
+        1 => Ok(false),
+        _ => Err(anyhow::anyhow!(
+            "Portal responded with error code {code} while accessing the camera"
+        )),
+    }
+}
+
+/// Open the PipeWire remote which exposes the camera streams.
+#[cfg(target_os = "linux")]
+async fn open_pipewire_remote(
+    connection: &gio::DBusConnection,
+) -> anyhow::Result<std::os::fd::OwnedFd> {
+    let options = glib::VariantDict::new(None);
+    let parameters = glib::Variant::tuple_from_iter([options.end()]);
+    let (reply, fd_list) = connection
+        .call_with_unix_fd_list_future(
+            Some("org.freedesktop.portal.Desktop"),
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Camera",
+            "OpenPipeWireRemote",
+            Some(&parameters),
+            Some(glib::VariantTy::new("(h)")?),
+            gio::DBusCallFlags::NONE,
+            -1,
+            gio::UnixFDList::NONE,
+        )
+        .await?;
+    let handle = reply
+        .child_value(0)
+        .get::<glib::variant::Handle>()
+        .ok_or_else(|| anyhow::anyhow!("Portal reply has unexpected type"))?;
+    let fd_list =
+        fd_list.ok_or_else(|| anyhow::anyhow!("Portal reply contains no file descriptors"))?;
+    Ok(fd_list.get(handle.0)?)
+}
+
+#[cfg(target_os = "linux")]
+mod pipewire {
+    use super::CameraFrame;
+    use gstreamer::prelude::*;
+    use std::os::fd::{AsRawFd, OwnedFd};
+
+    /// The frames that are skipped before one is captured, giving the camera time to adjust its exposure.
+    const SKIPPED_FRAMES: u32 = 15;
+    /// How long to wait for a single frame.
+    const FRAME_TIMEOUT: gstreamer::ClockTime = gstreamer::ClockTime::from_seconds(5);
+
+    /// Capture a frame from the default camera stream of the PipeWire remote. Blocks until it is received.
+    pub(super) fn capture_frame(pipewire_fd: OwnedFd) -> anyhow::Result<CameraFrame> {
+        gstreamer::init()?;
+        let pipeline = gstreamer::parse::launch(&format!(
+            "pipewiresrc fd={} ! videoconvert ! video/x-raw,format=RGBA ! appsink name=sink sync=false",
+            pipewire_fd.as_raw_fd()
+        ))?
+        .downcast::<gstreamer::Pipeline>()
+        .map_err(|_| anyhow::anyhow!("Capture pipeline has unexpected type"))?;
+        let sink = pipeline
+            .by_name("sink")
+            .and_then(|sink| sink.downcast::<gstreamer_app::AppSink>().ok())
+            .ok_or_else(|| anyhow::anyhow!("Capture pipeline has no app sink"))?;
+
+        pipeline.set_state(gstreamer::State::Playing)?;
+        let result = pull_frame(&sink);
+        pipeline.set_state(gstreamer::State::Null)?;
+        // The stream must be stopped before the remote gets closed
+        drop(pipewire_fd);
+        result
+    }
+
+    fn pull_frame(sink: &gstreamer_app::AppSink) -> anyhow::Result<CameraFrame> {
+        let pull_sample = || {
+            sink.try_pull_sample(FRAME_TIMEOUT)
+                .ok_or_else(|| anyhow::anyhow!("Receiving a frame from the camera timed out"))
+        };
+        let mut sample = pull_sample()?;
+        for _ in 0..SKIPPED_FRAMES {
+            sample = pull_sample()?;
+        }
+        let caps_struct = sample
+            .caps()
+            .and_then(|caps| caps.structure(0))
+            .ok_or_else(|| anyhow::anyhow!("Captured frame has no caps"))?;
+        let width = caps_struct.get::<i32>("width")?;
+        let height = caps_struct.get::<i32>("height")?;
+        let buffer = sample
+            .buffer()
+            .ok_or_else(|| anyhow::anyhow!("Captured frame has no buffer"))?;
+        let data = buffer.map_readable()?.as_slice().to_vec();
+        let stride = width as usize * 4;
+        if data.len() < stride * height as usize {
+            return Err(anyhow::anyhow!(
+                "Captured frame buffer is smaller than its dimensions"
+            ));
+        }
+        Ok(CameraFrame {
+            width,
+            height,
+            stride,
+            data,
+        })
+    }
+}
@@ -3,10 +3,12 @@ use crate::{config, dialogs, RnMainHeader, RnOverlays, RnSidebar};
 use adw::{prelude::*, subclass::prelude::*};
 use gettextrs::gettext;
 use gtk4::{
-    gdk, glib, glib::clone, Align, ArrowType, CompositeTemplate, CornerType, CssProvider, PackType,
-    PadActionType, PadController, PositionType,
+    gdk, gio, glib, glib::clone, Align, ArrowType, CompositeTemplate, CornerType, CssProvider,
+    EventControllerMotion, PackType, PadActionType, PadController, PositionType, PropagationPhase,
+    TextDirection,
 };
 use once_cell::sync::Lazy;
+use rnote_engine::engine::Replay;
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use tracing::{error, trace};
@@ -26,12 +28,23 @@ pub(crate) struct RnAppWindow {
     pub(crate) block_pinch_zoom: Cell<bool>,
     pub(crate) respect_borders: Cell<bool>,
     pub(crate) touch_drawing: Cell<bool>,
+    pub(crate) palm_rejection_timeout_ms: Cell<u32>,
+    pub(crate) blocked_input_devices: RefCell<Vec<String>>,
+    pub(crate) replay_speed: Cell<f64>,
     pub(crate) focus_mode: Cell<bool>,
+    pub(crate) distraction_free_mode: Cell<bool>,
+    pub(crate) distraction_free_mode_sidebar_shown: Cell<bool>,
+    pub(crate) presentation_mode: Cell<bool>,
+    pub(crate) presentation_mode_sidebar_shown: Cell<bool>,
     pub(crate) close_in_progress: Cell<bool>,
+    /// Serves the document in the local network, while it is active.
+    pub(crate) doc_server: RefCell<Option<super::DocServer>>,
 
     #[template_child]
     pub(crate) overview: TemplateChild<adw::TabOverview>,
     #[template_child]
+    pub(crate) toolbar_view: TemplateChild<adw::ToolbarView>,
+    #[template_child]
     pub(crate) main_header: TemplateChild<RnMainHeader>,
     #[template_child]
     pub(crate) split_view: TemplateChild<adw::OverlaySplitView>,
@@ -58,10 +71,21 @@ impl Default for RnAppWindow {
             block_pinch_zoom: Cell::new(false),
             respect_borders: Cell::new(false),
             touch_drawing: Cell::new(false),
+            palm_rejection_timeout_ms: Cell::new(
+                super::RnAppWindow::PALM_REJECTION_TIMEOUT_MS_DEFAULT,
+            ),
+            blocked_input_devices: RefCell::new(vec![]),
+            replay_speed: Cell::new(Replay::SPEED_DEFAULT),
             focus_mode: Cell::new(false),
+            distraction_free_mode: Cell::new(false),
+            distraction_free_mode_sidebar_shown: Cell::new(false),
+            presentation_mode: Cell::new(false),
+            presentation_mode_sidebar_shown: Cell::new(false),
             close_in_progress: Cell::new(false),
+            doc_server: RefCell::new(None),
 
             overview: TemplateChild::<adw::TabOverview>::default(),
+            toolbar_view: TemplateChild::<adw::ToolbarView>::default(),
             main_header: TemplateChild::<RnMainHeader>::default(),
             split_view: TemplateChild::<adw::OverlaySplitView>::default(),
             sidebar: TemplateChild::<RnSidebar>::default(),
@@ -110,7 +134,9 @@ impl ObjectImpl for RnAppWindow {
         self.setup_input();
         self.setup_overview();
         self.setup_split_view();
+        self.setup_distraction_free_mode();
         self.setup_tabbar();
+        self.setup_penssidebar_breakpoint();
     }
 
     fn dispose(&self) {
@@ -146,9 +172,26 @@ impl ObjectImpl for RnAppWindow {
                 glib::ParamSpecBoolean::builder("respect-borders")
                     .default_value(false)
                     .build(),
+                glib::ParamSpecUInt::builder("palm-rejection-timeout-ms")
+                    .minimum(0)
+                    .maximum(super::RnAppWindow::PALM_REJECTION_TIMEOUT_MS_MAX)
+                    .default_value(super::RnAppWindow::PALM_REJECTION_TIMEOUT_MS_DEFAULT)
+                    .build(),
+                glib::ParamSpecBoxed::builder::<Vec<String>>("blocked-input-devices").build(),
+                glib::ParamSpecDouble::builder("replay-speed")
+                    .minimum(Replay::SPEED_MIN)
+                    .maximum(Replay::SPEED_MAX)
+                    .default_value(Replay::SPEED_DEFAULT)
+                    .build(),
                 glib::ParamSpecBoolean::builder("focus-mode")
                     .default_value(false)
                     .build(),
+                glib::ParamSpecBoolean::builder("distraction-free-mode")
+                    .default_value(false)
+                    .build(),
+                glib::ParamSpecBoolean::builder("presentation-mode")
+                    .default_value(false)
+                    .build(),
             ]
         });
         PROPERTIES.as_ref()
@@ -163,7 +206,12 @@ impl ObjectImpl for RnAppWindow {
             "block-pinch-zoom" => self.block_pinch_zoom.get().to_value(),
             "respect-borders" => self.respect_borders.get().to_value(),
             "touch-drawing" => self.touch_drawing.get().to_value(),
+            "palm-rejection-timeout-ms" => self.palm_rejection_timeout_ms.get().to_value(),
+            "blocked-input-devices" => self.blocked_input_devices.borrow().to_value(),
+            "replay-speed" => self.replay_speed.get().to_value(),
             "focus-mode" => self.focus_mode.get().to_value(),
+            "distraction-free-mode" => self.distraction_free_mode.get().to_value(),
+            "presentation-mode" => self.presentation_mode.get().to_value(),
             _ => unimplemented!(),
         }
     }
@@ -225,13 +273,46 @@ impl ObjectImpl for RnAppWindow {
                     value.get().expect("The value needs to be of type `bool`");
                 self.touch_drawing.replace(touch_drawing);
             }
+            "palm-rejection-timeout-ms" => {
+                let palm_rejection_timeout_ms: u32 =
+                    value.get().expect("The value needs to be of type `u32`");
+                self.palm_rejection_timeout_ms
+                    .replace(palm_rejection_timeout_ms);
+            }
+            "blocked-input-devices" => {
+                let blocked_input_devices: Vec<String> = value
+                    .get()
+                    .expect("The value needs to be of type `Vec<String>`");
+                self.blocked_input_devices.replace(blocked_input_devices);
+            }
+            "replay-speed" => {
+                let replay_speed: f64 = value.get().expect("The value needs to be of type `f64`");
+                self.replay_speed.replace(replay_speed);
+                if let Some(canvas) = self.obj().active_tab_canvas() {
+                    canvas.engine_mut().set_replay_speed(replay_speed);
+                }
+            }
             "focus-mode" => {
                 let focus_mode: bool = value.get().expect("The value needs to be of type `bool`");
                 self.focus_mode.replace(focus_mode);
 
-                self.overlays.penpicker().set_visible(!focus_mode);
-                self.overlays.colorpicker().set_visible(!focus_mode);
-                self.overlays.sidebar_box().set_visible(!focus_mode);
+                self.update_overlays_visibility();
+            }
+            "distraction-free-mode" => {
+                let distraction_free_mode: bool =
+                    value.get().expect("The value needs to be of type `bool`");
+                if self.distraction_free_mode.replace(distraction_free_mode)
+                    != distraction_free_mode
+                {
+                    self.handle_distraction_free_mode_property(distraction_free_mode);
+                }
+            }
+            "presentation-mode" => {
+                let presentation_mode: bool =
+                    value.get().expect("The value needs to be of type `bool`");
+                if self.presentation_mode.replace(presentation_mode) != presentation_mode {
+                    self.handle_presentation_mode_property(presentation_mode);
+                }
             }
             _ => unimplemented!(),
         }
@@ -313,12 +394,12 @@ impl RnAppWindow {
                     for (i, tab) in tabs.iter().enumerate() {
                         let canvas = tab.canvas();
                         if canvas.unsaved_changes() {
-                            if let Some(output_file) = canvas.output_file() {
+                            if let Some(output_file) = canvas.writable_output_file() {
                                 trace!(
                                     "there are unsaved changes on the tab {:?} with a file on disk, saving",i
                                 );
                                 glib::spawn_future_local(clone!(#[weak] canvas, #[weak] appwindow ,async move {
-                                    if let Err(e) = canvas.save_document_to_file(&output_file).await {
+                                    if let Err(e) = canvas.autosave_document_to_file(&output_file).await {
                                         error!("Saving document failed, Err: `{e:?}`");
                                         canvas.set_output_file(None);
                                         appwindow
@@ -397,6 +478,24 @@ impl RnAppWindow {
         self.tabbar.set_view(Some(&self.overlays.tabview()));
     }
 
+    /// Switch the pens sidebar into its compact mode on narrow windows.
+    fn setup_penssidebar_breakpoint(&self) {
+        let obj = self.obj();
+        let Ok(condition) =
+            adw::BreakpointCondition::parse(super::RnAppWindow::PENSSIDEBAR_COMPACT_CONDITION)
+        else {
+            error!("Parsing the pens sidebar compact breakpoint condition failed");
+            return;
+        };
+        let breakpoint = adw::Breakpoint::new(condition);
+        breakpoint.add_setter(
+            &self.overlays.penssidebar(),
+            "compact-auto",
+            Some(&true.to_value()),
+        );
+        obj.add_breakpoint(breakpoint);
+    }
+
     fn setup_split_view(&self) {
         let obj = self.obj();
         let split_view = self.split_view.get();
@@ -515,9 +614,148 @@ impl RnAppWindow {
         ));
     }
 
+    fn setup_distraction_free_mode(&self) {
+        let obj = self.obj();
+        let motion_controller = EventControllerMotion::builder()
+            .name("distraction_free_mode_motion_controller")
+            .propagation_phase(PropagationPhase::Capture)
+            .build();
+
+        motion_controller.connect_motion(clone!(
+            #[weak(rename_to=appwindow)]
+            obj,
+            move |_, x, y| {
+                appwindow.imp().update_distraction_free_mode_reveal(x, y);
+            }
+        ));
+        obj.add_controller(motion_controller);
+
+        // Leaving fullscreen through the window manager also leaves the mode
+        obj.connect_fullscreened_notify(|appwindow| {
+            if !appwindow.is_fullscreened()
+                && appwindow.distraction_free_mode()
+                && !appwindow.imp().presentation_mode.get()
+            {
+                appwindow.set_distraction_free_mode(false);
+            }
+        });
+    }
+
+    fn handle_distraction_free_mode_property(&self, distraction_free_mode: bool) {
+        let obj = self.obj();
+
+        if distraction_free_mode {
+            self.distraction_free_mode_sidebar_shown
+                .set(self.split_view.shows_sidebar());
+            self.split_view.set_show_sidebar(false);
+        } else {
+            self.split_view
+                .set_show_sidebar(self.distraction_free_mode_sidebar_shown.get());
+        }
+        // The content stays in place when the top bars are revealed over it
+        self.toolbar_view
+            .set_extend_content_to_top_edge(distraction_free_mode);
+        self.toolbar_view
+            .set_reveal_top_bars(!distraction_free_mode);
+        if !self.presentation_mode.get() {
+            obj.set_fullscreened(distraction_free_mode);
+        }
+        if let Some(canvas) = obj.active_tab_canvas() {
+            canvas.grab_focus();
+        }
+    }
+
+    /// Reveals the top bars and the sidebar while the pointer approaches their window edges,
+    /// and hides them again when the pointer moves away.
+    fn update_distraction_free_mode_reveal(&self, x: f64, y: f64) {
+        if !self.distraction_free_mode.get() || self.presentation_mode.get() {
+            return;
+        }
+        let obj = self.obj();
+        let reveal_distance = super::RnAppWindow::DISTRACTION_FREE_MODE_REVEAL_DISTANCE;
+
+        let top_bars_revealed = self.toolbar_view.reveals_top_bars();
+        let reveal_top_bars = y <= reveal_distance
+            || (top_bars_revealed && y <= self.toolbar_view.top_bar_height() as f64);
+        if reveal_top_bars != top_bars_revealed {
+            self.toolbar_view.set_reveal_top_bars(reveal_top_bars);
+        }
+
+        let sidebar_on_left = (self.split_view.sidebar_position() == PackType::Start)
+            != (obj.direction() == TextDirection::Rtl);
+        let sidebar_edge_distance = if sidebar_on_left {
+            x
+        } else {
+            obj.width() as f64 - x
+        };
+        let sidebar_shown = self.split_view.shows_sidebar();
+        let show_sidebar = sidebar_edge_distance <= reveal_distance
+            || (sidebar_shown && sidebar_edge_distance <= self.sidebar.width() as f64);
+        if show_sidebar != sidebar_shown {
+            self.split_view.set_show_sidebar(show_sidebar);
+        }
+    }
+
+    fn update_overlays_visibility(&self) {
+        let visible = !self.focus_mode.get() && !self.presentation_mode.get();
+
+        self.overlays.penpicker().set_visible(visible);
+        self.overlays.colorpicker().set_visible(visible);
+        self.overlays.sidebar_box().set_visible(visible);
+    }
+
+    fn handle_presentation_mode_property(&self, presentation_mode: bool) {
+        let obj = self.obj();
+
+        for wrapper in obj.get_all_tabs() {
+            let canvas = wrapper.canvas();
+            let widget_flags = canvas.engine_mut().set_presentation_mode(presentation_mode);
+            obj.handle_widget_flags(widget_flags, &canvas);
+        }
+
+        // Actions that would modify the document are unavailable while presenting
+        for action_name in super::RnAppWindow::PRESENTATION_MODE_DISABLED_ACTIONS {
+            if let Some(action) = obj
+                .lookup_action(action_name)
+                .and_downcast::<gio::SimpleAction>()
+            {
+                action.set_enabled(!presentation_mode);
+            }
+        }
+
+        if presentation_mode {
+            self.presentation_mode_sidebar_shown
+                .set(self.split_view.shows_sidebar());
+            self.split_view.set_show_sidebar(false);
+        } else {
+            self.split_view
+                .set_show_sidebar(self.presentation_mode_sidebar_shown.get());
+        }
+        self.main_header.set_visible(!presentation_mode);
+        self.tabbar.set_visible(!presentation_mode);
+        self.update_overlays_visibility();
+        obj.set_fullscreened(presentation_mode || self.distraction_free_mode.get());
+
+        if presentation_mode {
+            obj.overlays().dispatch_toast_text(
+                &gettext("Press Escape to exit the presentation mode"),
+                crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+            );
+        }
+        if let Some(canvas) = obj.active_tab_canvas() {
+            canvas.grab_focus();
+        }
+    }
+
     fn handle_righthanded_property(&self, righthanded: bool) {
         let obj = self.obj();
 
+        for wrapper in obj.get_all_tabs() {
+            let canvas = wrapper.canvas();
+            let widget_flags = canvas.engine_mut().set_righthanded(righthanded);
+            obj.handle_widget_flags(widget_flags, &canvas);
+        }
+
         if righthanded {
             obj.split_view().set_sidebar_position(PackType::Start);
             obj.main_header()
@@ -586,6 +824,13 @@ impl RnAppWindow {
                 .settings_scroller()
                 .set_placement(CornerType::TopRight);
 
+            obj.overlays()
+                .colorpicker()
+                .set_direction(TextDirection::None);
+            obj.overlays()
+                .toast_overlay()
+                .remove_css_class("lefthanded");
+            obj.overlays().toast_overlay().add_css_class("righthanded");
             obj.overlays().sidebar_box().set_halign(Align::Start);
             obj.overlays()
                 .sidebar_scroller()
@@ -713,6 +958,18 @@ impl RnAppWindow {
                 .settings_scroller()
                 .set_placement(CornerType::TopLeft);
 
+            // Mirror the colorpicker, so that the active colors are placed at the opposite side
+            let mirrored_direction = match gtk4::Widget::default_direction() {
+                TextDirection::Rtl => TextDirection::Ltr,
+                _ => TextDirection::Rtl,
+            };
+            obj.overlays()
+                .colorpicker()
+                .set_direction(mirrored_direction);
+            obj.overlays()
+                .toast_overlay()
+                .remove_css_class("righthanded");
+            obj.overlays().toast_overlay().add_css_class("lefthanded");
             obj.overlays().sidebar_box().set_halign(Align::End);
             obj.overlays()
                 .sidebar_scroller()
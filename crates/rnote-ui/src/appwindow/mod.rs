@@ -1,9 +1,17 @@
 // Modules
 mod actions;
 mod appsettings;
+mod capture;
 mod imp;
+mod print;
+mod serve;
+mod share;
+
+// Re-exports
+pub(crate) use serve::{qr_code_texture, DocServer};
 
 // Imports
+use crate::canvas::FileLock;
 use crate::{
     config, dialogs, FileType, RnApp, RnCanvas, RnCanvasWrapper, RnMainHeader, RnOverlays,
     RnSidebar,
@@ -12,11 +20,12 @@ use adw::{prelude::*, subclass::prelude::*};
 use gettextrs::gettext;
 use gtk4::{gdk, gio, glib, Application, IconTheme};
 use rnote_compose::Color;
+use rnote_engine::engine::{EngineTask, ImportCancelled};
 use rnote_engine::ext::GdkRGBAExt;
 use rnote_engine::pens::pensconfig::brushconfig::BrushStyle;
 use rnote_engine::pens::pensconfig::shaperconfig::ShaperStyle;
 use rnote_engine::pens::PenStyle;
-use rnote_engine::{engine::EngineTask, WidgetFlags};
+use rnote_engine::WidgetFlags;
 use std::path::Path;
 use tracing::{error, warn};
 
@@ -30,6 +39,57 @@ glib::wrapper! {
 impl RnAppWindow {
     const AUTOSAVE_INTERVAL_DEFAULT: u32 = 30;
     const PERIODIC_CONFIGSAVE_INTERVAL: u32 = 10;
+    const PALM_REJECTION_TIMEOUT_MS_DEFAULT: u32 = 500;
+    const PALM_REJECTION_TIMEOUT_MS_MAX: u32 = 5000;
+    /// The window size condition below which the pens sidebar switches to its compact mode.
+    const PENSSIDEBAR_COMPACT_CONDITION: &'static str = "max-width: 700sp";
+    /// The distance to the window edges in which the pointer reveals the hidden bars in the distraction free mode.
+    const DISTRACTION_FREE_MODE_REVEAL_DISTANCE: f64 = 12.0;
+    /// The actions that are disabled while the presentation mode is active.
+    const PRESENTATION_MODE_DISABLED_ACTIONS: &'static [&'static str] = &[
+        "pen-style",
+        "pen-style-previous",
+        "pen-width-preset",
+        "pen-preset-add",
+        "pen-preset-apply",
+        "pen-preset-remove",
+        "insert-snippet",
+        "undo",
+        "redo",
+        "add-page-to-doc",
+        "remove-page-from-doc",
+        "resize-to-fit-content",
+        "clear-guides",
+        "selection-trash",
+        "selection-duplicate",
+        "selection-flip-horizontal",
+        "selection-flip-vertical",
+        "selection-bring-forward",
+        "selection-bring-to-front",
+        "selection-send-backward",
+        "selection-send-to-back",
+        "selection-invert-color",
+        "selection-remap-colors",
+        "selection-simplify",
+        "selection-lock",
+        "selection-add-caption",
+        "selection-text-along-path",
+        "selection-edit-nodes",
+        "unlock-all-strokes",
+        "selection-select-all",
+        "selection-select-similar",
+        "clear-doc",
+        "scale-doc-content",
+        "insert-table",
+        "import-file",
+        "append-rnote-file",
+        "clipboard-cut",
+        "clipboard-paste",
+        "clipboard-paste-contextmenu",
+        "insert-text-contextmenu",
+        "import-file-contextmenu",
+        "select-page-contextmenu",
+    ];
 
     pub(crate) fn new(app: &Application) -> Self {
         glib::Object::builder().property("application", app).build()
@@ -85,6 +145,49 @@ impl RnAppWindow {
         self.set_property("touch-drawing", touch_drawing.to_value());
     }
 
+    #[allow(unused)]
+    pub(crate) fn palm_rejection_timeout_ms(&self) -> u32 {
+        self.property::<u32>("palm-rejection-timeout-ms")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_palm_rejection_timeout_ms(&self, palm_rejection_timeout_ms: u32) {
+        self.set_property(
+            "palm-rejection-timeout-ms",
+            palm_rejection_timeout_ms.to_value(),
+        );
+    }
+
+    #[allow(unused)]
+    pub(crate) fn replay_speed(&self) -> f64 {
+        self.property::<f64>("replay-speed")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_replay_speed(&self, replay_speed: f64) {
+        self.set_property("replay-speed", replay_speed.to_value());
+    }
+
+    #[allow(unused)]
+    pub(crate) fn blocked_input_devices(&self) -> Vec<String> {
+        self.property::<Vec<String>>("blocked-input-devices")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_blocked_input_devices(&self, blocked_input_devices: Vec<String>) {
+        self.set_property("blocked-input-devices", blocked_input_devices.to_value());
+    }
+
+    #[allow(unused)]
+    pub(crate) fn distraction_free_mode(&self) -> bool {
+        self.property::<bool>("distraction-free-mode")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_distraction_free_mode(&self, distraction_free_mode: bool) {
+        self.set_property("distraction-free-mode", distraction_free_mode.to_value());
+    }
+
     #[allow(unused)]
     pub(crate) fn focus_mode(&self) -> bool {
         self.property::<bool>("focus-mode")
@@ -95,6 +198,16 @@ impl RnAppWindow {
         self.set_property("focus-mode", focus_mode.to_value());
     }
 
+    #[allow(unused)]
+    pub(crate) fn presentation_mode(&self) -> bool {
+        self.property::<bool>("presentation-mode")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_presentation_mode(&self, presentation_mode: bool) {
+        self.set_property("presentation-mode", presentation_mode.to_value());
+    }
+
     #[allow(unused)]
     pub(crate) fn respect_borders(&self) -> bool {
         self.property::<bool>("respect-borders")
@@ -223,6 +336,7 @@ impl RnAppWindow {
         if widget_flags.store_modified {
             canvas.set_unsaved_changes(true);
             canvas.set_empty(false);
+            canvas.emit_content_changed();
         }
         if widget_flags.view_modified {
             let widget_size = canvas.widget_size();
@@ -268,6 +382,16 @@ impl RnAppWindow {
         if let Some(enable_text_preprocessing) = widget_flags.enable_text_preprocessing {
             canvas.set_text_preprocessing(enable_text_preprocessing);
         }
+        // The text selection can change with every redraw while writing
+        if widget_flags.redraw
+            && canvas.engine_ref().penholder.current_pen_style_w_override() == PenStyle::Typewriter
+            && self.active_tab_canvas().as_ref() == Some(canvas)
+        {
+            self.overlays()
+                .penssidebar()
+                .typewriter_page()
+                .refresh_font(canvas);
+        }
     }
 
     /// Get the active (selected) tab page.
@@ -330,12 +454,31 @@ impl RnAppWindow {
     }
 
     /// Creates a new canvas wrapper without attaching it as a tab.
+    ///
+    /// The engine config stored in the settings serves as defaults for the new document.
     pub(crate) fn new_canvas_wrapper(&self) -> RnCanvasWrapper {
+        let wrapper = RnCanvasWrapper::new();
+        if let Some(app_settings) = self.app().app_settings() {
+            // Store the current config first, so that app wide preferences are up to date
+            if let Some(canvas) = self.active_tab_canvas() {
+                if let Err(e) = canvas.save_engine_config(&app_settings) {
+                    error!("Saving engine config of the active tab failed, Err: {e:?}");
+                }
+            }
+            match wrapper
+                .canvas()
+                .load_engine_config_from_settings(&app_settings)
+            {
+                Ok(()) => return wrapper,
+                Err(e) => {
+                    error!("Failed to load engine config for new tab, Err: {e:?}");
+                }
+            }
+        }
         let engine_config = self
             .active_tab_wrapper()
             .map(|w| w.canvas().engine_ref().extract_engine_config())
             .unwrap_or_default();
-        let wrapper = RnCanvasWrapper::new();
         let widget_flags = wrapper
             .canvas()
             .engine_mut()
@@ -426,6 +569,26 @@ impl RnAppWindow {
             .map(|(found, _)| found)
     }
 
+    /// Switch to the tab that has the file opened, in this or any other window of the app.
+    ///
+    /// Returns false if the file is not opened in any tab.
+    fn focus_tab_w_file_opened(&self, input_file_path: impl AsRef<Path>) -> bool {
+        let other_appwindows = self
+            .app()
+            .windows()
+            .into_iter()
+            .filter_map(|w| w.downcast::<RnAppWindow>().ok())
+            .filter(|w| w != self);
+        for appwindow in std::iter::once(self.clone()).chain(other_appwindows) {
+            if let Some(page) = appwindow.tabs_query_file_opened(input_file_path.as_ref()) {
+                appwindow.overlays().tabview().set_selected_page(&page);
+                appwindow.present();
+                return true;
+            }
+        }
+        false
+    }
+
     /// Set all unselected tabs inactive.
     ///
     /// This clears the rendering and deinits the current pen of the engine in the tabs.
@@ -470,7 +633,11 @@ impl RnAppWindow {
 
     pub(crate) fn refresh_titles(&self, canvas: &RnCanvas) {
         // Titles
-        let title = canvas.doc_title_display();
+        let title = if canvas.read_only() {
+            canvas.doc_title_display() + " (" + &gettext("Read-Only") + ")"
+        } else {
+            canvas.doc_title_display()
+        };
         let subtitle = canvas.doc_folderpath_display();
 
         self.set_title(Some(
@@ -515,6 +682,13 @@ impl RnAppWindow {
             Ok(false) => {
                 self.overlays().progressbar_abort();
             }
+            Err(e) if e.is::<ImportCancelled>() => {
+                self.overlays().dispatch_toast_text(
+                    &gettext("Import cancelled"),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+                self.overlays().progressbar_abort();
+            }
             Err(e) => {
                 error!("Opening file with dialogs failed, Err: {e:?}");
 
@@ -541,10 +715,24 @@ impl RnAppWindow {
                 })?;
 
                 // If the file is already opened in a tab, simply switch to it
-                if let Some(page) = self.tabs_query_file_opened(input_file_path) {
-                    self.overlays().tabview().set_selected_page(&page);
+                if self.focus_tab_w_file_opened(&input_file_path) {
                     false
                 } else {
+                    // Another instance might be editing the file
+                    let read_only = match FileLock::query_other_owner(&input_file_path) {
+                        Some(owner) => {
+                            match dialogs::dialog_file_locked(self, &input_file, &owner).await {
+                                Some(true) => true,
+                                Some(false) => {
+                                    FileLock::break_lock(&input_file_path)?;
+                                    false
+                                }
+                                None => return Ok(false),
+                            }
+                        }
+                        None => false,
+                    };
+
                     let (rnote_file_new_tab, wrapper) =
                         match (rnote_file_new_tab, self.active_tab_wrapper()) {
                             (true, None) => (true, self.new_canvas_wrapper()),
@@ -562,9 +750,17 @@ impl RnAppWindow {
                         };
 
                     let (bytes, _) = input_file.load_bytes_future().await?;
+                    wrapper.canvas().set_read_only(read_only);
                     let widget_flags = wrapper
                         .canvas()
-                        .load_in_rnote_bytes(bytes.to_vec(), input_file.path())
+                        .load_in_rnote_bytes(
+                            bytes.to_vec(),
+                            input_file.path(),
+                            self.sidebar()
+                                .settings_panel()
+                                .general_restore_view_state_row()
+                                .is_active(),
+                        )
                         .await?;
                     if rnote_file_new_tab {
                         self.append_wrapper_new_tab(&wrapper);
@@ -580,7 +776,13 @@ impl RnAppWindow {
                     .canvas();
                 let (bytes, _) = input_file.load_bytes_future().await?;
                 canvas
-                    .load_in_vectorimage_bytes(bytes.to_vec(), target_pos, self.respect_borders())
+                    .load_in_vectorimage_bytes(
+                        self,
+                        bytes.to_vec(),
+                        target_pos,
+                        self.respect_borders(),
+                        false,
+                    )
                     .await?;
                 true
             }
@@ -590,8 +792,20 @@ impl RnAppWindow {
                     .ok_or_else(|| anyhow::anyhow!("No active tab to import into"))?
                     .canvas();
                 let (bytes, _) = input_file.load_bytes_future().await?;
+                let Some(downscale) =
+                    dialogs::import::bitmapimage_size_check_and_dialog(self, &canvas, &bytes).await
+                else {
+                    return Ok(false);
+                };
                 canvas
-                    .load_in_bitmapimage_bytes(bytes.to_vec(), target_pos, self.respect_borders())
+                    .load_in_bitmapimage_bytes(
+                        self,
+                        bytes.to_vec(),
+                        target_pos,
+                        self.respect_borders(),
+                        false,
+                        downscale,
+                    )
                     .await?;
                 true
             }
@@ -646,12 +860,15 @@ impl RnAppWindow {
 
         // Avoids already borrowed
         let pen_style = canvas.engine_ref().penholder.current_pen_style_w_override();
-        let pen_sounds = canvas.engine_ref().pen_sounds();
         let doc_format = canvas.engine_ref().document.format;
         let total_zoom = canvas.engine_ref().camera.total_zoom();
         let snap_positions = canvas.engine_ref().document.snap_positions;
+        let onion_skinning = canvas.engine_ref().document.onion_skinning;
+        let highlighters_behind = canvas.engine_ref().document.highlighters_behind;
+        let replay_drawing = canvas.engine_ref().replay_active();
         let can_undo = canvas.engine_ref().can_undo();
         let can_redo = canvas.engine_ref().can_redo();
+        let recent_colors = canvas.engine_ref().pens_config.recent_colors.clone();
 
         self.overlays()
             .penpicker()
@@ -664,6 +881,9 @@ impl RnAppWindow {
         self.main_header()
             .canvasmenu()
             .refresh_zoom_reset_label(total_zoom);
+        self.overlays()
+            .colorpicker()
+            .set_recent_colors(&recent_colors);
 
         // we change the state through the actions, because they themselves hold state.
         // (for example needed to display ticks in menus for boolean actions)
@@ -672,11 +892,6 @@ impl RnAppWindow {
             "pen-style",
             &pen_style.to_string().to_variant(),
         );
-        adw::prelude::ActionGroupExt::change_action_state(
-            self,
-            "pen-sounds",
-            &pen_sounds.to_variant(),
-        );
         adw::prelude::ActionGroupExt::change_action_state(
             self,
             "snap-positions",
@@ -692,6 +907,21 @@ impl RnAppWindow {
             "show-origin-indicator",
             &doc_format.show_origin_indicator.to_variant(),
         );
+        adw::prelude::ActionGroupExt::change_action_state(
+            self,
+            "onion-skinning",
+            &onion_skinning.to_variant(),
+        );
+        adw::prelude::ActionGroupExt::change_action_state(
+            self,
+            "highlighters-behind",
+            &highlighters_behind.to_variant(),
+        );
+        adw::prelude::ActionGroupExt::change_action_state(
+            self,
+            "replay-drawing",
+            &replay_drawing.to_variant(),
+        );
 
         // Current pen
         match pen_style {
@@ -760,6 +990,18 @@ impl RnAppWindow {
                             .colorpicker()
                             .set_stroke_color(gdk::RGBA::from_compose_color(stroke_color));
                     }
+                    BrushStyle::Calligraphy => {
+                        let stroke_color = canvas
+                            .engine_ref()
+                            .pens_config
+                            .brush_config
+                            .calligraphy_options
+                            .stroke_color
+                            .unwrap_or(Color::TRANSPARENT);
+                        self.overlays()
+                            .colorpicker()
+                            .set_stroke_color(gdk::RGBA::from_compose_color(stroke_color));
+                    }
                 }
             }
             PenStyle::Shaper => {
@@ -887,6 +1129,7 @@ impl RnAppWindow {
             .penssidebar()
             .tools_page()
             .refresh_ui(active_tab);
+        self.overlays().penssidebar().refresh_presets(active_tab);
         self.sidebar().settings_panel().refresh_ui(active_tab);
         self.refresh_titles(&canvas);
     }
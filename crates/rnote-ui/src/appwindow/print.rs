@@ -0,0 +1,261 @@
+// Imports
+use super::RnAppWindow;
+use crate::RnCanvas;
+use gettextrs::gettext;
+use gtk4::{
+    glib::clone, prelude::*, CheckButton, DropDown, Grid, Label, PageOrientation, PageSetup,
+    PaperSize, PrintOperation, PrintOperationAction, Unit,
+};
+use rnote_compose::SplitOrder;
+use rnote_engine::engine::export::{PrintPrefs, PrintScaling};
+use rnote_engine::engine::StrokeContent;
+use rnote_engine::Engine;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use tracing::{debug, error};
+
+/// The document specific options, shown in a separate tab of the print dialog.
+struct PrintOptions {
+    grid: Grid,
+    with_background: CheckButton,
+    with_pattern: CheckButton,
+    optimize_printing: CheckButton,
+    page_order: DropDown,
+    scaling: DropDown,
+}
+
+impl PrintOptions {
+    fn new(prefs: PrintPrefs) -> Self {
+        let with_background = CheckButton::builder()
+            .label(gettext("Print Background"))
+            .active(prefs.with_background)
+            .build();
+        let with_pattern = CheckButton::builder()
+            .label(gettext("Print Background Pattern"))
+            .active(prefs.with_pattern)
+            .build();
+        let optimize_printing = CheckButton::builder()
+            .label(gettext("Optimize for Printing"))
+            .active(prefs.optimize_printing)
+            .build();
+        let horizontal_first = gettext("Horizontal First");
+        let vertical_first = gettext("Vertical First");
+        let page_order = DropDown::from_strings(&[&horizontal_first, &vertical_first]);
+        page_order.set_selected(match prefs.page_order {
+            SplitOrder::RowMajor => 0,
+            SplitOrder::ColumnMajor => 1,
+        });
+        let fit_to_paper = gettext("Fit to Paper");
+        let actual_size = gettext("Actual Size");
+        let scaling = DropDown::from_strings(&[&fit_to_paper, &actual_size]);
+        scaling.set_selected(match prefs.scaling {
+            PrintScaling::FitToPaper => 0,
+            PrintScaling::ActualSize => 1,
+        });
+        with_background
+            .bind_property("active", &with_pattern, "sensitive")
+            .sync_create()
+            .build();
+
+        let grid = Grid::builder()
+            .row_spacing(6)
+            .column_spacing(12)
+            .margin_top(12)
+            .margin_bottom(12)
+            .margin_start(12)
+            .margin_end(12)
+            .build();
+        grid.attach(&with_background, 0, 0, 2, 1);
+        grid.attach(&with_pattern, 0, 1, 2, 1);
+        grid.attach(&optimize_printing, 0, 2, 2, 1);
+        grid.attach(
+            &Label::builder()
+                .label(gettext("Page Order"))
+                .xalign(0.0)
+                .build(),
+            0,
+            3,
+            1,
+            1,
+        );
+        grid.attach(&page_order, 1, 3, 1, 1);
+        grid.attach(
+            &Label::builder()
+                .label(gettext("Scaling"))
+                .xalign(0.0)
+                .build(),
+            0,
+            4,
+            1,
+            1,
+        );
+        grid.attach(&scaling, 1, 4, 1, 1);
+
+        Self {
+            grid,
+            with_background,
+            with_pattern,
+            optimize_printing,
+            page_order,
+            scaling,
+        }
+    }
+
+    fn prefs(&self) -> PrintPrefs {
+        PrintPrefs {
+            with_background: self.with_background.is_active(),
+            with_pattern: self.with_pattern.is_active(),
+            optimize_printing: self.optimize_printing.is_active(),
+            page_order: match self.page_order.selected() {
+                1 => SplitOrder::ColumnMajor,
+                _ => SplitOrder::RowMajor,
+            },
+            scaling: match self.scaling.selected() {
+                1 => PrintScaling::ActualSize,
+                _ => PrintScaling::FitToPaper,
+            },
+        }
+    }
+}
+
+impl RnAppWindow {
+    /// Print the document through the print dialog.
+    ///
+    /// The paper size defaults to the page size of the document.
+    /// The paper size, margins and page ranges are chosen in the dialog, which handles them itself.
+    pub(crate) fn print_doc(&self, canvas: &RnCanvas) {
+        let prefs = canvas.engine_ref().export_prefs.print_prefs;
+        let options = Rc::new(PrintOptions::new(prefs));
+        let prefs = Rc::new(Cell::new(prefs));
+        let pages_content: Rc<RefCell<Vec<StrokeContent>>> = Rc::default();
+        let dpi = canvas.engine_ref().document.format.dpi();
+
+        let print_op = PrintOperation::new();
+        print_op.set_unit(Unit::Points);
+        print_op.set_embed_page_setup(true);
+        print_op.set_default_page_setup(Some(&doc_page_setup(canvas)));
+        print_op.set_custom_tab_label(Some(&gettext("Document")));
+
+        print_op.connect_create_custom_widget(clone!(
+            #[strong]
+            options,
+            move |_| options.grid.clone().upcast()
+        ));
+
+        print_op.connect_custom_widget_apply(clone!(
+            #[strong]
+            options,
+            #[strong]
+            prefs,
+            #[weak]
+            canvas,
+            move |_, _| {
+                let new_prefs = options.prefs();
+                prefs.set(new_prefs);
+                canvas.engine_mut().export_prefs.print_prefs = new_prefs;
+            }
+        ));
+
+        // The pages are extracted after the dialog was closed, because the page order is chosen in it
+        print_op.connect_begin_print(clone!(
+            #[strong]
+            prefs,
+            #[strong]
+            pages_content,
+            #[weak]
+            canvas,
+            move |print_op, _print_cx| {
+                let content = canvas
+                    .engine_ref()
+                    .extract_pages_content(prefs.get().page_order);
+                print_op.set_n_pages(content.len() as i32);
+                *pages_content.borrow_mut() = content;
+            }
+        ));
+
+        print_op.connect_draw_page(clone!(
+            #[strong]
+            prefs,
+            #[strong]
+            pages_content,
+            move |_print_op, print_cx, page_no| {
+                let prefs = prefs.get();
+                let pages_content = pages_content.borrow();
+                let Some(page_content) = pages_content.get(page_no as usize) else {
+                    return;
+                };
+                let Some(page_bounds) = page_content.bounds else {
+                    return;
+                };
+                let extents = page_bounds.extents();
+                let print_scale = match prefs.scaling {
+                    PrintScaling::FitToPaper => {
+                        (print_cx.width() / extents[0]).min(print_cx.height() / extents[1])
+                    }
+                    // The document is measured in pixels of its Dpi, the print context in points
+                    PrintScaling::ActualSize => 72.0 / dpi,
+                };
+                // Pages that are smaller than the printable area get centered on it
+                let offset_x = ((print_cx.width() - extents[0] * print_scale) * 0.5).max(0.0);
+                let offset_y = ((print_cx.height() - extents[1] * print_scale) * 0.5).max(0.0);
+                let cairo_cx = print_cx.cairo_context();
+
+                cairo_cx.translate(offset_x, offset_y);
+                cairo_cx.scale(print_scale, print_scale);
+                cairo_cx.translate(-page_bounds.mins[0], -page_bounds.mins[1]);
+                if let Err(e) = page_content.draw_to_cairo(
+                    &cairo_cx,
+                    prefs.with_background,
+                    prefs.with_pattern,
+                    prefs.optimize_printing,
+                    0.0,
+                    Engine::STROKE_EXPORT_IMAGE_SCALE,
+                ) {
+                    error!("Drawing page no: {page_no} while printing failed, Err: {e:?}");
+                }
+            }
+        ));
+
+        print_op.connect_status_changed(|print_op| {
+            debug!(
+                "Print operation status has changed to: {:?}",
+                print_op.status()
+            );
+        });
+
+        self.overlays().progressbar_start_pulsing();
+
+        // Run the print op
+        if let Err(e) = print_op.run(PrintOperationAction::PrintDialog, Some(self)) {
+            error!("Running print operation failed , Err: {e:?}");
+            self.overlays()
+                .dispatch_toast_error(&gettext("Printing document failed"));
+            self.overlays().progressbar_abort();
+        } else {
+            self.overlays().progressbar_finish();
+        }
+    }
+}
+
+/// The page setup with the page size of the document as paper size, and the default margins for it.
+fn doc_page_setup(canvas: &RnCanvas) -> PageSetup {
+    let format = canvas.engine_ref().document.format;
+    // The document is measured in pixels of its Dpi, the paper size in points
+    let width = format.width() * 72.0 / format.dpi();
+    let height = format.height() * 72.0 / format.dpi();
+    let paper_size = PaperSize::new_custom(
+        "rnote-document",
+        &gettext("Document Page Size"),
+        width.min(height),
+        width.max(height),
+        Unit::Points,
+    );
+    let page_setup = PageSetup::new();
+    page_setup.set_paper_size_and_default_margins(&paper_size);
+    page_setup.set_orientation(if width > height {
+        PageOrientation::Landscape
+    } else {
+        PageOrientation::Portrait
+    });
+    page_setup
+}
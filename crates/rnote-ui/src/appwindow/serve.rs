@@ -0,0 +1,337 @@
+// Imports
+use super::RnAppWindow;
+use crate::RnCanvas;
+use adw::subclass::prelude::*;
+use futures::future::{FutureExt, LocalBoxFuture, Shared};
+use gettextrs::gettext;
+use gtk4::{gdk, gio, glib, glib::clone, prelude::*};
+use std::cell::RefCell;
+use std::rc::Rc;
+use tracing::{debug, warn};
+
+/// Serves the document as Html viewer page over Http in the local network, while it is alive.
+///
+/// The page is only reachable under a random token path, so that it can't be found by scanning the network.
+/// It is generated once and then reused until the content of the document changes.
+#[derive(Debug)]
+pub(crate) struct DocServer {
+    service: gio::SocketService,
+    port: u16,
+    served: Rc<ServedDoc>,
+    content_changed_handler: Option<glib::SignalHandlerId>,
+}
+
+/// The state that is shared with the connection handlers.
+struct ServedDoc {
+    canvas: glib::WeakRef<RnCanvas>,
+    token: String,
+    /// The generated page, together with the document title it was generated with.
+    html_cache: RefCell<Option<(String, HtmlFuture)>>,
+}
+
+/// Resolves to the generated page, `None` when generating it failed.
+type HtmlFuture = Shared<LocalBoxFuture<'static, Option<Rc<Vec<u8>>>>>;
+
+impl std::fmt::Debug for ServedDoc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServedDoc")
+            .field("canvas", &self.canvas)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for DocServer {
+    fn drop(&mut self) {
+        if let (Some(canvas), Some(handler)) = (
+            self.served.canvas.upgrade(),
+            self.content_changed_handler.take(),
+        ) {
+            canvas.disconnect(handler);
+        }
+        self.service.stop();
+        self.service.close();
+    }
+}
+
+impl DocServer {
+    /// The maximum size of a request that is read.
+    const REQUEST_SIZE_MAX: usize = 8192;
+
+    /// Start serving the document of the canvas on a free port.
+    pub(crate) fn start(canvas: &RnCanvas) -> anyhow::Result<Self> {
+        let service = gio::SocketService::new();
+        let port = service.add_any_inet_port(None::<&glib::Object>)?;
+        let served = Rc::new(ServedDoc {
+            canvas: canvas.downgrade(),
+            token: glib::uuid_string_random().replace('-', ""),
+            html_cache: RefCell::new(None),
+        });
+
+        let content_changed_handler = canvas.connect_local(
+            "content-changed",
+            false,
+            clone!(
+                #[weak]
+                served,
+                #[upgrade_or]
+                None,
+                move |_| {
+                    served.html_cache.take();
+                    None
+                }
+            ),
+        );
+
+        service.connect_incoming(clone!(
+            #[weak]
+            served,
+            #[upgrade_or]
+            false,
+            move |_, connection, _| {
+                glib::spawn_future_local(clone!(
+                    #[strong]
+                    served,
+                    #[strong]
+                    connection,
+                    async move {
+                        if let Err(e) = handle_connection(&served, &connection).await {
+                            debug!("Handling connection to document server failed, Err: {e:?}");
+                        }
+                        if let Err(e) = connection.close_future(glib::Priority::DEFAULT).await {
+                            debug!("Closing connection to document server failed, Err: {e:?}");
+                        }
+                    }
+                ));
+                true
+            }
+        ));
+        service.start();
+
+        Ok(Self {
+            service,
+            port,
+            served,
+            content_changed_handler: Some(content_changed_handler),
+        })
+    }
+
+    /// The url under which the document is reachable from other devices in the local network.
+    pub(crate) fn url(&self) -> String {
+        format!(
+            "http://{}:{}/{}",
+            local_address(),
+            self.port,
+            self.served.token
+        )
+    }
+
+    /// Whether the document of the given canvas is served.
+    pub(crate) fn serves(&self, canvas: &RnCanvas) -> bool {
+        self.served.canvas.upgrade().as_ref() == Some(canvas)
+    }
+}
+
+impl ServedDoc {
+    /// The generated page of the document, reusing the cached one when it is still current.
+    async fn html(&self) -> Option<Rc<Vec<u8>>> {
+        let canvas = self.canvas.upgrade()?;
+        let title = canvas.doc_title_display();
+        let cached = self
+            .html_cache
+            .borrow()
+            .as_ref()
+            .filter(|(cached_title, _)| *cached_title == title)
+            .map(|(_, html)| html.clone());
+        let html = match cached {
+            Some(html) => html,
+            None => {
+                let receiver = canvas
+                    .engine_ref()
+                    .export_doc_as_html_viewer_bytes(title.clone());
+                let html = async move {
+                    match receiver.await {
+                        Ok(Ok(html_bytes)) => Some(Rc::new(html_bytes)),
+                        Ok(Err(e)) => {
+                            warn!("Generating Html page of served document failed, Err: {e:?}");
+                            None
+                        }
+                        Err(e) => {
+                            warn!("Receiving Html page of served document failed, Err: {e:?}");
+                            None
+                        }
+                    }
+                }
+                .boxed_local()
+                .shared();
+                self.html_cache.replace(Some((title, html.clone())));
+                html
+            }
+        };
+        html.await
+    }
+}
+
+impl RnAppWindow {
+    /// Start serving the document of the canvas in the local network, replacing the previously served one.
+    ///
+    /// Returns the url of the served document.
+    pub(crate) fn start_serving_doc(&self, canvas: &RnCanvas) -> anyhow::Result<String> {
+        let server = DocServer::start(canvas)?;
+        let url = server.url();
+        self.imp().doc_server.replace(Some(server));
+        Ok(url)
+    }
+
+    /// Stop serving the document and reset the state of the `serve-doc` action.
+    pub(crate) fn stop_serving_doc(&self) {
+        self.imp().doc_server.take();
+        if let Some(action) = self
+            .lookup_action("serve-doc")
+            .and_downcast::<gio::SimpleAction>()
+        {
+            action.set_state(&false.to_variant());
+        }
+    }
+
+    /// Stop serving the document when it is the one of the given canvas, for example because its tab got closed.
+    pub(crate) fn stop_serving_doc_of(&self, canvas: &RnCanvas) {
+        self.stop_serving_doc_if(|server| server.serves(canvas));
+    }
+
+    /// Stop serving the document when it is not the one of the given canvas, for example because another tab got selected.
+    pub(crate) fn stop_serving_doc_unless_of(&self, canvas: &RnCanvas) {
+        self.stop_serving_doc_if(|server| !server.serves(canvas));
+    }
+
+    fn stop_serving_doc_if(&self, condition: impl Fn(&DocServer) -> bool) {
+        let stop = self
+            .imp()
+            .doc_server
+            .borrow()
+            .as_ref()
+            .is_some_and(condition);
+        if stop {
+            self.stop_serving_doc();
+            self.overlays().dispatch_toast_text(
+                &gettext("Stopped serving document"),
+                crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+            );
+        }
+    }
+}
+
+/// Read the request and respond with the generated page when the path contains the token.
+async fn handle_connection(
+    served: &ServedDoc,
+    connection: &gio::SocketConnection,
+) -> anyhow::Result<()> {
+    let (buf, n_read) = connection
+        .input_stream()
+        .read_future(
+            vec![0; DocServer::REQUEST_SIZE_MAX],
+            glib::Priority::DEFAULT,
+        )
+        .await
+        .map_err(|(_, e)| e)?;
+    let request = String::from_utf8_lossy(&buf[..n_read]);
+    let mut request_line = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    let (method, path) = (request_line.next(), request_line.next());
+    let doc_path = format!("/{}", served.token);
+
+    let response = match (method, path) {
+        (Some("GET"), Some(path)) if path == doc_path => match served.html().await {
+            Some(html_bytes) => response_bytes(
+                "200 OK",
+                "text/html; charset=utf-8",
+                html_bytes.as_ref().clone(),
+            ),
+            None => response_bytes(
+                "500 Internal Server Error",
+                "text/plain; charset=utf-8",
+                b"Internal Server Error".to_vec(),
+            ),
+        },
+        (Some("GET"), Some(_)) => response_bytes(
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            b"Not Found".to_vec(),
+        ),
+        _ => response_bytes(
+            "405 Method Not Allowed",
+            "text/plain; charset=utf-8",
+            b"Method Not Allowed".to_vec(),
+        ),
+    };
+    connection
+        .output_stream()
+        .write_all_future(response, glib::Priority::DEFAULT)
+        .await
+        .map_err(|(_, e)| e)?;
+    Ok(())
+}
+
+fn response_bytes(status: &str, content_type: &str, body: Vec<u8>) -> Vec<u8> {
+    let mut bytes = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    bytes.extend(body);
+    bytes
+}
+
+/// The address of this device in the local network.
+///
+/// Connecting an udp socket does not send anything, but selects the interface that routes to outside addresses.
+/// Falls back to the host name when there is no such interface.
+fn local_address() -> String {
+    std::net::UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| {
+            socket.connect("192.0.2.1:80")?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_else(|e| {
+            warn!("Retrieving local network address failed, falling back to the host name. Err: {e:?}");
+            glib::host_name().to_string()
+        })
+}
+
+/// Generate a texture of the QR code that encodes the text.
+pub(crate) fn qr_code_texture(text: &str) -> anyhow::Result<gdk::MemoryTexture> {
+    /// The size of a single module of the code in pixels.
+    const MODULE_SIZE: usize = 8;
+    /// The width of the light border around the code in modules, required by readers to detect it.
+    const QUIET_ZONE: usize = 4;
+
+    let code = qrcode::QrCode::new(text.as_bytes())?;
+    let code_width = code.width();
+    let colors = code.to_colors();
+    let size = (code_width + QUIET_ZONE * 2) * MODULE_SIZE;
+    let mut pixels = vec![0xff_u8; size * size * 4];
+
+    for (i, color) in colors.iter().enumerate() {
+        if *color != qrcode::Color::Dark {
+            continue;
+        }
+        let (module_x, module_y) = (i % code_width + QUIET_ZONE, i / code_width + QUIET_ZONE);
+        for y in module_y * MODULE_SIZE..(module_y + 1) * MODULE_SIZE {
+            let row_start = (y * size + module_x * MODULE_SIZE) * 4;
+            for pixel in pixels[row_start..row_start + MODULE_SIZE * 4].chunks_exact_mut(4) {
+                pixel.copy_from_slice(&[0x00, 0x00, 0x00, 0xff]);
+            }
+        }
+    }
+
+    Ok(gdk::MemoryTexture::new(
+        size as i32,
+        size as i32,
+        gdk::MemoryFormat::R8g8b8a8,
+        &glib::Bytes::from_owned(pixels),
+        size * 4,
+    ))
+}
@@ -0,0 +1,152 @@
+// Imports
+use super::RnAppWindow;
+use crate::RnCanvas;
+use gtk4::{gio, glib, prelude::*, FileLauncher};
+use rnote_engine::engine::export::{
+    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs,
+};
+use std::path::PathBuf;
+
+/// The format the document is exported to when it is shared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ShareFormat {
+    /// The document as a single Pdf file.
+    Pdf,
+    /// Every page of the document as a Png file.
+    Png,
+}
+
+impl TryFrom<&str> for ShareFormat {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "pdf" => Ok(Self::Pdf),
+            "png" => Ok(Self::Png),
+            _ => Err(anyhow::anyhow!(
+                "ShareFormat try_from() for value `{value}` failed"
+            )),
+        }
+    }
+}
+
+impl RnAppWindow {
+    /// Export the document to temporary files and hand them to the email client through the desktop portal.
+    ///
+    /// A new email is composed with the exported files as attachments.
+    pub(crate) async fn share_doc_email(
+        &self,
+        canvas: &RnCanvas,
+        format: ShareFormat,
+    ) -> anyhow::Result<()> {
+        let title = canvas.doc_title_display();
+        let paths = export_doc_temp_files(canvas, title.clone(), format).await?;
+        compose_email_w_attachments(&title, &paths).await
+    }
+
+    /// Export the document to a temporary Pdf file and let the user choose the application it gets sent to.
+    pub(crate) async fn share_doc_open_with(&self, canvas: &RnCanvas) -> anyhow::Result<()> {
+        let title = canvas.doc_title_display();
+        let paths = export_doc_temp_files(canvas, title, ShareFormat::Pdf).await?;
+        let Some(path) = paths.into_iter().next() else {
+            return Err(anyhow::anyhow!("Exporting the document produced no file."));
+        };
+        let launcher = FileLauncher::new(Some(&gio::File::for_path(path)));
+        launcher.set_always_ask(true);
+        launcher.launch_future(Some(self)).await?;
+        Ok(())
+    }
+}
+
+/// Export the document into a new temporary directory and return the paths of the written files.
+///
+/// The files are not removed afterwards, because the receiving application might still read them
+/// after it was handed the files. They are cleaned up together with the other temporary files of the system.
+async fn export_doc_temp_files(
+    canvas: &RnCanvas,
+    title: String,
+    format: ShareFormat,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let dir = glib::dir_make_tmp(Some("rnote-share-XXXXXX"))?;
+
+    let files_bytes = match format {
+        ShareFormat::Pdf => {
+            let export_prefs = DocExportPrefs {
+                export_format: DocExportFormat::Pdf,
+                ..canvas.engine_ref().export_prefs.doc_export_prefs
+            };
+            let export_bytes = canvas
+                .engine_ref()
+                .export_doc(title.clone(), Some(export_prefs));
+            vec![(title + ".pdf", export_bytes.await??)]
+        }
+        ShareFormat::Png => {
+            let export_prefs = DocPagesExportPrefs {
+                export_format: DocPagesExportFormat::Png,
+                ..canvas.engine_ref().export_prefs.doc_pages_export_prefs
+            };
+            let export_bytes = canvas.engine_ref().export_doc_pages(Some(export_prefs));
+            export_bytes
+                .await??
+                .into_iter()
+                .enumerate()
+                .map(|(i, bytes)| {
+                    (
+                        rnote_engine::utils::doc_pages_files_names(title.clone(), i + 1) + ".png",
+                        bytes,
+                    )
+                })
+                .collect()
+        }
+    };
+
+    let mut paths = Vec::with_capacity(files_bytes.len());
+    for (file_name, bytes) in files_bytes {
+        let path = dir.join(file_name);
+        crate::utils::create_replace_file_future(bytes, &gio::File::for_path(&path)).await?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// Compose a new email with the files as attachments through the email portal.
+///
+/// The files are passed as file descriptors, so that sandboxed email clients can read them.
+#[cfg(unix)]
+async fn compose_email_w_attachments(subject: &str, paths: &[PathBuf]) -> anyhow::Result<()> {
+    let connection = gio::bus_get_future(gio::BusType::Session).await?;
+    let fd_list = gio::UnixFDList::new();
+    let mut attachment_fds = Vec::with_capacity(paths.len());
+    for path in paths {
+        let file = std::fs::File::open(path)?;
+        attachment_fds.push(glib::variant::Handle(fd_list.append(&file)?));
+    }
+
+    let options = glib::VariantDict::new(None);
+    options.insert_value("subject", &subject.to_variant());
+    options.insert_value("attachment_fds", &attachment_fds.to_variant());
+    // The parent window identifier is left empty, the portal then does not attach its dialogs to the window
+    let parameters = glib::Variant::tuple_from_iter(["".to_variant(), options.end()]);
+
+    connection
+        .call_with_unix_fd_list_future(
+            Some("org.freedesktop.portal.Desktop"),
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Email",
+            "ComposeEmail",
+            Some(&parameters),
+            Some(glib::VariantTy::new("(o)")?),
+            gio::DBusCallFlags::NONE,
+            -1,
+            Some(&fd_list),
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn compose_email_w_attachments(_subject: &str, _paths: &[PathBuf]) -> anyhow::Result<()> {
+    Err(anyhow::anyhow!(
+        "Composing emails through the desktop portal is not supported on this platform."
+    ))
+}
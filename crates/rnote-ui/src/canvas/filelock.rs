@@ -0,0 +1,145 @@
+// Imports
+use gtk4::glib;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// The process that holds the lock of a document file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "lock_owner")]
+pub(crate) struct LockOwner {
+    #[serde(rename = "pid")]
+    pub(crate) pid: u32,
+    #[serde(rename = "host")]
+    pub(crate) host: String,
+    #[serde(rename = "user")]
+    pub(crate) user: String,
+    /// Process ids inside a sandbox can't be compared with the ones outside of it.
+    #[serde(rename = "sandboxed")]
+    pub(crate) sandboxed: bool,
+}
+
+impl LockOwner {
+    fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            host: glib::host_name().to_string(),
+            user: glib::user_name().to_string_lossy().to_string(),
+            sandboxed: Path::new("/.flatpak-info").exists(),
+        }
+    }
+
+    /// Whether the lock was left behind by an instance that is no longer running, for example after a crash.
+    ///
+    /// Only locks of processes on this host and outside of a sandbox can be checked,
+    /// all others are assumed to be still held.
+    fn stale(&self) -> bool {
+        let current = Self::current();
+        if self.host != current.host || self.sandboxed || current.sandboxed {
+            return false;
+        }
+        !process_running(self.pid)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn process_running(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_running(_pid: u32) -> bool {
+    true
+}
+
+/// A lock file next to a document file, telling other instances that the document is opened for editing.
+///
+/// The lock file is removed when the lock is dropped.
+#[derive(Debug)]
+pub(crate) struct FileLock {
+    lock_path: PathBuf,
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        // Another instance might have taken over the lock in the meantime
+        if read_owner(&self.lock_path).is_some_and(|owner| owner == LockOwner::current()) {
+            if let Err(e) = std::fs::remove_file(&self.lock_path) {
+                warn!(
+                    "Removing lock file `{}` failed, Err: {e:?}",
+                    self.lock_path.display()
+                );
+            }
+        }
+    }
+}
+
+impl FileLock {
+    /// The owner of the lock of the document file, when it is held by another instance.
+    pub(crate) fn query_other_owner(file_path: impl AsRef<Path>) -> Option<LockOwner> {
+        let owner = read_owner(&lock_path(file_path.as_ref())?)?;
+        (owner != LockOwner::current() && !owner.stale()).then_some(owner)
+    }
+
+    /// Acquire the lock of the document file.
+    ///
+    /// Fails when another instance holds the lock. Stale locks are taken over.
+    pub(crate) fn acquire(file_path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let file_path = file_path.as_ref();
+        let lock_path = lock_path(file_path).ok_or_else(|| {
+            anyhow::anyhow!("No lock file path for file `{}`.", file_path.display())
+        })?;
+        let owner_bytes = serde_json::to_vec(&LockOwner::current())?;
+
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&lock_path)
+        {
+            Ok(mut lock_file) => lock_file.write_all(&owner_bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                if let Some(owner) = Self::query_other_owner(file_path) {
+                    return Err(anyhow::anyhow!(
+                        "Lock of file `{}` is held by process {} of user `{}` on host `{}`.",
+                        file_path.display(),
+                        owner.pid,
+                        owner.user,
+                        owner.host
+                    ));
+                }
+                debug!("Taking over lock file `{}`", lock_path.display());
+                std::fs::write(&lock_path, owner_bytes)?;
+            }
+            Err(e) => return Err(e.into()),
+        }
+        Ok(Self { lock_path })
+    }
+
+    /// Remove the lock of the document file, regardless of which instance holds it.
+    pub(crate) fn break_lock(file_path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let Some(lock_path) = lock_path(file_path.as_ref()) else {
+            return Ok(());
+        };
+        match std::fs::remove_file(lock_path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The lock file path of a document file, a hidden file in the same directory.
+fn lock_path(file_path: &Path) -> Option<PathBuf> {
+    let mut lock_file_name = OsString::from(".~lock.");
+    lock_file_name.push(file_path.file_name()?);
+    lock_file_name.push("#");
+    Some(file_path.with_file_name(lock_file_name))
+}
+
+fn read_owner(lock_path: &Path) -> Option<LockOwner> {
+    let bytes = std::fs::read(lock_path).ok()?;
+    serde_json::from_slice(&bytes)
+        .inspect_err(|e| debug!("Lock file `{}` is invalid, Err: {e:?}", lock_path.display()))
+        .ok()
+}
@@ -1,23 +1,33 @@
 // Imports
 use super::RnCanvas;
+use crate::RnAppWindow;
 use anyhow::Context;
 use futures::channel::oneshot;
+use futures::future::Either;
 use futures::AsyncWriteExt;
-use gtk4::{gio, prelude::*};
+use gettextrs::gettext;
+use gtk4::{gio, glib, glib::clone, prelude::*};
 use rnote_compose::ext::Vector2Ext;
-use rnote_engine::engine::export::{DocExportPrefs, DocPagesExportPrefs, SelectionExportPrefs};
-use rnote_engine::engine::{EngineSnapshot, StrokeContent};
+use rnote_compose::shapes::Shapeable;
+use rnote_compose::transform::Transformable;
+use rnote_engine::engine::export::{
+    DocExportPrefs, DocPagesExportPrefs, SelectionExportFormat, SelectionExportPrefs,
+    ViewportExportPrefs,
+};
+use rnote_engine::engine::{EngineSnapshot, ImportJobHandle, StrokeContent};
 use rnote_engine::strokes::resize::ImageSizeOption;
 use rnote_engine::strokes::Stroke;
 use rnote_engine::WidgetFlags;
-use std::ops::Range;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tracing::{debug, error};
 
 impl RnCanvas {
     /// Load the bytes of a `.rnote` file and imports it into the engine.
     ///
     /// `file_path` is optional but needs to be supplied when the origin file should be tracked.
+    /// When `restore_view_state` is false, the stored camera and pen style are discarded
+    /// and the document is opened at its origin.
     ///
     /// The function returns `WidgetFlags` instead of emitting the `handle_signal_flags` signal, because a signal
     /// handler might not yet be connected when this function is called.
@@ -25,15 +35,24 @@ impl RnCanvas {
         &self,
         bytes: Vec<u8>,
         file_path: Option<P>,
+        restore_view_state: bool,
     ) -> anyhow::Result<WidgetFlags>
     where
         P: AsRef<Path>,
     {
-        let engine_snapshot = EngineSnapshot::load_from_rnote_bytes(bytes).await?;
+        let mut engine_snapshot = EngineSnapshot::load_from_rnote_bytes(bytes).await?;
+        if !restore_view_state {
+            engine_snapshot.discard_view_state();
+        }
         let mut widget_flags = self.engine_mut().load_snapshot(engine_snapshot);
         widget_flags |= self
             .engine_mut()
             .set_scale_factor(self.scale_factor() as f64);
+        if !restore_view_state {
+            widget_flags |= self
+                .engine_mut()
+                .return_to_origin(self.parent().map(|p| p.width() as f64));
+        }
 
         self.set_output_file(file_path.map(gio::File::for_path));
         self.dismiss_output_file_modified_toast();
@@ -54,16 +73,72 @@ impl RnCanvas {
         };
         let (bytes, _) = output_file.load_bytes_future().await?;
         let widget_flags = self
-            .load_in_rnote_bytes(bytes.to_vec(), output_file.path())
+            .load_in_rnote_bytes(bytes.to_vec(), output_file.path(), true)
             .await?;
         self.emit_handle_widget_flags(widget_flags);
         Ok(())
     }
 
-    pub(crate) async fn load_in_xopp_bytes(&self, bytes: Vec<u8>) -> anyhow::Result<()> {
+    /// When the revisions that are stored in the output file were saved, in seconds since the unix epoch, newest first.
+    pub(crate) async fn output_file_revisions_saved_at(&self) -> anyhow::Result<Vec<Option<i64>>> {
+        let Some(output_file) = self.output_file() else {
+            return Err(anyhow::anyhow!(
+                "Failed to load revision history, no file path saved."
+            ));
+        };
+        let (bytes, _) = output_file.load_bytes_future().await?;
+        let revision_history =
+            EngineSnapshot::load_revision_history_from_rnote_bytes(bytes.to_vec()).await?;
+        Ok(revision_history.revisions_saved_at())
+    }
+
+    /// Replace the document with a revision that is stored in the output file.
+    ///
+    /// The document is not saved, so the restored revision is only written to the file when the user saves it.
+    pub(crate) async fn restore_output_file_revision(
+        &self,
+        index: usize,
+    ) -> anyhow::Result<WidgetFlags> {
+        let Some(output_file) = self.output_file() else {
+            return Err(anyhow::anyhow!(
+                "Failed to restore revision, no file path saved."
+            ));
+        };
+        let (bytes, _) = output_file.load_bytes_future().await?;
+        let engine_snapshot =
+            EngineSnapshot::load_revision_from_rnote_bytes(bytes.to_vec(), index).await?;
+        let mut widget_flags = self.engine_mut().load_snapshot(engine_snapshot);
+        widget_flags |= self
+            .engine_mut()
+            .set_scale_factor(self.scale_factor() as f64);
+        self.set_unsaved_changes(true);
+
+        Ok(widget_flags)
+    }
+
+    /// Load the bytes of a `.rnote` file and append its strokes to the current document.
+    ///
+    /// Unlike [`Self::load_in_rnote_bytes()`], the current document is kept and the origin file is not changed.
+    pub(crate) async fn append_rnote_bytes(&self, bytes: Vec<u8>) -> anyhow::Result<()> {
+        let engine_snapshot = EngineSnapshot::load_from_rnote_bytes(bytes).await?;
+        let widget_flags = self.engine_mut().append_snapshot(engine_snapshot);
+        self.emit_handle_widget_flags(widget_flags);
+
+        self.set_empty(false);
+        Ok(())
+    }
+
+    /// Loads in bytes from a Xournal++ file.
+    ///
+    /// The file path is used to resolve referenced background pdfs.
+    pub(crate) async fn load_in_xopp_bytes(
+        &self,
+        bytes: Vec<u8>,
+        file_path: Option<PathBuf>,
+    ) -> anyhow::Result<()> {
         let xopp_import_prefs = self.engine_ref().import_prefs.xopp_import_prefs;
         let engine_snapshot =
-            EngineSnapshot::load_from_xopp_bytes(bytes, xopp_import_prefs).await?;
+            EngineSnapshot::load_from_xopp_bytes(bytes, file_path, xopp_import_prefs).await?;
         let widget_flags = self.engine_mut().load_snapshot(engine_snapshot);
         self.emit_handle_widget_flags(widget_flags);
 
@@ -75,47 +150,116 @@ impl RnCanvas {
 
     /// Loads in bytes from a vector image and imports it.
     ///
+    /// If the image was exported from a selection with embedded strokes, the strokes are restored instead.
+    ///
     /// `target_pos` is in coordinate space of the doc.
+    /// When `center_in_viewport` is set and no `target_pos` is given, the image is centered in the current viewport.
     pub(crate) async fn load_in_vectorimage_bytes(
         &self,
+        appwindow: &RnAppWindow,
         bytes: Vec<u8>,
         target_pos: Option<na::Vector2<f64>>,
         respect_borders: bool,
+        center_in_viewport: bool,
     ) -> anyhow::Result<()> {
-        let pos = self.determine_stroke_import_pos(target_pos);
+        let mut pos = self.determine_stroke_import_pos(target_pos);
+
+        let (bytes, embedded_content) = Self::extract_embedded_stroke_content(bytes).await?;
+        if let Some(content) = embedded_content {
+            if center_in_viewport && target_pos.is_none() {
+                if let Some(size) = content.size() {
+                    pos = self.engine_ref().camera.viewport_center() - size * 0.5;
+                }
+            }
+            let widget_flags = self.engine_mut().insert_stroke_content(
+                content,
+                pos,
+                ImageSizeOption::RespectOriginalSize,
+            );
+            self.emit_handle_widget_flags(widget_flags);
+            return Ok(());
+        }
 
         // Splitting the import operation into two parts: a receiver that gets awaited with the content, and
         // the blocking import avoids borrowing the entire engine RefCell while awaiting the content, avoiding panics.
-        let vectorimage_receiver =
+        let (job, vectorimage_receiver) =
             self.engine_mut()
                 .generate_vectorimage_from_bytes(pos, bytes, respect_borders);
-        let vectorimage = vectorimage_receiver.await??;
+        let vectorimage = self
+            .await_import_job(appwindow, job, vectorimage_receiver)
+            .await?;
+        let mut stroke = Stroke::VectorImage(vectorimage);
+        if center_in_viewport && target_pos.is_none() {
+            self.center_stroke_in_viewport(&mut stroke);
+        }
         let widget_flags = self
             .engine_mut()
-            .import_generated_content(vec![(Stroke::VectorImage(vectorimage), None)], false);
+            .import_generated_content(vec![(stroke, None)], false);
 
         self.emit_handle_widget_flags(widget_flags);
         Ok(())
     }
 
+    /// Extracts the stroke content that might be embedded in the Svg bytes, handing the bytes back.
+    async fn extract_embedded_stroke_content(
+        bytes: Vec<u8>,
+    ) -> anyhow::Result<(Vec<u8>, Option<StrokeContent>)> {
+        let (oneshot_sender, oneshot_receiver) =
+            oneshot::channel::<(Vec<u8>, Option<StrokeContent>)>();
+
+        rayon::spawn(move || {
+            let content = match std::str::from_utf8(&bytes)
+                .map_err(anyhow::Error::from)
+                .and_then(StrokeContent::from_svg_metadata)
+            {
+                Ok(content) => content,
+                Err(e) => {
+                    // Fall back to importing the Svg as image
+                    error!("Extracting embedded stroke content from Svg failed, Err: {e:?}");
+                    None
+                }
+            };
+            if oneshot_sender.send((bytes, content)).is_err() {
+                error!(
+                    "Sending result to receiver while extracting embedded stroke content failed. Receiver already dropped."
+                );
+            }
+        });
+        Ok(oneshot_receiver.await?)
+    }
+
     /// Loads in bytes from a bitmap image and imports it.
     ///
     /// `target_pos` is in coordinate space of the doc.
+    /// When `center_in_viewport` is set and no `target_pos` is given, the image is centered in the current viewport.
+    /// When `downscale` is set, images exceeding the maximum size of the bitmap import prefs are downscaled.
     pub(crate) async fn load_in_bitmapimage_bytes(
         &self,
+        appwindow: &RnAppWindow,
         bytes: Vec<u8>,
         target_pos: Option<na::Vector2<f64>>,
         respect_borders: bool,
+        center_in_viewport: bool,
+        downscale: bool,
     ) -> anyhow::Result<()> {
         let pos = self.determine_stroke_import_pos(target_pos);
 
-        let bitmapimage_receiver =
-            self.engine_mut()
-                .generate_bitmapimage_from_bytes(pos, bytes, respect_borders);
-        let bitmapimage = bitmapimage_receiver.await??;
+        let (job, bitmapimage_receiver) = self.engine_mut().generate_bitmapimage_from_bytes(
+            pos,
+            bytes,
+            respect_borders,
+            downscale,
+        );
+        let bitmapimage = self
+            .await_import_job(appwindow, job, bitmapimage_receiver)
+            .await?;
+        let mut stroke = Stroke::BitmapImage(bitmapimage);
+        if center_in_viewport && target_pos.is_none() {
+            self.center_stroke_in_viewport(&mut stroke);
+        }
         let widget_flags = self
             .engine_mut()
-            .import_generated_content(vec![(Stroke::BitmapImage(bitmapimage), None)], false);
+            .import_generated_content(vec![(stroke, None)], false);
 
         self.emit_handle_widget_flags(widget_flags);
         Ok(())
@@ -126,9 +270,10 @@ impl RnCanvas {
     /// `target_pos` is in coordinate space of the doc.
     pub(crate) async fn load_in_pdf_bytes(
         &self,
+        appwindow: &RnAppWindow,
         bytes: Vec<u8>,
         target_pos: Option<na::Vector2<f64>>,
-        page_range: Option<Range<u32>>,
+        pages: Option<Vec<u32>>,
         password: Option<String>,
     ) -> anyhow::Result<()> {
         let pos = self.determine_stroke_import_pos(target_pos);
@@ -138,10 +283,12 @@ impl RnCanvas {
             .pdf_import_prefs
             .adjust_document;
 
-        let strokes_receiver = self
+        let (job, strokes_receiver) = self
             .engine_mut()
-            .generate_pdf_pages_from_bytes(bytes, pos, page_range, password);
-        let strokes = strokes_receiver.await??;
+            .generate_pdf_pages_from_bytes(bytes, pos, pages, password);
+        let strokes = self
+            .await_import_job(appwindow, job, strokes_receiver)
+            .await?;
         let widget_flags = self
             .engine_mut()
             .import_generated_content(strokes, adjust_document);
@@ -150,6 +297,60 @@ impl RnCanvas {
         Ok(())
     }
 
+    /// Await the result of an import job while showing its progress in the progressbar.
+    ///
+    /// When the job takes a while, a toast is shown that offers cancelling it.
+    /// A cancelled job results in an `ImportCancelled` error.
+    async fn await_import_job<T>(
+        &self,
+        appwindow: &RnAppWindow,
+        job: ImportJobHandle,
+        receiver: oneshot::Receiver<anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        const CANCEL_TOAST_DELAY: Duration = Duration::from_secs(1);
+
+        appwindow.overlays().progressbar_start_pulsing();
+        appwindow
+            .overlays()
+            .progressbar_track_import_job(job.clone());
+
+        let result =
+            match futures::future::select(receiver, glib::timeout_future(CANCEL_TOAST_DELAY)).await
+            {
+                Either::Left((result, _)) => result,
+                Either::Right(((), receiver)) => {
+                    let cancel_toast = appwindow.overlays().dispatch_toast_w_button(
+                        &gettext("Importing…"),
+                        &gettext("Cancel"),
+                        clone!(
+                            #[strong]
+                            job,
+                            move |_| {
+                                job.cancel();
+                            }
+                        ),
+                        None,
+                    );
+                    let result = receiver.await;
+                    if let Some(cancel_toast) = cancel_toast.upgrade() {
+                        cancel_toast.dismiss();
+                    }
+                    result
+                }
+            };
+
+        match result.map_err(anyhow::Error::from).and_then(|res| res) {
+            Ok(content) => {
+                appwindow.overlays().progressbar_finish();
+                Ok(content)
+            }
+            Err(e) => {
+                appwindow.overlays().progressbar_abort();
+                Err(e)
+            }
+        }
+    }
+
     /// Imports a text.
     ///
     /// `target_pos` is in coordinate space of the doc.
@@ -202,13 +403,33 @@ impl RnCanvas {
     ///
     /// Returns Ok(true) if saved successfully, Ok(false) when a save is already in progress and no file operatiosn were
     /// executed, Err(e) when saving failed in any way.
-    #[tracing::instrument(skip_all, fields(path = format!("{:?}", file.path())))]
     pub(crate) async fn save_document_to_file(&self, file: &gio::File) -> anyhow::Result<bool> {
+        self.save_document_to_file_impl(file, false).await
+    }
+
+    /// Autosaves the document to the given file.
+    ///
+    /// In contrast to explicit saves, autosaves are merged into the next save in the revision history.
+    pub(crate) async fn autosave_document_to_file(&self, file: &gio::File) -> anyhow::Result<bool> {
+        self.save_document_to_file_impl(file, true).await
+    }
+
+    #[tracing::instrument(skip_all, fields(path = format!("{:?}", file.path()), autosave))]
+    async fn save_document_to_file_impl(
+        &self,
+        file: &gio::File,
+        autosave: bool,
+    ) -> anyhow::Result<bool> {
         // skip saving when it is already in progress
         if self.save_in_progress() {
             debug!("Returning early, saving file is already in progress");
             return Ok(false);
         }
+        if self.read_only() && self.output_file().is_some_and(|f| f.equal(file)) {
+            return Err(anyhow::anyhow!(
+                "Saving to file `{file:?}` refused, the document is opened read-only."
+            ));
+        }
         self.set_save_in_progress(true);
         debug!("Saving file is now in progress");
 
@@ -218,15 +439,31 @@ impl RnCanvas {
         let basename = file
             .basename()
             .ok_or_else(|| anyhow::anyhow!("Could not retrieve basename for file: `{file:?}`."))?;
-        let rnote_bytes_receiver = self
-            .engine_ref()
-            .save_as_rnote_bytes(basename.to_string_lossy().to_string());
         let mut skip_set_output_file = false;
         if let Some(output_file_path) = self.output_file().and_then(|f| f.path()) {
             if crate::utils::paths_abs_eq(output_file_path, &file_path).unwrap_or(false) {
                 skip_set_output_file = true;
             }
         }
+        // The overwritten file is recorded as a revision in the revision history, but only when it is the previously
+        // saved file of this document. Any other file gets a fresh history.
+        let previous_bytes = if skip_set_output_file
+            && self
+                .engine_ref()
+                .export_prefs
+                .revision_history_prefs
+                .enabled
+        {
+            async_fs::read(&file_path).await.ok()
+        } else {
+            None
+        };
+        let rnote_bytes_receiver = self.engine_ref().save_as_rnote_bytes_w_revisions(
+            basename.to_string_lossy().to_string(),
+            previous_bytes,
+            autosave,
+        );
+
         self.dismiss_output_file_modified_toast();
 
         let file_write_operation = async move {
@@ -245,6 +482,8 @@ impl RnCanvas {
             if !skip_set_output_file {
                 // this installs the file watcher.
                 self.set_output_file(Some(file.to_owned()));
+                // the document is now saved as a separate copy that can be edited
+                self.set_read_only(false);
             }
             write_file.write_all(&bytes).await.context(format!(
                 "Failed to write bytes to file with path '{}'",
@@ -266,6 +505,8 @@ impl RnCanvas {
         }
 
         debug!("Saving file has finished successfully");
+        // Keep the timestamps in sync with the ones written to the file
+        self.engine_mut().metadata.mark_saved();
         self.set_unsaved_changes(false);
         self.set_save_in_progress(false);
 
@@ -343,6 +584,63 @@ impl RnCanvas {
         Ok(())
     }
 
+    pub(crate) async fn export_viewport(
+        &self,
+        file: &gio::File,
+        export_prefs_override: Option<ViewportExportPrefs>,
+    ) -> anyhow::Result<()> {
+        let export_bytes = self.engine_ref().export_viewport(export_prefs_override);
+
+        crate::utils::create_replace_file_future(export_bytes.await??, file).await?;
+
+        self.set_last_export_dir(file.parent());
+
+        Ok(())
+    }
+
+    /// Exports the replay of the document strokes in drawing order as animated Gif.
+    pub(crate) async fn export_replay(&self, file: &gio::File, speed: f64) -> anyhow::Result<()> {
+        let export_bytes = self.engine_ref().export_replay_as_gif(speed);
+
+        crate::utils::create_replace_file_future(export_bytes.await??, file).await?;
+
+        self.set_last_export_dir(file.parent());
+
+        Ok(())
+    }
+
+    /// Export the selection as Svg and Png for dragging it out of the canvas, paired with their mime types.
+    ///
+    /// Blocks until the export is finished, because the drag content must be provided synchronously.
+    pub(crate) fn export_selection_drag_content(&self) -> Vec<(Vec<u8>, String)> {
+        let selection_export_prefs = self.engine_ref().export_prefs.selection_export_prefs;
+        let mut content = vec![];
+
+        for (export_format, mime_type) in [
+            (SelectionExportFormat::Svg, "image/svg+xml"),
+            (SelectionExportFormat::Png, "image/png"),
+        ] {
+            let export_prefs = SelectionExportPrefs {
+                export_format,
+                ..selection_export_prefs
+            };
+            let receiver = self.engine_ref().export_selection(Some(export_prefs));
+
+            match futures::executor::block_on(receiver) {
+                Ok(Ok(Some(data))) => content.push((data, mime_type.to_string())),
+                Ok(Ok(None)) => {}
+                Ok(Err(e)) => {
+                    error!("Exporting selection as {mime_type} for dragging failed, Err: {e:?}")
+                }
+                Err(e) => error!(
+                    "Receiving exported selection as {mime_type} for dragging failed, Err: {e:?}"
+                ),
+            }
+        }
+
+        content
+    }
+
     /// exports and writes the engine state as json into the file.
     /// Only for debugging!
     pub(crate) async fn export_engine_state(&self, file: &gio::File) -> anyhow::Result<()> {
@@ -367,6 +665,19 @@ impl RnCanvas {
         Ok(())
     }
 
+    /// Export the timings recorded by the engine profiler.
+    pub(crate) async fn export_profile(&self, file: &gio::File) -> anyhow::Result<()> {
+        let exported_profile = rnote_engine::engine::profiling::PROFILER
+            .profile()
+            .export_as_json()?;
+
+        crate::utils::create_replace_file_future(exported_profile.into_bytes(), file).await?;
+
+        self.set_last_export_dir(file.parent());
+
+        Ok(())
+    }
+
     fn determine_stroke_import_pos(
         &self,
         target_pos: Option<na::Vector2<f64>>,
@@ -384,4 +695,11 @@ impl RnCanvas {
                 ])
         })
     }
+
+    /// Translates the stroke so that its center is located at the center of the viewport.
+    fn center_stroke_in_viewport(&self, stroke: &mut Stroke) {
+        let viewport_center = self.engine_ref().camera.viewport_center();
+        let offset = viewport_center - stroke.bounds().center().coords;
+        stroke.translate(offset);
+    }
 }
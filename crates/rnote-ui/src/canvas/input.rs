@@ -1,6 +1,6 @@
 // Imports
 use super::RnCanvas;
-use gtk4::{gdk, glib, graphene, prelude::*, Native};
+use gtk4::{gdk, glib, graphene, prelude::*, subclass::prelude::*, Native};
 use rnote_compose::penevent::{KeyboardKey, ModifierKey, PenEvent, PenState, ShortcutKey};
 use rnote_compose::penpath::Element;
 use rnote_engine::ext::EventPropagationExt;
@@ -10,7 +10,7 @@ use rnote_engine::pens::PenMode;
 use rnote_engine::WidgetFlags;
 use std::collections::HashSet;
 use std::time::{Duration, Instant};
-use tracing::trace;
+use tracing::{error, trace};
 
 // Returns whether the event should be inhibited from propagating, and the new pen state
 pub(crate) fn handle_pointer_controller_event(
@@ -23,16 +23,45 @@ pub(crate) fn handle_pointer_controller_event(
     let touch_drawing = canvas.touch_drawing();
     let gdk_event_type = event.event_type();
     let gdk_modifiers = event.modifier_state();
-    let _gdk_device = event.device().unwrap();
+    let gdk_device = event.device().unwrap();
     let backlog_policy = canvas.engine_ref().penholder.backlog_policy();
     let is_stylus = event_is_stylus(event);
 
     //std::thread::sleep(std::time::Duration::from_millis(100));
     //super::input::debug_gdk_event(event);
 
+    if canvas.input_device_blocked(&gdk_device) {
+        return (glib::Propagation::Stop, pen_state);
+    }
+    if is_stylus {
+        canvas.imp().last_stylus_input.set(Some(now));
+    }
+    // Palms resting on the screen while using the stylus neither draw nor scroll the canvas
+    if event_is_touch(event) && canvas.palm_rejection_active(now) {
+        trace!("canvas event rejected by palm rejection - gdk_event_type: {gdk_event_type:?}");
+        return (glib::Propagation::Stop, pen_state);
+    }
     if reject_pointer_input(event, touch_drawing) {
         return (glib::Propagation::Proceed, pen_state);
     }
+    // Pressing down with Alt held over the selection starts dragging it out of the canvas,
+    // which is handled by the canvas drag source.
+    if gdk_event_type == gdk::EventType::ButtonPress
+        && gdk_modifiers.contains(gdk::ModifierType::ALT_MASK)
+        && event_widget_pos(canvas, event).is_some_and(|pos| canvas.pos_in_selection(pos))
+    {
+        return (glib::Propagation::Proceed, pen_state);
+    }
+
+    // Dragging with the secondary mouse button pans the canvas when enabled, which is handled by the canvas wrapper.
+    if !is_stylus
+        && canvas.secondary_drag_pan()
+        && event
+            .downcast_ref::<gdk::ButtonEvent>()
+            .is_some_and(|button_event| button_event.button() == gdk::BUTTON_SECONDARY)
+    {
+        return (glib::Propagation::Proceed, pen_state);
+    }
 
     let mut handle_pen_event = false;
     let mut propagation = glib::Propagation::Proceed;
@@ -96,6 +125,13 @@ pub(crate) fn handle_pointer_controller_event(
 
             trace!("canvas event ButtonRelease - gdk_button: {gdk_button}, is_stylus: {is_stylus}");
 
+            if let Some(shortcut_key) = retrieve_button_shortcut_key(gdk_button, is_stylus) {
+                let (_, wf) = canvas
+                    .engine_mut()
+                    .handle_released_shortcut_key(shortcut_key, now);
+                widget_flags |= wf;
+            }
+
             if is_stylus {
                 if gdk_button == gdk::BUTTON_PRIMARY
                     || gdk_button == gdk::BUTTON_SECONDARY
@@ -238,6 +274,28 @@ pub(crate) fn handle_key_controller_key_pressed(
     trace!("canvas event key pressed - gdk_key: {gdk_key:?}, gdk_modifiers: {gdk_modifiers:?}");
     canvas.grab_focus();
 
+    if canvas.engine_ref().presentation_mode() {
+        return handle_presentation_mode_key_pressed(canvas, gdk_key);
+    }
+
+    // Adjusting the stroke width with the bracket keys.
+    // Pens without a stroke width, like the typewriter, receive the keys as usual.
+    if !gdk_modifiers.intersects(
+        gdk::ModifierType::CONTROL_MASK
+            | gdk::ModifierType::ALT_MASK
+            | gdk::ModifierType::SUPER_MASK,
+    ) {
+        match gdk_key {
+            gdk::Key::bracketleft if canvas.step_stroke_width(false) => {
+                return glib::Propagation::Stop;
+            }
+            gdk::Key::bracketright if canvas.step_stroke_width(true) => {
+                return glib::Propagation::Stop;
+            }
+            _ => {}
+        }
+    }
+
     let now = Instant::now();
     let keyboard_key = retrieve_keyboard_key(gdk_key);
     let modifier_keys = retrieve_modifier_keys(gdk_modifiers);
@@ -262,12 +320,46 @@ pub(crate) fn handle_key_controller_key_pressed(
     propagation.into_glib()
 }
 
+fn handle_presentation_mode_key_pressed(canvas: &RnCanvas, gdk_key: gdk::Key) -> glib::Propagation {
+    let widget_flags = match gdk_key {
+        gdk::Key::Left | gdk::Key::Up | gdk::Key::Page_Up | gdk::Key::BackSpace => {
+            canvas.engine_mut().flip_page(false)
+        }
+        gdk::Key::Right | gdk::Key::Down | gdk::Key::Page_Down | gdk::Key::space => {
+            canvas.engine_mut().flip_page(true)
+        }
+        gdk::Key::Escape => {
+            if let Err(e) = canvas.activate_action("win.presentation-mode", None) {
+                error!("Activating action `presentation-mode` failed, Err: {e:?}");
+            }
+            return glib::Propagation::Stop;
+        }
+        _ => return glib::Propagation::Proceed,
+    };
+    canvas.emit_handle_widget_flags(widget_flags);
+    glib::Propagation::Stop
+}
+
 pub(crate) fn handle_key_controller_key_released(
-    _canvas: &RnCanvas,
+    canvas: &RnCanvas,
     gdk_key: gdk::Key,
     gdk_modifiers: gdk::ModifierType,
 ) {
     trace!("canvas event key released - gdk_key: {gdk_key:?}, gdk_modifiers: {gdk_modifiers:?}");
+
+    // Releasing any key of a key combination releases the shortcut
+    let shortcut_key = match gdk_key {
+        gdk::Key::space | gdk::Key::Control_L | gdk::Key::Control_R => {
+            Some(ShortcutKey::KeyboardCtrlSpace)
+        }
+        _ => None,
+    };
+    if let Some(shortcut_key) = shortcut_key {
+        let (_, widget_flags) = canvas
+            .engine_mut()
+            .handle_released_shortcut_key(shortcut_key, Instant::now());
+        canvas.emit_handle_widget_flags(widget_flags);
+    }
 }
 
 pub(crate) fn handle_imcontext_text_commit(canvas: &RnCanvas, text: &str) {
@@ -306,24 +398,40 @@ fn reject_pointer_input(event: &gdk::Event, touch_drawing: bool) -> bool {
             return true;
         }
     } else {
-        let event_type = event.event_type();
-        if event.is_pointer_emulated()
-            || event_type == gdk::EventType::TouchBegin
-            || event_type == gdk::EventType::TouchUpdate
-            || event_type == gdk::EventType::TouchEnd
-            || event_type == gdk::EventType::TouchCancel
-        {
+        if event_is_touch(event) {
             return true;
         }
     }
     false
 }
 
+fn event_is_touch(event: &gdk::Event) -> bool {
+    let event_type = event.event_type();
+    event.is_pointer_emulated()
+        || event_type == gdk::EventType::TouchBegin
+        || event_type == gdk::EventType::TouchUpdate
+        || event_type == gdk::EventType::TouchEnd
+        || event_type == gdk::EventType::TouchCancel
+}
+
 fn event_is_stylus(event: &gdk::Event) -> bool {
     // As in gtk4 'gtkgesturestylus.c:106' we detect if the pointer is a stylus when it has a device tool
     event.device_tool().is_some()
 }
 
+/// The position of the event in the canvas widget coordinate space.
+fn event_widget_pos(canvas: &RnCanvas, event: &gdk::Event) -> Option<na::Vector2<f64>> {
+    let event_native = Native::for_surface(&event.surface()?)?;
+    let (surface_trans_x, surface_trans_y) = event_native.surface_transform();
+    let (x, y) = event.position()?;
+    event_native
+        .compute_point(
+            canvas,
+            &graphene::Point::from_na_vec(na::vector![x - surface_trans_x, y - surface_trans_y]),
+        )
+        .map(|p| na::vector![p.x() as f64, p.y() as f64])
+}
+
 fn retrieve_pointer_elements(
     canvas: &RnCanvas,
     now: Instant,
@@ -492,6 +600,10 @@ pub(crate) fn retrieve_keyboard_key(gdk_key: gdk::Key) -> KeyboardKey {
             gdk::Key::KP_Left => KeyboardKey::NavLeft,
             gdk::Key::Right => KeyboardKey::NavRight,
             gdk::Key::KP_Right => KeyboardKey::NavRight,
+            gdk::Key::Page_Up => KeyboardKey::PageUp,
+            gdk::Key::KP_Page_Up => KeyboardKey::PageUp,
+            gdk::Key::Page_Down => KeyboardKey::PageDown,
+            gdk::Key::KP_Page_Down => KeyboardKey::PageDown,
             gdk::Key::Shift_L => KeyboardKey::ShiftLeft,
             gdk::Key::Shift_R => KeyboardKey::ShiftRight,
             gdk::Key::Control_L => KeyboardKey::CtrlLeft,
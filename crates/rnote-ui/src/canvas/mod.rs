@@ -1,11 +1,13 @@
 // Modules
 mod canvaslayout;
+mod filelock;
 pub(crate) mod imexport;
 mod input;
 mod widgetflagsboxed;
 
 // Re-exports
 pub(crate) use canvaslayout::RnCanvasLayout;
+pub(crate) use filelock::{FileLock, LockOwner};
 pub(crate) use widgetflagsboxed::WidgetFlagsBoxed;
 
 // Imports
@@ -14,8 +16,8 @@ use futures::StreamExt;
 use gettextrs::gettext;
 use gtk4::{
     gdk, gio, glib, glib::clone, graphene, prelude::*, subclass::prelude::*, Adjustment,
-    DropTarget, EventControllerKey, EventControllerLegacy, IMMulticontext, PropagationPhase,
-    Scrollable, ScrollablePolicy, Widget,
+    DragSource, DropTarget, EventControllerKey, EventControllerLegacy, EventSequenceState,
+    GestureClick, IMMulticontext, PropagationPhase, Scrollable, ScrollablePolicy, Widget,
 };
 use notify::event::{AccessKind, AccessMode, ModifyKind, RenameMode};
 use notify::EventKind;
@@ -26,11 +28,12 @@ use rnote_compose::ext::AabbExt;
 use rnote_compose::penevent::PenState;
 use rnote_engine::ext::GraphenePointExt;
 use rnote_engine::ext::GrapheneRectExt;
+use rnote_engine::pens::pensconfig::{BrushConfig, EraserConfig, ShaperConfig};
 use rnote_engine::Camera;
 use rnote_engine::{Engine, WidgetFlags};
 use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::path::Path;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tracing::{debug, error, warn};
 
 #[derive(Debug, Default)]
@@ -44,8 +47,12 @@ struct Connections {
     appwindow_scalefactor: Option<glib::SignalHandlerId>,
     appwindow_save_in_progress: Option<glib::SignalHandlerId>,
     appwindow_unsaved_changes: Option<glib::SignalHandlerId>,
+    appwindow_read_only: Option<glib::SignalHandlerId>,
     appwindow_touch_drawing: Option<glib::Binding>,
+    appwindow_palm_rejection_timeout_ms: Option<glib::Binding>,
+    appwindow_blocked_input_devices: Option<glib::Binding>,
     appwindow_show_drawing_cursor: Option<glib::Binding>,
+    appwindow_secondary_drag_pan: Option<glib::Binding>,
     appwindow_regular_cursor: Option<glib::Binding>,
     appwindow_drawing_cursor: Option<glib::Binding>,
     appwindow_drop_target: Option<glib::SignalHandlerId>,
@@ -71,6 +78,10 @@ mod imp {
         pub(crate) key_controller: EventControllerKey,
         pub(crate) key_controller_im_context: IMMulticontext,
         pub(crate) drop_target: DropTarget,
+        pub(crate) drag_source: DragSource,
+        pub(crate) presentation_tap_gesture: GestureClick,
+        pub(crate) stroke_width_indicator: Cell<Option<f64>>,
+        pub(crate) stroke_width_indicator_hide_source: RefCell<Option<glib::SourceId>>,
         pub(crate) drawing_cursor_enabled: Cell<bool>,
 
         pub(crate) engine: RefCell<Engine>,
@@ -81,10 +92,16 @@ mod imp {
         pub(crate) output_file_watcher_task: RefCell<Option<glib::JoinHandle<()>>>,
         pub(crate) output_file_modified_toast_singleton: glib::WeakRef<adw::Toast>,
         pub(crate) output_file_expect_write: Cell<bool>,
+        pub(crate) output_file_lock: RefCell<Option<FileLock>>,
+        pub(crate) read_only: Cell<bool>,
         pub(crate) save_in_progress: Cell<bool>,
         pub(crate) unsaved_changes: Cell<bool>,
         pub(crate) empty: Cell<bool>,
         pub(crate) touch_drawing: Cell<bool>,
+        pub(crate) secondary_drag_pan: Cell<bool>,
+        pub(crate) palm_rejection_timeout_ms: Cell<u32>,
+        pub(crate) blocked_input_devices: RefCell<Vec<String>>,
+        pub(crate) last_stylus_input: Cell<Option<Instant>>,
         pub(crate) show_drawing_cursor: Cell<bool>,
 
         pub(crate) last_export_dir: RefCell<Option<gio::File>>,
@@ -113,6 +130,21 @@ mod imp {
             // the order here is important: first files, then text
             drop_target.set_types(&[gio::File::static_type(), glib::types::Type::STRING]);
 
+            // Dragging the selection out of the canvas is started with the Alt modifier held down,
+            // so that it doesn't interfere with moving the selection.
+            let drag_source = DragSource::builder()
+                .name("canvas_drag_source")
+                .propagation_phase(PropagationPhase::Capture)
+                .actions(gdk::DragAction::COPY)
+                .build();
+
+            // In presentation mode, tapping on the left or right half of the canvas flips the pages
+            let presentation_tap_gesture = GestureClick::builder()
+                .name("presentation_tap_gesture")
+                .touch_only(true)
+                .propagation_phase(PropagationPhase::Bubble)
+                .build();
+
             let regular_cursor_icon_name = String::from("cursor-dot-medium");
             let regular_cursor = gdk::Cursor::from_texture(
                 &gdk::Texture::from_resource(
@@ -165,6 +197,10 @@ mod imp {
                 key_controller,
                 key_controller_im_context,
                 drop_target,
+                drag_source,
+                presentation_tap_gesture,
+                stroke_width_indicator: Cell::new(None),
+                stroke_width_indicator_hide_source: RefCell::new(None),
                 drawing_cursor_enabled: Cell::new(false),
 
                 engine: RefCell::new(engine),
@@ -176,10 +212,16 @@ mod imp {
                 // is automatically updated whenever the output file changes.
                 output_file_modified_toast_singleton: glib::WeakRef::new(),
                 output_file_expect_write: Cell::new(false),
+                output_file_lock: RefCell::new(None),
+                read_only: Cell::new(false),
                 save_in_progress: Cell::new(false),
                 unsaved_changes: Cell::new(false),
                 empty: Cell::new(true),
                 touch_drawing: Cell::new(false),
+                secondary_drag_pan: Cell::new(false),
+                palm_rejection_timeout_ms: Cell::new(0),
+                blocked_input_devices: RefCell::new(vec![]),
+                last_stylus_input: Cell::new(None),
                 show_drawing_cursor: Cell::new(false),
 
                 last_export_dir: RefCell::new(None),
@@ -219,6 +261,8 @@ mod imp {
             obj.add_controller(self.pointer_controller.clone());
             obj.add_controller(self.key_controller.clone());
             obj.add_controller(self.drop_target.clone());
+            obj.add_controller(self.drag_source.clone());
+            obj.add_controller(self.presentation_tap_gesture.clone());
 
             // receive and handle engine tasks
             let engine_task_handler_handle = glib::spawn_future_local(clone!(
@@ -232,14 +276,21 @@ mod imp {
                         return;
                     };
 
-                    loop {
-                        if let Some(task) = task_rx.recv().await {
-                            let (widget_flags, quit) = canvas.engine_mut().handle_engine_task(task);
-                            canvas.emit_handle_widget_flags(widget_flags);
+                    // Tasks that piled up in the meantime are handled together,
+                    // with the redundant ones coalesced
+                    while let Some(tasks) = task_rx.recv_coalesced().await {
+                        let mut widget_flags = WidgetFlags::default();
+                        let mut quit = false;
+                        for task in tasks {
+                            let (task_widget_flags, task_quit) =
+                                canvas.engine_mut().handle_engine_task(task);
+                            widget_flags |= task_widget_flags;
+                            quit |= task_quit;
+                        }
+                        canvas.emit_handle_widget_flags(widget_flags);
 
-                            if quit {
-                                break;
-                            }
+                        if quit {
+                            break;
                         }
                     }
                 }
@@ -276,6 +327,7 @@ mod imp {
         fn dispose(&self) {
             self.obj().disconnect_connections();
             self.obj().abort_engine_task_handler();
+            self.output_file_lock.take();
 
             while let Some(child) = self.obj().first_child() {
                 child.unparent();
@@ -287,6 +339,9 @@ mod imp {
                 vec![
                     // this is nullable, so it can be used to represent Option<gio::File>
                     glib::ParamSpecObject::builder::<gio::File>("output-file").build(),
+                    glib::ParamSpecBoolean::builder("read-only")
+                        .default_value(false)
+                        .build(),
                     glib::ParamSpecBoolean::builder("save-in-progress")
                         .default_value(false)
                         .build(),
@@ -299,6 +354,13 @@ mod imp {
                     glib::ParamSpecBoolean::builder("touch-drawing")
                         .default_value(false)
                         .build(),
+                    glib::ParamSpecBoolean::builder("secondary-drag-pan")
+                        .default_value(false)
+                        .build(),
+                    glib::ParamSpecUInt::builder("palm-rejection-timeout-ms")
+                        .default_value(0)
+                        .build(),
+                    glib::ParamSpecBoxed::builder::<Vec<String>>("blocked-input-devices").build(),
                     glib::ParamSpecBoolean::builder("show-drawing-cursor")
                         .default_value(true)
                         .build(),
@@ -321,6 +383,7 @@ mod imp {
         fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
             match pspec.name() {
                 "output-file" => self.output_file.borrow().to_value(),
+                "read-only" => self.read_only.get().to_value(),
                 "save-in-progress" => self.save_in_progress.get().to_value(),
                 "unsaved-changes" => self.unsaved_changes.get().to_value(),
                 "empty" => self.empty.get().to_value(),
@@ -329,6 +392,9 @@ mod imp {
                 "hscroll-policy" => self.hscroll_policy.get().to_value(),
                 "vscroll-policy" => self.vscroll_policy.get().to_value(),
                 "touch-drawing" => self.touch_drawing.get().to_value(),
+                "secondary-drag-pan" => self.secondary_drag_pan.get().to_value(),
+                "palm-rejection-timeout-ms" => self.palm_rejection_timeout_ms.get().to_value(),
+                "blocked-input-devices" => self.blocked_input_devices.borrow().to_value(),
                 "show-drawing-cursor" => self.show_drawing_cursor.get().to_value(),
                 "regular-cursor" => self.regular_cursor_icon_name.borrow().to_value(),
                 "drawing-cursor" => self.drawing_cursor_icon_name.borrow().to_value(),
@@ -344,7 +410,19 @@ mod imp {
                     let output_file = value
                         .get::<Option<gio::File>>()
                         .expect("The value needs to be of type `Option<gio::File>`");
+                    // Drafts are never read-only
+                    if output_file.is_none() && self.read_only.get() {
+                        self.read_only.set(false);
+                        obj.notify("read-only");
+                    }
                     self.output_file.replace(output_file);
+                    obj.update_output_file_lock();
+                }
+                "read-only" => {
+                    let read_only: bool =
+                        value.get().expect("The value needs to be of type `bool`");
+                    self.read_only.replace(read_only);
+                    obj.update_output_file_lock();
                 }
                 "save-in-progress" => {
                     let save_in_progress: bool =
@@ -384,6 +462,23 @@ mod imp {
                         value.get().expect("The value needs to be of type `bool`");
                     self.touch_drawing.replace(touch_drawing);
                 }
+                "secondary-drag-pan" => {
+                    let secondary_drag_pan: bool =
+                        value.get().expect("The value needs to be of type `bool`");
+                    self.secondary_drag_pan.replace(secondary_drag_pan);
+                }
+                "palm-rejection-timeout-ms" => {
+                    let palm_rejection_timeout_ms: u32 =
+                        value.get().expect("The value needs to be of type `u32`");
+                    self.palm_rejection_timeout_ms
+                        .replace(palm_rejection_timeout_ms);
+                }
+                "blocked-input-devices" => {
+                    let blocked_input_devices: Vec<String> = value
+                        .get()
+                        .expect("The value needs to be of type `Vec<String>`");
+                    self.blocked_input_devices.replace(blocked_input_devices);
+                }
                 "show-drawing-cursor" => {
                     let show_drawing_cursor: bool =
                         value.get().expect("The value needs to be of type `bool`");
@@ -452,6 +547,7 @@ mod imp {
                         .param_types([WidgetFlagsBoxed::static_type()])
                         .build(),
                     glib::subclass::Signal::builder("invalidate-thumbnail").build(),
+                    glib::subclass::Signal::builder("content-changed").build(),
                 ]
             });
             SIGNALS.as_ref()
@@ -484,6 +580,10 @@ mod imp {
                     .borrow()
                     .draw_to_gtk_snapshot(snapshot, obj.bounds())?;
 
+                if let Some(stroke_width) = self.stroke_width_indicator.get() {
+                    self.draw_stroke_width_indicator(snapshot, clip_bounds, stroke_width)?;
+                }
+
                 // pop the clip
                 snapshot.pop();
                 Ok(())
@@ -496,6 +596,52 @@ mod imp {
     impl ScrollableImpl for RnCanvas {}
 
     impl RnCanvas {
+        /// Draws the stroke width indicator in its actual size at the center of the visible area.
+        fn draw_stroke_width_indicator(
+            &self,
+            snapshot: &gtk4::Snapshot,
+            clip_bounds: Aabb,
+            stroke_width: f64,
+        ) -> anyhow::Result<()> {
+            const OUTLINE_WIDTH: f64 = 2.0;
+            const LABEL_FONT_SIZE: f64 = 14.0;
+            let total_zoom = self.engine.borrow().camera.total_zoom();
+            let radius = (stroke_width * total_zoom * 0.5).max(1.0);
+            let center = clip_bounds.center();
+
+            let cairo_cx = snapshot.append_cairo(&graphene::Rect::from_p2d_aabb(clip_bounds));
+            cairo_cx.arc(
+                center[0],
+                center[1],
+                radius,
+                0.0,
+                std::f64::consts::PI * 2.0,
+            );
+            cairo_cx.set_source_rgba(0.0, 0.0, 0.0, 0.2);
+            cairo_cx.fill_preserve()?;
+            cairo_cx.set_line_width(OUTLINE_WIDTH);
+            cairo_cx.set_source_rgba(1.0, 1.0, 1.0, 0.9);
+            cairo_cx.stroke_preserve()?;
+            cairo_cx.set_line_width(OUTLINE_WIDTH * 0.5);
+            cairo_cx.set_source_rgba(0.0, 0.0, 0.0, 0.9);
+            cairo_cx.stroke()?;
+
+            let label = format!("{stroke_width:.1}");
+            cairo_cx.set_font_size(LABEL_FONT_SIZE);
+            let extents = cairo_cx.text_extents(&label)?;
+            cairo_cx.move_to(
+                center[0] - extents.width() * 0.5,
+                center[1] + radius + OUTLINE_WIDTH + LABEL_FONT_SIZE * 1.5,
+            );
+            cairo_cx.text_path(&label);
+            cairo_cx.set_line_width(3.0);
+            cairo_cx.set_source_rgba(1.0, 1.0, 1.0, 0.9);
+            cairo_cx.stroke_preserve()?;
+            cairo_cx.set_source_rgba(0.0, 0.0, 0.0, 1.0);
+            cairo_cx.fill()?;
+            Ok(())
+        }
+
         fn setup_input(&self) {
             let obj = self.obj();
 
@@ -519,6 +665,65 @@ mod imp {
                 }
             ));
 
+            // Drag source
+            self.drag_source.connect_prepare(clone!(
+                #[weak(rename_to=canvas)]
+                obj,
+                #[upgrade_or]
+                None,
+                move |drag_source, x, y| {
+                    let modifiers = drag_source.current_event_state();
+                    if !modifiers.contains(gdk::ModifierType::ALT_MASK)
+                        || !canvas.pos_in_selection(na::vector![x, y])
+                    {
+                        return None;
+                    }
+                    let content = canvas.export_selection_drag_content();
+                    if content.is_empty() {
+                        return None;
+                    }
+                    if let Some(png_bytes) = content
+                        .iter()
+                        .find_map(|(data, mime_type)| (mime_type == "image/png").then_some(data))
+                    {
+                        match gdk::Texture::from_bytes(&glib::Bytes::from(png_bytes.as_slice())) {
+                            Ok(texture) => drag_source.set_icon(Some(&texture), 0, 0),
+                            Err(e) => warn!(
+                                "Creating drag icon from exported selection failed, Err: {e:?}"
+                            ),
+                        }
+                    }
+
+                    Some(gdk::ContentProvider::new_union(
+                        content
+                            .into_iter()
+                            .map(|(data, mime_type)| {
+                                gdk::ContentProvider::for_bytes(
+                                    &mime_type,
+                                    &glib::Bytes::from_owned(data),
+                                )
+                            })
+                            .collect::<Vec<gdk::ContentProvider>>()
+                            .as_slice(),
+                    ))
+                }
+            ));
+
+            // Presentation tap gesture
+            self.presentation_tap_gesture.connect_released(clone!(
+                #[weak(rename_to=canvas)]
+                obj,
+                move |gesture, n_press, x, _y| {
+                    if n_press != 1 || !canvas.engine_ref().presentation_mode() {
+                        return;
+                    }
+                    gesture.set_state(EventSequenceState::Claimed);
+                    let forward = x >= f64::from(canvas.width()) * 0.5;
+                    let widget_flags = canvas.engine_mut().flip_page(forward);
+                    canvas.emit_handle_widget_flags(widget_flags);
+                }
+            ));
+
             // For unicode text the input is committed from the IM context, and won't trigger the key_pressed signal
             self.key_controller_im_context.connect_commit(clone!(
                 #[weak(rename_to=canvas)]
@@ -695,6 +900,49 @@ impl RnCanvas {
         self.set_property("output-file", output_file.to_value());
     }
 
+    /// Whether the document is opened read-only, then it is never saved to its output file.
+    #[allow(unused)]
+    pub(crate) fn read_only(&self) -> bool {
+        self.property::<bool>("read-only")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_read_only(&self, read_only: bool) {
+        if self.imp().read_only.get() != read_only {
+            self.set_property("read-only", read_only.to_value());
+        }
+    }
+
+    /// The output file that the document is saved to, None when there is none or the document is opened read-only.
+    pub(crate) fn writable_output_file(&self) -> Option<gio::File> {
+        self.output_file().filter(|_| !self.read_only())
+    }
+
+    /// Hold the lock of the output file while the document is opened for editing, so that other instances
+    /// can notice it. Releases the lock of the previous output file.
+    fn update_output_file_lock(&self) {
+        let imp = self.imp();
+        imp.output_file_lock.take();
+        if imp.read_only.get() {
+            return;
+        }
+        let Some(output_file_path) = imp.output_file.borrow().as_ref().and_then(|f| f.path())
+        else {
+            return;
+        };
+        match FileLock::acquire(&output_file_path) {
+            Ok(lock) => {
+                imp.output_file_lock.replace(Some(lock));
+            }
+            Err(e) => {
+                warn!(
+                    "Acquiring lock for output file `{}` failed, Err: {e:?}",
+                    output_file_path.display()
+                );
+            }
+        }
+    }
+
     #[allow(unused)]
     pub(crate) fn output_file_expect_write(&self) -> bool {
         self.imp().output_file_expect_write.get()
@@ -753,6 +1001,45 @@ impl RnCanvas {
         }
     }
 
+    #[allow(unused)]
+    pub(crate) fn palm_rejection_timeout_ms(&self) -> u32 {
+        self.property::<u32>("palm-rejection-timeout-ms")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_palm_rejection_timeout_ms(&self, palm_rejection_timeout_ms: u32) {
+        if self.imp().palm_rejection_timeout_ms.get() != palm_rejection_timeout_ms {
+            self.set_property(
+                "palm-rejection-timeout-ms",
+                palm_rejection_timeout_ms.to_value(),
+            );
+        }
+    }
+
+    /// Whether dragging with the secondary mouse button pans the canvas instead of being handled as pen input.
+    pub(crate) fn secondary_drag_pan(&self) -> bool {
+        self.property::<bool>("secondary-drag-pan")
+    }
+
+    /// Whether input from the device is ignored on the canvas.
+    pub(crate) fn input_device_blocked(&self, device: &gdk::Device) -> bool {
+        let name = device.name();
+        self.imp()
+            .blocked_input_devices
+            .borrow()
+            .iter()
+            .any(|blocked| *blocked == name)
+    }
+
+    /// Whether touch input is currently rejected, because the stylus was used within the palm rejection timeout.
+    pub(crate) fn palm_rejection_active(&self, now: Instant) -> bool {
+        let imp = self.imp();
+        let timeout = Duration::from_millis(imp.palm_rejection_timeout_ms.get() as u64);
+        imp.last_stylus_input
+            .get()
+            .is_some_and(|last| now.saturating_duration_since(last) < timeout)
+    }
+
     #[allow(unused)]
     pub(crate) fn show_drawing_cursor(&self) -> bool {
         self.property::<bool>("show-drawing-cursor")
@@ -777,6 +1064,11 @@ impl RnCanvas {
         self.emit_by_name::<()>("invalidate-thumbnail", &[]);
     }
 
+    /// Emitted when the strokes of the document were modified.
+    pub(crate) fn emit_content_changed(&self) {
+        self.emit_by_name::<()>("content-changed", &[]);
+    }
+
     pub(crate) fn last_export_dir(&self) -> Option<gio::File> {
         self.imp().last_export_dir.borrow().clone()
     }
@@ -840,6 +1132,68 @@ impl RnCanvas {
         self.imp().engine.borrow_mut()
     }
 
+    /// Increase or decrease the stroke width of the current pen and show the stroke width indicator.
+    ///
+    /// Returns false if the current pen has no stroke width.
+    pub(crate) fn step_stroke_width(&self, increase: bool) -> bool {
+        let (stroke_width, widget_flags) =
+            self.engine_mut().step_current_pen_stroke_width(increase);
+        let Some(stroke_width) = stroke_width else {
+            return false;
+        };
+        self.emit_handle_widget_flags(widget_flags);
+        self.show_stroke_width_indicator(stroke_width);
+        true
+    }
+
+    /// Switch the stroke width of the current pen to its width preset at the given index
+    /// and show the stroke width indicator.
+    ///
+    /// Returns false if the current pen has no stroke width.
+    pub(crate) fn apply_width_preset(&self, index: usize) -> bool {
+        let (stroke_width, widget_flags) = self.engine_mut().apply_current_pen_width_preset(index);
+        let Some(stroke_width) = stroke_width else {
+            return false;
+        };
+        self.emit_handle_widget_flags(widget_flags);
+        self.show_stroke_width_indicator(stroke_width);
+        true
+    }
+
+    fn show_stroke_width_indicator(&self, stroke_width: f64) {
+        /// How long the stroke width indicator stays visible after the last change.
+        const INDICATOR_TIMEOUT: Duration = Duration::from_millis(800);
+
+        self.imp().stroke_width_indicator.set(Some(stroke_width));
+        if let Some(source) = self.imp().stroke_width_indicator_hide_source.replace(Some(
+            glib::timeout_add_local_once(
+                INDICATOR_TIMEOUT,
+                clone!(
+                    #[weak(rename_to=canvas)]
+                    self,
+                    move || {
+                        canvas.imp().stroke_width_indicator_hide_source.take();
+                        canvas.imp().stroke_width_indicator.set(None);
+                        canvas.queue_draw();
+                    }
+                ),
+            ),
+        )) {
+            source.remove();
+        }
+        self.queue_draw();
+    }
+
+    /// Whether the given position in widget coordinates lies inside the bounds of the current selection.
+    pub(crate) fn pos_in_selection(&self, pos: na::Vector2<f64>) -> bool {
+        let engine = self.engine_ref();
+        let Some(selection_bounds) = engine.selection_bounds() else {
+            return false;
+        };
+        let doc_pos = engine.camera.transform().inverse() * na::Point2::from(pos);
+        selection_bounds.contains_local_point(&doc_pos)
+    }
+
     /// Abort the engine task handler.
     ///
     /// Because the installed engine task handler holds a reference to the canvas,
@@ -894,6 +1248,57 @@ impl RnCanvas {
         if let Some(widget_flags) = widget_flags {
             self.emit_handle_widget_flags(widget_flags);
         }
+        if let Err(e) = self.migrate_width_presets_from_settings(settings) {
+            error!("Migrating the stroke width presets from the settings failed, Err: {e:?}");
+        }
+        Ok(())
+    }
+
+    /// Migrate the stroke widths that older versions stored in the deprecated `<pen>-width-<n>` settings keys
+    /// into the width presets of the pen configs.
+    ///
+    /// Runs only once, afterwards the presets are stored with the engine config.
+    /// Only the widths that were changed by the user are migrated.
+    fn migrate_width_presets_from_settings(&self, settings: &gio::Settings) -> anyhow::Result<()> {
+        if settings.boolean("width-presets-migrated") {
+            return Ok(());
+        }
+        let migrate = |presets: &mut [f64; 3], pen: &str, min: f64, max: f64| {
+            for (i, preset) in presets.iter_mut().enumerate() {
+                let key = format!("{pen}-width-{}", i + 1);
+                if settings.user_value(&key).is_some() {
+                    *preset = settings.double(&key).clamp(min, max);
+                }
+            }
+        };
+        let mut widget_flags = WidgetFlags::default();
+        {
+            let mut engine = self.engine_mut();
+            let pens_config = &mut engine.pens_config;
+            migrate(
+                &mut pens_config.brush_config.width_presets,
+                "brush",
+                BrushConfig::STROKE_WIDTH_MIN,
+                BrushConfig::STROKE_WIDTH_MAX,
+            );
+            migrate(
+                &mut pens_config.shaper_config.width_presets,
+                "shaper",
+                ShaperConfig::STROKE_WIDTH_MIN,
+                ShaperConfig::STROKE_WIDTH_MAX,
+            );
+            migrate(
+                &mut pens_config.eraser_config.width_presets,
+                "eraser",
+                EraserConfig::WIDTH_MIN,
+                EraserConfig::WIDTH_MAX,
+            );
+        }
+        settings.set_boolean("width-presets-migrated", true)?;
+        debug!("Migrated the stroke width presets from the settings");
+
+        widget_flags.refresh_ui = true;
+        self.emit_handle_widget_flags(widget_flags);
         Ok(())
     }
 
@@ -1173,6 +1578,17 @@ impl RnCanvas {
             ),
         );
 
+        // set handedness, is updated by the appwindow when it changes
+        let _ = self.engine_mut().set_righthanded(appwindow.righthanded());
+        // the visual settings, are updated by the settings panel when they change
+        let _ = self
+            .engine_mut()
+            .set_visual_settings(appwindow.sidebar().settings_panel().visual_settings());
+        // and the presentation mode, so that newly opened tabs can't be modified while presenting
+        let _ = self
+            .engine_mut()
+            .set_presentation_mode(appwindow.presentation_mode());
+
         // set scale factor initially
         let _ = self
             .engine_mut()
@@ -1202,6 +1618,17 @@ impl RnCanvas {
             ),
         );
 
+        let appwindow_read_only = self.connect_notify_local(
+            Some("read-only"),
+            clone!(
+                #[weak]
+                appwindow,
+                move |canvas, _| {
+                    appwindow.refresh_titles(canvas);
+                }
+            ),
+        );
+
         // Update titles when there are changes
         let appwindow_unsaved_changes = self.connect_notify_local(
             Some("unsaved-changes"),
@@ -1220,6 +1647,20 @@ impl RnCanvas {
             .sync_create()
             .build();
 
+        let appwindow_palm_rejection_timeout_ms = appwindow
+            .bind_property(
+                "palm-rejection-timeout-ms",
+                self,
+                "palm-rejection-timeout-ms",
+            )
+            .sync_create()
+            .build();
+
+        let appwindow_blocked_input_devices = appwindow
+            .bind_property("blocked-input-devices", self, "blocked-input-devices")
+            .sync_create()
+            .build();
+
         // bind cursors
         let appwindow_regular_cursor = appwindow
             .sidebar()
@@ -1248,6 +1689,14 @@ impl RnCanvas {
             .sync_create()
             .build();
 
+        let appwindow_secondary_drag_pan = appwindow
+            .sidebar()
+            .settings_panel()
+            .input_secondary_drag_pan_row()
+            .bind_property("active", self, "secondary-drag-pan")
+            .sync_create()
+            .build();
+
         // Drop Target
         let appwindow_drop_target = self.imp().drop_target.connect_drop(clone!(
             #[weak(rename_to=canvas)]
@@ -1346,18 +1795,39 @@ impl RnCanvas {
         {
             self.disconnect(old);
         }
+        if let Some(old) = connections.appwindow_read_only.replace(appwindow_read_only) {
+            self.disconnect(old);
+        }
         if let Some(old) = connections
             .appwindow_touch_drawing
             .replace(appwindow_touch_drawing)
         {
             old.unbind();
         }
+        if let Some(old) = connections
+            .appwindow_palm_rejection_timeout_ms
+            .replace(appwindow_palm_rejection_timeout_ms)
+        {
+            old.unbind();
+        }
+        if let Some(old) = connections
+            .appwindow_blocked_input_devices
+            .replace(appwindow_blocked_input_devices)
+        {
+            old.unbind();
+        }
         if let Some(old) = connections
             .appwindow_show_drawing_cursor
             .replace(appwindow_show_drawing_cursor)
         {
             old.unbind();
         }
+        if let Some(old) = connections
+            .appwindow_secondary_drag_pan
+            .replace(appwindow_secondary_drag_pan)
+        {
+            old.unbind();
+        }
         if let Some(old) = connections
             .appwindow_regular_cursor
             .replace(appwindow_regular_cursor)
@@ -1403,12 +1873,24 @@ impl RnCanvas {
         if let Some(old) = connections.appwindow_unsaved_changes.take() {
             self.disconnect(old);
         }
+        if let Some(old) = connections.appwindow_read_only.take() {
+            self.disconnect(old);
+        }
         if let Some(old) = connections.appwindow_touch_drawing.take() {
             old.unbind();
         }
+        if let Some(old) = connections.appwindow_palm_rejection_timeout_ms.take() {
+            old.unbind();
+        }
+        if let Some(old) = connections.appwindow_blocked_input_devices.take() {
+            old.unbind();
+        }
         if let Some(old) = connections.appwindow_show_drawing_cursor.take() {
             old.unbind();
         }
+        if let Some(old) = connections.appwindow_secondary_drag_pan.take() {
+            old.unbind();
+        }
         if let Some(old) = connections.appwindow_regular_cursor.take() {
             old.unbind();
         }
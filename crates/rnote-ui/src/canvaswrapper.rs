@@ -1,24 +1,30 @@
 // Imports
-use crate::{RnAppWindow, RnCanvas, RnContextMenu};
+use crate::{RnAppWindow, RnCanvas, RnContextMenu, RnPageThumbnails, RnRuler};
 use gtk4::{
     gdk, glib, glib::clone, graphene, prelude::*, subclass::prelude::*, CompositeTemplate,
     CornerType, EventControllerMotion, EventControllerScroll, EventControllerScrollFlags,
-    EventSequenceState, GestureDrag, GestureLongPress, GestureZoom, PropagationPhase,
-    ScrolledWindow, Widget,
+    EventSequenceState, GestureClick, GestureDrag, GestureLongPress, GestureZoom, PropagationPhase,
+    Revealer, ScrolledWindow, Widget,
 };
 use once_cell::sync::Lazy;
 use rnote_compose::penevent::ShortcutKey;
 use rnote_engine::ext::GraphenePointExt;
-use rnote_engine::Camera;
+use rnote_engine::{Camera, WidgetFlags};
 use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
 use std::rc::Rc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Default)]
 struct Connections {
     appwindow_block_pinch_zoom_bind: Option<glib::Binding>,
     appwindow_show_scrollbars_bind: Option<glib::Binding>,
+    appwindow_show_rulers_bind: Option<glib::Binding>,
+    appwindow_show_page_thumbnails_bind: Option<glib::Binding>,
     appwindow_inertial_scrolling_bind: Option<glib::Binding>,
+    appwindow_shift_scroll_horizontal_bind: Option<glib::Binding>,
+    appwindow_wheel_zoom_bind: Option<glib::Binding>,
+    appwindow_zoom_around_pointer_bind: Option<glib::Binding>,
     appwindow_righthanded_bind: Option<glib::Binding>,
 }
 
@@ -31,27 +37,43 @@ mod imp {
         pub(super) connections: RefCell<Connections>,
         pub(crate) canvas_touch_drawing_handler: RefCell<Option<glib::SignalHandlerId>>,
         pub(crate) show_scrollbars: Cell<bool>,
+        pub(crate) show_rulers: Cell<bool>,
+        pub(crate) show_page_thumbnails: Cell<bool>,
         pub(crate) block_pinch_zoom: Cell<bool>,
         pub(crate) inertial_scrolling: Cell<bool>,
+        pub(crate) shift_scroll_horizontal: Cell<bool>,
+        pub(crate) wheel_zoom: Cell<bool>,
+        pub(crate) zoom_around_pointer: Cell<bool>,
         pub(crate) pointer_pos: Cell<Option<na::Vector2<f64>>>,
         pub(crate) last_contextmenu_pos: Cell<Option<na::Vector2<f64>>>,
+        pub(crate) touch_scroll_momentum_callback_id: RefCell<Option<gtk4::TickCallbackId>>,
 
         pub(crate) pointer_motion_controller: EventControllerMotion,
         pub(crate) canvas_drag_gesture: GestureDrag,
         pub(crate) canvas_zoom_gesture: GestureZoom,
         pub(crate) canvas_zoom_scroll_controller: EventControllerScroll,
         pub(crate) canvas_mouse_drag_middle_gesture: GestureDrag,
+        pub(crate) canvas_mouse_drag_secondary_gesture: GestureDrag,
         pub(crate) canvas_alt_drag_gesture: GestureDrag,
         pub(crate) canvas_alt_shift_drag_gesture: GestureDrag,
         pub(crate) touch_two_finger_long_press_gesture: GestureLongPress,
         pub(crate) touch_long_press_gesture: GestureLongPress,
+        pub(crate) canvas_secondary_click_gesture: GestureClick,
 
+        #[template_child]
+        pub(crate) hruler: TemplateChild<RnRuler>,
+        #[template_child]
+        pub(crate) vruler: TemplateChild<RnRuler>,
         #[template_child]
         pub(crate) scroller: TemplateChild<ScrolledWindow>,
         #[template_child]
         pub(crate) canvas: TemplateChild<RnCanvas>,
         #[template_child]
         pub(crate) contextmenu: TemplateChild<RnContextMenu>,
+        #[template_child]
+        pub(crate) page_thumbnails_revealer: TemplateChild<Revealer>,
+        #[template_child]
+        pub(crate) page_thumbnails: TemplateChild<RnPageThumbnails>,
     }
 
     impl Default for RnCanvasWrapper {
@@ -88,6 +110,14 @@ mod imp {
                 .propagation_phase(PropagationPhase::Bubble)
                 .build();
 
+            // panning with the secondary mouse button, when enabled in the settings
+            let canvas_mouse_drag_secondary_gesture = GestureDrag::builder()
+                .name("canvas_mouse_drag_secondary_gesture")
+                .button(gdk::BUTTON_SECONDARY)
+                .exclusive(true)
+                .propagation_phase(PropagationPhase::Bubble)
+                .build();
+
             // alt + drag for panning with pointer
             let canvas_alt_drag_gesture = GestureDrag::builder()
                 .name("canvas_alt_drag_gesture")
@@ -118,28 +148,45 @@ mod imp {
                 .touch_only(true)
                 .build();
 
+            let canvas_secondary_click_gesture = GestureClick::builder()
+                .name("canvas_secondary_click_gesture")
+                .button(gdk::BUTTON_SECONDARY)
+                .build();
+
             Self {
                 connections: RefCell::new(Connections::default()),
                 canvas_touch_drawing_handler: RefCell::new(None),
                 show_scrollbars: Cell::new(false),
+                show_rulers: Cell::new(false),
+                show_page_thumbnails: Cell::new(false),
                 block_pinch_zoom: Cell::new(false),
                 inertial_scrolling: Cell::new(true),
+                shift_scroll_horizontal: Cell::new(true),
+                wheel_zoom: Cell::new(false),
+                zoom_around_pointer: Cell::new(true),
                 pointer_pos: Cell::new(None),
                 last_contextmenu_pos: Cell::new(None),
+                touch_scroll_momentum_callback_id: RefCell::new(None),
 
                 pointer_motion_controller,
                 canvas_drag_gesture,
                 canvas_zoom_gesture,
                 canvas_zoom_scroll_controller,
                 canvas_mouse_drag_middle_gesture,
+                canvas_mouse_drag_secondary_gesture,
                 canvas_alt_drag_gesture,
                 canvas_alt_shift_drag_gesture,
                 touch_two_finger_long_press_gesture,
                 touch_long_press_gesture,
+                canvas_secondary_click_gesture,
 
+                hruler: TemplateChild::<RnRuler>::default(),
+                vruler: TemplateChild::<RnRuler>::default(),
                 scroller: TemplateChild::<ScrolledWindow>::default(),
                 canvas: TemplateChild::<RnCanvas>::default(),
                 contextmenu: TemplateChild::<RnContextMenu>::default(),
+                page_thumbnails_revealer: TemplateChild::<Revealer>::default(),
+                page_thumbnails: TemplateChild::<RnPageThumbnails>::default(),
             }
         }
     }
@@ -175,6 +222,8 @@ mod imp {
                 .add_controller(self.canvas_zoom_scroll_controller.clone());
             self.scroller
                 .add_controller(self.canvas_mouse_drag_middle_gesture.clone());
+            self.scroller
+                .add_controller(self.canvas_mouse_drag_secondary_gesture.clone());
             self.scroller
                 .add_controller(self.canvas_alt_drag_gesture.clone());
             self.scroller
@@ -183,12 +232,16 @@ mod imp {
                 .add_controller(self.touch_two_finger_long_press_gesture.clone());
             self.canvas
                 .add_controller(self.touch_long_press_gesture.clone());
+            self.canvas
+                .add_controller(self.canvas_secondary_click_gesture.clone());
 
             // group
             self.touch_two_finger_long_press_gesture
                 .group_with(&self.canvas_zoom_gesture);
 
             self.setup_input();
+            self.setup_rulers();
+            self.setup_page_thumbnails();
 
             let canvas_touch_drawing_handler = self.canvas.connect_notify_local(
                 Some("touch-drawing"),
@@ -209,6 +262,7 @@ mod imp {
 
         fn dispose(&self) {
             self.obj().disconnect_connections();
+            self.stop_touch_scroll_momentum();
 
             if let Some(handler) = self.canvas_touch_drawing_handler.take() {
                 self.canvas.disconnect(handler);
@@ -230,12 +284,27 @@ mod imp {
                     glib::ParamSpecBoolean::builder("show-scrollbars")
                         .default_value(false)
                         .build(),
+                    glib::ParamSpecBoolean::builder("show-rulers")
+                        .default_value(false)
+                        .build(),
+                    glib::ParamSpecBoolean::builder("show-page-thumbnails")
+                        .default_value(false)
+                        .build(),
                     glib::ParamSpecBoolean::builder("block-pinch-zoom")
                         .default_value(false)
                         .build(),
                     glib::ParamSpecBoolean::builder("inertial-scrolling")
                         .default_value(true)
                         .build(),
+                    glib::ParamSpecBoolean::builder("shift-scroll-horizontal")
+                        .default_value(true)
+                        .build(),
+                    glib::ParamSpecBoolean::builder("wheel-zoom")
+                        .default_value(false)
+                        .build(),
+                    glib::ParamSpecBoolean::builder("zoom-around-pointer")
+                        .default_value(true)
+                        .build(),
                 ]
             });
             PROPERTIES.as_ref()
@@ -244,8 +313,13 @@ mod imp {
         fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
             match pspec.name() {
                 "show-scrollbars" => self.show_scrollbars.get().to_value(),
+                "show-rulers" => self.show_rulers.get().to_value(),
+                "show-page-thumbnails" => self.show_page_thumbnails.get().to_value(),
                 "block-pinch-zoom" => self.block_pinch_zoom.get().to_value(),
                 "inertial-scrolling" => self.inertial_scrolling.get().to_value(),
+                "shift-scroll-horizontal" => self.shift_scroll_horizontal.get().to_value(),
+                "wheel-zoom" => self.wheel_zoom.get().to_value(),
+                "zoom-around-pointer" => self.zoom_around_pointer.get().to_value(),
                 _ => unimplemented!(),
             }
         }
@@ -261,6 +335,24 @@ mod imp {
                     self.scroller.hscrollbar().set_visible(show_scrollbars);
                     self.scroller.vscrollbar().set_visible(show_scrollbars);
                 }
+                "show-rulers" => {
+                    let show_rulers = value
+                        .get::<bool>()
+                        .expect("The value needs to be of type `bool`");
+                    self.show_rulers.replace(show_rulers);
+
+                    self.hruler.set_visible(show_rulers);
+                    self.vruler.set_visible(show_rulers);
+                }
+                "show-page-thumbnails" => {
+                    let show_page_thumbnails = value
+                        .get::<bool>()
+                        .expect("The value needs to be of type `bool`");
+                    self.show_page_thumbnails.replace(show_page_thumbnails);
+
+                    self.page_thumbnails_revealer
+                        .set_reveal_child(show_page_thumbnails);
+                }
                 "block-pinch-zoom" => {
                     let block_pinch_zoom = value
                         .get::<bool>()
@@ -276,6 +368,25 @@ mod imp {
                     self.inertial_scrolling.replace(inertial_scrolling);
                     self.canvas_kinetic_scrolling_update();
                 }
+                "shift-scroll-horizontal" => {
+                    let shift_scroll_horizontal = value
+                        .get::<bool>()
+                        .expect("The value needs to be of type `bool`");
+                    self.shift_scroll_horizontal
+                        .replace(shift_scroll_horizontal);
+                }
+                "wheel-zoom" => {
+                    let wheel_zoom = value
+                        .get::<bool>()
+                        .expect("The value needs to be of type `bool`");
+                    self.wheel_zoom.replace(wheel_zoom);
+                }
+                "zoom-around-pointer" => {
+                    let zoom_around_pointer = value
+                        .get::<bool>()
+                        .expect("The value needs to be of type `bool`");
+                    self.zoom_around_pointer.replace(zoom_around_pointer);
+                }
                 _ => unimplemented!(),
             }
         }
@@ -300,6 +411,130 @@ mod imp {
             );
         }
 
+        /// Keep panning the canvas with the velocity of a touch drag after the finger was lifted,
+        /// decelerating until it comes to a halt.
+        fn start_touch_scroll_momentum(&self, velocity: na::Vector2<f64>) {
+            self.stop_touch_scroll_momentum();
+            let velocity = Cell::new(velocity);
+            let last_frame_time: Cell<Option<i64>> = Cell::new(None);
+
+            let callback_id = self
+                .obj()
+                .add_tick_callback(move |canvaswrapper, frame_clock| {
+                    let canvas = canvaswrapper.canvas();
+                    // in microseconds
+                    let frame_time = frame_clock.frame_time();
+                    let dt = last_frame_time
+                        .replace(Some(frame_time))
+                        .map(|last| (frame_time - last) as f64 / 1_000_000.0)
+                        .unwrap_or(0.0);
+                    let new_velocity = velocity.get()
+                        * (-super::RnCanvasWrapper::TOUCH_SCROLL_MOMENTUM_DECELERATION * dt).exp();
+                    velocity.set(new_velocity);
+
+                    if new_velocity.magnitude()
+                        < super::RnCanvasWrapper::TOUCH_SCROLL_MOMENTUM_VELOCITY_MIN
+                    {
+                        // Returning `Break` removes the callback, so the id must not be removed again.
+                        canvaswrapper.imp().touch_scroll_momentum_callback_id.take();
+                        let widget_flags = canvas.engine_mut().update_rendering_current_viewport();
+                        canvas.emit_handle_widget_flags(widget_flags);
+                        return glib::ControlFlow::Break;
+                    }
+
+                    let new_offset = canvas.engine_ref().camera.offset() + new_velocity * dt;
+                    let widget_flags = canvas.engine_mut().camera_set_offset_expand(new_offset);
+                    canvas.emit_handle_widget_flags(widget_flags);
+                    glib::ControlFlow::Continue
+                });
+            *self.touch_scroll_momentum_callback_id.borrow_mut() = Some(callback_id);
+        }
+
+        fn stop_touch_scroll_momentum(&self) {
+            if let Some(callback_id) = self.touch_scroll_momentum_callback_id.take() {
+                callback_id.remove();
+            }
+        }
+
+        fn setup_page_thumbnails(&self) {
+            let page_thumbnails = self.page_thumbnails.get();
+            page_thumbnails.set_canvas(&self.canvas);
+
+            self.canvas.connect_local(
+                "content-changed",
+                false,
+                clone!(
+                    #[weak]
+                    page_thumbnails,
+                    #[upgrade_or]
+                    None,
+                    move |_| {
+                        page_thumbnails.invalidate();
+                        None
+                    }
+                ),
+            );
+            // A different document was loaded.
+            self.canvas.connect_notify_local(
+                Some("output-file"),
+                clone!(
+                    #[weak]
+                    page_thumbnails,
+                    move |_, _| {
+                        page_thumbnails.invalidate();
+                    }
+                ),
+            );
+            for adj in [self.scroller.hadjustment(), self.scroller.vadjustment()] {
+                adj.connect_value_changed(clone!(
+                    #[weak]
+                    page_thumbnails,
+                    move |_| {
+                        page_thumbnails.refresh_current_page();
+                    }
+                ));
+                // The adjustments change when the document is resized, for example when pages are added.
+                adj.connect_changed(clone!(
+                    #[weak]
+                    page_thumbnails,
+                    move |_| {
+                        page_thumbnails.sync_pages();
+                    }
+                ));
+            }
+        }
+
+        fn setup_rulers(&self) {
+            let hruler = self.hruler.get();
+            let vruler = self.vruler.get();
+            hruler.set_canvas(&self.canvas);
+            vruler.set_canvas(&self.canvas);
+
+            // The rulers need to be redrawn whenever the viewport is moved or zoomed.
+            for adj in [self.scroller.hadjustment(), self.scroller.vadjustment()] {
+                adj.connect_value_changed(clone!(
+                    #[weak]
+                    hruler,
+                    #[weak]
+                    vruler,
+                    move |_| {
+                        hruler.queue_draw();
+                        vruler.queue_draw();
+                    }
+                ));
+                adj.connect_changed(clone!(
+                    #[weak]
+                    hruler,
+                    #[weak]
+                    vruler,
+                    move |_| {
+                        hruler.queue_draw();
+                        vruler.queue_draw();
+                    }
+                ));
+            }
+        }
+
         fn setup_input(&self) {
             let obj = self.obj();
 
@@ -335,7 +570,8 @@ mod imp {
                 ));
             }
 
-            // zoom scrolling with <ctrl> + scroll
+            // zoom scrolling with <ctrl> + scroll, or with the plain mouse wheel when enabled.
+            // horizontal scrolling with <shift> + mouse wheel
             {
                 self.canvas_zoom_scroll_controller.connect_scroll(clone!(
                     #[weak(rename_to=canvaswrapper)]
@@ -343,10 +579,49 @@ mod imp {
                     #[upgrade_or]
                     glib::Propagation::Proceed,
                     move |controller, _, dy| {
-                        if controller.current_event_state() != gdk::ModifierType::CONTROL_MASK {
+                        let modifiers = controller.current_event_state()
+                            & (gdk::ModifierType::CONTROL_MASK
+                                | gdk::ModifierType::SHIFT_MASK
+                                | gdk::ModifierType::ALT_MASK);
+                        let is_wheel = controller.unit() == gdk::ScrollUnit::Wheel;
+                        let canvas = canvaswrapper.canvas();
+
+                        if is_wheel
+                            && dy != 0.0
+                            && modifiers == gdk::ModifierType::SHIFT_MASK
+                            && canvaswrapper.shift_scroll_horizontal()
+                        {
+                            // The same step as the scrolled window uses for scrolling with the wheel
+                            let step = canvas
+                                .hadjustment()
+                                .map(|hadj| hadj.page_size().powf(2.0 / 3.0))
+                                .unwrap_or_default();
+                            let new_offset =
+                                canvas.engine_ref().camera.offset() + na::vector![dy * step, 0.0];
+                            let widget_flags =
+                                canvas.engine_mut().camera_set_offset_expand(new_offset);
+                            canvas.emit_handle_widget_flags(widget_flags);
+                            return glib::Propagation::Stop;
+                        }
+
+                        // With wheel zoom enabled, <ctrl> + wheel scrolls instead
+                        let zoom = if is_wheel && canvaswrapper.wheel_zoom() {
+                            modifiers.is_empty()
+                        } else {
+                            modifiers == gdk::ModifierType::CONTROL_MASK
+                        };
+                        if !zoom {
                             return glib::Propagation::Proceed;
                         }
-                        let canvas = canvaswrapper.canvas();
+                        // Scrolling with a stylus adjusts the stroke width of the current pen instead of zooming
+                        if controller
+                            .current_event()
+                            .is_some_and(|event| event.device_tool().is_some())
+                            && dy != 0.0
+                            && canvas.step_stroke_width(dy < 0.0)
+                        {
+                            return glib::Propagation::Stop;
+                        }
                         let old_zoom = canvas.engine_ref().camera.total_zoom();
                         let new_zoom = if dy < 0.0 {
                             old_zoom * (1.0 - dy * RnCanvas::ZOOM_SCROLL_STEP)
@@ -355,27 +630,7 @@ mod imp {
                         };
 
                         if (Camera::ZOOM_MIN..=Camera::ZOOM_MAX).contains(&new_zoom) {
-                            let camera_offset = canvas.engine_ref().camera.offset();
-                            let camera_size = canvas.engine_ref().camera.size();
-                            let screen_offset = canvaswrapper
-                                .imp()
-                                .pointer_pos
-                                .get()
-                                .map(|p| {
-                                    let p = canvaswrapper
-                                        .compute_point(&canvas, &graphene::Point::from_na_vec(p))
-                                        .unwrap();
-                                    p.to_na_vec()
-                                })
-                                .unwrap_or_else(|| camera_size * 0.5);
-                            let new_camera_offset = (((camera_offset + screen_offset) / old_zoom)
-                                * new_zoom)
-                                - screen_offset;
-
-                            let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
-                            widget_flags |= canvas
-                                .engine_mut()
-                                .camera_set_offset_expand(new_camera_offset);
+                            let widget_flags = canvaswrapper.zoom_w_timeout_around_anchor(new_zoom);
                             canvas.emit_handle_widget_flags(widget_flags);
                         }
 
@@ -387,16 +642,25 @@ mod imp {
             // Drag canvas gesture
             {
                 let touch_drag_start = Rc::new(Cell::new(na::vector![0.0, 0.0]));
+                // The most recent drag offsets with their timestamps, for calculating the velocity when the drag ends.
+                let drag_samples: Rc<RefCell<VecDeque<(Instant, na::Vector2<f64>)>>> =
+                    Rc::new(RefCell::new(VecDeque::new()));
 
                 self.canvas_drag_gesture.connect_drag_begin(clone!(
                     #[strong]
                     touch_drag_start,
+                    #[strong]
+                    drag_samples,
                     #[weak(rename_to=canvaswrapper)]
                     obj,
                     move |_, _, _| {
                         // We don't claim the sequence, because we we want to allow touch zooming.
                         // When the zoom gesture is recognized, it claims it and denies this touch drag gesture.
 
+                        // Touching the canvas again catches it while it is still moving.
+                        canvaswrapper.imp().stop_touch_scroll_momentum();
+                        drag_samples.borrow_mut().clear();
+
                         touch_drag_start.set(na::vector![
                             canvaswrapper.canvas().hadjustment().unwrap().value(),
                             canvaswrapper.canvas().vadjustment().unwrap().value()
@@ -406,9 +670,22 @@ mod imp {
                 self.canvas_drag_gesture.connect_drag_update(clone!(
                     #[strong]
                     touch_drag_start,
+                    #[strong]
+                    drag_samples,
                     #[weak(rename_to=canvaswrapper)]
                     obj,
                     move |_, x, y| {
+                        let now = Instant::now();
+                        let mut samples = drag_samples.borrow_mut();
+                        samples.push_back((now, na::vector![x, y]));
+                        while samples.front().is_some_and(|(time, _)| {
+                            now.duration_since(*time)
+                                > super::RnCanvasWrapper::TOUCH_SCROLL_MOMENTUM_SAMPLE_WINDOW
+                        }) {
+                            samples.pop_front();
+                        }
+                        drop(samples);
+
                         let canvas = canvaswrapper.canvas();
                         let new_offset = touch_drag_start.get() - na::vector![x, y];
                         let widget_flags = canvas.engine_mut().camera_set_offset_expand(new_offset);
@@ -416,9 +693,23 @@ mod imp {
                     }
                 ));
                 self.canvas_drag_gesture.connect_drag_end(clone!(
+                    #[strong]
+                    drag_samples,
                     #[weak(rename_to=canvaswrapper)]
                     obj,
-                    move |_, _, _| {
+                    move |gesture, _, _| {
+                        let is_touch = gesture
+                            .device()
+                            .is_some_and(|d| d.source() == gdk::InputSource::Touchscreen);
+                        let velocity = drag_velocity(&drag_samples.take())
+                            .filter(|_| is_touch && canvaswrapper.inertial_scrolling());
+
+                        if let Some(velocity) = velocity {
+                            // The camera offset moves opposite to the dragging direction.
+                            canvaswrapper.imp().start_touch_scroll_momentum(-velocity);
+                            return;
+                        }
+
                         let widget_flags = canvaswrapper
                             .canvas()
                             .engine_mut()
@@ -430,6 +721,60 @@ mod imp {
                 ));
             }
 
+            // Move Canvas with secondary mouse button
+            {
+                let mouse_drag_start = Rc::new(Cell::new(na::vector![0.0, 0.0]));
+
+                self.canvas_mouse_drag_secondary_gesture
+                    .connect_drag_begin(clone!(
+                        #[strong]
+                        mouse_drag_start,
+                        #[weak(rename_to=canvaswrapper)]
+                        obj,
+                        move |gesture, _, _| {
+                            if !canvaswrapper.canvas().secondary_drag_pan() {
+                                gesture.set_state(EventSequenceState::Denied);
+                                return;
+                            }
+                            mouse_drag_start
+                                .set(canvaswrapper.canvas().engine_ref().camera.offset());
+                        }
+                    ));
+                self.canvas_mouse_drag_secondary_gesture
+                    .connect_drag_update(clone!(
+                        #[strong]
+                        mouse_drag_start,
+                        #[weak(rename_to=canvaswrapper)]
+                        obj,
+                        move |gesture, x, y| {
+                            if na::vector![x, y].magnitude()
+                                > super::RnCanvasWrapper::MOUSE_DRAG_SECONDARY_CLAIM_THRESHOLD
+                            {
+                                gesture.set_state(EventSequenceState::Claimed);
+                            }
+                            let canvas = canvaswrapper.canvas();
+                            let new_offset = mouse_drag_start.get() - na::vector![x, y];
+                            let widget_flags =
+                                canvas.engine_mut().camera_set_offset_expand(new_offset);
+                            canvas.emit_handle_widget_flags(widget_flags);
+                        }
+                    ));
+                self.canvas_mouse_drag_secondary_gesture
+                    .connect_drag_end(clone!(
+                        #[weak(rename_to=canvaswrapper)]
+                        obj,
+                        move |_, _, _| {
+                            let widget_flags = canvaswrapper
+                                .canvas()
+                                .engine_mut()
+                                .update_rendering_current_viewport();
+                            canvaswrapper
+                                .canvas()
+                                .emit_handle_widget_flags(widget_flags);
+                        }
+                    ));
+            }
+
             // Move Canvas with middle mouse button
             {
                 let mouse_drag_start = Rc::new(Cell::new(na::vector![0.0, 0.0]));
@@ -497,6 +842,10 @@ mod imp {
                     #[weak(rename_to=canvaswrapper)]
                     obj,
                     move |gesture, _| {
+                        if canvaswrapper.canvas().palm_rejection_active(Instant::now()) {
+                            gesture.set_state(EventSequenceState::Denied);
+                            return;
+                        }
                         gesture.set_state(EventSequenceState::Claimed);
                         let current_zoom = canvaswrapper.canvas().engine_ref().camera.total_zoom();
 
@@ -747,14 +1096,24 @@ mod imp {
                     #[weak(rename_to=canvaswrapper)]
                     obj,
                     move |_gesture, x, y| {
-                        let popover = canvaswrapper.contextmenu().popover();
-                        canvaswrapper
-                            .imp()
-                            .last_contextmenu_pos
-                            .set(Some(na::vector![x, y]));
-                        popover
-                            .set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 4, 4)));
-                        popover.popup();
+                        canvaswrapper.popup_contextmenu(x, y);
+                    }
+                ));
+
+                // Stationary right clicks with the mouse. Pen buttons are handled as shortcut keys.
+                self.canvas_secondary_click_gesture.connect_released(clone!(
+                    #[weak(rename_to=canvaswrapper)]
+                    obj,
+                    move |gesture, n_press, x, y| {
+                        if n_press != 1
+                            || gesture
+                                .current_event()
+                                .and_then(|event| event.device())
+                                .is_some_and(|device| device.source() == gdk::InputSource::Pen)
+                        {
+                            return;
+                        }
+                        canvaswrapper.popup_contextmenu(x, y);
                     }
                 ));
             }
@@ -774,6 +1133,18 @@ impl Default for RnCanvasWrapper {
 }
 
 impl RnCanvasWrapper {
+    /// The distance the pointer needs to be dragged with the secondary mouse button before panning claims the
+    /// sequence, so that clicking still opens the context menu.
+    const MOUSE_DRAG_SECONDARY_CLAIM_THRESHOLD: f64 = 4.0;
+    /// The time span of the most recent drag motion that the momentum velocity is calculated from.
+    const TOUCH_SCROLL_MOMENTUM_SAMPLE_WINDOW: Duration = Duration::from_millis(100);
+    /// The momentum is only kept when the finger moved until at most this long before it was lifted.
+    const TOUCH_SCROLL_MOMENTUM_RELEASE_DELAY_MAX: Duration = Duration::from_millis(50);
+    /// The exponential decay rate of the momentum velocity, in 1/s.
+    const TOUCH_SCROLL_MOMENTUM_DECELERATION: f64 = 3.5;
+    /// The velocity in px/s below which the momentum stops.
+    const TOUCH_SCROLL_MOMENTUM_VELOCITY_MIN: f64 = 40.0;
+
     pub(crate) fn new() -> Self {
         glib::Object::new()
     }
@@ -787,6 +1158,26 @@ impl RnCanvasWrapper {
     pub(crate) fn set_show_scrollbars(&self, show_scrollbars: bool) {
         self.set_property("show-scrollbars", show_scrollbars.to_value());
     }
+
+    #[allow(unused)]
+    pub(crate) fn show_rulers(&self) -> bool {
+        self.property::<bool>("show-rulers")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_show_rulers(&self, show_rulers: bool) {
+        self.set_property("show-rulers", show_rulers.to_value());
+    }
+
+    #[allow(unused)]
+    pub(crate) fn show_page_thumbnails(&self) -> bool {
+        self.property::<bool>("show-page-thumbnails")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_show_page_thumbnails(&self, show_page_thumbnails: bool) {
+        self.set_property("show-page-thumbnails", show_page_thumbnails.to_value());
+    }
     #[allow(unused)]
     pub(crate) fn block_pinch_zoom(&self) -> bool {
         self.property::<bool>("block-pinch-zoom")
@@ -807,6 +1198,61 @@ impl RnCanvasWrapper {
         self.set_property("inertial-scrolling", inertial_scrolling);
     }
 
+    #[allow(unused)]
+    pub(crate) fn shift_scroll_horizontal(&self) -> bool {
+        self.property::<bool>("shift-scroll-horizontal")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_shift_scroll_horizontal(&self, shift_scroll_horizontal: bool) {
+        self.set_property("shift-scroll-horizontal", shift_scroll_horizontal);
+    }
+
+    #[allow(unused)]
+    pub(crate) fn wheel_zoom(&self) -> bool {
+        self.property::<bool>("wheel-zoom")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_wheel_zoom(&self, wheel_zoom: bool) {
+        self.set_property("wheel-zoom", wheel_zoom);
+    }
+
+    pub(crate) fn zoom_around_pointer(&self) -> bool {
+        self.property::<bool>("zoom-around-pointer")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_zoom_around_pointer(&self, zoom_around_pointer: bool) {
+        self.set_property("zoom-around-pointer", zoom_around_pointer);
+    }
+
+    /// Zooms the canvas with a timeout, keeping the anchor at the same position on screen.
+    ///
+    /// The anchor is the pointer position when zooming around the pointer is enabled
+    /// and the pointer hovers over the canvas, else it is the center of the viewport.
+    pub(crate) fn zoom_w_timeout_around_anchor(&self, new_zoom: f64) -> WidgetFlags {
+        let canvas = self.canvas();
+        let old_zoom = canvas.engine_ref().camera.total_zoom();
+        let camera_offset = canvas.engine_ref().camera.offset();
+        let camera_size = canvas.engine_ref().camera.size();
+        let screen_offset = self
+            .pointer_pos()
+            .filter(|_| self.zoom_around_pointer())
+            .and_then(|p| self.compute_point(&canvas, &graphene::Point::from_na_vec(p)))
+            .map(|p| p.to_na_vec())
+            .unwrap_or_else(|| camera_size * 0.5);
+        let new_zoom = new_zoom.clamp(Camera::ZOOM_MIN, Camera::ZOOM_MAX);
+        let new_camera_offset =
+            (((camera_offset + screen_offset) / old_zoom) * new_zoom) - screen_offset;
+
+        let mut widget_flags = canvas.engine_mut().zoom_w_timeout(new_zoom);
+        widget_flags |= canvas
+            .engine_mut()
+            .camera_set_offset_expand(new_camera_offset);
+        widget_flags
+    }
+
     pub(crate) fn pointer_pos(&self) -> Option<na::Vector2<f64>> {
         self.imp().pointer_pos.get()
     }
@@ -815,6 +1261,31 @@ impl RnCanvasWrapper {
         self.imp().last_contextmenu_pos.get()
     }
 
+    /// The last context menu position, in document coordinates.
+    pub(crate) fn last_contextmenu_doc_pos(&self) -> Option<na::Vector2<f64>> {
+        self.last_contextmenu_pos().map(|pos| {
+            (self.canvas().engine_ref().camera.transform().inverse() * na::Point2::from(pos)).coords
+        })
+    }
+
+    /// Pops up the context menu at the given position in canvas coordinates.
+    ///
+    /// Shows the selection entries when the position is on the current selection.
+    pub(crate) fn popup_contextmenu(&self, x: f64, y: f64) {
+        self.imp().last_contextmenu_pos.set(Some(na::vector![x, y]));
+        let on_selection = self.last_contextmenu_doc_pos().is_some_and(|doc_pos| {
+            self.canvas()
+                .engine_ref()
+                .selection_bounds()
+                .is_some_and(|bounds| bounds.contains_local_point(&doc_pos.into()))
+        });
+        let contextmenu = self.contextmenu();
+        contextmenu.update_menu(on_selection);
+        let popover = contextmenu.popover();
+        popover.set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 4, 4)));
+        popover.popup();
+    }
+
     pub(crate) fn scroller(&self) -> ScrolledWindow {
         self.imp().scroller.get()
     }
@@ -848,6 +1319,22 @@ impl RnCanvasWrapper {
             .sync_create()
             .build();
 
+        let appwindow_show_rulers_bind = appwindow
+            .sidebar()
+            .settings_panel()
+            .general_show_rulers_row()
+            .bind_property("active", self, "show-rulers")
+            .sync_create()
+            .build();
+
+        let appwindow_show_page_thumbnails_bind = appwindow
+            .sidebar()
+            .settings_panel()
+            .general_show_page_thumbnails_row()
+            .bind_property("active", self, "show-page-thumbnails")
+            .sync_create()
+            .build();
+
         let appwindow_inertial_scrolling_bind = appwindow
             .sidebar()
             .settings_panel()
@@ -856,6 +1343,30 @@ impl RnCanvasWrapper {
             .sync_create()
             .build();
 
+        let appwindow_shift_scroll_horizontal_bind = appwindow
+            .sidebar()
+            .settings_panel()
+            .input_shift_scroll_horizontal_row()
+            .bind_property("active", self, "shift-scroll-horizontal")
+            .sync_create()
+            .build();
+
+        let appwindow_wheel_zoom_bind = appwindow
+            .sidebar()
+            .settings_panel()
+            .input_wheel_zoom_row()
+            .bind_property("active", self, "wheel-zoom")
+            .sync_create()
+            .build();
+
+        let appwindow_zoom_around_pointer_bind = appwindow
+            .sidebar()
+            .settings_panel()
+            .input_zoom_around_pointer_row()
+            .bind_property("active", self, "zoom-around-pointer")
+            .sync_create()
+            .build();
+
         let appwindow_righthanded_bind = appwindow
             .bind_property("righthanded", &self.scroller(), "window-placement")
             .transform_to(|_, righthanded: bool| {
@@ -881,12 +1392,42 @@ impl RnCanvasWrapper {
         {
             old.unbind();
         }
+        if let Some(old) = connections
+            .appwindow_show_rulers_bind
+            .replace(appwindow_show_rulers_bind)
+        {
+            old.unbind();
+        }
+        if let Some(old) = connections
+            .appwindow_show_page_thumbnails_bind
+            .replace(appwindow_show_page_thumbnails_bind)
+        {
+            old.unbind();
+        }
         if let Some(old) = connections
             .appwindow_inertial_scrolling_bind
             .replace(appwindow_inertial_scrolling_bind)
         {
             old.unbind();
         }
+        if let Some(old) = connections
+            .appwindow_shift_scroll_horizontal_bind
+            .replace(appwindow_shift_scroll_horizontal_bind)
+        {
+            old.unbind();
+        }
+        if let Some(old) = connections
+            .appwindow_wheel_zoom_bind
+            .replace(appwindow_wheel_zoom_bind)
+        {
+            old.unbind();
+        }
+        if let Some(old) = connections
+            .appwindow_zoom_around_pointer_bind
+            .replace(appwindow_zoom_around_pointer_bind)
+        {
+            old.unbind();
+        }
         if let Some(old) = connections
             .appwindow_righthanded_bind
             .replace(appwindow_righthanded_bind)
@@ -909,9 +1450,24 @@ impl RnCanvasWrapper {
         if let Some(old) = connections.appwindow_show_scrollbars_bind.take() {
             old.unbind();
         }
+        if let Some(old) = connections.appwindow_show_rulers_bind.take() {
+            old.unbind();
+        }
+        if let Some(old) = connections.appwindow_show_page_thumbnails_bind.take() {
+            old.unbind();
+        }
         if let Some(old) = connections.appwindow_inertial_scrolling_bind.take() {
             old.unbind();
         }
+        if let Some(old) = connections.appwindow_shift_scroll_horizontal_bind.take() {
+            old.unbind();
+        }
+        if let Some(old) = connections.appwindow_wheel_zoom_bind.take() {
+            old.unbind();
+        }
+        if let Some(old) = connections.appwindow_zoom_around_pointer_bind.take() {
+            old.unbind();
+        }
         if let Some(old) = connections.appwindow_righthanded_bind.take() {
             old.unbind();
         }
@@ -926,3 +1482,21 @@ impl RnCanvasWrapper {
         self.canvas().connect_to_tab_page(page);
     }
 }
+
+/// The velocity in px/s of the drag from its most recent offsets.
+///
+/// None when the drag was too slow for momentum, or it has paused before it ended.
+fn drag_velocity(samples: &VecDeque<(Instant, na::Vector2<f64>)>) -> Option<na::Vector2<f64>> {
+    let (first_time, first_offset) = samples.front()?;
+    let (last_time, last_offset) = samples.back()?;
+    if last_time.elapsed() > RnCanvasWrapper::TOUCH_SCROLL_MOMENTUM_RELEASE_DELAY_MAX {
+        return None;
+    }
+    let dt = last_time.duration_since(*first_time).as_secs_f64();
+    if dt <= 0.0 {
+        return None;
+    }
+    let velocity = (last_offset - first_offset) / dt;
+    (velocity.magnitude() >= RnCanvasWrapper::TOUCH_SCROLL_MOMENTUM_VELOCITY_MIN)
+        .then_some(velocity)
+}
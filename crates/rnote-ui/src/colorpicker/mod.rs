@@ -8,15 +8,19 @@ pub(crate) use colorsetter::RnColorSetter;
 
 // Imports
 use crate::RnAppWindow;
+use futures::channel::oneshot;
+use gettextrs::gettext;
 use gtk4::{
-    gdk, glib, glib::clone, prelude::*, subclass::prelude::*, Button, ColorDialog,
+    gdk, gio, glib, glib::clone, prelude::*, subclass::prelude::*, Button, ColorDialog,
     CompositeTemplate, Label, Widget,
 };
 use once_cell::sync::Lazy;
 use rnote_compose::{color, Color};
 use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::pens::PensConfig;
 use std::cell::RefCell;
-use tracing::debug;
+use std::collections::HashMap;
+use tracing::{debug, error};
 
 mod imp {
     use super::*;
@@ -57,6 +61,10 @@ mod imp {
         #[template_child]
         pub(crate) colordialog_button: TemplateChild<Button>,
         #[template_child]
+        pub(crate) eyedropper_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) recent_colors_box: TemplateChild<gtk4::Box>,
+        #[template_child]
         pub(crate) active_color_label: TemplateChild<Label>,
     }
 
@@ -83,6 +91,8 @@ mod imp {
                 setter_8: TemplateChild::default(),
                 setter_9: TemplateChild::default(),
                 colordialog_button: TemplateChild::default(),
+                eyedropper_button: TemplateChild::default(),
+                recent_colors_box: TemplateChild::default(),
                 active_color_label: TemplateChild::default(),
             }
         }
@@ -109,6 +119,7 @@ mod imp {
             let obj = self.obj();
 
             self.setup_setters();
+            self.setup_recent_colors();
 
             self.stroke_color_pad
                 .bind_property("color", &*obj, "stroke-color")
@@ -387,6 +398,30 @@ mod imp {
             });
         }
 
+        fn setup_recent_colors(&self) {
+            let obj = self.obj();
+
+            for _ in 0..PensConfig::RECENT_COLORS_MAX {
+                let setter = RnColorSetter::new();
+                setter.set_width_request(20);
+                setter.set_height_request(20);
+                setter.set_visible(false);
+
+                setter.connect_clicked(clone!(
+                    #[weak(rename_to=colorpicker)]
+                    obj,
+                    move |setter| {
+                        // Recent colors are applied once and don't stay selected
+                        setter.set_active(false);
+                        colorpicker.deselect_setters();
+                        colorpicker.set_color_active_pad(setter.color());
+                    }
+                ));
+
+                self.recent_colors_box.append(&setter);
+            }
+        }
+
         fn default_color(i: usize) -> gdk::RGBA {
             match i {
                 0 => gdk::RGBA::new(0.0, 0.0, 0.0, 1.0),
@@ -487,6 +522,25 @@ impl RnColorPicker {
         self.imp().setter_9.get()
     }
 
+    /// Update the recent colors row. Unused slots are hidden.
+    pub(crate) fn set_recent_colors(&self, colors: &[Color]) {
+        let imp = self.imp();
+        let mut child = imp.recent_colors_box.first_child();
+        let mut colors_iter = colors.iter();
+
+        while let Some(setter) = child.and_downcast::<RnColorSetter>() {
+            match colors_iter.next() {
+                Some(color) => {
+                    setter.set_color(gdk::RGBA::from_compose_color(*color));
+                    setter.set_visible(true);
+                }
+                None => setter.set_visible(false),
+            }
+            child = setter.next_sibling();
+        }
+        imp.recent_colors_box.set_visible(!colors.is_empty());
+    }
+
     pub(crate) fn active_color_label(&self) -> Label {
         self.imp().active_color_label.get()
     }
@@ -521,6 +575,38 @@ impl RnColorPicker {
                 }
             }),
         );
+
+        self.imp().eyedropper_button.connect_clicked(clone!(
+            #[weak(rename_to=colorpicker)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    colorpicker,
+                    #[weak]
+                    appwindow,
+                    async move {
+                        match pick_screen_color().await {
+                            Ok(Some(new_color)) => {
+                                colorpicker.set_color_active_pad(new_color);
+                                colorpicker.set_color_active_setter(new_color);
+                            }
+                            Ok(None) => {
+                                debug!("Picking color from screen was cancelled by the user")
+                            }
+                            Err(e) => {
+                                error!("Picking color from screen failed, Err: {e:?}");
+                                appwindow.overlays().dispatch_toast_error(&gettext(
+                                    "Picking color from screen failed",
+                                ));
+                            }
+                        }
+                    }
+                ));
+            }
+        ));
     }
 
     fn set_color_active_setter(&self, color: gdk::RGBA) {
@@ -579,3 +665,75 @@ impl RnColorPicker {
         imp.setter_9.set_active(false);
     }
 }
+
+/// Pick a color anywhere on the screen through the screenshot desktop portal.
+///
+/// Returns `None` when picking was cancelled by the user.
+async fn pick_screen_color() -> anyhow::Result<Option<gdk::RGBA>> {
+    let connection = gio::bus_get_future(gio::BusType::Session).await?;
+    let sender = connection
+        .unique_name()
+        .ok_or_else(|| anyhow::anyhow!("Session bus connection has no unique name"))?;
+    let token = format!("rnote_{}", glib::random_int());
+    // The portal responds on a request object with a path that is derived from the sender and the token
+    let request_path = format!(
+        "/org/freedesktop/portal/desktop/request/{}/{token}",
+        sender.trim_start_matches(':').replace('.', "_")
+    );
+
+    // Subscribe before calling the portal, so that the response can't be missed
+    let (response_sender, response_receiver) = oneshot::channel::<glib::Variant>();
+    let response_sender = RefCell::new(Some(response_sender));
+    let subscription_id = connection.signal_subscribe(
+        Some("org.freedesktop.portal.Desktop"),
+        Some("org.freedesktop.portal.Request"),
+        Some("Response"),
+        Some(&request_path),
+        None,
+        gio::DBusSignalFlags::NONE,
+        move |_, _, _, _, _, parameters| {
+            if let Some(response_sender) = response_sender.take() {
+                let _ = response_sender.send(parameters.clone());
+            }
+        },
+    );
+
+    let options = glib::VariantDict::new(None);
+    options.insert_value("handle_token", &token.to_variant());
+    // The parent window identifier is left empty, the portal then does not attach its dialogs to the window
+    let parameters = glib::Variant::tuple_from_iter(["".to_variant(), options.end()]);
+    let response = match connection
+        .call_future(
+            Some("org.freedesktop.portal.Desktop"),
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Screenshot",
+            "PickColor",
+            Some(&parameters),
+            Some(glib::VariantTy::new("(o)")?),
+            gio::DBusCallFlags::NONE,
+            -1,
+        )
+        .await
+    {
+        Ok(_) => response_receiver.await.map_err(anyhow::Error::from),
+        Err(e) => Err(e.into()),
+    };
+    connection.signal_unsubscribe(subscription_id);
+
+    let (code, results) = response?
+        .get::<(u32, HashMap<String, glib::Variant>)>()
+        .ok_or_else(|| anyhow::anyhow!("Portal response has unexpected type"))?;
+    match code {
+        0 => {
+            let (r, g, b) = results
+                .get("color")
+                .and_then(|color| color.get::<(f64, f64, f64)>())
+                .ok_or_else(|| anyhow::anyhow!("Portal response does not contain a color"))?;
+            Ok(Some(gdk::RGBA::new(r as f32, g as f32, b as f32, 1.0)))
+        }
+        1 => Ok(None),
+        _ => Err(anyhow::anyhow!(
+            "Portal responded with error code {code} while picking a color"
+        )),
+    }
+}
@@ -1,5 +1,5 @@
 // Imports
-use gtk4::{glib, prelude::*, subclass::prelude::*, CompositeTemplate, PopoverMenu, Widget};
+use gtk4::{gio, glib, prelude::*, subclass::prelude::*, CompositeTemplate, PopoverMenu, Widget};
 
 mod imp {
     use super::*;
@@ -9,6 +9,10 @@ mod imp {
     pub(crate) struct RnContextMenu {
         #[template_child]
         pub(crate) popover: TemplateChild<PopoverMenu>,
+        #[template_child]
+        pub(crate) selection_menu: TemplateChild<gio::Menu>,
+        #[template_child]
+        pub(crate) canvas_menu: TemplateChild<gio::Menu>,
     }
 
     #[glib::object_subclass]
@@ -66,4 +70,14 @@ impl RnContextMenu {
     pub(crate) fn popover(&self) -> PopoverMenu {
         self.imp().popover.get()
     }
+
+    /// Switches between the entries for the selection and the entries for the canvas.
+    pub(crate) fn update_menu(&self, on_selection: bool) {
+        let imp = self.imp();
+        if on_selection {
+            imp.popover.set_menu_model(Some(&imp.selection_menu.get()));
+        } else {
+            imp.popover.set_menu_model(Some(&imp.canvas_menu.get()));
+        }
+    }
 }
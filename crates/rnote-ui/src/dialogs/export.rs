@@ -11,8 +11,9 @@ use num_traits::ToPrimitive;
 use rnote_compose::SplitOrder;
 use rnote_engine::document::Layout;
 use rnote_engine::engine::export::{
-    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs,
-    SelectionExportFormat, SelectionExportPrefs,
+    DocExportFormat, DocExportPrefs, DocPagesExportFormat, DocPagesExportPrefs, ExportPrefs,
+    PageNumberFont, PageNumberPosition, PageNumberingPrefs, SelectionExportFormat,
+    SelectionExportPrefs, ViewportExportPrefs,
 };
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -45,11 +46,16 @@ pub(crate) async fn dialog_save_doc_as(appwindow: &RnAppWindow, canvas: &RnCanva
         .default_filter(&filter)
         .build();
 
-    // Set the output file as default, else at least the current workspace directory
-    if let Some(output_file) = canvas.output_file() {
+    // Set the output file as default, else at least the current workspace directory.
+    // Documents opened read-only can't be saved to their output file, so only its directory is suggested.
+    if let Some(output_file) = canvas.writable_output_file() {
         filedialog.set_initial_file(Some(&output_file));
     } else {
-        if let Some(current_workspace_dir) = appwindow.sidebar().workspacebrowser().dir_list_dir() {
+        if let Some(output_dir) = canvas.output_file().and_then(|f| f.parent()) {
+            filedialog.set_initial_folder(Some(&output_dir));
+        } else if let Some(current_workspace_dir) =
+            appwindow.sidebar().workspacebrowser().dir_list_dir()
+        {
             filedialog.set_initial_folder(Some(&gio::File::for_path(current_workspace_dir)));
         }
 
@@ -105,6 +111,14 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
         builder.object("export_doc_optimize_printing_row").unwrap();
     let export_format_row: adw::ComboRow = builder.object("export_doc_export_format_row").unwrap();
     let page_order_row: adw::ComboRow = builder.object("export_doc_page_order_row").unwrap();
+    let smart_page_breaks_row: adw::SwitchRow =
+        builder.object("export_doc_smart_page_breaks_row").unwrap();
+    let crop_to_content_row: adw::SwitchRow =
+        builder.object("export_doc_crop_to_content_row").unwrap();
+    let page_numbering_row =
+        setup_page_numbering_rows(&builder, "export_doc", canvas, |export_prefs| {
+            &mut export_prefs.doc_export_prefs.page_numbering
+        });
     let export_file_label: Label = builder.object("export_doc_export_file_label").unwrap();
     let export_file_button: Button = builder.object("export_doc_export_file_button").unwrap();
     let preview: RnStrokeContentPreview = builder.object("export_doc_preview").unwrap();
@@ -125,13 +139,24 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
     preview.set_contents(
         canvas
             .engine_ref()
-            .extract_pages_content(initial_doc_export_prefs.page_order),
+            .extract_doc_export_pages_content(&initial_doc_export_prefs),
     );
     export_format_row.set_selected(initial_doc_export_prefs.export_format.to_u32().unwrap());
     page_order_row.set_selected(initial_doc_export_prefs.page_order.to_u32().unwrap());
+    smart_page_breaks_row.set_active(initial_doc_export_prefs.smart_page_breaks);
+    crop_to_content_row.set_active(initial_doc_export_prefs.crop_to_content);
     export_file_label.set_label(&gettext("- no file selected -"));
-    page_order_row
-        .set_sensitive(doc_layout == Layout::SemiInfinite || doc_layout == Layout::Infinite);
+    page_order_row.set_sensitive(
+        (doc_layout == Layout::SemiInfinite || doc_layout == Layout::Infinite)
+            && !initial_doc_export_prefs.crop_to_content,
+    );
+    smart_page_breaks_row.set_sensitive(
+        !matches!(doc_layout, Layout::FixedSize | Layout::Whiteboard)
+            && initial_doc_export_prefs.export_format == DocExportFormat::Pdf
+            && !initial_doc_export_prefs.crop_to_content,
+    );
+    page_numbering_row
+        .set_sensitive(initial_doc_export_prefs.export_format == DocExportFormat::Pdf);
     button_confirm.set_sensitive(false);
 
     // Update prefs
@@ -230,6 +255,12 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
         #[weak]
         button_confirm,
         #[weak]
+        page_numbering_row,
+        #[weak]
+        smart_page_breaks_row,
+        #[weak]
+        preview,
+        #[weak]
         canvas,
         move |row| {
             let export_format = DocExportFormat::try_from(row.selected()).unwrap();
@@ -238,6 +269,23 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
                 .export_prefs
                 .doc_export_prefs
                 .export_format = export_format;
+            page_numbering_row.set_sensitive(export_format == DocExportFormat::Pdf);
+            let crop_to_content = canvas
+                .engine_ref()
+                .export_prefs
+                .doc_export_prefs
+                .crop_to_content;
+            smart_page_breaks_row.set_sensitive(
+                !matches!(doc_layout, Layout::FixedSize | Layout::Whiteboard)
+                    && export_format == DocExportFormat::Pdf
+                    && !crop_to_content,
+            );
+            let doc_export_prefs = canvas.engine_ref().export_prefs.doc_export_prefs;
+            preview.set_contents(
+                canvas
+                    .engine_ref()
+                    .extract_doc_export_pages_content(&doc_export_prefs),
+            );
 
             // force the user to pick another file
             export_file_label.set_label(&gettext("- no file selected -"));
@@ -254,7 +302,66 @@ pub(crate) async fn dialog_export_doc_w_prefs(appwindow: &RnAppWindow, canvas: &
         move |row| {
             let page_order = SplitOrder::try_from(row.selected()).unwrap();
             canvas.engine_mut().export_prefs.doc_export_prefs.page_order = page_order;
-            preview.set_contents(canvas.engine_ref().extract_pages_content(page_order));
+            let doc_export_prefs = canvas.engine_ref().export_prefs.doc_export_prefs;
+            preview.set_contents(
+                canvas
+                    .engine_ref()
+                    .extract_doc_export_pages_content(&doc_export_prefs),
+            );
+        }
+    ));
+
+    crop_to_content_row.connect_active_notify(clone!(
+        #[weak]
+        page_order_row,
+        #[weak]
+        smart_page_breaks_row,
+        #[weak]
+        preview,
+        #[weak]
+        canvas,
+        move |row| {
+            let crop_to_content = row.is_active();
+            canvas
+                .engine_mut()
+                .export_prefs
+                .doc_export_prefs
+                .crop_to_content = crop_to_content;
+            let doc_export_prefs = canvas.engine_ref().export_prefs.doc_export_prefs;
+            page_order_row.set_sensitive(
+                (doc_layout == Layout::SemiInfinite || doc_layout == Layout::Infinite)
+                    && !crop_to_content,
+            );
+            smart_page_breaks_row.set_sensitive(
+                !matches!(doc_layout, Layout::FixedSize | Layout::Whiteboard)
+                    && doc_export_prefs.export_format == DocExportFormat::Pdf
+                    && !crop_to_content,
+            );
+            preview.set_contents(
+                canvas
+                    .engine_ref()
+                    .extract_doc_export_pages_content(&doc_export_prefs),
+            );
+        }
+    ));
+
+    smart_page_breaks_row.connect_active_notify(clone!(
+        #[weak]
+        preview,
+        #[weak]
+        canvas,
+        move |row| {
+            canvas
+                .engine_mut()
+                .export_prefs
+                .doc_export_prefs
+                .smart_page_breaks = row.is_active();
+            let doc_export_prefs = canvas.engine_ref().export_prefs.doc_export_prefs;
+            preview.set_contents(
+                canvas
+                    .engine_ref()
+                    .extract_doc_export_pages_content(&doc_export_prefs),
+            );
         }
     ));
 
@@ -412,6 +519,9 @@ pub(crate) async fn dialog_export_doc_pages_w_prefs(appwindow: &RnAppWindow, can
         .unwrap();
     let jpeg_quality_row: adw::SpinRow =
         builder.object("export_doc_pages_jpeg_quality_row").unwrap();
+    setup_page_numbering_rows(&builder, "export_doc_pages", canvas, |export_prefs| {
+        &mut export_prefs.doc_pages_export_prefs.page_numbering
+    });
     let export_dir_label: Label = builder.object("export_doc_pages_export_dir_label").unwrap();
     let export_dir_button: Button = builder
         .object("export_doc_pages_export_dir_button")
@@ -802,6 +912,9 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
     let optimize_printing_row: adw::SwitchRow = builder
         .object("export_selection_optimize_printing_row")
         .unwrap();
+    let embed_strokes_row: adw::SwitchRow = builder
+        .object("export_selection_embed_strokes_row")
+        .unwrap();
     let export_format_row: adw::ComboRow = builder
         .object("export_selection_export_format_row")
         .unwrap();
@@ -830,6 +943,9 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
     with_background_row.set_active(initial_selection_export_prefs.with_background);
     with_pattern_row.set_active(initial_selection_export_prefs.with_pattern);
     optimize_printing_row.set_active(initial_selection_export_prefs.optimize_printing);
+    embed_strokes_row.set_active(initial_selection_export_prefs.embed_strokes);
+    embed_strokes_row
+        .set_sensitive(initial_selection_export_prefs.export_format == SelectionExportFormat::Svg);
     preview.set_draw_background(initial_selection_export_prefs.with_background);
     preview.set_draw_pattern(initial_selection_export_prefs.with_pattern);
     preview.set_optimize_printing(initial_selection_export_prefs.optimize_printing);
@@ -953,10 +1069,24 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
         }
     ));
 
+    embed_strokes_row.connect_active_notify(clone!(
+        #[weak]
+        canvas,
+        move |embed_strokes_row| {
+            canvas
+                .engine_mut()
+                .export_prefs
+                .selection_export_prefs
+                .embed_strokes = embed_strokes_row.is_active();
+        }
+    ));
+
     export_format_row.connect_selected_notify(clone!(
         #[strong]
         selected_file,
         #[weak]
+        embed_strokes_row,
+        #[weak]
         bitmap_scalefactor_row,
         #[weak]
         jpeg_quality_row,
@@ -977,6 +1107,8 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
             button_confirm.set_sensitive(false);
             selected_file.replace(None);
 
+            // Strokes can only be embedded into Svgs
+            embed_strokes_row.set_sensitive(export_format == SelectionExportFormat::Svg);
             // Set the bitmap scalefactor sensitive only when exporting to a bitmap image
             bitmap_scalefactor_row.set_sensitive(
                 export_format == SelectionExportFormat::Png
@@ -1087,160 +1219,685 @@ pub(crate) async fn dialog_export_selection_w_prefs(appwindow: &RnAppWindow, can
     dialog.present(appwindow.root().as_ref());
 }
 
-/// Returns (if possible) a "reasonable" folder for export operations
-/// concerning the specified `appwindow` and `canvas`. The main goal
-/// of this function is to provide a "good" initial folder for the
-/// file chooser dialog when a canvas is exported.
-///
-/// The following locations are checked:
-///
-/// 1. the last export directory of `canvas`
-/// 2. the parent directory of the output file of `canvas`
-/// 3. the directory shown in the sidebar of the window
-///
-/// The first available will be returned.
-fn get_initial_folder_for_export(appwindow: &RnAppWindow, canvas: &RnCanvas) -> Option<gio::File> {
-    canvas
-        .last_export_dir()
-        .or_else(|| canvas.output_file().and_then(|p| p.parent()))
-        .or_else(|| {
-            appwindow
-                .sidebar()
-                .workspacebrowser()
-                .dir_list_dir()
-                .map(gio::File::for_path)
-        })
-}
-
-fn create_filedialog_export_selection(
-    appwindow: &RnAppWindow,
+/// Set up the page numbering rows with the given id prefix, reading from and writing to the page numbering
+/// preferences returned by `page_numbering_prefs`.
+fn setup_page_numbering_rows(
+    builder: &Builder,
+    id_prefix: &str,
     canvas: &RnCanvas,
-    selection_export_prefs: &SelectionExportPrefs,
-) -> FileDialog {
-    let filedialog = FileDialog::builder()
-        .title(gettext("Export Selection"))
-        .modal(true)
-        .accept_label(gettext("Select"))
-        .build();
+    page_numbering_prefs: fn(&mut ExportPrefs) -> &mut PageNumberingPrefs,
+) -> adw::ExpanderRow {
+    let page_numbering_row: adw::ExpanderRow = builder
+        .object(format!("{id_prefix}_page_numbering_row").as_str())
+        .unwrap();
+    let position_row: adw::ComboRow = builder
+        .object(format!("{id_prefix}_page_number_position_row").as_str())
+        .unwrap();
+    let font_row: adw::ComboRow = builder
+        .object(format!("{id_prefix}_page_number_font_row").as_str())
+        .unwrap();
+    let font_size_row: adw::SpinRow = builder
+        .object(format!("{id_prefix}_page_number_font_size_row").as_str())
+        .unwrap();
+    let start_row: adw::SpinRow = builder
+        .object(format!("{id_prefix}_page_number_start_row").as_str())
+        .unwrap();
 
-    filedialog.set_initial_folder(get_initial_folder_for_export(appwindow, canvas).as_ref());
+    let initial_prefs = *page_numbering_prefs(&mut canvas.engine_mut().export_prefs);
+    page_numbering_row.set_enable_expansion(initial_prefs.enabled);
+    position_row.set_selected(initial_prefs.position.to_u32().unwrap());
+    font_row.set_selected(initial_prefs.font.to_u32().unwrap());
+    font_size_row.set_value(initial_prefs.font_size);
+    start_row.set_value(initial_prefs.start as f64);
 
-    let filter = FileFilter::new();
-    // note : mimetypes are not supported with the native file picker on windows
-    // See the limitations on FileChooserNative
-    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
-    match selection_export_prefs.export_format {
-        SelectionExportFormat::Svg => {
-            if cfg!(target_os = "windows") {
-                filter.add_pattern("*.svg");
-            } else {
-                filter.add_mime_type("image/svg+xml");
-            }
-            if cfg!(target_os = "macos") {
-                filter.add_suffix("svg");
-            }
-            filter.set_name(Some(&gettext("Svg")));
+    page_numbering_row.connect_enable_expansion_notify(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            page_numbering_prefs(&mut canvas.engine_mut().export_prefs).enabled =
+                row.enables_expansion();
         }
-        SelectionExportFormat::Png => {
-            if cfg!(target_os = "windows") {
-                filter.add_pattern("*.png");
-            } else {
-                filter.add_mime_type("image/png");
-            }
-            if cfg!(target_os = "macos") {
-                filter.add_suffix("png");
-            }
-            filter.set_name(Some(&gettext("Png")));
+    ));
+
+    position_row.connect_selected_notify(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            page_numbering_prefs(&mut canvas.engine_mut().export_prefs).position =
+                PageNumberPosition::try_from(row.selected()).unwrap();
         }
-        SelectionExportFormat::Jpeg => {
-            if cfg!(target_os = "windows") {
-                filter.add_pattern("*.jpg");
-                filter.add_pattern("*.jpeg");
-            } else {
-                filter.add_mime_type("image/jpeg");
-            }
-            if cfg!(target_os = "macos") {
-                filter.add_suffix("jpg");
-                filter.add_suffix("jpeg");
-            }
-            filter.set_name(Some(&gettext("Jpeg")));
+    ));
+
+    font_row.connect_selected_notify(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            page_numbering_prefs(&mut canvas.engine_mut().export_prefs).font =
+                PageNumberFont::try_from(row.selected()).unwrap();
         }
-    }
-    let file_ext = selection_export_prefs.export_format.file_ext();
-    let file_name = crate::utils::default_file_title_for_export(
-        canvas.output_file(),
-        Some(&canvas::OUTPUT_FILE_NEW_TITLE),
-        Some(&(String::from(" - Selection") + "." + &file_ext)),
-    );
+    ));
 
-    let filter_list = gio::ListStore::new::<FileFilter>();
-    filter_list.append(&filter);
-    filedialog.set_filters(Some(&filter_list));
+    font_size_row.connect_changed(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            page_numbering_prefs(&mut canvas.engine_mut().export_prefs).font_size = row.value();
+        }
+    ));
 
-    filedialog.set_default_filter(Some(&filter));
-    filedialog.set_initial_name(Some(&file_name));
+    start_row.connect_changed(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            page_numbering_prefs(&mut canvas.engine_mut().export_prefs).start =
+                row.value().round() as u32;
+        }
+    ));
 
-    filedialog
+    page_numbering_row
 }
 
-pub(crate) async fn filechooser_export_engine_state(appwindow: &RnAppWindow, canvas: &RnCanvas) {
-    let filter = FileFilter::new();
-    // note : mimetypes are not supported with the native file picker on windows
-    // See the limitations on FileChooserNative
-    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
-    if cfg!(target_os = "windows") {
-        filter.add_pattern("*.json");
-    } else {
-        filter.add_mime_type("application/json");
-    }
-    if cfg!(target_os = "macos") {
-        filter.add_suffix("json");
-    }
-    filter.set_name(Some(&gettext("Json")));
-
-    let filter_list = gio::ListStore::new::<FileFilter>();
-    filter_list.append(&filter);
-
-    let initial_name = crate::utils::default_file_title_for_export(
-        canvas.output_file(),
-        Some(&canvas::OUTPUT_FILE_NEW_TITLE),
-        Some(" - engine state.json"),
+pub(crate) async fn dialog_export_viewport_w_prefs(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/export.ui").as_str(),
     );
+    let dialog: adw::Dialog = builder.object("dialog_export_viewport_w_prefs").unwrap();
+    let button_confirm: Button = builder.object("export_viewport_button_confirm").unwrap();
+    let with_background_row: adw::SwitchRow = builder
+        .object("export_viewport_with_background_row")
+        .unwrap();
+    let with_pattern_row: adw::SwitchRow =
+        builder.object("export_viewport_with_pattern_row").unwrap();
+    let optimize_printing_row: adw::SwitchRow = builder
+        .object("export_viewport_optimize_printing_row")
+        .unwrap();
+    let export_format_row: adw::ComboRow =
+        builder.object("export_viewport_export_format_row").unwrap();
+    let export_file_label: Label = builder.object("export_viewport_export_file_label").unwrap();
+    let export_file_button: Button = builder
+        .object("export_viewport_export_file_button")
+        .unwrap();
+    let bitmap_scalefactor_row: adw::SpinRow = builder
+        .object("export_viewport_bitmap_scalefactor_row")
+        .unwrap();
+    let jpeg_quality_row: adw::SpinRow =
+        builder.object("export_viewport_jpeg_quality_row").unwrap();
+    let preview: RnStrokeContentPreview = builder.object("export_viewport_preview").unwrap();
+    let export_viewport_button_cancel: Button =
+        builder.object("export_viewport_button_cancel").unwrap();
+    let export_viewport_button_confirm: Button =
+        builder.object("export_viewport_button_confirm").unwrap();
 
-    let filedialog = FileDialog::builder()
-        .title(gettext("Export Engine State"))
-        .modal(true)
-        .accept_label(gettext("Export"))
-        .filters(&filter_list)
-        .default_filter(&filter)
-        .initial_name(&initial_name)
-        .build();
+    let initial_viewport_export_prefs = canvas.engine_ref().export_prefs.viewport_export_prefs;
 
-    filedialog.set_initial_folder(get_initial_folder_for_export(appwindow, canvas).as_ref());
+    // initial widget state with the preferences
+    let selected_file: Rc<RefCell<Option<gio::File>>> = Rc::new(RefCell::new(None));
+    with_background_row.set_active(initial_viewport_export_prefs.with_background);
+    with_pattern_row.set_active(initial_viewport_export_prefs.with_pattern);
+    optimize_printing_row.set_active(initial_viewport_export_prefs.optimize_printing);
+    preview.set_draw_background(initial_viewport_export_prefs.with_background);
+    preview.set_draw_pattern(initial_viewport_export_prefs.with_pattern);
+    preview.set_optimize_printing(initial_viewport_export_prefs.optimize_printing);
+    preview.set_margin(0.0);
+    preview.set_contents(vec![canvas.engine_ref().extract_viewport_content()]);
+    export_format_row.set_selected(
+        initial_viewport_export_prefs
+            .export_format
+            .to_u32()
+            .unwrap(),
+    );
+    bitmap_scalefactor_row.set_sensitive(
+        initial_viewport_export_prefs.export_format == SelectionExportFormat::Png
+            || initial_viewport_export_prefs.export_format == SelectionExportFormat::Jpeg,
+    );
+    bitmap_scalefactor_row.set_value(initial_viewport_export_prefs.bitmap_scalefactor);
+    jpeg_quality_row
+        .set_sensitive(initial_viewport_export_prefs.export_format == SelectionExportFormat::Jpeg);
+    jpeg_quality_row.set_value(initial_viewport_export_prefs.jpeg_quality as f64);
+    export_file_label.set_label(&gettext("- no file selected -"));
+    button_confirm.set_sensitive(false);
 
-    match filedialog.save_future(Some(appwindow)).await {
-        Ok(selected_file) => {
-            appwindow.overlays().progressbar_start_pulsing();
+    // Update prefs
 
-            if let Err(e) = canvas.export_engine_state(&selected_file).await {
-                error!("Exporting engine state failed, Err: {e:?}");
+    export_file_button.connect_clicked(
+        clone!(#[strong] selected_file, #[weak] export_file_label, #[weak] button_confirm, #[weak] dialog, #[weak] canvas, #[weak] appwindow , move |_| {
+            glib::spawn_future_local(clone!(#[strong] selected_file, #[weak] export_file_label, #[weak] button_confirm, #[weak] dialog, #[weak] canvas, #[weak] appwindow , async move {
+                dialog.set_sensitive(false);
 
-                appwindow
-                    .overlays()
-                    .dispatch_toast_error(&gettext("Exporting engine state failed"));
-                appwindow.overlays().progressbar_abort();
-            } else {
-                appwindow.overlays().dispatch_toast_text(
-                    &gettext("Exported engine state successfully"),
-                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                let viewport_export_prefs = canvas
+                    .engine_ref()
+                    .export_prefs
+                    .viewport_export_prefs;
+                let filedialog = create_filedialog_export_viewport(
+                    &appwindow,
+                    &canvas,
+                    &viewport_export_prefs,
                 );
-                appwindow.overlays().progressbar_finish();
-            }
-        }
-        Err(e) => {
-            debug!("Did not export engine state (Error or dialog dismissed by user), Err: {e:?}");
-        }
-    }
+                match filedialog.save_future(Some(&appwindow)).await {
+                    Ok(f) => {
+                        if let Some(path_string) = f.path().map(|p| p.to_string_lossy().to_string()) {
+                            export_file_label.set_label(&path_string);
+                            button_confirm.set_sensitive(true);
+                            selected_file.replace(Some(f));
+                        } else {
+                            export_file_label.set_label(&gettext("- no file selected -"));
+                            button_confirm.set_sensitive(false);
+                            selected_file.replace(None);
+                        }
+                    }
+                    Err(e) => {
+                        debug!("Did not export view (Error or dialog dismissed by user), Err: {e:?}");
+                        export_file_label.set_label(&gettext("- no file selected -"));
+                        button_confirm.set_sensitive(false);
+                        selected_file.replace(None);
+                    }
+                }
+
+                dialog.set_sensitive(true);
+            }));
+        }),
+    );
+
+    with_background_row
+        .bind_property("active", &with_pattern_row, "sensitive")
+        .sync_create()
+        .build();
+
+    with_background_row.connect_active_notify(clone!(
+        #[weak]
+        preview,
+        #[weak]
+        canvas,
+        move |row| {
+            let active = row.is_active();
+            canvas
+                .engine_mut()
+                .export_prefs
+                .viewport_export_prefs
+                .with_background = active;
+            preview.set_draw_background(active);
+        }
+    ));
+
+    with_pattern_row.connect_active_notify(clone!(
+        #[weak]
+        preview,
+        #[weak]
+        canvas,
+        move |row| {
+            let active = row.is_active();
+            canvas
+                .engine_mut()
+                .export_prefs
+                .viewport_export_prefs
+                .with_pattern = active;
+            preview.set_draw_pattern(active);
+        }
+    ));
+
+    optimize_printing_row.connect_active_notify(clone!(
+        #[weak]
+        preview,
+        #[weak]
+        canvas,
+        move |optimize_printing_row| {
+            let active = optimize_printing_row.is_active();
+            canvas
+                .engine_mut()
+                .export_prefs
+                .viewport_export_prefs
+                .optimize_printing = active;
+            preview.set_optimize_printing(active);
+        }
+    ));
+
+    export_format_row.connect_selected_notify(clone!(
+        #[strong]
+        selected_file,
+        #[weak]
+        bitmap_scalefactor_row,
+        #[weak]
+        jpeg_quality_row,
+        #[weak]
+        export_file_label,
+        #[weak]
+        canvas,
+        move |row| {
+            let export_format = SelectionExportFormat::try_from(row.selected()).unwrap();
+            canvas
+                .engine_mut()
+                .export_prefs
+                .viewport_export_prefs
+                .export_format = export_format;
+
+            // force the user to pick another file
+            export_file_label.set_label(&gettext("- no file selected -"));
+            button_confirm.set_sensitive(false);
+            selected_file.replace(None);
+
+            // Set the bitmap scalefactor sensitive only when exporting to a bitmap image
+            bitmap_scalefactor_row.set_sensitive(
+                export_format == SelectionExportFormat::Png
+                    || export_format == SelectionExportFormat::Jpeg,
+            );
+            // Set the jpeg quality pref only sensitive when jpeg is actually selected
+            jpeg_quality_row.set_sensitive(export_format == SelectionExportFormat::Jpeg);
+        }
+    ));
+
+    bitmap_scalefactor_row.connect_changed(clone!(
+        #[weak]
+        canvas,
+        move |bitmap_scalefactor_row| {
+            canvas
+                .engine_mut()
+                .export_prefs
+                .viewport_export_prefs
+                .bitmap_scalefactor = bitmap_scalefactor_row.value();
+        }
+    ));
+
+    jpeg_quality_row.connect_changed(clone!(
+        #[weak]
+        canvas,
+        move |jpeg_quality_row| {
+            canvas
+                .engine_mut()
+                .export_prefs
+                .viewport_export_prefs
+                .jpeg_quality = jpeg_quality_row.value().clamp(1.0, 100.0) as u8;
+        }
+    ));
+
+    // Listen to responses
+
+    export_viewport_button_cancel.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| {
+            dialog.close();
+        }
+    ));
+
+    export_viewport_button_confirm.connect_clicked(clone!(#[weak] dialog, #[weak] canvas, #[weak] appwindow , move |_| {
+        dialog.close();
+
+        let Some(file) = selected_file.take() else {
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Exporting view failed, no file selected"));
+            return;
+        };
+
+        glib::spawn_future_local(clone!(#[weak] canvas, #[weak] appwindow , async move {
+            appwindow.overlays().progressbar_start_pulsing();
+
+            if let Err(e) = canvas.export_viewport(&file, None).await {
+                error!("Exporting view failed, Err: {e:?}");
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("Exporting view failed"));
+                appwindow.overlays().progressbar_abort();
+                return;
+            }
+
+            appwindow.overlays().dispatch_toast_w_button(
+                &gettext("Exported view successfully"),
+                &gettext("View in file manager"),
+                clone!(#[weak] appwindow , move |_| {
+                    let Some(folder_path_string) = file
+                        .parent()
+                        .and_then(|p|
+                            p.path())
+                        .and_then(|p| p.into_os_string().into_string().ok()) else {
+                            error!("Failed to get the parent folder of the output file `{file:?}.");
+                            appwindow.overlays().dispatch_toast_error(&gettext("Failed to view the file in the file manager"));
+                            return;
+                    };
+
+                    if let Err(e) = open::that(&folder_path_string) {
+                        error!("Opening the parent folder '{folder_path_string}' in the file manager failed, Err: {e:?}");
+                        appwindow.overlays().dispatch_toast_error(&gettext("Failed to view the file in the file manager"));
+                    }
+                }),
+                crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+            );
+            appwindow.overlays().progressbar_finish();
+        }));
+    }));
+
+    dialog.present(appwindow.root().as_ref());
+}
+
+/// Set up the page numbering rows with the given id prefix, reading from and writing to the page numbering
+/// preferences returned by `page_numbering_prefs`.
+fn setup_page_numbering_rows(
+    builder: &Builder,
+    id_prefix: &str,
+    canvas: &RnCanvas,
+    page_numbering_prefs: fn(&mut ExportPrefs) -> &mut PageNumberingPrefs,
+) -> adw::ExpanderRow {
+    let page_numbering_row: adw::ExpanderRow = builder
+        .object(format!("{id_prefix}_page_numbering_row").as_str())
+        .unwrap();
+    let position_row: adw::ComboRow = builder
+        .object(format!("{id_prefix}_page_number_position_row").as_str())
+        .unwrap();
+    let font_row: adw::ComboRow = builder
+        .object(format!("{id_prefix}_page_number_font_row").as_str())
+        .unwrap();
+    let font_size_row: adw::SpinRow = builder
+        .object(format!("{id_prefix}_page_number_font_size_row").as_str())
+        .unwrap();
+    let start_row: adw::SpinRow = builder
+        .object(format!("{id_prefix}_page_number_start_row").as_str())
+        .unwrap();
+
+    let initial_prefs = *page_numbering_prefs(&mut canvas.engine_mut().export_prefs);
+    page_numbering_row.set_enable_expansion(initial_prefs.enabled);
+    position_row.set_selected(initial_prefs.position.to_u32().unwrap());
+    font_row.set_selected(initial_prefs.font.to_u32().unwrap());
+    font_size_row.set_value(initial_prefs.font_size);
+    start_row.set_value(initial_prefs.start as f64);
+
+    page_numbering_row.connect_enable_expansion_notify(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            page_numbering_prefs(&mut canvas.engine_mut().export_prefs).enabled =
+                row.enables_expansion();
+        }
+    ));
+
+    position_row.connect_selected_notify(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            page_numbering_prefs(&mut canvas.engine_mut().export_prefs).position =
+                PageNumberPosition::try_from(row.selected()).unwrap();
+        }
+    ));
+
+    font_row.connect_selected_notify(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            page_numbering_prefs(&mut canvas.engine_mut().export_prefs).font =
+                PageNumberFont::try_from(row.selected()).unwrap();
+        }
+    ));
+
+    font_size_row.connect_changed(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            page_numbering_prefs(&mut canvas.engine_mut().export_prefs).font_size = row.value();
+        }
+    ));
+
+    start_row.connect_changed(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            page_numbering_prefs(&mut canvas.engine_mut().export_prefs).start =
+                row.value().round() as u32;
+        }
+    ));
+
+    page_numbering_row
+}
+
+/// Returns (if possible) a "reasonable" folder for export operations
+/// concerning the specified `appwindow` and `canvas`. The main goal
+/// of this function is to provide a "good" initial folder for the
+/// file chooser dialog when a canvas is exported.
+///
+/// The following locations are checked:
+///
+/// 1. the last export directory of `canvas`
+/// 2. the parent directory of the output file of `canvas`
+/// 3. the directory shown in the sidebar of the window
+///
+/// The first available will be returned.
+fn get_initial_folder_for_export(appwindow: &RnAppWindow, canvas: &RnCanvas) -> Option<gio::File> {
+    canvas
+        .last_export_dir()
+        .or_else(|| canvas.output_file().and_then(|p| p.parent()))
+        .or_else(|| {
+            appwindow
+                .sidebar()
+                .workspacebrowser()
+                .dir_list_dir()
+                .map(gio::File::for_path)
+        })
+}
+
+fn create_filedialog_export_selection(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    selection_export_prefs: &SelectionExportPrefs,
+) -> FileDialog {
+    let filedialog = FileDialog::builder()
+        .title(gettext("Export Selection"))
+        .modal(true)
+        .accept_label(gettext("Select"))
+        .build();
+
+    filedialog.set_initial_folder(get_initial_folder_for_export(appwindow, canvas).as_ref());
+
+    let filter = image_export_format_filter(selection_export_prefs.export_format);
+    let file_ext = selection_export_prefs.export_format.file_ext();
+    let file_name = crate::utils::default_file_title_for_export(
+        canvas.output_file(),
+        Some(&canvas::OUTPUT_FILE_NEW_TITLE),
+        Some(&(String::from(" - Selection") + "." + &file_ext)),
+    );
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+    filedialog.set_filters(Some(&filter_list));
+
+    filedialog.set_default_filter(Some(&filter));
+    filedialog.set_initial_name(Some(&file_name));
+
+    filedialog
+}
+
+fn create_filedialog_export_viewport(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    viewport_export_prefs: &ViewportExportPrefs,
+) -> FileDialog {
+    let filedialog = FileDialog::builder()
+        .title(gettext("Export View"))
+        .modal(true)
+        .accept_label(gettext("Select"))
+        .build();
+
+    filedialog.set_initial_folder(get_initial_folder_for_export(appwindow, canvas).as_ref());
+
+    let filter = image_export_format_filter(viewport_export_prefs.export_format);
+    let file_ext = viewport_export_prefs.export_format.file_ext();
+    let file_name = crate::utils::default_file_title_for_export(
+        canvas.output_file(),
+        Some(&canvas::OUTPUT_FILE_NEW_TITLE),
+        Some(&(String::from(" - View") + "." + &file_ext)),
+    );
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+    filedialog.set_filters(Some(&filter_list));
+
+    filedialog.set_default_filter(Some(&filter));
+    filedialog.set_initial_name(Some(&file_name));
+
+    filedialog
+}
+
+/// The file filter for the given image export format.
+fn image_export_format_filter(export_format: SelectionExportFormat) -> FileFilter {
+    let filter = FileFilter::new();
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    match export_format {
+        SelectionExportFormat::Svg => {
+            if cfg!(target_os = "windows") {
+                filter.add_pattern("*.svg");
+            } else {
+                filter.add_mime_type("image/svg+xml");
+            }
+            if cfg!(target_os = "macos") {
+                filter.add_suffix("svg");
+            }
+            filter.set_name(Some(&gettext("Svg")));
+        }
+        SelectionExportFormat::Png => {
+            if cfg!(target_os = "windows") {
+                filter.add_pattern("*.png");
+            } else {
+                filter.add_mime_type("image/png");
+            }
+            if cfg!(target_os = "macos") {
+                filter.add_suffix("png");
+            }
+            filter.set_name(Some(&gettext("Png")));
+        }
+        SelectionExportFormat::Jpeg => {
+            if cfg!(target_os = "windows") {
+                filter.add_pattern("*.jpg");
+                filter.add_pattern("*.jpeg");
+            } else {
+                filter.add_mime_type("image/jpeg");
+            }
+            if cfg!(target_os = "macos") {
+                filter.add_suffix("jpg");
+                filter.add_suffix("jpeg");
+            }
+            filter.set_name(Some(&gettext("Jpeg")));
+        }
+    }
+    filter
+}
+
+pub(crate) async fn filedialog_export_replay(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let filter = FileFilter::new();
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.gif");
+    } else {
+        filter.add_mime_type("image/gif");
+    }
+    if cfg!(target_os = "macos") {
+        filter.add_suffix("gif");
+    }
+    filter.set_name(Some(&gettext("Gif")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let initial_name = crate::utils::default_file_title_for_export(
+        canvas.output_file(),
+        Some(&canvas::OUTPUT_FILE_NEW_TITLE),
+        Some(" - replay.gif"),
+    );
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Export Drawing Replay"))
+        .modal(true)
+        .accept_label(gettext("Export"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .initial_name(&initial_name)
+        .build();
+
+    filedialog.set_initial_folder(get_initial_folder_for_export(appwindow, canvas).as_ref());
+
+    match filedialog.save_future(Some(appwindow)).await {
+        Ok(selected_file) => {
+            appwindow.overlays().progressbar_start_pulsing();
+
+            if let Err(e) = canvas
+                .export_replay(&selected_file, appwindow.replay_speed())
+                .await
+            {
+                error!("Exporting drawing replay failed, Err: {e:?}");
+
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("Exporting drawing replay failed"));
+                appwindow.overlays().progressbar_abort();
+            } else {
+                appwindow.overlays().dispatch_toast_text(
+                    &gettext("Exported drawing replay successfully"),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+                appwindow.overlays().progressbar_finish();
+            }
+        }
+        Err(e) => {
+            debug!("Did not export drawing replay (Error or dialog dismissed by user), Err: {e:?}");
+        }
+    }
+}
+
+pub(crate) async fn filechooser_export_engine_state(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let filter = FileFilter::new();
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.json");
+    } else {
+        filter.add_mime_type("application/json");
+    }
+    if cfg!(target_os = "macos") {
+        filter.add_suffix("json");
+    }
+    filter.set_name(Some(&gettext("Json")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let initial_name = crate::utils::default_file_title_for_export(
+        canvas.output_file(),
+        Some(&canvas::OUTPUT_FILE_NEW_TITLE),
+        Some(" - engine state.json"),
+    );
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Export Engine State"))
+        .modal(true)
+        .accept_label(gettext("Export"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .initial_name(&initial_name)
+        .build();
+
+    filedialog.set_initial_folder(get_initial_folder_for_export(appwindow, canvas).as_ref());
+
+    match filedialog.save_future(Some(appwindow)).await {
+        Ok(selected_file) => {
+            appwindow.overlays().progressbar_start_pulsing();
+
+            if let Err(e) = canvas.export_engine_state(&selected_file).await {
+                error!("Exporting engine state failed, Err: {e:?}");
+
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("Exporting engine state failed"));
+                appwindow.overlays().progressbar_abort();
+            } else {
+                appwindow.overlays().dispatch_toast_text(
+                    &gettext("Exported engine state successfully"),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+                appwindow.overlays().progressbar_finish();
+            }
+        }
+        Err(e) => {
+            debug!("Did not export engine state (Error or dialog dismissed by user), Err: {e:?}");
+        }
+    }
 }
 
 pub(crate) async fn filechooser_export_engine_config(appwindow: &RnAppWindow, canvas: &RnCanvas) {
@@ -1303,3 +1960,119 @@ pub(crate) async fn filechooser_export_engine_config(appwindow: &RnAppWindow, ca
         }
     }
 }
+
+pub(crate) async fn filechooser_export_profile(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let filter = FileFilter::new();
+
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.json");
+    } else {
+        filter.add_mime_type("application/json");
+    }
+    if cfg!(target_os = "macos") {
+        filter.add_suffix("json");
+    }
+    filter.set_name(Some(&gettext("Json")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let initial_name = crate::utils::default_file_title_for_export(
+        canvas.output_file(),
+        Some(&canvas::OUTPUT_FILE_NEW_TITLE),
+        Some(" - profile.json"),
+    );
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Export Profile"))
+        .modal(true)
+        .accept_label(gettext("Export"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .initial_name(&initial_name)
+        .build();
+
+    filedialog.set_initial_folder(get_initial_folder_for_export(appwindow, canvas).as_ref());
+
+    match filedialog.save_future(Some(appwindow)).await {
+        Ok(selected_file) => {
+            appwindow.overlays().progressbar_start_pulsing();
+
+            if let Err(e) = canvas.export_profile(&selected_file).await {
+                error!("Exporting profile failed, Err: {e:?}");
+
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("Exporting profile failed"));
+                appwindow.overlays().progressbar_abort();
+            } else {
+                appwindow.overlays().dispatch_toast_text(
+                    &gettext("Exported profile successfully"),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+                appwindow.overlays().progressbar_finish();
+            }
+        }
+        Err(e) => {
+            debug!("Did not export profile (Error or dialog dismissed by user), Err: {e:?}");
+        }
+    }
+}
+
+pub(crate) async fn filedialog_export_workspace_list(appwindow: &RnAppWindow) {
+    let filter = FileFilter::new();
+
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.json");
+    } else {
+        filter.add_mime_type("application/json");
+    }
+    if cfg!(target_os = "macos") {
+        filter.add_suffix("json");
+    }
+    filter.set_name(Some(&gettext("Json")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Export Workspaces"))
+        .modal(true)
+        .accept_label(gettext("Export"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .initial_name("workspaces.json")
+        .build();
+
+    match filedialog.save_future(Some(appwindow)).await {
+        Ok(selected_file) => {
+            if let Err(e) = appwindow
+                .sidebar()
+                .workspacebrowser()
+                .workspacesbar()
+                .export_workspace_list(&selected_file)
+                .await
+            {
+                error!("Exporting workspace list failed, Err: {e:?}");
+
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("Exporting workspaces failed"));
+            } else {
+                appwindow.overlays().dispatch_toast_text(
+                    &gettext("Exported workspaces successfully"),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+            }
+        }
+        Err(e) => {
+            debug!("Did not export workspace list (Error or dialog dismissed by user), Err: {e:?}");
+        }
+    }
+}
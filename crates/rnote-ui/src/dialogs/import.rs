@@ -7,11 +7,22 @@ use adw::prelude::*;
 use futures::StreamExt;
 use gettextrs::gettext;
 use gtk4::{
-    gio, glib, glib::clone, graphene, gsk, Builder, Button, CallbackAction, FileDialog, FileFilter,
-    Label, Shortcut, ShortcutController, ShortcutTrigger, ToggleButton,
+    gdk, gio, glib, glib::clone, graphene, gsk, Builder, Button, CallbackAction, ColorDialogButton,
+    FileDialog, FileFilter, Label, Picture, Shortcut, ShortcutController, ShortcutTrigger,
+    ToggleButton,
 };
 use num_traits::ToPrimitive;
-use rnote_engine::engine::import::{PdfImportPageSpacing, PdfImportPagesType};
+use p2d::bounding_volume::Aabb;
+use rnote_compose::color;
+use rnote_engine::document::background::PatternTile;
+use rnote_engine::engine::import::{
+    parse_pdf_page_selection, BitmapImportPrefs, ColorRemapPrefs, PdfImportPageSpacing,
+    PdfImportPagesType,
+};
+use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::render;
+use std::cell::Cell;
+use std::rc::Rc;
 use tracing::{debug, error};
 
 /// Opens a new rnote save file in a new tab
@@ -55,7 +66,81 @@ pub(crate) async fn filedialog_open_doc(appwindow: &RnAppWindow) {
     }
 }
 
-pub(crate) async fn filedialog_import_file(appwindow: &RnAppWindow) {
+/// Chooses an image or Svg file as tile of the custom background pattern of the active document.
+///
+/// The file is embedded in the document when `embed` is set, else only its path is stored.
+pub(crate) async fn filedialog_choose_background_pattern_tile(
+    appwindow: &RnAppWindow,
+    embed: bool,
+) {
+    let filter = FileFilter::new();
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.svg");
+        filter.add_pattern("*.png");
+        filter.add_pattern("*.jpg");
+        filter.add_pattern("*.jpeg");
+    } else {
+        filter.add_mime_type("image/svg+xml");
+        filter.add_mime_type("image/png");
+        filter.add_mime_type("image/jpeg");
+    }
+    filter.add_suffix("svg");
+    filter.add_suffix("png");
+    filter.add_suffix("jpg");
+    filter.add_suffix("jpeg");
+    filter.set_name(Some(&gettext("Svg or Image File")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Choose Pattern Tile"))
+        .modal(true)
+        .accept_label(gettext("Choose"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .build();
+
+    let selected_file = match filedialog.open_future(Some(appwindow)).await {
+        Ok(selected_file) => selected_file,
+        Err(e) => {
+            debug!("Did not choose pattern tile (Error or dialog dismissed by user), Err: {e:?}");
+            return;
+        }
+    };
+    let (Some(path), Some(canvas)) = (selected_file.path(), appwindow.active_tab_canvas()) else {
+        return;
+    };
+    let pattern_tile = if embed {
+        PatternTile::Reference { path }.embed()
+    } else {
+        Ok(PatternTile::Reference { path })
+    };
+    let widget_flags =
+        pattern_tile.and_then(|tile| canvas.engine_mut().set_background_pattern_tile(tile));
+
+    match widget_flags {
+        Ok(mut widget_flags) => {
+            widget_flags.store_modified = true;
+            appwindow.handle_widget_flags(widget_flags, &canvas);
+            if let Some(active_tab) = appwindow.active_tab_wrapper() {
+                appwindow.sidebar().settings_panel().refresh_ui(&active_tab);
+            }
+        }
+        Err(e) => {
+            error!("Setting background pattern tile failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Setting the pattern tile failed"));
+        }
+    }
+}
+
+/// Imports the selected file. It is inserted at the target position in document coordinates when supplied.
+pub(crate) async fn filedialog_import_file(
+    appwindow: &RnAppWindow,
+    target_pos: Option<na::Vector2<f64>>,
+) {
     let filter = FileFilter::new();
     // note : mimetypes are not supported with the native file picker on windows
     // See the limitations on FileChooserNative
@@ -102,7 +187,7 @@ pub(crate) async fn filedialog_import_file(appwindow: &RnAppWindow) {
     match dialog.open_future(Some(appwindow)).await {
         Ok(selected_file) => {
             appwindow
-                .open_file_w_dialogs(selected_file, None, true)
+                .open_file_w_dialogs(selected_file, target_pos, true)
                 .await;
         }
         Err(e) => {
@@ -111,6 +196,114 @@ pub(crate) async fn filedialog_import_file(appwindow: &RnAppWindow) {
     }
 }
 
+/// Appends the strokes of a rnote save file to the document of the active tab
+pub(crate) async fn filedialog_append_rnote_file(appwindow: &RnAppWindow) {
+    let Some(canvas) = appwindow.active_tab_canvas() else {
+        return;
+    };
+
+    let filter = FileFilter::new();
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.rnote");
+    } else {
+        filter.add_mime_type("application/rnote");
+    }
+    filter.add_suffix("rnote");
+    filter.set_name(Some(&gettext(".rnote")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Append File to Document"))
+        .modal(true)
+        .accept_label(gettext("Append"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .build();
+
+    if let Some(current_workspace_dir) = appwindow.sidebar().workspacebrowser().dir_list_dir() {
+        filedialog.set_initial_folder(Some(&gio::File::for_path(current_workspace_dir)));
+    }
+
+    let selected_file = match filedialog.open_future(Some(appwindow)).await {
+        Ok(selected_file) => selected_file,
+        Err(e) => {
+            debug!("Did not append file (Error or dialog dismissed by user), Err: {e:?}");
+            return;
+        }
+    };
+
+    appwindow.overlays().progressbar_start_pulsing();
+    let res = async {
+        let (bytes, _) = selected_file.load_bytes_future().await?;
+        canvas.append_rnote_bytes(bytes.to_vec()).await
+    }
+    .await;
+    match res {
+        Ok(()) => {
+            appwindow.overlays().progressbar_finish();
+        }
+        Err(e) => {
+            error!("Appending file to document failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Appending file to document failed"));
+            appwindow.overlays().progressbar_abort();
+        }
+    }
+}
+
+/// Replaces the workspaces with the ones from an exported workspace list
+pub(crate) async fn filedialog_import_workspace_list(appwindow: &RnAppWindow) {
+    let filter = FileFilter::new();
+    // note : mimetypes are not supported with the native file picker on windows
+    // See the limitations on FileChooserNative
+    // https://gtk-rs.org/gtk3-rs/stable/latest/docs/gtk/struct.FileChooserNative.html#win32-details--gtkfilechooserdialognative-win32
+    if cfg!(target_os = "windows") {
+        filter.add_pattern("*.json");
+    } else {
+        filter.add_mime_type("application/json");
+    }
+    filter.add_suffix("json");
+    filter.set_name(Some(&gettext("Json")));
+
+    let filter_list = gio::ListStore::new::<FileFilter>();
+    filter_list.append(&filter);
+
+    let filedialog = FileDialog::builder()
+        .title(gettext("Import Workspaces"))
+        .modal(true)
+        .accept_label(gettext("Import"))
+        .filters(&filter_list)
+        .default_filter(&filter)
+        .build();
+
+    match filedialog.open_future(Some(appwindow)).await {
+        Ok(selected_file) => {
+            if let Err(e) = appwindow
+                .sidebar()
+                .workspacebrowser()
+                .workspacesbar()
+                .import_workspace_list(&selected_file)
+                .await
+            {
+                error!("Importing workspace list failed, Err: {e:?}");
+
+                appwindow
+                    .overlays()
+                    .dispatch_toast_error(&gettext("Importing workspaces failed"));
+            }
+        }
+        Err(e) => {
+            debug!("Did not import workspace list (Error or dialog dismissed by user), Err: {e:?}");
+        }
+    }
+}
+
 /// Check for a pdf encryption and request a password if needed from the user
 ///
 /// Returns a password Option and a boolean weather the user canceled the file import or not
@@ -220,6 +413,69 @@ pub(crate) async fn pdf_encryption_check_and_dialog(
     }
 }
 
+/// Check whether the bitmap image exceeds the maximum size of the import prefs
+/// and ask the user whether it should be downscaled if it does.
+///
+/// Returns whether the image should be downscaled, or None when the user canceled the import.
+pub(crate) async fn bitmapimage_size_check_and_dialog(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    bytes: &[u8],
+) -> Option<bool> {
+    let (pixel_width, pixel_height) = match canvas.engine_ref().bitmapimage_bytes_oversized(bytes) {
+        Ok(Some(pixel_size)) => pixel_size,
+        Ok(None) => return Some(false),
+        Err(e) => {
+            // Decoding will fail again on import and report the error there
+            debug!("Reading pixel size of bitmap image bytes failed, Err: {e:?}");
+            return Some(false);
+        }
+    };
+
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/import.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder
+        .object("dialog_import_oversized_bitmapimage")
+        .unwrap();
+    let max_pixel_size_row: adw::SpinRow = builder
+        .object("bitmapimage_import_max_pixel_size_row")
+        .unwrap();
+
+    dialog.set_body(&format!(
+        "{} ({pixel_width} × {pixel_height} px)",
+        dialog.body()
+    ));
+    max_pixel_size_row.set_range(
+        f64::from(BitmapImportPrefs::MAX_PIXEL_SIZE_MIN),
+        f64::from(BitmapImportPrefs::MAX_PIXEL_SIZE_MAX),
+    );
+    max_pixel_size_row.set_value(f64::from(
+        canvas
+            .engine_ref()
+            .import_prefs
+            .bitmap_import_prefs
+            .max_pixel_size,
+    ));
+    max_pixel_size_row.connect_changed(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            canvas
+                .engine_mut()
+                .import_prefs
+                .bitmap_import_prefs
+                .max_pixel_size = row.value().round() as u32;
+        }
+    ));
+
+    match dialog.choose_future(appwindow).await.as_str() {
+        "downscale" => Some(true),
+        "original" => Some(false),
+        _ => None,
+    }
+}
+
 /// Imports the file as Pdf with an import dialog.
 ///
 /// Returns true when the file was imported, else false.
@@ -238,12 +494,12 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
         (String::from(config::APP_IDPATH) + "ui/dialogs/import.ui").as_str(),
     );
     let dialog: adw::Dialog = builder.object("dialog_import_pdf_w_prefs").unwrap();
-    let pdf_page_start_row: adw::SpinRow = builder.object("pdf_page_start_row").unwrap();
-    let pdf_page_end_row: adw::SpinRow = builder.object("pdf_page_end_row").unwrap();
+    let pdf_pages_row: adw::EntryRow = builder.object("pdf_pages_row").unwrap();
     let pdf_info_label: Label = builder.object("pdf_info_label").unwrap();
     let pdf_import_width_row: adw::SpinRow = builder.object("pdf_import_width_row").unwrap();
     let pdf_import_page_spacing_row: adw::ComboRow =
         builder.object("pdf_import_page_spacing_row").unwrap();
+    let pdf_import_page_gap_row: adw::SpinRow = builder.object("pdf_import_page_gap_row").unwrap();
     let pdf_import_as_bitmap_toggle: ToggleButton =
         builder.object("pdf_import_as_bitmap_toggle").unwrap();
     let pdf_import_as_vector_toggle: ToggleButton =
@@ -267,6 +523,21 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
         .invert_boolean()
         .sync_create()
         .build();
+    let update_page_gap_sensitivity = clone!(
+        #[weak]
+        pdf_import_adjust_document_row,
+        #[weak]
+        pdf_import_page_spacing_row,
+        #[weak]
+        pdf_import_page_gap_row,
+        move || {
+            pdf_import_page_gap_row.set_sensitive(
+                !pdf_import_adjust_document_row.is_active()
+                    && pdf_import_page_spacing_row.selected()
+                        == PdfImportPageSpacing::Continuous.to_u32().unwrap(),
+            );
+        }
+    );
 
     let pdf_import_prefs = canvas.engine_ref().import_prefs.pdf_import_prefs;
 
@@ -283,19 +554,12 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
         }
     }
     pdf_import_page_spacing_row.set_selected(pdf_import_prefs.page_spacing.to_u32().unwrap());
+    pdf_import_page_gap_row.set_value(pdf_import_prefs.page_gap);
+    update_page_gap_sensitivity();
     pdf_import_bitmap_scalefactor_row.set_value(pdf_import_prefs.bitmap_scalefactor);
     pdf_import_page_borders_row.set_active(pdf_import_prefs.page_borders);
     pdf_import_adjust_document_row.set_active(pdf_import_prefs.adjust_document);
 
-    pdf_page_start_row
-        .bind_property("value", &pdf_page_end_row.adjustment(), "lower")
-        .sync_create()
-        .build();
-    pdf_page_end_row
-        .bind_property("value", &pdf_page_start_row.adjustment(), "upper")
-        .sync_create()
-        .build();
-
     // Update preferences
     pdf_import_as_vector_toggle.connect_toggled(clone!(
         #[weak]
@@ -340,6 +604,8 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
     pdf_import_page_spacing_row.connect_selected_notify(clone!(
         #[weak]
         canvas,
+        #[strong]
+        update_page_gap_sensitivity,
         move |row| {
             let page_spacing = PdfImportPageSpacing::try_from(row.selected()).unwrap();
 
@@ -348,6 +614,15 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
                 .import_prefs
                 .pdf_import_prefs
                 .page_spacing = page_spacing;
+            update_page_gap_sensitivity();
+        }
+    ));
+
+    pdf_import_page_gap_row.connect_changed(clone!(
+        #[weak]
+        canvas,
+        move |row| {
+            canvas.engine_mut().import_prefs.pdf_import_prefs.page_gap = row.value();
         }
     ));
 
@@ -378,15 +653,20 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
     pdf_import_adjust_document_row.connect_active_notify(clone!(
         #[weak]
         canvas,
+        #[strong]
+        update_page_gap_sensitivity,
         move |row| {
             canvas
                 .engine_mut()
                 .import_prefs
                 .pdf_import_prefs
                 .adjust_document = row.is_active();
+            update_page_gap_sensitivity();
         }
     ));
 
+    let pdf_n_pages = Rc::new(Cell::new(0));
+    let mut color_remap_preview = None;
     if let Ok(poppler_doc) =
         poppler::Document::from_gfile(&input_file, password.as_deref(), None::<&gio::Cancellable>)
     {
@@ -416,14 +696,55 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
                 &gettext("Pages:"))
         );
 
-        // Configure pages spinners
-        pdf_page_start_row.set_range(1.into(), n_pages.into());
-        pdf_page_start_row.set_value(1.into());
-
-        pdf_page_end_row.set_range(1.into(), n_pages.into());
-        pdf_page_end_row.set_value(n_pages.into());
+        // Configure the page selection
+        let n_pages = n_pages.max(0) as u32;
+        pdf_pages_row.set_text(&format!("1-{n_pages}"));
+        pdf_pages_row.connect_changed(clone!(
+            #[weak]
+            import_pdf_button_confirm,
+            move |row| {
+                let valid = parse_pdf_page_selection(&row.text(), n_pages)
+                    .is_ok_and(|pages| !pages.is_empty());
+                if valid {
+                    row.remove_css_class("error");
+                } else {
+                    row.add_css_class("error");
+                }
+                import_pdf_button_confirm.set_sensitive(valid);
+            }
+        ));
+        pdf_n_pages.set(n_pages);
+
+        color_remap_preview =
+            poppler_doc
+                .page(0)
+                .and_then(|page| match pdf_page_preview_surface(&page) {
+                    Ok(surface) => Some(surface),
+                    Err(e) => {
+                        error!("Rendering Pdf page preview failed, Err: {e:?}");
+                        None
+                    }
+                });
     }
 
+    setup_color_remap_widgets(
+        &builder,
+        "pdf",
+        pdf_import_prefs.color_remap,
+        color_remap_preview,
+        clone!(
+            #[weak]
+            canvas,
+            move |color_remap| {
+                canvas
+                    .engine_mut()
+                    .import_prefs
+                    .pdf_import_prefs
+                    .color_remap = color_remap;
+            }
+        ),
+    );
+
     // Listen to responses
 
     let (tx, mut rx) = futures::channel::mpsc::unbounded::<anyhow::Result<bool>>();
@@ -444,14 +765,21 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
         }
     ));
 
-    import_pdf_button_confirm.connect_clicked(clone!(#[weak] pdf_page_start_row, #[weak] pdf_page_end_row, #[weak] input_file, #[weak] dialog, #[weak] canvas, #[strong] password, move |_| {
+    import_pdf_button_confirm.connect_clicked(clone!(#[weak] pdf_pages_row, #[strong] pdf_n_pages, #[weak] input_file, #[weak] dialog, #[weak] appwindow, #[weak] canvas, #[strong] password, move |_| {
         dialog.close();
 
         let inner_tx_confirm = tx_confirm.clone();
 
-        glib::spawn_future_local(clone!(#[weak] pdf_page_start_row, #[weak] pdf_page_end_row, #[weak] input_file, #[weak] canvas, #[strong] password , async move {
-            let page_range =
-                (pdf_page_start_row.value() as u32 - 1)..pdf_page_end_row.value() as u32;
+        glib::spawn_future_local(clone!(#[weak] pdf_pages_row, #[strong] pdf_n_pages, #[weak] input_file, #[weak] appwindow, #[weak] canvas, #[strong] password , async move {
+            let pages = match parse_pdf_page_selection(&pdf_pages_row.text(), pdf_n_pages.get()) {
+                Ok(pages) => pages,
+                Err(err) => {
+                    if let Err(e) = inner_tx_confirm.unbounded_send(Err(err)) {
+                        error!("Invalid page selection, but failed to send signal through channel. Err: {e:?}");
+                    }
+                    return;
+                }
+            };
 
             let (bytes, _) = match input_file.load_bytes_future().await {
                 Ok(res) => {res}
@@ -462,7 +790,7 @@ pub(crate) async fn dialog_import_pdf_w_prefs(
                     return;
                 }
             };
-            if let Err(e) = canvas.load_in_pdf_bytes(bytes.to_vec(), target_pos, Some(page_range), password).await {
+            if let Err(e) = canvas.load_in_pdf_bytes(&appwindow, bytes.to_vec(), target_pos, Some(pages), password).await {
                 if let Err(e) = inner_tx_confirm.unbounded_send(Err(e)) {
                     error!("Failed to load PDF, but failed to send signal through channel. Err: {e:?}");
                 }
@@ -533,6 +861,31 @@ pub(crate) async fn dialog_import_xopp_w_prefs(
         }
     ));
 
+    let color_remap_preview = match xopp_sample_preview_surface() {
+        Ok(surface) => Some(surface),
+        Err(e) => {
+            error!("Rendering Xopp sample preview failed, Err: {e:?}");
+            None
+        }
+    };
+    setup_color_remap_widgets(
+        &builder,
+        "xopp",
+        xopp_import_prefs.color_remap,
+        color_remap_preview,
+        clone!(
+            #[weak]
+            canvas,
+            move |color_remap| {
+                canvas
+                    .engine_mut()
+                    .import_prefs
+                    .xopp_import_prefs
+                    .color_remap = color_remap;
+            }
+        ),
+    );
+
     // Listen to responses
 
     let (tx, mut rx) = futures::channel::mpsc::unbounded::<anyhow::Result<bool>>();
@@ -568,7 +921,7 @@ pub(crate) async fn dialog_import_xopp_w_prefs(
                     return;
                 }
             };
-            if let Err(e) = canvas.load_in_xopp_bytes(bytes.to_vec()).await {
+            if let Err(e) = canvas.load_in_xopp_bytes(bytes.to_vec(), input_file.path()).await {
                 if let Err(e) = inner_tx_confirm.unbounded_send(Err(e)) {
                     error!("Failed to load XOPP, but failed to send signal through channel. Err: {e:?}");
                 }
@@ -609,3 +962,161 @@ pub(crate) async fn dialog_import_xopp_w_prefs(
         )),
     }
 }
+
+/// The pixel width of the color remap previews.
+const COLOR_REMAP_PREVIEW_WIDTH: i32 = 320;
+
+/// Sets up the color remap widgets of an import dialog, which have ids starting with the prefix.
+///
+/// The preview shows the preview surface with the current remap preferences applied.
+fn setup_color_remap_widgets(
+    builder: &Builder,
+    id_prefix: &str,
+    color_remap: ColorRemapPrefs,
+    preview_surface: Option<cairo::ImageSurface>,
+    update_prefs: impl Fn(ColorRemapPrefs) + 'static,
+) {
+    let row: adw::ExpanderRow = builder
+        .object(&format!("{id_prefix}_import_color_remap_row"))
+        .unwrap();
+    let dark_target_button: ColorDialogButton = builder
+        .object(&format!(
+            "{id_prefix}_import_color_remap_dark_target_button"
+        ))
+        .unwrap();
+    let light_target_button: ColorDialogButton = builder
+        .object(&format!(
+            "{id_prefix}_import_color_remap_light_target_button"
+        ))
+        .unwrap();
+    let preview: Picture = builder
+        .object(&format!("{id_prefix}_import_color_remap_preview"))
+        .unwrap();
+
+    row.set_enable_expansion(color_remap.enabled);
+    dark_target_button.set_rgba(&gdk::RGBA::from_compose_color(color_remap.dark_target));
+    light_target_button.set_rgba(&gdk::RGBA::from_compose_color(color_remap.light_target));
+    preview.set_visible(preview_surface.is_some());
+
+    let update = Rc::new(clone!(
+        #[weak]
+        row,
+        #[weak]
+        dark_target_button,
+        #[weak]
+        light_target_button,
+        #[weak]
+        preview,
+        move || {
+            let color_remap = ColorRemapPrefs {
+                enabled: row.enables_expansion(),
+                dark_target: dark_target_button.rgba().into_compose_color(),
+                light_target: light_target_button.rgba().into_compose_color(),
+            };
+            update_prefs(color_remap);
+
+            if let Some(preview_surface) = &preview_surface {
+                match color_remap_preview_texture(preview_surface, color_remap) {
+                    Ok(texture) => preview.set_paintable(Some(&texture)),
+                    Err(e) => error!("Generating color remap preview failed, Err: {e:?}"),
+                }
+            }
+        }
+    ));
+    update();
+
+    row.connect_enable_expansion_notify(clone!(
+        #[strong]
+        update,
+        move |_| update()
+    ));
+    dark_target_button.connect_rgba_notify(clone!(
+        #[strong]
+        update,
+        move |_| update()
+    ));
+    light_target_button.connect_rgba_notify(clone!(
+        #[strong]
+        update,
+        move |_| update()
+    ));
+}
+
+/// Copies the preview surface and remaps its colors, if enabled.
+fn color_remap_preview_texture(
+    preview_surface: &cairo::ImageSurface,
+    color_remap: ColorRemapPrefs,
+) -> anyhow::Result<gdk::MemoryTexture> {
+    let (width, height) = (preview_surface.width(), preview_surface.height());
+    let mut surface = cairo::ImageSurface::create(cairo::Format::ARgb32, width, height)?;
+    {
+        let cx = cairo::Context::new(&surface)?;
+        cx.set_source_surface(preview_surface, 0.0, 0.0)?;
+        cx.paint()?;
+    }
+    if color_remap.enabled {
+        color_remap.remap_image_surface(&mut surface)?;
+    }
+    let bounds = Aabb::new(
+        na::point![0.0, 0.0],
+        na::point![f64::from(width), f64::from(height)],
+    );
+    render::Image::try_from_cairo_surface(surface, bounds)?.to_memtexture()
+}
+
+/// Renders the Pdf page scaled down to the preview width, on a white background like when it is imported.
+fn pdf_page_preview_surface(page: &poppler::Page) -> anyhow::Result<cairo::ImageSurface> {
+    let (page_width, page_height) = page.size();
+    let scale = f64::from(COLOR_REMAP_PREVIEW_WIDTH) / page_width;
+    let surface = cairo::ImageSurface::create(
+        cairo::Format::ARgb32,
+        COLOR_REMAP_PREVIEW_WIDTH,
+        (page_height * scale).ceil() as i32,
+    )?;
+    {
+        let cx = cairo::Context::new(&surface)?;
+        cx.scale(scale, scale);
+        cx.set_source_rgba(1.0, 1.0, 1.0, 1.0);
+        cx.paint()?;
+        page.render(&cx);
+    }
+    Ok(surface)
+}
+
+/// Renders sample strokes on a white background, resembling a typical Xournal++ page.
+fn xopp_sample_preview_surface() -> anyhow::Result<cairo::ImageSurface> {
+    let height = COLOR_REMAP_PREVIEW_WIDTH / 2;
+    let surface =
+        cairo::ImageSurface::create(cairo::Format::ARgb32, COLOR_REMAP_PREVIEW_WIDTH, height)?;
+    {
+        let cx = cairo::Context::new(&surface)?;
+        cx.set_source_rgba(1.0, 1.0, 1.0, 1.0);
+        cx.paint()?;
+
+        let stroke_colors = [
+            (0.0, 0.0, 0.0, 1.0),
+            (0.4, 0.4, 0.4, 1.0),
+            color::GNOME_REDS[3].as_rgba(),
+            color::GNOME_BLUES[3].as_rgba(),
+        ];
+        let width = f64::from(COLOR_REMAP_PREVIEW_WIDTH);
+        let spacing = f64::from(height) / (stroke_colors.len() + 1) as f64;
+        cx.set_line_width(4.0);
+        cx.set_line_cap(cairo::LineCap::Round);
+        for (i, (r, g, b, _)) in stroke_colors.into_iter().enumerate() {
+            let y = spacing * (i + 1) as f64;
+            cx.set_source_rgb(r, g, b);
+            cx.move_to(width * 0.1, y);
+            cx.curve_to(
+                width * 0.35,
+                y - spacing * 0.5,
+                width * 0.65,
+                y + spacing * 0.5,
+                width * 0.9,
+                y,
+            );
+            cx.stroke()?;
+        }
+    }
+    Ok(surface)
+}
@@ -3,18 +3,25 @@ pub(crate) mod export;
 pub(crate) mod import;
 
 // Imports
-use crate::appwindow::RnAppWindow;
-use crate::canvas::RnCanvas;
+use crate::appwindow::{qr_code_texture, RnAppWindow};
+use crate::canvas::{LockOwner, RnCanvas};
 use crate::canvaswrapper::RnCanvasWrapper;
+use crate::colorpicker::RnColorSetter;
 use crate::config;
 use crate::workspacebrowser::workspacesbar::RnWorkspaceRow;
 use crate::{globals, RnIconPicker};
 use adw::prelude::*;
 use gettextrs::{gettext, pgettext};
 use gtk4::{
-    gio, glib, glib::clone, Builder, Button, CheckButton, ColorDialogButton, FileDialog, Label,
-    MenuButton, ShortcutsWindow, StringList,
+    gdk, gio, glib, glib::clone, Builder, Button, CheckButton, ColorDialogButton, FileDialog,
+    FlowBox, Label, MenuButton, Picture, ShortcutsWindow, StringList,
 };
+use rnote_engine::engine::tablegen::TableGenerator;
+use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::{Engine, WidgetFlags};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use tracing::{debug, error, warn};
 
 // About Dialog
@@ -111,7 +118,7 @@ pub(crate) async fn dialog_new_doc(appwindow: &RnAppWindow, canvas: &RnCanvas) {
                 #[weak]
                 appwindow,
                 async move {
-                    if let Some(output_file) = canvas.output_file() {
+                    if let Some(output_file) = canvas.writable_output_file() {
                         appwindow.overlays().progressbar_start_pulsing();
 
                         if let Err(e) = canvas.save_document_to_file(&output_file).await {
@@ -161,7 +168,7 @@ pub(crate) async fn dialog_close_tab(appwindow: &RnAppWindow, tab_page: &adw::Ta
         .downcast::<RnCanvasWrapper>()
         .unwrap()
         .canvas();
-    let canvas_output_file = canvas.output_file();
+    let canvas_output_file = canvas.writable_output_file();
 
     let mut save_file = canvas_output_file.clone();
     let save_folder_path = if let Some(p) = canvas
@@ -281,7 +288,7 @@ pub(crate) async fn dialog_close_window(appwindow: &RnAppWindow) {
     let mut doc_postfix = 0;
     for (i, tab) in tabs.iter().enumerate() {
         let canvas = tab.child().downcast::<RnCanvasWrapper>().unwrap().canvas();
-        let canvas_output_file = canvas.output_file();
+        let canvas_output_file = canvas.writable_output_file();
 
         if !canvas.unsaved_changes() {
             continue;
@@ -586,6 +593,641 @@ pub(crate) async fn dialog_edit_selected_workspace(appwindow: &RnAppWindow) {
     dialog.present(appwindow.root().as_ref());
 }
 
+pub(crate) async fn dialog_remap_selection_colors(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_remap_selection_colors").unwrap();
+    let colors_flowbox: FlowBox = builder.object("remap_selection_colors_flowbox").unwrap();
+    let from_button: ColorDialogButton = builder
+        .object("remap_selection_colors_from_button")
+        .unwrap();
+    let to_button: ColorDialogButton = builder.object("remap_selection_colors_to_button").unwrap();
+    let button_cancel: Button = builder
+        .object("remap_selection_colors_button_cancel")
+        .unwrap();
+    let button_apply: Button = builder
+        .object("remap_selection_colors_button_apply")
+        .unwrap();
+
+    let selection_colors = canvas.engine_ref().selection_colors();
+    if selection_colors.is_empty() {
+        appwindow.overlays().dispatch_toast_text(
+            &gettext("No colors in the selection"),
+            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+        );
+        return;
+    }
+
+    for color in selection_colors.iter() {
+        let setter = RnColorSetter::new();
+        setter.set_width_request(24);
+        setter.set_height_request(24);
+        setter.set_color(gdk::RGBA::from_compose_color(*color));
+        setter.connect_clicked(clone!(
+            #[weak]
+            from_button,
+            move |setter| {
+                setter.set_active(false);
+                from_button.set_rgba(&setter.color());
+            }
+        ));
+        colors_flowbox.append(&setter);
+    }
+    from_button.set_rgba(&gdk::RGBA::from_compose_color(selection_colors[0]));
+    to_button.set_rgba(&gdk::RGBA::from_compose_color(selection_colors[0]));
+
+    button_cancel.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| {
+            dialog.close();
+        }
+    ));
+
+    button_apply.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        #[weak]
+        from_button,
+        #[weak]
+        to_button,
+        move |_| {
+            dialog.close();
+
+            let widget_flags = canvas.engine_mut().remap_selection_colors(
+                from_button.rgba().into_compose_color(),
+                to_button.rgba().into_compose_color(),
+            );
+            appwindow.handle_widget_flags(widget_flags, &canvas);
+        }
+    ));
+
+    dialog.present(appwindow.root().as_ref());
+}
+
+pub(crate) async fn dialog_scale_doc_content(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_scale_doc_content").unwrap();
+    let factor_row: adw::SpinRow = builder.object("scale_doc_content_factor_row").unwrap();
+    let stroke_widths_row: adw::SwitchRow = builder
+        .object("scale_doc_content_stroke_widths_row")
+        .unwrap();
+    let format_row: adw::SwitchRow = builder.object("scale_doc_content_format_row").unwrap();
+    let button_cancel: Button = builder.object("scale_doc_content_button_cancel").unwrap();
+    let button_apply: Button = builder.object("scale_doc_content_button_apply").unwrap();
+
+    button_cancel.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| {
+            dialog.close();
+        }
+    ));
+
+    button_apply.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        #[weak]
+        factor_row,
+        #[weak]
+        stroke_widths_row,
+        #[weak]
+        format_row,
+        move |_| {
+            dialog.close();
+
+            let res = canvas.engine_mut().scale_doc_content(
+                factor_row.value() / 100.0,
+                stroke_widths_row.is_active(),
+                format_row.is_active(),
+            );
+            match res {
+                Ok(widget_flags) => appwindow.handle_widget_flags(widget_flags, &canvas),
+                Err(e) => {
+                    error!("Scaling document content failed, Err: {e:?}");
+                    appwindow
+                        .overlays()
+                        .dispatch_toast_error(&gettext("Scaling document content failed"));
+                }
+            }
+        }
+    ));
+
+    dialog.present(appwindow.root().as_ref());
+}
+
+pub(crate) async fn dialog_insert_table(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_insert_table").unwrap();
+    let rows_row: adw::SpinRow = builder.object("insert_table_rows_row").unwrap();
+    let columns_row: adw::SpinRow = builder.object("insert_table_columns_row").unwrap();
+    let cell_width_row: adw::SpinRow = builder.object("insert_table_cell_width_row").unwrap();
+    let cell_height_row: adw::SpinRow = builder.object("insert_table_cell_height_row").unwrap();
+    let line_width_row: adw::SpinRow = builder.object("insert_table_line_width_row").unwrap();
+    let line_color_button: ColorDialogButton =
+        builder.object("insert_table_line_color_button").unwrap();
+    let button_cancel: Button = builder.object("insert_table_button_cancel").unwrap();
+    let button_insert: Button = builder.object("insert_table_button_insert").unwrap();
+
+    line_color_button.set_rgba(&gdk::RGBA::from_compose_color(
+        TableGenerator::default().line_color,
+    ));
+
+    button_cancel.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| {
+            dialog.close();
+        }
+    ));
+
+    button_insert.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        #[weak]
+        rows_row,
+        #[weak]
+        columns_row,
+        #[weak]
+        cell_width_row,
+        #[weak]
+        cell_height_row,
+        #[weak]
+        line_width_row,
+        #[weak]
+        line_color_button,
+        move |_| {
+            dialog.close();
+
+            let table = TableGenerator {
+                rows: rows_row.value().round() as u32,
+                columns: columns_row.value().round() as u32,
+                cell_width: cell_width_row.value(),
+                cell_height: cell_height_row.value(),
+                line_width: line_width_row.value(),
+                line_color: line_color_button.rgba().into_compose_color(),
+            };
+            let widget_flags = canvas.engine_mut().insert_table(table);
+            appwindow.handle_widget_flags(widget_flags, &canvas);
+        }
+    ));
+
+    dialog.present(appwindow.root().as_ref());
+}
+
+/// Jump to the top of the page entered by the user.
+pub(crate) fn dialog_go_to_page(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_go_to_page").unwrap();
+    let page_row: adw::SpinRow = builder.object("go_to_page_row").unwrap();
+    let button_cancel: Button = builder.object("go_to_page_button_cancel").unwrap();
+    let button_go: Button = builder.object("go_to_page_button_go").unwrap();
+
+    let (n_pages, current_page) = {
+        let engine = canvas.engine_ref();
+        (engine.pages_bounds().len(), engine.current_page())
+    };
+    if n_pages == 0 {
+        return;
+    }
+    page_row.set_range(1.0, n_pages as f64);
+    page_row.set_value((current_page + 1) as f64);
+
+    button_cancel.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| {
+            dialog.close();
+        }
+    ));
+
+    button_go.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        #[weak]
+        page_row,
+        move |_| {
+            dialog.close();
+
+            let index = (page_row.value().round() as usize).saturating_sub(1);
+            let widget_flags = canvas.engine_mut().go_to_page(index);
+            appwindow.handle_widget_flags(widget_flags, &canvas);
+        }
+    ));
+
+    dialog.present(appwindow.root().as_ref());
+}
+
+/// Edit the metadata of the document.
+pub(crate) fn dialog_doc_properties(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_doc_properties").unwrap();
+    let title_row: adw::EntryRow = builder.object("doc_properties_title_row").unwrap();
+    let author_row: adw::EntryRow = builder.object("doc_properties_author_row").unwrap();
+    let subject_row: adw::EntryRow = builder.object("doc_properties_subject_row").unwrap();
+    let tags_row: adw::EntryRow = builder.object("doc_properties_tags_row").unwrap();
+    let created_row: adw::ActionRow = builder.object("doc_properties_created_row").unwrap();
+    let modified_row: adw::ActionRow = builder.object("doc_properties_modified_row").unwrap();
+    let button_cancel: Button = builder.object("doc_properties_button_cancel").unwrap();
+    let button_apply: Button = builder.object("doc_properties_button_apply").unwrap();
+
+    let metadata = canvas.engine_ref().metadata.clone();
+    let format_timestamp = |timestamp: Option<i64>| {
+        timestamp
+            .and_then(|t| glib::DateTime::from_unix_local(t).ok())
+            .and_then(|t| t.format("%x %X").ok())
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| gettext("Not saved yet"))
+    };
+    title_row.set_text(&metadata.title);
+    author_row.set_text(&metadata.author);
+    subject_row.set_text(&metadata.subject);
+    tags_row.set_text(&metadata.tags_joined());
+    created_row.set_subtitle(&format_timestamp(metadata.created));
+    modified_row.set_subtitle(&format_timestamp(metadata.modified));
+
+    button_cancel.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        move |_| {
+            dialog.close();
+        }
+    ));
+
+    button_apply.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        #[weak]
+        title_row,
+        #[weak]
+        author_row,
+        #[weak]
+        subject_row,
+        #[weak]
+        tags_row,
+        move |_| {
+            dialog.close();
+
+            let mut new_metadata = metadata.clone();
+            new_metadata.title = title_row.text().trim().to_string();
+            new_metadata.author = author_row.text().trim().to_string();
+            new_metadata.subject = subject_row.text().trim().to_string();
+            new_metadata.set_tags_from_str(&tags_row.text());
+            if new_metadata == canvas.engine_ref().metadata {
+                return;
+            }
+            canvas.engine_mut().metadata = new_metadata;
+            let mut widget_flags = WidgetFlags::default();
+            widget_flags.store_modified = true;
+            appwindow.handle_widget_flags(widget_flags, &canvas);
+        }
+    ));
+
+    dialog.present(appwindow.root().as_ref());
+}
+
+/// Show the link and QR code of the document that is served in the local network.
+pub(crate) fn dialog_serve_doc(appwindow: &RnAppWindow, url: &str) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_serve_doc").unwrap();
+    let qr_code_picture: Picture = builder.object("serve_doc_qr_code_picture").unwrap();
+    let url_label: Label = builder.object("serve_doc_url_label").unwrap();
+    let button_copy_link: Button = builder.object("serve_doc_button_copy_link").unwrap();
+
+    url_label.set_label(url);
+    match qr_code_texture(url) {
+        Ok(texture) => qr_code_picture.set_paintable(Some(&texture)),
+        Err(e) => error!("Generating QR code for document link failed, Err: {e:?}"),
+    }
+
+    let url = url.to_string();
+    button_copy_link.connect_clicked(clone!(
+        #[weak]
+        appwindow,
+        move |_| {
+            appwindow.clipboard().set_text(&url);
+            appwindow.overlays().dispatch_toast_text(
+                &gettext("Copied link to clipboard"),
+                crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+            );
+        }
+    ));
+
+    dialog.present(appwindow.root().as_ref());
+}
+
+pub(crate) async fn dialog_recently_deleted(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    const THUMBNAIL_SIZE: i32 = 48;
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_recently_deleted").unwrap();
+    let group: adw::PreferencesGroup = builder.object("recently_deleted_group").unwrap();
+    let button_restore_all: Button = builder
+        .object("recently_deleted_button_restore_all")
+        .unwrap();
+
+    let trashed_strokes = canvas.engine_ref().trashed_strokes();
+    if trashed_strokes.is_empty() {
+        appwindow.overlays().dispatch_toast_text(
+            &gettext("No recently deleted strokes"),
+            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+        );
+        return;
+    }
+    let remaining_keys = Rc::new(RefCell::new(
+        trashed_strokes.iter().map(|t| t.key).collect::<Vec<_>>(),
+    ));
+    if trashed_strokes.len() >= Engine::TRASHED_STROKES_MAX {
+        group.set_description(Some(&gettext(
+            "Only the most recently deleted strokes are listed",
+        )));
+    }
+    let mut pictures = HashMap::with_capacity(trashed_strokes.len());
+
+    for trashed_stroke in trashed_strokes {
+        let key = trashed_stroke.key;
+        let title = trashed_stroke
+            .trashed_at
+            .map(|t| t.format("%H:%M:%S").to_string())
+            .unwrap_or_else(|| gettext("Unknown time"));
+        let row = adw::ActionRow::builder()
+            .title(format!("{} {}", gettext("Deleted at"), title))
+            .build();
+
+        let picture = Picture::builder()
+            .width_request(THUMBNAIL_SIZE)
+            .height_request(THUMBNAIL_SIZE)
+            .content_fit(gtk4::ContentFit::Contain)
+            .margin_top(6)
+            .margin_bottom(6)
+            .build();
+        row.add_prefix(&picture);
+        pictures.insert(key, picture);
+
+        let restore_button = Button::builder()
+            .icon_name("edit-undo-symbolic")
+            .tooltip_text(gettext("Restore"))
+            .valign(gtk4::Align::Center)
+            .build();
+        restore_button.add_css_class("flat");
+        restore_button.connect_clicked(clone!(
+            #[weak]
+            dialog,
+            #[weak]
+            appwindow,
+            #[weak]
+            canvas,
+            #[weak]
+            group,
+            #[weak]
+            row,
+            #[strong]
+            remaining_keys,
+            move |_| {
+                let widget_flags = canvas.engine_mut().restore_trashed_strokes(&[key]);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+                group.remove(&row);
+                remaining_keys.borrow_mut().retain(|&k| k != key);
+                if remaining_keys.borrow().is_empty() {
+                    dialog.close();
+                }
+            }
+        ));
+        row.add_suffix(&restore_button);
+        group.add(&row);
+    }
+
+    button_restore_all.connect_clicked(clone!(
+        #[weak]
+        dialog,
+        #[weak]
+        appwindow,
+        #[weak]
+        canvas,
+        #[strong]
+        remaining_keys,
+        move |_| {
+            dialog.close();
+
+            let keys = remaining_keys.take();
+            let widget_flags = canvas.engine_mut().restore_trashed_strokes(&keys);
+            appwindow.handle_widget_flags(widget_flags, &canvas);
+        }
+    ));
+
+    dialog.present(appwindow.root().as_ref());
+
+    // The thumbnails are generated in the background and filled in once they are ready
+    let thumbnails_receiver = canvas.engine_ref().gen_trashed_strokes_thumbnails(
+        &pictures.keys().copied().collect::<Vec<_>>(),
+        THUMBNAIL_SIZE as f64 * f64::from(appwindow.scale_factor()),
+    );
+    let thumbnails = match thumbnails_receiver.await {
+        Ok(thumbnails) => thumbnails,
+        Err(e) => {
+            error!("Awaiting trashed strokes thumbnails failed, Err: {e:?}");
+            return;
+        }
+    };
+    for (key, thumbnail) in thumbnails {
+        let Some(picture) = pictures.get(&key) else {
+            continue;
+        };
+        match thumbnail.to_memtexture() {
+            Ok(texture) => picture.set_paintable(Some(&texture)),
+            Err(e) => {
+                error!("Converting trashed stroke thumbnail to texture failed, Err: {e:?}")
+            }
+        }
+    }
+}
+
+pub(crate) async fn dialog_revision_history(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    if canvas.output_file().is_none() {
+        appwindow.overlays().dispatch_toast_text(
+            &gettext("The document has not been saved yet"),
+            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+        );
+        return;
+    }
+    let revisions_saved_at = match canvas.output_file_revisions_saved_at().await {
+        Ok(revisions_saved_at) => revisions_saved_at,
+        Err(e) => {
+            error!("Loading the revision history of the document file failed, Err: {e:?}");
+            appwindow
+                .overlays()
+                .dispatch_toast_error(&gettext("Loading the revision history failed"));
+            return;
+        }
+    };
+    if revisions_saved_at.is_empty() {
+        appwindow.overlays().dispatch_toast_text(
+            &gettext("The document file has no stored revisions"),
+            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+        );
+        return;
+    }
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::Dialog = builder.object("dialog_revision_history").unwrap();
+    let group: adw::PreferencesGroup = builder.object("revision_history_group").unwrap();
+
+    for (index, saved_at) in revisions_saved_at.into_iter().enumerate() {
+        let title = saved_at
+            .and_then(|t| glib::DateTime::from_unix_local(t).ok())
+            .and_then(|t| t.format("%x %X").ok())
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| gettext("Unknown time"));
+        let row = adw::ActionRow::builder()
+            .title(format!("{} {}", gettext("Saved at"), title))
+            .build();
+
+        let restore_button = Button::builder()
+            .icon_name("edit-undo-symbolic")
+            .tooltip_text(gettext("Restore"))
+            .valign(gtk4::Align::Center)
+            .build();
+        restore_button.add_css_class("flat");
+        restore_button.connect_clicked(clone!(
+            #[weak]
+            dialog,
+            #[weak]
+            appwindow,
+            #[weak]
+            canvas,
+            move |_| {
+                dialog.close();
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    #[weak]
+                    canvas,
+                    async move {
+                        match canvas.restore_output_file_revision(index).await {
+                            Ok(widget_flags) => {
+                                appwindow.handle_widget_flags(widget_flags, &canvas);
+                                appwindow.overlays().dispatch_toast_text(
+                                    &gettext("Restored revision, save the document to keep it"),
+                                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                                );
+                            }
+                            Err(e) => {
+                                error!(
+                                    "Restoring revision of the document file failed, Err: {e:?}"
+                                );
+                                appwindow
+                                    .overlays()
+                                    .dispatch_toast_error(&gettext("Restoring revision failed"));
+                            }
+                        }
+                    }
+                ));
+            }
+        ));
+        row.add_suffix(&restore_button);
+        group.add(&row);
+    }
+
+    dialog.present(appwindow.root().as_ref());
+}
+
+pub(crate) async fn dialog_add_pen_preset(appwindow: &RnAppWindow, canvas: &RnCanvas) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_add_pen_preset").unwrap();
+    let name_row: adw::EntryRow = builder.object("pen_preset_name_row").unwrap();
+
+    match dialog.choose_future(appwindow).await.as_str() {
+        "add" => {
+            let n_presets = canvas.engine_ref().pens_config.presets.len();
+            let name = match name_row.text().trim() {
+                "" => format!("{} {}", gettext("Preset"), n_presets + 1),
+                name => name.to_string(),
+            };
+            if !canvas.engine_mut().add_pen_preset(name) {
+                appwindow.overlays().dispatch_toast_text(
+                    &gettext("The current pen can't be pinned as a preset"),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+                return;
+            }
+            let mut widget_flags = WidgetFlags::default();
+            widget_flags.refresh_ui = true;
+            appwindow.handle_widget_flags(widget_flags, canvas);
+        }
+        _ => {
+            // Cancel
+        }
+    }
+}
+
+pub(crate) async fn dialog_remove_pen_preset(
+    appwindow: &RnAppWindow,
+    canvas: &RnCanvas,
+    index: usize,
+) {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_remove_pen_preset").unwrap();
+    let Some(preset_name) = canvas
+        .engine_ref()
+        .pens_config
+        .presets
+        .get(index)
+        .map(|preset| preset.name.clone())
+    else {
+        return;
+    };
+    dialog.set_body(&preset_name);
+
+    match dialog.choose_future(appwindow).await.as_str() {
+        "remove" => {
+            canvas.engine_mut().remove_pen_preset(index);
+            let mut widget_flags = WidgetFlags::default();
+            widget_flags.refresh_ui = true;
+            appwindow.handle_widget_flags(widget_flags, canvas);
+        }
+        _ => {
+            // Cancel
+        }
+    }
+}
+
 pub(crate) async fn dialog_trash_file(appwindow: &RnAppWindow, current_file: &gio::File) {
     let builder = Builder::from_resource(
         (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
@@ -630,6 +1272,36 @@ pub(crate) async fn dialog_trash_file(appwindow: &RnAppWindow, current_file: &gi
     }
 }
 
+/// Asks how to open a document whose file is locked by another instance.
+///
+/// Returns None when opening is canceled, else whether the document should be opened read-only.
+pub(crate) async fn dialog_file_locked(
+    appwindow: &RnAppWindow,
+    input_file: &gio::File,
+    owner: &LockOwner,
+) -> Option<bool> {
+    let builder = Builder::from_resource(
+        (String::from(config::APP_IDPATH) + "ui/dialogs/dialogs.ui").as_str(),
+    );
+    let dialog: adw::AlertDialog = builder.object("dialog_file_locked").unwrap();
+    let file_name = input_file.basename().map_or_else(
+        || gettext("- no file name -"),
+        |s| s.to_string_lossy().to_string(),
+    );
+    dialog.set_body(&format!(
+        "{file_name}\n\n{}\n\n{}@{}",
+        dialog.body(),
+        owner.user,
+        owner.host
+    ));
+
+    match dialog.choose_future(appwindow).await.as_str() {
+        "read-only" => Some(true),
+        "open-anyway" => Some(false),
+        _ => None,
+    }
+}
+
 const WORKSPACELISTENTRY_ICONS_LIST: &[&str] = &[
     "workspacelistentryicon-bandaid-symbolic",
     "workspacelistentryicon-bank-symbolic",
@@ -43,6 +43,13 @@ pub(crate) fn pkg_data_dir() -> anyhow::Result<PathBuf> {
     Ok(data_dir()?.join(config::APP_NAME))
 }
 
+/// The directory containing user installed pen sound themes, each in its own subdirectory.
+pub(crate) fn sound_themes_dir() -> PathBuf {
+    gtk4::glib::user_data_dir()
+        .join(config::APP_NAME)
+        .join("sound-themes")
+}
+
 pub(crate) fn locale_dir() -> anyhow::Result<PathBuf> {
     if cfg!(target_os = "windows") {
         let exec_dir = exec_parent_dir()?;
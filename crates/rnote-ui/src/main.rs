@@ -24,13 +24,17 @@ pub(crate) mod groupediconpicker;
 pub(crate) mod iconpicker;
 pub(crate) mod mainheader;
 pub(crate) mod overlays;
+pub(crate) mod pagethumbnails;
 pub(crate) mod penpicker;
 pub(crate) mod penssidebar;
+pub(crate) mod ruler;
 pub(crate) mod settingspanel;
 pub(crate) mod sidebar;
 pub(crate) mod strokecontentpaintable;
 pub(crate) mod strokecontentpreview;
 pub(crate) mod strokewidthpicker;
+pub(crate) mod textsearchbar;
+pub(crate) mod toolpalette;
 pub(crate) mod unitentry;
 pub(crate) mod utils;
 pub(crate) mod workspacebrowser;
@@ -49,13 +53,17 @@ pub(crate) use groupediconpicker::RnGroupedIconPicker;
 pub(crate) use iconpicker::RnIconPicker;
 pub(crate) use mainheader::RnMainHeader;
 pub(crate) use overlays::RnOverlays;
+pub(crate) use pagethumbnails::RnPageThumbnails;
 pub(crate) use penpicker::RnPenPicker;
 pub(crate) use penssidebar::RnPensSideBar;
+pub(crate) use ruler::RnRuler;
 pub(crate) use settingspanel::RnSettingsPanel;
 pub(crate) use sidebar::RnSidebar;
 pub(crate) use strokecontentpaintable::StrokeContentPaintable;
 pub(crate) use strokecontentpreview::RnStrokeContentPreview;
 pub(crate) use strokewidthpicker::RnStrokeWidthPicker;
+pub(crate) use textsearchbar::RnTextSearchBar;
+pub(crate) use toolpalette::RnToolPalette;
 pub(crate) use unitentry::RnUnitEntry;
 pub(crate) use workspacebrowser::RnWorkspaceBrowser;
 
@@ -1,12 +1,13 @@
 // Imports
 use crate::canvaswrapper::RnCanvasWrapper;
 use crate::RnPensSideBar;
-use crate::{dialogs, RnAppWindow, RnColorPicker, RnPenPicker};
+use crate::{dialogs, RnAppWindow, RnColorPicker, RnPenPicker, RnTextSearchBar, RnToolPalette};
 use core::time::Duration;
 use gtk4::{
     gio, glib, glib::clone, prelude::*, subclass::prelude::*, CompositeTemplate, Overlay,
     ProgressBar, ScrolledWindow, Widget,
 };
+use rnote_engine::engine::ImportJobHandle;
 use rnote_engine::ext::GdkRGBAExt;
 use rnote_engine::pens::PenStyle;
 use std::cell::{Cell, RefCell};
@@ -20,6 +21,8 @@ mod imp {
     pub(crate) struct RnOverlays {
         pub(crate) progresspulses_active: Cell<usize>,
         pub(crate) progresspulse_id: RefCell<Option<glib::SourceId>>,
+        pub(crate) progress_import_jobs: RefCell<Vec<ImportJobHandle>>,
+        pub(crate) progress_fraction: Cell<Option<f64>>,
         pub(super) prev_active_tab_page: glib::WeakRef<adw::TabPage>,
 
         #[template_child]
@@ -33,6 +36,10 @@ mod imp {
         #[template_child]
         pub(crate) colorpicker: TemplateChild<RnColorPicker>,
         #[template_child]
+        pub(crate) toolpalette: TemplateChild<RnToolPalette>,
+        #[template_child]
+        pub(crate) textsearchbar: TemplateChild<RnTextSearchBar>,
+        #[template_child]
         pub(crate) tabview: TemplateChild<adw::TabView>,
         #[template_child]
         pub(crate) sidebar_box: TemplateChild<gtk4::Box>,
@@ -112,6 +119,14 @@ impl RnOverlays {
         self.imp().colorpicker.get()
     }
 
+    pub(crate) fn toolpalette(&self) -> RnToolPalette {
+        self.imp().toolpalette.get()
+    }
+
+    pub(crate) fn textsearchbar(&self) -> RnTextSearchBar {
+        self.imp().textsearchbar.get()
+    }
+
     pub(crate) fn toast_overlay(&self) -> adw::ToastOverlay {
         self.imp().toast_overlay.get()
     }
@@ -141,6 +156,8 @@ impl RnOverlays {
         imp.colorpicker.get().init(appwindow);
         imp.penssidebar.get().init(appwindow);
         imp.penpicker.get().init(appwindow);
+        imp.toolpalette.get().init(appwindow);
+        imp.textsearchbar.get().init(appwindow);
         imp.penssidebar.get().brush_page().init(appwindow);
         imp.penssidebar.get().shaper_page().init(appwindow);
         imp.penssidebar.get().typewriter_page().init(appwindow);
@@ -188,6 +205,7 @@ impl RnOverlays {
                         .engine_mut()
                         .pens_config
                         .set_all_stroke_colors(stroke_color);
+                    colorpicker.set_recent_colors(&canvas.engine_ref().pens_config.recent_colors);
                 }
             ),
         );
@@ -222,6 +240,7 @@ impl RnOverlays {
                         .engine_mut()
                         .pens_config
                         .set_all_fill_colors(fill_color);
+                    colorpicker.set_recent_colors(&canvas.engine_ref().pens_config.recent_colors);
                 }
             ),
         );
@@ -244,6 +263,7 @@ impl RnOverlays {
                     .downcast::<RnCanvasWrapper>()
                     .unwrap();
                 appwindow.tabs_set_unselected_inactive();
+                appwindow.stop_serving_doc_unless_of(&active_canvaswrapper.canvas());
 
                 if let Some(prev_active_tab_page) = overlays.imp().prev_active_tab_page.upgrade() {
                     if prev_active_tab_page != active_tab_page {
@@ -276,8 +296,11 @@ impl RnOverlays {
         imp.tabview.connect_page_detached(clone!(
             #[weak(rename_to=overlays)]
             self,
+            #[weak]
+            appwindow,
             move |_, page, _| {
                 let canvaswrapper = page.child().downcast::<RnCanvasWrapper>().unwrap();
+                appwindow.stop_serving_doc_of(&canvaswrapper.canvas());
 
                 // if the to be detached page was the active (selected), remove it.
                 if overlays
@@ -377,7 +400,13 @@ impl RnOverlays {
                         #[upgrade_or]
                         glib::ControlFlow::Break,
                         move || {
-                            appwindow.progressbar().pulse();
+                            match appwindow
+                                .import_jobs_progress()
+                                .or(appwindow.imp().progress_fraction.get())
+                            {
+                                Some(progress) => appwindow.progressbar().set_fraction(progress),
+                                None => appwindow.progressbar().pulse(),
+                            }
 
                             glib::ControlFlow::Continue
                         }
@@ -388,6 +417,32 @@ impl RnOverlays {
         }
     }
 
+    /// Show the progress of the import job in the progressbar instead of pulsing, until it is finished.
+    ///
+    /// Expects that the progressbar is currently pulsing.
+    pub(crate) fn progressbar_track_import_job(&self, job: ImportJobHandle) {
+        self.imp().progress_import_jobs.borrow_mut().push(job);
+    }
+
+    /// Show the given fraction in the progressbar instead of pulsing, until it is finished.
+    ///
+    /// Expects that the progressbar is currently pulsing. Tracked import jobs take precedence.
+    pub(crate) fn progressbar_set_fraction(&self, fraction: f64) {
+        self.imp()
+            .progress_fraction
+            .set(Some(fraction.clamp(0.0, 1.0)));
+    }
+
+    /// The combined progress of the tracked import jobs that are not finished yet.
+    fn import_jobs_progress(&self) -> Option<f64> {
+        let mut jobs = self.imp().progress_import_jobs.borrow_mut();
+        jobs.retain(|job| !job.is_finished());
+        if jobs.is_empty() {
+            return None;
+        }
+        Some(jobs.iter().map(|job| job.progress()).sum::<f64>() / jobs.len() as f64)
+    }
+
     pub(crate) fn progressbar_finish(&self) {
         const FINISH_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(300);
 
@@ -400,6 +455,7 @@ impl RnOverlays {
             if let Some(src) = self.imp().progresspulse_id.take() {
                 src.remove();
             }
+            self.imp().progress_fraction.set(None);
             glib::source::timeout_add_local_once(
                 FINISH_TIMEOUT,
                 clone!(
@@ -423,6 +479,7 @@ impl RnOverlays {
             if let Some(src) = self.imp().progresspulse_id.take() {
                 src.remove();
             }
+            self.imp().progress_fraction.set(None);
             self.progressbar().set_fraction(0.);
         }
     }
@@ -0,0 +1,342 @@
+// Imports
+use crate::RnCanvas;
+use gettextrs::gettext;
+use gtk4::{
+    glib, glib::clone, prelude::*, subclass::prelude::*, Align, BinLayout, Button, ContentFit,
+    Label, Orientation, Picture, PolicyType, ScrolledWindow, Widget,
+};
+use rnote_engine::document::Layout;
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
+use tracing::error;
+
+/// The button of a page in the strip, showing its thumbnail.
+#[derive(Debug, Clone)]
+struct PageThumbnail {
+    button: Button,
+    picture: Picture,
+    /// Whether the thumbnail is outdated and needs to be regenerated.
+    dirty: bool,
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    pub(crate) struct RnPageThumbnails {
+        pub(crate) canvas: glib::WeakRef<RnCanvas>,
+        pub(crate) scroller: ScrolledWindow,
+        pub(crate) pages_box: gtk4::Box,
+        pub(super) pages: RefCell<Vec<PageThumbnail>>,
+        /// The aspect ratio (height / width) of the pages the thumbnails were created for.
+        pub(crate) page_aspect_ratio: Cell<f64>,
+        pub(crate) current_page: Cell<Option<usize>>,
+        pub(crate) regenerate_source: RefCell<Option<glib::SourceId>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnPageThumbnails {
+        const NAME: &'static str = "RnPageThumbnails";
+        type Type = super::RnPageThumbnails;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_css_name("pagethumbnails");
+            klass.set_layout_manager_type::<BinLayout>();
+        }
+    }
+
+    impl ObjectImpl for RnPageThumbnails {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            self.pages_box.set_orientation(Orientation::Horizontal);
+            self.pages_box.set_spacing(6);
+            self.pages_box.set_margin_start(6);
+            self.pages_box.set_margin_end(6);
+            self.pages_box.set_margin_top(6);
+            self.pages_box.set_margin_bottom(6);
+            self.scroller
+                .set_policy(PolicyType::Automatic, PolicyType::Never);
+            self.scroller.set_child(Some(&self.pages_box));
+            self.scroller.set_parent(&*obj);
+
+            // The thumbnails are only generated for the pages that are scrolled into view.
+            let hadj = self.scroller.hadjustment();
+            hadj.connect_value_changed(clone!(
+                #[weak(rename_to=pagethumbnails)]
+                obj,
+                move |_| {
+                    pagethumbnails.queue_regenerate(false);
+                }
+            ));
+            // Emitted when new page buttons were allocated.
+            hadj.connect_changed(clone!(
+                #[weak(rename_to=pagethumbnails)]
+                obj,
+                move |_| {
+                    pagethumbnails.queue_regenerate(false);
+                }
+            ));
+            obj.connect_map(|pagethumbnails| {
+                pagethumbnails.queue_regenerate(false);
+            });
+        }
+
+        fn dispose(&self) {
+            if let Some(source) = self.regenerate_source.take() {
+                source.remove();
+            }
+            self.scroller.unparent();
+        }
+    }
+
+    impl WidgetImpl for RnPageThumbnails {}
+}
+
+glib::wrapper! {
+    pub(crate) struct RnPageThumbnails(ObjectSubclass<imp::RnPageThumbnails>)
+        @extends Widget;
+}
+
+impl Default for RnPageThumbnails {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RnPageThumbnails {
+    /// The width of the thumbnails.
+    const THUMBNAIL_WIDTH: i32 = 80;
+    /// The maximum height of the thumbnails, for very tall page formats.
+    const THUMBNAIL_HEIGHT_MAX: i32 = 160;
+    /// The delay for regenerating the thumbnails after the document was modified,
+    /// so that they are not regenerated for every single stroke while writing.
+    const REGENERATE_DELAY: Duration = Duration::from_millis(800);
+
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub(crate) fn set_canvas(&self, canvas: &RnCanvas) {
+        self.imp().canvas.set(Some(canvas));
+        self.invalidate();
+    }
+
+    /// Mark all thumbnails as outdated. The visible ones are regenerated after a delay.
+    pub(crate) fn invalidate(&self) {
+        self.sync_pages();
+        for page in self.imp().pages.borrow_mut().iter_mut() {
+            page.dirty = true;
+        }
+        self.queue_regenerate(true);
+    }
+
+    /// Update the page buttons when the number of pages or their format has changed.
+    pub(crate) fn sync_pages(&self) {
+        let imp = self.imp();
+        let Some(canvas) = imp.canvas.upgrade() else {
+            return;
+        };
+        let (n_pages, page_aspect_ratio) = {
+            let engine = canvas.engine_ref();
+            // Pages can't be navigated meaningfully in the infinite layout
+            if engine.document.layout == Layout::Infinite {
+                (0, 1.0)
+            } else {
+                (
+                    engine.pages_bounds().len(),
+                    engine.document.format.height() / engine.document.format.width(),
+                )
+            }
+        };
+        self.set_visible(n_pages > 0);
+
+        let same_aspect_ratio = (imp.page_aspect_ratio.get() - page_aspect_ratio).abs() < 1e-6;
+        if !same_aspect_ratio {
+            imp.page_aspect_ratio.set(page_aspect_ratio);
+            for page in imp.pages.borrow().iter() {
+                imp.pages_box.remove(&page.button);
+            }
+            imp.pages.borrow_mut().clear();
+        }
+        let n_current = imp.pages.borrow().len();
+        if n_pages == n_current {
+            return;
+        } else if n_pages < n_current {
+            for page in imp.pages.borrow_mut().drain(n_pages..) {
+                imp.pages_box.remove(&page.button);
+            }
+        } else {
+            for i in n_current..n_pages {
+                let page = self.create_page_thumbnail(i, page_aspect_ratio);
+                imp.pages_box.append(&page.button);
+                imp.pages.borrow_mut().push(page);
+            }
+        }
+        // The highlight needs to be applied to the new buttons
+        imp.current_page.set(None);
+        self.refresh_current_page();
+        self.queue_regenerate(false);
+    }
+
+    /// Highlight the page that is currently viewed on the canvas, and scroll it into view.
+    pub(crate) fn refresh_current_page(&self) {
+        let imp = self.imp();
+        let Some(canvas) = imp.canvas.upgrade() else {
+            return;
+        };
+        let current_page = canvas.engine_ref().current_page();
+        if imp.current_page.replace(Some(current_page)) == Some(current_page) {
+            return;
+        }
+        let pages = imp.pages.borrow();
+        for (i, page) in pages.iter().enumerate() {
+            if i == current_page {
+                page.button.add_css_class("current-page");
+            } else {
+                page.button.remove_css_class("current-page");
+            }
+        }
+        if let Some(bounds) = pages
+            .get(current_page)
+            .and_then(|page| page.button.compute_bounds(&imp.pages_box))
+        {
+            imp.scroller
+                .hadjustment()
+                .clamp_page(bounds.x() as f64, (bounds.x() + bounds.width()) as f64);
+        }
+    }
+
+    fn create_page_thumbnail(&self, index: usize, page_aspect_ratio: f64) -> PageThumbnail {
+        let height = ((Self::THUMBNAIL_WIDTH as f64 * page_aspect_ratio).round() as i32)
+            .clamp(1, Self::THUMBNAIL_HEIGHT_MAX);
+        let picture = Picture::builder()
+            .width_request(Self::THUMBNAIL_WIDTH)
+            .height_request(height)
+            .content_fit(ContentFit::Contain)
+            .can_shrink(true)
+            .build();
+        let label = Label::new(Some(&(index + 1).to_string()));
+        label.add_css_class("caption");
+        let content = gtk4::Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(3)
+            .build();
+        content.append(&picture);
+        content.append(&label);
+        let button = Button::builder()
+            .child(&content)
+            .valign(Align::Start)
+            .tooltip_text(gettext("Go to Page") + " " + &(index + 1).to_string())
+            .build();
+        button.add_css_class("flat");
+        button.add_css_class("page-thumbnail");
+        button.connect_clicked(clone!(
+            #[weak(rename_to=pagethumbnails)]
+            self,
+            move |_| {
+                let Some(canvas) = pagethumbnails.imp().canvas.upgrade() else {
+                    return;
+                };
+                let widget_flags = canvas.engine_mut().go_to_page(index);
+                canvas.emit_handle_widget_flags(widget_flags);
+            }
+        ));
+        PageThumbnail {
+            button,
+            picture,
+            dirty: true,
+        }
+    }
+
+    fn queue_regenerate(&self, delayed: bool) {
+        let delay = if delayed {
+            Self::REGENERATE_DELAY
+        } else {
+            Duration::ZERO
+        };
+        let source = glib::timeout_add_local_once(
+            delay,
+            clone!(
+                #[weak(rename_to=pagethumbnails)]
+                self,
+                move || {
+                    pagethumbnails.imp().regenerate_source.take();
+                    pagethumbnails.regenerate_visible();
+                }
+            ),
+        );
+        if let Some(old) = self.imp().regenerate_source.replace(Some(source)) {
+            old.remove();
+        }
+    }
+
+    /// Generate the outdated thumbnails of the pages that are scrolled into view.
+    fn regenerate_visible(&self) {
+        let imp = self.imp();
+        let Some(canvas) = imp.canvas.upgrade() else {
+            return;
+        };
+        if !self.is_mapped() {
+            return;
+        }
+        let hadj = imp.scroller.hadjustment();
+        let (visible_start, visible_end) = (hadj.value(), hadj.value() + hadj.page_size());
+        let indices = imp
+            .pages
+            .borrow_mut()
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, page)| page.dirty)
+            .filter_map(|(i, page)| {
+                let bounds = page.button.compute_bounds(&imp.pages_box)?;
+                let visible = (bounds.x() + bounds.width()) as f64 >= visible_start
+                    && (bounds.x() as f64) <= visible_end;
+                // Marked as up-to-date right away, so that they are not requested again while generating.
+                visible.then(|| {
+                    page.dirty = false;
+                    i
+                })
+            })
+            .collect::<Vec<usize>>();
+        if indices.is_empty() {
+            return;
+        }
+
+        let thumbnail_width = (Self::THUMBNAIL_WIDTH * self.scale_factor()) as f64;
+        let receiver = canvas
+            .engine_ref()
+            .gen_page_thumbnails(&indices, thumbnail_width);
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to=pagethumbnails)]
+            self,
+            async move {
+                let thumbnails = match receiver.await {
+                    Ok(Ok(thumbnails)) => thumbnails,
+                    Ok(Err(e)) => {
+                        error!("Generating page thumbnails failed, Err: {e:?}");
+                        return;
+                    }
+                    Err(e) => {
+                        error!("Awaiting generated page thumbnails failed, Err: {e:?}");
+                        return;
+                    }
+                };
+                let pages = pagethumbnails.imp().pages.borrow();
+                for (i, image) in thumbnails {
+                    let Some(page) = pages.get(i) else {
+                        continue;
+                    };
+                    match image.to_memtexture() {
+                        Ok(texture) => page.picture.set_paintable(Some(&texture)),
+                        Err(e) => {
+                            error!("Converting page thumbnail to texture failed, Err: {e:?}")
+                        }
+                    }
+                }
+            }
+        ));
+    }
+}
@@ -2,15 +2,18 @@
 use crate::{RnAppWindow, RnCanvasWrapper, RnStrokeWidthPicker};
 use adw::prelude::*;
 use gtk4::{
-    glib, glib::clone, subclass::prelude::*, Button, CompositeTemplate, ListBox, MenuButton,
-    Popover,
+    gdk, glib, glib::clone, subclass::prelude::*, Button, ColorDialogButton, CompositeTemplate,
+    ListBox, MenuButton, Popover,
 };
 use num_traits::cast::ToPrimitive;
 use rnote_compose::builders::PenPathBuilderType;
+use rnote_compose::style::calligraphy::CalligraphyOptions;
 use rnote_compose::style::textured::{TexturedDotsDistribution, TexturedOptions};
 use rnote_compose::style::PressureCurve;
+use rnote_engine::ext::GdkRGBAExt;
 use rnote_engine::pens::pensconfig::brushconfig::{BrushStyle, SolidOptions};
 use rnote_engine::pens::pensconfig::BrushConfig;
+use rnote_engine::strokes::StrokeOutline;
 
 mod imp {
     use super::*;
@@ -33,6 +36,8 @@ mod imp {
         #[template_child]
         pub(crate) brushstyle_textured_row: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub(crate) brushstyle_calligraphy_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
         pub(crate) brushconfig_menubutton: TemplateChild<MenuButton>,
         #[template_child]
         pub(crate) brushconfig_popover: TemplateChild<Popover>,
@@ -47,12 +52,36 @@ mod imp {
         #[template_child]
         pub(crate) brush_buildertype_modeled: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub(crate) brush_simplify_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) brush_simplify_epsilon_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) brush_predict_input_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) brush_prediction_window_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) brush_speed_sensitivity_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) brush_outline_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) brush_outline_color_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(crate) brush_outline_color_button: TemplateChild<ColorDialogButton>,
+        #[template_child]
+        pub(crate) brush_outline_width_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) brush_modifier_color_button: TemplateChild<ColorDialogButton>,
+        #[template_child]
         pub(crate) solidstyle_pressure_curves_row: TemplateChild<adw::ComboRow>,
         #[template_child]
         pub(crate) texturedstyle_density_row: TemplateChild<adw::SpinRow>,
         #[template_child]
         pub(crate) texturedstyle_distribution_row: TemplateChild<adw::ComboRow>,
         #[template_child]
+        pub(crate) calligraphystyle_nib_angle_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) calligraphystyle_width_ratio_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
         pub(crate) stroke_width_picker: TemplateChild<RnStrokeWidthPicker>,
     }
 
@@ -129,6 +158,10 @@ impl RnBrushPage {
                 .imp()
                 .brushstyle_listbox
                 .select_row(Some(&*self.imp().brushstyle_textured_row)),
+            BrushStyle::Calligraphy => self
+                .imp()
+                .brushstyle_listbox
+                .select_row(Some(&*self.imp().brushstyle_calligraphy_row)),
         }
     }
 
@@ -217,10 +250,23 @@ impl RnBrushPage {
         imp.stroke_width_picker
             .spinbutton()
             .set_range(BrushConfig::STROKE_WIDTH_MIN, BrushConfig::STROKE_WIDTH_MAX);
+        imp.stroke_width_picker
+            .set_presets(BrushConfig::WIDTH_PRESETS_DEFAULT);
         // set value after the range!
         imp.stroke_width_picker
             .set_stroke_width(SolidOptions::default().stroke_width);
 
+        imp.stroke_width_picker.connect_presets_changed(clone!(
+            #[weak]
+            appwindow,
+            move |picker| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                canvas.engine_mut().pens_config.brush_config.width_presets = picker.presets();
+            }
+        ));
+
         imp.stroke_width_picker.connect_notify_local(
             Some("stroke-width"),
             clone!(
@@ -258,6 +304,14 @@ impl RnBrushPage {
                                 .textured_options
                                 .stroke_width = stroke_width;
                         }
+                        BrushStyle::Calligraphy => {
+                            canvas
+                                .engine_mut()
+                                .pens_config
+                                .brush_config
+                                .calligraphy_options
+                                .stroke_width = stroke_width;
+                        }
                     }
                 }
             ),
@@ -327,7 +381,26 @@ impl RnBrushPage {
                                 .brushstyle_menubutton
                                 .set_icon_name("pen-brush-style-textured-symbolic");
                         }
+                        BrushStyle::Calligraphy => {
+                            let stroke_width = canvas
+                                .engine_mut()
+                                .pens_config
+                                .brush_config
+                                .calligraphy_options
+                                .stroke_width;
+                            brushpage
+                                .imp()
+                                .stroke_width_picker
+                                .set_stroke_width(stroke_width);
+                            brushpage
+                                .imp()
+                                .brushstyle_menubutton
+                                .set_icon_name("pen-brush-style-calligraphy-symbolic");
+                        }
                     }
+                    brushpage
+                        .stroke_width_picker()
+                        .select_setter_matching_stroke_width();
                 }
             }
         ));
@@ -349,6 +422,182 @@ impl RnBrushPage {
             }
         ));
 
+        // Simplification
+        imp.brush_simplify_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas.engine_mut().pens_config.brush_config.simplify = row.is_active();
+            }
+        ));
+        imp.brush_simplify_epsilon_row.get().set_range(
+            BrushConfig::SIMPLIFY_EPSILON_MIN,
+            BrushConfig::SIMPLIFY_EPSILON_MAX,
+        );
+        // set value after the range!
+        imp.brush_simplify_epsilon_row
+            .get()
+            .set_value(BrushConfig::SIMPLIFY_EPSILON_DEFAULT);
+
+        imp.brush_simplify_epsilon_row.get().connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas
+                    .engine_mut()
+                    .pens_config
+                    .brush_config
+                    .simplify_epsilon = row.value();
+            }
+        ));
+
+        // Input prediction
+        imp.brush_predict_input_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas.engine_mut().pens_config.brush_config.predict_input = row.is_active();
+            }
+        ));
+        imp.brush_prediction_window_row.get().set_range(
+            BrushConfig::PREDICTION_WINDOW_MIN,
+            BrushConfig::PREDICTION_WINDOW_MAX,
+        );
+        // set value after the range!
+        imp.brush_prediction_window_row
+            .get()
+            .set_value(BrushConfig::PREDICTION_WINDOW_DEFAULT);
+
+        imp.brush_prediction_window_row
+            .get()
+            .connect_changed(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+
+                    canvas
+                        .engine_mut()
+                        .pens_config
+                        .brush_config
+                        .prediction_window = row.value();
+                }
+            ));
+
+        // Speed sensitivity
+        imp.brush_speed_sensitivity_row.get().set_range(
+            BrushConfig::SPEED_SENSITIVITY_MIN,
+            BrushConfig::SPEED_SENSITIVITY_MAX,
+        );
+        // set value after the range!
+        imp.brush_speed_sensitivity_row
+            .get()
+            .set_value(BrushConfig::SPEED_SENSITIVITY_DEFAULT);
+
+        imp.brush_speed_sensitivity_row
+            .get()
+            .connect_changed(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+
+                    canvas
+                        .engine_mut()
+                        .pens_config
+                        .brush_config
+                        .speed_sensitivity = row.value();
+                }
+            ));
+
+        // Outline
+        imp.brush_outline_row
+            .bind_property("active", &*imp.brush_outline_color_row, "sensitive")
+            .sync_create()
+            .build();
+        imp.brush_outline_row
+            .bind_property("active", &*imp.brush_outline_width_row, "sensitive")
+            .sync_create()
+            .build();
+
+        imp.brush_outline_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas.engine_mut().pens_config.brush_config.outline_enabled = row.is_active();
+            }
+        ));
+
+        imp.brush_outline_color_button
+            .set_rgba(&gdk::RGBA::from_compose_color(
+                StrokeOutline::default().color,
+            ));
+
+        imp.brush_outline_color_button.connect_rgba_notify(clone!(
+            #[weak]
+            appwindow,
+            move |button| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas.engine_mut().pens_config.brush_config.outline.color =
+                    button.rgba().into_compose_color();
+            }
+        ));
+
+        imp.brush_outline_width_row
+            .set_range(StrokeOutline::WIDTH_MIN, StrokeOutline::WIDTH_MAX);
+        // set value after the range!
+        imp.brush_outline_width_row
+            .set_value(StrokeOutline::default().width);
+
+        imp.brush_outline_width_row.connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas.engine_mut().pens_config.brush_config.outline.width = row.value();
+            }
+        ));
+
+        // Modifier color
+        imp.brush_modifier_color_button.connect_rgba_notify(clone!(
+            #[weak]
+            appwindow,
+            move |button| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas.engine_mut().pens_config.brush_config.modifier_color =
+                    button.rgba().into_compose_color();
+            }
+        ));
+
         // Solid style
         // Pressure curve
         imp.solidstyle_pressure_curves_row
@@ -420,6 +669,60 @@ impl RnBrushPage {
                         .distribution = brushpage.texturedstyle_dots_distribution();
                 }
             ));
+
+        // Calligraphy style
+        // Nib angle
+        imp.calligraphystyle_nib_angle_row
+            .get()
+            .set_value(CalligraphyOptions::default().nib_angle.to_degrees());
+
+        imp.calligraphystyle_nib_angle_row
+            .get()
+            .connect_changed(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+
+                    canvas
+                        .engine_mut()
+                        .pens_config
+                        .brush_config
+                        .calligraphy_options
+                        .nib_angle = row.value().to_radians();
+                }
+            ));
+
+        // Nib width ratio
+        imp.calligraphystyle_width_ratio_row.get().set_range(
+            CalligraphyOptions::WIDTH_RATIO_MIN,
+            CalligraphyOptions::WIDTH_RATIO_MAX,
+        );
+        // set value after the range!
+        imp.calligraphystyle_width_ratio_row
+            .get()
+            .set_value(CalligraphyOptions::default().width_ratio);
+
+        imp.calligraphystyle_width_ratio_row
+            .get()
+            .connect_changed(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+
+                    canvas
+                        .engine_mut()
+                        .pens_config
+                        .brush_config
+                        .calligraphy_options
+                        .width_ratio = row.value();
+                }
+            ));
     }
 
     pub(crate) fn refresh_ui(&self, active_tab: &RnCanvasWrapper) {
@@ -431,14 +734,37 @@ impl RnBrushPage {
             .brush_config
             .clone();
 
+        imp.brush_simplify_row.set_active(brush_config.simplify);
+        imp.brush_simplify_epsilon_row
+            .set_value(brush_config.simplify_epsilon);
+        imp.brush_predict_input_row
+            .set_active(brush_config.predict_input);
+        imp.brush_prediction_window_row
+            .set_value(brush_config.prediction_window);
+        imp.brush_speed_sensitivity_row
+            .set_value(brush_config.speed_sensitivity);
+        imp.brush_outline_row
+            .set_active(brush_config.outline_enabled);
+        imp.brush_outline_color_button
+            .set_rgba(&gdk::RGBA::from_compose_color(brush_config.outline.color));
+        imp.brush_outline_width_row
+            .set_value(brush_config.outline.width);
+        imp.brush_modifier_color_button
+            .set_rgba(&gdk::RGBA::from_compose_color(brush_config.modifier_color));
         self.set_solidstyle_pressure_curve(brush_config.solid_options.pressure_curve);
         imp.texturedstyle_density_row
             .set_value(brush_config.textured_options.density);
         self.set_texturedstyle_distribution_variant(brush_config.textured_options.distribution);
+        imp.calligraphystyle_nib_angle_row
+            .set_value(brush_config.calligraphy_options.nib_angle.to_degrees());
+        imp.calligraphystyle_width_ratio_row
+            .set_value(brush_config.calligraphy_options.width_ratio);
 
         self.set_brush_style(brush_config.style);
         self.set_buildertype(brush_config.builder_type);
 
+        imp.stroke_width_picker
+            .set_presets(brush_config.width_presets);
         match brush_config.style {
             BrushStyle::Marker => {
                 imp.stroke_width_picker
@@ -452,6 +778,12 @@ impl RnBrushPage {
                 imp.stroke_width_picker
                     .set_stroke_width(brush_config.textured_options.stroke_width);
             }
+            BrushStyle::Calligraphy => {
+                imp.stroke_width_picker
+                    .set_stroke_width(brush_config.calligraphy_options.stroke_width);
+            }
         }
+        imp.stroke_width_picker
+            .select_setter_matching_stroke_width();
     }
 }
@@ -2,8 +2,12 @@
 use crate::RnStrokeWidthPicker;
 use crate::{RnAppWindow, RnCanvasWrapper};
 use adw::prelude::*;
-use gtk4::{glib, glib::clone, subclass::prelude::*, CompositeTemplate, ToggleButton};
-use rnote_engine::pens::pensconfig::eraserconfig::EraserStyle;
+use gtk4::{
+    gdk, glib, glib::clone, subclass::prelude::*, Button, ColorDialogButton, CompositeTemplate,
+    MenuButton, Popover, ToggleButton,
+};
+use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::pens::pensconfig::eraserconfig::{EraserFilter, EraserStyle};
 use rnote_engine::pens::pensconfig::EraserConfig;
 
 mod imp {
@@ -17,6 +21,22 @@ mod imp {
         #[template_child]
         pub(crate) eraserstyle_split_colliding_strokes_toggle: TemplateChild<ToggleButton>,
         #[template_child]
+        pub(crate) eraserfilter_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) eraserfilter_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) eraserfilter_popover_close_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) eraserfilter_ink_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) eraserfilter_highlighter_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) eraserfilter_shapes_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) eraserfilter_color_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) eraserfilter_color_button: TemplateChild<ColorDialogButton>,
+        #[template_child]
         pub(crate) stroke_width_picker: TemplateChild<RnStrokeWidthPicker>,
     }
 
@@ -139,6 +159,80 @@ impl RnEraserPage {
                 }
             ));
 
+        // filters
+        let eraserfilter_popover = imp.eraserfilter_popover.get();
+        imp.eraserfilter_popover_close_button
+            .connect_clicked(clone!(
+                #[weak]
+                eraserfilter_popover,
+                move |_| {
+                    eraserfilter_popover.popdown();
+                }
+            ));
+
+        imp.eraserfilter_ink_row.connect_active_notify(clone!(
+            #[weak(rename_to=eraserpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |row| {
+                eraserpage.update_filter(&appwindow, |filter| filter.ink = row.is_active());
+            }
+        ));
+
+        imp.eraserfilter_highlighter_row
+            .connect_active_notify(clone!(
+                #[weak(rename_to=eraserpage)]
+                self,
+                #[weak]
+                appwindow,
+                move |row| {
+                    eraserpage
+                        .update_filter(&appwindow, |filter| filter.highlighter = row.is_active());
+                }
+            ));
+
+        imp.eraserfilter_shapes_row.connect_active_notify(clone!(
+            #[weak(rename_to=eraserpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |row| {
+                eraserpage.update_filter(&appwindow, |filter| filter.shapes = row.is_active());
+            }
+        ));
+
+        imp.eraserfilter_color_row.connect_active_notify(clone!(
+            #[weak(rename_to=eraserpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |row| {
+                let color = row.is_active().then(|| {
+                    eraserpage
+                        .imp()
+                        .eraserfilter_color_button
+                        .rgba()
+                        .into_compose_color()
+                });
+                eraserpage.update_filter(&appwindow, |filter| filter.color = color);
+            }
+        ));
+
+        imp.eraserfilter_color_button.connect_rgba_notify(clone!(
+            #[weak(rename_to=eraserpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |button| {
+                if !eraserpage.imp().eraserfilter_color_row.is_active() {
+                    return;
+                }
+                let color = button.rgba().into_compose_color();
+                eraserpage.update_filter(&appwindow, |filter| filter.color = Some(color));
+            }
+        ));
+
         // width
         imp.stroke_width_picker.spinbutton().set_digits(0);
         imp.stroke_width_picker
@@ -147,10 +241,23 @@ impl RnEraserPage {
         imp.stroke_width_picker
             .spinbutton()
             .set_range(EraserConfig::WIDTH_MIN, EraserConfig::WIDTH_MAX);
+        imp.stroke_width_picker
+            .set_presets(EraserConfig::WIDTH_PRESETS_DEFAULT);
         // set value after the range!
         imp.stroke_width_picker
             .set_stroke_width(EraserConfig::WIDTH_DEFAULT);
 
+        imp.stroke_width_picker.connect_presets_changed(clone!(
+            #[weak]
+            appwindow,
+            move |picker| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                canvas.engine_mut().pens_config.eraser_config.width_presets = picker.presets();
+            }
+        ));
+
         imp.stroke_width_picker.connect_notify_local(
             Some("stroke-width"),
             clone!(
@@ -177,9 +284,48 @@ impl RnEraserPage {
             .eraser_config
             .clone();
 
+        imp.stroke_width_picker
+            .set_presets(eraser_config.width_presets);
         imp.stroke_width_picker
             .set_stroke_width(eraser_config.width);
+        imp.stroke_width_picker
+            .select_setter_matching_stroke_width();
 
         self.set_eraser_style(eraser_config.style);
+
+        let filter = &eraser_config.filter;
+        imp.eraserfilter_ink_row.set_active(filter.ink);
+        imp.eraserfilter_highlighter_row
+            .set_active(filter.highlighter);
+        imp.eraserfilter_shapes_row.set_active(filter.shapes);
+        if let Some(color) = filter.color {
+            imp.eraserfilter_color_button
+                .set_rgba(&gdk::RGBA::from_compose_color(color));
+        }
+        imp.eraserfilter_color_row
+            .set_active(filter.color.is_some());
+        self.refresh_filter_indicator(filter);
+    }
+
+    fn update_filter(&self, appwindow: &RnAppWindow, f: impl FnOnce(&mut EraserFilter)) {
+        let Some(canvas) = appwindow.active_tab_canvas() else {
+            return;
+        };
+        let filter = {
+            let mut engine = canvas.engine_mut();
+            f(&mut engine.pens_config.eraser_config.filter);
+            engine.pens_config.eraser_config.filter.clone()
+        };
+        self.refresh_filter_indicator(&filter);
+    }
+
+    /// Highlight the filter button while the filters restrict the erased strokes, so it is not forgotten.
+    fn refresh_filter_indicator(&self, filter: &EraserFilter) {
+        let menubutton = &self.imp().eraserfilter_menubutton;
+        if filter.is_restricting() {
+            menubutton.add_css_class("accent");
+        } else {
+            menubutton.remove_css_class("accent");
+        }
     }
 }
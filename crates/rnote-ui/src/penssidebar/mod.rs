@@ -16,11 +16,16 @@ pub(crate) use toolspage::RnToolsPage;
 pub(crate) use typewriterpage::RnTypewriterPage;
 
 // Imports
-use crate::RnAppWindow;
+use crate::{RnAppWindow, RnCanvasWrapper};
+use gettextrs::gettext;
 use gtk4::{
-    glib, glib::clone, prelude::*, subclass::prelude::*, CompositeTemplate, Stack, StackPage,
-    Widget,
+    gdk, glib, glib::clone, prelude::*, subclass::prelude::*, Button, CompositeTemplate,
+    DrawingArea, GestureClick, Image, MenuButton, Popover, Stack, StackPage, ToggleButton, Widget,
 };
+use once_cell::sync::Lazy;
+use rnote_engine::pens::PensConfig;
+use std::cell::Cell;
+use tracing::error;
 
 mod imp {
     use super::*;
@@ -28,6 +33,15 @@ mod imp {
     #[derive(Default, Debug, CompositeTemplate)]
     #[template(resource = "/com/github/flxzt/rnote/ui/penssidebar/penssidebar.ui")]
     pub(crate) struct RnPensSideBar {
+        pub(crate) compact: Cell<bool>,
+        pub(crate) compact_auto: Cell<bool>,
+
+        #[template_child]
+        pub(crate) presets_box: TemplateChild<gtk4::Box>,
+        #[template_child]
+        pub(crate) presets_list_box: TemplateChild<gtk4::Box>,
+        #[template_child]
+        pub(crate) presets_add_button: TemplateChild<Button>,
         #[template_child]
         pub(crate) sidebar_stack: TemplateChild<Stack>,
         #[template_child]
@@ -54,6 +68,12 @@ mod imp {
         pub(crate) tools_stackpage: TemplateChild<StackPage>,
         #[template_child]
         pub(crate) tools_page: TemplateChild<RnToolsPage>,
+        #[template_child]
+        pub(crate) compact_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) compact_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) compact_togglebutton: TemplateChild<ToggleButton>,
     }
 
     #[glib::object_subclass]
@@ -82,8 +102,78 @@ mod imp {
                 child.unparent();
             }
         }
+
+        fn properties() -> &'static [glib::ParamSpec] {
+            static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+                vec![
+                    // Toggled by the user
+                    glib::ParamSpecBoolean::builder("compact")
+                        .default_value(false)
+                        .build(),
+                    // Activated when the window width drops below a threshold
+                    glib::ParamSpecBoolean::builder("compact-auto")
+                        .default_value(false)
+                        .build(),
+                ]
+            });
+            PROPERTIES.as_ref()
+        }
+
+        fn set_property(&self, _id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+            match pspec.name() {
+                "compact" => {
+                    let compact = value.get::<bool>().expect("value not of type `bool`");
+                    self.compact.set(compact);
+                    self.update_compact_mode();
+                }
+                "compact-auto" => {
+                    let compact_auto = value.get::<bool>().expect("value not of type `bool`");
+                    self.compact_auto.set(compact_auto);
+                    self.update_compact_mode();
+                }
+                _ => panic!("invalid property name"),
+            }
+        }
+
+        fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            match pspec.name() {
+                "compact" => self.compact.get().to_value(),
+                "compact-auto" => self.compact_auto.get().to_value(),
+                _ => panic!("invalid property name"),
+            }
+        }
     }
     impl WidgetImpl for RnPensSideBar {}
+
+    impl RnPensSideBar {
+        /// Moves the pen pages into the compact popover or back into the sidebar,
+        /// depending on whether the sidebar is currently compact.
+        fn update_compact_mode(&self) {
+            let obj = self.obj();
+            let compact = self.compact.get() || self.compact_auto.get();
+            let sidebar_stack = self.sidebar_stack.get();
+            let stack_in_popover = sidebar_stack
+                .parent()
+                .is_some_and(|parent| parent != *obj.upcast_ref::<Widget>());
+
+            if compact && !stack_in_popover {
+                sidebar_stack.unparent();
+                self.compact_popover.set_child(Some(&sidebar_stack));
+            } else if !compact && stack_in_popover {
+                self.compact_popover.popdown();
+                self.compact_popover.set_child(None::<&Widget>);
+                sidebar_stack.insert_before(&*obj, Some(&self.compact_menubutton.get()));
+            }
+
+            self.presets_box.set_visible(!compact);
+            self.compact_menubutton.set_visible(compact);
+            self.compact_togglebutton.set_icon_name(if compact {
+                "pan-start-symbolic"
+            } else {
+                "pan-end-symbolic"
+            });
+        }
+    }
 }
 
 glib::wrapper! {
@@ -106,6 +196,16 @@ impl RnPensSideBar {
         self.imp().sidebar_stack.get()
     }
 
+    #[allow(unused)]
+    pub(crate) fn compact(&self) -> bool {
+        self.property::<bool>("compact")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_compact(&self, compact: bool) {
+        self.set_property("compact", compact.to_value());
+    }
+
     pub(crate) fn brush_page(&self) -> RnBrushPage {
         self.imp().brush_page.get()
     }
@@ -130,61 +230,109 @@ impl RnPensSideBar {
         self.imp().tools_page.get()
     }
 
+    /// Rebuild the pen presets strip from the presets of the given tab.
+    pub(crate) fn refresh_presets(&self, active_tab: &RnCanvasWrapper) {
+        let presets_list_box = self.imp().presets_list_box.get();
+        while let Some(child) = presets_list_box.first_child() {
+            presets_list_box.remove(&child);
+        }
+        let presets = active_tab.canvas().engine_ref().pens_config.presets.clone();
+
+        for (i, preset) in presets.iter().enumerate() {
+            let content = gtk4::Box::builder()
+                .orientation(gtk4::Orientation::Vertical)
+                .spacing(2)
+                .build();
+            content.append(&Image::from_icon_name(&preset.pen_style().icon_name()));
+            if let Some(color) = preset.stroke_color() {
+                let color_indicator = DrawingArea::builder()
+                    .content_width(16)
+                    .content_height(4)
+                    .halign(gtk4::Align::Center)
+                    .build();
+                color_indicator.set_draw_func(move |_, cx, width, height| {
+                    cx.set_source_rgba(color.r, color.g, color.b, color.a);
+                    cx.rectangle(0.0, 0.0, width as f64, height as f64);
+                    if let Err(e) = cx.fill() {
+                        error!("Drawing pen preset color indicator failed, Err: {e:?}");
+                    }
+                });
+                content.append(&color_indicator);
+            }
+            let title = match preset.stroke_width() {
+                Some(width) => format!("{} ({width:.1})", preset.name),
+                None => preset.name.clone(),
+            };
+            let tooltip = format!(
+                "{title}\n{} Shift+Alt+{}\n{}",
+                gettext("Shortcut:"),
+                i + 1,
+                gettext("Right-click to remove")
+            );
+            let button = Button::builder()
+                .child(&content)
+                .tooltip_text(tooltip)
+                .action_name("win.pen-preset-apply")
+                .action_target(&(i as i32).to_variant())
+                .css_classes(["flat", "sidebar_action_button"])
+                .build();
+
+            // Remove on secondary click
+            let remove_gesture = GestureClick::builder()
+                .button(gdk::BUTTON_SECONDARY)
+                .build();
+            remove_gesture.connect_pressed(clone!(
+                #[weak]
+                button,
+                move |_, _, _, _| {
+                    if let Err(e) = button
+                        .activate_action("win.pen-preset-remove", Some(&(i as i32).to_variant()))
+                    {
+                        error!("Activating action `pen-preset-remove` failed, Err: {e:?}");
+                    }
+                }
+            ));
+            button.add_controller(remove_gesture);
+
+            presets_list_box.append(&button);
+        }
+
+        self.imp()
+            .presets_add_button
+            .set_sensitive(presets.len() < PensConfig::PRESETS_MAX);
+    }
+
     pub(crate) fn init(&self, appwindow: &RnAppWindow) {
         self.imp()
             .sidebar_stack
             .get()
             .connect_visible_child_name_notify(clone!(
+                #[weak(rename_to=penssidebar)]
+                self,
                 #[weak]
                 appwindow,
                 move |sidebar_stack| {
-                    if let Some(child_name) = sidebar_stack.visible_child_name() {
-                        match child_name.to_value().get::<String>().unwrap().as_str() {
-                            "brush_page" => {
-                                adw::prelude::ActionGroupExt::activate_action(
-                                    &appwindow,
-                                    "pen-style",
-                                    Some(&PenStyle::Brush.to_string().to_variant()),
-                                );
-                            }
-                            "shaper_page" => {
-                                adw::prelude::ActionGroupExt::activate_action(
-                                    &appwindow,
-                                    "pen-style",
-                                    Some(&PenStyle::Shaper.to_string().to_variant()),
-                                );
-                            }
-                            "typewriter_page" => {
-                                adw::prelude::ActionGroupExt::activate_action(
-                                    &appwindow,
-                                    "pen-style",
-                                    Some(&PenStyle::Typewriter.to_string().to_variant()),
-                                );
-                            }
-                            "eraser_page" => {
-                                adw::prelude::ActionGroupExt::activate_action(
-                                    &appwindow,
-                                    "pen-style",
-                                    Some(&PenStyle::Eraser.to_string().to_variant()),
-                                );
-                            }
-                            "selector_page" => {
-                                adw::prelude::ActionGroupExt::activate_action(
-                                    &appwindow,
-                                    "pen-style",
-                                    Some(&PenStyle::Selector.to_string().to_variant()),
-                                );
-                            }
-                            "tools_page" => {
-                                adw::prelude::ActionGroupExt::activate_action(
-                                    &appwindow,
-                                    "pen-style",
-                                    Some(&PenStyle::Tools.to_string().to_variant()),
-                                );
-                            }
-                            _ => {}
-                        };
+                    let Some(child_name) = sidebar_stack.visible_child_name() else {
+                        return;
+                    };
+                    let pen_style = match child_name.to_value().get::<String>().unwrap().as_str() {
+                        "brush_page" => PenStyle::Brush,
+                        "shaper_page" => PenStyle::Shaper,
+                        "typewriter_page" => PenStyle::Typewriter,
+                        "eraser_page" => PenStyle::Eraser,
+                        "selector_page" => PenStyle::Selector,
+                        "tools_page" => PenStyle::Tools,
+                        _ => return,
                     };
+                    penssidebar
+                        .imp()
+                        .compact_menubutton
+                        .set_icon_name(&pen_style.icon_name());
+                    adw::prelude::ActionGroupExt::activate_action(
+                        &appwindow,
+                        "pen-style",
+                        Some(&pen_style.to_string().to_variant()),
+                    );
                 }
             ));
     }
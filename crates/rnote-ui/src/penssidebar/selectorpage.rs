@@ -1,7 +1,12 @@
 // Imports
 use crate::{RnAppWindow, RnCanvasWrapper};
-use gtk4::{glib, glib::clone, prelude::*, subclass::prelude::*, CompositeTemplate, ToggleButton};
+use adw::prelude::*;
+use gtk4::{
+    glib, glib::clone, subclass::prelude::*, Button, CompositeTemplate, MenuButton, Popover,
+    ToggleButton,
+};
 use rnote_engine::pens::pensconfig::selectorconfig::SelectorStyle;
+use std::cell::Cell;
 
 mod imp {
     use super::*;
@@ -19,6 +24,27 @@ mod imp {
         pub(crate) selectorstyle_intersectingpath_toggle: TemplateChild<ToggleButton>,
         #[template_child]
         pub(crate) resize_lock_aspectratio_togglebutton: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) resize_scale_stroke_width_togglebutton: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) selection_resize_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) selection_resize_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) selection_resize_popover_close_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) selection_resize_width_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) selection_resize_height_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) selection_resize_lock_aspectratio_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) selection_resize_apply_button: TemplateChild<Button>,
+
+        /// Width / height of the selection when the resize popover was opened.
+        pub(crate) selection_resize_aspectratio: Cell<f64>,
+        /// Set while one dimension row is updated to follow the other one.
+        pub(crate) selection_resize_syncing: Cell<bool>,
     }
 
     #[glib::object_subclass]
@@ -174,6 +200,137 @@ impl RnSelectorPage {
                         .resize_lock_aspectratio = resize_lock_aspectratio_togglebutton.is_active();
                 }
             ));
+
+        imp.resize_scale_stroke_width_togglebutton
+            .connect_toggled(clone!(
+                #[weak]
+                appwindow,
+                move |resize_scale_stroke_width_togglebutton| {
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+
+                    canvas
+                        .engine_mut()
+                        .pens_config
+                        .selector_config
+                        .resize_scale_stroke_width =
+                        resize_scale_stroke_width_togglebutton.is_active();
+                }
+            ));
+
+        self.init_selection_resize(appwindow);
+    }
+
+    fn init_selection_resize(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        let selection_resize_popover = imp.selection_resize_popover.get();
+
+        imp.resize_lock_aspectratio_togglebutton
+            .bind_property(
+                "active",
+                &*imp.selection_resize_lock_aspectratio_row,
+                "active",
+            )
+            .sync_create()
+            .bidirectional()
+            .build();
+
+        imp.selection_resize_popover_close_button
+            .connect_clicked(clone!(
+                #[weak]
+                selection_resize_popover,
+                move |_| {
+                    selection_resize_popover.popdown();
+                }
+            ));
+
+        // Fill in the current dimensions of the selection when opening the popover
+        selection_resize_popover.connect_show(clone!(
+            #[weak(rename_to=selectorpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                let imp = selectorpage.imp();
+                let selection_bounds = appwindow
+                    .active_tab_canvas()
+                    .and_then(|canvas| canvas.engine_ref().selection_bounds());
+                let Some(selection_bounds) = selection_bounds else {
+                    imp.selection_resize_apply_button.set_sensitive(false);
+                    return;
+                };
+                let extents = selection_bounds.extents();
+                imp.selection_resize_aspectratio
+                    .set(extents[0] / extents[1]);
+                imp.selection_resize_syncing.set(true);
+                imp.selection_resize_width_row.set_value(extents[0]);
+                imp.selection_resize_height_row.set_value(extents[1]);
+                imp.selection_resize_syncing.set(false);
+                imp.selection_resize_apply_button.set_sensitive(true);
+            }
+        ));
+
+        imp.selection_resize_width_row.connect_changed(clone!(
+            #[weak(rename_to=selectorpage)]
+            self,
+            move |row| {
+                let imp = selectorpage.imp();
+                let aspectratio = imp.selection_resize_aspectratio.get();
+                if imp.selection_resize_syncing.get()
+                    || !imp.selection_resize_lock_aspectratio_row.is_active()
+                    || !aspectratio.is_normal()
+                {
+                    return;
+                }
+                imp.selection_resize_syncing.set(true);
+                imp.selection_resize_height_row
+                    .set_value(row.value() / aspectratio);
+                imp.selection_resize_syncing.set(false);
+            }
+        ));
+
+        imp.selection_resize_height_row.connect_changed(clone!(
+            #[weak(rename_to=selectorpage)]
+            self,
+            move |row| {
+                let imp = selectorpage.imp();
+                let aspectratio = imp.selection_resize_aspectratio.get();
+                if imp.selection_resize_syncing.get()
+                    || !imp.selection_resize_lock_aspectratio_row.is_active()
+                    || !aspectratio.is_normal()
+                {
+                    return;
+                }
+                imp.selection_resize_syncing.set(true);
+                imp.selection_resize_width_row
+                    .set_value(row.value() * aspectratio);
+                imp.selection_resize_syncing.set(false);
+            }
+        ));
+
+        imp.selection_resize_apply_button.connect_clicked(clone!(
+            #[weak(rename_to=selectorpage)]
+            self,
+            #[weak]
+            appwindow,
+            #[weak]
+            selection_resize_popover,
+            move |_| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let imp = selectorpage.imp();
+                let size = na::vector![
+                    imp.selection_resize_width_row.value(),
+                    imp.selection_resize_height_row.value()
+                ];
+                selection_resize_popover.popdown();
+
+                let widget_flags = canvas.engine_mut().resize_selection(size);
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
     }
 
     pub(crate) fn refresh_ui(&self, active_tab: &RnCanvasWrapper) {
@@ -190,5 +347,7 @@ impl RnSelectorPage {
 
         imp.resize_lock_aspectratio_togglebutton
             .set_active(selector_config.resize_lock_aspectratio);
+        imp.resize_scale_stroke_width_togglebutton
+            .set_active(selector_config.resize_scale_stroke_width);
     }
 }
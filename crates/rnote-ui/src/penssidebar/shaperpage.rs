@@ -6,15 +6,19 @@ use crate::{
 use adw::{prelude::*, subclass::prelude::*};
 use gettextrs::gettext;
 use gtk4::{
-    glib, glib::clone, Button, CompositeTemplate, ListBox, MenuButton, Popover, StringList,
+    gdk, glib, glib::clone, Button, ColorDialogButton, CompositeTemplate, ListBox, MenuButton,
+    Popover, StringList,
 };
 use num_traits::cast::ToPrimitive;
 use rnote_compose::builders::ShapeBuilderType;
 use rnote_compose::constraints::ConstraintRatio;
+use rnote_compose::shapes::LineCap;
 use rnote_compose::style::rough::roughoptions::FillStyle;
 use rnote_compose::style::smooth::SmoothOptions;
+use rnote_engine::ext::GdkRGBAExt;
 use rnote_engine::pens::pensconfig::shaperconfig::ShaperStyle;
 use rnote_engine::pens::pensconfig::ShaperConfig;
+use rnote_engine::strokes::StrokeOutline;
 
 mod imp {
     use super::*;
@@ -37,6 +41,24 @@ mod imp {
         #[template_child]
         pub(crate) roughstyle_hachure_angle_row: TemplateChild<adw::SpinRow>,
         #[template_child]
+        pub(crate) join_nearly_closed_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) join_tolerance_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) line_start_cap_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) line_end_cap_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) dimension_line_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) shaper_outline_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) shaper_outline_color_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(crate) shaper_outline_color_button: TemplateChild<ColorDialogButton>,
+        #[template_child]
+        pub(crate) shaper_outline_width_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
         pub(crate) stroke_width_picker: TemplateChild<RnStrokeWidthPicker>,
         #[template_child]
         pub(crate) shapebuildertype_menubutton: TemplateChild<MenuButton>,
@@ -174,6 +196,28 @@ impl RnShaperPage {
             .set_selected(position);
     }
 
+    pub(crate) fn line_start_cap(&self) -> LineCap {
+        LineCap::try_from(self.imp().line_start_cap_row.get().selected()).unwrap()
+    }
+
+    pub(crate) fn set_line_start_cap(&self, cap: LineCap) {
+        self.imp()
+            .line_start_cap_row
+            .get()
+            .set_selected(cap.to_u32().unwrap());
+    }
+
+    pub(crate) fn line_end_cap(&self) -> LineCap {
+        LineCap::try_from(self.imp().line_end_cap_row.get().selected()).unwrap()
+    }
+
+    pub(crate) fn set_line_end_cap(&self, cap: LineCap) {
+        self.imp()
+            .line_end_cap_row
+            .get()
+            .set_selected(cap.to_u32().unwrap());
+    }
+
     pub(crate) fn stroke_width_picker(&self) -> RnStrokeWidthPicker {
         self.imp().stroke_width_picker.get()
     }
@@ -221,10 +265,23 @@ impl RnShaperPage {
             ShaperConfig::STROKE_WIDTH_MIN,
             ShaperConfig::STROKE_WIDTH_MAX,
         );
+        imp.stroke_width_picker
+            .set_presets(ShaperConfig::WIDTH_PRESETS_DEFAULT);
         // set value after the range!
         imp.stroke_width_picker
             .set_stroke_width(SmoothOptions::default().stroke_width);
 
+        imp.stroke_width_picker.connect_presets_changed(clone!(
+            #[weak]
+            appwindow,
+            move |picker| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                canvas.engine_mut().pens_config.shaper_config.width_presets = picker.presets();
+            }
+        ));
+
         imp.stroke_width_picker.connect_notify_local(
             Some("stroke-width"),
             clone!(
@@ -311,6 +368,9 @@ impl RnShaperPage {
                                 .set_icon_name("pen-shaper-style-rough-symbolic");
                         }
                     }
+                    shaperpage
+                        .stroke_width_picker()
+                        .select_setter_matching_stroke_width();
                 }
             }
         ));
@@ -362,6 +422,154 @@ impl RnShaperPage {
                 }
             ));
 
+        // Join nearly closed shapes
+        imp.join_nearly_closed_row
+            .bind_property("active", &*imp.join_tolerance_row, "sensitive")
+            .sync_create()
+            .build();
+
+        imp.join_nearly_closed_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas
+                    .engine_mut()
+                    .pens_config
+                    .shaper_config
+                    .join_nearly_closed = row.is_active();
+            }
+        ));
+
+        imp.join_tolerance_row.set_range(
+            ShaperConfig::JOIN_TOLERANCE_MIN,
+            ShaperConfig::JOIN_TOLERANCE_MAX,
+        );
+        // set value after the range!
+        imp.join_tolerance_row
+            .set_value(ShaperConfig::JOIN_TOLERANCE_DEFAULT);
+
+        imp.join_tolerance_row.connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas.engine_mut().pens_config.shaper_config.join_tolerance = row.value();
+            }
+        ));
+
+        // Line caps
+        imp.line_start_cap_row.get().connect_selected_notify(clone!(
+            #[weak(rename_to=shaperpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas.engine_mut().pens_config.shaper_config.line_start_cap =
+                    shaperpage.line_start_cap();
+            }
+        ));
+
+        imp.line_end_cap_row.get().connect_selected_notify(clone!(
+            #[weak(rename_to=shaperpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas.engine_mut().pens_config.shaper_config.line_end_cap =
+                    shaperpage.line_end_cap();
+            }
+        ));
+
+        // Dimension line
+        imp.dimension_line_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas.engine_mut().pens_config.shaper_config.dimension_line = row.is_active();
+            }
+        ));
+
+        // Outline
+        imp.shaper_outline_row
+            .bind_property("active", &*imp.shaper_outline_color_row, "sensitive")
+            .sync_create()
+            .build();
+        imp.shaper_outline_row
+            .bind_property("active", &*imp.shaper_outline_width_row, "sensitive")
+            .sync_create()
+            .build();
+
+        imp.shaper_outline_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas
+                    .engine_mut()
+                    .pens_config
+                    .shaper_config
+                    .outline_enabled = row.is_active();
+            }
+        ));
+
+        imp.shaper_outline_color_button
+            .set_rgba(&gdk::RGBA::from_compose_color(
+                StrokeOutline::default().color,
+            ));
+
+        imp.shaper_outline_color_button.connect_rgba_notify(clone!(
+            #[weak]
+            appwindow,
+            move |button| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas.engine_mut().pens_config.shaper_config.outline.color =
+                    button.rgba().into_compose_color();
+            }
+        ));
+
+        imp.shaper_outline_width_row
+            .set_range(StrokeOutline::WIDTH_MIN, StrokeOutline::WIDTH_MAX);
+        // set value after the range!
+        imp.shaper_outline_width_row
+            .set_value(StrokeOutline::default().width);
+
+        imp.shaper_outline_width_row.connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas.engine_mut().pens_config.shaper_config.outline.width = row.value();
+            }
+        ));
+
         // shape builder type
         imp.shapebuildertype_picker.set_groups(
             shape_builder_type_icons_get_groups(),
@@ -516,6 +724,8 @@ impl RnShaperPage {
 
         self.set_shaper_style(shaper_config.style);
 
+        imp.stroke_width_picker
+            .set_presets(shaper_config.width_presets);
         match shaper_config.style {
             ShaperStyle::Smooth => {
                 imp.stroke_width_picker
@@ -526,6 +736,8 @@ impl RnShaperPage {
                     .set_stroke_width(shaper_config.rough_options.stroke_width);
             }
         }
+        imp.stroke_width_picker
+            .select_setter_matching_stroke_width();
 
         // builder type
         self.set_shapebuildertype(shaper_config.builder_type);
@@ -535,6 +747,26 @@ impl RnShaperPage {
         imp.roughstyle_hachure_angle_row
             .set_value(shaper_config.rough_options.hachure_angle.to_degrees());
 
+        // Join nearly closed shapes
+        imp.join_nearly_closed_row
+            .set_active(shaper_config.join_nearly_closed);
+        imp.join_tolerance_row
+            .set_value(shaper_config.join_tolerance);
+
+        // Lines
+        self.set_line_start_cap(shaper_config.line_start_cap);
+        self.set_line_end_cap(shaper_config.line_end_cap);
+        imp.dimension_line_row
+            .set_active(shaper_config.dimension_line);
+
+        // Outline
+        imp.shaper_outline_row
+            .set_active(shaper_config.outline_enabled);
+        imp.shaper_outline_color_button
+            .set_rgba(&gdk::RGBA::from_compose_color(shaper_config.outline.color));
+        imp.shaper_outline_width_row
+            .set_value(shaper_config.outline.width);
+
         // constraints
         imp.constraint_enabled_row
             .set_active(shaper_config.constraints.enabled);
@@ -592,6 +824,7 @@ fn shape_builder_type_icons_get_groups() -> Vec<GroupedIconPickerGroupData> {
                 "shapebuilder-cubbez-symbolic",
                 "shapebuilder-polyline-symbolic",
                 "shapebuilder-polygon-symbolic",
+                "shapebuilder-bezierpath-symbolic",
             ]),
         },
     ]
@@ -614,5 +847,6 @@ fn shape_builder_type_icons_to_display_name(icon_name: &str) -> String {
         ShapeBuilderType::CubBez => gettext("Cubic bezier curve"),
         ShapeBuilderType::Polyline => gettext("Polyline"),
         ShapeBuilderType::Polygon => gettext("Polygon"),
+        ShapeBuilderType::BezierPath => gettext("Bezier path"),
     }
 }
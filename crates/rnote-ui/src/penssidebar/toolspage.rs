@@ -4,7 +4,9 @@ use gtk4::{
     glib, glib::clone, prelude::*, subclass::prelude::*, Button, CompositeTemplate, MenuButton,
     Popover, ToggleButton,
 };
-use rnote_engine::pens::pensconfig::toolsconfig::ToolStyle;
+use num_traits::ToPrimitive;
+use rnote_engine::document::format::MeasureUnit;
+use rnote_engine::pens::pensconfig::toolsconfig::{PushApartToolConfig, ToolStyle};
 
 mod imp {
     use super::*;
@@ -15,12 +17,20 @@ mod imp {
         #[template_child]
         pub(crate) toolstyle_verticalspace_toggle: TemplateChild<ToggleButton>,
         #[template_child]
+        pub(crate) toolstyle_pushapart_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
         pub(crate) toolstyle_offsetcamera_toggle: TemplateChild<ToggleButton>,
         #[template_child]
         pub(crate) toolstyle_zoom_toggle: TemplateChild<ToggleButton>,
         #[template_child]
         pub(crate) toolstyle_laser_toggle: TemplateChild<ToggleButton>,
         #[template_child]
+        pub(crate) toolstyle_pdftext_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) toolstyle_fill_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) toolstyle_measure_toggle: TemplateChild<ToggleButton>,
+        #[template_child]
         pub(crate) verticalspace_menubutton: TemplateChild<MenuButton>,
         #[template_child]
         pub(crate) verticalspace_popover: TemplateChild<Popover>,
@@ -31,6 +41,26 @@ mod imp {
         #[template_child]
         pub(crate) verticalspace_limit_movement_horizontal_bordersrow:
             TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) pushapart_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) pushapart_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) pushapart_popover_close_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) pushapart_radius_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) pushapart_strength_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) measure_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) measure_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) measure_popover_close_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) measure_unit_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) measure_leave_annotation_row: TemplateChild<adw::SwitchRow>,
     }
 
     #[glib::object_subclass]
@@ -86,12 +116,20 @@ impl RnToolsPage {
 
         if imp.toolstyle_verticalspace_toggle.is_active() {
             Some(ToolStyle::VerticalSpace)
+        } else if imp.toolstyle_pushapart_toggle.is_active() {
+            Some(ToolStyle::PushApart)
         } else if imp.toolstyle_offsetcamera_toggle.is_active() {
             Some(ToolStyle::OffsetCamera)
         } else if imp.toolstyle_zoom_toggle.is_active() {
             Some(ToolStyle::Zoom)
         } else if imp.toolstyle_laser_toggle.is_active() {
             Some(ToolStyle::Laser)
+        } else if imp.toolstyle_pdftext_toggle.is_active() {
+            Some(ToolStyle::PdfText)
+        } else if imp.toolstyle_fill_toggle.is_active() {
+            Some(ToolStyle::Fill)
+        } else if imp.toolstyle_measure_toggle.is_active() {
+            Some(ToolStyle::Measure)
         } else {
             None
         }
@@ -108,9 +146,13 @@ impl RnToolsPage {
 
         match style {
             ToolStyle::VerticalSpace => imp.toolstyle_verticalspace_toggle.set_active(true),
+            ToolStyle::PushApart => imp.toolstyle_pushapart_toggle.set_active(true),
             ToolStyle::OffsetCamera => imp.toolstyle_offsetcamera_toggle.set_active(true),
             ToolStyle::Zoom => imp.toolstyle_zoom_toggle.set_active(true),
             ToolStyle::Laser => imp.toolstyle_laser_toggle.set_active(true),
+            ToolStyle::PdfText => imp.toolstyle_pdftext_toggle.set_active(true),
+            ToolStyle::Fill => imp.toolstyle_fill_toggle.set_active(true),
+            ToolStyle::Measure => imp.toolstyle_measure_toggle.set_active(true),
         }
     }
 
@@ -118,6 +160,8 @@ impl RnToolsPage {
         let imp = self.imp();
         // for now doesn't do anything but for the close button later
         let verticalspace_popover = imp.verticalspace_popover.get();
+        let pushapart_popover = imp.pushapart_popover.get();
+        let measure_popover = imp.measure_popover.get();
 
         imp.toolstyle_verticalspace_toggle.connect_toggled(clone!(
             #[weak]
@@ -135,6 +179,22 @@ impl RnToolsPage {
             }
         ));
 
+        imp.toolstyle_pushapart_toggle.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                if toggle.is_active() {
+                    canvas.engine_mut().pens_config.tools_config.style = ToolStyle::PushApart;
+                    let widget_flags = canvas.engine_mut().reinstall_pen_current_style();
+                    canvas.emit_handle_widget_flags(widget_flags);
+                }
+            }
+        ));
+
         imp.toolstyle_offsetcamera_toggle.connect_toggled(clone!(
             #[weak]
             appwindow,
@@ -183,6 +243,54 @@ impl RnToolsPage {
             }
         ));
 
+        imp.toolstyle_pdftext_toggle.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                if toggle.is_active() {
+                    canvas.engine_mut().pens_config.tools_config.style = ToolStyle::PdfText;
+                    let widget_flags = canvas.engine_mut().reinstall_pen_current_style();
+                    canvas.emit_handle_widget_flags(widget_flags);
+                }
+            }
+        ));
+
+        imp.toolstyle_fill_toggle.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                if toggle.is_active() {
+                    canvas.engine_mut().pens_config.tools_config.style = ToolStyle::Fill;
+                    let widget_flags = canvas.engine_mut().reinstall_pen_current_style();
+                    canvas.emit_handle_widget_flags(widget_flags);
+                }
+            }
+        ));
+
+        imp.toolstyle_measure_toggle.connect_toggled(clone!(
+            #[weak]
+            appwindow,
+            move |toggle| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                if toggle.is_active() {
+                    canvas.engine_mut().pens_config.tools_config.style = ToolStyle::Measure;
+                    let widget_flags = canvas.engine_mut().reinstall_pen_current_style();
+                    canvas.emit_handle_widget_flags(widget_flags);
+                }
+            }
+        ));
+
         imp.verticalspace_menubutton.connect_active_notify(clone!(
             #[weak(rename_to=toolspage)]
             self,
@@ -238,6 +346,129 @@ impl RnToolsPage {
                         .limit_movement_horizontal_borders = row.is_active();
                 }
             ));
+
+        imp.pushapart_menubutton.connect_active_notify(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            move |menubutton| {
+                if menubutton.is_active() {
+                    toolspage.set_tool_style(ToolStyle::PushApart);
+                }
+            }
+        ));
+
+        imp.pushapart_popover_close_button.connect_clicked(clone!(
+            #[weak]
+            pushapart_popover,
+            move |_| {
+                pushapart_popover.popdown();
+            }
+        ));
+
+        imp.pushapart_radius_row.get().set_range(
+            PushApartToolConfig::RADIUS_MIN,
+            PushApartToolConfig::RADIUS_MAX,
+        );
+        // set value after the range!
+        imp.pushapart_radius_row
+            .get()
+            .set_value(PushApartToolConfig::RADIUS_DEFAULT);
+        imp.pushapart_radius_row.get().connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas
+                    .engine_mut()
+                    .pens_config
+                    .tools_config
+                    .pushapart_tool_config
+                    .radius = row.value();
+            }
+        ));
+
+        imp.pushapart_strength_row.get().set_range(
+            PushApartToolConfig::STRENGTH_MIN,
+            PushApartToolConfig::STRENGTH_MAX,
+        );
+        imp.pushapart_strength_row
+            .get()
+            .set_value(PushApartToolConfig::STRENGTH_DEFAULT);
+        imp.pushapart_strength_row.get().connect_changed(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                canvas
+                    .engine_mut()
+                    .pens_config
+                    .tools_config
+                    .pushapart_tool_config
+                    .strength = row.value();
+            }
+        ));
+
+        imp.measure_menubutton.connect_active_notify(clone!(
+            #[weak(rename_to=toolspage)]
+            self,
+            move |menubutton| {
+                if menubutton.is_active() {
+                    toolspage.set_tool_style(ToolStyle::Measure);
+                }
+            }
+        ));
+
+        imp.measure_popover_close_button.connect_clicked(clone!(
+            #[weak]
+            measure_popover,
+            move |_| {
+                measure_popover.popdown();
+            }
+        ));
+
+        imp.measure_unit_row.get().connect_selected_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let Ok(unit) = MeasureUnit::try_from(row.selected()) else {
+                    return;
+                };
+
+                canvas
+                    .engine_mut()
+                    .pens_config
+                    .tools_config
+                    .measure_tool_config
+                    .unit = unit;
+            }
+        ));
+        imp.measure_leave_annotation_row
+            .get()
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+
+                    canvas
+                        .engine_mut()
+                        .pens_config
+                        .tools_config
+                        .measure_tool_config
+                        .leave_annotation = row.is_active();
+                }
+            ));
     }
 
     pub(crate) fn refresh_ui(&self, active_tab: &RnCanvasWrapper) {
@@ -263,5 +494,18 @@ impl RnToolsPage {
                     .verticalspace_tool_config
                     .limit_movement_vertical_borders,
             );
+        imp.pushapart_radius_row
+            .set_value(tools_config.pushapart_tool_config.radius);
+        imp.pushapart_strength_row
+            .set_value(tools_config.pushapart_tool_config.strength);
+        imp.measure_unit_row.set_selected(
+            tools_config
+                .measure_tool_config
+                .unit
+                .to_u32()
+                .unwrap_or_default(),
+        );
+        imp.measure_leave_annotation_row
+            .set_active(tools_config.measure_tool_config.leave_annotation);
     }
 }
@@ -1,11 +1,19 @@
 // Imports
-use crate::{RnAppWindow, RnCanvasWrapper};
+use crate::{RnAppWindow, RnCanvas, RnCanvasWrapper};
+use adw::prelude::*;
+use gettextrs::gettext;
 use gtk4::{
-    glib, glib::clone, pango, prelude::*, subclass::prelude::*, Button, CompositeTemplate,
-    EmojiChooser, FontDialog, MenuButton, SpinButton, ToggleButton,
+    gdk, gio, glib, glib::clone, glib::translate::IntoGlib, pango, subclass::prelude::*, Button,
+    ColorDialogButton, CompositeTemplate, EmojiChooser, FontDialog, MenuButton, Popover,
+    SpinButton, ToggleButton,
 };
-use rnote_engine::strokes::textstroke::{FontStyle, TextAlignment, TextAttribute, TextStyle};
-use std::cell::RefCell;
+use rnote_compose::Color;
+use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::pens::TextFont;
+use rnote_engine::strokes::textstroke::{
+    FontStyle, TextAlignment, TextAttribute, TextStyle, TextVerticalAlignment,
+};
+use std::cell::Cell;
 use tracing::debug;
 
 mod imp {
@@ -14,7 +22,8 @@ mod imp {
     #[derive(Default, Debug, CompositeTemplate)]
     #[template(resource = "/com/github/flxzt/rnote/ui/penssidebar/typewriterpage.ui")]
     pub(crate) struct RnTypewriterPage {
-        pub(super) prev_picked_font_family: RefCell<Option<pango::FontFamily>>,
+        /// Set while the font widgets are updated from the engine, so that the values are not applied back.
+        pub(super) refreshing_font: Cell<bool>,
 
         #[template_child]
         pub(crate) fontdialog_button: TemplateChild<Button>,
@@ -25,6 +34,8 @@ mod imp {
         #[template_child]
         pub(crate) emojichooser: TemplateChild<EmojiChooser>,
         #[template_child]
+        pub(crate) snippets_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
         pub(crate) text_reset_button: TemplateChild<Button>,
         #[template_child]
         pub(crate) text_bold_button: TemplateChild<Button>,
@@ -42,6 +53,28 @@ mod imp {
         pub(crate) text_align_end_togglebutton: TemplateChild<ToggleButton>,
         #[template_child]
         pub(crate) text_align_fill_togglebutton: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) textbox_menubutton: TemplateChild<MenuButton>,
+        #[template_child]
+        pub(crate) textbox_popover: TemplateChild<Popover>,
+        #[template_child]
+        pub(crate) textbox_popover_close_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) textbox_vertical_alignment_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) textbox_height_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) textbox_padding_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) text_path_offset_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) text_path_spacing_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) textbox_background_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) textbox_background_color_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(crate) textbox_background_color_button: TemplateChild<ColorDialogButton>,
     }
 
     #[glib::object_subclass]
@@ -119,27 +152,82 @@ impl RnTypewriterPage {
         }
     }
 
+    pub(crate) fn vertical_alignment(&self) -> TextVerticalAlignment {
+        match self.imp().textbox_vertical_alignment_row.selected() {
+            1 => TextVerticalAlignment::Center,
+            2 => TextVerticalAlignment::Bottom,
+            _ => TextVerticalAlignment::Top,
+        }
+    }
+
+    pub(crate) fn set_vertical_alignment(&self, vertical_alignment: TextVerticalAlignment) {
+        let position = match vertical_alignment {
+            TextVerticalAlignment::Top => 0,
+            TextVerticalAlignment::Center => 1,
+            TextVerticalAlignment::Bottom => 2,
+        };
+        self.imp()
+            .textbox_vertical_alignment_row
+            .set_selected(position);
+    }
+
+    /// The background color, if the background fill is enabled.
+    pub(crate) fn background_color(&self) -> Option<Color> {
+        let imp = self.imp();
+        imp.textbox_background_row.is_active().then(|| {
+            imp.textbox_background_color_button
+                .rgba()
+                .into_compose_color()
+        })
+    }
+
     pub(crate) fn init(&self, appwindow: &RnAppWindow) {
         let imp = self.imp();
+        let textbox_popover = imp.textbox_popover.get();
+
+        imp.textbox_popover_close_button.connect_clicked(clone!(
+            #[weak]
+            textbox_popover,
+            move |_| {
+                textbox_popover.popdown();
+            }
+        ));
 
         imp.fontdialog_button.connect_clicked(clone!(#[weak(rename_to=typewriterpage)] self , #[weak] appwindow , move |_| {
             glib::spawn_future_local(clone!(#[weak] typewriterpage, #[weak] appwindow , async move {
-                let dialog = FontDialog::builder().modal(false).build();
-                let prev_picked_font_family = typewriterpage.imp().prev_picked_font_family.borrow().clone();
                 let Some(canvas) = appwindow.active_tab_canvas() else {
                     return;
                 };
-
-                match dialog.choose_family_future(Some(&appwindow), prev_picked_font_family.as_ref()).await {
-                    Ok(new_font_family) => {
-                        let font_family_name = new_font_family.name().to_string();
-
-                        typewriterpage.imp().prev_picked_font_family.borrow_mut().replace(new_font_family);
-                        canvas.engine_mut().pens_config.typewriter_config.text_style.font_family.clone_from(&font_family_name);
-                        let widget_flags = canvas.engine_mut().text_selection_change_style(|style| {style.font_family = font_family_name});
+                let dialog = FontDialog::builder().modal(false).build();
+                let initial_font_description = typewriterpage.font_description(&canvas);
+
+                match dialog.choose_font_future(Some(&appwindow), Some(&initial_font_description)).await {
+                    Ok(font_description) => {
+                        let font = TextFont {
+                            font_family: font_description.family().map(|family| family.to_string()),
+                            font_size: (font_description.size() > 0)
+                                .then(|| f64::from(font_description.size()) / f64::from(pango::SCALE))
+                                .map(|size| size.clamp(TextStyle::FONT_SIZE_MIN, TextStyle::FONT_SIZE_MAX)),
+                            font_weight: u16::try_from(font_description.weight().into_glib()).ok(),
+                        };
+                        let widget_flags = {
+                            let mut engine = canvas.engine_mut();
+                            let text_style = &mut engine.pens_config.typewriter_config.text_style;
+                            if let Some(font_family) = &font.font_family {
+                                text_style.font_family.clone_from(font_family);
+                            }
+                            if let Some(font_size) = font.font_size {
+                                text_style.font_size = font_size;
+                            }
+                            if let Some(font_weight) = font.font_weight {
+                                text_style.font_weight = font_weight;
+                            }
+                            engine.text_change_font(font)
+                        };
                         appwindow.handle_widget_flags(widget_flags, &canvas);
+                        typewriterpage.refresh_font(&canvas);
                     }
-                    Err(e) => debug!("Did not choose new font family (Error or dialog dismissed by user), Err: {e:?}"),
+                    Err(e) => debug!("Did not choose new font (Error or dialog dismissed by user), Err: {e:?}"),
                 }
             }));
         }));
@@ -152,9 +240,14 @@ impl RnTypewriterPage {
             .set_value(TextStyle::FONT_SIZE_DEFAULT);
 
         imp.font_size_spinbutton.connect_value_changed(clone!(
+            #[weak(rename_to=typewriterpage)]
+            self,
             #[weak]
             appwindow,
             move |spinbutton| {
+                if typewriterpage.imp().refreshing_font.get() {
+                    return;
+                }
                 let font_size = spinbutton.value();
                 let Some(canvas) = appwindow.active_tab_canvas() else {
                     return;
@@ -166,10 +259,12 @@ impl RnTypewriterPage {
                     .typewriter_config
                     .text_style
                     .font_size = font_size;
-                let widget_flags = canvas
-                    .engine_mut()
-                    .text_selection_change_style(|style| style.font_size = font_size);
+                let widget_flags = canvas.engine_mut().text_change_font(TextFont {
+                    font_size: Some(font_size),
+                    ..Default::default()
+                });
                 appwindow.handle_widget_flags(widget_flags, &canvas);
+                typewriterpage.refresh_font(&canvas);
             }
         ));
 
@@ -186,6 +281,21 @@ impl RnTypewriterPage {
             }
         ));
 
+        // Snippets
+        if let Some(app_settings) = appwindow.app().app_settings() {
+            self.refresh_snippets_menu(&app_settings.strv("typewriter-snippets"));
+            app_settings.connect_changed(
+                Some("typewriter-snippets"),
+                clone!(
+                    #[weak(rename_to=typewriterpage)]
+                    self,
+                    move |app_settings, key| {
+                        typewriterpage.refresh_snippets_menu(&app_settings.strv(key));
+                    }
+                ),
+            );
+        }
+
         // reset
         imp.text_reset_button.connect_clicked(clone!(
             #[weak]
@@ -358,6 +468,254 @@ impl RnTypewriterPage {
                     appwindow.handle_widget_flags(widget_flags, &canvas);
                 }
             ));
+
+        // Text box
+        imp.textbox_vertical_alignment_row
+            .connect_selected_notify(clone!(
+                #[weak(rename_to=typewriterpage)]
+                self,
+                #[weak]
+                appwindow,
+                move |_| {
+                    let vertical_alignment = typewriterpage.vertical_alignment();
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+                    canvas
+                        .engine_mut()
+                        .pens_config
+                        .typewriter_config
+                        .text_style
+                        .vertical_alignment = vertical_alignment;
+                    let widget_flags = canvas.engine_mut().text_selection_change_style(|style| {
+                        style.vertical_alignment = vertical_alignment
+                    });
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            ));
+
+        imp.textbox_height_row
+            .set_range(0.0, TextStyle::BOX_HEIGHT_MAX);
+        imp.textbox_height_row.connect_value_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                // A height of zero means the box height follows the text
+                let box_height = Some(row.value()).filter(|h| *h > 0.0);
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                canvas
+                    .engine_mut()
+                    .pens_config
+                    .typewriter_config
+                    .text_style
+                    .set_box_height(box_height);
+                let widget_flags = canvas
+                    .engine_mut()
+                    .text_selection_change_style(|style| style.set_box_height(box_height));
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        imp.textbox_padding_row
+            .set_range(0.0, TextStyle::PADDING_MAX);
+        imp.textbox_padding_row.connect_value_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let padding = row.value();
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                canvas
+                    .engine_mut()
+                    .pens_config
+                    .typewriter_config
+                    .text_style
+                    .set_padding(padding);
+                let widget_flags = canvas
+                    .engine_mut()
+                    .text_selection_change_style(|style| style.set_padding(padding));
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        imp.text_path_offset_row
+            .set_range(-TextStyle::PATH_OFFSET_MAX, TextStyle::PATH_OFFSET_MAX);
+        imp.text_path_offset_row.connect_value_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let path_offset = row.value();
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                canvas
+                    .engine_mut()
+                    .pens_config
+                    .typewriter_config
+                    .text_style
+                    .set_path_offset(path_offset);
+                let widget_flags = canvas
+                    .engine_mut()
+                    .text_selection_change_style(|style| style.set_path_offset(path_offset));
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        imp.text_path_spacing_row
+            .set_range(0.0, TextStyle::PATH_SPACING_MAX);
+        imp.text_path_spacing_row.connect_value_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let path_spacing = row.value();
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                canvas
+                    .engine_mut()
+                    .pens_config
+                    .typewriter_config
+                    .text_style
+                    .set_path_spacing(path_spacing);
+                let widget_flags = canvas
+                    .engine_mut()
+                    .text_selection_change_style(|style| style.set_path_spacing(path_spacing));
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+            }
+        ));
+
+        imp.textbox_background_color_button
+            .set_rgba(&gdk::RGBA::from_compose_color(Color::WHITE));
+        imp.textbox_background_color_row.set_sensitive(false);
+
+        imp.textbox_background_row.connect_active_notify(clone!(
+            #[weak(rename_to=typewriterpage)]
+            self,
+            #[weak]
+            appwindow,
+            move |row| {
+                typewriterpage
+                    .imp()
+                    .textbox_background_color_row
+                    .set_sensitive(row.is_active());
+                typewriterpage.apply_background_color(&appwindow);
+            }
+        ));
+        imp.textbox_background_color_button
+            .connect_rgba_notify(clone!(
+                #[weak(rename_to=typewriterpage)]
+                self,
+                #[weak]
+                appwindow,
+                move |_| {
+                    typewriterpage.apply_background_color(&appwindow);
+                }
+            ));
+    }
+
+    fn apply_background_color(&self, appwindow: &RnAppWindow) {
+        let Some(canvas) = appwindow.active_tab_canvas() else {
+            return;
+        };
+        let background_color = self.background_color();
+        let current_background_color = canvas
+            .engine_ref()
+            .pens_config
+            .typewriter_config
+            .text_style
+            .background_color;
+        if current_background_color == background_color {
+            return;
+        }
+
+        canvas
+            .engine_mut()
+            .pens_config
+            .typewriter_config
+            .text_style
+            .background_color = background_color;
+        let widget_flags = canvas
+            .engine_mut()
+            .text_selection_change_style(|style| style.background_color = background_color);
+        appwindow.handle_widget_flags(widget_flags, &canvas);
+    }
+
+    /// The font description of the current text font.
+    ///
+    /// Values that differ within the text selection are taken from the typewriter config.
+    fn font_description(&self, canvas: &RnCanvas) -> pango::FontDescription {
+        let engine = canvas.engine_ref();
+        let text_style = engine.pens_config.typewriter_config.text_style.clone();
+        let font = engine.text_font().unwrap_or_default();
+
+        let mut font_description = pango::FontDescription::new();
+        font_description.set_family(&font.font_family.unwrap_or(text_style.font_family));
+        font_description.set_size(
+            (font.font_size.unwrap_or(text_style.font_size) * f64::from(pango::SCALE)).round()
+                as i32,
+        );
+        font_description.set_weight(pango_weight(
+            font.font_weight.unwrap_or(text_style.font_weight),
+        ));
+        font_description
+    }
+
+    /// Refresh the font widgets with the font of the current text selection.
+    ///
+    /// Values that differ within the selection are indicated as mixed.
+    pub(crate) fn refresh_font(&self, canvas: &RnCanvas) {
+        let imp = self.imp();
+        let font = canvas.engine_ref().text_font();
+        let (family_mixed, size_mixed) = match &font {
+            Some(font) => (
+                font.font_family.is_none() || font.font_weight.is_none(),
+                font.font_size.is_none(),
+            ),
+            None => (false, false),
+        };
+
+        if family_mixed {
+            imp.fontdialog_button.add_css_class("dim-label");
+            imp.fontdialog_button
+                .set_tooltip_text(Some(&gettext("Choose Font (Selection Has Mixed Fonts)")));
+        } else {
+            imp.fontdialog_button.remove_css_class("dim-label");
+            imp.fontdialog_button
+                .set_tooltip_text(Some(&gettext("Choose Font")));
+        }
+        if size_mixed {
+            imp.font_size_spinbutton.add_css_class("dim-label");
+            imp.font_size_spinbutton
+                .set_tooltip_text(Some(&gettext("Font Size (Selection Has Mixed Sizes)")));
+        } else {
+            imp.font_size_spinbutton.remove_css_class("dim-label");
+            imp.font_size_spinbutton
+                .set_tooltip_text(Some(&gettext("Font Size")));
+        }
+        if let Some(font_size) = font.and_then(|font| font.font_size) {
+            imp.refreshing_font.set(true);
+            imp.font_size_spinbutton.set_value(font_size);
+            imp.refreshing_font.set(false);
+        }
+    }
+
+    fn refresh_snippets_menu(&self, snippets: &glib::StrV) {
+        let menu = gio::Menu::new();
+        for snippet in snippets.iter() {
+            let item = gio::MenuItem::new(Some(snippet.as_str()), None);
+            item.set_action_and_target_value(
+                Some("win.insert-snippet"),
+                Some(&snippet.as_str().to_variant()),
+            );
+            menu.append_item(&item);
+        }
+        self.imp().snippets_menubutton.set_menu_model(Some(&menu));
+        self.imp()
+            .snippets_menubutton
+            .set_sensitive(!snippets.is_empty());
     }
 
     pub(crate) fn refresh_ui(&self, active_tab: &RnCanvasWrapper) {
@@ -370,9 +728,45 @@ impl RnTypewriterPage {
             .typewriter_config
             .clone();
 
+        imp.refreshing_font.set(true);
         imp.font_size_spinbutton
             .set_value(typewriter_config.text_style.font_size);
+        imp.refreshing_font.set(false);
+        self.refresh_font(&active_tab.canvas());
 
         self.set_alignment(typewriter_config.text_style.alignment);
+        self.set_vertical_alignment(typewriter_config.text_style.vertical_alignment);
+        imp.textbox_height_row
+            .set_value(typewriter_config.text_style.box_height().unwrap_or(0.0));
+        imp.textbox_padding_row
+            .set_value(typewriter_config.text_style.padding());
+        imp.text_path_offset_row
+            .set_value(typewriter_config.text_style.path_offset());
+        imp.text_path_spacing_row
+            .set_value(typewriter_config.text_style.path_spacing());
+        if let Some(background_color) = typewriter_config.text_style.background_color {
+            imp.textbox_background_color_button
+                .set_rgba(&gdk::RGBA::from_compose_color(background_color));
+        }
+        imp.textbox_background_row
+            .set_active(typewriter_config.text_style.background_color.is_some());
+    }
+}
+
+/// The pango weight that is closest to the numeric font weight.
+fn pango_weight(font_weight: u16) -> pango::Weight {
+    match font_weight {
+        ..=150 => pango::Weight::Thin,
+        151..=250 => pango::Weight::Ultralight,
+        251..=325 => pango::Weight::Light,
+        326..=370 => pango::Weight::Semilight,
+        371..=390 => pango::Weight::Book,
+        391..=450 => pango::Weight::Normal,
+        451..=550 => pango::Weight::Medium,
+        551..=650 => pango::Weight::Semibold,
+        651..=750 => pango::Weight::Bold,
+        751..=850 => pango::Weight::Ultrabold,
+        851..=950 => pango::Weight::Heavy,
+        _ => pango::Weight::Ultraheavy,
     }
 }
@@ -0,0 +1,386 @@
+// Imports
+use crate::RnCanvas;
+use gtk4::{
+    gdk, glib, glib::clone, graphene, prelude::*, subclass::prelude::*, GestureDrag, Orientation,
+    SizeRequestMode, Widget,
+};
+use once_cell::sync::Lazy;
+use rnote_engine::document::{Guide, GuideOrientation};
+use std::cell::Cell;
+use tracing::error;
+
+mod imp {
+    use super::*;
+
+    #[derive(Debug)]
+    pub(crate) struct RnRuler {
+        pub(crate) orientation: Cell<Orientation>,
+        pub(crate) canvas: glib::WeakRef<RnCanvas>,
+        /// The index of the guide that is currently dragged.
+        pub(crate) dragged_guide: Cell<Option<usize>>,
+    }
+
+    impl Default for RnRuler {
+        fn default() -> Self {
+            Self {
+                orientation: Cell::new(Orientation::Horizontal),
+                canvas: glib::WeakRef::new(),
+                dragged_guide: Cell::new(None),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnRuler {
+        const NAME: &'static str = "RnRuler";
+        type Type = super::RnRuler;
+        type ParentType = Widget;
+        type Interfaces = (gtk4::Orientable,);
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_css_name("ruler");
+        }
+    }
+
+    impl ObjectImpl for RnRuler {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.setup_guide_dragging();
+        }
+
+        fn properties() -> &'static [glib::ParamSpec] {
+            static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+                vec![glib::ParamSpecOverride::for_interface::<gtk4::Orientable>(
+                    "orientation",
+                )]
+            });
+            PROPERTIES.as_ref()
+        }
+
+        fn set_property(&self, _id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+            let obj = self.obj();
+
+            match pspec.name() {
+                "orientation" => {
+                    let orientation = value
+                        .get::<Orientation>()
+                        .expect("value not of type `Orientation`");
+                    self.orientation.set(orientation);
+                    obj.queue_resize();
+                }
+                _ => panic!("invalid property name"),
+            }
+        }
+
+        fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            match pspec.name() {
+                "orientation" => self.orientation.get().to_value(),
+                _ => panic!("invalid property name"),
+            }
+        }
+    }
+
+    impl WidgetImpl for RnRuler {
+        fn request_mode(&self) -> SizeRequestMode {
+            SizeRequestMode::ConstantSize
+        }
+
+        fn measure(&self, orientation: Orientation, _for_size: i32) -> (i32, i32, i32, i32) {
+            if orientation == self.orientation.get() {
+                (0, 0, -1, -1)
+            } else {
+                (super::RnRuler::THICKNESS, super::RnRuler::THICKNESS, -1, -1)
+            }
+        }
+
+        fn snapshot(&self, snapshot: &gtk4::Snapshot) {
+            let obj = self.obj();
+            let Some(canvas) = self.canvas.upgrade() else {
+                return;
+            };
+            let (width, height) = (obj.width() as f64, obj.height() as f64);
+            let horizontal = self.orientation.get() == Orientation::Horizontal;
+            let length = if horizontal { width } else { height };
+            let thickness = if horizontal { height } else { width };
+            let Some((doc_start, total_zoom)) = obj.doc_coord_at(&canvas, 0.0) else {
+                return;
+            };
+            let guides = canvas.engine_ref().document.guides.clone();
+
+            // accessing colors through the style context is deprecated,
+            // but this needs new color API to fetch theme colors.
+            #[allow(deprecated)]
+            let window_fg_color = obj
+                .style_context()
+                .lookup_color("window_fg_color")
+                .unwrap_or(gdk::RGBA::BLACK);
+
+            let cairo_cx =
+                snapshot.append_cairo(&graphene::Rect::new(0.0, 0.0, width as f32, height as f32));
+            // Maps from a position along and across the ruler to widget coordinates.
+            let to_widget = |along: f64, across: f64| {
+                if horizontal {
+                    (along, across)
+                } else {
+                    (across, along)
+                }
+            };
+            cairo_cx.set_source_rgba(
+                window_fg_color.red() as f64,
+                window_fg_color.green() as f64,
+                window_fg_color.blue() as f64,
+                window_fg_color.alpha() as f64 * 0.7,
+            );
+            cairo_cx.set_line_width(1.0);
+            cairo_cx.set_font_size(9.0);
+
+            let major_step = super::major_tick_step(total_zoom);
+            let minor_step = major_step / 5.0;
+            let doc_end = doc_start + length / total_zoom;
+            let mut labels = Vec::new();
+            let mut tick = (doc_start / minor_step).floor() as i64;
+
+            while (tick as f64) * minor_step <= doc_end {
+                let doc_pos = tick as f64 * minor_step;
+                let pos = ((doc_pos - doc_start) * total_zoom).round() + 0.5;
+                let tick_len = if tick % 5 == 0 {
+                    labels.push((pos, doc_pos.round()));
+                    thickness
+                } else {
+                    thickness * 0.3
+                };
+
+                let (x, y) = to_widget(pos, thickness);
+                cairo_cx.move_to(x, y);
+                let (x, y) = to_widget(pos, thickness - tick_len);
+                cairo_cx.line_to(x, y);
+                tick += 1;
+            }
+            if let Err(e) = cairo_cx.stroke() {
+                error!("Failed to draw ruler ticks, stroke returned Err: {e:?}");
+            }
+
+            for (pos, label) in labels {
+                cairo_cx.save().ok();
+                if horizontal {
+                    cairo_cx.move_to(pos + 3.0, 10.0);
+                } else {
+                    // Labels on the vertical ruler read from bottom to top.
+                    cairo_cx.translate(10.0, pos - 3.0);
+                    cairo_cx.rotate(-std::f64::consts::FRAC_PI_2);
+                    cairo_cx.move_to(0.0, 0.0);
+                }
+                if let Err(e) = cairo_cx.show_text(&format!("{label}")) {
+                    error!("Failed to draw ruler label, Err: {e:?}");
+                }
+                cairo_cx.restore().ok();
+            }
+
+            // Mark the positions of the guides that can be dragged from this ruler.
+            cairo_cx.set_source_rgba(
+                Guide::COLOR.r,
+                Guide::COLOR.g,
+                Guide::COLOR.b,
+                Guide::COLOR.a,
+            );
+            let marker_size = thickness * 0.4;
+            for guide in guides
+                .iter()
+                .filter(|g| g.orientation == super::guide_orientation(horizontal))
+            {
+                let pos = (guide.pos - doc_start) * total_zoom;
+                let (x, y) = to_widget(pos - marker_size, thickness);
+                cairo_cx.move_to(x, y);
+                let (x, y) = to_widget(pos + marker_size, thickness);
+                cairo_cx.line_to(x, y);
+                let (x, y) = to_widget(pos, thickness - marker_size);
+                cairo_cx.line_to(x, y);
+                cairo_cx.close_path();
+            }
+            if let Err(e) = cairo_cx.fill() {
+                error!("Failed to draw ruler guide markers, fill returned Err: {e:?}");
+            }
+        }
+    }
+
+    impl RnRuler {
+        fn setup_guide_dragging(&self) {
+            let obj = self.obj();
+            let drag_gesture = GestureDrag::builder()
+                .name("ruler_drag_gesture")
+                .button(gdk::BUTTON_PRIMARY)
+                .build();
+            obj.add_controller(drag_gesture.clone());
+
+            drag_gesture.connect_drag_begin(clone!(
+                #[weak(rename_to=ruler)]
+                obj,
+                move |_, x, y| {
+                    let Some(canvas) = ruler.imp().canvas.upgrade() else {
+                        return;
+                    };
+                    let horizontal = ruler.imp().orientation.get() == Orientation::Horizontal;
+                    let along = if horizontal { x } else { y };
+                    let Some((doc_pos, total_zoom)) = ruler.doc_coord_at(&canvas, along) else {
+                        return;
+                    };
+                    let orientation = super::guide_orientation(horizontal);
+                    let pos = if horizontal {
+                        na::vector![doc_pos, 0.0]
+                    } else {
+                        na::vector![0.0, doc_pos]
+                    };
+
+                    // Pick up an existing guide when starting on its marker, else add a new one.
+                    let existing = canvas.engine_ref().document.guide_near(
+                        orientation,
+                        pos,
+                        super::RnRuler::PICKUP_DIST / total_zoom,
+                    );
+                    let index = match existing {
+                        Some(index) => index,
+                        None => {
+                            let (index, widget_flags) = canvas
+                                .engine_mut()
+                                .add_guide(Guide::new(orientation, doc_pos));
+                            canvas.emit_handle_widget_flags(widget_flags);
+                            index
+                        }
+                    };
+                    ruler.imp().dragged_guide.set(Some(index));
+                    ruler.queue_draw();
+                }
+            ));
+
+            drag_gesture.connect_drag_update(clone!(
+                #[weak(rename_to=ruler)]
+                obj,
+                move |gesture, x, y| {
+                    let Some(canvas) = ruler.imp().canvas.upgrade() else {
+                        return;
+                    };
+                    let Some(index) = ruler.imp().dragged_guide.get() else {
+                        return;
+                    };
+                    let Some((start_x, start_y)) = gesture.start_point() else {
+                        return;
+                    };
+                    let horizontal = ruler.imp().orientation.get() == Orientation::Horizontal;
+                    let along = if horizontal { start_x + x } else { start_y + y };
+                    let Some((doc_pos, _)) = ruler.doc_coord_at(&canvas, along) else {
+                        return;
+                    };
+                    let widget_flags = canvas.engine_mut().move_guide(index, doc_pos);
+                    canvas.emit_handle_widget_flags(widget_flags);
+                    ruler.queue_draw();
+                }
+            ));
+
+            drag_gesture.connect_drag_end(clone!(
+                #[weak(rename_to=ruler)]
+                obj,
+                move |gesture, x, y| {
+                    let Some(index) = ruler.imp().dragged_guide.take() else {
+                        return;
+                    };
+                    let Some(canvas) = ruler.imp().canvas.upgrade() else {
+                        return;
+                    };
+                    let Some((start_x, start_y)) = gesture.start_point() else {
+                        return;
+                    };
+                    let horizontal = ruler.imp().orientation.get() == Orientation::Horizontal;
+                    let (along, length) = if horizontal {
+                        (start_x + x, ruler.width() as f64)
+                    } else {
+                        (start_y + y, ruler.height() as f64)
+                    };
+
+                    // Dragging a guide off the ruler removes it.
+                    if !(0.0..=length).contains(&along) {
+                        let widget_flags = canvas.engine_mut().remove_guide(index);
+                        canvas.emit_handle_widget_flags(widget_flags);
+                    }
+                    ruler.queue_draw();
+                }
+            ));
+        }
+    }
+}
+
+glib::wrapper! {
+    pub(crate) struct RnRuler(ObjectSubclass<imp::RnRuler>)
+        @extends Widget,
+        @implements gtk4::Accessible, gtk4::Buildable, gtk4::ConstraintTarget, gtk4::Orientable;
+}
+
+impl Default for RnRuler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RnRuler {
+    /// The ruler thickness in pixels.
+    pub(crate) const THICKNESS: i32 = 20;
+    /// The distance in pixels in which guide markers are picked up.
+    const PICKUP_DIST: f64 = 6.0;
+
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub(crate) fn set_canvas(&self, canvas: &RnCanvas) {
+        self.imp().canvas.set(Some(canvas));
+        self.queue_draw();
+    }
+
+    /// The document coordinate along the ruler axis at the given ruler position, and the current total zoom.
+    fn doc_coord_at(&self, canvas: &RnCanvas, along: f64) -> Option<(f64, f64)> {
+        let horizontal = self.imp().orientation.get() == Orientation::Horizontal;
+        let point = if horizontal {
+            graphene::Point::new(along as f32, 0.0)
+        } else {
+            graphene::Point::new(0.0, along as f32)
+        };
+        let canvas_point = self.compute_point(canvas, &point)?;
+        let engine = canvas.engine_ref();
+        let viewport = engine.camera.viewport();
+        let total_zoom = engine.camera.total_zoom();
+
+        Some(if horizontal {
+            (
+                viewport.mins[0] + canvas_point.x() as f64 / total_zoom,
+                total_zoom,
+            )
+        } else {
+            (
+                viewport.mins[1] + canvas_point.y() as f64 / total_zoom,
+                total_zoom,
+            )
+        })
+    }
+}
+
+/// Guides dragged from a horizontal ruler are vertical lines and vice versa.
+fn guide_orientation(horizontal_ruler: bool) -> GuideOrientation {
+    if horizontal_ruler {
+        GuideOrientation::Vertical
+    } else {
+        GuideOrientation::Horizontal
+    }
+}
+
+/// The distance between major ticks in document coordinates, so that they are spaced at least 50 pixels apart.
+fn major_tick_step(total_zoom: f64) -> f64 {
+    const MIN_SPACING: f64 = 50.0;
+    let min_step = MIN_SPACING / total_zoom;
+    let magnitude = 10_f64.powf(min_step.log10().floor());
+
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|step| *step >= min_step)
+        .unwrap_or(magnitude * 10.0)
+}
@@ -7,20 +7,26 @@ pub(crate) use penshortcutrow::RnPenShortcutRow;
 use rnote_compose::ext::Vector2Ext;
 
 // Imports
-use crate::{RnAppWindow, RnCanvasWrapper, RnIconPicker, RnUnitEntry};
+use crate::{dialogs, RnAppWindow, RnCanvasWrapper, RnIconPicker, RnUnitEntry};
 use adw::prelude::*;
 use gettextrs::{gettext, pgettext};
 use gtk4::{
-    gdk, glib, glib::clone, subclass::prelude::*, Adjustment, Button, ColorDialogButton,
+    gdk, gio, glib, glib::clone, subclass::prelude::*, Adjustment, Button, ColorDialogButton,
     CompositeTemplate, MenuButton, ScrolledWindow, StringList, ToggleButton, Widget,
 };
 use num_traits::ToPrimitive;
 use rnote_compose::penevent::ShortcutKey;
-use rnote_engine::document::background::PatternStyle;
-use rnote_engine::document::format::{self, Format, PredefinedFormat};
-use rnote_engine::document::Layout;
+use rnote_engine::audioplayer::PenSoundsConfig;
+use rnote_engine::document::background::{PatternStyle, PatternTile};
+use rnote_engine::document::format::{self, CustomFormat, Format, PredefinedFormat};
+use rnote_engine::document::{ExpandDirection, Layout};
+use rnote_engine::engine::VisualSettings;
 use rnote_engine::ext::GdkRGBAExt;
+use rnote_engine::fileformats::rnoteformat::CompressionMethod;
+use rnote_engine::pens::PenStyle;
+use rnote_engine::{Document, WidgetFlags};
 use std::cell::RefCell;
+use tracing::error;
 
 mod imp {
     use super::*;
@@ -30,6 +36,9 @@ mod imp {
     pub(crate) struct RnSettingsPanel {
         pub(crate) temporary_format: RefCell<Format>,
         pub(crate) app_restart_toast_singleton: RefCell<Option<adw::Toast>>,
+        pub(crate) snippet_rows: RefCell<Vec<adw::ActionRow>>,
+        pub(crate) custom_format_rows: RefCell<Vec<adw::ActionRow>>,
+        pub(crate) input_device_rows: RefCell<Vec<adw::SwitchRow>>,
 
         #[template_child]
         pub(crate) settings_scroller: TemplateChild<ScrolledWindow>,
@@ -40,18 +49,40 @@ mod imp {
         #[template_child]
         pub(crate) general_show_scrollbars_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub(crate) general_show_rulers_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) general_show_page_thumbnails_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub(crate) general_optimize_epd_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub(crate) general_revision_history_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) general_revision_history_max_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) general_restore_view_state_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub(crate) general_inertial_scrolling_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
+        pub(crate) general_replay_speed_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
         pub(crate) general_regular_cursor_picker: TemplateChild<RnIconPicker>,
         #[template_child]
         pub(crate) general_regular_cursor_picker_menubutton: TemplateChild<MenuButton>,
         #[template_child]
+        pub(crate) general_custom_accent_color_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) general_accent_color_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(crate) general_accent_color_button: TemplateChild<ColorDialogButton>,
+        #[template_child]
         pub(crate) general_show_drawing_cursor_row: TemplateChild<adw::SwitchRow>,
         #[template_child]
         pub(crate) general_drawing_cursor_picker_row: TemplateChild<adw::ActionRow>,
         #[template_child]
+        pub(crate) accessibility_high_contrast_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) accessibility_reduced_motion_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub(crate) general_drawing_cursor_picker: TemplateChild<RnIconPicker>,
         #[template_child]
         pub(crate) general_drawing_cursor_picker_menubutton: TemplateChild<MenuButton>,
@@ -76,12 +107,54 @@ mod imp {
         #[template_child]
         pub(crate) format_dpi_adj: TemplateChild<Adjustment>,
         #[template_child]
+        pub(crate) format_scale_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) format_scale_adj: TemplateChild<Adjustment>,
+        #[template_child]
+        pub(crate) format_unit_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
         pub(crate) format_revert_button: TemplateChild<Button>,
         #[template_child]
         pub(crate) format_apply_button: TemplateChild<Button>,
         #[template_child]
+        pub(crate) format_custom_formats_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub(crate) format_custom_formats_add_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
+        pub(crate) pen_sounds_theme_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) pen_sounds_brush_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) pen_sounds_typewriter_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) pen_sounds_spatial_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) pen_sounds_volume_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) input_palm_rejection_timeout_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
+        pub(crate) input_blocked_devices_row: TemplateChild<adw::ExpanderRow>,
+        #[template_child]
+        pub(crate) input_shift_scroll_horizontal_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) input_wheel_zoom_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) input_zoom_around_pointer_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) input_secondary_drag_pan_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) snippets_group: TemplateChild<adw::PreferencesGroup>,
+        #[template_child]
+        pub(crate) snippets_add_row: TemplateChild<adw::EntryRow>,
+        #[template_child]
         pub(crate) doc_document_layout_row: TemplateChild<adw::ComboRow>,
         #[template_child]
+        pub(crate) doc_infinite_expand_direction_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) doc_two_page_spread_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
+        pub(crate) doc_clamp_panning_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub(crate) doc_format_border_color_button: TemplateChild<ColorDialogButton>,
         #[template_child]
         pub(crate) doc_background_color_button: TemplateChild<ColorDialogButton>,
@@ -90,12 +163,22 @@ mod imp {
         #[template_child]
         pub(crate) doc_background_pattern_color_button: TemplateChild<ColorDialogButton>,
         #[template_child]
+        pub(crate) doc_background_pattern_tile_row: TemplateChild<adw::ActionRow>,
+        #[template_child]
+        pub(crate) doc_background_pattern_tile_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) doc_background_pattern_tile_embed_row: TemplateChild<adw::SwitchRow>,
+        #[template_child]
         pub(crate) doc_background_pattern_width_unitentry: TemplateChild<RnUnitEntry>,
         #[template_child]
         pub(crate) doc_background_pattern_height_unitentry: TemplateChild<RnUnitEntry>,
         #[template_child]
+        pub(crate) doc_onion_skinning_opacity_row: TemplateChild<adw::SpinRow>,
+        #[template_child]
         pub(crate) background_pattern_invert_color_button: TemplateChild<Button>,
         #[template_child]
+        pub(crate) penmode_eraser_end_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
         pub(crate) penshortcut_stylus_button_primary_row: TemplateChild<RnPenShortcutRow>,
         #[template_child]
         pub(crate) penshortcut_stylus_button_secondary_row: TemplateChild<RnPenShortcutRow>,
@@ -113,6 +196,10 @@ mod imp {
         pub(crate) penshortcut_drawing_pad_button_2: TemplateChild<RnPenShortcutRow>,
         #[template_child]
         pub(crate) penshortcut_drawing_pad_button_3: TemplateChild<RnPenShortcutRow>,
+        #[template_child]
+        pub(crate) advanced_rnote_compression_method_row: TemplateChild<adw::ComboRow>,
+        #[template_child]
+        pub(crate) advanced_rnote_compression_level_row: TemplateChild<adw::SpinRow>,
     }
 
     #[glib::object_subclass]
@@ -224,6 +311,31 @@ mod imp {
                         .set_dpi(adj.value());
                 }
             ));
+
+            self.format_scale_adj.connect_value_changed(clone!(
+                #[weak(rename_to=settings_panel)]
+                obj,
+                move |adj| {
+                    settings_panel
+                        .imp()
+                        .temporary_format
+                        .borrow_mut()
+                        .set_scale(adj.value());
+                }
+            ));
+
+            self.format_unit_row.connect_selected_notify(clone!(
+                #[weak(rename_to=settings_panel)]
+                obj,
+                move |row| {
+                    match format::MeasureUnit::try_from(row.selected()) {
+                        Ok(unit) => settings_panel.imp().temporary_format.borrow_mut().unit = unit,
+                        Err(e) => {
+                            error!("Converting the selected dimension unit failed, Err: {e:?}")
+                        }
+                    }
+                }
+            ));
         }
 
         fn dispose(&self) {
@@ -366,6 +478,30 @@ impl RnSettingsPanel {
         self.imp().general_regular_cursor_picker.clone()
     }
 
+    pub(crate) fn general_custom_accent_color_row(&self) -> adw::SwitchRow {
+        self.imp().general_custom_accent_color_row.clone()
+    }
+
+    pub(crate) fn general_accent_color_button(&self) -> ColorDialogButton {
+        self.imp().general_accent_color_button.clone()
+    }
+
+    pub(crate) fn input_shift_scroll_horizontal_row(&self) -> adw::SwitchRow {
+        self.imp().input_shift_scroll_horizontal_row.clone()
+    }
+
+    pub(crate) fn input_wheel_zoom_row(&self) -> adw::SwitchRow {
+        self.imp().input_wheel_zoom_row.clone()
+    }
+
+    pub(crate) fn input_zoom_around_pointer_row(&self) -> adw::SwitchRow {
+        self.imp().input_zoom_around_pointer_row.clone()
+    }
+
+    pub(crate) fn input_secondary_drag_pan_row(&self) -> adw::SwitchRow {
+        self.imp().input_secondary_drag_pan_row.clone()
+    }
+
     pub(crate) fn general_show_drawing_cursor_row(&self) -> adw::SwitchRow {
         self.imp().general_show_drawing_cursor_row.clone()
     }
@@ -374,10 +510,22 @@ impl RnSettingsPanel {
         self.imp().general_drawing_cursor_picker.clone()
     }
 
+    pub(crate) fn general_restore_view_state_row(&self) -> adw::SwitchRow {
+        self.imp().general_restore_view_state_row.clone()
+    }
+
     pub(crate) fn general_show_scrollbars_row(&self) -> adw::SwitchRow {
         self.imp().general_show_scrollbars_row.clone()
     }
 
+    pub(crate) fn general_show_rulers_row(&self) -> adw::SwitchRow {
+        self.imp().general_show_rulers_row.clone()
+    }
+
+    pub(crate) fn general_show_page_thumbnails_row(&self) -> adw::SwitchRow {
+        self.imp().general_show_page_thumbnails_row.clone()
+    }
+
     pub(crate) fn general_inertial_scrolling_row(&self) -> adw::SwitchRow {
         self.imp().general_inertial_scrolling_row.clone()
     }
@@ -392,11 +540,62 @@ impl RnSettingsPanel {
             .set_selected(layout.to_u32().unwrap());
     }
 
+    pub(crate) fn infinite_expand_direction(&self) -> ExpandDirection {
+        ExpandDirection::try_from(
+            self.imp()
+                .doc_infinite_expand_direction_row
+                .get()
+                .selected(),
+        )
+        .unwrap()
+    }
+
+    pub(crate) fn set_infinite_expand_direction(&self, expand_direction: ExpandDirection) {
+        self.imp()
+            .doc_infinite_expand_direction_row
+            .set_selected(expand_direction.to_u32().unwrap());
+    }
+
+    /// The pen style the stylus eraser end switches to. `None` keeps the last used style.
+    pub(crate) fn eraser_end_pen_style(&self) -> Option<PenStyle> {
+        // The first entry is "Last Used", the following follow the order of the pen styles
+        match self.imp().penmode_eraser_end_row.get().selected() {
+            0 => None,
+            i => PenStyle::try_from(i - 1).ok(),
+        }
+    }
+
+    pub(crate) fn set_eraser_end_pen_style(&self, style: Option<PenStyle>) {
+        self.imp()
+            .penmode_eraser_end_row
+            .set_selected(style.map(|style| style.to_u32().unwrap() + 1).unwrap_or(0));
+    }
+
+    pub(crate) fn rnote_compression_method(&self) -> CompressionMethod {
+        CompressionMethod::try_from(
+            self.imp()
+                .advanced_rnote_compression_method_row
+                .get()
+                .selected(),
+        )
+        .unwrap()
+    }
+
+    /// Limit the compression level row to the levels supported by the method.
+    fn set_rnote_compression_level_range(&self, method: CompressionMethod) {
+        let level_range = method.level_range();
+        self.imp()
+            .advanced_rnote_compression_level_row
+            .set_range(*level_range.start() as f64, *level_range.end() as f64);
+    }
+
     pub(crate) fn refresh_ui(&self, active_tab: &RnCanvasWrapper) {
         self.refresh_general_ui(active_tab);
+        self.refresh_pen_sounds_ui(active_tab);
         self.refresh_format_ui(active_tab);
         self.refresh_doc_ui(active_tab);
         self.refresh_shortcuts_ui(active_tab);
+        self.refresh_advanced_ui(active_tab);
     }
 
     fn refresh_general_ui(&self, active_tab: &RnCanvasWrapper) {
@@ -405,11 +604,43 @@ impl RnSettingsPanel {
 
         let format_border_color = canvas.engine_ref().document.format.border_color;
         let optimize_epd = canvas.engine_ref().optimize_epd();
+        let revision_history_prefs = canvas.engine_ref().export_prefs.revision_history_prefs;
 
         imp.doc_format_border_color_button
             .set_rgba(&gdk::RGBA::from_compose_color(format_border_color));
 
         imp.general_optimize_epd_row.set_active(optimize_epd);
+        imp.general_revision_history_row
+            .set_active(revision_history_prefs.enabled);
+        imp.general_revision_history_max_row
+            .set_value(revision_history_prefs.max_revisions as f64);
+    }
+
+    fn refresh_pen_sounds_ui(&self, active_tab: &RnCanvasWrapper) {
+        let imp = self.imp();
+        let pen_sounds_config = active_tab.canvas().engine_ref().pen_sounds_config().clone();
+
+        let theme_index = pen_sounds_config
+            .theme_dir
+            .as_ref()
+            .and_then(|theme_dir| theme_dir.file_name())
+            .and_then(|theme_name| {
+                sound_theme_names()
+                    .iter()
+                    .position(|name| theme_name == name.as_str())
+            })
+            // The first entry is the builtin theme
+            .map(|i| i as u32 + 1)
+            .unwrap_or(0);
+        imp.pen_sounds_theme_row.set_selected(theme_index);
+        imp.pen_sounds_brush_row
+            .set_active(pen_sounds_config.brush_sounds);
+        imp.pen_sounds_typewriter_row
+            .set_active(pen_sounds_config.typewriter_sounds);
+        imp.pen_sounds_spatial_row
+            .set_active(pen_sounds_config.spatial);
+        imp.pen_sounds_volume_row
+            .set_value(pen_sounds_config.volume);
     }
 
     fn refresh_format_ui(&self, active_tab: &RnCanvasWrapper) {
@@ -425,34 +656,69 @@ impl RnSettingsPanel {
         imp.format_width_unitentry.set_value_in_px(format.width());
         imp.format_height_unitentry.set_dpi(format.dpi());
         imp.format_height_unitentry.set_value_in_px(format.height());
+        imp.format_scale_adj.set_value(format.scale());
+        imp.format_unit_row
+            .set_selected(format.unit.to_u32().unwrap());
     }
 
     fn refresh_doc_ui(&self, active_tab: &RnCanvasWrapper) {
         let imp = self.imp();
         let canvas = active_tab.canvas();
-        let background = canvas.engine_ref().document.background;
+        let background = canvas.engine_ref().document.background.clone();
         let format = canvas.engine_ref().document.format;
         let document_layout = canvas.engine_ref().document.layout;
+        let infinite_expand_direction = canvas.engine_ref().document.infinite_expand_direction;
+        let two_page_spread = canvas.engine_ref().document.two_page_spread;
+        let clamp_panning = canvas.engine_ref().document.clamp_panning;
+        let onion_skinning_opacity = canvas.engine_ref().document.onion_skinning_opacity;
 
         imp.doc_background_color_button
             .set_rgba(&gdk::RGBA::from_compose_color(background.color));
         self.set_background_pattern(background.pattern);
+        match &background.pattern_tile {
+            Some(PatternTile::Reference { path }) => {
+                imp.doc_background_pattern_tile_row
+                    .set_subtitle(&path.display().to_string());
+                imp.doc_background_pattern_tile_embed_row.set_active(false);
+            }
+            Some(PatternTile::Embedded { .. }) => {
+                imp.doc_background_pattern_tile_row
+                    .set_subtitle(&gettext("Embedded in the document"));
+                imp.doc_background_pattern_tile_embed_row.set_active(true);
+            }
+            None => {
+                imp.doc_background_pattern_tile_row.set_subtitle(&gettext(
+                    "Choose an image or Svg file that is tiled as custom pattern",
+                ));
+            }
+        }
         imp.doc_background_pattern_color_button
             .set_rgba(&gdk::RGBA::from_compose_color(background.pattern_color));
         imp.doc_background_pattern_width_unitentry
             .set_dpi(format.dpi());
+        imp.doc_background_pattern_width_unitentry
+            .set_scale(format.scale());
         imp.doc_background_pattern_width_unitentry
             .set_value_in_px(background.pattern_size[0]);
         imp.doc_background_pattern_height_unitentry
             .set_dpi(format.dpi());
+        imp.doc_background_pattern_height_unitentry
+            .set_scale(format.scale());
         imp.doc_background_pattern_height_unitentry
             .set_value_in_px(background.pattern_size[1]);
         self.set_document_layout(&document_layout);
+        self.set_infinite_expand_direction(infinite_expand_direction);
+        imp.doc_two_page_spread_row.set_active(two_page_spread);
+        imp.doc_clamp_panning_row.set_active(clamp_panning);
+        imp.doc_onion_skinning_opacity_row
+            .set_value(onion_skinning_opacity);
     }
 
     fn refresh_shortcuts_ui(&self, active_tab: &RnCanvasWrapper) {
         let imp = self.imp();
         let canvas = active_tab.canvas();
+
+        self.set_eraser_end_pen_style(canvas.engine_ref().pens_config.eraser_end_pen_style);
         let current_shortcuts = canvas.engine_ref().penholder.list_current_shortcuts();
 
         current_shortcuts
@@ -491,11 +757,31 @@ impl RnSettingsPanel {
             });
     }
 
+    fn refresh_advanced_ui(&self, active_tab: &RnCanvasWrapper) {
+        let imp = self.imp();
+        let compression_prefs = active_tab
+            .canvas()
+            .engine_ref()
+            .export_prefs
+            .rnote_compression_prefs;
+
+        self.set_rnote_compression_level_range(compression_prefs.method);
+        imp.advanced_rnote_compression_method_row
+            .set_selected(compression_prefs.method.to_u32().unwrap());
+        imp.advanced_rnote_compression_level_row
+            .set_value(compression_prefs.level as f64);
+    }
+
     pub(crate) fn init(&self, appwindow: &RnAppWindow) {
         self.setup_general(appwindow);
+        self.setup_accessibility(appwindow);
+        self.setup_input(appwindow);
+        self.setup_pen_sounds(appwindow);
+        self.setup_snippets(appwindow);
         self.setup_format(appwindow);
         self.setup_doc(appwindow);
         self.setup_shortcuts(appwindow);
+        self.setup_advanced(appwindow);
     }
 
     fn setup_general(&self, appwindow: &RnAppWindow) {
@@ -527,6 +813,13 @@ impl RnSettingsPanel {
             .bidirectional()
             .build();
 
+        imp.general_replay_speed_row
+            .get()
+            .bind_property("value", appwindow, "replay-speed")
+            .sync_create()
+            .bidirectional()
+            .build();
+
         let set_overlays_margins = |appwindow: &RnAppWindow, row_active: bool| {
             let (m1, m2) = if row_active { (18, 72) } else { (9, 63) };
             appwindow.overlays().colorpicker().set_margin_top(m1);
@@ -569,6 +862,49 @@ impl RnSettingsPanel {
             }
         ));
 
+        imp.general_revision_history_row
+            .bind_property(
+                "active",
+                &*imp.general_revision_history_max_row,
+                "sensitive",
+            )
+            .sync_create()
+            .build();
+
+        imp.general_revision_history_row
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+
+                    canvas
+                        .engine_mut()
+                        .export_prefs
+                        .revision_history_prefs
+                        .enabled = row.is_active();
+                }
+            ));
+
+        imp.general_revision_history_max_row
+            .connect_value_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+
+                    canvas
+                        .engine_mut()
+                        .export_prefs
+                        .revision_history_prefs
+                        .max_revisions = row.value().round() as u32;
+                }
+            ));
+
         // Regular cursor picker
         imp.general_regular_cursor_picker.set_list(
             StringList::new(CURSORS_LIST),
@@ -629,108 +965,571 @@ impl RnSettingsPanel {
             ));
     }
 
-    fn setup_format(&self, appwindow: &RnAppWindow) {
+    fn setup_pen_sounds(&self, appwindow: &RnAppWindow) {
         let imp = self.imp();
 
-        // revert format
-        imp.format_revert_button.get().connect_clicked(clone!(
-            #[weak(rename_to=settings_panel)]
-            self,
+        let theme_list = StringList::new(&[gettext("Default").as_str()]);
+        for name in sound_theme_names() {
+            theme_list.append(&name);
+        }
+        imp.pen_sounds_theme_row.set_model(Some(&theme_list));
+
+        imp.pen_sounds_theme_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let theme_dir = row
+                        .selected_item()
+                        .and_downcast::<gtk4::StringObject>()
+                        .filter(|_| row.selected() > 0)
+                        .map(|theme| crate::env::sound_themes_dir().join(theme.string()));
+                    update_pen_sounds_config(&appwindow, |config| config.theme_dir = theme_dir);
+                }
+            ));
+
+        imp.pen_sounds_brush_row.connect_active_notify(clone!(
             #[weak]
             appwindow,
-            move |_format_revert_button| {
-                settings_panel.revert_format(&appwindow);
+            move |row| {
+                let active = row.is_active();
+                update_pen_sounds_config(&appwindow, |config| config.brush_sounds = active);
             }
         ));
 
-        // Apply format
-        imp.format_apply_button.get().connect_clicked(clone!(
-            #[weak(rename_to=settingspanel)]
-            self,
+        imp.pen_sounds_typewriter_row.connect_active_notify(clone!(
             #[weak]
             appwindow,
-            move |_| {
-                settingspanel.apply_format(&appwindow);
+            move |row| {
+                let active = row.is_active();
+                update_pen_sounds_config(&appwindow, |config| config.typewriter_sounds = active);
+            }
+        ));
+
+        imp.pen_sounds_spatial_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let active = row.is_active();
+                update_pen_sounds_config(&appwindow, |config| config.spatial = active);
+            }
+        ));
+
+        imp.pen_sounds_volume_row.connect_value_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let volume = row
+                    .value()
+                    .clamp(PenSoundsConfig::VOLUME_MIN, PenSoundsConfig::VOLUME_MAX);
+                update_pen_sounds_config(&appwindow, |config| config.volume = volume);
             }
         ));
     }
 
-    fn setup_doc(&self, appwindow: &RnAppWindow) {
+    /// The visual settings for the engine, as set in the accessibility group.
+    pub(crate) fn visual_settings(&self) -> VisualSettings {
+        let imp = self.imp();
+        VisualSettings {
+            high_contrast: imp.accessibility_high_contrast_row.is_active(),
+            reduced_motion: imp.accessibility_reduced_motion_row.is_active(),
+            accent_color: imp
+                .general_custom_accent_color_row
+                .is_active()
+                .then(|| imp.general_accent_color_button.rgba().into_compose_color()),
+        }
+    }
+
+    fn setup_accessibility(&self, appwindow: &RnAppWindow) {
         let imp = self.imp();
 
-        imp.doc_format_border_color_button
-            .connect_rgba_notify(clone!(
+        // Follow the system settings initially and when they change
+        adw::StyleManager::default()
+            .bind_property(
+                "high-contrast",
+                &*imp.accessibility_high_contrast_row,
+                "active",
+            )
+            .sync_create()
+            .build();
+        if let Some(gtk_settings) = gtk4::Settings::default() {
+            gtk_settings
+                .bind_property(
+                    "gtk-enable-animations",
+                    &*imp.accessibility_reduced_motion_row,
+                    "active",
+                )
+                .invert_boolean()
+                .sync_create()
+                .build();
+        }
+
+        imp.accessibility_high_contrast_row
+            .connect_active_notify(clone!(
                 #[weak(rename_to=settingspanel)]
                 self,
                 #[weak]
                 appwindow,
-                move |button| {
-                    let format_border_color = button.rgba().into_compose_color();
-                    let Some(canvas) = appwindow.active_tab_canvas() else {
-                        return;
-                    };
-
-                    // Because the format border color is applied immediately to the engine,
-                    // we need to update the temporary format too.
-                    settingspanel
-                        .imp()
-                        .temporary_format
-                        .borrow_mut()
-                        .border_color = format_border_color;
-                    let current_color = canvas.engine_ref().document.format.border_color;
-
-                    if !current_color.approx_eq_f32(format_border_color) {
-                        canvas.engine_mut().document.format.border_color = format_border_color;
-                        let mut widget_flags =
-                            canvas.engine_mut().update_rendering_current_viewport();
-                        widget_flags.store_modified = true;
-                        appwindow.handle_widget_flags(widget_flags, &canvas);
-                    }
+                move |_| {
+                    settingspanel.update_visual_settings(&appwindow);
                 }
             ));
 
-        imp.doc_background_color_button.connect_rgba_notify(clone!(
-            #[weak]
-            appwindow,
-            move |button| {
-                let background_color = button.rgba().into_compose_color();
-                let Some(canvas) = appwindow.active_tab_canvas() else {
-                    return;
-                };
-
-                if !canvas
-                    .engine_ref()
-                    .document
-                    .background
-                    .color
-                    .approx_eq_f32(background_color)
-                {
-                    canvas.engine_mut().document.background.color = background_color;
-                    let mut widget_flags = canvas.engine_mut().background_rendering_regenerate();
-                    widget_flags.store_modified = true;
-                    appwindow.handle_widget_flags(widget_flags, &canvas);
-                }
-            }
-        ));
-
-        imp.doc_document_layout_row
-            .get()
-            .connect_selected_item_notify(clone!(
-                #[weak(rename_to=settings_panel)]
+        imp.accessibility_reduced_motion_row
+            .connect_active_notify(clone!(
+                #[weak(rename_to=settingspanel)]
                 self,
                 #[weak]
                 appwindow,
                 move |_| {
-                    let document_layout = settings_panel.document_layout();
-                    let Some(canvas) = appwindow.active_tab_canvas() else {
-                        return;
-                    };
+                    settingspanel.update_visual_settings(&appwindow);
+                }
+            ));
 
-                    appwindow
+        // The accent color is applied to the interface by the app, only the drawn overlays are updated here
+        imp.general_custom_accent_color_row
+            .bind_property("active", &*imp.general_accent_color_row, "sensitive")
+            .sync_create()
+            .build();
+
+        imp.general_custom_accent_color_row
+            .connect_active_notify(clone!(
+                #[weak(rename_to=settingspanel)]
+                self,
+                #[weak]
+                appwindow,
+                move |_| {
+                    settingspanel.update_visual_settings(&appwindow);
+                }
+            ));
+
+        imp.general_accent_color_button.connect_rgba_notify(clone!(
+            #[weak(rename_to=settingspanel)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                settingspanel.update_visual_settings(&appwindow);
+            }
+        ));
+    }
+
+    fn update_visual_settings(&self, appwindow: &RnAppWindow) {
+        let visual_settings = self.visual_settings();
+
+        for wrapper in appwindow.get_all_tabs() {
+            let canvas = wrapper.canvas();
+            let widget_flags = canvas.engine_mut().set_visual_settings(visual_settings);
+            appwindow.handle_widget_flags(widget_flags, &canvas);
+        }
+    }
+
+    fn setup_input(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        imp.input_palm_rejection_timeout_row
+            .get()
+            .bind_property("value", appwindow, "palm-rejection-timeout-ms")
+            .transform_to(|_, val: f64| Some((val.round() as u32).to_value()))
+            .transform_from(|_, val: u32| Some(f64::from(val).to_value()))
+            .sync_create()
+            .bidirectional()
+            .build();
+
+        self.refresh_input_device_rows(appwindow);
+        appwindow.connect_notify_local(
+            Some("blocked-input-devices"),
+            clone!(
+                #[weak(rename_to=settingspanel)]
+                self,
+                move |appwindow, _| {
+                    settingspanel.refresh_input_device_rows(appwindow);
+                }
+            ),
+        );
+        if let Some(seat) = gdk::Display::default().and_then(|display| display.default_seat()) {
+            seat.connect_device_added(clone!(
+                #[weak(rename_to=settingspanel)]
+                self,
+                #[weak]
+                appwindow,
+                move |_, _| {
+                    settingspanel.refresh_input_device_rows(&appwindow);
+                }
+            ));
+            seat.connect_device_removed(clone!(
+                #[weak(rename_to=settingspanel)]
+                self,
+                #[weak]
+                appwindow,
+                move |_, _| {
+                    settingspanel.refresh_input_device_rows(&appwindow);
+                }
+            ));
+        }
+    }
+
+    /// Lists the connected input devices together with the blocked devices that are currently not connected.
+    fn refresh_input_device_rows(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        for row in imp.input_device_rows.borrow_mut().drain(..) {
+            imp.input_blocked_devices_row.remove(&row);
+        }
+
+        let blocked_input_devices = appwindow.blocked_input_devices();
+        let mut device_names = gdk::Display::default()
+            .and_then(|display| display.default_seat())
+            .map(|seat| {
+                seat.devices(gdk::SeatCapabilities::ALL)
+                    .iter()
+                    .map(|device| device.name().to_string())
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default();
+        device_names.extend(blocked_input_devices.iter().cloned());
+        device_names.sort();
+        device_names.dedup();
+
+        for device_name in device_names {
+            let row = adw::SwitchRow::builder()
+                .title(glib::markup_escape_text(&device_name))
+                .active(blocked_input_devices.contains(&device_name))
+                .build();
+            row.connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let mut blocked_input_devices = appwindow.blocked_input_devices();
+                    blocked_input_devices.retain(|blocked| *blocked != device_name);
+                    if row.is_active() {
+                        blocked_input_devices.push(device_name.clone());
+                    }
+                    appwindow.set_blocked_input_devices(blocked_input_devices);
+                }
+            ));
+            imp.input_blocked_devices_row.add_row(&row);
+            imp.input_device_rows.borrow_mut().push(row);
+        }
+    }
+
+    fn setup_snippets(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        let Some(app_settings) = appwindow.app().app_settings() else {
+            imp.snippets_group.set_sensitive(false);
+            return;
+        };
+
+        self.refresh_snippet_rows(&app_settings);
+        app_settings.connect_changed(
+            Some("typewriter-snippets"),
+            clone!(
+                #[weak(rename_to=settingspanel)]
+                self,
+                move |app_settings, _| {
+                    settingspanel.refresh_snippet_rows(app_settings);
+                }
+            ),
+        );
+
+        imp.snippets_add_row.connect_apply(clone!(
+            #[weak]
+            app_settings,
+            move |row| {
+                let snippet = row.text();
+                if snippet.is_empty() {
+                    return;
+                }
+                let mut snippets = app_settings.strv("typewriter-snippets");
+                snippets.push(snippet);
+                if let Err(e) = app_settings.set_strv("typewriter-snippets", snippets) {
+                    error!("Failed to set setting `typewriter-snippets`, Err: {e:?}");
+                }
+                row.set_text("");
+            }
+        ));
+    }
+
+    fn refresh_snippet_rows(&self, app_settings: &gio::Settings) {
+        let imp = self.imp();
+        for row in imp.snippet_rows.borrow_mut().drain(..) {
+            imp.snippets_group.remove(&row);
+        }
+
+        for (i, snippet) in app_settings.strv("typewriter-snippets").iter().enumerate() {
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(snippet.as_str()))
+                .build();
+            let remove_button = Button::builder()
+                .icon_name("trash-symbolic")
+                .tooltip_text(gettext("Remove Snippet"))
+                .valign(gtk4::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            remove_button.connect_clicked(clone!(
+                #[weak]
+                app_settings,
+                move |_| {
+                    let mut snippets = app_settings
+                        .strv("typewriter-snippets")
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect::<Vec<String>>();
+                    if i < snippets.len() {
+                        snippets.remove(i);
+                    }
+                    if let Err(e) =
+                        app_settings.set_strv("typewriter-snippets", snippets.as_slice())
+                    {
+                        error!("Failed to set setting `typewriter-snippets`, Err: {e:?}");
+                    }
+                }
+            ));
+            row.add_suffix(&remove_button);
+            imp.snippets_group.add(&row);
+            imp.snippet_rows.borrow_mut().push(row);
+        }
+    }
+
+    fn setup_format(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        // revert format
+        imp.format_revert_button.get().connect_clicked(clone!(
+            #[weak(rename_to=settings_panel)]
+            self,
+            #[weak]
+            appwindow,
+            move |_format_revert_button| {
+                settings_panel.revert_format(&appwindow);
+            }
+        ));
+
+        // Apply format
+        imp.format_apply_button.get().connect_clicked(clone!(
+            #[weak(rename_to=settingspanel)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                settingspanel.apply_format(&appwindow);
+            }
+        ));
+
+        let Some(app_settings) = appwindow.app().app_settings() else {
+            imp.format_custom_formats_group.set_sensitive(false);
+            return;
+        };
+
+        self.refresh_custom_format_rows(&app_settings);
+        app_settings.connect_changed(
+            Some("custom-formats"),
+            clone!(
+                #[weak(rename_to=settingspanel)]
+                self,
+                move |app_settings, _| {
+                    settingspanel.refresh_custom_format_rows(app_settings);
+                }
+            ),
+        );
+
+        // Save the current temporary format under the entered name, replacing a format with the same name
+        imp.format_custom_formats_add_row.connect_apply(clone!(
+            #[weak(rename_to=settingspanel)]
+            self,
+            #[weak]
+            app_settings,
+            move |row| {
+                let name = row.text().trim().to_string();
+                if name.is_empty() {
+                    return;
+                }
+                let custom_format =
+                    CustomFormat::from_format(name, &settingspanel.imp().temporary_format.borrow());
+                let mut custom_formats = custom_formats(&app_settings);
+                match custom_formats
+                    .iter_mut()
+                    .find(|f| f.name == custom_format.name)
+                {
+                    Some(existing) => *existing = custom_format,
+                    None => custom_formats.push(custom_format),
+                }
+                set_custom_formats(&app_settings, &custom_formats);
+                row.set_text("");
+            }
+        ));
+    }
+
+    fn refresh_custom_format_rows(&self, app_settings: &gio::Settings) {
+        let imp = self.imp();
+        for row in imp.custom_format_rows.borrow_mut().drain(..) {
+            imp.format_custom_formats_group.remove(&row);
+        }
+
+        for (i, custom_format) in custom_formats(app_settings).into_iter().enumerate() {
+            let width_mm = format::MeasureUnit::convert_measurement(
+                custom_format.width,
+                format::MeasureUnit::Px,
+                custom_format.dpi,
+                format::MeasureUnit::Mm,
+                custom_format.dpi,
+            );
+            let height_mm = format::MeasureUnit::convert_measurement(
+                custom_format.height,
+                format::MeasureUnit::Px,
+                custom_format.dpi,
+                format::MeasureUnit::Mm,
+                custom_format.dpi,
+            );
+            let orientation = match custom_format.orientation() {
+                format::Orientation::Portrait => gettext("Portrait"),
+                format::Orientation::Landscape => gettext("Landscape"),
+            };
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&custom_format.name))
+                .subtitle(format!(
+                    "{width_mm:.1} × {height_mm:.1} mm, {} DPI, {orientation}",
+                    custom_format.dpi.round()
+                ))
+                .tooltip_text(gettext("Load Into Page Format"))
+                .activatable(true)
+                .build();
+            row.connect_activated(clone!(
+                #[weak(rename_to=settingspanel)]
+                self,
+                move |_| {
+                    settingspanel.load_custom_format(&custom_format);
+                }
+            ));
+            let remove_button = Button::builder()
+                .icon_name("trash-symbolic")
+                .tooltip_text(gettext("Remove Format"))
+                .valign(gtk4::Align::Center)
+                .css_classes(["flat"])
+                .build();
+            remove_button.connect_clicked(clone!(
+                #[weak]
+                app_settings,
+                move |_| {
+                    let mut custom_formats = custom_formats(&app_settings);
+                    if i < custom_formats.len() {
+                        custom_formats.remove(i);
+                    }
+                    set_custom_formats(&app_settings, &custom_formats);
+                }
+            ));
+            row.add_suffix(&remove_button);
+            imp.format_custom_formats_group.add(&row);
+            imp.custom_format_rows.borrow_mut().push(row);
+        }
+    }
+
+    /// Loads the custom format into the temporary format. It still needs to be applied.
+    fn load_custom_format(&self, custom_format: &CustomFormat) {
+        let imp = self.imp();
+        let mut format = *imp.temporary_format.borrow();
+        custom_format.apply_to(&mut format);
+        *imp.temporary_format.borrow_mut() = format;
+
+        self.set_format_predefined_format_variant(format::PredefinedFormat::Custom);
+        imp.format_dpi_adj.set_value(format.dpi());
+        imp.format_width_unitentry.set_dpi(format.dpi());
+        imp.format_width_unitentry.set_value_in_px(format.width());
+        imp.format_height_unitentry.set_dpi(format.dpi());
+        imp.format_height_unitentry.set_value_in_px(format.height());
+    }
+
+    fn setup_doc(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        imp.doc_format_border_color_button
+            .connect_rgba_notify(clone!(
+                #[weak(rename_to=settingspanel)]
+                self,
+                #[weak]
+                appwindow,
+                move |button| {
+                    let format_border_color = button.rgba().into_compose_color();
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+
+                    // Because the format border color is applied immediately to the engine,
+                    // we need to update the temporary format too.
+                    settingspanel
+                        .imp()
+                        .temporary_format
+                        .borrow_mut()
+                        .border_color = format_border_color;
+                    let current_color = canvas.engine_ref().document.format.border_color;
+
+                    if !current_color.approx_eq_f32(format_border_color) {
+                        canvas.engine_mut().document.format.border_color = format_border_color;
+                        let mut widget_flags =
+                            canvas.engine_mut().update_rendering_current_viewport();
+                        widget_flags.store_modified = true;
+                        appwindow.handle_widget_flags(widget_flags, &canvas);
+                    }
+                }
+            ));
+
+        imp.doc_background_color_button.connect_rgba_notify(clone!(
+            #[weak]
+            appwindow,
+            move |button| {
+                let background_color = button.rgba().into_compose_color();
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+
+                if !canvas
+                    .engine_ref()
+                    .document
+                    .background
+                    .color
+                    .approx_eq_f32(background_color)
+                {
+                    canvas.engine_mut().document.background.color = background_color;
+                    let mut widget_flags = canvas.engine_mut().background_rendering_regenerate();
+                    widget_flags.store_modified = true;
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            }
+        ));
+
+        imp.doc_document_layout_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak(rename_to=settings_panel)]
+                self,
+                #[weak]
+                appwindow,
+                move |_| {
+                    let document_layout = settings_panel.document_layout();
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+
+                    appwindow
                         .main_header()
                         .canvasmenu()
                         .fixedsize_quickactions_box()
                         .set_sensitive(document_layout == Layout::FixedSize);
+                    settings_panel
+                        .imp()
+                        .doc_clamp_panning_row
+                        .set_sensitive(matches!(
+                            document_layout,
+                            Layout::FixedSize | Layout::ContinuousVertical
+                        ));
+                    settings_panel
+                        .imp()
+                        .doc_infinite_expand_direction_row
+                        .set_sensitive(document_layout == Layout::Infinite);
+                    settings_panel
+                        .imp()
+                        .doc_two_page_spread_row
+                        .set_sensitive(document_layout == Layout::FixedSize);
 
                     if canvas.engine_ref().document.layout != document_layout {
                         let mut widget_flags = canvas.engine_mut().set_doc_layout(document_layout);
@@ -740,6 +1539,64 @@ impl RnSettingsPanel {
                 }
             ));
 
+        imp.doc_infinite_expand_direction_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak(rename_to=settings_panel)]
+                self,
+                #[weak]
+                appwindow,
+                move |_| {
+                    let expand_direction = settings_panel.infinite_expand_direction();
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+
+                    if canvas.engine_ref().document.infinite_expand_direction != expand_direction {
+                        let mut widget_flags = canvas
+                            .engine_mut()
+                            .set_doc_infinite_expand_direction(expand_direction);
+                        widget_flags.store_modified = true;
+                        appwindow.handle_widget_flags(widget_flags, &canvas);
+                    }
+                }
+            ));
+
+        imp.doc_two_page_spread_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let two_page_spread = row.is_active();
+
+                if canvas.engine_ref().document.two_page_spread != two_page_spread {
+                    let mut widget_flags =
+                        canvas.engine_mut().set_doc_two_page_spread(two_page_spread);
+                    widget_flags.store_modified = true;
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            }
+        ));
+
+        imp.doc_clamp_panning_row.connect_active_notify(clone!(
+            #[weak]
+            appwindow,
+            move |row| {
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let clamp_panning = row.is_active();
+
+                if canvas.engine_ref().document.clamp_panning != clamp_panning {
+                    let mut widget_flags = canvas.engine_mut().set_doc_clamp_panning(clamp_panning);
+                    widget_flags.store_modified = true;
+                    appwindow.handle_widget_flags(widget_flags, &canvas);
+                }
+            }
+        ));
+
         imp.doc_background_patterns_row
             .get()
             .connect_selected_item_notify(clone!(
@@ -814,6 +1671,16 @@ impl RnSettingsPanel {
                                 .doc_background_pattern_height_unitentry
                                 .set_sensitive(true);
                         }
+                        PatternStyle::CustomTile => {
+                            settings_panel
+                                .imp()
+                                .doc_background_pattern_width_unitentry
+                                .set_sensitive(true);
+                            settings_panel
+                                .imp()
+                                .doc_background_pattern_height_unitentry
+                                .set_sensitive(true);
+                        }
                     }
 
                     if canvas.engine_ref().document.background.pattern != pattern {
@@ -826,6 +1693,66 @@ impl RnSettingsPanel {
                 }
             ));
 
+        imp.doc_background_pattern_tile_button
+            .connect_clicked(clone!(
+                #[weak(rename_to=settings_panel)]
+                self,
+                #[weak]
+                appwindow,
+                move |_| {
+                    let embed = settings_panel
+                        .imp()
+                        .doc_background_pattern_tile_embed_row
+                        .is_active();
+                    glib::spawn_future_local(clone!(
+                        #[weak]
+                        appwindow,
+                        async move {
+                            dialogs::import::filedialog_choose_background_pattern_tile(
+                                &appwindow, embed,
+                            )
+                            .await;
+                        }
+                    ));
+                }
+            ));
+
+        imp.doc_background_pattern_tile_embed_row
+            .connect_active_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    // A referenced tile gets embedded, but an embedded one can't be turned back into a reference.
+                    // Disabling only affects the next chosen tile.
+                    if !row.is_active() {
+                        return;
+                    }
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+                    let Some(pattern_tile @ PatternTile::Reference { .. }) =
+                        canvas.engine_ref().document.background.pattern_tile.clone()
+                    else {
+                        return;
+                    };
+                    match pattern_tile.embed() {
+                        Ok(pattern_tile) => {
+                            canvas.engine_mut().document.background.pattern_tile =
+                                Some(pattern_tile);
+                            let mut widget_flags = WidgetFlags::default();
+                            widget_flags.store_modified = true;
+                            appwindow.handle_widget_flags(widget_flags, &canvas);
+                        }
+                        Err(e) => {
+                            error!("Embedding background pattern tile failed, Err: {e:?}");
+                            appwindow.overlays().dispatch_toast_error(&gettext(
+                                "Embedding the pattern tile failed",
+                            ));
+                        }
+                    }
+                }
+            ));
+
         imp.doc_background_pattern_color_button
             .connect_rgba_notify(clone!(
                 #[weak]
@@ -914,6 +1841,35 @@ impl RnSettingsPanel {
                 ),
             );
 
+        imp.doc_onion_skinning_opacity_row
+            .get()
+            .connect_value_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+                    let onion_skinning_opacity = row.value().clamp(
+                        Document::ONION_SKINNING_OPACITY_MIN,
+                        Document::ONION_SKINNING_OPACITY_MAX,
+                    );
+
+                    if (canvas.engine_ref().document.onion_skinning_opacity
+                        - onion_skinning_opacity)
+                        .abs()
+                        > f64::EPSILON
+                    {
+                        canvas.engine_mut().document.onion_skinning_opacity =
+                            onion_skinning_opacity;
+                        let mut widget_flags = WidgetFlags::default();
+                        widget_flags.redraw = true;
+                        widget_flags.store_modified = true;
+                        appwindow.handle_widget_flags(widget_flags, &canvas);
+                    }
+                }
+            ));
+
         imp.background_pattern_invert_color_button
             .get()
             .connect_clicked(clone!(
@@ -953,6 +1909,23 @@ impl RnSettingsPanel {
 
     fn setup_shortcuts(&self, appwindow: &RnAppWindow) {
         let imp = self.imp();
+
+        imp.penmode_eraser_end_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak(rename_to=settings_panel)]
+                self,
+                #[weak]
+                appwindow,
+                move |_| {
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+                    canvas.engine_mut().pens_config.eraser_end_pen_style =
+                        settings_panel.eraser_end_pen_style();
+                }
+            ));
+
         let penshortcut_stylus_button_primary_row = imp.penshortcut_stylus_button_primary_row.get();
         let penshortcut_stylus_button_secondary_row =
             imp.penshortcut_stylus_button_secondary_row.get();
@@ -1166,6 +2139,62 @@ impl RnSettingsPanel {
         );
     }
 
+    fn setup_advanced(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        imp.advanced_rnote_compression_method_row
+            .get()
+            .connect_selected_item_notify(clone!(
+                #[weak(rename_to=settings_panel)]
+                self,
+                #[weak]
+                appwindow,
+                move |_| {
+                    let method = settings_panel.rnote_compression_method();
+                    settings_panel.set_rnote_compression_level_range(method);
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+
+                    if canvas
+                        .engine_ref()
+                        .export_prefs
+                        .rnote_compression_prefs
+                        .method
+                        != method
+                    {
+                        canvas
+                            .engine_mut()
+                            .export_prefs
+                            .rnote_compression_prefs
+                            .method = method;
+                        // Levels are not comparable between the methods
+                        settings_panel
+                            .imp()
+                            .advanced_rnote_compression_level_row
+                            .set_value(method.default_level() as f64);
+                    }
+                }
+            ));
+
+        imp.advanced_rnote_compression_level_row
+            .connect_value_notify(clone!(
+                #[weak]
+                appwindow,
+                move |row| {
+                    let Some(canvas) = appwindow.active_tab_canvas() else {
+                        return;
+                    };
+
+                    canvas
+                        .engine_mut()
+                        .export_prefs
+                        .rnote_compression_prefs
+                        .level = row.value().round() as u32;
+                }
+            ));
+    }
+
     fn revert_format(&self, appwindow: &RnAppWindow) {
         let imp = self.imp();
         let Some(canvas) = appwindow.active_tab_canvas() else {
@@ -1182,6 +2211,9 @@ impl RnSettingsPanel {
         imp.format_height_unitentry.set_dpi(revert_format.dpi());
         imp.format_height_unitentry
             .set_value_in_px(revert_format.height());
+        imp.format_scale_adj.set_value(revert_format.scale());
+        imp.format_unit_row
+            .set_selected(revert_format.unit.to_u32().unwrap());
     }
 
     fn apply_format(&self, appwindow: &RnAppWindow) {
@@ -1195,6 +2227,10 @@ impl RnSettingsPanel {
             .set_dpi_keep_value(temporary_format.dpi());
         imp.doc_background_pattern_height_unitentry
             .set_dpi_keep_value(temporary_format.dpi());
+        imp.doc_background_pattern_width_unitentry
+            .set_scale(temporary_format.scale());
+        imp.doc_background_pattern_height_unitentry
+            .set_scale(temporary_format.scale());
 
         canvas.engine_mut().document.format = temporary_format;
         let mut widget_flags = canvas.engine_mut().doc_resize_to_fit_content();
@@ -1203,6 +2239,30 @@ impl RnSettingsPanel {
     }
 }
 
+/// The user-defined page formats stored in the settings.
+pub(crate) fn custom_formats(app_settings: &gio::Settings) -> Vec<CustomFormat> {
+    match serde_json::from_str::<Vec<CustomFormat>>(&app_settings.string("custom-formats")) {
+        Ok(custom_formats) => custom_formats,
+        Err(e) => {
+            error!("Failed to deserialize setting `custom-formats`, Err: {e:?}");
+            vec![]
+        }
+    }
+}
+
+fn set_custom_formats(app_settings: &gio::Settings, custom_formats: &[CustomFormat]) {
+    let json = match serde_json::to_string(custom_formats) {
+        Ok(json) => json,
+        Err(e) => {
+            error!("Failed to serialize custom formats, Err: {e:?}");
+            return;
+        }
+    };
+    if let Err(e) = app_settings.set_string("custom-formats", &json) {
+        error!("Failed to set setting `custom-formats`, Err: {e:?}");
+    }
+}
+
 const CURSORS_LIST: &[&str] = &[
     "cursor-crosshair-small",
     "cursor-crosshair-medium",
@@ -1224,6 +2284,38 @@ const CURSORS_LIST: &[&str] = &[
     "cursor-beam-large",
 ];
 
+/// The names of the installed sound themes, sorted alphabetically.
+fn sound_theme_names() -> Vec<String> {
+    let Ok(read_dir) = std::fs::read_dir(crate::env::sound_themes_dir()) else {
+        return vec![];
+    };
+    let mut names = read_dir
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            if !entry.file_type().ok()?.is_dir() {
+                return None;
+            }
+            entry.file_name().into_string().ok()
+        })
+        .collect::<Vec<String>>();
+    names.sort();
+    names
+}
+
+/// Modify the pen sounds config of the active tab.
+fn update_pen_sounds_config(appwindow: &RnAppWindow, f: impl FnOnce(&mut PenSoundsConfig)) {
+    let Some(canvas) = appwindow.active_tab_canvas() else {
+        return;
+    };
+    let mut pen_sounds_config = canvas.engine_ref().pen_sounds_config().clone();
+    f(&mut pen_sounds_config);
+    if &pen_sounds_config != canvas.engine_ref().pen_sounds_config() {
+        canvas
+            .engine_mut()
+            .set_pen_sounds_config(pen_sounds_config, crate::env::pkg_data_dir().ok());
+    }
+}
+
 fn cursors_list_to_display_name(icon_name: &str) -> String {
     match icon_name {
         "cursor-crosshair-small" => pgettext("a cursor type", "Crosshair (Small)"),
@@ -73,6 +73,9 @@ mod imp {
                     ShortcutAction::ChangePenStyle { style, .. } => {
                         *style = new_pen_style;
                     }
+                    // The selected pen style is not used when changing to the previous pen style
+                    // or the modifier color
+                    ShortcutAction::ChangeToPreviousPenStyle | ShortcutAction::ModifierColor => {}
                 }
                 row.emit_by_name::<()>("action-changed", &[]);
             });
@@ -80,12 +83,20 @@ mod imp {
             self.mode_dropdown.get().connect_selected_notify(clone!(
                 #[weak(rename_to=penshortcutrow)]
                 obj,
-                move |_| {
-                    match &mut *penshortcutrow.imp().action.borrow_mut() {
-                        ShortcutAction::ChangePenStyle { mode, .. } => {
-                            *mode = penshortcutrow.shortcut_mode();
+                move |mode_dropdown| {
+                    let new_action = match mode_dropdown.selected() {
+                        super::RnPenShortcutRow::MODE_DROPDOWN_PREVIOUS_PEN_STYLE => {
+                            ShortcutAction::ChangeToPreviousPenStyle
                         }
-                    }
+                        super::RnPenShortcutRow::MODE_DROPDOWN_MODIFIER_COLOR => {
+                            ShortcutAction::ModifierColor
+                        }
+                        _ => ShortcutAction::ChangePenStyle {
+                            style: penshortcutrow.pen_style(),
+                            mode: penshortcutrow.shortcut_mode(),
+                        },
+                    };
+                    *penshortcutrow.imp().action.borrow_mut() = new_action;
                     penshortcutrow.emit_by_name::<()>("action-changed", &[]);
                 }
             ));
@@ -133,6 +144,11 @@ glib::wrapper! {
 }
 
 impl RnPenShortcutRow {
+    /// The entry of the mode dropdown that maps the shortcut to changing back to the previous pen style.
+    const MODE_DROPDOWN_PREVIOUS_PEN_STYLE: u32 = 4;
+    /// The entry of the mode dropdown that maps the shortcut to drawing with the modifier color while it is held.
+    const MODE_DROPDOWN_MODIFIER_COLOR: u32 = 5;
+
     #[allow(clippy::new_without_default)]
     #[allow(unused)]
     pub(crate) fn new() -> Self {
@@ -174,6 +190,16 @@ impl RnPenShortcutRow {
                 self.set_pen_style(style);
                 self.set_shortcut_mode(mode);
             }
+            ShortcutAction::ChangeToPreviousPenStyle => {
+                self.imp()
+                    .mode_dropdown
+                    .set_selected(Self::MODE_DROPDOWN_PREVIOUS_PEN_STYLE);
+            }
+            ShortcutAction::ModifierColor => {
+                self.imp()
+                    .mode_dropdown
+                    .set_selected(Self::MODE_DROPDOWN_MODIFIER_COLOR);
+            }
         }
     }
 }
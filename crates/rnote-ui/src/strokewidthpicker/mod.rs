@@ -10,8 +10,8 @@ pub(crate) use strokewidthsetter::RnStrokeWidthSetter;
 
 // Imports
 use gtk4::{
-    glib, glib::clone, prelude::*, subclass::prelude::*, BoxLayout, CompositeTemplate, Orientation,
-    PositionType, SpinButton, Widget,
+    gdk, glib, glib::clone, prelude::*, subclass::prelude::*, BoxLayout, CompositeTemplate,
+    EventSequenceState, GestureLongPress, Orientation, Popover, PositionType, SpinButton, Widget,
 };
 use once_cell::sync::Lazy;
 use std::cell::Cell;
@@ -26,6 +26,8 @@ mod imp {
         pub(crate) stroke_width: Cell<f64>,
         pub(crate) preview_style: Cell<StrokeWidthPreviewStyle>,
 
+        #[template_child]
+        pub(crate) edit_popover: TemplateChild<Popover>,
         #[template_child]
         pub(crate) spinbutton: TemplateChild<SpinButton>,
         #[template_child]
@@ -45,6 +47,7 @@ mod imp {
                 stroke_width: Cell::new(1.0),
                 preview_style: Cell::new(StrokeWidthPreviewStyle::Circle),
 
+                edit_popover: TemplateChild::default(),
                 spinbutton: TemplateChild::default(),
                 setter_box: TemplateChild::default(),
                 setter_1: TemplateChild::default(),
@@ -131,6 +134,24 @@ mod imp {
                 }
             ));
 
+            for setter in obj.setters() {
+                let long_press_gesture = GestureLongPress::builder()
+                    .name("long_press_gesture")
+                    .build();
+                setter.add_controller(long_press_gesture.clone());
+                long_press_gesture.connect_pressed(clone!(
+                    #[weak(rename_to=strokewidthpicker)]
+                    obj,
+                    #[weak]
+                    setter,
+                    move |gesture, _, _| {
+                        // Prevents the button from being toggled again when the press is released
+                        gesture.set_state(EventSequenceState::Claimed);
+                        strokewidthpicker.popup_edit_preset(&setter);
+                    }
+                ));
+            }
+
             self.spinbutton.connect_value_changed(clone!(
                 #[weak(rename_to=strokewidthpicker)]
                 obj,
@@ -194,22 +215,18 @@ mod imp {
                         PositionType::Left => {
                             layout_manager.set_orientation(Orientation::Vertical);
                             self.setter_box.set_orientation(Orientation::Vertical);
-                            self.spinbutton.set_orientation(Orientation::Vertical);
                         }
                         PositionType::Right => {
                             layout_manager.set_orientation(Orientation::Vertical);
                             self.setter_box.set_orientation(Orientation::Vertical);
-                            self.spinbutton.set_orientation(Orientation::Vertical);
                         }
                         PositionType::Top => {
                             layout_manager.set_orientation(Orientation::Horizontal);
                             self.setter_box.set_orientation(Orientation::Horizontal);
-                            self.spinbutton.set_orientation(Orientation::Horizontal);
                         }
                         PositionType::Bottom => {
                             layout_manager.set_orientation(Orientation::Horizontal);
                             self.setter_box.set_orientation(Orientation::Horizontal);
-                            self.spinbutton.set_orientation(Orientation::Horizontal);
                         }
                         _ => {}
                     }
@@ -230,7 +247,12 @@ mod imp {
         }
     }
 
-    impl WidgetImpl for RnStrokeWidthPicker {}
+    impl WidgetImpl for RnStrokeWidthPicker {
+        fn size_allocate(&self, width: i32, height: i32, baseline: i32) {
+            self.parent_size_allocate(width, height, baseline);
+            self.edit_popover.present();
+        }
+    }
 
     impl RnStrokeWidthPicker {}
 }
@@ -298,6 +320,80 @@ impl RnStrokeWidthPicker {
         self.imp().setter_3.get()
     }
 
+    pub(crate) fn setters(&self) -> [RnStrokeWidthSetter; 3] {
+        let imp = self.imp();
+        [imp.setter_1.get(), imp.setter_2.get(), imp.setter_3.get()]
+    }
+
+    /// The stroke widths of the presets.
+    pub(crate) fn presets(&self) -> [f64; 3] {
+        self.setters().map(|setter| setter.stroke_width())
+    }
+
+    pub(crate) fn set_presets(&self, presets: [f64; 3]) {
+        for (setter, stroke_width) in self.setters().iter().zip(presets) {
+            setter.set_stroke_width(stroke_width);
+        }
+    }
+
+    /// Connect to changes of the stroke widths of the presets.
+    pub(crate) fn connect_presets_changed<F: Fn(&Self) + Clone + 'static>(&self, f: F) {
+        for setter in self.setters() {
+            setter.connect_notify_local(
+                Some("stroke-width"),
+                clone!(
+                    #[weak(rename_to=strokewidthpicker)]
+                    self,
+                    #[strong]
+                    f,
+                    move |_, _| f(&strokewidthpicker)
+                ),
+            );
+        }
+    }
+
+    /// Select the preset that matches the current stroke width, or deselect all when none does.
+    pub(crate) fn select_setter_matching_stroke_width(&self) {
+        let stroke_width = self.stroke_width();
+        let setters = self.setters();
+        let matches =
+            |setter: &RnStrokeWidthSetter| (setter.stroke_width() - stroke_width).abs() < 1e-3;
+
+        if setters
+            .iter()
+            .any(|setter| setter.is_active() && matches(setter))
+        {
+            return;
+        }
+        match setters.iter().find(|setter| matches(setter)) {
+            Some(setter) => setter.set_active(true),
+            None => self.deselect_setters(),
+        }
+    }
+
+    /// Select the preset and pop up the popover for editing its stroke width.
+    pub(crate) fn popup_edit_preset(&self, setter: &RnStrokeWidthSetter) {
+        let imp = self.imp();
+
+        setter.set_active(true);
+        let Some(bounds) = setter.compute_bounds(self) else {
+            return;
+        };
+        imp.edit_popover.set_position(match self.position() {
+            PositionType::Left => PositionType::Right,
+            PositionType::Right => PositionType::Left,
+            PositionType::Top => PositionType::Bottom,
+            _ => PositionType::Top,
+        });
+        imp.edit_popover.set_pointing_to(Some(&gdk::Rectangle::new(
+            bounds.x() as i32,
+            bounds.y() as i32,
+            bounds.width() as i32,
+            bounds.height() as i32,
+        )));
+        imp.edit_popover.popup();
+    }
+
     pub(crate) fn set_active_setter_stroke_width(&self, stroke_width: f64) {
         let imp = self.imp();
 
@@ -0,0 +1,297 @@
+// Imports
+use crate::RnAppWindow;
+use gettextrs::gettext;
+use gtk4::{
+    glib, glib::clone, prelude::*, subclass::prelude::*, Button, CompositeTemplate, Entry, Label,
+    SearchEntry, TemplateChild, ToggleButton, Widget,
+};
+use rnote_engine::engine::{TextMatch, TextReplaceScope};
+use std::cell::{Cell, RefCell};
+
+mod imp {
+    use super::*;
+
+    #[derive(Default, Debug, CompositeTemplate)]
+    #[template(resource = "/com/github/flxzt/rnote/ui/textsearchbar.ui")]
+    pub(crate) struct RnTextSearchBar {
+        pub(crate) matches: RefCell<Vec<TextMatch>>,
+        pub(crate) current_match: Cell<usize>,
+
+        #[template_child]
+        pub(crate) search_entry: TemplateChild<SearchEntry>,
+        #[template_child]
+        pub(crate) matches_label: TemplateChild<Label>,
+        #[template_child]
+        pub(crate) prev_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) next_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) match_case_togglebutton: TemplateChild<ToggleButton>,
+        #[template_child]
+        pub(crate) close_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) replace_entry: TemplateChild<Entry>,
+        #[template_child]
+        pub(crate) replace_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) replace_all_button: TemplateChild<Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnTextSearchBar {
+        const NAME: &'static str = "RnTextSearchBar";
+        type Type = super::RnTextSearchBar;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnTextSearchBar {
+        fn constructed(&self) {
+            self.parent_constructed();
+        }
+
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for RnTextSearchBar {}
+}
+
+glib::wrapper! {
+    pub(crate) struct RnTextSearchBar(ObjectSubclass<imp::RnTextSearchBar>)
+    @extends Widget;
+}
+
+impl Default for RnTextSearchBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RnTextSearchBar {
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// Shows the search bar and focuses the search entry, or closes it when it is already shown.
+    pub(crate) fn toggle(&self, appwindow: &RnAppWindow) {
+        if self.is_visible() {
+            self.close();
+        } else {
+            self.set_visible(true);
+            self.imp().search_entry.grab_focus();
+            // The document might have changed while the search bar was closed
+            self.search(appwindow);
+        }
+    }
+
+    pub(crate) fn close(&self) {
+        let imp = self.imp();
+        self.set_visible(false);
+        imp.matches.borrow_mut().clear();
+        imp.current_match.set(0);
+        self.update_matches_label();
+    }
+
+    pub(crate) fn init(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+
+        imp.search_entry.connect_search_changed(clone!(
+            #[weak(rename_to=searchbar)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                searchbar.search(&appwindow);
+            }
+        ));
+
+        imp.search_entry.connect_activate(clone!(
+            #[weak(rename_to=searchbar)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                searchbar.step_match(&appwindow, true);
+            }
+        ));
+
+        imp.search_entry.connect_stop_search(clone!(
+            #[weak(rename_to=searchbar)]
+            self,
+            move |_| {
+                searchbar.close();
+            }
+        ));
+
+        imp.match_case_togglebutton.connect_toggled(clone!(
+            #[weak(rename_to=searchbar)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                searchbar.search(&appwindow);
+            }
+        ));
+
+        imp.prev_button.connect_clicked(clone!(
+            #[weak(rename_to=searchbar)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                searchbar.step_match(&appwindow, false);
+            }
+        ));
+
+        imp.next_button.connect_clicked(clone!(
+            #[weak(rename_to=searchbar)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                searchbar.step_match(&appwindow, true);
+            }
+        ));
+
+        imp.close_button.connect_clicked(clone!(
+            #[weak(rename_to=searchbar)]
+            self,
+            move |_| {
+                searchbar.close();
+            }
+        ));
+
+        imp.replace_button.connect_clicked(clone!(
+            #[weak(rename_to=searchbar)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                let imp = searchbar.imp();
+                let Some(text_match) = imp.matches.borrow().get(imp.current_match.get()).cloned()
+                else {
+                    return;
+                };
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let (_, widget_flags) = canvas.engine_mut().replace_text_matches(
+                    &imp.search_entry.text(),
+                    &imp.replace_entry.text(),
+                    imp.match_case_togglebutton.is_active(),
+                    TextReplaceScope::Match(text_match),
+                );
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+                searchbar.refresh_matches(&appwindow);
+            }
+        ));
+
+        imp.replace_all_button.connect_clicked(clone!(
+            #[weak(rename_to=searchbar)]
+            self,
+            #[weak]
+            appwindow,
+            move |_| {
+                let imp = searchbar.imp();
+                let Some(canvas) = appwindow.active_tab_canvas() else {
+                    return;
+                };
+                let (n_replaced, widget_flags) = canvas.engine_mut().replace_text_matches(
+                    &imp.search_entry.text(),
+                    &imp.replace_entry.text(),
+                    imp.match_case_togglebutton.is_active(),
+                    TextReplaceScope::Document,
+                );
+                appwindow.handle_widget_flags(widget_flags, &canvas);
+                appwindow.overlays().dispatch_toast_text(
+                    &format!("{} {}", gettext("Replaced matches:"), n_replaced),
+                    crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+                );
+                searchbar.refresh_matches(&appwindow);
+            }
+        ));
+
+        self.update_matches_label();
+    }
+
+    /// Searches the active document from the start and shows the first match.
+    pub(crate) fn search(&self, appwindow: &RnAppWindow) {
+        self.imp().current_match.set(0);
+        self.refresh_matches(appwindow);
+    }
+
+    /// Searches the active document again, keeping the position of the current match where possible.
+    fn refresh_matches(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        let query = imp.search_entry.text();
+        let matches = match appwindow.active_tab_canvas() {
+            Some(canvas) if !query.is_empty() => canvas
+                .engine_ref()
+                .search_text(&query, imp.match_case_togglebutton.is_active()),
+            _ => vec![],
+        };
+        imp.current_match
+            .set(imp.current_match.get().min(matches.len().saturating_sub(1)));
+        *imp.matches.borrow_mut() = matches;
+        self.show_current_match(appwindow);
+    }
+
+    fn step_match(&self, appwindow: &RnAppWindow, forward: bool) {
+        let imp = self.imp();
+        let n_matches = imp.matches.borrow().len();
+        if n_matches == 0 {
+            return;
+        }
+        let current = imp.current_match.get();
+        imp.current_match.set(if forward {
+            (current + 1) % n_matches
+        } else {
+            (current + n_matches - 1) % n_matches
+        });
+        self.show_current_match(appwindow);
+    }
+
+    fn show_current_match(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        self.update_matches_label();
+        let Some(text_match) = imp.matches.borrow().get(imp.current_match.get()).cloned() else {
+            return;
+        };
+        let Some(canvas) = appwindow.active_tab_canvas() else {
+            return;
+        };
+        let widget_flags = canvas.engine_mut().show_text_match(&text_match);
+        appwindow.handle_widget_flags(widget_flags, &canvas);
+    }
+
+    fn update_matches_label(&self) {
+        let imp = self.imp();
+        let n_matches = imp.matches.borrow().len();
+        let has_matches = n_matches > 0;
+
+        if imp.search_entry.text().is_empty() {
+            imp.matches_label.set_label("");
+        } else if !has_matches {
+            imp.matches_label.set_label(&gettext("No matches"));
+        } else {
+            imp.matches_label
+                .set_label(&format!("{}/{}", imp.current_match.get() + 1, n_matches));
+        }
+        imp.prev_button.set_sensitive(has_matches);
+        imp.next_button.set_sensitive(has_matches);
+        imp.replace_button.set_sensitive(has_matches);
+        imp.replace_all_button.set_sensitive(has_matches);
+    }
+}
@@ -0,0 +1,222 @@
+// Imports
+use crate::colorpicker::RnColorSetter;
+use crate::RnAppWindow;
+use gtk4::{
+    glib, glib::clone, prelude::*, subclass::prelude::*, Button, CompositeTemplate, GestureDrag,
+    Image, TemplateChild, Widget,
+};
+use once_cell::sync::Lazy;
+use std::cell::Cell;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default, Debug, CompositeTemplate)]
+    #[template(resource = "/com/github/flxzt/rnote/ui/toolpalette.ui")]
+    pub(crate) struct RnToolPalette {
+        pub(crate) offset_x: Cell<f64>,
+        pub(crate) offset_y: Cell<f64>,
+
+        #[template_child]
+        pub(crate) drag_handle: TemplateChild<Image>,
+        #[template_child]
+        pub(crate) undo_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) redo_button: TemplateChild<Button>,
+        #[template_child]
+        pub(crate) swatch_1: TemplateChild<RnColorSetter>,
+        #[template_child]
+        pub(crate) swatch_2: TemplateChild<RnColorSetter>,
+        #[template_child]
+        pub(crate) swatch_3: TemplateChild<RnColorSetter>,
+        #[template_child]
+        pub(crate) swatch_4: TemplateChild<RnColorSetter>,
+        #[template_child]
+        pub(crate) swatch_5: TemplateChild<RnColorSetter>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RnToolPalette {
+        const NAME: &'static str = "RnToolPalette";
+        type Type = super::RnToolPalette;
+        type ParentType = Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    impl ObjectImpl for RnToolPalette {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.setup_drag_handle();
+        }
+
+        fn dispose(&self) {
+            self.dispose_template();
+            while let Some(child) = self.obj().first_child() {
+                child.unparent();
+            }
+        }
+
+        fn properties() -> &'static [glib::ParamSpec] {
+            static PROPERTIES: Lazy<Vec<glib::ParamSpec>> = Lazy::new(|| {
+                vec![
+                    glib::ParamSpecDouble::builder("offset-x")
+                        .minimum(0.0)
+                        .default_value(0.0)
+                        .build(),
+                    glib::ParamSpecDouble::builder("offset-y")
+                        .minimum(0.0)
+                        .default_value(0.0)
+                        .build(),
+                ]
+            });
+            PROPERTIES.as_ref()
+        }
+
+        fn set_property(&self, _id: usize, value: &glib::Value, pspec: &glib::ParamSpec) {
+            let obj = self.obj();
+
+            match pspec.name() {
+                "offset-x" => {
+                    let offset_x = value.get::<f64>().expect("value not of type `f64`");
+                    self.offset_x.set(offset_x);
+                    obj.set_margin_start(offset_x.round() as i32);
+                }
+                "offset-y" => {
+                    let offset_y = value.get::<f64>().expect("value not of type `f64`");
+                    self.offset_y.set(offset_y);
+                    obj.set_margin_top(offset_y.round() as i32);
+                }
+                _ => panic!("invalid property name"),
+            }
+        }
+
+        fn property(&self, _id: usize, pspec: &glib::ParamSpec) -> glib::Value {
+            match pspec.name() {
+                "offset-x" => self.offset_x.get().to_value(),
+                "offset-y" => self.offset_y.get().to_value(),
+                _ => panic!("invalid property name"),
+            }
+        }
+    }
+
+    impl WidgetImpl for RnToolPalette {}
+
+    impl RnToolPalette {
+        fn setup_drag_handle(&self) {
+            let obj = self.obj();
+            let drag_gesture = GestureDrag::builder()
+                .name("tool_palette_drag_gesture")
+                .propagation_phase(gtk4::PropagationPhase::Capture)
+                .build();
+            self.drag_handle.add_controller(drag_gesture.clone());
+            self.drag_handle.set_cursor_from_name(Some("move"));
+
+            drag_gesture.connect_drag_update(clone!(
+                #[weak(rename_to=palette)]
+                obj,
+                move |_, offset_x, offset_y| {
+                    // The offset is relative to the palette, which moves with every update.
+                    // Applying it incrementally keeps the palette under the pointer.
+                    palette.move_by(offset_x, offset_y);
+                }
+            ));
+        }
+    }
+}
+
+glib::wrapper! {
+    pub(crate) struct RnToolPalette(ObjectSubclass<imp::RnToolPalette>)
+    @extends Widget;
+}
+
+impl Default for RnToolPalette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RnToolPalette {
+    pub(crate) fn new() -> Self {
+        glib::Object::new()
+    }
+
+    #[allow(unused)]
+    pub(crate) fn offset_x(&self) -> f64 {
+        self.property::<f64>("offset-x")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_offset_x(&self, offset_x: f64) {
+        self.set_property("offset-x", offset_x.to_value());
+    }
+
+    #[allow(unused)]
+    pub(crate) fn offset_y(&self) -> f64 {
+        self.property::<f64>("offset-y")
+    }
+
+    #[allow(unused)]
+    pub(crate) fn set_offset_y(&self, offset_y: f64) {
+        self.set_property("offset-y", offset_y.to_value());
+    }
+
+    /// Moves the palette by the given delta, keeping it inside the bounds of the parent.
+    pub(crate) fn move_by(&self, dx: f64, dy: f64) {
+        let (max_x, max_y) = self
+            .parent()
+            .map(|parent| {
+                (
+                    f64::from(parent.width() - self.width()).max(0.0),
+                    f64::from(parent.height() - self.height()).max(0.0),
+                )
+            })
+            .unwrap_or((f64::MAX, f64::MAX));
+
+        self.set_offset_x((self.offset_x() + dx).clamp(0.0, max_x));
+        self.set_offset_y((self.offset_y() + dy).clamp(0.0, max_y));
+    }
+
+    pub(crate) fn init(&self, appwindow: &RnAppWindow) {
+        let imp = self.imp();
+        let penpicker = appwindow.overlays().penpicker();
+        let colorpicker = appwindow.overlays().colorpicker();
+
+        penpicker
+            .undo_button()
+            .bind_property("sensitive", &*imp.undo_button, "sensitive")
+            .sync_create()
+            .build();
+        penpicker
+            .redo_button()
+            .bind_property("sensitive", &*imp.redo_button, "sensitive")
+            .sync_create()
+            .build();
+
+        // The swatches mirror the first setters of the colorpicker
+        for (swatch, setter) in [
+            (&imp.swatch_1, colorpicker.setter_1()),
+            (&imp.swatch_2, colorpicker.setter_2()),
+            (&imp.swatch_3, colorpicker.setter_3()),
+            (&imp.swatch_4, colorpicker.setter_4()),
+            (&imp.swatch_5, colorpicker.setter_5()),
+        ] {
+            setter
+                .bind_property("color", &**swatch, "color")
+                .sync_create()
+                .build();
+            setter
+                .bind_property("active", &**swatch, "active")
+                .sync_create()
+                .bidirectional()
+                .build();
+        }
+    }
+}
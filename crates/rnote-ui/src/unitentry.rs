@@ -18,6 +18,8 @@ mod imp {
         pub(crate) value: Cell<f64>,
         pub(crate) unit: Cell<MeasureUnit>,
         pub(crate) dpi: Cell<f64>,
+        /// The drawing scale applied to the shown value, as real-world length per drawn length.
+        pub(crate) scale: Cell<f64>,
 
         #[template_child]
         pub(crate) value_spinner: TemplateChild<SpinButton>,
@@ -31,6 +33,7 @@ mod imp {
                 value: Cell::new(1.0),
                 unit: Cell::new(MeasureUnit::Px),
                 dpi: Cell::new(96.0),
+                scale: Cell::new(1.0),
                 value_spinner: TemplateChild::<SpinButton>::default(),
                 unit_dropdown: TemplateChild::<DropDown>::default(),
             }
@@ -57,7 +60,7 @@ mod imp {
             self.parent_constructed();
             let obj = self.obj();
 
-            self.configure_spinner(self.unit.get(), self.dpi.get());
+            self.configure_spinner(self.unit.get(), self.dpi.get(), self.scale.get());
             self.value_spinner.set_value(10.0);
 
             // Disable scrolling entirely,
@@ -123,6 +126,11 @@ mod imp {
                         .maximum(f64::MAX)
                         .default_value(96.0)
                         .build(),
+                    glib::ParamSpecDouble::builder("scale")
+                        .minimum(f64::MIN_POSITIVE)
+                        .maximum(f64::MAX)
+                        .default_value(1.0)
+                        .build(),
                 ]
             });
             PROPERTIES.as_ref()
@@ -133,6 +141,7 @@ mod imp {
                 "value" => self.value.get().to_value(),
                 "unit" => self.unit.get().to_u32().unwrap().to_value(),
                 "dpi" => self.dpi.get().to_value(),
+                "scale" => self.scale.get().to_value(),
                 _ => unimplemented!(),
             }
         }
@@ -153,7 +162,7 @@ mod imp {
                     )
                     .expect("Could not convert u32 to MeasureUnit.");
                     if unit != self.unit.get() {
-                        self.configure_spinner(unit, self.dpi.get());
+                        self.configure_spinner(unit, self.dpi.get(), self.scale.get());
                         obj.set_value(MeasureUnit::convert_measurement(
                             self.value.get(),
                             self.unit.get(),
@@ -167,7 +176,7 @@ mod imp {
                 "dpi" => {
                     let dpi = value.get::<f64>().expect("The value must be of type 'f64'");
                     if dpi != self.dpi.get() {
-                        self.configure_spinner(self.unit.get(), dpi);
+                        self.configure_spinner(self.unit.get(), dpi, self.scale.get());
                         obj.set_value(MeasureUnit::convert_measurement(
                             self.value.get(),
                             self.unit.get(),
@@ -178,6 +187,14 @@ mod imp {
                         self.dpi.replace(dpi);
                     }
                 }
+                "scale" => {
+                    let scale = value.get::<f64>().expect("The value must be of type 'f64'");
+                    if scale != self.scale.get() {
+                        let prev_scale = self.scale.replace(scale);
+                        self.configure_spinner(self.unit.get(), self.dpi.get(), scale);
+                        obj.set_value(self.value.get() * scale / prev_scale);
+                    }
+                }
                 _ => unimplemented!(),
             }
         }
@@ -201,21 +218,25 @@ mod imp {
         const CLIMB_RATE_CM: f64 = 0.2;
         const DIGITS_CM: u32 = 2;
 
-        fn configure_spinner(&self, unit: MeasureUnit, dpi: f64) {
+        const STEP_INCREMENT_INCH: f64 = 0.05;
+        const CLIMB_RATE_INCH: f64 = 0.1;
+        const DIGITS_INCH: u32 = 2;
+
+        fn configure_spinner(&self, unit: MeasureUnit, dpi: f64, scale: f64) {
             let min_val = MeasureUnit::convert_measurement(
                 Self::MIN_VAL_IN_PX,
                 MeasureUnit::Px,
                 dpi,
                 unit,
                 dpi,
-            );
+            ) * scale;
             let max_val = MeasureUnit::convert_measurement(
                 Self::MAX_VAL_IN_PX,
                 MeasureUnit::Px,
                 dpi,
                 unit,
                 dpi,
-            );
+            ) * scale;
 
             let (step_increment, climb_rate, digits) = match unit {
                 MeasureUnit::Px => (
@@ -233,6 +254,11 @@ mod imp {
                     Self::CLIMB_RATE_CM,
                     Self::DIGITS_CM,
                 ),
+                MeasureUnit::Inch => (
+                    Self::STEP_INCREMENT_INCH,
+                    Self::CLIMB_RATE_INCH,
+                    Self::DIGITS_INCH,
+                ),
             };
 
             self.value_spinner.set_range(min_val, max_val);
@@ -292,9 +318,20 @@ impl RnUnitEntry {
         self.set_property("dpi", dpi.to_value());
     }
 
+    #[allow(unused)]
+    pub(crate) fn scale(&self) -> f64 {
+        self.property::<f64>("scale")
+    }
+
+    /// Set the drawing scale, keeping the value in pixels.
+    #[allow(unused)]
+    pub(crate) fn set_scale(&self, scale: f64) {
+        self.set_property("scale", scale.to_value());
+    }
+
     pub(crate) fn value_in_px(&self) -> f64 {
         MeasureUnit::convert_measurement(
-            self.value(),
+            self.value() / self.scale(),
             self.unit(),
             self.dpi(),
             MeasureUnit::Px,
@@ -303,13 +340,15 @@ impl RnUnitEntry {
     }
 
     pub(crate) fn set_value_in_px(&self, val_px: f64) {
-        self.set_value(MeasureUnit::convert_measurement(
-            val_px,
-            MeasureUnit::Px,
-            self.dpi(),
-            self.unit(),
-            self.dpi(),
-        ));
+        self.set_value(
+            MeasureUnit::convert_measurement(
+                val_px,
+                MeasureUnit::Px,
+                self.dpi(),
+                self.unit(),
+                self.dpi(),
+            ) * self.scale(),
+        );
     }
 
     pub(crate) fn set_dpi_keep_value(&self, dpi: f64) {
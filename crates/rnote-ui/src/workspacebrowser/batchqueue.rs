@@ -0,0 +1,221 @@
+// Imports
+use crate::{FileType, RnAppWindow};
+use gettextrs::gettext;
+use gtk4::{gio, glib, glib::clone, prelude::*};
+use rnote_engine::engine::export::{DocExportFormat, DocExportPrefs};
+use rnote_engine::engine::import::XoppImportPrefs;
+use rnote_engine::engine::EngineSnapshot;
+use rnote_engine::Engine;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use tracing::error;
+
+/// An operation that is applied to every file of a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BatchOperation {
+    /// Open the files in tabs.
+    Open,
+    /// Export the `.rnote` files to Pdf files next to them.
+    ExportPdf,
+    /// Convert the Xournal++ files to `.rnote` files next to them.
+    ConvertXopp,
+}
+
+impl BatchOperation {
+    /// Whether the operation can be applied to the file, other files of the batch are skipped.
+    fn applies_to(&self, file: &gio::File) -> bool {
+        match (self, FileType::lookup_file_type(file)) {
+            (Self::Open, FileType::Folder | FileType::Unsupported) => false,
+            (Self::Open, _) => true,
+            (Self::ExportPdf, FileType::RnoteFile) => true,
+            (Self::ConvertXopp, FileType::XoppFile) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BatchJob {
+    operation: BatchOperation,
+    files: Vec<gio::File>,
+}
+
+/// A queue for batch operations on the files selected in the workspace browser.
+///
+/// The jobs are processed one after another, the heavy work is done by the engine in its thread pool.
+/// The progress of the running job is shown in the progressbar.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BatchQueue {
+    pending: Rc<RefCell<VecDeque<BatchJob>>>,
+    running: Rc<Cell<bool>>,
+}
+
+impl BatchQueue {
+    pub(crate) fn enqueue(
+        &self,
+        appwindow: &RnAppWindow,
+        operation: BatchOperation,
+        files: Vec<gio::File>,
+    ) {
+        let files = files
+            .into_iter()
+            .filter(|file| operation.applies_to(file))
+            .collect::<Vec<gio::File>>();
+        if files.is_empty() {
+            appwindow.overlays().dispatch_toast_text(
+                &gettext("No suitable files selected"),
+                crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+            );
+            return;
+        }
+        self.pending
+            .borrow_mut()
+            .push_back(BatchJob { operation, files });
+        if self.running.get() {
+            return;
+        }
+
+        self.running.set(true);
+        glib::spawn_future_local(clone!(
+            #[strong(rename_to=queue)]
+            self,
+            #[weak]
+            appwindow,
+            async move {
+                loop {
+                    let Some(job) = queue.pending.borrow_mut().pop_front() else {
+                        break;
+                    };
+                    process_job(&appwindow, job).await;
+                }
+                queue.running.set(false);
+            }
+        ));
+    }
+}
+
+async fn process_job(appwindow: &RnAppWindow, job: BatchJob) {
+    let n_files = job.files.len();
+    let mut n_failed = 0;
+    // Use the export and import preferences of the active document, if there is one
+    let (doc_export_prefs, xopp_import_prefs) = appwindow
+        .active_tab_canvas()
+        .map(|canvas| {
+            let engine = canvas.engine_ref();
+            (
+                engine.export_prefs.doc_export_prefs,
+                engine.import_prefs.xopp_import_prefs,
+            )
+        })
+        .unwrap_or_default();
+
+    appwindow.overlays().progressbar_start_pulsing();
+    appwindow.overlays().progressbar_set_fraction(0.0);
+
+    for (i, file) in job.files.into_iter().enumerate() {
+        let result = match job.operation {
+            BatchOperation::Open => {
+                // Reports its errors itself
+                appwindow
+                    .open_file_w_dialogs(file.clone(), None, true)
+                    .await;
+                Ok(())
+            }
+            BatchOperation::ExportPdf => export_to_pdf(&file, doc_export_prefs).await,
+            BatchOperation::ConvertXopp => convert_xopp(&file, xopp_import_prefs).await,
+        };
+        if let Err(e) = result {
+            error!(
+                "Batch operation {:?} failed for file '{}', Err: {e:?}",
+                job.operation,
+                file.uri()
+            );
+            n_failed += 1;
+        }
+        appwindow
+            .overlays()
+            .progressbar_set_fraction((i + 1) as f64 / n_files as f64);
+    }
+
+    appwindow.overlays().progressbar_finish();
+    if n_failed > 0 {
+        appwindow.overlays().dispatch_toast_error(&format!(
+            "{} {n_failed}/{n_files}",
+            gettext("Processing failed for files:")
+        ));
+    } else if job.operation != BatchOperation::Open {
+        appwindow.overlays().dispatch_toast_text(
+            &format!("{} {n_files}", gettext("Processed files:")),
+            crate::overlays::TEXT_TOAST_TIMEOUT_DEFAULT,
+        );
+    }
+}
+
+async fn export_to_pdf(file: &gio::File, doc_export_prefs: DocExportPrefs) -> anyhow::Result<()> {
+    let input_path = file
+        .path()
+        .ok_or_else(|| anyhow::anyhow!("File has no path"))?;
+    let (bytes, _) = file.load_bytes_future().await?;
+    let engine_snapshot = EngineSnapshot::load_from_rnote_bytes(bytes.to_vec()).await?;
+    let mut engine = Engine::default();
+    let _ = engine.load_snapshot(engine_snapshot);
+
+    let title = crate::utils::default_file_title_for_export(Some(file.clone()), None, None);
+    let export_bytes = engine
+        .export_doc(
+            title,
+            Some(DocExportPrefs {
+                export_format: DocExportFormat::Pdf,
+                ..doc_export_prefs
+            }),
+        )
+        .await??;
+    crate::utils::create_replace_file_future(
+        export_bytes,
+        &gio::File::for_path(unused_output_path(&input_path, "pdf")),
+    )
+    .await
+}
+
+async fn convert_xopp(file: &gio::File, xopp_import_prefs: XoppImportPrefs) -> anyhow::Result<()> {
+    let input_path = file
+        .path()
+        .ok_or_else(|| anyhow::anyhow!("File has no path"))?;
+    let output_path = unused_output_path(&input_path, "rnote");
+    let output_file_name = output_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| anyhow::anyhow!("Output path has no file name"))?;
+    let (bytes, _) = file.load_bytes_future().await?;
+    let engine_snapshot =
+        EngineSnapshot::load_from_xopp_bytes(bytes.to_vec(), Some(input_path), xopp_import_prefs)
+            .await?;
+    let mut engine = Engine::default();
+    let _ = engine.load_snapshot(engine_snapshot);
+
+    let rnote_bytes = engine.save_as_rnote_bytes(output_file_name).await??;
+    crate::utils::create_replace_file_future(rnote_bytes, &gio::File::for_path(output_path)).await
+}
+
+/// The path next to the input file with the given extension, with a numbered suffix when it already exists.
+fn unused_output_path(input_path: &Path, extension: &str) -> PathBuf {
+    let output_path = input_path.with_extension(extension);
+    if !output_path.exists() {
+        return output_path;
+    }
+    let stem = input_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    (1..)
+        .map(|i| {
+            input_path.with_file_name(format!(
+                "{stem}{}{i}.{extension}",
+                crate::utils::FILE_DUP_SUFFIX_DELIM
+            ))
+        })
+        .find(|path| !path.exists())
+        .unwrap()
+}
@@ -75,7 +75,13 @@ pub(crate) fn duplicate(filerow: &RnFileRow, appwindow: &RnAppWindow) -> gio::Si
 
 async fn duplicate_file(source: impl AsRef<Path>) -> anyhow::Result<()> {
     let destination = generate_destination_path(&source)?;
-    async_fs::copy(source, destination).await?;
+    // Without the overwrite flag the copy fails if the destination was created in the meantime
+    let (copy_future, _progress) = gio::File::for_path(source.as_ref()).copy_future(
+        &gio::File::for_path(destination),
+        gio::FileCopyFlags::NONE,
+        glib::Priority::DEFAULT,
+    );
+    copy_future.await?;
     Ok(())
 }
 
@@ -1,5 +1,6 @@
 // Modules
 mod duplicate;
+mod new_folder;
 mod open;
 mod open_in_default_app;
 mod rename;
@@ -7,6 +8,7 @@ mod trash;
 
 // Re-exports
 pub(crate) use duplicate::duplicate;
+pub(crate) use new_folder::new_folder;
 pub(crate) use open::open;
 pub(crate) use open_in_default_app::open_in_default_app;
 pub(crate) use rename::rename;
@@ -0,0 +1,122 @@
+// Imports
+use crate::workspacebrowser::{widgethelper, RnFileRow};
+use crate::RnAppWindow;
+use gettextrs::gettext;
+use gtk4::{gio, glib, glib::clone, pango, prelude::*, Align, Entry, Label};
+use tracing::{debug, error};
+
+/// Create a new `new-folder` action.
+///
+/// The folder is created inside the row's directory, or next to the row's file.
+pub(crate) fn new_folder(filerow: &RnFileRow, appwindow: &RnAppWindow) -> gio::SimpleAction {
+    let action = gio::SimpleAction::new("new-folder", None);
+    action.connect_activate(clone!(
+        #[weak]
+        filerow,
+        #[weak]
+        appwindow,
+        move |_, _| {
+            let Some(current_path) = filerow.current_file().and_then(|f| f.path()) else {
+                return;
+            };
+            let parent_path = if current_path.is_dir() {
+                current_path
+            } else {
+                let Some(parent_path) = current_path.parent().map(|p| p.to_path_buf()) else {
+                    return;
+                };
+                parent_path
+            };
+            let entry = create_entry();
+            let label = create_label();
+            let (apply_button, popover) = widgethelper::create_entry_dialog(&entry, &label);
+            filerow.menubutton_box().append(&popover);
+
+            // at first don't allow applying, since the user did not enter any text yet.
+            apply_button.set_sensitive(false);
+
+            entry.connect_changed(clone!(
+                #[weak]
+                apply_button,
+                #[strong]
+                parent_path,
+                move |entry| {
+                    let entry_text = entry.text();
+
+                    if widgethelper::is_valid_file_name(&entry_text)
+                        && !parent_path.join(&entry_text).exists()
+                    {
+                        apply_button.set_sensitive(true);
+                        entry.remove_css_class("error");
+                    } else {
+                        apply_button.set_sensitive(false);
+                        entry.add_css_class("error");
+                    }
+                }
+            ));
+
+            apply_button.connect_clicked(clone!(
+                #[weak]
+                popover,
+                #[weak]
+                entry,
+                #[weak]
+                appwindow,
+                move |_| {
+                    let folder_name = entry.text();
+                    if !widgethelper::is_valid_file_name(&folder_name) {
+                        debug!("Couldn't create new folder with invalid name `{folder_name}`");
+                        return;
+                    }
+                    let new_folder_path = parent_path.join(folder_name.as_str());
+
+                    glib::spawn_future_local(clone!(
+                        #[weak]
+                        appwindow,
+                        async move {
+                            // Fails instead of replacing anything when the folder already exists
+                            if let Err(e) = gio::File::for_path(&new_folder_path)
+                                .make_directory_future(glib::Priority::DEFAULT)
+                                .await
+                            {
+                                error!(
+                                    "Creating folder with path `{}` failed, Err: {e:?}",
+                                    new_folder_path.display()
+                                );
+                                let msg = if e.matches(gio::IOErrorEnum::Exists) {
+                                    gettext("Creating folder failed, it already exists")
+                                } else {
+                                    gettext("Creating folder failed")
+                                };
+                                appwindow.overlays().dispatch_toast_error(&msg);
+                            }
+                        }
+                    ));
+                    popover.popdown();
+                }
+            ));
+
+            popover.popup();
+            entry.grab_focus();
+        }
+    ));
+    action
+}
+
+fn create_entry() -> Entry {
+    Entry::builder()
+        .placeholder_text(gettext("Folder Name"))
+        .build()
+}
+
+fn create_label() -> Label {
+    let label = Label::builder()
+        .margin_bottom(12)
+        .halign(Align::Center)
+        .label(gettext("New Folder"))
+        .width_chars(24)
+        .ellipsize(pango::EllipsizeMode::End)
+        .build();
+    label.add_css_class("title-4");
+    label
+}
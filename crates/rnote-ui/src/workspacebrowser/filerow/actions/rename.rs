@@ -38,9 +38,12 @@ pub(crate) fn rename(filerow: &RnFileRow, appwindow: &RnAppWindow) -> gio::Simpl
                 #[weak]
                 apply_button,
                 move |entry2| {
-                    let new_file_path = parent_path.join(entry2.text());
+                    let new_file_name = entry2.text();
+                    let new_file_path = parent_path.join(&new_file_name);
                     // Disable apply button to prevent overwrites when file already exists
-                    apply_button.set_sensitive(!new_file_path.exists());
+                    apply_button.set_sensitive(
+                        widgethelper::is_valid_file_name(&new_file_name) && !new_file_path.exists(),
+                    );
                 }
             ));
 
@@ -52,9 +55,15 @@ pub(crate) fn rename(filerow: &RnFileRow, appwindow: &RnAppWindow) -> gio::Simpl
                 #[weak]
                 appwindow,
                 move |_| {
-                    let new_file_path = parent_path.join(entry.text());
+                    let new_file_name = entry.text();
+                    let new_file_path = parent_path.join(&new_file_name);
 
-                    if new_file_path.exists() {
+                    if !widgethelper::is_valid_file_name(&new_file_name) {
+                        appwindow
+                            .overlays()
+                            .dispatch_toast_error(&gettext("Renaming file failed, invalid name"));
+                        debug!("Renaming file failed, invalid name `{new_file_name}`");
+                    } else if new_file_path.exists() {
                         appwindow.overlays().dispatch_toast_error(&gettext(
                             "Renaming file failed, target file already exists",
                         ));
@@ -65,24 +74,35 @@ pub(crate) fn rename(filerow: &RnFileRow, appwindow: &RnAppWindow) -> gio::Simpl
                     } else {
                         glib::spawn_future_local(clone!(
                             #[strong]
-                            current_file_path,
+                            current_file,
                             #[weak]
                             appwindow,
                             async move {
                                 appwindow.overlays().progressbar_start_pulsing();
-                                if let Err(e) =
-                                    async_fs::rename(&current_file_path, &new_file_path).await
+                                // Fails instead of overwriting when the target was created in the meantime
+                                match current_file
+                                    .set_display_name_future(
+                                        &new_file_name,
+                                        glib::Priority::DEFAULT,
+                                    )
+                                    .await
                                 {
-                                    error!(
-                                        "Renaming file with path `{}` failed, Err: {e:?}",
-                                        new_file_path.display()
-                                    );
-                                    appwindow
-                                        .overlays()
-                                        .dispatch_toast_error(&gettext("Renaming file failed"));
-                                    appwindow.overlays().progressbar_abort();
-                                } else {
-                                    appwindow.overlays().progressbar_finish();
+                                    Ok(_) => appwindow.overlays().progressbar_finish(),
+                                    Err(e) => {
+                                        error!(
+                                            "Renaming file with path `{}` failed, Err: {e:?}",
+                                            new_file_path.display()
+                                        );
+                                        let msg = if e.matches(gio::IOErrorEnum::Exists) {
+                                            gettext(
+                                                "Renaming file failed, target file already exists",
+                                            )
+                                        } else {
+                                            gettext("Renaming file failed")
+                                        };
+                                        appwindow.overlays().dispatch_toast_error(&msg);
+                                        appwindow.overlays().progressbar_abort();
+                                    }
                                 }
                             }
                         ));
@@ -3,12 +3,26 @@ mod actions;
 
 // Imports
 use crate::RnAppWindow;
+use gettextrs::gettext;
 use gtk4::{
     gdk, gio, glib, glib::clone, prelude::*, subclass::prelude::*, CompositeTemplate, DragSource,
     GestureClick, GestureLongPress, Image, Label, MenuButton, PopoverMenu, Widget,
 };
 use once_cell::sync::Lazy;
+use rnote_engine::document::DocumentMetadata;
 use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use tracing::debug;
+
+thread_local! {
+    /// The tooltip markup of the metadata of .rnote files, together with the modification time it was loaded for.
+    ///
+    /// `None` while it is loading, `Some(None)` when the file has no metadata to show.
+    static METADATA_TOOLTIPS: RefCell<HashMap<PathBuf, (SystemTime, Option<Option<String>>)>> =
+        RefCell::new(HashMap::new());
+}
 
 mod imp {
     use super::*;
@@ -130,6 +144,11 @@ mod imp {
             let obj = self.obj();
             obj.add_controller(self.drag_source.clone());
 
+            obj.set_has_tooltip(true);
+            obj.connect_query_tooltip(|filerow, _x, _y, _keyboard_mode, tooltip| {
+                filerow.query_metadata_tooltip(tooltip)
+            });
+
             let rightclick_gesture = GestureClick::builder()
                 .name("rightclick_gesture")
                 .button(gdk::BUTTON_SECONDARY)
@@ -197,6 +216,67 @@ impl RnFileRow {
         self.set_property("position", position);
     }
 
+    /// Show the metadata of .rnote files in the tooltip.
+    ///
+    /// The metadata is loaded in the background the first time the tooltip is queried, and cached per file and
+    /// modification time. The tooltip is queried again once it is loaded.
+    fn query_metadata_tooltip(&self, tooltip: &gtk4::Tooltip) -> bool {
+        let Some(file) = self.current_file() else {
+            return false;
+        };
+        let Some(path) = file
+            .path()
+            .filter(|p| p.extension().is_some_and(|e| e == "rnote"))
+        else {
+            return false;
+        };
+        let Ok(modified) = std::fs::metadata(&path).and_then(|m| m.modified()) else {
+            return false;
+        };
+        let cached = METADATA_TOOLTIPS.with_borrow(|tooltips| {
+            tooltips
+                .get(&path)
+                .filter(|(cached_modified, _)| *cached_modified == modified)
+                .map(|(_, markup)| markup.clone())
+        });
+        match cached {
+            Some(Some(Some(markup))) => {
+                tooltip.set_markup(Some(&markup));
+                return true;
+            }
+            // Loading is in progress, or the file has no metadata
+            Some(_) => return false,
+            None => {}
+        }
+
+        METADATA_TOOLTIPS
+            .with_borrow_mut(|tooltips| tooltips.insert(path.clone(), (modified, None)));
+        glib::spawn_future_local(clone!(
+            #[weak(rename_to=filerow)]
+            self,
+            async move {
+                let metadata = match file.load_bytes_future().await {
+                    Ok((bytes, _)) => DocumentMetadata::load_from_rnote_bytes(bytes.to_vec()).await,
+                    Err(e) => Err(e.into()),
+                };
+                let markup = match metadata {
+                    Ok(metadata) => metadata_tooltip_markup(&metadata),
+                    Err(e) => {
+                        debug!("Loading metadata of file `{file:?}` failed, Err: {e:?}");
+                        None
+                    }
+                };
+                METADATA_TOOLTIPS
+                    .with_borrow_mut(|tooltips| tooltips.insert(path, (modified, Some(markup))));
+                // The row might be bound to a different file by now
+                if filerow.current_file().is_some_and(|f| f.equal(&file)) {
+                    filerow.trigger_tooltip_query();
+                }
+            }
+        ));
+        false
+    }
+
     pub(crate) fn file_image(&self) -> Image {
         self.imp().file_image.clone()
     }
@@ -222,11 +302,10 @@ impl RnFileRow {
             #[weak]
             appwindow,
             move |w| {
-                if w.get_visible() {
-                    appwindow
-                        .sidebar()
-                        .workspacebrowser()
-                        .files_list_set_selected(Some(filerow.position()));
+                let workspacebrowser = appwindow.sidebar().workspacebrowser();
+                // Keep an existing multi-selection when the menu is opened on one of the selected files
+                if w.get_visible() && !workspacebrowser.files_list_is_selected(filerow.position()) {
+                    workspacebrowser.files_list_set_selected(Some(filerow.position()));
                 }
             }
         ));
@@ -250,5 +329,39 @@ impl RnFileRow {
         self.imp()
             .action_group
             .add_action(&actions::duplicate(self, appwindow));
+        self.imp()
+            .action_group
+            .add_action(&actions::new_folder(self, appwindow));
+    }
+}
+
+fn metadata_tooltip_markup(metadata: &DocumentMetadata) -> Option<String> {
+    let mut lines = Vec::new();
+    if !metadata.title.is_empty() {
+        lines.push(format!(
+            "<b>{}</b>",
+            glib::markup_escape_text(&metadata.title)
+        ));
+    }
+    for (label, value) in [
+        (gettext("Author"), metadata.author.clone()),
+        (gettext("Subject"), metadata.subject.clone()),
+        (gettext("Tags"), metadata.tags_joined()),
+    ] {
+        if !value.is_empty() {
+            lines.push(format!("{label}: {}", glib::markup_escape_text(&value)));
+        }
+    }
+    if let Some(modified) = metadata
+        .modified
+        .and_then(|t| glib::DateTime::from_unix_local(t).ok())
+        .and_then(|t| t.format("%x %X").ok())
+    {
+        lines.push(format!("{}: {modified}", gettext("Modified")));
+    }
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
     }
 }
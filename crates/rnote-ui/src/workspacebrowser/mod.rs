@@ -1,4 +1,5 @@
 // Modules
+pub(crate) mod batchqueue;
 mod filerow;
 mod widgethelper;
 mod workspaceactions;
@@ -11,12 +12,13 @@ pub(crate) use workspacesbar::RnWorkspacesBar;
 
 // Imports
 use crate::appwindow::RnAppWindow;
+use batchqueue::BatchQueue;
 use gtk4::{
     gdk, gio, glib, glib::clone, glib::closure, prelude::*, subclass::prelude::*, Button,
     CompositeTemplate, ConstantExpression, CustomFilter, CustomSorter, DirectoryList, FileFilter,
-    FilterChange, FilterListModel, Grid, Label, ListItem, ListView, MultiSorter,
-    PropertyExpression, ScrolledWindow, Separator, SignalListItemFactory, SingleSelection,
-    SortListModel, SorterChange, Widget,
+    FilterChange, FilterListModel, Grid, Label, ListItem, ListView, MultiSelection, MultiSorter,
+    PropertyExpression, ScrolledWindow, Separator, SignalListItemFactory, SortListModel,
+    SorterChange, Widget,
 };
 use std::path::PathBuf;
 use tracing::warn;
@@ -29,7 +31,8 @@ mod imp {
     pub(crate) struct RnWorkspaceBrowser {
         pub(crate) action_group: gio::SimpleActionGroup,
         pub(crate) dir_list: DirectoryList,
-        pub(crate) list_selection_model: SingleSelection,
+        pub(crate) list_selection_model: MultiSelection,
+        pub(crate) batch_queue: BatchQueue,
 
         #[template_child]
         pub(crate) grid: TemplateChild<Grid>,
@@ -61,7 +64,8 @@ mod imp {
             Self {
                 action_group: gio::SimpleActionGroup::new(),
                 dir_list,
-                list_selection_model: SingleSelection::default(),
+                list_selection_model: MultiSelection::default(),
+                batch_queue: BatchQueue::default(),
 
                 grid: TemplateChild::<Grid>::default(),
                 dir_box: TemplateChild::<gtk4::Box>::default(),
@@ -191,6 +195,10 @@ impl RnWorkspaceBrowser {
         }
     }
 
+    pub(crate) fn batch_queue(&self) -> BatchQueue {
+        self.imp().batch_queue.clone()
+    }
+
     fn setup_actions(&self, appwindow: &RnAppWindow) {
         self.imp()
             .action_group
@@ -198,6 +206,15 @@ impl RnWorkspaceBrowser {
         self.imp()
             .action_group
             .add_action(&workspaceactions::open_folder(self, appwindow));
+        self.imp()
+            .action_group
+            .add_action(&workspaceactions::open_selected(self, appwindow));
+        self.imp()
+            .action_group
+            .add_action(&workspaceactions::export_selected(self, appwindow));
+        self.imp()
+            .action_group
+            .add_action(&workspaceactions::convert_selected(self, appwindow));
     }
 
     fn setup_dir_controls(&self, _appwindow: &RnAppWindow) {
@@ -259,9 +276,7 @@ impl RnWorkspaceBrowser {
         combined_list.append(&files_list_model);
 
         imp.list_selection_model
-            .set_model(Some(&SingleSelection::new(Some(FlattenListModel::new(
-                Some(combined_list),
-            )))));
+            .set_model(Some(&FlattenListModel::new(Some(combined_list))));
 
         imp.files_listview
             .get()
@@ -356,11 +371,40 @@ impl RnWorkspaceBrowser {
         ));
     }
 
-    /// Set the selected file in the files list with its position.
+    /// Set the selected file in the files list with its position, unselecting all others.
     pub(crate) fn files_list_set_selected(&self, position: Option<u32>) {
-        self.imp()
-            .list_selection_model
-            .set_selected(position.unwrap_or(gtk4::INVALID_LIST_POSITION));
+        let list_selection_model = &self.imp().list_selection_model;
+        match position {
+            Some(position) => {
+                list_selection_model.select_item(position, true);
+            }
+            None => {
+                list_selection_model.unselect_all();
+            }
+        }
+    }
+
+    /// Whether the file at the position in the files list is selected.
+    pub(crate) fn files_list_is_selected(&self, position: u32) -> bool {
+        self.imp().list_selection_model.is_selected(position)
+    }
+
+    /// The files that are currently selected in the files list.
+    pub(crate) fn selected_files(&self) -> Vec<gio::File> {
+        let list_selection_model = &self.imp().list_selection_model;
+        let selection = list_selection_model.selection();
+
+        (0..selection.size())
+            .filter_map(|i| {
+                list_selection_model
+                    .item(selection.nth(i as u32))?
+                    .downcast::<gio::FileInfo>()
+                    .ok()?
+                    .attribute_object("standard::file")?
+                    .downcast::<gio::File>()
+                    .ok()
+            })
+            .collect()
     }
 }
 
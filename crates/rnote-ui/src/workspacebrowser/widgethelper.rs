@@ -59,3 +59,14 @@ pub(crate) fn create_entry_dialog(entry: &Entry, label: &Label) -> (Button, Popo
 
     (apply_button, popover)
 }
+
+/// Whether the entered text is usable as the name of a new file or folder inside a directory.
+///
+/// Rejects names that would refer to a different directory.
+pub(crate) fn is_valid_file_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains('/')
+        && !name.contains(std::path::MAIN_SEPARATOR)
+}
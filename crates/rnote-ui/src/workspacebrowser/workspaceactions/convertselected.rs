@@ -0,0 +1,28 @@
+// Imports
+use crate::workspacebrowser::batchqueue::BatchOperation;
+use crate::{RnAppWindow, RnWorkspaceBrowser};
+use gtk4::{gio, glib, glib::clone};
+
+/// Create a new `convert-selected` action to convert the selected Xournal++ files.
+pub(crate) fn convert_selected(
+    workspacebrowser: &RnWorkspaceBrowser,
+    appwindow: &RnAppWindow,
+) -> gio::SimpleAction {
+    let convert_selected_action = gio::SimpleAction::new("convert-selected", None);
+
+    convert_selected_action.connect_activate(clone!(
+        #[weak]
+        workspacebrowser,
+        #[weak]
+        appwindow,
+        move |_, _| {
+            workspacebrowser.batch_queue().enqueue(
+                &appwindow,
+                BatchOperation::ConvertXopp,
+                workspacebrowser.selected_files(),
+            );
+        }
+    ));
+
+    convert_selected_action
+}
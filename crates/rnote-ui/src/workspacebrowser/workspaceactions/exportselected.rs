@@ -0,0 +1,28 @@
+// Imports
+use crate::workspacebrowser::batchqueue::BatchOperation;
+use crate::{RnAppWindow, RnWorkspaceBrowser};
+use gtk4::{gio, glib, glib::clone};
+
+/// Create a new `export-selected` action to export the selected documents to Pdf.
+pub(crate) fn export_selected(
+    workspacebrowser: &RnWorkspaceBrowser,
+    appwindow: &RnAppWindow,
+) -> gio::SimpleAction {
+    let export_selected_action = gio::SimpleAction::new("export-selected", None);
+
+    export_selected_action.connect_activate(clone!(
+        #[weak]
+        workspacebrowser,
+        #[weak]
+        appwindow,
+        move |_, _| {
+            workspacebrowser.batch_queue().enqueue(
+                &appwindow,
+                BatchOperation::ExportPdf,
+                workspacebrowser.selected_files(),
+            );
+        }
+    ));
+
+    export_selected_action
+}
@@ -1,7 +1,13 @@
 // Modules
+mod convertselected;
 mod createfolder;
+mod exportselected;
 mod openfolder;
+mod openselected;
 
 // Re-exports
+pub(crate) use convertselected::convert_selected;
 pub(crate) use createfolder::create_folder;
+pub(crate) use exportselected::export_selected;
 pub(crate) use openfolder::open_folder;
+pub(crate) use openselected::open_selected;
@@ -0,0 +1,28 @@
+// Imports
+use crate::workspacebrowser::batchqueue::BatchOperation;
+use crate::{RnAppWindow, RnWorkspaceBrowser};
+use gtk4::{gio, glib, glib::clone};
+
+/// Create a new `open-selected` action to open the selected files in tabs.
+pub(crate) fn open_selected(
+    workspacebrowser: &RnWorkspaceBrowser,
+    appwindow: &RnAppWindow,
+) -> gio::SimpleAction {
+    let open_selected_action = gio::SimpleAction::new("open-selected", None);
+
+    open_selected_action.connect_activate(clone!(
+        #[weak]
+        workspacebrowser,
+        #[weak]
+        appwindow,
+        move |_, _| {
+            workspacebrowser.batch_queue().enqueue(
+                &appwindow,
+                BatchOperation::Open,
+                workspacebrowser.selected_files(),
+            );
+        }
+    ));
+
+    open_selected_action
+}
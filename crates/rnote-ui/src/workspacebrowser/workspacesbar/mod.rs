@@ -119,6 +119,10 @@ impl RnWorkspacesBar {
         self.imp().workspaces_scroller.clone()
     }
 
+    pub(crate) fn n_workspaces(&self) -> u32 {
+        self.imp().workspace_list.n_items()
+    }
+
     pub(crate) fn push_workspace(&self, entry: RnWorkspaceListEntry) {
         self.imp().workspace_list.push(entry);
 
@@ -170,6 +174,30 @@ impl RnWorkspacesBar {
         }
     }
 
+    /// Move the workspace of `entry` to the position of `target`.
+    ///
+    /// Returns true if the workspace was moved.
+    pub(crate) fn move_workspace_to_entry(
+        &self,
+        entry: &RnWorkspaceListEntry,
+        target: &RnWorkspaceListEntry,
+    ) -> bool {
+        let workspace_list = &self.imp().workspace_list;
+        let (Some(from), Some(to)) = (
+            workspace_list.position(entry),
+            workspace_list.position(target),
+        ) else {
+            return false;
+        };
+        if from == to {
+            return false;
+        }
+
+        let entry = workspace_list.remove(from as usize);
+        self.insert_workspace_entry(to, entry);
+        true
+    }
+
     pub(crate) fn select_workspace_by_index(&self, index: u32) {
         let n_items = self.imp().workspace_list.n_items();
 
@@ -262,6 +290,39 @@ impl RnWorkspacesBar {
         }
     }
 
+    /// Export the workspace list as json into the file.
+    pub(crate) async fn export_workspace_list(&self, file: &gio::File) -> anyhow::Result<()> {
+        let json = self.imp().workspace_list.to_json()?;
+        crate::utils::create_replace_file_future(json.into_bytes(), file).await?;
+        Ok(())
+    }
+
+    /// Replace the current workspaces with the ones in the workspace list json file.
+    pub(crate) async fn import_workspace_list(&self, file: &gio::File) -> anyhow::Result<()> {
+        let (bytes, _) = file.load_bytes_future().await?;
+        let workspace_list = RnWorkspaceList::from_json(std::str::from_utf8(&bytes)?)?;
+        if workspace_list.n_items() == 0 {
+            return Err(anyhow::anyhow!("Imported workspace list is empty."));
+        }
+
+        if !cfg!(target_os = "windows") {
+            for entry in &workspace_list.iter() {
+                if let Err(err) = entry.ensure_dir() {
+                    warn!(
+                        dir = entry.dir(),
+                        name = entry.name(),
+                        ?err,
+                        "Failed to ensure dir",
+                    );
+                }
+            }
+        }
+
+        self.imp().workspace_list.replace_self(workspace_list);
+        self.select_workspace_by_index(0);
+        Ok(())
+    }
+
     pub(crate) fn load_from_settings(&self, settings: &gio::Settings) {
         let workspace_list = settings.get::<RnWorkspaceList>("workspace-list");
         // Be sure to get the index before loading the workspaces, else the setting gets overridden
@@ -452,6 +513,11 @@ impl RnWorkspacesBar {
             gio::SimpleAction::new("edit-selected-workspace", None);
         imp.action_group.add_action(&action_edit_selected_workspace);
 
+        let action_export_workspace_list = gio::SimpleAction::new("export-workspace-list", None);
+        imp.action_group.add_action(&action_export_workspace_list);
+        let action_import_workspace_list = gio::SimpleAction::new("import-workspace-list", None);
+        imp.action_group.add_action(&action_import_workspace_list);
+
         // Move selected workspace up
         action_move_selected_workspace_up.connect_activate(clone!(
             #[weak(rename_to=workspacesbar)]
@@ -518,5 +584,35 @@ impl RnWorkspacesBar {
                 ));
             }
         ));
+
+        // Export workspace list
+        action_export_workspace_list.connect_activate(clone!(
+            #[weak]
+            appwindow,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::export::filedialog_export_workspace_list(&appwindow).await;
+                    }
+                ));
+            }
+        ));
+
+        // Import workspace list
+        action_import_workspace_list.connect_activate(clone!(
+            #[weak]
+            appwindow,
+            move |_, _| {
+                glib::spawn_future_local(clone!(
+                    #[weak]
+                    appwindow,
+                    async move {
+                        dialogs::import::filedialog_import_workspace_list(&appwindow).await;
+                    }
+                ));
+            }
+        ));
     }
 }
@@ -76,6 +76,28 @@ impl RnWorkspaceList {
         VecRefWrapper::new(self.imp().list.borrow())
     }
 
+    /// The position of the given entry in the list, compared by identity.
+    pub(crate) fn position(&self, entry: &RnWorkspaceListEntry) -> Option<u32> {
+        self.imp()
+            .list
+            .borrow()
+            .iter()
+            .position(|e| e == entry)
+            .map(|i| i as u32)
+    }
+
+    /// Serializes the workspace entries to json, used for exporting the workspace list.
+    pub(crate) fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(&*self.imp().list.borrow())?)
+    }
+
+    /// Deserializes a workspace list from json, as exported by [`Self::to_json()`].
+    pub(crate) fn from_json(json: &str) -> anyhow::Result<Self> {
+        Ok(Self::from_vec(serde_json::from_str::<
+            Vec<RnWorkspaceListEntry>,
+        >(json)?))
+    }
+
     pub(crate) fn push(&self, item: RnWorkspaceListEntry) {
         self.imp().list.borrow_mut().push(item);
 
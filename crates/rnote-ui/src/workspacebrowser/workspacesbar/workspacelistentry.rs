@@ -204,3 +204,22 @@ impl glib::variant::FromVariant for RnWorkspaceListEntry {
         Some(Self::new(RnWorkspaceListEntryInner::from_variant(variant)?))
     }
 }
+
+impl serde::Serialize for RnWorkspaceListEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(&*self.imp().inner.borrow(), serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for RnWorkspaceListEntry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let inner: RnWorkspaceListEntryInner = serde::Deserialize::deserialize(deserializer)?;
+        Ok(Self::new(inner))
+    }
+}
@@ -2,8 +2,8 @@
 use super::RnWorkspaceListEntry;
 use crate::RnAppWindow;
 use gtk4::{
-    glib, glib::clone, prelude::*, subclass::prelude::*, CompositeTemplate, CssProvider, Image,
-    Label, Widget,
+    gdk, glib, glib::clone, prelude::*, subclass::prelude::*, CompositeTemplate, CssProvider,
+    DragSource, DropTarget, Image, Label, Widget,
 };
 use once_cell::sync::Lazy;
 use rnote_compose::{color, Color};
@@ -221,7 +221,60 @@ impl RnWorkspaceRow {
         self.set_property("entry", entry.to_value());
     }
 
-    pub(crate) fn init(&self, _appwindow: &RnAppWindow) {
+    pub(crate) fn init(&self, appwindow: &RnAppWindow) {
         // TODO: add gestures / menu for editing the row
+        self.setup_reorder_dnd(appwindow);
+    }
+
+    /// Reorder the workspaces by dragging a row onto another one.
+    fn setup_reorder_dnd(&self, appwindow: &RnAppWindow) {
+        let drag_source = DragSource::builder()
+            .name("workspacerow-drag-source")
+            .actions(gdk::DragAction::MOVE)
+            .build();
+        let drop_target = DropTarget::builder()
+            .name("workspacerow-drop-target")
+            .actions(gdk::DragAction::MOVE)
+            .build();
+        drop_target.set_types(&[RnWorkspaceListEntry::static_type()]);
+        self.add_controller(drag_source.clone());
+        self.add_controller(drop_target.clone());
+
+        drag_source.connect_prepare(clone!(
+            #[weak(rename_to=workspacerow)]
+            self,
+            #[upgrade_or]
+            None,
+            move |_, _, _| Some(gdk::ContentProvider::for_value(
+                &workspacerow.entry().to_value()
+            ))
+        ));
+
+        drag_source.connect_drag_begin(clone!(
+            #[weak(rename_to=workspacerow)]
+            self,
+            move |drag_source, _| {
+                drag_source.set_icon(Some(&gtk4::WidgetPaintable::new(Some(&workspacerow))), 0, 0);
+            }
+        ));
+
+        drop_target.connect_drop(clone!(
+            #[weak(rename_to=workspacerow)]
+            self,
+            #[weak]
+            appwindow,
+            #[upgrade_or]
+            false,
+            move |_, value, _, _| {
+                let Ok(dropped_entry) = value.get::<RnWorkspaceListEntry>() else {
+                    return false;
+                };
+                appwindow
+                    .sidebar()
+                    .workspacebrowser()
+                    .workspacesbar()
+                    .move_workspace_to_entry(&dropped_entry, &workspacerow.entry())
+            }
+        ));
     }
 }